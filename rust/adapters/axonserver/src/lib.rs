@@ -2,22 +2,32 @@ use anyhow::Result;
 use async_trait::async_trait;
 use axonserver_client::proto::dcb::source_events_response;
 use axonserver_client::proto::dcb::{Criterion, Event, Tag, TaggedEvent, TagsAndNamesCriterion};
-use axonserver_client::AxonServerClient;
+use axonserver_client::{AxonServerClient, ChannelConfig};
 use bench_core::adapter::{
-    EventData, EventStoreAdapter, ReadEvent, ReadRequest, StoreDataDir, StoreManager, StoreManagerFactory,
+    decode_tags, encode_extra_tags, AppendCondition, AppendOutcome, EventData, EventStoreAdapter, ReadEvent,
+    ReadRequest, StoreDataDir, StoreManager, StoreManagerFactory,
 };
+use bench_core::instrument::{record_server_timing, time_phase, time_phase_async, Phase};
 use bench_core::wait_for_ready;
 use bench_testcontainers::axonserver::{AxonServer, AXONSERVER_GRPC_PORT};
+use std::collections::HashMap;
 use std::sync::Arc;
 use testcontainers::runners::AsyncRunner;
 use testcontainers::ContainerAsync;
 use tokio::time::Duration;
 
+/// Event metadata key holding the tags after the stream tag
+const TAGS_METADATA: &str = "tags";
+/// Event metadata key holding the stream tag, for reads of the global log
+const STREAM_METADATA: &str = "stream";
+
 // Store manager - handles lifecycle and adapter creation
 pub struct AxonServerStoreManager {
     uri: Option<String>,
     container: Option<ContainerAsync<AxonServer>>,
     data_dir: StoreDataDir,
+    /// gRPC channel settings of every client, from store options
+    channel: ChannelConfig,
 }
 
 impl AxonServerStoreManager {
@@ -26,7 +36,48 @@ impl AxonServerStoreManager {
             uri: None,
             container: None,
             data_dir: StoreDataDir::new(data_dir, "axonserver"),
+            channel: ChannelConfig::default(),
+        }
+    }
+
+    /// Tune the clients' gRPC channel from store options: `stream_window_bytes`,
+    /// `connection_window_bytes`, `adaptive_window` (`on`/`off`), `concurrency_limit` (requests
+    /// in flight per client, the rest waiting in the client), `keepalive_interval_ms`, `keepalive_timeout_ms`,
+    /// `keepalive_while_idle` and `tcp_nodelay` (`on`/`off`)
+    pub fn with_options(mut self, options: &HashMap<String, String>) -> Result<Self> {
+        let channel = &mut self.channel;
+        for (key, value) in options {
+            match key.as_str() {
+                "stream_window_bytes" => channel.initial_stream_window_size = Some(number(key, value)?),
+                "connection_window_bytes" => channel.initial_connection_window_size = Some(number(key, value)?),
+                "adaptive_window" => channel.http2_adaptive_window = Some(switch(key, value)?),
+                "concurrency_limit" => channel.concurrency_limit = Some(number(key, value)?),
+                "keepalive_interval_ms" => {
+                    channel.keep_alive_interval = Some(Duration::from_millis(number(key, value)?))
+                }
+                "keepalive_timeout_ms" => {
+                    channel.keep_alive_timeout = Some(Duration::from_millis(number(key, value)?))
+                }
+                "keepalive_while_idle" => channel.keep_alive_while_idle = Some(switch(key, value)?),
+                "tcp_nodelay" => channel.tcp_nodelay = Some(switch(key, value)?),
+                other => anyhow::bail!("unknown axonserver store option '{}'", other),
+            }
         }
+        Ok(self)
+    }
+}
+
+fn number<T: std::str::FromStr<Err = std::num::ParseIntError>>(key: &str, value: &str) -> Result<T> {
+    value
+        .parse()
+        .map_err(|e| anyhow::anyhow!("invalid axonserver {} '{}': {}", key, value, e))
+}
+
+fn switch(key: &str, value: &str) -> Result<bool> {
+    match value {
+        "on" | "true" => Ok(true),
+        "off" | "false" => Ok(false),
+        other => anyhow::bail!("invalid axonserver {} '{}' (expected on or off)", key, other),
     }
 }
 
@@ -42,7 +93,7 @@ impl StoreManager for AxonServerStoreManager {
         // Wait for the container to be ready
         let uri = self.uri.clone().unwrap();
         wait_for_ready("Axon Server", || async {
-            let mut client = AxonServerClient::connect(uri.clone()).await?;
+            let mut client = AxonServerClient::connect_with(uri.clone(), &self.channel).await?;
             client.get_head().await?;
             Ok(())
         }, Duration::from_secs(60)).await?;
@@ -67,6 +118,10 @@ impl StoreManager for AxonServerStoreManager {
         self.container.as_ref().map(|c| c.id().to_string())
     }
 
+    fn endpoint(&self) -> Option<String> {
+        self.uri.clone()
+    }
+
     fn name(&self) -> &'static str {
         "axonserver"
     }
@@ -74,7 +129,7 @@ impl StoreManager for AxonServerStoreManager {
     fn create_adapter(&self) -> Result<Arc<dyn EventStoreAdapter>> {
         let adapter = tokio::task::block_in_place(|| {
             tokio::runtime::Handle::current()
-                .block_on(async { AxonServerAdapter::new(&self.uri.clone().unwrap()).await })
+                .block_on(async { AxonServerAdapter::new(&self.uri.clone().unwrap(), &self.channel).await })
         })?;
         Ok(Arc::new(adapter))
     }
@@ -86,8 +141,8 @@ pub struct AxonServerAdapter {
 }
 
 impl AxonServerAdapter {
-    pub async fn new(uri: &str) -> Result<Self> {
-        let client = AxonServerClient::connect(uri.to_string()).await?;
+    pub async fn new(uri: &str, channel: &ChannelConfig) -> Result<Self> {
+        let client = AxonServerClient::connect_with(uri.to_string(), channel).await?;
         Ok(Self { client })
     }
 }
@@ -100,31 +155,9 @@ impl EventStoreAdapter for AxonServerAdapter {
         // This is a limitation of the axonserver_client API design.
         let mut client = self.client.clone();
 
-        let tagged_events: Vec<TaggedEvent> = events.into_iter().map(|evt| {
-            let tags: Vec<Tag> = evt
-                .tags
-                .iter()
-                .map(|t| Tag {
-                    key: t.as_bytes().to_vec().into(),
-                    value: Vec::new().into(),
-                })
-                .collect();
-
-            let event = Event {
-                identifier: uuid::Uuid::new_v4().to_string(),
-                timestamp: now_millis(),
-                name: evt.event_type,
-                version: String::new(),
-                payload: evt.payload.into(),
-                metadata: Default::default(),
-            };
-            TaggedEvent {
-                event: Some(event),
-                tag: tags,
-            }
-        }).collect();
-
-        client.append(tagged_events).await?;
+        let events = time_phase(Phase::Serialize, || to_tagged_events(events));
+        let response = time_phase_async(Phase::Network, client.append_timed(events)).await?;
+        record_server_timing(response.server_timing.as_deref());
         Ok(())
     }
 
@@ -132,29 +165,25 @@ impl EventStoreAdapter for AxonServerAdapter {
         let mut client = self.client.clone();
 
         let from = req.from_offset.unwrap_or(0) as i64;
-        let criterion = Criterion {
-            tags_and_names: Some(TagsAndNamesCriterion {
-                name: vec![],
-                tag: vec![Tag {
-                    key: req.stream.as_bytes().to_vec().into(),
-                    value: Vec::new().into(),
-                }],
-            }),
-        };
-        let responses = client.source(from, vec![criterion]).await?;
+        let responses =
+            time_phase_async(Phase::Network, client.source_timed(from, vec![stream_criterion(&req.stream)]))
+                .await?;
+        record_server_timing(responses.server_timing.as_deref());
 
         let mut out = Vec::new();
-        for resp in responses {
+        for resp in responses.value {
             if let Some(result) = resp.result {
                 match result {
                     source_events_response::Result::Event(seq_evt) => {
                         if let Some(evt) = seq_evt.event {
-                            out.push(ReadEvent {
+                            let tags = decode_tags(&req.stream, evt.metadata.get(TAGS_METADATA).map(String::as_str))?;
+                            out.push(time_phase(Phase::Deserialize, || ReadEvent {
                                 offset: seq_evt.sequence as u64,
                                 event_type: evt.name,
-                                payload: evt.payload.to_vec(),
+                                payload: evt.payload.into(),
                                 timestamp_ms: evt.timestamp as u64,
-                            });
+                                tags,
+                            }));
                         }
                         if let Some(lim) = req.limit {
                             if out.len() as u64 >= lim {
@@ -169,6 +198,71 @@ impl EventStoreAdapter for AxonServerAdapter {
         Ok(out)
     }
 
+    /// Sources without criteria, which matches every event; sequences are inclusive in Axon
+    /// Server
+    async fn read_all(&self, after: Option<u64>, limit: Option<u64>) -> Result<Vec<ReadEvent>> {
+        let mut client = self.client.clone();
+        let from = after.map_or(0, |after| after as i64 + 1);
+        let limit = limit.map_or(usize::MAX, |l| l as usize);
+        let events = time_phase_async(Phase::Network, client.source_limited(from, Vec::new(), limit)).await?;
+        let mut out = Vec::with_capacity(events.len());
+        for seq_evt in events {
+            let Some(evt) = seq_evt.event else {
+                continue;
+            };
+            let tags = match evt.metadata.get(STREAM_METADATA) {
+                Some(stream) => decode_tags(stream, evt.metadata.get(TAGS_METADATA).map(String::as_str))?,
+                None => Vec::new(),
+            };
+            out.push(time_phase(Phase::Deserialize, || ReadEvent {
+                offset: seq_evt.sequence as u64,
+                event_type: evt.name,
+                payload: evt.payload.into(),
+                timestamp_ms: evt.timestamp as u64,
+                tags,
+            }));
+        }
+        Ok(out)
+    }
+
+    async fn append_conditional(
+        &self,
+        events: Vec<EventData>,
+        condition: AppendCondition,
+    ) -> Result<AppendOutcome> {
+        let mut client = self.client.clone();
+
+        let stream = events
+            .first()
+            .and_then(|evt| evt.tags.first())
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("Conditional append requires a stream tag"))?;
+        // The consistency marker is inclusive, so "after position p" starts checking at p + 1
+        let consistency_marker = match condition {
+            AppendCondition::NoStream => 0,
+            AppendCondition::After(position) => position as i64 + 1,
+        };
+        let response = client
+            .append_conditional(
+                to_tagged_events(events),
+                consistency_marker,
+                vec![stream_criterion(&stream)],
+            )
+            .await?;
+        match response {
+            Some(resp) => Ok(AppendOutcome::Appended {
+                position: (resp.sequence_of_the_first_event + resp.transaction_size as i64 - 1) as u64,
+            }),
+            None => {
+                // Head points at the next sequence to be written
+                let head = client.get_head().await?;
+                Ok(AppendOutcome::Conflict {
+                    current: (head > 0).then(|| (head - 1) as u64),
+                })
+            }
+        }
+    }
+
     // async fn ping(&self) -> Result<Duration> {
     //     let mut client = self.client.clone();
     //     let t0 = std::time::Instant::now();
@@ -187,6 +281,61 @@ impl StoreManagerFactory for AxonServerFactory {
     fn create_store_manager(&self, data_dir: Option<String>) -> Result<Box<dyn StoreManager>> {
         Ok(Box::new(AxonServerStoreManager::new(data_dir)))
     }
+
+    fn create_store_manager_with_options(
+        &self,
+        data_dir: Option<String>,
+        options: &HashMap<String, String>,
+    ) -> Result<Box<dyn StoreManager>> {
+        Ok(Box::new(AxonServerStoreManager::new(data_dir).with_options(options)?))
+    }
+}
+
+fn to_tagged_events(events: Vec<EventData>) -> Vec<TaggedEvent> {
+    events.into_iter().map(|evt| {
+        let tags: Vec<Tag> = evt
+            .tags
+            .iter()
+            .map(|t| Tag {
+                key: t.as_bytes().to_vec().into(),
+                value: Vec::new().into(),
+            })
+            .collect();
+
+        // Sourcing doesn't return an event's tags, so they are also kept in its metadata
+        let metadata = evt
+            .tags
+            .first()
+            .map(|stream| (STREAM_METADATA.to_string(), stream.clone()))
+            .into_iter()
+            .chain(encode_extra_tags(&evt).map(|tags| (TAGS_METADATA.to_string(), tags)))
+            .collect();
+        let event = Event {
+            identifier: uuid::Uuid::new_v4().to_string(),
+            timestamp: now_millis(),
+            name: evt.event_type,
+            version: String::new(),
+            payload: evt.payload.into(),
+            metadata,
+        };
+        TaggedEvent {
+            event: Some(event),
+            tag: tags,
+        }
+    }).collect()
+}
+
+/// Criterion selecting the events tagged with a stream name
+fn stream_criterion(stream: &str) -> Criterion {
+    Criterion {
+        tags_and_names: Some(TagsAndNamesCriterion {
+            name: vec![],
+            tag: vec![Tag {
+                key: stream.as_bytes().to_vec().into(),
+                value: Vec::new().into(),
+            }],
+        }),
+    }
 }
 
 fn now_millis() -> i64 {
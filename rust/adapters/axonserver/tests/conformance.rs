@@ -0,0 +1,18 @@
+use bench_core::testkit::{run_conformance, Capabilities};
+use bench_core::StoreManagerFactory;
+use axonserver_adapter::AxonServerFactory;
+
+#[tokio::test]
+#[ignore = "starts an Axon Server container (needs Docker)"]
+async fn axonserver_conforms() -> anyhow::Result<()> {
+    let mut store = AxonServerFactory.create_store_manager(None)?;
+    store.start().await?;
+    let capabilities = Capabilities {
+        conditional_append: true,
+        subscribe: false,
+        read_all: true,
+    };
+    let result = run_conformance(store.as_ref(), capabilities).await;
+    store.stop().await?;
+    result
+}
@@ -2,9 +2,12 @@ use std::hint::spin_loop;
 use anyhow::Result;
 use async_trait::async_trait;
 use bench_core::adapter::{
-    EventData, EventStoreAdapter, ReadEvent, ReadRequest, StoreManager, StoreManagerFactory,
+    AppendCondition, AppendOutcome, EventData, EventStoreAdapter, ReadEvent, ReadRequest,
+    StoreManager, StoreManagerFactory,
 };
-use std::sync::Arc;
+use std::collections::{BTreeSet, HashMap};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::{Duration, Instant};
 use lazy_static::lazy_static;
@@ -18,11 +21,134 @@ lazy_static! {
         .expect("Failed to create delay thread pool");
 }
 
-pub struct DummyStoreManager {}
+/// Latency of every operation
+const BASE_LATENCY: Duration = Duration::from_micros(5000);
+
+/// Latency spike windows: of every `every` operations, the first `length` take `delay` longer
+#[derive(Debug, Clone, Copy)]
+pub struct LatencySpike {
+    pub every: u64,
+    pub length: u64,
+    pub delay: Duration,
+}
+
+/// Deterministic faults injected by the dummy store, so workflow logic (retries, error counts,
+/// SLO math) can be tested without containers. Operations are numbered from 1 across all
+/// adapters of the store, in the order they start.
+#[derive(Debug, Clone, Default)]
+pub struct FailureSchedule {
+    /// Fail every Nth operation
+    pub fail_every: Option<u64>,
+    /// Fail this share of operations, picked by hashing `seed` with the operation number
+    pub fail_rate: Option<f64>,
+    pub seed: u64,
+    pub latency_spike: Option<LatencySpike>,
+    /// Answer every Nth conditional append with a conflict (counted over conditional appends)
+    pub conflict_every: Option<u64>,
+}
+
+impl FailureSchedule {
+    /// Read the schedule from store options: `fail_every`, `fail_rate`, `seed`, `spike_every`,
+    /// `spike_length`, `spike_ms` and `conflict_every`
+    pub fn from_options(options: &HashMap<String, String>) -> Result<Self> {
+        fn parse<T: std::str::FromStr>(options: &HashMap<String, String>, key: &str) -> Result<Option<T>>
+        where
+            T::Err: std::fmt::Display,
+        {
+            options
+                .get(key)
+                .map(|value| value.parse().map_err(|e| anyhow::anyhow!("invalid dummy {} '{}': {}", key, value, e)))
+                .transpose()
+        }
+        const KEYS: [&str; 7] = ["fail_every", "fail_rate", "seed", "spike_every", "spike_length", "spike_ms", "conflict_every"];
+        if let Some(key) = options.keys().find(|key| !KEYS.contains(&key.as_str())) {
+            anyhow::bail!("unknown dummy store option '{}'", key);
+        }
+
+        let latency_spike = match (parse(options, "spike_every")?, parse(options, "spike_length")?, parse::<f64>(options, "spike_ms")?) {
+            (Some(every), Some(length), Some(ms)) => Some(LatencySpike {
+                every,
+                length,
+                delay: Duration::from_secs_f64(ms / 1000.0),
+            }),
+            (None, None, None) => None,
+            _ => anyhow::bail!("spike_every, spike_length and spike_ms must be set together"),
+        };
+        let schedule = Self {
+            fail_every: parse(options, "fail_every")?,
+            fail_rate: parse(options, "fail_rate")?,
+            seed: parse(options, "seed")?.unwrap_or(0),
+            latency_spike,
+            conflict_every: parse(options, "conflict_every")?,
+        };
+        if schedule.fail_every == Some(0) || schedule.conflict_every == Some(0) || latency_spike.is_some_and(|s| s.every == 0) {
+            anyhow::bail!("dummy fail_every, conflict_every and spike_every must be > 0");
+        }
+        if schedule.fail_rate.is_some_and(|rate| !(0.0..=1.0).contains(&rate)) {
+            anyhow::bail!("dummy fail_rate must be in [0, 1]");
+        }
+        Ok(schedule)
+    }
+
+    /// Whether operation `n` fails
+    pub fn fails(&self, n: u64) -> bool {
+        self.fail_every.is_some_and(|every| n.is_multiple_of(every))
+            || self.fail_rate.is_some_and(|rate| (splitmix64(self.seed ^ n) as f64) < rate * u64::MAX as f64)
+    }
+
+    /// Extra latency of operation `n`
+    pub fn extra_delay(&self, n: u64) -> Duration {
+        match self.latency_spike {
+            Some(spike) if (n - 1) % spike.every < spike.length => spike.delay,
+            _ => Duration::ZERO,
+        }
+    }
+
+    /// Whether conditional append `n` conflicts
+    pub fn conflicts(&self, n: u64) -> bool {
+        self.conflict_every.is_some_and(|every| n.is_multiple_of(every))
+    }
+}
+
+/// Spreads consecutive operation numbers over the whole `u64` range
+fn splitmix64(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// Faults and the operation counters they are scheduled by, shared by all adapters of a store
+#[derive(Default)]
+struct Faults {
+    schedule: FailureSchedule,
+    operations: AtomicU64,
+    conditional_appends: AtomicU64,
+}
+
+pub struct DummyStoreManager {
+    /// Last revision per stream, shared by all adapters so conditional appends can conflict
+    stream_revisions: Arc<Mutex<HashMap<String, u64>>>,
+    /// Streams appended to (by their first tag), shared by all adapters so they can be listed
+    streams: Arc<Mutex<BTreeSet<String>>>,
+    faults: Arc<Faults>,
+}
 
 impl DummyStoreManager {
     pub fn new() -> Self {
-        Self {}
+        Self::with_failures(FailureSchedule::default())
+    }
+
+    /// A store injecting the faults of `schedule`
+    pub fn with_failures(schedule: FailureSchedule) -> Self {
+        Self {
+            stream_revisions: Arc::new(Mutex::new(HashMap::new())),
+            streams: Arc::new(Mutex::new(BTreeSet::new())),
+            faults: Arc::new(Faults {
+                schedule,
+                ..Faults::default()
+            }),
+        }
     }
 }
 
@@ -44,22 +170,102 @@ impl StoreManager for DummyStoreManager {
         "dummy"
     }
     fn create_adapter(&self) -> Result<Arc<dyn EventStoreAdapter>> {
-        Ok(Arc::new(DummyAdapter))
+        Ok(Arc::new(DummyAdapter {
+            stream_revisions: self.stream_revisions.clone(),
+            streams: self.streams.clone(),
+            faults: self.faults.clone(),
+        }))
     }
 }
 
-pub struct DummyAdapter;
+pub struct DummyAdapter {
+    stream_revisions: Arc<Mutex<HashMap<String, u64>>>,
+    streams: Arc<Mutex<BTreeSet<String>>>,
+    faults: Arc<Faults>,
+}
+
+impl DummyAdapter {
+    /// Take the time of one operation, including any latency spike, and fail it if scheduled
+    async fn operate(&self) -> Result<()> {
+        let n = self.faults.operations.fetch_add(1, Ordering::Relaxed) + 1;
+        let schedule = &self.faults.schedule;
+        let delay = BASE_LATENCY + schedule.extra_delay(n);
+        if bench_core::virtual_time::is_enabled() {
+            tokio::time::sleep(delay).await;
+        } else {
+            precise_delay(delay).await;
+        }
+        if schedule.fails(n) {
+            anyhow::bail!("injected failure of operation {}", n);
+        }
+        Ok(())
+    }
+
+    /// Note the streams of appended events, so listing them finds them
+    fn record_streams(&self, events: &[EventData]) {
+        let mut streams = self.streams.lock().unwrap();
+        for stream in events.iter().filter_map(|e| e.tags.first()) {
+            if !streams.contains(stream) {
+                streams.insert(stream.clone());
+            }
+        }
+    }
+}
 
 #[async_trait]
 impl EventStoreAdapter for DummyAdapter {
-    async fn append(&self, _events: Vec<EventData>) -> Result<()> {
-        precise_delay(Duration::from_micros(5000)).await;
+    async fn append(&self, events: Vec<EventData>) -> Result<()> {
+        self.operate().await?;
+        self.record_streams(&events);
         Ok(())
     }
     async fn read(&self, _req: ReadRequest) -> Result<Vec<ReadEvent>> {
-        precise_delay(Duration::from_micros(5000)).await;
+        self.operate().await?;
         Ok(vec![])
     }
+    async fn append_conditional(
+        &self,
+        events: Vec<EventData>,
+        condition: AppendCondition,
+    ) -> Result<AppendOutcome> {
+        self.operate().await?;
+        let stream = events
+            .first()
+            .and_then(|e| e.tags.first())
+            .ok_or_else(|| anyhow::anyhow!("Conditional append requires a stream tag"))?;
+        let mut revisions = self.stream_revisions.lock().unwrap();
+        let current = revisions.get(stream).copied();
+        let n = self.faults.conditional_appends.fetch_add(1, Ordering::Relaxed) + 1;
+        if self.faults.schedule.conflicts(n) {
+            return Ok(AppendOutcome::Conflict { current });
+        }
+        let matches = match condition {
+            AppendCondition::NoStream => current.is_none(),
+            AppendCondition::After(position) => current == Some(position),
+        };
+        if !matches {
+            return Ok(AppendOutcome::Conflict { current });
+        }
+        let position = current.map_or(0, |r| r + 1) + events.len() as u64 - 1;
+        revisions.insert(stream.clone(), position);
+        self.record_streams(&events);
+        Ok(AppendOutcome::Appended { position })
+    }
+    async fn read_all(&self, _after: Option<u64>, _limit: Option<u64>) -> Result<Vec<ReadEvent>> {
+        self.operate().await?;
+        Ok(vec![])
+    }
+    /// Streams appended to, in name order
+    async fn list_streams(&self, prefix: &str, limit: Option<u64>) -> Result<Vec<String>> {
+        self.operate().await?;
+        let streams = self.streams.lock().unwrap();
+        Ok(streams
+            .range(prefix.to_string()..)
+            .take_while(|stream| stream.starts_with(prefix))
+            .take(limit.map_or(usize::MAX, |l| l.min(usize::MAX as u64) as usize))
+            .cloned()
+            .collect())
+    }
 }
 
 pub struct DummyFactory;
@@ -74,6 +280,13 @@ impl StoreManagerFactory for DummyFactory {
     ) -> Result<Box<dyn StoreManager>> {
         Ok(Box::new(DummyStoreManager::new()))
     }
+    fn create_store_manager_with_options(
+        &self,
+        _data_dir: Option<String>,
+        options: &HashMap<String, String>,
+    ) -> Result<Box<dyn StoreManager>> {
+        Ok(Box::new(DummyStoreManager::with_failures(FailureSchedule::from_options(options)?)))
+    }
 }
 
 pub async fn precise_delay(delay: Duration) {
@@ -98,4 +311,91 @@ pub async fn precise_delay(delay: Duration) {
     });
 
     let _ = rx.await;
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn schedules_faults_by_operation_number() {
+        let options: HashMap<String, String> = [
+            ("fail_every", "3"),
+            ("spike_every", "10"),
+            ("spike_length", "2"),
+            ("spike_ms", "50"),
+            ("conflict_every", "2"),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+        let schedule = FailureSchedule::from_options(&options).unwrap();
+        let failed: Vec<u64> = (1..=10).filter(|&n| schedule.fails(n)).collect();
+        assert_eq!(failed, [3, 6, 9]);
+        let spiked: Vec<u64> = (1..=22).filter(|&n| !schedule.extra_delay(n).is_zero()).collect();
+        assert_eq!(spiked, [1, 2, 11, 12, 21, 22]);
+        assert!(!schedule.conflicts(1) && schedule.conflicts(2));
+
+        // A seeded rate fails the same operations every time, close to the requested share
+        let seeded = FailureSchedule { fail_rate: Some(0.1), seed: 7, ..FailureSchedule::default() };
+        let failures = (1..=10_000).filter(|&n| seeded.fails(n)).count();
+        assert!((900..1100).contains(&failures), "{} failures", failures);
+        assert!((1..=10_000).all(|n| seeded.fails(n) == seeded.clone().fails(n)));
+    }
+
+    #[test]
+    fn paces_a_minute_of_writes_on_virtual_time() {
+        // The target counts events, whether they are appended one at a time or in batches
+        for batch_size in [1, 5] {
+            let yaml = format!(
+                "name: paced\nworkload_type: performance\nmode: write\nduration_seconds: 60\nconcurrency: {{writers: 2}}\n\
+                 operations: {{write: {{event_size_bytes: 64, batch_size: {}}}}}\ntarget_rate_eps: 50\n",
+                batch_size
+            );
+            let workload = bench_core::WorkloadFactory::create_from_yaml(&yaml, 1).unwrap();
+            let runtime = bench_core::virtual_time::runtime().unwrap();
+            let store = Box::new(DummyStoreManager::new());
+            let metrics = runtime
+                .block_on(bench_core::execute_run(store, &workload, tokio_util::sync::CancellationToken::new()))
+                .unwrap();
+            // 50 events per second for the minute and the second of warm-up before it, each
+            // append taking exactly the simulated latency
+            assert_eq!(metrics.summary.duration_s, 60.0);
+            let written = metrics.summary.events_written;
+            assert!((3040..=3060).contains(&written), "batch_size {}: {}", batch_size, written);
+            assert!((metrics.summary.latency.p50_ms - 5.0).abs() < 0.01);
+        }
+    }
+
+    #[test]
+    fn lists_the_prepopulated_streams() {
+        let yaml = "name: listed\nworkload_type: performance\nmode: list_streams\nduration_seconds: 5\nconcurrency: {readers: 1}\n\
+                    list_streams: {limit: 4}\noperations: {write: {event_size_bytes: 64}}\n\
+                    setup: {prepopulate_events: 30, prepopulate_streams: 10}\n";
+        let workload = bench_core::WorkloadFactory::create_from_yaml(yaml, 1).unwrap();
+        let runtime = bench_core::virtual_time::runtime().unwrap();
+        let store = Box::new(DummyStoreManager::new());
+        let metrics = runtime
+            .block_on(bench_core::execute_run(store, &workload, tokio_util::sync::CancellationToken::new()))
+            .unwrap();
+        // Every call of the measured 5 s lists a full page of 4 streams
+        let listed = metrics.summary.events_read;
+        assert!(listed > 0 && listed.is_multiple_of(4), "{} streams listed", listed);
+    }
+
+    #[test]
+    fn failing_contention_run_breaks_the_error_rate_assertion() {
+        let yaml = "name: hot\nworkload_type: performance\nmode: contention\nduration_seconds: 5\nconcurrency: {writers: 2}\n\
+                    operations: {write: {event_size_bytes: 64}}\ncontention: {streams: 1}\nassert: {max_error_rate: 0.01}\n";
+        let workload = bench_core::WorkloadFactory::create_from_yaml(yaml, 1).unwrap();
+        let runtime = bench_core::virtual_time::runtime().unwrap();
+        let schedule = FailureSchedule { fail_every: Some(4), ..FailureSchedule::default() };
+        let store = Box::new(DummyStoreManager::with_failures(schedule));
+        let metrics = runtime
+            .block_on(bench_core::execute_run(store, &workload, tokio_util::sync::CancellationToken::new()))
+            .unwrap();
+        let assertion = &metrics.summary.assertions[0];
+        assert_eq!(assertion.assertion, "max_error_rate");
+        assert!(!assertion.passed, "error rate {:?}", assertion.actual);
+        assert!(assertion.actual.is_some_and(|rate| rate > 0.2));
+    }
+}
@@ -3,27 +3,72 @@ use async_trait::async_trait;
 use bench_core::adapter::{
     EventData, EventStoreAdapter, ReadEvent, ReadRequest, StoreDataDir, StoreManager, StoreManagerFactory,
 };
-use bench_core::wait_for_ready;
+use bench_core::instrument::{time_phase, time_phase_async, Phase};
+use bench_core::{wait_for_ready, CloudEvent};
 use bench_testcontainers::eventsourcingdb::{
     EventsourcingDb, EVENTSOURCINGDB_API_TOKEN, EVENTSOURCINGDB_PORT,
 };
+use eventsourcingdb::client::request_options::{Bound, BoundType, ReadEventsOptions};
 use eventsourcingdb::client::Client;
 use eventsourcingdb::event::EventCandidate;
 use futures::StreamExt;
 use serde_json::json;
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use testcontainers::runners::AsyncRunner;
 use testcontainers::ContainerAsync;
 use tokio::time::Duration;
 use url::Url;
 
+/// Source of events whose payload isn't a CloudEvent
+const BENCH_SOURCE: &str = "https://bench.eventsourcingdb.io";
+/// Query parameter of an event's source holding each tag after the stream tag (the subject), as
+/// EventsourcingDB events have no tags of their own
+const TAG_PARAM: &str = "tag";
+
+fn tagged_source(source: String, extra_tags: &[String]) -> String {
+    if extra_tags.is_empty() {
+        return source;
+    }
+    let query = url::form_urlencoded::Serializer::new(String::new())
+        .extend_pairs(extra_tags.iter().map(|tag| (TAG_PARAM, tag)))
+        .finish();
+    let separator = if source.contains('?') { '&' } else { '?' };
+    format!("{}{}{}", source, separator, query)
+}
+
+/// Tags of an event read from `stream`: the stream, then those in its source's query
+fn source_tags(stream: &str, source: &str) -> Vec<String> {
+    let mut tags = vec![stream.to_string()];
+    if let Some((_, query)) = source.split_once('?') {
+        tags.extend(
+            url::form_urlencoded::parse(query.as_bytes())
+                .filter(|(key, _)| key == TAG_PARAM)
+                .map(|(_, tag)| tag.into_owned()),
+        );
+    }
+    tags
+}
+
+/// Certificate and private key files (on the host) the container serves HTTPS with
+struct TlsFiles {
+    certificate_file: String,
+    private_key_file: String,
+}
+
 // Store manager - handles lifecycle and adapter creation
 pub struct EventsourcingDbStoreManager {
     uri: Option<String>,
     options: HashMap<String, String>,
     container: Option<ContainerAsync<EventsourcingDb>>,
     data_dir: StoreDataDir,
+    /// Tokens the server accepts; adapters take them in turn
+    api_tokens: Vec<String>,
+    next_token: AtomicUsize,
+    tls: Option<TlsFiles>,
+    /// Image tag from the `image_tag` store option (the pinned default when unset)
+    image_tag: Option<String>,
 }
 
 impl EventsourcingDbStoreManager {
@@ -33,34 +78,98 @@ impl EventsourcingDbStoreManager {
             container: None,
             options: HashMap::new(),
             data_dir: StoreDataDir::new(data_dir, "eventsourcingdb"),
+            api_tokens: vec![EVENTSOURCINGDB_API_TOKEN.to_string()],
+            next_token: AtomicUsize::new(0),
+            tls: None,
+            image_tag: None,
+        }
+    }
+
+    /// Configure a secured server from store options:
+    /// - `api_tokens`: comma-separated tokens the server accepts; each adapter (client) uses
+    ///   the next one in turn, so load is spread over all of them
+    /// - `tls_certificate_file`, `tls_private_key_file`: serve HTTPS only. The certificate
+    ///   must be valid for `localhost` and trusted by the host (e.g. via `SSL_CERT_FILE`).
+    /// - `image_tag`: run another EventsourcingDB version
+    pub fn with_options(data_dir: Option<String>, options: &HashMap<String, String>) -> Result<Self> {
+        let mut manager = Self::new(data_dir);
+        for key in options.keys() {
+            if !["api_tokens", "tls_certificate_file", "tls_private_key_file", "image_tag"].contains(&key.as_str()) {
+                anyhow::bail!("unknown eventsourcingdb store option '{}'", key);
+            }
+        }
+        if let Some(tokens) = options.get("api_tokens") {
+            manager.api_tokens = tokens
+                .split(',')
+                .map(|token| token.trim().to_string())
+                .filter(|token| !token.is_empty())
+                .collect();
+            if manager.api_tokens.is_empty() {
+                anyhow::bail!("api_tokens must list at least one token");
+            }
+        }
+        manager.tls = match (options.get("tls_certificate_file"), options.get("tls_private_key_file")) {
+            (Some(certificate_file), Some(private_key_file)) => Some(TlsFiles {
+                certificate_file: absolute_path(certificate_file)?,
+                private_key_file: absolute_path(private_key_file)?,
+            }),
+            (None, None) => None,
+            _ => anyhow::bail!("tls_certificate_file and tls_private_key_file must be set together"),
+        };
+        manager.image_tag = options.get("image_tag").cloned();
+        Ok(manager)
+    }
+
+    /// The image to run, at the tag from the `image_tag` store option if set
+    fn image(&self, mount_path: Option<String>) -> EventsourcingDb {
+        let image = EventsourcingDb::new(mount_path);
+        match &self.image_tag {
+            Some(tag) => image.with_image_tag(tag),
+            None => image,
         }
     }
 }
 
+/// Bind mounts need absolute host paths
+fn absolute_path(path: &str) -> Result<String> {
+    let path = std::fs::canonicalize(path)
+        .map_err(|e| anyhow::anyhow!("cannot read TLS file {}: {}", path, e))?;
+    Ok(path.to_string_lossy().to_string())
+}
+
 #[async_trait]
 impl StoreManager for EventsourcingDbStoreManager {
     async fn start(&mut self) -> Result<()> {
         let mount_path = self.data_dir.setup()?;
-        let container = EventsourcingDb::new(mount_path).start().await?;
+        let mut image = self.image(mount_path).with_api_tokens(self.api_tokens.clone());
+        if let Some(tls) = &self.tls {
+            image = image.with_tls(tls.certificate_file.clone(), tls.private_key_file.clone());
+        }
+        let container = image.start().await?;
         let host_port = container.get_host_port_ipv4(EVENTSOURCINGDB_PORT).await?;
-        self.uri = Some(format!("http://localhost:{}/", host_port));
+        let scheme = if self.tls.is_some() { "https" } else { "http" };
+        self.uri = Some(format!("{}://localhost:{}/", scheme, host_port));
         self.container = Some(container);
 
-        // Use the default API token for the container
-        self.options
-            .insert("api_token".to_string(), EVENTSOURCINGDB_API_TOKEN.to_string());
-
         let url: Url = self.uri.clone().unwrap().parse()?;
         wait_for_ready("EventsourcingDB", || async {
-            let client = Client::new(url.clone(), EVENTSOURCINGDB_API_TOKEN);
+            let client = Client::new(url.clone(), self.api_tokens[0].clone());
             client.ping().await.map_err(|e| anyhow::anyhow!(e))
         }, Duration::from_secs(60)).await?;
 
+        // Fail before the run rather than with rejected appends if a token is not accepted
+        for token in &self.api_tokens {
+            Client::new(url.clone(), token.clone())
+                .verify_api_token()
+                .await
+                .map_err(|e| anyhow::anyhow!("API token rejected by EventsourcingDB: {}", e))?;
+        }
+
         Ok(())
     }
 
     async fn pull(&mut self) -> Result<()> {
-        let _ = EventsourcingDb::new(None).pull_image().await?;
+        let _ = self.image(None).pull_image().await?;
         Ok(())
     }
 
@@ -76,12 +185,22 @@ impl StoreManager for EventsourcingDbStoreManager {
         self.container.as_ref().map(|c| c.id().to_string())
     }
 
+    fn endpoint(&self) -> Option<String> {
+        self.uri.clone()
+    }
+
     fn name(&self) -> &'static str {
         "eventsourcingdb"
     }
 
     fn create_adapter(&self) -> Result<Arc<dyn EventStoreAdapter>> {
-        Ok(Arc::new(EventsourcingDbAdapter::new(&self.uri.clone().unwrap(), &self.options)?))
+        let turn = self.next_token.fetch_add(1, Ordering::Relaxed);
+        let mut options = self.options.clone();
+        options.insert(
+            "api_token".to_string(),
+            self.api_tokens[turn % self.api_tokens.len()].clone(),
+        );
+        Ok(Arc::new(EventsourcingDbAdapter::new(&self.uri.clone().unwrap(), &options)?))
     }
 }
 
@@ -104,14 +223,23 @@ impl EventsourcingDbAdapter {
 #[async_trait]
 impl EventStoreAdapter for EventsourcingDbAdapter {
     async fn append(&self, events: Vec<EventData>) -> Result<()> {
-        let candidates: Vec<EventCandidate> = events.into_iter().map(|evt| {
+        let candidates: Vec<EventCandidate> = time_phase(Phase::Serialize, || events.into_iter().map(|evt| {
+            // CloudEvents payloads map onto the event's own fields
+            if let Some(event) = CloudEvent::parse(&evt.payload) {
+                return EventCandidate::builder()
+                    .source(tagged_source(event.source, evt.extra_tags()))
+                    .subject(format!("/{}", evt.tags[0]))
+                    .ty(event.ty)
+                    .data(event.data)
+                    .build();
+            }
             let data: serde_json::Value = serde_json::from_slice(&evt.payload).unwrap_or_else(|_| {
                 json!({"raw": serde_json::Value::String(
                     String::from_utf8_lossy(&evt.payload).to_string()
                 )})
             });
             EventCandidate::builder()
-                .source("https://bench.eventsourcingdb.io".to_string())
+                .source(tagged_source(BENCH_SOURCE.to_string(), evt.extra_tags()))
                 .subject(format!("/{}", evt.tags[0]))
                 .ty(if evt.event_type.contains('.') {
                     evt.event_type
@@ -120,10 +248,9 @@ impl EventStoreAdapter for EventsourcingDbAdapter {
                 })
                 .data(data)
                 .build()
-        }).collect();
+        }).collect());
 
-        self.client
-            .write_events(candidates, vec![])
+        time_phase_async(Phase::Network, self.client.write_events(candidates, vec![]))
             .await
             .map_err(|e| anyhow::anyhow!("{}", e))?;
         Ok(())
@@ -131,14 +258,12 @@ impl EventStoreAdapter for EventsourcingDbAdapter {
 
     async fn read(&self, req: ReadRequest) -> Result<Vec<ReadEvent>> {
         let subject = format!("/{}", req.stream);
-        let mut stream = self
-            .client
-            .read_events(&subject, None)
+        let mut stream = time_phase_async(Phase::Network, self.client.read_events(&subject, None))
             .await
             .map_err(|e| anyhow::anyhow!("{}", e))?;
         let mut out = Vec::new();
         let mut offset: u64 = 0;
-        while let Some(result) = stream.next().await {
+        while let Some(result) = time_phase_async(Phase::Network, stream.next()).await {
             let event = result.map_err(|e| anyhow::anyhow!("{}", e))?;
             let current_offset = offset;
             offset += 1;
@@ -147,13 +272,14 @@ impl EventStoreAdapter for EventsourcingDbAdapter {
                     continue;
                 }
             }
-            let payload = serde_json::to_vec(event.data())?;
+            let payload = time_phase(Phase::Deserialize, || serde_json::to_vec(event.data()))?;
             let timestamp_ms = event.time().timestamp_millis() as u64;
             out.push(ReadEvent {
                 offset: current_offset,
                 event_type: event.ty().to_string(),
-                payload,
+                payload: payload.into(),
                 timestamp_ms,
+                tags: source_tags(&req.stream, event.source()),
             });
             if let Some(lim) = req.limit {
                 if out.len() as u64 >= lim {
@@ -164,14 +290,73 @@ impl EventStoreAdapter for EventsourcingDbAdapter {
         Ok(out)
     }
 
-    // async fn ping(&self) -> Result<Duration> {
-    //     let t0 = std::time::Instant::now();
-    //     self.client
-    //         .ping()
-    //         .await
-    //         .map_err(|e| anyhow::anyhow!("{}", e))?;
-    //     Ok(t0.elapsed())
-    // }
+    /// Reads the root subject recursively; positions are the event IDs, which count up from 0
+    /// across all subjects
+    async fn read_all(&self, after: Option<u64>, limit: Option<u64>) -> Result<Vec<ReadEvent>> {
+        if limit == Some(0) {
+            return Ok(Vec::new());
+        }
+        let after = after.map(|after| after.to_string());
+        let options = ReadEventsOptions {
+            recursive: true,
+            lower_bound: after.as_deref().map(|id| Bound {
+                bound_type: BoundType::Exclusive,
+                id,
+            }),
+            ..Default::default()
+        };
+        let mut stream = time_phase_async(Phase::Network, self.client.read_events("/", Some(options)))
+            .await
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
+        let mut out = Vec::new();
+        while let Some(result) = time_phase_async(Phase::Network, stream.next()).await {
+            let event = result.map_err(|e| anyhow::anyhow!("{}", e))?;
+            let offset = event
+                .id()
+                .parse()
+                .map_err(|_| anyhow::anyhow!("EventsourcingDB event ID '{}' is not a number", event.id()))?;
+            let payload = time_phase(Phase::Deserialize, || serde_json::to_vec(event.data()))?;
+            out.push(ReadEvent {
+                offset,
+                event_type: event.ty().to_string(),
+                payload: payload.into(),
+                timestamp_ms: event.time().timestamp_millis() as u64,
+                tags: source_tags(event.subject().trim_start_matches('/'), event.source()),
+            });
+            if limit.is_some_and(|lim| out.len() as u64 >= lim) {
+                break;
+            }
+        }
+        Ok(out)
+    }
+
+    async fn list_streams(&self, prefix: &str, limit: Option<u64>) -> Result<Vec<String>> {
+        let mut subjects = self
+            .client
+            .list_subjects(Some("/"))
+            .await
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
+        let mut out = Vec::new();
+        while let Some(result) = subjects.next().await {
+            let subject = result.map_err(|e| anyhow::anyhow!("{}", e))?;
+            let stream = subject.trim_start_matches('/');
+            if !stream.starts_with(prefix) {
+                continue;
+            }
+            out.push(stream.to_string());
+            if limit.is_some_and(|lim| out.len() as u64 >= lim) {
+                break;
+            }
+        }
+        Ok(out)
+    }
+
+    async fn ping(&self) -> Result<()> {
+        self.client
+            .ping()
+            .await
+            .map_err(|e| anyhow::anyhow!("{}", e))
+    }
 }
 
 pub struct EventsourcingDbFactory;
@@ -184,4 +369,12 @@ impl StoreManagerFactory for EventsourcingDbFactory {
     fn create_store_manager(&self, data_dir: Option<String>) -> Result<Box<dyn StoreManager>> {
         Ok(Box::new(EventsourcingDbStoreManager::new(data_dir)))
     }
+
+    fn create_store_manager_with_options(
+        &self,
+        data_dir: Option<String>,
+        options: &HashMap<String, String>,
+    ) -> Result<Box<dyn StoreManager>> {
+        Ok(Box::new(EventsourcingDbStoreManager::with_options(data_dir, options)?))
+    }
 }
@@ -0,0 +1,17 @@
+use bench_core::testkit::{run_conformance, Capabilities};
+use bench_core::StoreManagerFactory;
+use eventsourcingdb_adapter::EventsourcingDbFactory;
+
+#[tokio::test]
+#[ignore = "starts an EventsourcingDB container (needs Docker)"]
+async fn eventsourcingdb_conforms() -> anyhow::Result<()> {
+    let mut store = EventsourcingDbFactory.create_store_manager(None)?;
+    store.start().await?;
+    let capabilities = Capabilities {
+        read_all: true,
+        ..Capabilities::default()
+    };
+    let result = run_conformance(store.as_ref(), capabilities).await;
+    store.stop().await?;
+    result
+}
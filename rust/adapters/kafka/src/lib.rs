@@ -0,0 +1,335 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use bench_core::adapter::{
+    ConnectionParams, EventData, EventStoreAdapter, ReadEvent, ReadRequest, StoreDataDir, StoreManager,
+    StoreManagerFactory,
+};
+use bench_core::instrument::{time_phase, time_phase_async, Phase};
+use bench_core::wait_for_ready;
+use bench_testcontainers::redpanda::{Redpanda, REDPANDA_PORT};
+use rdkafka::admin::{AdminClient, AdminOptions, NewTopic, TopicReplication};
+use rdkafka::client::DefaultClientContext;
+use rdkafka::config::ClientConfig;
+use rdkafka::consumer::{BaseConsumer, Consumer};
+use rdkafka::error::{KafkaError, RDKafkaErrorCode};
+use rdkafka::message::{Header, Headers, Message, OwnedHeaders};
+use rdkafka::producer::{FutureProducer, FutureRecord, Producer};
+use rdkafka::{Offset, TopicPartitionList};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use testcontainers::runners::AsyncRunner;
+use testcontainers::{ContainerAsync, ImageExt};
+use tokio::time::Duration;
+
+/// Timeout of broker requests (metadata, watermarks, polls and queueing a record)
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+/// Header holding the event type of a record
+const TYPE_HEADER: &str = "type";
+/// Header repeated for each tag after the stream tag (the record key), in order
+const TAG_HEADER: &str = "tag";
+
+/// Store options and the producer settings they map to
+const PRODUCER_OPTIONS: [(&str, &str); 4] = [
+    ("acks", "acks"),
+    ("linger_ms", "linger.ms"),
+    ("batch_size", "batch.num.messages"),
+    ("compression", "compression.type"),
+];
+
+// Store manager - handles lifecycle and adapter creation
+pub struct KafkaStoreManager {
+    params: Option<ConnectionParams>,
+    container: Option<ContainerAsync<Redpanda>>,
+    data_dir: StoreDataDir,
+    /// Producer settings from store options, passed to adapters as connection options
+    options: HashMap<String, String>,
+    /// Image tag from the `image_tag` store option (the pinned default when unset)
+    image_tag: Option<String>,
+}
+
+impl KafkaStoreManager {
+    pub fn new(data_dir: Option<String>) -> Self {
+        Self {
+            params: None,
+            container: None,
+            data_dir: StoreDataDir::new(data_dir, "kafka"),
+            options: HashMap::new(),
+            image_tag: None,
+        }
+    }
+
+    /// Configure the producers from store options: `acks` (`0`, `1` or `all`), `linger_ms`
+    /// (time to wait for more records to batch), `batch_size` (most records per batch) and
+    /// `compression` (`none`, `gzip`, `snappy`, `lz4` or `zstd`); `image_tag` runs another
+    /// Redpanda version
+    pub fn with_options(data_dir: Option<String>, options: &HashMap<String, String>) -> Result<Self> {
+        let mut manager = Self::new(data_dir);
+        for (key, value) in options {
+            match key.as_str() {
+                "image_tag" => manager.image_tag = Some(value.clone()),
+                key if PRODUCER_OPTIONS.iter().any(|(option, _)| *option == key) => {
+                    manager.options.insert(key.to_string(), value.clone());
+                }
+                other => anyhow::bail!("unknown kafka store option '{}'", other),
+            }
+        }
+        Ok(manager)
+    }
+
+    /// The image to run, at the tag from the `image_tag` store option if set
+    fn image(&self, mount_path: Option<String>, host_port: u16) -> Redpanda {
+        let image = Redpanda::new(mount_path, host_port);
+        match &self.image_tag {
+            Some(tag) => image.with_image_tag(tag),
+            None => image,
+        }
+    }
+}
+
+/// A free port on the host; the broker advertises the port it is mapped to, so it must be
+/// known before the container starts
+fn free_host_port() -> Result<u16> {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0")?;
+    Ok(listener.local_addr()?.port())
+}
+
+#[async_trait]
+impl StoreManager for KafkaStoreManager {
+    async fn start(&mut self) -> Result<()> {
+        let mount_path = self.data_dir.setup()?;
+        let host_port = free_host_port()?;
+        let container = self
+            .image(mount_path, host_port)
+            .with_mapped_port(host_port, REDPANDA_PORT)
+            .start()
+            .await?;
+        self.container = Some(container);
+
+        let params = ConnectionParams {
+            uri: format!("localhost:{}", host_port),
+            options: self.options.clone(),
+        };
+        wait_for_ready("Kafka", || async {
+            let adapter = KafkaAdapter::connect(&params)?;
+            adapter.ping().await
+        }, Duration::from_secs(60)).await?;
+        self.params = Some(params);
+
+        Ok(())
+    }
+
+    async fn pull(&mut self) -> Result<()> {
+        let _ = self.image(None, REDPANDA_PORT.as_u16()).pull_image().await?;
+        Ok(())
+    }
+
+    async fn stop(&mut self) -> Result<()> {
+        if let Some(container) = self.container.take() {
+            container.stop().await?;
+        }
+        self.data_dir.cleanup()?;
+        Ok(())
+    }
+
+    fn container_id(&self) -> Option<String> {
+        self.container.as_ref().map(|c| c.id().to_string())
+    }
+
+    fn endpoint(&self) -> Option<String> {
+        self.params.as_ref().map(|params| params.uri.clone())
+    }
+
+    fn name(&self) -> &'static str {
+        "kafka"
+    }
+
+    fn create_adapter(&self) -> Result<Arc<dyn EventStoreAdapter>> {
+        let params = self.params.as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Kafka not started. Did you call start()?"))?;
+        Ok(Arc::new(KafkaAdapter::connect(params)?))
+    }
+}
+
+/// Topic of a stream: its category, the part of its name before the first `-` (as in
+/// `account-123`), with characters Kafka doesn't allow in topic names replaced
+fn topic_of(stream: &str) -> String {
+    let category = stream.split('-').next().unwrap_or(stream);
+    category
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '.' || c == '_' { c } else { '_' })
+        .collect()
+}
+
+// Adapter - a producer, a consumer for reads and an admin client creating topics on first use.
+// Each topic has a single partition keyed by stream, so a stream's events keep their order and
+// reads scan the partition for the stream's key.
+pub struct KafkaAdapter {
+    producer: FutureProducer,
+    consumer: Arc<Mutex<BaseConsumer>>,
+    admin: AdminClient<DefaultClientContext>,
+    topics: tokio::sync::Mutex<HashSet<String>>,
+}
+
+impl KafkaAdapter {
+    pub fn connect(params: &ConnectionParams) -> Result<Self> {
+        let mut producer_config = ClientConfig::new();
+        producer_config.set("bootstrap.servers", &params.uri);
+        for (key, value) in &params.options {
+            let (_, property) = PRODUCER_OPTIONS
+                .iter()
+                .find(|(option, _)| option == key)
+                .ok_or_else(|| anyhow::anyhow!("unknown kafka connection option '{}'", key))?;
+            producer_config.set(*property, value);
+        }
+        let consumer = ClientConfig::new()
+            .set("bootstrap.servers", &params.uri)
+            .set("group.id", "es-bench")
+            .set("enable.auto.commit", "false")
+            .set("enable.partition.eof", "false")
+            .create()?;
+        let admin = ClientConfig::new().set("bootstrap.servers", &params.uri).create()?;
+        Ok(Self {
+            producer: producer_config.create()?,
+            consumer: Arc::new(Mutex::new(consumer)),
+            admin,
+            topics: tokio::sync::Mutex::new(HashSet::new()),
+        })
+    }
+
+    /// Create the topic with one partition unless this adapter already did (or found it)
+    async fn ensure_topic(&self, topic: &str) -> Result<()> {
+        let mut topics = self.topics.lock().await;
+        if topics.contains(topic) {
+            return Ok(());
+        }
+        let new_topic = NewTopic::new(topic, 1, TopicReplication::Fixed(1));
+        let results = self.admin.create_topics([&new_topic], &AdminOptions::new()).await?;
+        for result in results {
+            match result {
+                Ok(_) | Err((_, RDKafkaErrorCode::TopicAlreadyExists)) => {}
+                Err((topic, code)) => anyhow::bail!("Failed to create topic {}: {}", topic, code),
+            }
+        }
+        topics.insert(topic.to_string());
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl EventStoreAdapter for KafkaAdapter {
+    /// Every event is a record keyed by its stream; the append completes once all of them are
+    /// acknowledged as configured by `acks`
+    async fn append(&self, events: Vec<EventData>) -> Result<()> {
+        let mut records = Vec::with_capacity(events.len());
+        for evt in &events {
+            let stream = evt
+                .tags
+                .first()
+                .ok_or_else(|| anyhow::anyhow!("Kafka appends require a stream tag"))?;
+            let topic = topic_of(stream);
+            self.ensure_topic(&topic).await?;
+            records.push((topic, stream, evt));
+        }
+        let sends = time_phase(Phase::Serialize, || {
+            records.iter().map(|(topic, stream, evt)| {
+                let headers = evt.extra_tags().iter().fold(
+                    OwnedHeaders::new().insert(Header {
+                        key: TYPE_HEADER,
+                        value: Some(evt.event_type.as_str()),
+                    }),
+                    |headers, tag| headers.insert(Header { key: TAG_HEADER, value: Some(tag.as_str()) }),
+                );
+                let record = FutureRecord::to(topic)
+                    .key(stream.as_str())
+                    .payload(evt.payload.as_ref())
+                    .headers(headers);
+                self.producer.send(record, REQUEST_TIMEOUT)
+            })
+            .collect::<Vec<_>>()
+        });
+        for result in time_phase_async(Phase::Network, futures::future::join_all(sends)).await {
+            result.map_err(|(e, _)| anyhow::anyhow!("Kafka append failed: {}", e))?;
+        }
+        Ok(())
+    }
+
+    /// Offsets are positions in the stream's topic, shared with the other streams of its category
+    async fn read(&self, req: ReadRequest) -> Result<Vec<ReadEvent>> {
+        let consumer = self.consumer.clone();
+        let task = tokio::task::spawn_blocking(move || -> Result<Vec<ReadEvent>> {
+            let consumer = consumer.lock().map_err(|_| anyhow::anyhow!("Kafka consumer poisoned"))?;
+            let topic = topic_of(&req.stream);
+            let (low, high) = match consumer.fetch_watermarks(&topic, 0, REQUEST_TIMEOUT) {
+                Ok(watermarks) => watermarks,
+                Err(KafkaError::MetadataFetch(RDKafkaErrorCode::UnknownTopicOrPartition)) => return Ok(Vec::new()),
+                Err(e) => return Err(e.into()),
+            };
+            let start = (req.from_offset.unwrap_or(0) as i64).max(low);
+            let limit = req.limit.unwrap_or(u64::MAX) as usize;
+            let mut events = Vec::new();
+            if start >= high || limit == 0 {
+                return Ok(events);
+            }
+
+            let mut assignment = TopicPartitionList::new();
+            assignment.add_partition_offset(&topic, 0, Offset::Offset(start))?;
+            consumer.assign(&assignment)?;
+            // Scan up to the end as of the start of the read
+            loop {
+                let message = consumer
+                    .poll(REQUEST_TIMEOUT)
+                    .ok_or_else(|| anyhow::anyhow!("Timed out reading topic {}", topic))??;
+                if message.key() == Some(req.stream.as_bytes()) {
+                    let header_values = |key: &'static str| {
+                        message
+                            .headers()
+                            .into_iter()
+                            .flat_map(|headers| headers.iter())
+                            .filter(move |h| h.key == key)
+                            .map(|h| String::from_utf8_lossy(h.value.unwrap_or_default()).to_string())
+                    };
+                    let event_type = header_values(TYPE_HEADER).next().unwrap_or_default();
+                    let tags = std::iter::once(req.stream.clone()).chain(header_values(TAG_HEADER)).collect();
+                    events.push(ReadEvent {
+                        offset: message.offset() as u64,
+                        event_type,
+                        payload: message.payload().unwrap_or_default().to_vec().into(),
+                        timestamp_ms: message.timestamp().to_millis().unwrap_or(0) as u64,
+                        tags,
+                    });
+                }
+                if events.len() >= limit || message.offset() + 1 >= high {
+                    break;
+                }
+            }
+            Ok(events)
+        });
+        time_phase_async(Phase::Network, task).await?
+    }
+
+    async fn ping(&self) -> Result<()> {
+        let producer = self.producer.clone();
+        tokio::task::spawn_blocking(move || producer.client().fetch_metadata(None, REQUEST_TIMEOUT)).await??;
+        Ok(())
+    }
+}
+
+pub struct KafkaFactory;
+
+impl StoreManagerFactory for KafkaFactory {
+    fn name(&self) -> &'static str {
+        "kafka"
+    }
+
+    fn create_store_manager(&self, data_dir: Option<String>) -> Result<Box<dyn StoreManager>> {
+        Ok(Box::new(KafkaStoreManager::new(data_dir)))
+    }
+
+    fn create_store_manager_with_options(
+        &self,
+        data_dir: Option<String>,
+        options: &HashMap<String, String>,
+    ) -> Result<Box<dyn StoreManager>> {
+        Ok(Box::new(KafkaStoreManager::with_options(data_dir, options)?))
+    }
+}
@@ -0,0 +1,18 @@
+use bench_core::testkit::{run_conformance, Capabilities};
+use bench_core::StoreManagerFactory;
+use kafka_adapter::KafkaFactory;
+
+#[tokio::test]
+#[ignore = "starts a Redpanda container (needs Docker)"]
+async fn kafka_conforms() -> anyhow::Result<()> {
+    let mut store = KafkaFactory.create_store_manager(None)?;
+    store.start().await?;
+    let capabilities = Capabilities {
+        conditional_append: false,
+        subscribe: false,
+        read_all: false,
+    };
+    let result = run_conformance(store.as_ref(), capabilities).await;
+    store.stop().await?;
+    result
+}
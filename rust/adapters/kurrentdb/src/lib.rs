@@ -1,22 +1,49 @@
 use anyhow::Result;
 use async_trait::async_trait;
 use bench_core::adapter::{
-    EventData, EventStoreAdapter, ReadEvent, ReadRequest, StoreDataDir, StoreManager, StoreManagerFactory,
+    decode_tags, encode_extra_tags, AdminOp, AppendCondition, AppendOutcome, EventData, EventStoreAdapter,
+    EventStream, ReadConsistency, ReadEvent, ReadRequest, ReadStream, RetentionPolicy, StoreDataDir,
+    StoreManager, StoreManagerFactory,
 };
+use futures::TryStreamExt;
+use bench_core::instrument::{time_phase, time_phase_async, Phase};
 use bench_core::wait_for_ready;
 use bench_testcontainers::kurrentdb::{KurrentDb, KURRENTDB_PORT};
-use kurrentdb::{AppendToStreamOptions, Client, ClientSettings, ReadStreamOptions, StreamPosition};
+use kurrentdb::{
+    AppendToStreamOptions, Client, ClientSettings, CreateProjectionOptions, CurrentRevision,
+    GenericProjectionOptions, Position, ProjectionClient, ReadAllOptions, ReadStreamOptions, ResolvedEvent,
+    StreamMetadata, StreamPosition, StreamState, SubscribeToStreamOptions,
+};
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use testcontainers::runners::AsyncRunner;
 use testcontainers::ContainerAsync;
 use tokio::time::Duration;
 use uuid::Uuid;
 
+/// Continuous projection kept running by the projections variant: a per-stream event count
+/// over everything written, which makes the projection subsystem read and checkpoint
+/// alongside the workload like a typical read-model projection would
+const PROJECTION_NAME: &str = "bench-events-per-stream";
+const PROJECTION_QUERY: &str = r#"fromAll()
+  .foreachStream()
+  .when({
+    $init: function () { return { count: 0 }; },
+    $any: function (state, event) { state.count += 1; return state; }
+  });"#;
+
 // Store manager - handles lifecycle and adapter creation
 pub struct KurrentDbStoreManager {
     uri: Option<String>,
     container: Option<ContainerAsync<KurrentDb>>,
     data_dir: StoreDataDir,
+    projections: bool,
+    /// Extra connection string settings from store options
+    connection_settings: String,
+    /// Image tag from the `image_tag` store option (the pinned default when unset)
+    image_tag: Option<String>,
+    /// Resolve link events to the events they point at on reads (`resolve_link_tos` store option)
+    resolve_link_tos: bool,
 }
 
 impl KurrentDbStoreManager {
@@ -25,6 +52,101 @@ impl KurrentDbStoreManager {
             uri: None,
             container: None,
             data_dir: StoreDataDir::new(data_dir, "kurrentdb"),
+            projections: false,
+            connection_settings: String::new(),
+            image_tag: None,
+            resolve_link_tos: false,
+        }
+    }
+
+    /// Start the standard projections and a continuous user projection before the workload
+    pub fn with_projections(data_dir: Option<String>) -> Self {
+        Self {
+            uri: None,
+            container: None,
+            data_dir: StoreDataDir::new(data_dir, "kurrentdb-projections"),
+            projections: true,
+            connection_settings: String::new(),
+            image_tag: None,
+            resolve_link_tos: false,
+        }
+    }
+
+    /// Configure the client from store options: `keepalive` (`on`/`off`) toggles gRPC
+    /// keep-alive pings, `resolve_link_tos` (`on`/`off`) resolves link events on reads,
+    /// `node_preference` (`leader`, `follower`, `random`, `read_only_replica`) picks the node
+    /// reads go to in a cluster, `image_tag` runs another KurrentDB version
+    pub fn with_options(mut self, options: &HashMap<String, String>) -> Result<Self> {
+        for (key, value) in options {
+            match (key.as_str(), value.as_str()) {
+                ("keepalive", "on" | "true") => {}
+                ("keepalive", "off" | "false") => {
+                    self.connection_settings.push_str("&keepAliveInterval=-1&keepAliveTimeout=-1")
+                }
+                ("keepalive", other) => {
+                    anyhow::bail!("invalid kurrentdb keepalive '{}' (expected on or off)", other)
+                }
+                ("resolve_link_tos", "on" | "true") => self.resolve_link_tos = true,
+                ("resolve_link_tos", "off" | "false") => self.resolve_link_tos = false,
+                ("resolve_link_tos", other) => {
+                    anyhow::bail!("invalid kurrentdb resolve_link_tos '{}' (expected on or off)", other)
+                }
+                ("node_preference", preference @ ("leader" | "follower" | "random" | "read_only_replica")) => {
+                    self.connection_settings
+                        .push_str(&format!("&nodePreference={}", preference.replace('_', "")))
+                }
+                ("node_preference", other) => anyhow::bail!(
+                    "invalid kurrentdb node_preference '{}' (expected leader, follower, random or read_only_replica)",
+                    other
+                ),
+                ("image_tag", tag) => self.image_tag = Some(tag.to_string()),
+                (other, _) => anyhow::bail!("unknown kurrentdb store option '{}'", other),
+            }
+        }
+        Ok(self)
+    }
+
+    /// The image to run, at the tag from the `image_tag` store option if set
+    fn image(&self, mount_path: Option<String>) -> KurrentDb {
+        let image = KurrentDb::new(mount_path);
+        match &self.image_tag {
+            Some(tag) => image.with_image_tag(tag),
+            None => image,
+        }
+    }
+
+    async fn create_projection(&self, settings: ClientSettings) -> Result<()> {
+        let client = ProjectionClient::new(settings).map_err(|e| anyhow::anyhow!(e))?;
+        let options = CreateProjectionOptions::default();
+        client
+            .create(PROJECTION_NAME, PROJECTION_QUERY.to_string(), &options)
+            .await?;
+        println!("Created continuous projection '{}'", PROJECTION_NAME);
+        Ok(())
+    }
+
+    /// Print how far the projection got, so a lagging or faulted projection is visible
+    async fn report_projection(&self) {
+        let Some(uri) = &self.uri else {
+            return;
+        };
+        let Ok(settings) = uri.parse::<ClientSettings>() else {
+            return;
+        };
+        let Ok(client) = ProjectionClient::new(settings) else {
+            return;
+        };
+        match client.get_status(PROJECTION_NAME, &GenericProjectionOptions::default()).await {
+            Ok(Some(status)) => println!(
+                "Projection '{}': {} ({:.1}% progress, {} events processed, {} buffered)",
+                PROJECTION_NAME,
+                status.status,
+                status.progress,
+                status.events_processed_after_restart,
+                status.buffered_events
+            ),
+            Ok(None) => println!("Projection '{}' not found", PROJECTION_NAME),
+            Err(e) => eprintln!("Failed to read projection status: {}", e),
         }
     }
 }
@@ -33,9 +155,14 @@ impl KurrentDbStoreManager {
 impl StoreManager for KurrentDbStoreManager {
     async fn start(&mut self) -> Result<()> {
         let mount_path = self.data_dir.setup()?;
-        let container = KurrentDb::new(mount_path).start().await?;
+        let image = self.image(mount_path);
+        let image = if self.projections { image.with_standard_projections() } else { image };
+        let container = image.start().await?;
         let host_port = container.get_host_port_ipv4(KURRENTDB_PORT).await?;
-        self.uri = Some(format!("esdb://localhost:{}?tls=false", host_port));
+        self.uri = Some(format!(
+            "esdb://localhost:{}?tls=false{}",
+            host_port, self.connection_settings
+        ));
         self.container = Some(container);
 
         // Wait for the container to be ready
@@ -51,15 +178,22 @@ impl StoreManager for KurrentDbStoreManager {
             Ok(())
         }, Duration::from_secs(60)).await?;
 
+        if self.projections {
+            self.create_projection(uri.parse::<ClientSettings>()?).await?;
+        }
+
         Ok(())
     }
 
     async fn pull(&mut self) -> Result<()> {
-        let _ = KurrentDb::new(None).pull_image().await?;
+        let _ = self.image(None).pull_image().await?;
         Ok(())
     }
 
     async fn stop(&mut self) -> Result<()> {
+        if self.projections {
+            self.report_projection().await;
+        }
         if let Some(container) = self.container.take() {
             container.stop().await?;
         }
@@ -71,25 +205,89 @@ impl StoreManager for KurrentDbStoreManager {
         self.container.as_ref().map(|c| c.id().to_string())
     }
 
+    fn endpoint(&self) -> Option<String> {
+        self.uri.clone()
+    }
+
     fn name(&self) -> &'static str {
-        "kurrentdb"
+        if self.projections {
+            "kurrentdb-projections"
+        } else {
+            "kurrentdb"
+        }
     }
 
     fn create_adapter(&self) -> Result<Arc<dyn EventStoreAdapter>> {
-        Ok(Arc::new(KurrentDbAdapter::new(&self.uri.clone().unwrap())?))
+        let adapter = KurrentDbAdapter::new(&self.uri.clone().unwrap())?;
+        Ok(Arc::new(adapter.with_resolve_link_tos(self.resolve_link_tos)))
     }
 }
 
+/// How often a running scavenge's progress stream is polled for its completion
+const SCAVENGE_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
 // Lightweight adapter - just wraps a client
 pub struct KurrentDbAdapter {
     client: Client,
+    resolve_link_tos: bool,
 }
 
 impl KurrentDbAdapter {
     pub fn new(uri: &str) -> Result<Self> {
         let settings: ClientSettings = uri.parse()?;
         let client = Client::new(settings).map_err(|e| anyhow::anyhow!("{}", e))?;
-        Ok(Self { client })
+        Ok(Self { client, resolve_link_tos: false })
+    }
+
+    /// Resolve link events to the events they point at on reads
+    pub fn with_resolve_link_tos(mut self, resolve_link_tos: bool) -> Self {
+        self.resolve_link_tos = resolve_link_tos;
+        self
+    }
+
+    /// The events of one stream read, at most `max_count` of them requested from the server
+    fn stream_events(&self, req: ReadRequest, max_count: usize) -> ReadStream<'_> {
+        let mut options = ReadStreamOptions::default()
+            .position(match req.from_offset {
+                Some(off) => StreamPosition::Position(off),
+                None => StreamPosition::Start,
+            })
+            .max_count(max_count);
+        options = match req.consistency {
+            ReadConsistency::Default => options,
+            ReadConsistency::Strong => options.requires_leader(true),
+            ReadConsistency::Eventual => options.requires_leader(false),
+        };
+        if self.resolve_link_tos {
+            options = options.resolve_link_tos();
+        }
+        let opened = async move {
+            let stream = time_phase_async(Phase::Network, self.client.read_stream(req.stream, &options)).await?;
+            Ok::<_, anyhow::Error>(events_of(stream, req.limit, |event| Some(Ok(read_event(event)))))
+        };
+        Box::pin(futures::stream::once(opened).try_flatten())
+    }
+
+    /// The events of $all after `after`, up to `limit`, leaving out system streams and with
+    /// prepare positions as offsets
+    fn global_events(&self, after: Option<u64>, limit: Option<u64>) -> ReadStream<'_> {
+        let start = match after {
+            None => StreamPosition::Start,
+            Some(after) => StreamPosition::Position(Position { commit: after, prepare: after }),
+        };
+        let options = ReadAllOptions::default().position(start);
+        let opened = async move {
+            let stream = time_phase_async(Phase::Network, self.client.read_all(&options)).await?;
+            Ok::<_, anyhow::Error>(events_of(stream, limit, move |event| {
+                let recorded = event.get_original_event();
+                let position = recorded.position.prepare;
+                if recorded.stream_id().starts_with('$') || after.is_some_and(|after| position <= after) {
+                    return None;
+                }
+                Some(Ok(ReadEvent { offset: position, ..read_event(event) }))
+            }))
+        };
+        Box::pin(futures::stream::once(opened).try_flatten())
     }
 }
 
@@ -100,36 +298,100 @@ impl EventStoreAdapter for KurrentDbAdapter {
             return Ok(());
         }
         let stream_name = events[0].tags[0].clone();
-        let k_events: Vec<kurrentdb::EventData> = events.into_iter().map(|evt| {
-            kurrentdb::EventData::binary(evt.event_type, evt.payload.into()).id(Uuid::new_v4())
-        }).collect();
+        let k_events: Vec<kurrentdb::EventData> = time_phase(Phase::Serialize, || events.into_iter().map(to_event_data).collect());
         let options = AppendToStreamOptions::default();
-        self.client
-            .append_to_stream(stream_name, &options, k_events)
-            .await?;
+        time_phase_async(
+            Phase::Network,
+            self.client.append_to_stream(stream_name, &options, k_events),
+        )
+        .await?;
         Ok(())
     }
 
     async fn read(&self, req: ReadRequest) -> Result<Vec<ReadEvent>> {
-        let count = req.limit.unwrap_or(4096) as usize;
-        let options = ReadStreamOptions::default()
-            .position(match req.from_offset {
-                Some(off) => StreamPosition::Position(off),
-                None => StreamPosition::Start,
-            })
-            .max_count(count);
-        let mut stream = self.client.read_stream(req.stream, &options).await?;
+        let max_count = req.limit.unwrap_or(4096) as usize;
+        self.stream_events(req, max_count).try_collect().await
+    }
+
+    /// One gRPC read, its events yielded as the server streams them
+    fn read_stream(&self, req: ReadRequest) -> ReadStream<'_> {
+        self.stream_events(req, usize::MAX)
+    }
+
+    async fn append_conditional(
+        &self,
+        events: Vec<EventData>,
+        condition: AppendCondition,
+    ) -> Result<AppendOutcome> {
+        if events.is_empty() {
+            anyhow::bail!("Conditional append requires at least one event");
+        }
+        let stream_name = events[0]
+            .tags
+            .first()
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("Conditional append requires a stream tag"))?;
+        let k_events: Vec<kurrentdb::EventData> = events.into_iter().map(to_event_data).collect();
+        let expected = match condition {
+            AppendCondition::NoStream => StreamState::NoStream,
+            AppendCondition::After(revision) => StreamState::StreamRevision(revision),
+        };
+        let options = AppendToStreamOptions::default().stream_state(expected);
+        match self.client.append_to_stream(stream_name, &options, k_events).await {
+            Ok(result) => Ok(AppendOutcome::Appended {
+                position: result.next_expected_version,
+            }),
+            Err(kurrentdb::Error::WrongExpectedVersion { current, .. }) => Ok(AppendOutcome::Conflict {
+                current: match current {
+                    CurrentRevision::Current(revision) => Some(revision),
+                    CurrentRevision::NoStream => None,
+                },
+            }),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// A catch-up subscription: the server replays the stored events, then pushes new ones
+    async fn subscribe(&self, stream: &str, from_offset: Option<u64>) -> Result<EventStream> {
+        // Subscriptions start after the given revision
+        let start = match from_offset {
+            None | Some(0) => StreamPosition::Start,
+            Some(offset) => StreamPosition::Position(offset - 1),
+        };
+        let options = SubscribeToStreamOptions::default().start_from(start);
+        let subscription = self.client.subscribe_to_stream(stream.to_string(), &options).await;
+        Ok(Box::pin(futures::stream::unfold(subscription, |mut subscription| async move {
+            let event = subscription.next().await.map(|event| read_event(&event)).map_err(anyhow::Error::from);
+            Some((event, subscription))
+        })))
+    }
+
+    /// Reads $all, leaving out system streams. Positions are prepare positions, which unlike
+    /// commit positions are unique per event; reading resumes at the record of the last event
+    /// read and skips it.
+    async fn read_all(&self, after: Option<u64>, limit: Option<u64>) -> Result<Vec<ReadEvent>> {
+        self.global_events(after, limit).try_collect().await
+    }
+
+    /// One gRPC read of $all to its end, its events yielded as the server streams them
+    fn read_all_stream(&self, after: Option<u64>) -> ReadStream<'_> {
+        self.global_events(after, None)
+    }
+
+    async fn list_streams(&self, prefix: &str, limit: Option<u64>) -> Result<Vec<String>> {
+        // Without the $streams system projection the only way to discover streams is scanning $all
+        let options = ReadAllOptions::default().position(StreamPosition::Start);
+        let mut stream = self.client.read_all(&options).await?;
+        let mut seen = HashSet::new();
         let mut out = Vec::new();
         while let Some(event) = stream.next().await? {
-            let recorded = event.get_original_event();
-            out.push(ReadEvent {
-                offset: recorded.revision,
-                event_type: recorded.event_type.clone(),
-                payload: recorded.data.to_vec(),
-                timestamp_ms: recorded.created.timestamp_millis() as u64,
-            });
-            if let Some(lim) = req.limit {
-                if out.len() as u64 >= lim {
+            let stream_id = event.get_original_event().stream_id();
+            if stream_id.starts_with('$') || !stream_id.starts_with(prefix) {
+                continue;
+            }
+            if seen.insert(stream_id.to_string()) {
+                out.push(stream_id.to_string());
+                if limit.is_some_and(|lim| out.len() as u64 >= lim) {
                     break;
                 }
             }
@@ -137,6 +399,59 @@ impl EventStoreAdapter for KurrentDbAdapter {
         Ok(out)
     }
 
+    /// Written as the stream's `$maxCount`, `$maxAge` and `$tb` metadata
+    async fn set_retention(&self, stream: &str, policy: &RetentionPolicy) -> Result<()> {
+        let mut metadata = StreamMetadata::builder();
+        if let Some(max_count) = policy.max_count {
+            metadata = metadata.max_count(max_count);
+        }
+        if let Some(max_age_s) = policy.max_age_s {
+            metadata = metadata.max_age(Duration::from_secs(max_age_s));
+        }
+        if let Some(truncate_before) = policy.truncate_before {
+            metadata = metadata.truncate_before(truncate_before);
+        }
+        let options = AppendToStreamOptions::default();
+        self.client.set_stream_metadata(stream, &options, &metadata.build()).await?;
+        Ok(())
+    }
+
+    /// A scavenge is followed on its `$scavenges-<id>` stream until the node reports it
+    /// completed; an index merge returns once the node started it
+    async fn admin(&self, op: AdminOp) -> Result<()> {
+        let operations = kurrentdb::operations::Client::from(self.client.clone());
+        let options = kurrentdb::operations::OperationalOptions::default();
+        match op {
+            AdminOp::Scavenge => {}
+            AdminOp::MergeIndexes => return Ok(operations.merge_indexes(&options).await?),
+            other => anyhow::bail!("Admin operation {} is not supported by KurrentDB", other.name()),
+        }
+        let scavenge = operations.start_scavenge(1, 0, &options).await?;
+        let stream = format!("$scavenges-{}", scavenge.id());
+        loop {
+            tokio::time::sleep(SCAVENGE_POLL_INTERVAL).await;
+            let req = ReadRequest {
+                stream: stream.clone(),
+                from_offset: None,
+                limit: None,
+                consistency: Default::default(),
+            };
+            let completed = self.read(req).await?.into_iter().find(|e| e.event_type == "$scavengeCompleted");
+            if let Some(completed) = completed {
+                let details: serde_json::Value = serde_json::from_slice(&completed.payload)?;
+                return match details["result"].as_str() {
+                    Some("Success") => Ok(()),
+                    result => anyhow::bail!(
+                        "Scavenge {} ended with {}: {}",
+                        scavenge.id(),
+                        result.unwrap_or("no result"),
+                        details["error"].as_str().unwrap_or_default()
+                    ),
+                };
+            }
+        }
+    }
+
     // async fn ping(&self) -> Result<Duration> {
     //     let t0 = std::time::Instant::now();
     //     // Perform an append operation to verify the node is leader and accepting writes
@@ -149,6 +464,60 @@ impl EventStoreAdapter for KurrentDbAdapter {
     // }
 }
 
+/// An event for its stream (the first tag), with any further tags in its custom metadata
+fn to_event_data(evt: EventData) -> kurrentdb::EventData {
+    let metadata = encode_extra_tags(&evt);
+    let event = kurrentdb::EventData::binary(evt.event_type, evt.payload).id(Uuid::new_v4());
+    match metadata {
+        Some(tags) => event.metadata(tags.into()),
+        None => event,
+    }
+}
+
+/// Yield the events of a KurrentDB read as they arrive, up to `limit` of them, converted by
+/// `convert`, which leaves out those it returns `None` for. A stream that was never written
+/// yields none, like on the other stores.
+fn events_of(
+    stream: kurrentdb::ReadStream,
+    limit: Option<u64>,
+    convert: impl FnMut(&ResolvedEvent) -> Option<Result<ReadEvent>> + Send + 'static,
+) -> ReadStream<'static> {
+    let state = Some((stream, convert, 0u64));
+    Box::pin(futures::stream::unfold(state, move |state| async move {
+        let (mut stream, mut convert, yielded) = state.filter(|(_, _, yielded)| limit.is_none_or(|lim| *yielded < lim))?;
+        loop {
+            let event = match time_phase_async(Phase::Network, stream.next()).await {
+                Ok(Some(event)) => event,
+                Ok(None) | Err(kurrentdb::Error::ResourceNotFound) => return None,
+                Err(e) => return Some((Err(e.into()), None)),
+            };
+            if let Some(read) = time_phase(Phase::Deserialize, || convert(&event)) {
+                return Some((read, Some((stream, convert, yielded + 1))));
+            }
+        }
+    }))
+}
+
+fn read_event(event: &ResolvedEvent) -> ReadEvent {
+    let recorded = event.get_original_event();
+    ReadEvent {
+        offset: recorded.revision,
+        event_type: recorded.event_type.clone(),
+        payload: recorded.data.clone(),
+        timestamp_ms: recorded.created.timestamp_millis() as u64,
+        tags: event_tags(recorded.stream_id(), &recorded.custom_metadata),
+    }
+}
+
+/// Tags of an event from its custom metadata; other clients writing to the store may keep
+/// anything there, so metadata that isn't our tag array leaves just the stream tag
+fn event_tags(stream: &str, custom_metadata: &[u8]) -> Vec<String> {
+    std::str::from_utf8(custom_metadata)
+        .ok()
+        .and_then(|metadata| decode_tags(stream, Some(metadata)).ok())
+        .unwrap_or_else(|| vec![stream.to_string()])
+}
+
 pub struct KurrentDbFactory;
 
 impl StoreManagerFactory for KurrentDbFactory {
@@ -159,4 +528,48 @@ impl StoreManagerFactory for KurrentDbFactory {
     fn create_store_manager(&self, data_dir: Option<String>) -> Result<Box<dyn StoreManager>> {
         Ok(Box::new(KurrentDbStoreManager::new(data_dir)))
     }
+
+    fn create_store_manager_with_options(
+        &self,
+        data_dir: Option<String>,
+        options: &HashMap<String, String>,
+    ) -> Result<Box<dyn StoreManager>> {
+        Ok(Box::new(KurrentDbStoreManager::new(data_dir).with_options(options)?))
+    }
+}
+
+/// KurrentDB with standard projections and a continuous user projection running during the
+/// workload, to compare against plain `kurrentdb`
+pub struct KurrentDbProjectionsFactory;
+
+impl StoreManagerFactory for KurrentDbProjectionsFactory {
+    fn name(&self) -> &'static str {
+        "kurrentdb-projections"
+    }
+
+    fn create_store_manager(&self, data_dir: Option<String>) -> Result<Box<dyn StoreManager>> {
+        Ok(Box::new(KurrentDbStoreManager::with_projections(data_dir)))
+    }
+
+    fn create_store_manager_with_options(
+        &self,
+        data_dir: Option<String>,
+        options: &HashMap<String, String>,
+    ) -> Result<Box<dyn StoreManager>> {
+        Ok(Box::new(KurrentDbStoreManager::with_projections(data_dir).with_options(options)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_tags_from_our_metadata_and_ignores_other_metadata() {
+        assert_eq!(event_tags("s", br#"["a","b"]"#), ["s", "a", "b"]);
+        assert_eq!(event_tags("s", b""), ["s"]);
+        for foreign in [&br#"{"$correlationId":"x"}"#[..], b"[1,2]", b"not json", b"\xff\xfe"] {
+            assert_eq!(event_tags("s", foreign), ["s"]);
+        }
+    }
 }
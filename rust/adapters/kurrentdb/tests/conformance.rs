@@ -0,0 +1,18 @@
+use bench_core::testkit::{run_conformance, Capabilities};
+use bench_core::StoreManagerFactory;
+use kurrentdb_adapter::KurrentDbFactory;
+
+#[tokio::test]
+#[ignore = "starts a KurrentDB container (needs Docker)"]
+async fn kurrentdb_conforms() -> anyhow::Result<()> {
+    let mut store = KurrentDbFactory.create_store_manager(None)?;
+    store.start().await?;
+    let capabilities = Capabilities {
+        conditional_append: true,
+        subscribe: true,
+        read_all: true,
+    };
+    let result = run_conformance(store.as_ref(), capabilities).await;
+    store.stop().await?;
+    result
+}
@@ -0,0 +1,449 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use bench_core::adapter::{
+    encode_extra_tags, AdminOp, AppendCondition, AppendOutcome, EventData, EventStoreAdapter, EventStream,
+    ReadEvent, ReadRequest, ReadStream, StoreDataDir, StoreManager, StoreManagerFactory,
+};
+use bench_core::instrument::{time_phase, time_phase_async, Phase};
+use bench_core::wait_for_ready;
+use bench_testcontainers::postgres::{Postgres, POSTGRES_DB, POSTGRES_PASSWORD, POSTGRES_PORT, POSTGRES_USER};
+use futures::TryStreamExt;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use testcontainers::runners::AsyncRunner;
+use testcontainers::ContainerAsync;
+use tokio::sync::Mutex;
+use tokio::time::Duration;
+use tokio_postgres::types::ToSql;
+use tokio_postgres::{Client, Config, NoTls, Row, Statement};
+
+/// Events table and append function in the style of Message DB: each stream's events are
+/// numbered from 0, and appends to a stream are serialized by an advisory lock on its name.
+/// `tags` holds an event's tags after the stream tag; they are passed to the function as one
+/// JSON array per event (NULL without any), as array parameters can't be ragged.
+const SCHEMA: &str = r#"
+CREATE TABLE IF NOT EXISTS events (
+    global_position BIGSERIAL PRIMARY KEY,
+    stream TEXT NOT NULL,
+    position BIGINT NOT NULL,
+    type TEXT NOT NULL,
+    payload BYTEA NOT NULL,
+    tags TEXT[] NOT NULL DEFAULT '{}',
+    recorded_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    UNIQUE (stream, position)
+);
+
+CREATE OR REPLACE FUNCTION append_events(
+    _stream TEXT,
+    _types TEXT[],
+    _payloads BYTEA[],
+    _tags TEXT[],
+    _expected BIGINT,
+    OUT last_position BIGINT,
+    OUT appended BOOLEAN
+) AS $$
+BEGIN
+    PERFORM pg_advisory_xact_lock(hashtextextended(_stream, 0));
+    SELECT COALESCE(MAX(e.position), -1) INTO last_position FROM events e WHERE e.stream = _stream;
+    appended := _expected IS NULL OR _expected = last_position;
+    IF appended THEN
+        INSERT INTO events (stream, position, type, payload, tags)
+        SELECT _stream, last_position + t.n, t.type, t.payload,
+            ARRAY(SELECT jsonb_array_elements_text(t.tags::jsonb))
+        FROM unnest(_types, _payloads, _tags) WITH ORDINALITY AS t(type, payload, tags, n);
+        last_position := last_position + cardinality(_types);
+    END IF;
+END;
+$$ LANGUAGE plpgsql;
+"#;
+
+const APPEND_SQL: &str = "SELECT last_position, appended FROM append_events($1, $2, $3, $4, $5)";
+const READ_SQL: &str = "SELECT position, type, payload, (extract(epoch FROM recorded_at) * 1000)::bigint, tags \
+     FROM events WHERE stream = $1 AND position >= $2 ORDER BY position LIMIT $3";
+const READ_ALL_SQL: &str = "SELECT global_position, type, payload, (extract(epoch FROM recorded_at) * 1000)::bigint, \
+     tags, stream FROM events WHERE global_position > $1 ORDER BY global_position LIMIT $2";
+/// Pause between polls of a subscription that is caught up (the default of Message DB consumers)
+const SUBSCRIPTION_POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// Most events fetched per subscription poll
+const SUBSCRIPTION_BATCH: i64 = 1000;
+const LIST_STREAMS_SQL: &str = "SELECT stream FROM events WHERE position = 0 AND starts_with(stream, $1) \
+     ORDER BY global_position LIMIT $2";
+
+// Store manager - handles lifecycle and adapter creation
+pub struct PostgresStoreManager {
+    config: Option<Config>,
+    container: Option<ContainerAsync<Postgres>>,
+    data_dir: StoreDataDir,
+    /// Image tag from the `image_tag` store option (the pinned default when unset)
+    image_tag: Option<String>,
+}
+
+impl PostgresStoreManager {
+    pub fn new(data_dir: Option<String>) -> Self {
+        Self {
+            config: None,
+            container: None,
+            data_dir: StoreDataDir::new(data_dir, "postgres"),
+            image_tag: None,
+        }
+    }
+
+    /// Configure the container from store options: `image_tag` runs another PostgreSQL version
+    pub fn with_options(data_dir: Option<String>, options: &HashMap<String, String>) -> Result<Self> {
+        let mut manager = Self::new(data_dir);
+        for (key, value) in options {
+            match key.as_str() {
+                "image_tag" => manager.image_tag = Some(value.clone()),
+                other => anyhow::bail!("unknown postgres store option '{}'", other),
+            }
+        }
+        Ok(manager)
+    }
+
+    /// The image to run, at the tag from the `image_tag` store option if set
+    fn image(&self, mount_path: Option<String>) -> Postgres {
+        let image = Postgres::new(mount_path);
+        match &self.image_tag {
+            Some(tag) => image.with_image_tag(tag),
+            None => image,
+        }
+    }
+}
+
+#[async_trait]
+impl StoreManager for PostgresStoreManager {
+    async fn start(&mut self) -> Result<()> {
+        let mount_path = self.data_dir.setup()?;
+        let container = self.image(mount_path).start().await?;
+        let host_port = container.get_host_port_ipv4(POSTGRES_PORT).await?;
+        self.container = Some(container);
+
+        let mut config = Config::new();
+        config
+            .host("localhost")
+            .port(host_port)
+            .user(POSTGRES_USER)
+            .password(POSTGRES_PASSWORD)
+            .dbname(POSTGRES_DB);
+
+        // Wait for the final server (not the one initializing the database) and create the schema
+        wait_for_ready("PostgreSQL", || async {
+            let (client, connection) = config.connect(NoTls).await?;
+            tokio::spawn(connection);
+            client.batch_execute(SCHEMA).await?;
+            Ok(())
+        }, Duration::from_secs(60)).await?;
+        self.config = Some(config);
+
+        Ok(())
+    }
+
+    async fn pull(&mut self) -> Result<()> {
+        let _ = self.image(None).pull_image().await?;
+        Ok(())
+    }
+
+    async fn stop(&mut self) -> Result<()> {
+        if let Some(container) = self.container.take() {
+            container.stop().await?;
+        }
+        self.data_dir.cleanup()?;
+        Ok(())
+    }
+
+    fn container_id(&self) -> Option<String> {
+        self.container.as_ref().map(|c| c.id().to_string())
+    }
+
+    fn endpoint(&self) -> Option<String> {
+        let port = self.config.as_ref()?.get_ports().first()?;
+        Some(format!("postgres://localhost:{}", port))
+    }
+
+    fn name(&self) -> &'static str {
+        "postgres"
+    }
+
+    fn create_adapter(&self) -> Result<Arc<dyn EventStoreAdapter>> {
+        let config = self.config.clone()
+            .ok_or_else(|| anyhow::anyhow!("PostgreSQL not started. Did you call start()?"))?;
+        Ok(Arc::new(PostgresAdapter {
+            config,
+            session: Mutex::new(None),
+        }))
+    }
+}
+
+/// A connection with the adapter's statements prepared on it
+struct Session {
+    client: Arc<Client>,
+    append: Statement,
+    read: Statement,
+}
+
+// Adapter - one connection per adapter, opened on first use and reopened once closed
+pub struct PostgresAdapter {
+    config: Config,
+    session: Mutex<Option<Session>>,
+}
+
+impl PostgresAdapter {
+    async fn session(&self) -> Result<(Arc<Client>, Statement, Statement)> {
+        let mut session = self.session.lock().await;
+        if let Some(s) = session.as_ref().filter(|s| !s.client.is_closed()) {
+            return Ok((s.client.clone(), s.append.clone(), s.read.clone()));
+        }
+        let (client, connection) = self.config.connect(NoTls).await?;
+        tokio::spawn(async move {
+            if let Err(e) = connection.await {
+                tracing::debug!("PostgreSQL connection closed: {}", e);
+            }
+        });
+        let append = client.prepare(APPEND_SQL).await?;
+        let read = client.prepare(READ_SQL).await?;
+        let s = session.insert(Session {
+            client: Arc::new(client),
+            append,
+            read,
+        });
+        Ok((s.client.clone(), s.append.clone(), s.read.clone()))
+    }
+
+    /// Append events to one stream if its last position is `expected` (any position if `None`);
+    /// returns the stream's last position and whether the events were appended
+    async fn append_to_stream(
+        &self,
+        stream: &str,
+        events: &[EventData],
+        expected: Option<i64>,
+    ) -> Result<(i64, bool)> {
+        let (client, append, _) = self.session().await?;
+        let (types, payloads): (Vec<&str>, Vec<&[u8]>) = time_phase(Phase::Serialize, || {
+            events.iter().map(|evt| (evt.event_type.as_str(), evt.payload.as_ref())).unzip()
+        });
+        let tags: Vec<Option<String>> = time_phase(Phase::Serialize, || events.iter().map(encode_extra_tags).collect());
+        let row = time_phase_async(
+            Phase::Network,
+            client.query_one(&append, &[&stream, &types, &payloads, &tags, &expected]),
+        )
+        .await?;
+        Ok((row.get(0), row.get(1)))
+    }
+}
+
+fn read_event(stream: &str, row: Row) -> ReadEvent {
+    ReadEvent {
+        offset: row.get::<_, i64>(0) as u64,
+        event_type: row.get(1),
+        payload: row.get::<_, Vec<u8>>(2).into(),
+        timestamp_ms: row.get::<_, i64>(3) as u64,
+        tags: std::iter::once(stream.to_string()).chain(row.get::<_, Vec<String>>(4)).collect(),
+    }
+}
+
+/// Position a polling subscription reads from next, and the events it fetched but hasn't yielded
+struct Poll {
+    client: Arc<Client>,
+    read: Statement,
+    stream: String,
+    next: i64,
+    fetched: VecDeque<ReadEvent>,
+}
+
+/// The stream an event is appended to: its first tag
+fn stream_of(event: &EventData) -> Result<&str> {
+    event
+        .tags
+        .first()
+        .map(String::as_str)
+        .ok_or_else(|| anyhow::anyhow!("PostgreSQL appends require a stream tag"))
+}
+
+#[async_trait]
+impl EventStoreAdapter for PostgresAdapter {
+    /// Consecutive events of the same stream are appended atomically, in one call
+    async fn append(&self, events: Vec<EventData>) -> Result<()> {
+        let mut start = 0;
+        while start < events.len() {
+            let stream = stream_of(&events[start])?;
+            let mut end = start + 1;
+            while end < events.len() && stream_of(&events[end])? == stream {
+                end += 1;
+            }
+            self.append_to_stream(stream, &events[start..end], None).await?;
+            start = end;
+        }
+        Ok(())
+    }
+
+    async fn read(&self, req: ReadRequest) -> Result<Vec<ReadEvent>> {
+        let (client, _, read) = self.session().await?;
+        let from = req.from_offset.unwrap_or(0) as i64;
+        let limit = req.limit.map(|l| l as i64);
+        let rows = time_phase_async(Phase::Network, client.query(&read, &[&req.stream, &from, &limit])).await?;
+        Ok(time_phase(Phase::Deserialize, || rows.into_iter().map(|row| read_event(&req.stream, row)).collect()))
+    }
+
+    /// One query, its rows yielded as the server sends them
+    fn read_stream(&self, req: ReadRequest) -> ReadStream<'_> {
+        let opened = async move {
+            let (client, _, read) = self.session().await?;
+            let from = req.from_offset.unwrap_or(0) as i64;
+            let limit = req.limit.map(|l| l as i64);
+            let params: [&(dyn ToSql + Sync); 3] = [&req.stream, &from, &limit];
+            let rows = time_phase_async(Phase::Network, client.query_raw(&read, params)).await?;
+            let stream = req.stream.clone();
+            Ok::<_, anyhow::Error>(rows.map_ok(move |row| read_event(&stream, row)).map_err(anyhow::Error::from))
+        };
+        Box::pin(futures::stream::once(opened).try_flatten())
+    }
+
+    /// Global positions start at 1, so `after` 0 reads from the start. A transaction still in
+    /// flight can commit a position below one already read, so a scan racing appends may miss
+    /// events.
+    async fn read_all(&self, after: Option<u64>, limit: Option<u64>) -> Result<Vec<ReadEvent>> {
+        let (client, _, _) = self.session().await?;
+        let after = after.map_or(0, |a| a as i64);
+        let limit = limit.map(|l| l as i64);
+        let rows = time_phase_async(Phase::Network, client.query(READ_ALL_SQL, &[&after, &limit])).await?;
+        Ok(time_phase(Phase::Deserialize, || {
+            rows.into_iter()
+                .map(|row| {
+                    let stream: String = row.get(5);
+                    read_event(&stream, row)
+                })
+                .collect()
+        }))
+    }
+
+    /// One query of the whole log after `after`, its rows yielded as the server sends them
+    fn read_all_stream(&self, after: Option<u64>) -> ReadStream<'_> {
+        let opened = async move {
+            let (client, _, _) = self.session().await?;
+            let after = after.map_or(0, |a| a as i64);
+            let params: [&(dyn ToSql + Sync); 2] = [&after, &None::<i64>];
+            let rows = time_phase_async(Phase::Network, client.query_raw(READ_ALL_SQL, params)).await?;
+            Ok::<_, anyhow::Error>(
+                rows.map_ok(|row| {
+                    let stream: String = row.get(5);
+                    read_event(&stream, row)
+                })
+                .map_err(anyhow::Error::from),
+            )
+        };
+        Box::pin(futures::stream::once(opened).try_flatten())
+    }
+
+    async fn append_conditional(
+        &self,
+        events: Vec<EventData>,
+        condition: AppendCondition,
+    ) -> Result<AppendOutcome> {
+        let stream = events
+            .first()
+            .map(stream_of)
+            .transpose()?
+            .ok_or_else(|| anyhow::anyhow!("Conditional append requires events"))?;
+        // The condition is on one stream's position, so the batch can't span streams
+        for event in &events[1..] {
+            if stream_of(event)? != stream {
+                anyhow::bail!("Conditional append requires all events to be in one stream");
+            }
+        }
+        let expected = match condition {
+            AppendCondition::NoStream => -1,
+            AppendCondition::After(position) => position as i64,
+        };
+        let (last_position, appended) = self.append_to_stream(stream, &events, Some(expected)).await?;
+        Ok(if appended {
+            AppendOutcome::Appended {
+                position: last_position as u64,
+            }
+        } else {
+            AppendOutcome::Conflict {
+                current: (last_position >= 0).then_some(last_position as u64),
+            }
+        })
+    }
+
+    /// Polls the stream on the adapter's connection, as Message DB consumers do, pausing while
+    /// caught up
+    async fn subscribe(&self, stream: &str, from_offset: Option<u64>) -> Result<EventStream> {
+        let (client, _, read) = self.session().await?;
+        let poll = Poll {
+            client,
+            read,
+            stream: stream.to_string(),
+            next: from_offset.unwrap_or(0) as i64,
+            fetched: VecDeque::new(),
+        };
+        Ok(Box::pin(futures::stream::unfold(poll, |mut poll| async move {
+            loop {
+                if let Some(event) = poll.fetched.pop_front() {
+                    return Some((Ok(event), poll));
+                }
+                let params: [&(dyn tokio_postgres::types::ToSql + Sync); 3] =
+                    [&poll.stream, &poll.next, &Some(SUBSCRIPTION_BATCH)];
+                match poll.client.query(&poll.read, &params).await {
+                    Ok(rows) if rows.is_empty() => tokio::time::sleep(SUBSCRIPTION_POLL_INTERVAL).await,
+                    Ok(rows) => {
+                        poll.fetched.extend(rows.into_iter().map(|row| read_event(&poll.stream, row)));
+                        poll.next = poll.fetched.back().map_or(poll.next, |event| event.offset as i64 + 1);
+                    }
+                    Err(e) => return Some((Err(e.into()), poll)),
+                }
+            }
+        })))
+    }
+
+    async fn list_streams(&self, prefix: &str, limit: Option<u64>) -> Result<Vec<String>> {
+        let (client, _, _) = self.session().await?;
+        let limit = limit.map(|l| l as i64);
+        let rows = time_phase_async(Phase::Network, client.query(LIST_STREAMS_SQL, &[&prefix, &limit])).await?;
+        Ok(rows.into_iter().map(|row| row.get(0)).collect())
+    }
+
+    async fn ping(&self) -> Result<()> {
+        let (client, _, _) = self.session().await?;
+        client.simple_query("SELECT 1").await?;
+        Ok(())
+    }
+
+    /// VACUUM, REINDEX or CHECKPOINT, run on the adapter's connection
+    async fn admin(&self, op: AdminOp) -> Result<()> {
+        let sql = match op {
+            AdminOp::Scavenge => "VACUUM events",
+            AdminOp::RebuildIndexes => "REINDEX TABLE events",
+            AdminOp::Checkpoint => "CHECKPOINT",
+            other => anyhow::bail!("Admin operation {} is not supported by PostgreSQL", other.name()),
+        };
+        let (client, _, _) = self.session().await?;
+        time_phase_async(Phase::Network, client.batch_execute(sql)).await?;
+        Ok(())
+    }
+}
+
+pub struct PostgresFactory;
+
+impl StoreManagerFactory for PostgresFactory {
+    fn name(&self) -> &'static str {
+        "postgres"
+    }
+
+    fn create_store_manager(&self, data_dir: Option<String>) -> Result<Box<dyn StoreManager>> {
+        Ok(Box::new(PostgresStoreManager::new(data_dir)))
+    }
+
+    fn create_store_manager_with_options(
+        &self,
+        data_dir: Option<String>,
+        options: &HashMap<String, String>,
+    ) -> Result<Box<dyn StoreManager>> {
+        Ok(Box::new(PostgresStoreManager::with_options(data_dir, options)?))
+    }
+
+    /// PostgreSQL's default `max_connections`; each adapter holds one connection
+    fn max_connections(&self) -> Option<usize> {
+        Some(100)
+    }
+}
@@ -0,0 +1,18 @@
+use bench_core::testkit::{run_conformance, Capabilities};
+use bench_core::StoreManagerFactory;
+use postgres_adapter::PostgresFactory;
+
+#[tokio::test]
+#[ignore = "starts a PostgreSQL container (needs Docker)"]
+async fn postgres_conforms() -> anyhow::Result<()> {
+    let mut store = PostgresFactory.create_store_manager(None)?;
+    store.start().await?;
+    let capabilities = Capabilities {
+        conditional_append: true,
+        subscribe: true,
+        read_all: true,
+    };
+    let result = run_conformance(store.as_ref(), capabilities).await;
+    store.stop().await?;
+    result
+}
@@ -0,0 +1,394 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use bench_core::adapter::{
+    decode_tags, encode_extra_tags, AdminOp, AppendCondition, AppendOutcome, EventData, EventStoreAdapter,
+    EventStream, ReadEvent, ReadRequest, StoreDataDir, StoreManager, StoreManagerFactory,
+};
+use bench_core::instrument::{time_phase_async, Phase};
+use rusqlite::{params, Connection, OptionalExtension, TransactionBehavior};
+use std::collections::{HashMap, VecDeque};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Events table in the style of the PostgreSQL adapter: each stream's events are numbered from 0.
+/// `tags` holds an event's tags after the stream tag as a JSON array (NULL without any).
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS events (
+    global_position INTEGER PRIMARY KEY AUTOINCREMENT,
+    stream TEXT NOT NULL,
+    position INTEGER NOT NULL,
+    type TEXT NOT NULL,
+    payload BLOB NOT NULL,
+    tags TEXT,
+    recorded_at_ms INTEGER NOT NULL,
+    UNIQUE (stream, position)
+);
+";
+
+const LAST_POSITION_SQL: &str = "SELECT MAX(position) FROM events WHERE stream = ?1";
+const INSERT_SQL: &str =
+    "INSERT INTO events (stream, position, type, payload, tags, recorded_at_ms) VALUES (?1, ?2, ?3, ?4, ?5, ?6)";
+const READ_SQL: &str = "SELECT position, type, payload, recorded_at_ms, tags FROM events \
+     WHERE stream = ?1 AND position >= ?2 ORDER BY position LIMIT ?3";
+const READ_ALL_SQL: &str = "SELECT global_position, type, payload, recorded_at_ms, tags, stream FROM events \
+     WHERE global_position > ?1 ORDER BY global_position LIMIT ?2";
+const LIST_STREAMS_SQL: &str = "SELECT stream FROM events WHERE position = 0 AND substr(stream, 1, length(?1)) = ?1 \
+     ORDER BY global_position LIMIT ?2";
+/// How long a connection waits for another one's write lock before failing
+const BUSY_TIMEOUT: Duration = Duration::from_secs(30);
+/// Pause between polls of a subscription that is caught up (no network round trip to save)
+const SUBSCRIPTION_POLL_INTERVAL: Duration = Duration::from_millis(10);
+/// Most events fetched per subscription poll
+const SUBSCRIPTION_BATCH: i64 = 1000;
+/// Values of the `synchronous` store option
+const SYNCHRONOUS_MODES: [&str; 4] = ["off", "normal", "full", "extra"];
+
+// Store manager - creates the database file; there is no container
+pub struct SqliteStoreManager {
+    path: Option<PathBuf>,
+    data_dir: StoreDataDir,
+    /// Directory created for the database when no data directory was given, removed on stop
+    temp_dir: Option<PathBuf>,
+    /// `PRAGMA synchronous` of every connection (`full` unless set by the store option)
+    synchronous: String,
+}
+
+impl SqliteStoreManager {
+    pub fn new(data_dir: Option<String>) -> Self {
+        Self {
+            path: None,
+            data_dir: StoreDataDir::new(data_dir, "sqlite"),
+            temp_dir: None,
+            synchronous: "full".to_string(),
+        }
+    }
+
+    /// Configure the connections from store options: `synchronous` (`off`, `normal`, `full` or
+    /// `extra`) trades durability of the last commits for fewer fsyncs
+    pub fn with_options(data_dir: Option<String>, options: &HashMap<String, String>) -> Result<Self> {
+        let mut manager = Self::new(data_dir);
+        for (key, value) in options {
+            match key.as_str() {
+                "synchronous" => {
+                    let mode = value.to_ascii_lowercase();
+                    if !SYNCHRONOUS_MODES.contains(&mode.as_str()) {
+                        anyhow::bail!("invalid sqlite synchronous '{}' (expected one of {})", value, SYNCHRONOUS_MODES.join(", "));
+                    }
+                    manager.synchronous = mode;
+                }
+                other => anyhow::bail!("unknown sqlite store option '{}'", other),
+            }
+        }
+        Ok(manager)
+    }
+}
+
+#[async_trait]
+impl StoreManager for SqliteStoreManager {
+    async fn start(&mut self) -> Result<()> {
+        let dir = match self.data_dir.setup()? {
+            Some(dir) => PathBuf::from(dir),
+            None => {
+                let dir = std::env::temp_dir().join(format!("es-bench-sqlite-{}", uuid::Uuid::new_v4()));
+                std::fs::create_dir_all(&dir)?;
+                self.temp_dir = Some(dir.clone());
+                dir
+            }
+        };
+        let path = dir.join("events.db");
+        let conn = open(&path, &self.synchronous)?;
+        let mode: String = conn.query_row("PRAGMA journal_mode = WAL", [], |row| row.get(0))?;
+        anyhow::ensure!(mode.eq_ignore_ascii_case("wal"), "SQLite refused WAL mode (journal mode {})", mode);
+        conn.execute_batch(SCHEMA)?;
+        self.path = Some(path);
+        Ok(())
+    }
+
+    async fn pull(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    async fn stop(&mut self) -> Result<()> {
+        self.path = None;
+        if let Some(dir) = self.temp_dir.take() {
+            std::fs::remove_dir_all(&dir)?;
+        }
+        self.data_dir.cleanup()?;
+        Ok(())
+    }
+
+    fn container_id(&self) -> Option<String> {
+        None
+    }
+
+    fn name(&self) -> &'static str {
+        "sqlite"
+    }
+
+    fn create_adapter(&self) -> Result<Arc<dyn EventStoreAdapter>> {
+        let path = self.path.as_ref()
+            .ok_or_else(|| anyhow::anyhow!("SQLite not started. Did you call start()?"))?;
+        Ok(Arc::new(SqliteAdapter {
+            conn: Arc::new(Mutex::new(open(path, &self.synchronous)?)),
+        }))
+    }
+}
+
+/// A connection to the database, waiting for other connections' write locks
+fn open(path: &std::path::Path, synchronous: &str) -> Result<Connection> {
+    let conn = Connection::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    conn.busy_timeout(BUSY_TIMEOUT)?;
+    conn.execute_batch(&format!("PRAGMA synchronous = {};", synchronous))?;
+    Ok(conn)
+}
+
+fn now_ms() -> i64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_millis() as i64)
+}
+
+/// The stream an event is appended to: its first tag
+fn stream_of(event: &EventData) -> Result<&str> {
+    event
+        .tags
+        .first()
+        .map(String::as_str)
+        .ok_or_else(|| anyhow::anyhow!("SQLite appends require a stream tag"))
+}
+
+/// Append `events` in one transaction, each to the stream of its first tag, if the last
+/// position of the first event's stream is `expected` (any position if `None`); returns that
+/// stream's last position and whether the events were appended
+fn append_events(conn: &mut Connection, events: &[EventData], expected: Option<i64>) -> Result<(i64, bool)> {
+    let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+    let mut last_positions: HashMap<&str, i64> = HashMap::new();
+    let recorded_at = now_ms();
+    {
+        let mut last_position = tx.prepare_cached(LAST_POSITION_SQL)?;
+        let mut insert = tx.prepare_cached(INSERT_SQL)?;
+        for (i, event) in events.iter().enumerate() {
+            let stream = stream_of(event)?;
+            let last = match last_positions.get(stream) {
+                Some(last) => *last,
+                None => last_position
+                    .query_row([stream], |row| row.get::<_, Option<i64>>(0))
+                    .optional()?
+                    .flatten()
+                    .unwrap_or(-1),
+            };
+            if i == 0 && expected.is_some_and(|expected| expected != last) {
+                return Ok((last, false));
+            }
+            insert.execute(params![
+                stream,
+                last + 1,
+                event.event_type,
+                event.payload.as_ref(),
+                encode_extra_tags(event),
+                recorded_at
+            ])?;
+            last_positions.insert(stream, last + 1);
+        }
+    }
+    tx.commit()?;
+    let first = events.first().map(stream_of).transpose()?;
+    Ok((first.and_then(|s| last_positions.get(s).copied()).unwrap_or(-1), true))
+}
+
+fn read_events(conn: &Connection, stream: &str, from: i64, limit: i64) -> Result<Vec<ReadEvent>> {
+    let mut read = conn.prepare_cached(READ_SQL)?;
+    let rows = read.query_map(params![stream, from, limit], |row| {
+        let event = ReadEvent {
+            offset: row.get::<_, i64>(0)? as u64,
+            event_type: row.get(1)?,
+            payload: row.get::<_, Vec<u8>>(2)?.into(),
+            timestamp_ms: row.get::<_, i64>(3)? as u64,
+            tags: Vec::new(),
+        };
+        Ok((event, row.get::<_, Option<String>>(4)?))
+    })?;
+    rows.map(|row| {
+        let (mut event, tags) = row?;
+        event.tags = decode_tags(stream, tags.as_deref())?;
+        Ok(event)
+    })
+    .collect()
+}
+
+/// Events of all streams after global position `after`, in log order
+fn read_all_events(conn: &Connection, after: i64, limit: i64) -> Result<Vec<ReadEvent>> {
+    let mut read = conn.prepare_cached(READ_ALL_SQL)?;
+    let rows = read.query_map(params![after, limit], |row| {
+        let event = ReadEvent {
+            offset: row.get::<_, i64>(0)? as u64,
+            event_type: row.get(1)?,
+            payload: row.get::<_, Vec<u8>>(2)?.into(),
+            timestamp_ms: row.get::<_, i64>(3)? as u64,
+            tags: Vec::new(),
+        };
+        Ok((event, row.get::<_, Option<String>>(4)?, row.get::<_, String>(5)?))
+    })?;
+    rows.map(|row| {
+        let (mut event, tags, stream) = row?;
+        event.tags = decode_tags(&stream, tags.as_deref())?;
+        Ok(event)
+    })
+    .collect()
+}
+
+// Adapter - one connection per adapter; calls run on the blocking pool
+pub struct SqliteAdapter {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl SqliteAdapter {
+    /// Run `f` on the adapter's connection on a blocking thread
+    async fn with_conn<T: Send + 'static>(
+        &self,
+        f: impl FnOnce(&mut Connection) -> Result<T> + Send + 'static,
+    ) -> Result<T> {
+        with_conn(self.conn.clone(), f).await
+    }
+}
+
+async fn with_conn<T: Send + 'static>(
+    conn: Arc<Mutex<Connection>>,
+    f: impl FnOnce(&mut Connection) -> Result<T> + Send + 'static,
+) -> Result<T> {
+    let task = tokio::task::spawn_blocking(move || {
+        let mut conn = conn.lock().map_err(|_| anyhow::anyhow!("SQLite connection poisoned"))?;
+        f(&mut conn)
+    });
+    time_phase_async(Phase::Network, task).await?
+}
+
+/// Position a polling subscription reads from next, and the events it fetched but hasn't yielded
+struct Poll {
+    conn: Arc<Mutex<Connection>>,
+    stream: String,
+    next: i64,
+    fetched: VecDeque<ReadEvent>,
+}
+
+#[async_trait]
+impl EventStoreAdapter for SqliteAdapter {
+    /// All events are appended in one transaction
+    async fn append(&self, events: Vec<EventData>) -> Result<()> {
+        self.with_conn(move |conn| append_events(conn, &events, None).map(|_| ())).await
+    }
+
+    async fn read(&self, req: ReadRequest) -> Result<Vec<ReadEvent>> {
+        let from = req.from_offset.unwrap_or(0) as i64;
+        // A negative LIMIT is no limit
+        let limit = req.limit.map_or(-1, |l| l.min(i64::MAX as u64) as i64);
+        self.with_conn(move |conn| read_events(conn, &req.stream, from, limit)).await
+    }
+
+    /// Global positions start at 1, so `after` 0 reads from the start
+    async fn read_all(&self, after: Option<u64>, limit: Option<u64>) -> Result<Vec<ReadEvent>> {
+        let after = after.map_or(0, |a| a.min(i64::MAX as u64) as i64);
+        let limit = limit.map_or(-1, |l| l.min(i64::MAX as u64) as i64);
+        self.with_conn(move |conn| read_all_events(conn, after, limit)).await
+    }
+
+    async fn append_conditional(
+        &self,
+        events: Vec<EventData>,
+        condition: AppendCondition,
+    ) -> Result<AppendOutcome> {
+        anyhow::ensure!(!events.is_empty(), "Conditional append requires events");
+        let expected = match condition {
+            AppendCondition::NoStream => -1,
+            AppendCondition::After(position) => position as i64,
+        };
+        let (last_position, appended) =
+            self.with_conn(move |conn| append_events(conn, &events, Some(expected))).await?;
+        Ok(if appended {
+            AppendOutcome::Appended {
+                position: last_position as u64,
+            }
+        } else {
+            AppendOutcome::Conflict {
+                current: (last_position >= 0).then_some(last_position as u64),
+            }
+        })
+    }
+
+    /// Polls the stream on the adapter's connection, pausing briefly while caught up
+    async fn subscribe(&self, stream: &str, from_offset: Option<u64>) -> Result<EventStream> {
+        let poll = Poll {
+            conn: self.conn.clone(),
+            stream: stream.to_string(),
+            next: from_offset.unwrap_or(0) as i64,
+            fetched: VecDeque::new(),
+        };
+        Ok(Box::pin(futures::stream::unfold(poll, |mut poll| async move {
+            loop {
+                if let Some(event) = poll.fetched.pop_front() {
+                    return Some((Ok(event), poll));
+                }
+                let (stream, next) = (poll.stream.clone(), poll.next);
+                match with_conn(poll.conn.clone(), move |conn| read_events(conn, &stream, next, SUBSCRIPTION_BATCH)).await {
+                    Ok(events) if events.is_empty() => tokio::time::sleep(SUBSCRIPTION_POLL_INTERVAL).await,
+                    Ok(events) => {
+                        poll.fetched.extend(events);
+                        poll.next = poll.fetched.back().map_or(poll.next, |event| event.offset as i64 + 1);
+                    }
+                    Err(e) => return Some((Err(e), poll)),
+                }
+            }
+        })))
+    }
+
+    async fn list_streams(&self, prefix: &str, limit: Option<u64>) -> Result<Vec<String>> {
+        let prefix = prefix.to_string();
+        let limit = limit.map_or(-1, |l| l.min(i64::MAX as u64) as i64);
+        self.with_conn(move |conn| {
+            let mut list = conn.prepare_cached(LIST_STREAMS_SQL)?;
+            let rows = list.query_map(params![prefix, limit], |row| row.get(0))?;
+            Ok(rows.collect::<rusqlite::Result<_>>()?)
+        })
+        .await
+    }
+
+    async fn ping(&self) -> Result<()> {
+        self.with_conn(|conn| Ok(conn.query_row("SELECT 1", [], |_| Ok(()))?)).await
+    }
+
+    /// VACUUM, REINDEX or a truncating WAL checkpoint, run on the adapter's connection
+    async fn admin(&self, op: AdminOp) -> Result<()> {
+        self.with_conn(move |conn| {
+            match op {
+                AdminOp::Scavenge => conn.execute_batch("VACUUM")?,
+                AdminOp::RebuildIndexes => conn.execute_batch("REINDEX")?,
+                AdminOp::Checkpoint => {
+                    let busy: i64 = conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |row| row.get(0))?;
+                    anyhow::ensure!(busy == 0, "WAL checkpoint could not complete while other connections were busy");
+                }
+                other => anyhow::bail!("Admin operation {} is not supported by SQLite", other.name()),
+            }
+            Ok(())
+        })
+        .await
+    }
+}
+
+pub struct SqliteFactory;
+
+impl StoreManagerFactory for SqliteFactory {
+    fn name(&self) -> &'static str {
+        "sqlite"
+    }
+
+    fn create_store_manager(&self, data_dir: Option<String>) -> Result<Box<dyn StoreManager>> {
+        Ok(Box::new(SqliteStoreManager::new(data_dir)))
+    }
+
+    fn create_store_manager_with_options(
+        &self,
+        data_dir: Option<String>,
+        options: &HashMap<String, String>,
+    ) -> Result<Box<dyn StoreManager>> {
+        Ok(Box::new(SqliteStoreManager::with_options(data_dir, options)?))
+    }
+}
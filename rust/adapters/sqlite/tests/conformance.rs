@@ -0,0 +1,17 @@
+use bench_core::testkit::{run_conformance, Capabilities};
+use bench_core::StoreManagerFactory;
+use sqlite_adapter::SqliteFactory;
+
+#[tokio::test]
+async fn sqlite_conforms() -> anyhow::Result<()> {
+    let mut store = SqliteFactory.create_store_manager(None)?;
+    store.start().await?;
+    let capabilities = Capabilities {
+        conditional_append: true,
+        subscribe: true,
+        read_all: true,
+    };
+    let result = run_conformance(store.as_ref(), capabilities).await;
+    store.stop().await?;
+    result
+}
@@ -1,25 +1,39 @@
 use anyhow::Result;
 use async_trait::async_trait;
 use bench_core::adapter::{
-    EventData, EventStoreAdapter, ReadEvent, ReadRequest, StoreDataDir, StoreManager, StoreManagerFactory,
+    AppendCondition, AppendOutcome, EventData, EventStoreAdapter, ReadEvent, ReadRequest, StoreDataDir,
+    StoreManager, StoreManagerFactory,
 };
+use bench_core::instrument::{time_phase, time_phase_async, Phase};
 use bench_core::wait_for_ready;
 use bench_testcontainers::umadb::{UmaDb, UMADB_PORT};
 use futures::StreamExt;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use testcontainers::runners::AsyncRunner;
 use testcontainers::ContainerAsync;
 use tokio::time::Duration;
 use umadb_client::UmaDBClient;
-use umadb_dcb::{DCBEvent, DCBEventStoreAsync, DCBQuery, DCBQueryItem};
+use umadb_dcb::{
+    DCBAppendCondition, DCBError, DCBEvent, DCBEventStoreAsync, DCBQuery, DCBQueryItem,
+};
 
 // Store manager - handles lifecycle and adapter creation
 pub struct UmaDbStoreManager {
     uri: Option<String>,
     container: Option<ContainerAsync<UmaDb>>,
-    client: Option<Arc<umadb_client::AsyncUmaDBClient>>,
+    /// Shared clients, each with its own gRPC channel; adapters take them in turn
+    clients: Vec<Arc<umadb_client::AsyncUmaDBClient>>,
+    /// Number of clients (the `channels` store option, 1 by default)
+    channels: usize,
+    next_client: AtomicUsize,
     local: bool,
     data_dir: StoreDataDir,
+    /// Events per read response requested by the client (server default when unset)
+    batch_size: Option<u32>,
+    /// Image tag from the `image_tag` store option (the pinned default when unset)
+    image_tag: Option<String>,
 }
 
 impl UmaDbStoreManager {
@@ -27,9 +41,57 @@ impl UmaDbStoreManager {
         Self {
             uri: None,
             container: None,
-            client: None,
+            clients: Vec::new(),
+            channels: 1,
+            next_client: AtomicUsize::new(0),
             local: false,
             data_dir: StoreDataDir::new(data_dir, "umadb"),
+            batch_size: None,
+            image_tag: None,
+        }
+    }
+
+    /// Configure the client from store options: `batch_size`, `channels` to spread the
+    /// adapters over that many gRPC connections instead of sharing one, and `image_tag` to
+    /// run another UmaDB version
+    pub fn with_options(data_dir: Option<String>, options: &HashMap<String, String>) -> Result<Self> {
+        let mut manager = Self::new(data_dir);
+        for (key, value) in options {
+            match key.as_str() {
+                "batch_size" => {
+                    manager.batch_size = Some(value.parse().map_err(|e| {
+                        anyhow::anyhow!("invalid umadb batch_size '{}': {}", value, e)
+                    })?)
+                }
+                "channels" => {
+                    manager.channels = value
+                        .parse()
+                        .ok()
+                        .filter(|&channels| channels > 0)
+                        .ok_or_else(|| anyhow::anyhow!("invalid umadb channels '{}' (expected a number > 0)", value))?
+                }
+                "image_tag" => manager.image_tag = Some(value.clone()),
+                "stream_window_bytes" | "connection_window_bytes" | "adaptive_window" | "concurrency_limit"
+                | "keepalive_interval_ms" | "keepalive_timeout_ms" | "keepalive_while_idle" | "tcp_nodelay" => {
+                    anyhow::bail!(
+                        "umadb store option '{}' is not supported: umadb-client fixes its channel settings \
+                         (TCP_NODELAY on, 5 s keep-alive with a 10 s timeout, 4 MiB stream and 8 MiB \
+                         connection windows); use 'channels' to add connections",
+                        key
+                    )
+                }
+                other => anyhow::bail!("unknown umadb store option '{}'", other),
+            }
+        }
+        Ok(manager)
+    }
+
+    /// The image to run, at the tag from the `image_tag` store option if set
+    fn image(&self, mount_path: Option<String>) -> UmaDb {
+        let image = UmaDb::new(mount_path);
+        match &self.image_tag {
+            Some(tag) => image.with_image_tag(tag),
+            None => image,
         }
     }
 }
@@ -39,7 +101,7 @@ impl StoreManager for UmaDbStoreManager {
     async fn start(&mut self) -> Result<()> {
         if !self.local {
             let mount_path = self.data_dir.setup()?;
-            let container = UmaDb::new(mount_path).start().await?;
+            let container = self.image(mount_path).start().await?;
             let host_port = container.get_host_port_ipv4(UMADB_PORT).await?;
             self.uri = Some(format!("http://localhost:{}", host_port));
             self.container = Some(container);
@@ -47,20 +109,28 @@ impl StoreManager for UmaDbStoreManager {
             self.uri = Some(format!("http://localhost:{}", UMADB_PORT));
         }
 
-        // Wait for container to be ready and create shared client
+        // Wait for container to be ready and create the shared clients
         let uri = self.uri.clone().unwrap();
-        self.client = Some(Arc::new(wait_for_ready("UmaDB", || async {
-            let client = UmaDBClient::new(uri.clone()).connect_async().await?;
-            client.head().await?;
-            Ok(client)
-        }, Duration::from_secs(60)).await?));
+        let batch_size = self.batch_size;
+        self.clients.clear();
+        for _ in 0..self.channels {
+            self.clients.push(Arc::new(wait_for_ready("UmaDB", || async {
+                let mut builder = UmaDBClient::new(uri.clone());
+                if let Some(batch_size) = batch_size {
+                    builder = builder.batch_size(batch_size);
+                }
+                let client = builder.connect_async().await?;
+                client.head().await?;
+                Ok(client)
+            }, Duration::from_secs(60)).await?));
+        }
 
         Ok(())
     }
 
     async fn pull(&mut self) -> Result<()> {
         if !self.local {
-            let _ = UmaDb::new(None).pull_image().await?;
+            let _ = self.image(None).pull_image().await?;
         }
         Ok(())
     }
@@ -77,14 +147,20 @@ impl StoreManager for UmaDbStoreManager {
         self.container.as_ref().map(|c| c.id().to_string())
     }
 
+    fn endpoint(&self) -> Option<String> {
+        self.uri.clone()
+    }
+
     fn name(&self) -> &'static str {
         "umadb"
     }
 
     fn create_adapter(&self) -> Result<Arc<dyn EventStoreAdapter>> {
-        let client = self.client.as_ref()
-            .ok_or_else(|| anyhow::anyhow!("UmaDB client not initialized. Did you call start()?"))?
-            .clone();
+        if self.clients.is_empty() {
+            anyhow::bail!("UmaDB client not initialized. Did you call start()?");
+        }
+        let next = self.next_client.fetch_add(1, Ordering::Relaxed);
+        let client = self.clients[next % self.clients.len()].clone();
         Ok(Arc::new(UmaDbAdapter { client }))
     }
 }
@@ -97,13 +173,14 @@ pub struct UmaDbAdapter {
 #[async_trait]
 impl EventStoreAdapter for UmaDbAdapter {
     async fn append(&self, events: Vec<EventData>) -> Result<()> {
-        let dcb_events: Vec<DCBEvent> = events.into_iter().map(|evt| DCBEvent {
+        let dcb_events: Vec<DCBEvent> = time_phase(Phase::Serialize, || events.into_iter().map(|evt| DCBEvent {
             event_type: evt.event_type,
             tags: evt.tags,
-            data: evt.payload,
+            data: evt.payload.into(),
             uuid: None,
-        }).collect();
-        let _pos: u64 = self.client.append(dcb_events, None, None).await?;
+        }).collect());
+        let _pos: u64 =
+            time_phase_async(Phase::Network, self.client.append(dcb_events, None, None)).await?;
         Ok(())
     }
 
@@ -114,27 +191,29 @@ impl EventStoreAdapter for UmaDbAdapter {
                 tags: vec![req.stream],
             }],
         };
-        let mut rr = self
-            .client
-            .read(
+        let mut rr = time_phase_async(
+            Phase::Network,
+            self.client.read(
                 Some(query),
                 req.from_offset,
                 false,
                 req.limit.map(|l| l as u32),
                 false,
-            )
-            .await?;
+            ),
+        )
+        .await?;
         let mut out = Vec::new();
         let mut got: u64 = 0;
-        while let Some(item) = rr.next().await {
+        while let Some(item) = time_phase_async(Phase::Network, rr.next()).await {
             match item {
                 Ok(se) => {
-                    out.push(ReadEvent {
+                    out.push(time_phase(Phase::Deserialize, || ReadEvent {
                         offset: se.position,
                         event_type: se.event.event_type.clone(),
-                        payload: se.event.data.clone(),
+                        payload: se.event.data.into(),
                         timestamp_ms: 0,
-                    });
+                        tags: se.event.tags,
+                    }));
                     got += 1;
                     if let Some(lim) = req.limit {
                         if got >= lim {
@@ -148,6 +227,66 @@ impl EventStoreAdapter for UmaDbAdapter {
         Ok(out)
     }
 
+    /// Reads without a query, which matches every event; positions are inclusive in UmaDB
+    async fn read_all(&self, after: Option<u64>, limit: Option<u64>) -> Result<Vec<ReadEvent>> {
+        let start = after.map(|after| after + 1);
+        let limit = limit.map(|l| l.min(u32::MAX as u64) as u32);
+        let mut rr = time_phase_async(Phase::Network, self.client.read(None, start, false, limit, false)).await?;
+        let mut out = Vec::new();
+        while let Some(item) = time_phase_async(Phase::Network, rr.next()).await {
+            let se = item?;
+            out.push(time_phase(Phase::Deserialize, || ReadEvent {
+                offset: se.position,
+                event_type: se.event.event_type.clone(),
+                payload: se.event.data.into(),
+                timestamp_ms: 0,
+                tags: se.event.tags,
+            }));
+            if limit.is_some_and(|lim| out.len() as u64 >= lim as u64) {
+                break;
+            }
+        }
+        Ok(out)
+    }
+
+    async fn append_conditional(
+        &self,
+        events: Vec<EventData>,
+        condition: AppendCondition,
+    ) -> Result<AppendOutcome> {
+        let stream = events
+            .first()
+            .and_then(|evt| evt.tags.first())
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("Conditional append requires a stream tag"))?;
+        let dcb_events: Vec<DCBEvent> = events.into_iter().map(|evt| DCBEvent {
+            event_type: evt.event_type,
+            tags: evt.tags,
+            data: evt.payload.into(),
+            uuid: None,
+        }).collect();
+        // Fail if any event tagged with the stream was appended after the known position
+        let dcb_condition = DCBAppendCondition {
+            fail_if_events_match: DCBQuery {
+                items: vec![DCBQueryItem {
+                    types: vec![],
+                    tags: vec![stream],
+                }],
+            },
+            after: match condition {
+                AppendCondition::NoStream => None,
+                AppendCondition::After(position) => Some(position),
+            },
+        };
+        match self.client.append(dcb_events, Some(dcb_condition), None).await {
+            Ok(position) => Ok(AppendOutcome::Appended { position }),
+            Err(DCBError::IntegrityError(_)) => Ok(AppendOutcome::Conflict {
+                current: self.client.head().await?,
+            }),
+            Err(e) => Err(e.into()),
+        }
+    }
+
     // async fn ping(&self) -> Result<Duration> {
     //     let t0 = std::time::Instant::now();
     //     let _ = self.client.head().await?;
@@ -165,4 +304,12 @@ impl StoreManagerFactory for UmaDbFactory {
     fn create_store_manager(&self, data_dir: Option<String>) -> Result<Box<dyn StoreManager>> {
         Ok(Box::new(UmaDbStoreManager::new(data_dir)))
     }
+
+    fn create_store_manager_with_options(
+        &self,
+        data_dir: Option<String>,
+        options: &HashMap<String, String>,
+    ) -> Result<Box<dyn StoreManager>> {
+        Ok(Box::new(UmaDbStoreManager::with_options(data_dir, options)?))
+    }
 }
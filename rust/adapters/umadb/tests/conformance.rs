@@ -0,0 +1,18 @@
+use bench_core::testkit::{run_conformance, Capabilities};
+use bench_core::StoreManagerFactory;
+use umadb_adapter::UmaDbFactory;
+
+#[tokio::test]
+#[ignore = "starts a UmaDB container (needs Docker)"]
+async fn umadb_conforms() -> anyhow::Result<()> {
+    let mut store = UmaDbFactory.create_store_manager(None)?;
+    store.start().await?;
+    let capabilities = Capabilities {
+        conditional_append: true,
+        subscribe: false,
+        read_all: true,
+    };
+    let result = run_conformance(store.as_ref(), capabilities).await;
+    store.stop().await?;
+    result
+}
@@ -1,4 +1,4 @@
-use crate::session::{Sample, Session};
+use crate::session::{BulkStats, InterferenceStats, Sample, Session};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -71,21 +71,30 @@ pub struct StoreView {
     pub readers: u32,
     pub container: ContainerView,
     pub samples_data: SamplesData,
+    pub interference: Option<InterferenceStats>,
+    pub bulk: Option<BulkStats>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ContainerView {
     pub image_size_mb: Option<f64>,
     pub startup_time_s: f64,
+    /// Image pull time, if the image was pulled before this run
+    pub image_pull_s: Option<f64>,
     pub avg_cpu_percent: Option<f64>,
     pub peak_cpu_percent: Option<f64>,
     pub avg_memory_mb: Option<f64>,
     pub peak_memory_mb: Option<f64>,
+    /// On-disk data growth over the run, and per event written
+    pub data_written_mb: Option<f64>,
+    pub data_bytes_per_event: Option<f64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SamplesData {
     pub latency_cdf: Vec<CdfPoint>,
+    /// Exact CDF per operation type from the run's percentile tables (empty in older results)
+    pub op_latency_cdf: Vec<OpCdfPoint>,
     pub throughput_timeseries: Vec<TimePoint>,
 }
 
@@ -95,10 +104,22 @@ pub struct CdfPoint {
     pub percentile: f64,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpCdfPoint {
+    pub op: String,
+    pub latency_ms: f64,
+    pub percentile: f64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TimePoint {
     pub time_s: f64,
     pub throughput_eps: f64,
+    /// Latency percentiles of the successful samples in the bin (empty bins have none)
+    #[serde(default)]
+    pub latency_p50_ms: Option<f64>,
+    #[serde(default)]
+    pub latency_p99_ms: Option<f64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -202,6 +223,7 @@ pub fn compute_session_detail(session: &Session) -> SessionDetail {
                     .image_size_bytes
                     .map(|b| b as f64 / (1024.0 * 1024.0)),
                 startup_time_s: data.summary.container.startup_time_s,
+                image_pull_s: data.summary.container.image_pull_s,
                 avg_cpu_percent: data.summary.container.avg_cpu_percent,
                 peak_cpu_percent: data.summary.container.peak_cpu_percent,
                 avg_memory_mb: data
@@ -214,9 +236,31 @@ pub fn compute_session_detail(session: &Session) -> SessionDetail {
                     .container
                     .peak_memory_bytes
                     .map(|b| b as f64 / (1024.0 * 1024.0)),
+                data_written_mb: data
+                    .summary
+                    .container
+                    .data_bytes_written
+                    .map(|b| b as f64 / (1024.0 * 1024.0)),
+                data_bytes_per_event: data
+                    .summary
+                    .container
+                    .data_bytes_written
+                    .filter(|_| data.summary.events_written > 0)
+                    .map(|b| b as f64 / data.summary.events_written as f64),
             };
 
-            let samples_data = compute_samples_data(&data.samples);
+            let mut samples_data = compute_samples_data(&data.samples);
+            samples_data.op_latency_cdf = data
+                .op_cdf
+                .iter()
+                .flat_map(|(op, table)| {
+                    table.iter().map(move |p| OpCdfPoint {
+                        op: op.clone(),
+                        latency_ms: p.latency_us as f64 / 1000.0,
+                        percentile: p.percentile,
+                    })
+                })
+                .collect();
 
             StoreView {
                 name: name.clone(),
@@ -232,6 +276,8 @@ pub fn compute_session_detail(session: &Session) -> SessionDetail {
                 readers: data.summary.readers,
                 container,
                 samples_data,
+                interference: data.summary.interference.clone(),
+                bulk: data.summary.bulk.clone(),
             }
         })
         .collect();
@@ -253,10 +299,23 @@ pub fn compute_session_detail(session: &Session) -> SessionDetail {
 
 /// Compute latency CDF and throughput timeseries from raw samples
 fn compute_samples_data(samples: &[Sample]) -> SamplesData {
+    let latency_cdf = compute_latency_cdf(samples);
+
+    // Compute throughput timeseries (50ms bins)
+    let throughput_timeseries = compute_throughput_timeseries(samples, 50);
+
+    SamplesData {
+        latency_cdf,
+        op_latency_cdf: Vec::new(),
+        throughput_timeseries,
+    }
+}
+
+/// Latency CDF over the successful samples
+pub(crate) fn compute_latency_cdf(samples: &[Sample]) -> Vec<CdfPoint> {
     // Filter successful samples
     let mut success_samples: Vec<&Sample> = samples.iter().filter(|s| s.ok).collect();
 
-    // Compute latency CDF
     let mut latency_cdf = Vec::new();
     if !success_samples.is_empty() {
         // Sort by latency
@@ -272,14 +331,7 @@ fn compute_samples_data(samples: &[Sample]) -> SamplesData {
             });
         }
     }
-
-    // Compute throughput timeseries (50ms bins)
-    let throughput_timeseries = compute_throughput_timeseries(samples, 50);
-
-    SamplesData {
-        latency_cdf,
-        throughput_timeseries,
-    }
+    latency_cdf
 }
 
 /// Compute throughput over time using time bins
@@ -293,8 +345,14 @@ fn compute_throughput_timeseries(samples: &[Sample], bin_size_ms: u64) -> Vec<Ti
         return Vec::new();
     }
 
-    let min_time = success_samples.iter().map(|s| s.t_ms).min().unwrap();
-    let max_time = success_samples.iter().map(|s| s.t_ms).max().unwrap();
+    // Relative timestamps share the measurement start as origin, so bins line up across stores;
+    // epoch-only samples fall back to the earliest sample
+    let min_time = if success_samples.iter().all(|s| s.t_rel_us.is_some()) {
+        0
+    } else {
+        success_samples.iter().map(|s| s.time_us()).min().unwrap() / 1000
+    };
+    let max_time = success_samples.iter().map(|s| s.time_us()).max().unwrap() / 1000;
     let duration_ms = max_time - min_time;
 
     if duration_ms == 0 {
@@ -306,29 +364,38 @@ fn compute_throughput_timeseries(samples: &[Sample], bin_size_ms: u64) -> Vec<Ti
         return Vec::new();
     }
 
-    // Count samples per bin
-    let mut bins = vec![0u64; num_bins];
+    // Collect sample latencies per bin
+    let mut bins: Vec<Vec<u64>> = vec![Vec::new(); num_bins];
     for sample in &success_samples {
-        let bin_idx = ((sample.t_ms - min_time) / bin_size_ms) as usize;
+        let bin_idx = ((sample.time_us() / 1000 - min_time) / bin_size_ms) as usize;
         if bin_idx < num_bins {
-            bins[bin_idx] += 1;
+            bins[bin_idx].push(sample.latency_us);
         }
     }
 
-    // Convert to events per second
+    // Convert to events per second and in-bin percentiles
     bins.into_iter()
         .enumerate()
-        .map(|(idx, count)| {
+        .map(|(idx, mut latencies)| {
             let time_s = (idx as f64 + 0.5) * (bin_size_ms as f64 / 1000.0);
-            let throughput_eps = count as f64 * (1000.0 / bin_size_ms as f64);
+            let throughput_eps = latencies.len() as f64 * (1000.0 / bin_size_ms as f64);
+            latencies.sort_unstable();
             TimePoint {
                 time_s,
                 throughput_eps,
+                latency_p50_ms: bin_percentile_ms(&latencies, 0.50),
+                latency_p99_ms: bin_percentile_ms(&latencies, 0.99),
             }
         })
         .collect()
 }
 
+/// Nearest-rank percentile of sorted latencies in microseconds, in milliseconds
+fn bin_percentile_ms(sorted_us: &[u64], q: f64) -> Option<f64> {
+    let rank = ((q * sorted_us.len() as f64).ceil() as usize).max(1);
+    sorted_us.get(rank - 1).map(|&us| us as f64 / 1000.0)
+}
+
 /// Generate comparison charts across stores
 fn generate_comparison_charts(session: &Session) -> Vec<ComparisonChart> {
     let mut charts = Vec::new();
@@ -0,0 +1,143 @@
+use crate::session::StoreSummary;
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+/// Markdown table of a session's runs, for PR comments and job summaries
+pub const SUMMARY_FILE: &str = "benchmark-summary.md";
+/// Throughput entries for github-action-benchmark's `customBiggerIsBetter` tool
+pub const RESULTS_FILE: &str = "benchmark-results.json";
+/// Latency entries for github-action-benchmark's `customSmallerIsBetter` tool
+pub const LATENCY_RESULTS_FILE: &str = "benchmark-latency-results.json";
+
+/// One entry in github-action-benchmark's custom tool format. Names are
+/// `<workload>/<store>/<metric>`, so they stay stable across runs of the same config.
+#[derive(Debug, Clone, Serialize)]
+pub struct BenchmarkEntry {
+    pub name: String,
+    pub unit: &'static str,
+    pub value: f64,
+    pub extra: String,
+}
+
+struct Run {
+    workload: String,
+    store: String,
+    summary: StoreSummary,
+}
+
+/// Write benchmark-summary.md, benchmark-results.json and benchmark-latency-results.json for
+/// every run in `session_dir` into `output_dir`; returns the written files
+pub fn write_ci_artifacts(session_dir: &Path, output_dir: &Path) -> Result<Vec<PathBuf>> {
+    let runs = load_runs(session_dir)?;
+    if runs.is_empty() {
+        anyhow::bail!("{} contains no completed runs", session_dir.display());
+    }
+    std::fs::create_dir_all(output_dir)
+        .with_context(|| format!("Failed to create {}", output_dir.display()))?;
+
+    let mut throughput = Vec::new();
+    let mut latency = Vec::new();
+    for run in &runs {
+        let prefix = format!("{}/{}", run.workload, run.store);
+        let extra = format!(
+            "{} writers, {} readers, {:.1}s",
+            run.summary.writers, run.summary.readers, run.summary.duration_s
+        );
+        throughput.push(BenchmarkEntry {
+            name: format!("{}/throughput", prefix),
+            unit: "events/s",
+            value: run.summary.throughput_eps,
+            extra: extra.clone(),
+        });
+        for (metric, value) in [("p50", run.summary.latency.p50_ms), ("p99", run.summary.latency.p99_ms)] {
+            latency.push(BenchmarkEntry {
+                name: format!("{}/{}", prefix, metric),
+                unit: "ms",
+                value,
+                extra: extra.clone(),
+            });
+        }
+    }
+
+    let written = vec![
+        output_dir.join(SUMMARY_FILE),
+        output_dir.join(RESULTS_FILE),
+        output_dir.join(LATENCY_RESULTS_FILE),
+    ];
+    std::fs::write(&written[0], summary_markdown(session_dir, &runs))?;
+    std::fs::write(&written[1], serde_json::to_string_pretty(&throughput)?)?;
+    std::fs::write(&written[2], serde_json::to_string_pretty(&latency)?)?;
+    Ok(written)
+}
+
+fn summary_markdown(session_dir: &Path, runs: &[Run]) -> String {
+    let session_id = session_dir
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or_default();
+    let mut md = format!("## Event store benchmark results\n\nSession `{}`\n", session_id);
+    let mut current_workload = None;
+    for run in runs {
+        if current_workload != Some(&run.workload) {
+            current_workload = Some(&run.workload);
+            let _ = write!(
+                md,
+                "\n### {}\n\n| Store | Clients | Throughput (events/s) | p50 (ms) | p95 (ms) | p99 (ms) | p99.9 (ms) |\n|---|---:|---:|---:|---:|---:|---:|\n",
+                run.workload
+            );
+        }
+        let latency = &run.summary.latency;
+        let _ = writeln!(
+            md,
+            "| {} | {}w / {}r | {:.2} | {:.2} | {:.2} | {:.2} | {:.2} |",
+            run.store,
+            run.summary.writers,
+            run.summary.readers,
+            run.summary.throughput_eps,
+            latency.p50_ms,
+            latency.p95_ms,
+            latency.p99_ms,
+            latency.p999_ms
+        );
+    }
+    md
+}
+
+/// Summaries under `<session>/<workload>/<store>/`, sorted by workload and store
+fn load_runs(session_dir: &Path) -> Result<Vec<Run>> {
+    let mut runs = Vec::new();
+    for workload_entry in std::fs::read_dir(session_dir)
+        .with_context(|| format!("Failed to read {}", session_dir.display()))?
+    {
+        let workload_dir = workload_entry?.path();
+        if !workload_dir.is_dir() {
+            continue;
+        }
+        for store_entry in std::fs::read_dir(&workload_dir)? {
+            let summary_path = store_entry?.path().join("summary.json");
+            if !summary_path.exists() {
+                continue;
+            }
+            let summary: StoreSummary = serde_json::from_reader(
+                std::fs::File::open(&summary_path)
+                    .with_context(|| format!("Failed to open {}", summary_path.display()))?,
+            )
+            .with_context(|| format!("Failed to parse {}", summary_path.display()))?;
+            let name = |path: &Path| {
+                path.file_name()
+                    .and_then(|n| n.to_str())
+                    .unwrap_or_default()
+                    .to_string()
+            };
+            runs.push(Run {
+                workload: name(&workload_dir),
+                store: name(summary_path.parent().unwrap_or(&workload_dir)),
+                summary,
+            });
+        }
+    }
+    runs.sort_by(|a, b| (&a.workload, &a.store).cmp(&(&b.workload, &b.store)));
+    Ok(runs)
+}
@@ -0,0 +1,207 @@
+use crate::session::StoreSummary;
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// One store's run of a workload with its rank on each compared metric (1 is best; runs
+/// without a value rank last)
+#[derive(Debug, Clone, Serialize)]
+pub struct ComparedRun {
+    /// Run directory name (the store and any swept options), with the session appended when
+    /// several compared sessions ran the same store
+    pub store: String,
+    pub path: PathBuf,
+    pub throughput_eps: f64,
+    pub p50_ms: f64,
+    pub p99_ms: f64,
+    pub startup_time_s: f64,
+    pub image_size_bytes: Option<u64>,
+    pub peak_memory_bytes: Option<u64>,
+    pub throughput_rank: usize,
+    pub p99_rank: usize,
+    pub startup_rank: usize,
+    /// By peak container memory
+    pub footprint_rank: usize,
+    /// Mean of the four ranks; runs are listed by it
+    pub mean_rank: f64,
+}
+
+/// The runs of one workload, best first
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkloadComparison {
+    pub workload: String,
+    pub runs: Vec<ComparedRun>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Comparison {
+    pub workloads: Vec<WorkloadComparison>,
+}
+
+/// Load every summary.json under the given run, session or results directories and rank the
+/// stores of each workload against each other
+pub fn compare_results(dirs: &[PathBuf]) -> Result<Comparison> {
+    let mut by_workload: BTreeMap<String, Vec<(String, String, PathBuf, StoreSummary)>> = BTreeMap::new();
+    for dir in dirs {
+        anyhow::ensure!(dir.is_dir(), "{} is not a directory", dir.display());
+        for entry in WalkDir::new(dir).sort_by_file_name() {
+            let entry = entry?;
+            if entry.file_name() != "summary.json" {
+                continue;
+            }
+            let path = entry.path();
+            let summary: StoreSummary = serde_json::from_reader(
+                std::fs::File::open(path).with_context(|| format!("Failed to open {}", path.display()))?,
+            )
+            .with_context(|| format!("Failed to parse {}", path.display()))?;
+            let run_dir = path.parent().unwrap_or(dir).to_path_buf();
+            let session = run_dir.parent().and_then(Path::parent).map(dir_name).unwrap_or_default();
+            by_workload
+                .entry(summary.workload.clone())
+                .or_default()
+                .push((dir_name(&run_dir), session, run_dir, summary));
+        }
+    }
+    anyhow::ensure!(!by_workload.is_empty(), "No summary.json found in the given directories");
+
+    let workloads = by_workload
+        .into_iter()
+        .map(|(workload, runs)| {
+            let mut seen = HashSet::new();
+            let repeated: HashSet<String> =
+                runs.iter().filter(|(store, ..)| !seen.insert(store.clone())).map(|(store, ..)| store.clone()).collect();
+            let throughput_rank = ranks(runs.iter().map(|(.., s)| Some(-s.throughput_eps)));
+            let p99_rank = ranks(runs.iter().map(|(.., s)| Some(s.latency.p99_ms)));
+            let startup_rank = ranks(runs.iter().map(|(.., s)| Some(s.container.startup_time_s)));
+            let footprint_rank = ranks(runs.iter().map(|(.., s)| s.container.peak_memory_bytes.map(|b| b as f64)));
+            let mut compared: Vec<ComparedRun> = runs
+                .into_iter()
+                .enumerate()
+                .map(|(i, (store, session, path, summary))| {
+                    let rank = [throughput_rank[i], p99_rank[i], startup_rank[i], footprint_rank[i]];
+                    ComparedRun {
+                        store: if repeated.contains(&store) { format!("{}@{}", store, session) } else { store },
+                        path,
+                        throughput_eps: summary.throughput_eps,
+                        p50_ms: summary.latency.p50_ms,
+                        p99_ms: summary.latency.p99_ms,
+                        startup_time_s: summary.container.startup_time_s,
+                        image_size_bytes: summary.container.image_size_bytes,
+                        peak_memory_bytes: summary.container.peak_memory_bytes,
+                        throughput_rank: rank[0],
+                        p99_rank: rank[1],
+                        startup_rank: rank[2],
+                        footprint_rank: rank[3],
+                        mean_rank: rank.iter().sum::<usize>() as f64 / rank.len() as f64,
+                    }
+                })
+                .collect();
+            compared.sort_by(|a, b| {
+                a.mean_rank.total_cmp(&b.mean_rank).then(b.throughput_eps.total_cmp(&a.throughput_eps))
+            });
+            WorkloadComparison { workload, runs: compared }
+        })
+        .collect();
+    Ok(Comparison { workloads })
+}
+
+/// Competition ranks of values where lower is better: equal values share a rank, missing
+/// values come last
+fn ranks(values: impl Iterator<Item = Option<f64>>) -> Vec<usize> {
+    let values: Vec<Option<f64>> = values.collect();
+    let below = |v: &Option<f64>| {
+        values
+            .iter()
+            .filter(|other| match (other, v) {
+                (Some(other), Some(v)) => other < v,
+                (Some(_), None) => true,
+                (None, _) => false,
+            })
+            .count()
+    };
+    values.iter().map(|v| below(v) + 1).collect()
+}
+
+fn dir_name(path: &Path) -> String {
+    path.file_name().and_then(|n| n.to_str()).unwrap_or_default().to_string()
+}
+
+fn megabytes(bytes: Option<u64>) -> String {
+    bytes.map(|b| format!("{:.0}", b as f64 / 1e6)).unwrap_or_else(|| "-".to_string())
+}
+
+impl Comparison {
+    /// Plain-text tables for the terminal
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        for workload in &self.workloads {
+            let _ = writeln!(text, "\n{}", workload.workload);
+            let _ = writeln!(
+                text,
+                "{:>4}  {:<28} {:>14} {:>10} {:>10} {:>11} {:>9} {:>9}  ranks (tput/p99/start/mem)",
+                "#", "Store", "events/sec", "p50 (ms)", "p99 (ms)", "startup (s)", "image MB", "peak MB"
+            );
+            for (i, run) in workload.runs.iter().enumerate() {
+                let _ = writeln!(
+                    text,
+                    "{:>4}  {:<28} {:>14.2} {:>10.2} {:>10.2} {:>11.2} {:>9} {:>9}  {}/{}/{}/{}",
+                    i + 1,
+                    run.store,
+                    run.throughput_eps,
+                    run.p50_ms,
+                    run.p99_ms,
+                    run.startup_time_s,
+                    megabytes(run.image_size_bytes),
+                    megabytes(run.peak_memory_bytes),
+                    run.throughput_rank,
+                    run.p99_rank,
+                    run.startup_rank,
+                    run.footprint_rank
+                );
+            }
+        }
+        text
+    }
+
+    /// Markdown tables, one section per workload
+    pub fn to_markdown(&self) -> String {
+        let mut md = String::from("## Event store comparison\n");
+        for workload in &self.workloads {
+            let _ = write!(
+                md,
+                "\n### {}\n\n| # | Store | Throughput (events/s) | p50 (ms) | p99 (ms) | Startup (s) | Image (MB) | Peak memory (MB) | Mean rank |\n|---:|---|---:|---:|---:|---:|---:|---:|---:|\n",
+                workload.workload
+            );
+            for (i, run) in workload.runs.iter().enumerate() {
+                let _ = writeln!(
+                    md,
+                    "| {} | {} | {:.2} | {:.2} | {:.2} | {:.2} | {} | {} | {:.2} |",
+                    i + 1,
+                    run.store,
+                    run.throughput_eps,
+                    run.p50_ms,
+                    run.p99_ms,
+                    run.startup_time_s,
+                    megabytes(run.image_size_bytes),
+                    megabytes(run.peak_memory_bytes),
+                    run.mean_rank
+                );
+            }
+        }
+        md
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ranks_ties_together_and_missing_values_last() {
+        let values = [Some(3.0), None, Some(1.0), Some(3.0)];
+        assert_eq!(ranks(values.into_iter()), [2, 4, 1, 2]);
+    }
+}
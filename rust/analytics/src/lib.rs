@@ -1,11 +1,21 @@
 pub mod aggregation;
+pub mod ci;
+pub mod compare;
+pub mod plots;
 pub mod report;
 pub mod scanner;
 pub mod session;
 pub mod templates;
+pub mod timeline;
+pub mod trend;
 
+pub use ci::write_ci_artifacts;
+pub use compare::{compare_results, Comparison};
 pub use aggregation::{compute_session_detail, compute_session_index, SessionDetail, SessionIndex};
+pub use plots::{plot_run, PlotFormat};
 pub use report::ReportGenerator;
 pub use scanner::SessionScanner;
 pub use session::Session;
 pub use templates::{generate_index_html, generate_session_html};
+pub use timeline::{compare_timelines, TimelineComparison};
+pub use trend::{compute_trends, TrendReport};
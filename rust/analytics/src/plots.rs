@@ -0,0 +1,244 @@
+use crate::aggregation::compute_latency_cdf;
+use crate::session::{load_samples, Sample};
+use anyhow::{bail, Context, Result};
+use plotters::coord::Shift;
+use plotters::prelude::*;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+const WIDTH: u32 = 1024;
+const HEIGHT: u32 = 640;
+/// Width of the windows latency percentiles are computed over
+const LATENCY_WINDOW_US: u64 = 1_000_000;
+const SERIES_COLORS: [RGBColor; 3] = [
+    RGBColor(31, 119, 180),
+    RGBColor(255, 127, 14),
+    RGBColor(214, 39, 40),
+];
+
+/// Image format of rendered charts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlotFormat {
+    Png,
+    Svg,
+}
+
+impl PlotFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            PlotFormat::Png => "png",
+            PlotFormat::Svg => "svg",
+        }
+    }
+}
+
+impl FromStr for PlotFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "png" => Ok(PlotFormat::Png),
+            "svg" => Ok(PlotFormat::Svg),
+            other => bail!("unknown plot format '{}' (expected png or svg)", other),
+        }
+    }
+}
+
+/// Line in throughput.jsonl: cumulative operation count at a point in the run
+#[derive(Deserialize)]
+struct ThroughputLine {
+    elapsed_s: f64,
+    count: u64,
+}
+
+/// Line in latency.json's percentile list
+#[derive(Deserialize)]
+struct PercentileLine {
+    percentile: f64,
+    latency_us: u64,
+}
+
+#[derive(Deserialize)]
+struct PercentileFile {
+    percentiles: Vec<PercentileLine>,
+}
+
+struct LineChart {
+    file_stem: &'static str,
+    title: String,
+    x_desc: &'static str,
+    y_desc: &'static str,
+    series: Vec<(&'static str, Vec<(f64, f64)>)>,
+}
+
+/// Render throughput-over-time, latency-percentiles-over-time and latency CDF charts for one
+/// run directory (`<session>/<workload>/<store>/`) into `output_dir`
+///
+/// Charts whose input file is missing or empty are skipped. Returns the written files.
+pub fn plot_run(run_dir: &Path, output_dir: &Path, format: PlotFormat) -> Result<Vec<PathBuf>> {
+    let samples = load_samples(&run_dir.join("samples.jsonl"))?;
+    let charts = [
+        throughput_chart(run_dir)?,
+        latency_over_time_chart(&samples),
+        cdf_chart(run_dir, &samples)?,
+    ];
+
+    std::fs::create_dir_all(output_dir)
+        .with_context(|| format!("Failed to create {}", output_dir.display()))?;
+    let mut written = Vec::new();
+    for chart in charts.into_iter().flatten() {
+        let path = output_dir.join(format!("{}.{}", chart.file_stem, format.extension()));
+        match format {
+            PlotFormat::Png => draw(BitMapBackend::new(&path, (WIDTH, HEIGHT)).into_drawing_area(), &chart)?,
+            PlotFormat::Svg => draw(SVGBackend::new(&path, (WIDTH, HEIGHT)).into_drawing_area(), &chart)?,
+        }
+        written.push(path);
+    }
+    if written.is_empty() {
+        bail!("{} has no throughput, sample or latency data to plot", run_dir.display());
+    }
+    Ok(written)
+}
+
+/// Operations per second between consecutive throughput.jsonl points
+fn throughput_chart(run_dir: &Path) -> Result<Option<LineChart>> {
+    let path = run_dir.join("throughput.jsonl");
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let mut lines = Vec::new();
+    for line in content.lines().filter(|line| !line.trim().is_empty()) {
+        let line: ThroughputLine = serde_json::from_str(line)
+            .with_context(|| format!("Failed to parse throughput line in {}", path.display()))?;
+        lines.push(line);
+    }
+    let points: Vec<(f64, f64)> = lines
+        .windows(2)
+        .filter(|pair| pair[1].elapsed_s > pair[0].elapsed_s)
+        .map(|pair| {
+            let rate = pair[1].count.saturating_sub(pair[0].count) as f64
+                / (pair[1].elapsed_s - pair[0].elapsed_s);
+            (pair[1].elapsed_s, rate)
+        })
+        .collect();
+    if points.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(LineChart {
+        file_stem: "throughput",
+        title: "Throughput over time".to_string(),
+        x_desc: "time (s)",
+        y_desc: "operations/s",
+        series: vec![("throughput", points)],
+    }))
+}
+
+/// p50/p95/p99 of the successful samples in each one-second window
+fn latency_over_time_chart(samples: &[Sample]) -> Option<LineChart> {
+    let mut ok: Vec<&Sample> = samples.iter().filter(|s| s.ok).collect();
+    if ok.is_empty() {
+        return None;
+    }
+    // Epoch-only samples of older results start at their earliest sample
+    let origin = if ok.iter().all(|s| s.t_rel_us.is_some()) {
+        0
+    } else {
+        ok.iter().map(|s| s.time_us()).min().unwrap_or(0)
+    };
+    ok.sort_by_key(|s| s.time_us());
+
+    let mut series: Vec<(&'static str, Vec<(f64, f64)>)> =
+        vec![("p50", Vec::new()), ("p95", Vec::new()), ("p99", Vec::new())];
+    for window in ok.chunk_by(|a, b| {
+        (a.time_us() - origin) / LATENCY_WINDOW_US == (b.time_us() - origin) / LATENCY_WINDOW_US
+    }) {
+        let index = (window[0].time_us() - origin) / LATENCY_WINDOW_US;
+        let time_s = (index as f64 + 0.5) * LATENCY_WINDOW_US as f64 / 1_000_000.0;
+        let mut latencies: Vec<u64> = window.iter().map(|s| s.latency_us).collect();
+        latencies.sort_unstable();
+        for ((_, points), quantile) in series.iter_mut().zip([0.50, 0.95, 0.99]) {
+            let rank = ((latencies.len() - 1) as f64 * quantile).round() as usize;
+            points.push((time_s, latencies[rank] as f64 / 1000.0));
+        }
+    }
+    Some(LineChart {
+        file_stem: "latency_over_time",
+        title: "Latency percentiles over time (sampled operations)".to_string(),
+        x_desc: "time (s)",
+        y_desc: "latency (ms)",
+        series,
+    })
+}
+
+/// Latency CDF from the full histogram in latency.json, falling back to the samples
+fn cdf_chart(run_dir: &Path, samples: &[Sample]) -> Result<Option<LineChart>> {
+    let path = run_dir.join("latency.json");
+    let (points, source) = if path.exists() {
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let file: PercentileFile = serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse {}", path.display()))?;
+        let mut points: Vec<(f64, f64)> = file
+            .percentiles
+            .iter()
+            .map(|p| (p.latency_us as f64 / 1000.0, p.percentile))
+            .collect();
+        points.sort_by(|a, b| a.1.total_cmp(&b.1));
+        (points, "all operations")
+    } else {
+        let points = compute_latency_cdf(samples)
+            .iter()
+            .map(|p| (p.latency_ms, p.percentile))
+            .collect();
+        (points, "sampled operations")
+    };
+    if points.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(LineChart {
+        file_stem: "latency_cdf",
+        title: format!("Latency CDF ({})", source),
+        x_desc: "latency (ms)",
+        y_desc: "percentile",
+        series: vec![("latency", points)],
+    }))
+}
+
+fn draw<DB: DrawingBackend>(root: DrawingArea<DB, Shift>, chart: &LineChart) -> Result<()>
+where
+    DB::ErrorType: 'static,
+{
+    root.fill(&WHITE)?;
+    let points = chart.series.iter().flat_map(|(_, points)| points.iter());
+    let x_max = points.clone().map(|p| p.0).fold(0.0, f64::max).max(f64::EPSILON);
+    let y_max = points.map(|p| p.1).fold(0.0, f64::max).max(f64::EPSILON);
+
+    let mut ctx = ChartBuilder::on(&root)
+        .caption(&chart.title, ("sans-serif", 24))
+        .margin(15)
+        .x_label_area_size(40)
+        .y_label_area_size(60)
+        .build_cartesian_2d(0.0..x_max * 1.02, 0.0..y_max * 1.05)?;
+    ctx.configure_mesh()
+        .x_desc(chart.x_desc)
+        .y_desc(chart.y_desc)
+        .draw()?;
+
+    for (idx, (name, points)) in chart.series.iter().enumerate() {
+        let color = SERIES_COLORS[idx % SERIES_COLORS.len()];
+        ctx.draw_series(LineSeries::new(points.iter().copied(), color.stroke_width(2)))?
+            .label(*name)
+            .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], color.stroke_width(2)));
+    }
+    if chart.series.len() > 1 {
+        ctx.configure_series_labels()
+            .background_style(WHITE.mix(0.8))
+            .border_style(BLACK)
+            .draw()?;
+    }
+    root.present()?;
+    Ok(())
+}
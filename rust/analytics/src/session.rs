@@ -1,6 +1,6 @@
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::{Path, PathBuf};
 
 /// Session metadata from session.json
@@ -63,6 +63,9 @@ pub struct ContainerRuntimeInfo {
 /// Store-level summary from {store}/summary.json
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StoreSummary {
+    /// Layout version written by bench-core; files without it are version 1
+    #[serde(default = "legacy_schema_version")]
+    pub schema_version: u32,
     pub workload: String,
     pub adapter: String,
     pub writers: u32,
@@ -72,7 +75,77 @@ pub struct StoreSummary {
     pub duration_s: f64,
     pub throughput_eps: f64,
     pub latency: LatencyMetrics,
+    /// Throughput and latency per window of the run (performance workloads)
+    #[serde(default)]
+    pub throughput_series: Vec<SeriesBucket>,
     pub container: ContainerMetrics,
+    /// Read latency by concurrent write rate (mixed runs only)
+    #[serde(default)]
+    pub interference: Option<InterferenceStats>,
+    /// Periodic bulk operations and the latency during and between them (`bulk` configs)
+    #[serde(default)]
+    pub bulk: Option<BulkStats>,
+    /// Commit of the store under test, when given with `--store-rev`
+    #[serde(default)]
+    pub store_rev: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SeriesBucket {
+    /// Start of the window, in seconds from the measurement start
+    pub t_s: f64,
+    pub ops_per_s: f64,
+    #[serde(default)]
+    pub latency_p50_ms: Option<f64>,
+    #[serde(default)]
+    pub latency_p99_ms: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InterferenceStats {
+    pub window_ms: u64,
+    pub windows: usize,
+    pub write_rate_read_p99_correlation: Option<f64>,
+    pub top_to_bottom_read_p99_ratio: Option<f64>,
+    pub by_write_rate: Vec<WriteRateBucket>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WriteRateBucket {
+    pub min_write_ops_per_s: f64,
+    pub max_write_ops_per_s: f64,
+    pub windows: usize,
+    pub reads: usize,
+    pub read_p50_ms: f64,
+    pub read_p99_ms: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BulkStats {
+    pub kind: String,
+    pub every_seconds: f64,
+    pub windows: Vec<BulkWindow>,
+    pub ops_in_window: usize,
+    #[serde(default)]
+    pub in_window: Option<LatencyMetrics>,
+    pub ops_outside: usize,
+    #[serde(default)]
+    pub outside: Option<LatencyMetrics>,
+    #[serde(default)]
+    pub p99_ratio: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BulkWindow {
+    pub start_s: f64,
+    pub end_s: f64,
+    pub events: u64,
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+fn legacy_schema_version() -> u32 {
+    1
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -87,19 +160,46 @@ pub struct LatencyMetrics {
 pub struct ContainerMetrics {
     pub image_size_bytes: Option<u64>,
     pub startup_time_s: f64,
+    #[serde(default)]
+    pub image_pull_s: Option<f64>,
     pub avg_cpu_percent: Option<f64>,
     pub peak_cpu_percent: Option<f64>,
     pub avg_memory_bytes: Option<u64>,
     pub peak_memory_bytes: Option<u64>,
+    #[serde(default)]
+    pub data_bytes_written: Option<u64>,
 }
 
 /// Individual sample from samples.jsonl
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Sample {
-    pub t_ms: u64,
+    /// Microseconds since the measurement start (monotonic clock)
+    #[serde(default)]
+    pub t_rel_us: Option<u64>,
+    /// Wall-clock epoch milliseconds (optional in newer results)
+    #[serde(default)]
+    pub t_ms: Option<u64>,
+    /// Worker and per-worker operation sequence number (absent in older results)
+    #[serde(default)]
+    pub worker: Option<usize>,
+    #[serde(default)]
+    pub seq: Option<u64>,
     pub op: String,
     pub latency_us: u64,
     pub ok: bool,
+    /// Target stream index or bucket (only when `sample_stream` was enabled)
+    #[serde(default)]
+    pub stream: Option<u64>,
+}
+
+impl Sample {
+    /// Sample time in microseconds, preferring the time relative to the measurement start and
+    /// falling back to the epoch timestamp of results that predate it
+    pub fn time_us(&self) -> u64 {
+        self.t_rel_us
+            .or(self.t_ms.map(|t_ms| t_ms * 1000))
+            .unwrap_or(0)
+    }
 }
 
 /// Complete session data
@@ -117,6 +217,15 @@ pub struct Session {
 pub struct StoreData {
     pub summary: StoreSummary,
     pub samples: Vec<Sample>,
+    /// Percentile tables per operation type from latency_cdf.json (empty in older results)
+    pub op_cdf: BTreeMap<String, Vec<PercentilePoint>>,
+}
+
+/// Row of a percentile -> latency table in latency_cdf.json
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PercentilePoint {
+    pub percentile: f64,
+    pub latency_us: u64,
 }
 
 impl Session {
@@ -179,28 +288,21 @@ impl Session {
                 .with_context(|| format!("Failed to parse {}", summary_path.display()))?;
 
                 // Load samples
-                let samples_path = store_path.join("samples.jsonl");
-                let mut samples = Vec::new();
-
-                if samples_path.exists() {
-                    let file = std::fs::File::open(&samples_path)
-                        .with_context(|| format!("Failed to open {}", samples_path.display()))?;
-                    let reader = std::io::BufReader::new(file);
-
-                    use std::io::BufRead;
-                    for line in reader.lines() {
-                        let line = line?;
-                        if line.trim().is_empty() {
-                            continue;
-                        }
-                        let sample: Sample = serde_json::from_str(&line).with_context(|| {
-                            format!("Failed to parse sample line in {}", samples_path.display())
-                        })?;
-                        samples.push(sample);
-                    }
-                }
+                let samples = load_samples(&store_path.join("samples.jsonl"))?;
+
+                // Load per-operation percentile tables
+                let cdf_path = store_path.join("latency_cdf.json");
+                let op_cdf = if cdf_path.exists() {
+                    serde_json::from_reader(
+                        std::fs::File::open(&cdf_path)
+                            .with_context(|| format!("Failed to open {}", cdf_path.display()))?,
+                    )
+                    .with_context(|| format!("Failed to parse {}", cdf_path.display()))?
+                } else {
+                    BTreeMap::new()
+                };
 
-                stores.insert(store_name, StoreData { summary, samples });
+                stores.insert(store_name, StoreData { summary, samples, op_cdf });
             }
         }
 
@@ -213,3 +315,28 @@ impl Session {
         })
     }
 }
+
+/// Load a samples.jsonl file; a missing file yields no samples
+pub fn load_samples(samples_path: &Path) -> Result<Vec<Sample>> {
+    let mut samples = Vec::new();
+
+    if samples_path.exists() {
+        let file = std::fs::File::open(samples_path)
+            .with_context(|| format!("Failed to open {}", samples_path.display()))?;
+        let reader = std::io::BufReader::new(file);
+
+        use std::io::BufRead;
+        for line in reader.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let sample: Sample = serde_json::from_str(&line).with_context(|| {
+                format!("Failed to parse sample line in {}", samples_path.display())
+            })?;
+            samples.push(sample);
+        }
+    }
+
+    Ok(samples)
+}
@@ -768,11 +768,21 @@ function renderStores() {
             <div id="store-${idx}-throughput-ts"></div>
           </div>
         </div>
+        <div class="chart-row">
+          <div class="chart-half">
+            <h4>Latency Over Time (p50/p99)</h4>
+            <div id="store-${idx}-latency-ts"></div>
+          </div>
+        </div>
         <div class="chart-row">
           <div class="chart-half">
             <h4>Resource Usage</h4>
             <div id="store-${idx}-resources"></div>
           </div>
+          <div class="chart-half">
+            <h4>Read p99 by Concurrent Write Rate</h4>
+            <div id="store-${idx}-interference"></div>
+          </div>
         </div>
       </div>
     `;
@@ -784,13 +794,43 @@ function renderStores() {
     // Render throughput timeseries chart
     renderThroughputTimeseries(store, idx);
 
+    // Render latency percentiles over time
+    renderLatencyTimeseries(store, idx);
+
     // Render resource usage
     renderResourceUsage(store, idx);
+
+    // Render read/write interference
+    renderInterference(store, idx);
   });
 }
 
-// Render latency CDF for a store
+// Render latency CDF for a store: exact per-operation tables when the run has them,
+// otherwise the sampled operations
 function renderLatencyCdf(store, idx) {
+  const opCdf = store.samples_data.op_latency_cdf;
+  if (opCdf && opCdf.length > 0) {
+    const chart = Plot.plot({
+      marginLeft: 50,
+      marginBottom: 50,
+      height: 250,
+      x: {label: "Latency (ms)", type: "log", grid: true},
+      y: {label: "Percentile (%)", domain: [0, 100], grid: true},
+      color: {legend: true},
+      marks: [
+        Plot.line(opCdf, {
+          x: "latency_ms",
+          y: "percentile",
+          stroke: "op",
+          strokeWidth: 2
+        }),
+        Plot.ruleY([50, 95, 99], {stroke: "#ccc", strokeDasharray: "2,2"})
+      ]
+    });
+    document.getElementById(`store-${idx}-latency-cdf`).appendChild(chart);
+    return;
+  }
+
   if (!store.samples_data.latency_cdf || store.samples_data.latency_cdf.length === 0) {
     document.getElementById(`store-${idx}-latency-cdf`).innerHTML = '<p style="color: #999;">No latency data available</p>';
     return;
@@ -830,6 +870,7 @@ function renderThroughputTimeseries(store, idx) {
     x: {label: "Time (s)", grid: true},
     y: {label: "Throughput (events/sec)", grid: true},
     marks: [
+      ...bulkMarks(store),
       Plot.line(store.samples_data.throughput_timeseries, {
         x: "time_s",
         y: "throughput_eps",
@@ -843,17 +884,108 @@ function renderThroughputTimeseries(store, idx) {
   document.getElementById(`store-${idx}-throughput-ts`).appendChild(chart);
 }
 
+// Shade the bulk operations of a store's run on a timeline
+function bulkMarks(store) {
+  const windows = store.bulk ? store.bulk.windows : [];
+  return [Plot.rectX(windows, {x1: "start_s", x2: "end_s", fill: "#f59e0b", fillOpacity: 0.2})];
+}
+
+// Render per-bin p50 and p99 latency for a store
+function renderLatencyTimeseries(store, idx) {
+  const points = (store.samples_data.throughput_timeseries || []).filter(p => p.latency_p99_ms != null);
+  if (points.length === 0) {
+    document.getElementById(`store-${idx}-latency-ts`).innerHTML = '<p style="color: #999;">No latency data available</p>';
+    return;
+  }
+
+  const series = points.flatMap(p => [
+    {time_s: p.time_s, latency_ms: p.latency_p50_ms, percentile: "p50"},
+    {time_s: p.time_s, latency_ms: p.latency_p99_ms, percentile: "p99"}
+  ]);
+  const chart = Plot.plot({
+    marginLeft: 50,
+    marginBottom: 50,
+    height: 250,
+    x: {label: "Time (s)", grid: true},
+    y: {label: "Latency (ms)", grid: true},
+    color: {legend: true},
+    marks: [
+      ...bulkMarks(store),
+      Plot.line(series, {
+        x: "time_s",
+        y: "latency_ms",
+        stroke: "percentile",
+        strokeWidth: 2
+      }),
+      Plot.ruleY([0])
+    ]
+  });
+
+  const container = document.getElementById(`store-${idx}-latency-ts`);
+  container.appendChild(chart);
+
+  const bulk = store.bulk;
+  if (bulk) {
+    const p99 = stats => stats ? stats.p99_ms.toFixed(2) + ' ms' : 'N/A';
+    const note = document.createElement('p');
+    note.style.cssText = 'font-size: 12px; color: #666;';
+    note.textContent = `Shaded: ${bulk.windows.length} ${bulk.kind} operations every ${bulk.every_seconds} s. ` +
+      `p99 during: ${p99(bulk.in_window)}, between: ${p99(bulk.outside)}, ` +
+      `ratio: ${bulk.p99_ratio != null ? bulk.p99_ratio.toFixed(2) + 'x' : 'N/A'}`;
+    container.appendChild(note);
+  }
+}
+
+// Render read latency per write-rate decile for a store (mixed runs only)
+function renderInterference(store, idx) {
+  const container = document.getElementById(`store-${idx}-interference`);
+  const stats = store.interference;
+  if (!stats || stats.by_write_rate.length === 0) {
+    container.innerHTML = '<p style="color: #999;">No concurrent reads and writes sampled</p>';
+    return;
+  }
+
+  const buckets = stats.by_write_rate.map((b, i) => ({
+    ...b,
+    label: `${i + 1}: ${b.min_write_ops_per_s.toFixed(0)}-${b.max_write_ops_per_s.toFixed(0)}/s`
+  }));
+  const chart = Plot.plot({
+    marginLeft: 50,
+    marginBottom: 80,
+    height: 250,
+    x: {label: "Write rate bucket (appends/s)", domain: buckets.map(b => b.label), tickRotate: -30},
+    y: {label: "Read latency (ms)", grid: true},
+    marks: [
+      Plot.barY(buckets, {x: "label", y: "read_p99_ms", fill: "#3b82f6"}),
+      Plot.line(buckets, {x: "label", y: "read_p50_ms", stroke: "#f59e0b", strokeWidth: 2})
+    ]
+  });
+  container.appendChild(chart);
+
+  const correlation = stats.write_rate_read_p99_correlation;
+  const ratio = stats.top_to_bottom_read_p99_ratio;
+  const note = document.createElement('p');
+  note.style.cssText = 'font-size: 12px; color: #666;';
+  note.textContent = `Bars: read p99, line: read p50 over ${stats.windows} windows of ${stats.window_ms} ms. ` +
+    `Correlation: ${correlation != null ? correlation.toFixed(2) : 'N/A'}, ` +
+    `busiest/quietest p99: ${ratio != null ? ratio.toFixed(2) + 'x' : 'N/A'}`;
+  container.appendChild(note);
+}
+
 // Render resource usage for a store
 function renderResourceUsage(store, idx) {
   const container = document.getElementById(`store-${idx}-resources`);
 
   const resources = [
     {label: 'Startup Time', value: store.container.startup_time_s ? `${store.container.startup_time_s.toFixed(2)}s` : 'N/A'},
+    {label: 'Image Pull', value: store.container.image_pull_s != null ? `${store.container.image_pull_s.toFixed(2)}s` : 'N/A'},
     {label: 'Image Size', value: store.container.image_size_mb ? `${store.container.image_size_mb.toFixed(0)} MB` : 'N/A'},
     {label: 'Avg CPU', value: store.container.avg_cpu_percent ? `${store.container.avg_cpu_percent.toFixed(1)}%` : 'N/A'},
     {label: 'Peak CPU', value: store.container.peak_cpu_percent ? `${store.container.peak_cpu_percent.toFixed(1)}%` : 'N/A'},
     {label: 'Avg Memory', value: store.container.avg_memory_mb ? `${store.container.avg_memory_mb.toFixed(0)} MB` : 'N/A'},
-    {label: 'Peak Memory', value: store.container.peak_memory_mb ? `${store.container.peak_memory_mb.toFixed(0)} MB` : 'N/A'}
+    {label: 'Peak Memory', value: store.container.peak_memory_mb ? `${store.container.peak_memory_mb.toFixed(0)} MB` : 'N/A'},
+    {label: 'Data Written', value: store.container.data_written_mb != null ? `${store.container.data_written_mb.toFixed(1)} MB` : 'N/A'},
+    {label: 'Bytes per Event', value: store.container.data_bytes_per_event != null ? store.container.data_bytes_per_event.toFixed(0) : 'N/A'}
   ];
 
   container.innerHTML = `
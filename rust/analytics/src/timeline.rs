@@ -0,0 +1,382 @@
+use crate::session::{SeriesBucket, StoreSummary};
+use anyhow::{Context, Result};
+use plotters::prelude::*;
+use serde::Serialize;
+use std::fmt::Write as _;
+use std::path::Path;
+
+/// Widest sparkline printed to the terminal; longer runs are averaged into this many columns
+const MAX_COLUMNS: usize = 100;
+/// Share of divergent windows from which a difference counts as constant rather than episodic
+const CONSTANT_SHARE: f64 = 0.8;
+const SPARKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+const WIDTH: u32 = 1024;
+const HEIGHT: u32 = 360;
+const RUN_COLORS: [RGBColor; 2] = [RGBColor(31, 119, 180), RGBColor(255, 127, 14)];
+
+/// One window of the two runs, aligned by its offset from the measurement start
+#[derive(Debug, Clone, Serialize)]
+pub struct TimelineWindow {
+    pub t_s: f64,
+    pub a_ops_per_s: f64,
+    pub b_ops_per_s: f64,
+    pub a_p99_ms: Option<f64>,
+    pub b_p99_ms: Option<f64>,
+    /// B's throughput relative to A's (0.1 is 10% more); absent when A has none
+    pub throughput_change: Option<f64>,
+    /// B's p99 relative to A's; absent when either window has no latency
+    pub p99_change: Option<f64>,
+    /// Whether either change is beyond the threshold
+    pub divergent: bool,
+}
+
+/// Consecutive divergent windows
+#[derive(Debug, Clone, Serialize)]
+pub struct DivergentSpan {
+    pub start_s: f64,
+    pub end_s: f64,
+    pub windows: usize,
+    /// Mean of the span's throughput and p99 changes
+    pub throughput_change: Option<f64>,
+    pub p99_change: Option<f64>,
+}
+
+/// Whether the runs differ throughout, in episodes, or not at all
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TimelinePattern {
+    None,
+    Episodic,
+    Constant,
+}
+
+/// Per-window throughput and p99 of two runs side by side, with the windows where they diverge
+#[derive(Debug, Clone, Serialize)]
+pub struct TimelineComparison {
+    pub a: String,
+    pub b: String,
+    pub bucket_s: f64,
+    pub threshold: f64,
+    pub windows: Vec<TimelineWindow>,
+    pub spans: Vec<DivergentSpan>,
+    /// Share of the aligned windows that are divergent
+    pub divergent_share: f64,
+    pub pattern: TimelinePattern,
+}
+
+/// Align the throughput series of two run directories window by window and mark the windows
+/// whose throughput or p99 differ by more than `threshold` (0.2 is 20%)
+pub fn compare_timelines(a: &Path, b: &Path, threshold: f64) -> Result<TimelineComparison> {
+    anyhow::ensure!(threshold > 0.0, "The divergence threshold must be positive");
+    let (a_name, a_summary) = load_run(a)?;
+    let (b_name, b_summary) = load_run(b)?;
+    let (a_name, b_name) = if a_name == b_name {
+        (format!("{}@{}", a_name, session_name(a)), format!("{}@{}", b_name, session_name(b)))
+    } else {
+        (a_name, b_name)
+    };
+    let (a_series, b_series) = (&a_summary.throughput_series, &b_summary.throughput_series);
+    anyhow::ensure!(
+        !a_series.is_empty() && !b_series.is_empty(),
+        "Both runs need a throughput series in summary.json (performance workloads write one)"
+    );
+    let bucket_s = bucket_width(a_series);
+    if let (Some(a_bucket), Some(b_bucket)) = (bucket_width(a_series), bucket_width(b_series)) {
+        anyhow::ensure!(
+            (a_bucket - b_bucket).abs() < 1e-6,
+            "The runs use different series windows ({}s and {}s); rerun with the same series_bucket_ms",
+            a_bucket,
+            b_bucket
+        );
+    }
+
+    let windows: Vec<TimelineWindow> = a_series
+        .iter()
+        .zip(b_series)
+        .map(|(a, b)| {
+            let throughput_change = (a.ops_per_s > 0.0).then(|| b.ops_per_s / a.ops_per_s - 1.0);
+            let p99_change = match (a.latency_p99_ms, b.latency_p99_ms) {
+                (Some(a), Some(b)) if a > 0.0 => Some(b / a - 1.0),
+                _ => None,
+            };
+            let divergent = throughput_change.map_or(b.ops_per_s > 0.0, |c| c.abs() > threshold)
+                || p99_change.is_some_and(|c| c.abs() > threshold);
+            TimelineWindow {
+                t_s: a.t_s,
+                a_ops_per_s: a.ops_per_s,
+                b_ops_per_s: b.ops_per_s,
+                a_p99_ms: a.latency_p99_ms,
+                b_p99_ms: b.latency_p99_ms,
+                throughput_change,
+                p99_change,
+                divergent,
+            }
+        })
+        .collect();
+    let bucket_s = bucket_s.unwrap_or(1.0);
+    let spans = divergent_spans(&windows, bucket_s);
+    let divergent_share = windows.iter().filter(|w| w.divergent).count() as f64 / windows.len() as f64;
+    let pattern = if divergent_share == 0.0 {
+        TimelinePattern::None
+    } else if divergent_share >= CONSTANT_SHARE {
+        TimelinePattern::Constant
+    } else {
+        TimelinePattern::Episodic
+    };
+    Ok(TimelineComparison { a: a_name, b: b_name, bucket_s, threshold, windows, spans, divergent_share, pattern })
+}
+
+fn load_run(dir: &Path) -> Result<(String, StoreSummary)> {
+    let path = dir.join("summary.json");
+    anyhow::ensure!(path.is_file(), "{} is not a run directory (no summary.json)", dir.display());
+    let summary = serde_json::from_reader(
+        std::fs::File::open(&path).with_context(|| format!("Failed to open {}", path.display()))?,
+    )
+    .with_context(|| format!("Failed to parse {}", path.display()))?;
+    let name = dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf());
+    Ok((dir_name(&name), summary))
+}
+
+/// Session of a run directory (`<session>/<workload>/<store>`)
+fn session_name(run_dir: &Path) -> String {
+    let run_dir = run_dir.canonicalize().unwrap_or_else(|_| run_dir.to_path_buf());
+    run_dir.parent().and_then(Path::parent).map(dir_name).unwrap_or_default()
+}
+
+fn dir_name(path: &Path) -> String {
+    path.file_name().and_then(|n| n.to_str()).unwrap_or_default().to_string()
+}
+
+fn bucket_width(series: &[SeriesBucket]) -> Option<f64> {
+    series.get(1).map(|second| second.t_s - series[0].t_s)
+}
+
+fn divergent_spans(windows: &[TimelineWindow], bucket_s: f64) -> Vec<DivergentSpan> {
+    let mean = |values: Vec<f64>| (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64);
+    windows
+        .chunk_by(|a, b| a.divergent == b.divergent)
+        .filter(|run| run[0].divergent)
+        .map(|run| DivergentSpan {
+            start_s: run[0].t_s,
+            end_s: run[run.len() - 1].t_s + bucket_s,
+            windows: run.len(),
+            throughput_change: mean(run.iter().filter_map(|w| w.throughput_change).collect()),
+            p99_change: mean(run.iter().filter_map(|w| w.p99_change).collect()),
+        })
+        .collect()
+}
+
+fn percent(change: Option<f64>) -> String {
+    change.map(|c| format!("{:+.0}%", c * 100.0)).unwrap_or_else(|| "-".to_string())
+}
+
+/// One character per column, scaled to `max`; columns without a value are blank
+fn sparkline(values: &[Option<f64>], max: f64) -> String {
+    values
+        .iter()
+        .map(|v| match v {
+            Some(v) if max > 0.0 => SPARKS[((v / max) * (SPARKS.len() - 1) as f64).round() as usize],
+            Some(_) => SPARKS[0],
+            None => ' ',
+        })
+        .collect()
+}
+
+impl TimelineComparison {
+    fn columns(&self) -> Vec<&[TimelineWindow]> {
+        self.windows.chunks(self.windows.len().div_ceil(MAX_COLUMNS).max(1)).collect()
+    }
+
+    fn summary_line(&self) -> String {
+        let divergent = self.windows.iter().filter(|w| w.divergent).count();
+        let pattern = match self.pattern {
+            TimelinePattern::None => "no divergence",
+            TimelinePattern::Episodic => "episodic",
+            TimelinePattern::Constant => "constant",
+        };
+        format!(
+            "{}/{} windows diverge by more than {:.0}% ({:.0}%): {}",
+            divergent,
+            self.windows.len(),
+            self.threshold * 100.0,
+            self.divergent_share * 100.0,
+            pattern
+        )
+    }
+
+    /// Sparklines of both runs' throughput and p99 for the terminal, with the divergent
+    /// windows marked beneath them
+    pub fn to_text(&self) -> String {
+        let columns = self.columns();
+        let mean = |values: Vec<f64>| (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64);
+        let a_ops: Vec<Option<f64>> = columns.iter().map(|c| mean(c.iter().map(|w| w.a_ops_per_s).collect())).collect();
+        let b_ops: Vec<Option<f64>> = columns.iter().map(|c| mean(c.iter().map(|w| w.b_ops_per_s).collect())).collect();
+        let a_p99: Vec<Option<f64>> = columns.iter().map(|c| mean(c.iter().filter_map(|w| w.a_p99_ms).collect())).collect();
+        let b_p99: Vec<Option<f64>> = columns.iter().map(|c| mean(c.iter().filter_map(|w| w.b_p99_ms).collect())).collect();
+        let max = |a: &[Option<f64>], b: &[Option<f64>]| a.iter().chain(b).flatten().fold(0.0, |m: f64, v| m.max(*v));
+        let ops_max = max(&a_ops, &b_ops);
+        let p99_max = max(&a_p99, &b_p99);
+        let marks: String = columns.iter().map(|c| if c.iter().any(|w| w.divergent) { '^' } else { ' ' }).collect();
+
+        let mut text = String::new();
+        let _ = writeln!(
+            text,
+            "\nTimeline: A = {}, B = {} ({} windows of {}s, {} per column)",
+            self.a,
+            self.b,
+            self.windows.len(),
+            self.bucket_s,
+            columns.first().map_or(1, |c| c.len())
+        );
+        let _ = writeln!(text, "{:<14}{}  max {:.0} ops/s", "ops/s     A", sparkline(&a_ops, ops_max), ops_max);
+        let _ = writeln!(text, "{:<14}{}", "          B", sparkline(&b_ops, ops_max));
+        let _ = writeln!(text, "{:<14}{}  max {:.2} ms", "p99       A", sparkline(&a_p99, p99_max), p99_max);
+        let _ = writeln!(text, "{:<14}{}", "          B", sparkline(&b_p99, p99_max));
+        let _ = writeln!(text, "{:<14}{}", "divergent", marks.trim_end());
+        let _ = writeln!(text, "{}", self.summary_line());
+        for span in &self.spans {
+            let _ = writeln!(
+                text,
+                "  {:>7.1}s - {:>7.1}s  throughput {:>6}  p99 {:>6}",
+                span.start_s,
+                span.end_s,
+                percent(span.throughput_change),
+                percent(span.p99_change)
+            );
+        }
+        text
+    }
+
+    /// Self-contained HTML page overlaying both runs' throughput and p99, with the divergent
+    /// windows shaded
+    pub fn to_html(&self) -> Result<String> {
+        let throughput = self.overlay_svg("Throughput", "ops/s", |w| (Some(w.a_ops_per_s), Some(w.b_ops_per_s)))?;
+        let p99 = self.overlay_svg("p99 latency", "ms", |w| (w.a_p99_ms, w.b_p99_ms))?;
+        let mut rows = String::new();
+        for span in &self.spans {
+            let _ = writeln!(
+                rows,
+                "<tr><td>{:.1}s</td><td>{:.1}s</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                span.start_s,
+                span.end_s,
+                span.windows,
+                percent(span.throughput_change),
+                percent(span.p99_change)
+            );
+        }
+        Ok(format!(
+            r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>Timeline: {a} vs {b}</title>
+<style>
+body {{ font-family: sans-serif; margin: 2em; }}
+table {{ border-collapse: collapse; }}
+td, th {{ border: 1px solid #ccc; padding: 4px 10px; text-align: right; }}
+</style>
+</head>
+<body>
+<h1>Timeline: {a} vs {b}</h1>
+<p>A = {a}, B = {b}. {summary}</p>
+{throughput}
+{p99}
+<table>
+<tr><th>From</th><th>To</th><th>Windows</th><th>Throughput (B vs A)</th><th>p99 (B vs A)</th></tr>
+{rows}</table>
+</body>
+</html>
+"#,
+            a = html_escape(&self.a),
+            b = html_escape(&self.b),
+            summary = html_escape(&self.summary_line()),
+        ))
+    }
+
+    fn overlay_svg(
+        &self,
+        title: &str,
+        y_desc: &str,
+        values: impl Fn(&TimelineWindow) -> (Option<f64>, Option<f64>),
+    ) -> Result<String> {
+        let mut svg = String::new();
+        {
+            let root = SVGBackend::with_string(&mut svg, (WIDTH, HEIGHT)).into_drawing_area();
+            root.fill(&WHITE)?;
+            let x_max = self.windows.last().map_or(0.0, |w| w.t_s + self.bucket_s).max(f64::EPSILON);
+            let y_max = self
+                .windows
+                .iter()
+                .flat_map(|w| {
+                    let (a, b) = values(w);
+                    [a, b]
+                })
+                .flatten()
+                .fold(0.0, f64::max)
+                .max(f64::EPSILON);
+            let mut ctx = ChartBuilder::on(&root)
+                .caption(title, ("sans-serif", 20))
+                .margin(15)
+                .x_label_area_size(40)
+                .y_label_area_size(60)
+                .build_cartesian_2d(0.0..x_max, 0.0..y_max * 1.05)?;
+            ctx.configure_mesh().x_desc("seconds").y_desc(y_desc).draw()?;
+            ctx.draw_series(
+                self.spans
+                    .iter()
+                    .map(|span| Rectangle::new([(span.start_s, 0.0), (span.end_s, y_max * 1.05)], RED.mix(0.15).filled())),
+            )?;
+            for (idx, name) in [&self.a, &self.b].into_iter().enumerate() {
+                let color = RUN_COLORS[idx];
+                let points: Vec<(f64, f64)> = self
+                    .windows
+                    .iter()
+                    .filter_map(|w| {
+                        let (a, b) = values(w);
+                        if idx == 0 { a } else { b }.map(|v| (w.t_s, v))
+                    })
+                    .collect();
+                ctx.draw_series(LineSeries::new(points, color.stroke_width(2)))?
+                    .label(name.as_str())
+                    .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], color.stroke_width(2)));
+            }
+            ctx.configure_series_labels().background_style(WHITE.mix(0.8)).border_style(BLACK).draw()?;
+            root.present()?;
+        }
+        Ok(svg)
+    }
+}
+
+fn html_escape(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(t_s: f64, divergent: bool) -> TimelineWindow {
+        TimelineWindow {
+            t_s,
+            a_ops_per_s: 100.0,
+            b_ops_per_s: if divergent { 50.0 } else { 100.0 },
+            a_p99_ms: Some(1.0),
+            b_p99_ms: Some(1.0),
+            throughput_change: Some(if divergent { -0.5 } else { 0.0 }),
+            p99_change: Some(0.0),
+            divergent,
+        }
+    }
+
+    #[test]
+    fn merges_consecutive_divergent_windows_into_spans() {
+        let windows: Vec<TimelineWindow> =
+            [false, true, true, false, true].iter().enumerate().map(|(i, d)| window(i as f64, *d)).collect();
+        let spans = divergent_spans(&windows, 1.0);
+        assert_eq!(spans.len(), 2);
+        assert_eq!((spans[0].start_s, spans[0].end_s, spans[0].windows), (1.0, 3.0, 2));
+        assert_eq!(spans[0].throughput_change, Some(-0.5));
+        assert_eq!((spans[1].start_s, spans[1].end_s), (4.0, 5.0));
+        assert_eq!(sparkline(&[Some(0.0), Some(50.0), None, Some(100.0)], 100.0), "▁▅ █");
+    }
+}
@@ -0,0 +1,232 @@
+use crate::scanner::SessionScanner;
+use crate::session::StoreSummary;
+use anyhow::{Context, Result};
+use chrono::NaiveDateTime;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Session IDs are the session start time
+const SESSION_ID_FORMAT: &str = "%Y-%m-%dT%H-%M-%S";
+
+/// One run of a (store, workload) series
+#[derive(Debug, Clone, Serialize)]
+pub struct TrendPoint {
+    pub session_id: String,
+    /// Store commit the run measured (runs with `--store-rev`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub store_rev: Option<String>,
+    pub throughput_eps: f64,
+    pub p99_ms: f64,
+}
+
+/// Trend of one metric over a series
+#[derive(Debug, Clone, Serialize)]
+pub struct MetricTrend {
+    /// Least-squares slope over all runs, per day
+    pub slope_per_day: f64,
+    /// Mean and standard deviation of the runs before the latest
+    pub history_mean: f64,
+    pub history_std_dev: f64,
+    pub latest: f64,
+    /// Deviation of the latest run from history in standard deviations (None without at least
+    /// two earlier runs that vary)
+    pub z_score: Option<f64>,
+}
+
+/// Runs of one store on one workload, oldest first
+#[derive(Debug, Clone, Serialize)]
+pub struct SeriesTrend {
+    pub store: String,
+    pub workload: String,
+    pub points: Vec<TrendPoint>,
+    pub throughput_eps: MetricTrend,
+    pub p99_ms: MetricTrend,
+}
+
+/// Latest run of a series outside the allowed deviation from its history
+#[derive(Debug, Clone, Serialize)]
+pub struct TrendAlert {
+    pub store: String,
+    pub workload: String,
+    pub metric: &'static str,
+    pub session_id: String,
+    /// Store commit of the latest run, and of the run before it, to bisect a regression
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub store_rev: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub previous_store_rev: Option<String>,
+    pub latest: f64,
+    pub history_mean: f64,
+    pub z_score: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TrendReport {
+    pub sigma: f64,
+    pub series: Vec<SeriesTrend>,
+    pub alerts: Vec<TrendAlert>,
+}
+
+/// Group every run under `sessions_root` by (store, workload), fit throughput and p99 trend
+/// lines and flag series whose latest run deviates from the earlier runs by more than `sigma`
+/// standard deviations
+pub fn compute_trends(sessions_root: &Path, sigma: f64) -> Result<TrendReport> {
+    let mut series: BTreeMap<(String, String), Vec<(NaiveDateTime, TrendPoint)>> = BTreeMap::new();
+    for session_dir in SessionScanner::new(sessions_root).find_sessions()? {
+        let session_id = dir_name(&session_dir);
+        let Ok(started) = NaiveDateTime::parse_from_str(&session_id, SESSION_ID_FORMAT) else {
+            continue;
+        };
+        for workload_dir in subdirs(&session_dir)? {
+            for store_dir in subdirs(&workload_dir)? {
+                let summary_path = store_dir.join("summary.json");
+                if !summary_path.exists() {
+                    continue;
+                }
+                let summary: StoreSummary = serde_json::from_reader(
+                    std::fs::File::open(&summary_path)
+                        .with_context(|| format!("Failed to open {}", summary_path.display()))?,
+                )
+                .with_context(|| format!("Failed to parse {}", summary_path.display()))?;
+                series
+                    .entry((dir_name(&store_dir), dir_name(&workload_dir)))
+                    .or_default()
+                    .push((
+                        started,
+                        TrendPoint {
+                            session_id: session_id.clone(),
+                            store_rev: summary.store_rev,
+                            throughput_eps: summary.throughput_eps,
+                            p99_ms: summary.latency.p99_ms,
+                        },
+                    ));
+            }
+        }
+    }
+
+    let mut trends = Vec::new();
+    let mut alerts = Vec::new();
+    for ((store, workload), mut runs) in series {
+        runs.sort_by_key(|(started, _)| *started);
+        let first = runs[0].0;
+        let days: Vec<f64> = runs
+            .iter()
+            .map(|(started, _)| (*started - first).num_seconds() as f64 / 86_400.0)
+            .collect();
+        let points: Vec<TrendPoint> = runs.into_iter().map(|(_, point)| point).collect();
+        let throughput: Vec<f64> = points.iter().map(|p| p.throughput_eps).collect();
+        let p99: Vec<f64> = points.iter().map(|p| p.p99_ms).collect();
+        let trend = SeriesTrend {
+            throughput_eps: metric_trend(&days, &throughput),
+            p99_ms: metric_trend(&days, &p99),
+            store,
+            workload,
+            points,
+        };
+        for (metric, values) in [("throughput_eps", &trend.throughput_eps), ("p99_ms", &trend.p99_ms)] {
+            if let Some(z_score) = values.z_score.filter(|z| z.abs() > sigma) {
+                let latest = trend.points.last();
+                let previous = trend.points.len().checked_sub(2).map(|i| &trend.points[i]);
+                alerts.push(TrendAlert {
+                    store: trend.store.clone(),
+                    workload: trend.workload.clone(),
+                    metric,
+                    session_id: latest.map(|p| p.session_id.clone()).unwrap_or_default(),
+                    store_rev: latest.and_then(|p| p.store_rev.clone()),
+                    previous_store_rev: previous.and_then(|p| p.store_rev.clone()),
+                    latest: values.latest,
+                    history_mean: values.history_mean,
+                    z_score,
+                });
+            }
+        }
+        trends.push(trend);
+    }
+
+    Ok(TrendReport {
+        sigma,
+        series: trends,
+        alerts,
+    })
+}
+
+fn metric_trend(days: &[f64], values: &[f64]) -> MetricTrend {
+    let latest = values.last().copied().unwrap_or(0.0);
+    let history = &values[..values.len().saturating_sub(1)];
+    let (history_mean, history_std_dev) = mean_std_dev(history);
+    let z_score = (history.len() >= 2 && history_std_dev > 0.0)
+        .then(|| (latest - history_mean) / history_std_dev);
+    MetricTrend {
+        slope_per_day: slope(days, values),
+        history_mean,
+        history_std_dev,
+        latest,
+        z_score,
+    }
+}
+
+/// Sample mean and standard deviation
+fn mean_std_dev(values: &[f64]) -> (f64, f64) {
+    if values.is_empty() {
+        return (0.0, 0.0);
+    }
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    if values.len() < 2 {
+        return (mean, 0.0);
+    }
+    let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0);
+    (mean, variance.sqrt())
+}
+
+/// Least-squares slope of `ys` over `xs` (0 when the runs all happened at once)
+fn slope(xs: &[f64], ys: &[f64]) -> f64 {
+    let n = xs.len() as f64;
+    let mean_x = xs.iter().sum::<f64>() / n;
+    let mean_y = ys.iter().sum::<f64>() / n;
+    let (mut cov, mut var_x) = (0.0, 0.0);
+    for (x, y) in xs.iter().zip(ys) {
+        cov += (x - mean_x) * (y - mean_y);
+        var_x += (x - mean_x).powi(2);
+    }
+    if var_x == 0.0 {
+        0.0
+    } else {
+        cov / var_x
+    }
+}
+
+fn subdirs(dir: &Path) -> Result<Vec<std::path::PathBuf>> {
+    let mut dirs = Vec::new();
+    for entry in std::fs::read_dir(dir).with_context(|| format!("Failed to read {}", dir.display()))? {
+        let path = entry?.path();
+        if path.is_dir() {
+            dirs.push(path);
+        }
+    }
+    Ok(dirs)
+}
+
+fn dir_name(path: &Path) -> String {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or_default()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flags_latest_run_far_from_history() {
+        let days = [0.0, 1.0, 2.0, 3.0, 4.0];
+        let trend = metric_trend(&days, &[100.0, 102.0, 98.0, 100.0, 60.0]);
+        assert!(trend.z_score.unwrap() < -3.0);
+        assert!(trend.slope_per_day < 0.0);
+
+        let steady = metric_trend(&days, &[100.0, 102.0, 98.0, 100.0, 101.0]);
+        assert!(steady.z_score.unwrap().abs() < 1.0);
+    }
+}
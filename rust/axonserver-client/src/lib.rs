@@ -13,11 +13,82 @@ pub mod proto {
 
 use anyhow::Result;
 use proto::dcb::{
-    dcb_event_store_client::DcbEventStoreClient, AppendEventsRequest, Event, GetHeadRequest,
-    SourceEventsRequest, SourceEventsResponse, Tag, TaggedEvent,
+    dcb_event_store_client::DcbEventStoreClient, AppendEventsRequest, AppendEventsResponse,
+    ConsistencyCondition, Criterion, Event, GetHeadRequest, SourceEventsRequest,
+    SourceEventsResponse, Tag, TaggedEvent,
 };
 use tokio_stream::once;
-use tonic::transport::Channel;
+use tonic::metadata::MetadataMap;
+use std::time::Duration;
+use tonic::transport::{Channel, Endpoint};
+
+/// Response metadata in which a server may report its processing time, as in the HTTP
+/// `Server-Timing` header (`total;dur=<milliseconds>`)
+pub const SERVER_TIMING: &str = "server-timing";
+
+/// A response value with the server's `server-timing` metadata, if it sent any.
+pub struct Timed<T> {
+    pub value: T,
+    pub server_timing: Option<String>,
+}
+
+fn server_timing(metadata: &MetadataMap) -> Option<String> {
+    metadata
+        .get(SERVER_TIMING)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string)
+}
+
+/// HTTP/2 and TCP settings of the client's channel. Unset fields keep tonic's defaults.
+#[derive(Debug, Clone, Default)]
+pub struct ChannelConfig {
+    /// Initial HTTP/2 flow-control window of each stream, in bytes
+    pub initial_stream_window_size: Option<u32>,
+    /// Initial HTTP/2 flow-control window of the connection, in bytes
+    pub initial_connection_window_size: Option<u32>,
+    /// Let HTTP/2 grow the windows to the measured bandwidth-delay product
+    pub http2_adaptive_window: Option<bool>,
+    /// Most requests in flight on the channel at once; further ones wait for a slot
+    pub concurrency_limit: Option<usize>,
+    /// Interval of HTTP/2 keep-alive pings (none when unset)
+    pub keep_alive_interval: Option<Duration>,
+    /// How long a keep-alive ping may go unanswered before the connection is closed
+    pub keep_alive_timeout: Option<Duration>,
+    /// Send keep-alive pings while no request is in flight
+    pub keep_alive_while_idle: Option<bool>,
+    pub tcp_nodelay: Option<bool>,
+}
+
+impl ChannelConfig {
+    fn endpoint(&self, uri: String) -> Result<Endpoint> {
+        let mut endpoint = Endpoint::from_shared(uri)?;
+        if let Some(size) = self.initial_stream_window_size {
+            endpoint = endpoint.initial_stream_window_size(size);
+        }
+        if let Some(size) = self.initial_connection_window_size {
+            endpoint = endpoint.initial_connection_window_size(size);
+        }
+        if let Some(enabled) = self.http2_adaptive_window {
+            endpoint = endpoint.http2_adaptive_window(enabled);
+        }
+        if let Some(limit) = self.concurrency_limit {
+            endpoint = endpoint.concurrency_limit(limit);
+        }
+        if let Some(interval) = self.keep_alive_interval {
+            endpoint = endpoint.http2_keep_alive_interval(interval);
+        }
+        if let Some(timeout) = self.keep_alive_timeout {
+            endpoint = endpoint.keep_alive_timeout(timeout);
+        }
+        if let Some(enabled) = self.keep_alive_while_idle {
+            endpoint = endpoint.keep_alive_while_idle(enabled);
+        }
+        if let Some(enabled) = self.tcp_nodelay {
+            endpoint = endpoint.tcp_nodelay(enabled);
+        }
+        Ok(endpoint)
+    }
+}
 
 /// Minimal Axon Server DCB client.
 #[derive(Clone)]
@@ -32,14 +103,52 @@ impl AxonServerClient {
         Ok(Self { inner })
     }
 
+    /// Connect with the given channel settings.
+    pub async fn connect_with(uri: String, config: &ChannelConfig) -> Result<Self> {
+        let channel = config.endpoint(uri)?.connect().await?;
+        Ok(Self {
+            inner: DcbEventStoreClient::new(channel),
+        })
+    }
+
     /// Append a batch of tagged events unconditionally.
     pub async fn append(&mut self, events: Vec<TaggedEvent>) -> Result<i64> {
+        Ok(self.append_timed(events).await?.value)
+    }
+
+    /// Append a batch of tagged events unconditionally, keeping the server's timing metadata.
+    pub async fn append_timed(&mut self, events: Vec<TaggedEvent>) -> Result<Timed<i64>> {
         let req = AppendEventsRequest {
             condition: None,
             event: events,
         };
-        let response = self.inner.append(once(req)).await?.into_inner();
-        Ok(response.sequence_of_the_first_event)
+        let response = self.inner.append(once(req)).await?;
+        Ok(Timed {
+            server_timing: server_timing(response.metadata()),
+            value: response.into_inner().sequence_of_the_first_event,
+        })
+    }
+
+    /// Append a batch of tagged events only if no events matching `criteria` exist at or after
+    /// `consistency_marker`. Returns `None` when the condition was not met.
+    pub async fn append_conditional(
+        &mut self,
+        events: Vec<TaggedEvent>,
+        consistency_marker: i64,
+        criteria: Vec<Criterion>,
+    ) -> Result<Option<AppendEventsResponse>> {
+        let req = AppendEventsRequest {
+            condition: Some(ConsistencyCondition {
+                consistency_marker,
+                criterion: criteria,
+            }),
+            event: events,
+        };
+        match self.inner.append(once(req)).await {
+            Ok(response) => Ok(Some(response.into_inner())),
+            Err(status) if status.code() == tonic::Code::FailedPrecondition => Ok(None),
+            Err(status) => Err(status.into()),
+        }
     }
 
     /// Convenience: append a single event with tags derived from string labels.
@@ -77,16 +186,57 @@ impl AxonServerClient {
         from_sequence: i64,
         criteria: Vec<proto::dcb::Criterion>,
     ) -> Result<Vec<SourceEventsResponse>> {
+        Ok(self.source_timed(from_sequence, criteria).await?.value)
+    }
+
+    /// Source events like `source`, keeping the server's timing metadata (from the trailers,
+    /// or else the headers, of the stream).
+    pub async fn source_timed(
+        &mut self,
+        from_sequence: i64,
+        criteria: Vec<proto::dcb::Criterion>,
+    ) -> Result<Timed<Vec<SourceEventsResponse>>> {
         let req = SourceEventsRequest {
             from_sequence,
             criterion: criteria,
         };
-        let mut stream = self.inner.source(req).await?.into_inner();
+        let response = self.inner.source(req).await?;
+        let headers = server_timing(response.metadata());
+        let mut stream = response.into_inner();
         let mut results = Vec::new();
         while let Some(resp) = stream.message().await? {
             results.push(resp);
         }
-        Ok(results)
+        let trailers = stream.trailers().await?.as_ref().and_then(server_timing);
+        Ok(Timed {
+            value: results,
+            server_timing: trailers.or(headers),
+        })
+    }
+
+    /// Source up to `limit` events matching criteria from a given sequence, cancelling the
+    /// stream once they arrived. Consistency markers are dropped.
+    pub async fn source_limited(
+        &mut self,
+        from_sequence: i64,
+        criteria: Vec<proto::dcb::Criterion>,
+        limit: usize,
+    ) -> Result<Vec<proto::dcb::SequencedEvent>> {
+        let req = SourceEventsRequest {
+            from_sequence,
+            criterion: criteria,
+        };
+        let mut stream = self.inner.source(req).await?.into_inner();
+        let mut events = Vec::new();
+        while events.len() < limit {
+            let Some(resp) = stream.message().await? else {
+                break;
+            };
+            if let Some(proto::dcb::source_events_response::Result::Event(event)) = resp.result {
+                events.push(event);
+            }
+        }
+        Ok(events)
     }
 
     /// Get the current head sequence of the event store.
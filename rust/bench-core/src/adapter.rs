@@ -1,6 +1,8 @@
 use async_trait::async_trait;
+use bytes::Bytes;
+use futures::stream::BoxStream;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -12,12 +14,41 @@ pub struct ConnectionParams {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EventData {
-    pub payload: Vec<u8>,
+    /// Shared, so appending a pre-generated payload does not copy it
+    pub payload: Bytes,
     pub event_type: String,
+    /// The first tag is the stream the event is appended to; any further tags are kept with
+    /// the event and read back in the same order
     #[serde(default)]
     pub tags: Vec<String>,
 }
 
+impl EventData {
+    /// Tags after the stream tag
+    pub fn extra_tags(&self) -> &[String] {
+        self.tags.get(1..).unwrap_or_default()
+    }
+}
+
+/// Tags after the stream tag as a JSON array, for stores that keep them in a metadata field;
+/// `None` when there are none, so events without them carry no extra metadata
+pub fn encode_extra_tags(event: &EventData) -> Option<String> {
+    let extra = event.extra_tags();
+    (!extra.is_empty()).then(|| serde_json::to_string(extra).expect("strings serialize"))
+}
+
+/// Tags of an event read from `stream`, with the further tags decoded from
+/// [`encode_extra_tags`]
+pub fn decode_tags(stream: &str, encoded: Option<&str>) -> anyhow::Result<Vec<String>> {
+    let mut tags = vec![stream.to_string()];
+    if let Some(encoded) = encoded.filter(|e| !e.is_empty()) {
+        let extra: Vec<String> = serde_json::from_str(encoded)
+            .map_err(|e| anyhow::anyhow!("Invalid event tags {:?}: {}", encoded, e))?;
+        tags.extend(extra);
+    }
+    Ok(tags)
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ReadRequest {
     pub stream: String,
@@ -25,22 +56,230 @@ pub struct ReadRequest {
     pub from_offset: Option<u64>,
     #[serde(default)]
     pub limit: Option<u64>,
+    #[serde(default)]
+    pub consistency: ReadConsistency,
+}
+
+/// How up to date a read must be, normalized across stores. Each adapter maps it onto its
+/// store's read options and ignores what its store doesn't distinguish (a single node serves
+/// every read consistently).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReadConsistency {
+    /// Whatever the store and its client do by default
+    #[default]
+    Default,
+    /// Reflect every acknowledged append, e.g. by requiring the leader to serve the read
+    /// (KurrentDB `requires_leader`)
+    Strong,
+    /// May be served by any node and lag behind acknowledged appends
+    Eventual,
+}
+
+impl ReadConsistency {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Default => "default",
+            Self::Strong => "strong",
+            Self::Eventual => "eventual",
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ReadEvent {
     pub offset: u64,
     pub event_type: String,
-    pub payload: Vec<u8>,
+    pub payload: Bytes,
     pub timestamp_ms: u64,
+    /// Tags the event was appended with, the stream tag first
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+/// Optimistic concurrency check for a conditional append
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AppendCondition {
+    /// Fail if the stream already contains events
+    NoStream,
+    /// Fail if events were appended to the stream after this position. The position is one
+    /// previously returned by the same adapter (a stream revision or a global sequence number,
+    /// depending on the store).
+    After(u64),
+}
+
+/// Result of a conditional append
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AppendOutcome {
+    /// Events were written; `position` can be used as the next `AppendCondition::After`
+    Appended { position: u64 },
+    /// The condition did not hold; `current` is the position to retry with, if the store reported one
+    Conflict { current: Option<u64> },
+}
+
+/// Retention settings of one stream, for stores that expire or truncate old events
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RetentionPolicy {
+    /// Keep only the stream's last `max_count` events
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_count: Option<u64>,
+    /// Drop events older than this many seconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_age_s: Option<u64>,
+    /// Drop the events before this stream offset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub truncate_before: Option<u64>,
 }
 
+/// Store maintenance command, for measuring its impact on a workload
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AdminOp {
+    /// Reclaim the space of deleted and expired events (KurrentDB scavenge, VACUUM)
+    Scavenge,
+    /// Merge index files (KurrentDB index merge)
+    MergeIndexes,
+    /// Rebuild the indexes from the stored events (REINDEX)
+    RebuildIndexes,
+    /// Flush the write-ahead log into the data files (CHECKPOINT)
+    Checkpoint,
+}
+
+impl AdminOp {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Scavenge => "scavenge",
+            Self::MergeIndexes => "merge_indexes",
+            Self::RebuildIndexes => "rebuild_indexes",
+            Self::Checkpoint => "checkpoint",
+        }
+    }
+}
+
+/// Events delivered by a subscription, in stream order
+pub type EventStream = BoxStream<'static, anyhow::Result<ReadEvent>>;
+
+/// Events of one read, yielded as they arrive from the store
+pub type ReadStream<'a> = BoxStream<'a, anyhow::Result<ReadEvent>>;
+
+/// Events per call when `read_stream` and `read_all_stream` page through `read` and `read_all`
+pub const READ_STREAM_PAGE: u64 = 1000;
+
 /// Lightweight adapter - just wraps a client connection
 /// Multiple instances can be created to connect to the same server/container
 #[async_trait]
 pub trait EventStoreAdapter: Send + Sync {
     async fn append(&self, events: Vec<EventData>) -> anyhow::Result<()>;
+
+    /// Read events of one stream. A stream that was never written reads as an empty vec, not
+    /// an error (checked before each run, see `conformance`).
     async fn read(&self, req: ReadRequest) -> anyhow::Result<Vec<ReadEvent>>;
+
+    /// Append events to a single stream (the first tag of the first event) only if `condition` holds
+    async fn append_conditional(
+        &self,
+        _events: Vec<EventData>,
+        _condition: AppendCondition,
+    ) -> anyhow::Result<AppendOutcome> {
+        anyhow::bail!("Conditional appends are not supported by this adapter")
+    }
+
+    /// Read up to `limit` events of the store's global log, across all streams, in log order:
+    /// those after position `after` (from the start if `None`). Each event's `offset` is its
+    /// position in the log, which continues the read when passed back as `after`. An empty vec
+    /// means the end of the log.
+    async fn read_all(&self, _after: Option<u64>, _limit: Option<u64>) -> anyhow::Result<Vec<ReadEvent>> {
+        anyhow::bail!("Reading the global log is not supported by this adapter")
+    }
+
+    /// Read events of one stream like `read`, yielding them as they arrive instead of holding
+    /// them all in memory. Defaults to reading pages of `READ_STREAM_PAGE` events, a short page
+    /// ending the stream; adapters whose clients stream natively override it.
+    fn read_stream(&self, req: ReadRequest) -> ReadStream<'_> {
+        let state = (req, VecDeque::new(), false);
+        Box::pin(futures::stream::unfold(state, move |(mut req, mut fetched, mut done)| async move {
+            loop {
+                if let Some(event) = fetched.pop_front() {
+                    return Some((Ok(event), (req, fetched, done)));
+                }
+                if done || req.limit == Some(0) {
+                    return None;
+                }
+                let page = req.limit.map_or(READ_STREAM_PAGE, |limit| limit.min(READ_STREAM_PAGE));
+                match self.read(ReadRequest { limit: Some(page), ..req.clone() }).await {
+                    Ok(events) => {
+                        done = (events.len() as u64) < page;
+                        if let Some(last) = events.last() {
+                            req.from_offset = Some(last.offset + 1);
+                        }
+                        req.limit = req.limit.map(|limit| limit.saturating_sub(events.len() as u64));
+                        fetched.extend(events);
+                    }
+                    Err(e) => return Some((Err(e), (req, fetched, true))),
+                }
+            }
+        }))
+    }
+
+    /// Read the global log after position `after` like `read_all`, to its end, yielding events
+    /// as they arrive. Defaults to reading pages of `READ_STREAM_PAGE` events.
+    fn read_all_stream(&self, after: Option<u64>) -> ReadStream<'_> {
+        let state = (after, VecDeque::new(), false);
+        Box::pin(futures::stream::unfold(state, move |(mut after, mut fetched, done)| async move {
+            loop {
+                if let Some(event) = fetched.pop_front() {
+                    return Some((Ok(event), (after, fetched, done)));
+                }
+                if done {
+                    return None;
+                }
+                match self.read_all(after, Some(READ_STREAM_PAGE)).await {
+                    Ok(events) if events.is_empty() => return None,
+                    Ok(events) => {
+                        after = events.last().map(|last| last.offset);
+                        fetched.extend(events);
+                    }
+                    Err(e) => return Some((Err(e), (after, fetched, true))),
+                }
+            }
+        }))
+    }
+
+    /// List up to `limit` stream names starting with `prefix`, in store order
+    async fn list_streams(&self, _prefix: &str, _limit: Option<u64>) -> anyhow::Result<Vec<String>> {
+        anyhow::bail!("Listing streams is not supported by this adapter")
+    }
+
+    /// Subscribe to one stream from `from_offset` (inclusive; the start if `None`): the stream
+    /// yields the events already stored, then each event appended later, for as long as it is
+    /// polled. Stores without push delivery may poll, as their consumers would.
+    async fn subscribe(&self, _stream: &str, _from_offset: Option<u64>) -> anyhow::Result<EventStream> {
+        anyhow::bail!("Subscriptions are not supported by this adapter")
+    }
+
+    /// Set the retention policy of one stream. Events it excludes stop being readable and
+    /// their space is reclaimed by the next `AdminOp::Scavenge`.
+    async fn set_retention(&self, _stream: &str, _policy: &RetentionPolicy) -> anyhow::Result<()> {
+        anyhow::bail!("Retention policies are not supported by this adapter")
+    }
+
+    /// Run a store maintenance command and return once it has completed (or, where the store
+    /// gives no way to follow it, once the store accepted it)
+    async fn admin(&self, op: AdminOp) -> anyhow::Result<()> {
+        anyhow::bail!("Admin operation {} is not supported by this adapter", op.name())
+    }
+
+    /// Check that the store answers, for health polling during runs. Defaults to reading a
+    /// stream that is never written.
+    async fn ping(&self) -> anyhow::Result<()> {
+        let req = ReadRequest {
+            stream: "es-bench-ping".to_string(),
+            from_offset: None,
+            limit: Some(1),
+            consistency: Default::default(),
+        };
+        self.read(req).await.map(|_| ())
+    }
 }
 
 #[async_trait]
@@ -57,6 +296,20 @@ pub trait StoreManager: Send + Sync {
     /// Get the container ID for stats collection (if applicable)
     fn container_id(&self) -> Option<String>;
 
+    /// All containers to collect stats for, as (role, container ID) pairs. Stores running more
+    /// than one container (cluster nodes, sidecars) override this; the default is the single
+    /// `container_id` with role "store".
+    fn containers(&self) -> Vec<(String, String)> {
+        self.container_id()
+            .map(|id| vec![("store".to_string(), id)])
+            .unwrap_or_default()
+    }
+
+    /// Address clients connect to once started (e.g. `http://localhost:8024`), for timing the
+    /// DNS and TCP parts of connection setup; `None` for in-process stores
+    fn endpoint(&self) -> Option<String> {
+        None
+    }
 
     /// Store name (adapter name)
     fn name(&self) -> &'static str;
@@ -118,4 +371,22 @@ pub trait StoreManagerFactory: Send + Sync {
 
     /// Create a store manager instance with given (internal) connection params or defaults
     fn create_store_manager(&self, data_dir: Option<String>) -> anyhow::Result<Box<dyn StoreManager>>;
+
+    /// Create a store manager configured by the store's `store_options` entry in the workload
+    /// config. Stores that take no options reject any.
+    fn create_store_manager_with_options(
+        &self,
+        data_dir: Option<String>,
+        options: &HashMap<String, String>,
+    ) -> anyhow::Result<Box<dyn StoreManager>> {
+        if !options.is_empty() {
+            anyhow::bail!("store '{}' does not take any store_options", self.name());
+        }
+        self.create_store_manager(data_dir)
+    }
+
+    /// Most client connections the store accepts by default, if it limits them
+    fn max_connections(&self) -> Option<usize> {
+        None
+    }
 }
@@ -0,0 +1,110 @@
+use crate::metrics::RunMetrics;
+use serde::{Deserialize, Serialize};
+
+/// Bounds a run of the workload is expected to stay within (`assert` in a workload config),
+/// so a workload can serve as a pass/fail performance test
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AssertConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_throughput_eps: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_p50_ms: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_p95_ms: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_p99_ms: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_p999_ms: Option<f64>,
+    /// Failed operations as a share of all operations (0.01 is 1%)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_error_rate: Option<f64>,
+}
+
+/// Outcome of one bound of [`AssertConfig`] for a run, listed in summary.json
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssertionResult {
+    /// The bound's key, e.g. `max_p99_ms`
+    pub assertion: String,
+    pub limit: f64,
+    /// `None` if the run didn't measure it (no operations), which fails the assertion
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub actual: Option<f64>,
+    pub passed: bool,
+}
+
+impl AssertConfig {
+    pub fn validate(&self) -> anyhow::Result<()> {
+        let bounds = [
+            ("min_throughput_eps", self.min_throughput_eps),
+            ("max_p50_ms", self.max_p50_ms),
+            ("max_p95_ms", self.max_p95_ms),
+            ("max_p99_ms", self.max_p99_ms),
+            ("max_p999_ms", self.max_p999_ms),
+            ("max_error_rate", self.max_error_rate),
+        ];
+        for (name, limit) in bounds.into_iter().filter_map(|(name, limit)| Some((name, limit?))) {
+            if limit.is_nan() || limit < 0.0 {
+                anyhow::bail!("assert.{} must be >= 0, got {}", name, limit);
+            }
+        }
+        if self.max_error_rate.is_some_and(|rate| rate > 1.0) {
+            anyhow::bail!("assert.max_error_rate is a share of operations and must be <= 1");
+        }
+        Ok(())
+    }
+
+    /// Check every configured bound against a finished run
+    pub fn check(&self, metrics: &RunMetrics) -> Vec<AssertionResult> {
+        let summary = &metrics.summary;
+        let latency = |p: f64| (!metrics.op_latency.is_empty()).then_some(p);
+        // (bound, limit, measured value, whether the limit is a minimum)
+        [
+            ("min_throughput_eps", self.min_throughput_eps, Some(summary.throughput_eps), true),
+            ("max_p50_ms", self.max_p50_ms, latency(summary.latency.p50_ms), false),
+            ("max_p95_ms", self.max_p95_ms, latency(summary.latency.p95_ms), false),
+            ("max_p99_ms", self.max_p99_ms, latency(summary.latency.p99_ms), false),
+            ("max_p999_ms", self.max_p999_ms, latency(summary.latency.p999_ms), false),
+            ("max_error_rate", self.max_error_rate, error_rate(metrics), false),
+        ]
+        .into_iter()
+        .filter_map(|(name, limit, actual, minimum)| {
+            let limit = limit?;
+            Some(AssertionResult {
+                assertion: name.to_string(),
+                limit,
+                actual,
+                passed: actual.is_some_and(|actual| if minimum { actual >= limit } else { actual <= limit }),
+            })
+        })
+        .collect()
+    }
+}
+
+/// Failed operations (the `*_errors` counters of the workload mode) as a share of all
+/// operations; `None` without any operations
+pub fn error_rate(metrics: &RunMetrics) -> Option<f64> {
+    let errors: u64 = metrics
+        .summary
+        .extra
+        .iter()
+        .filter(|(name, _)| name.ends_with("_errors"))
+        .filter_map(|(_, value)| value.as_u64())
+        .sum();
+    let ok: u64 = metrics.op_latency.values().map(|rec| rec.hist.len()).sum();
+    (ok + errors > 0).then(|| errors as f64 / (ok + errors) as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_rejects_out_of_range_bounds() {
+        let config: AssertConfig = serde_yaml::from_str("{min_throughput_eps: 5000, max_p99_ms: 50, max_error_rate: 0.01}").unwrap();
+        assert!(config.validate().is_ok());
+        assert!(AssertConfig { max_p99_ms: Some(-1.0), ..Default::default() }.validate().is_err());
+        assert!(AssertConfig { max_error_rate: Some(1.5), ..Default::default() }.validate().is_err());
+        assert!(serde_yaml::from_str::<AssertConfig>("{max_p90_ms: 10}").is_err());
+    }
+}
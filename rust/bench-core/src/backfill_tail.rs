@@ -0,0 +1,249 @@
+use crate::adapter::{EventData, StoreManager};
+use crate::metrics::{LatencyRecorder, LatencyStats};
+use crate::propagation::sequenced_event;
+use crate::rate_limit::OpenLoopSchedule;
+use crate::workflow_support::{next_sequence, prepopulate_stream, run_on_started_store};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::Instant;
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+
+/// Time on either side of the switchover whose deliveries describe it; lag counts as steady
+/// state from this long after the switchover
+const SWITCHOVER_WINDOW: Duration = Duration::from_secs(1);
+
+/// Settings for a consumer that catches up on a backlog while live appends keep coming
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackfillTailConfig {
+    /// Events stored before the consumer starts, the backlog it has to catch up on
+    pub backlog_events: u64,
+    /// Appends per second from when the consumer starts
+    pub live_rate_eps: f64,
+    /// Seconds of live appends; the consumer has to catch up within them to reach steady state
+    pub live_duration_s: u64,
+    pub event_size_bytes: usize,
+}
+
+/// Appended and delivered events one second into the consumer's run
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BackfillSample {
+    pub t_s: u64,
+    /// Backlog plus live appends acknowledged so far
+    pub appended: u64,
+    pub delivered: u64,
+    /// Appended events the consumer has yet to deliver
+    pub behind: u64,
+}
+
+/// Outcome of a backfill-then-tail study
+#[derive(Debug, Clone, Serialize)]
+pub struct BackfillTailResult {
+    pub store: String,
+    pub config: BackfillTailConfig,
+    pub live_events: u64,
+    /// From subscribing until the last backlog event arrived
+    pub backlog_replay_s: f64,
+    pub backlog_replay_eps: f64,
+    /// From subscribing until the consumer had delivered every event appended so far (the
+    /// switchover from catching up to tailing)
+    pub catch_up_s: f64,
+    /// Live events appended while catching up and delivered before the switchover
+    pub live_events_during_catch_up: u64,
+    /// Whether the switchover came before the live appends ended; if not, the consumer never
+    /// kept up with them and there is no steady state
+    pub kept_up: bool,
+    /// Longest pause between deliveries within a second of the switchover
+    pub switchover_max_gap_ms: f64,
+    /// Highest lag of the live events delivered within a second after the switchover
+    pub switchover_lag_max_ms: f64,
+    /// Events delivered again, and events delivered after a later one
+    pub duplicates: u64,
+    pub out_of_order: u64,
+    /// From when each live append was due until the consumer delivered it, for the events
+    /// delivered from a second after the switchover on
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub steady_lag: Option<LatencyStats>,
+    pub steady_lag_max_ms: f64,
+    /// Once per second from subscribing
+    pub timeline: Vec<BackfillSample>,
+}
+
+/// Start the store, prepopulate a stream with a backlog, then subscribe to it from the start
+/// while appending to it at a steady rate, and record how the consumer catches up, switches
+/// over to the live appends and keeps up with them
+pub async fn execute_backfill_tail(
+    store: Box<dyn StoreManager>,
+    config: &BackfillTailConfig,
+    cancel_token: CancellationToken,
+) -> Result<BackfillTailResult> {
+    if config.backlog_events == 0 || config.live_duration_s == 0 {
+        anyhow::bail!("Backfill-then-tail requires backlog events and a live duration > 0");
+    }
+    if !(config.live_rate_eps.is_finite() && config.live_rate_eps > 0.0) {
+        anyhow::bail!("Backfill-then-tail requires a live rate > 0");
+    }
+
+    run_on_started_store(store, &cancel_token, "backfill-then-tail run", async |store| {
+        backfill_then_tail(store, config).await
+    })
+    .await
+}
+
+async fn backfill_then_tail(store: &dyn StoreManager, config: &BackfillTailConfig) -> Result<BackfillTailResult> {
+    let writer = store.create_adapter()?;
+    let subscriber = store.create_adapter()?;
+    let stream = format!("backfill-{}", Uuid::new_v4());
+    let backlog = config.backlog_events;
+    let live_events = ((config.live_rate_eps * config.live_duration_s as f64).round() as u64).max(1);
+    let total = backlog + live_events;
+    let event = |seq: u64| -> EventData { sequenced_event(&stream, "backfill", seq, config.event_size_bytes) };
+
+    // Fail fast for stores without subscriptions instead of after prepopulating
+    drop(subscriber.subscribe(&stream, None).await?);
+
+    println!("Prepopulating a backlog of {} events...", backlog);
+    prepopulate_stream(writer.as_ref(), backlog, event).await?;
+
+    println!(
+        "Catching up while appending {} live events at {} events/sec...",
+        live_events, config.live_rate_eps
+    );
+    let subscribed = Instant::now();
+    let mut subscription = subscriber.subscribe(&stream, None).await?;
+    // Due times of the live appends by their index, and how many were acknowledged when
+    let due: Arc<Mutex<Vec<Instant>>> = Arc::new(Mutex::new(Vec::with_capacity(live_events as usize)));
+    let acked = Arc::new(AtomicU64::new(0));
+    let live_writer = {
+        let (due, acked) = (due.clone(), acked.clone());
+        let mut schedule = OpenLoopSchedule::new(config.live_rate_eps)?;
+        async move {
+            let mut ack_s = Vec::with_capacity(live_events as usize);
+            for k in 0..live_events {
+                let next = schedule.next_due().await;
+                due.lock().unwrap().push(next);
+                writer.append(vec![event(backlog + k)]).await?;
+                acked.fetch_add(1, Ordering::Release);
+                ack_s.push(subscribed.elapsed().as_secs_f64());
+            }
+            Ok::<_, anyhow::Error>((ack_s, subscribed.elapsed()))
+        }
+    };
+    let consumer = async {
+        let mut seen = vec![false; total as usize];
+        let mut delivered_s = Vec::with_capacity(total as usize);
+        let (mut duplicates, mut out_of_order, mut highest) = (0u64, 0u64, None);
+        let mut backlog_left = backlog;
+        let mut backlog_done = Duration::ZERO;
+        let mut switchover: Option<(Duration, u64)> = None;
+        // (elapsed at delivery, lag) of each live event after the switchover
+        let mut tail_lags = Vec::new();
+        while (delivered_s.len() as u64) < total {
+            let seq = next_sequence(&mut subscription).await?;
+            let elapsed = subscribed.elapsed();
+            let Some(slot) = seen.get_mut(seq as usize) else {
+                anyhow::bail!("Consumer delivered event {} of {} appended", seq, total);
+            };
+            if std::mem::replace(slot, true) {
+                duplicates += 1;
+                continue;
+            }
+            if highest.is_some_and(|h| seq < h) {
+                out_of_order += 1;
+            }
+            highest = highest.max(Some(seq));
+            delivered_s.push(elapsed.as_secs_f64());
+            let delivered = delivered_s.len() as u64;
+            if seq < backlog {
+                backlog_left -= 1;
+                if backlog_left == 0 {
+                    backlog_done = elapsed;
+                }
+            } else if switchover.is_some() {
+                let started = due.lock().unwrap().get((seq - backlog) as usize).copied();
+                let started = started.ok_or_else(|| anyhow::anyhow!("Consumer delivered an event nobody appended"))?;
+                tail_lags.push((elapsed, started.elapsed()));
+            }
+            if switchover.is_none() && backlog_left == 0 && delivered >= backlog + acked.load(Ordering::Acquire) {
+                switchover = Some((elapsed, delivered - backlog));
+                println!("  caught up after {:.2} s", elapsed.as_secs_f64());
+            }
+        }
+        Ok::<_, anyhow::Error>((delivered_s, duplicates, out_of_order, backlog_done, switchover, tail_lags))
+    };
+    let ((ack_s, writer_done), (delivered_s, duplicates, out_of_order, backlog_done, switchover, tail_lags)) =
+        tokio::try_join!(live_writer, consumer)?;
+
+    // The last delivery completes the catch-up at the latest
+    let (switchover_at, live_events_during_catch_up) = switchover.unwrap_or((subscribed.elapsed(), live_events));
+    let window_end = switchover_at + SWITCHOVER_WINDOW;
+    let mut steady = LatencyRecorder::new();
+    let mut steady_max = Duration::ZERO;
+    let mut switchover_lag_max = Duration::ZERO;
+    for &(elapsed, lag) in &tail_lags {
+        if elapsed < window_end {
+            switchover_lag_max = switchover_lag_max.max(lag);
+        } else {
+            steady.record(lag);
+            steady_max = steady_max.max(lag);
+        }
+    }
+    let backlog_replay_s = backlog_done.as_secs_f64();
+    let end_s = delivered_s.last().copied().unwrap_or_default();
+
+    Ok(BackfillTailResult {
+        store: store.name().to_string(),
+        config: config.clone(),
+        live_events,
+        backlog_replay_s,
+        backlog_replay_eps: backlog as f64 / backlog_replay_s.max(f64::EPSILON),
+        catch_up_s: switchover_at.as_secs_f64(),
+        live_events_during_catch_up,
+        kept_up: switchover_at < writer_done,
+        switchover_max_gap_ms: max_gap_s(&delivered_s, switchover_at.as_secs_f64(), SWITCHOVER_WINDOW.as_secs_f64()) * 1000.0,
+        switchover_lag_max_ms: switchover_lag_max.as_secs_f64() * 1000.0,
+        duplicates,
+        out_of_order,
+        steady_lag: (!steady.hist.is_empty()).then(|| steady.to_stats()),
+        steady_lag_max_ms: steady_max.as_secs_f64() * 1000.0,
+        timeline: timeline(backlog, &ack_s, &delivered_s, end_s),
+    })
+}
+
+/// Longest pause between consecutive deliveries (seconds, ascending) within `window` of `at`
+fn max_gap_s(delivered_s: &[f64], at: f64, window: f64) -> f64 {
+    let from = delivered_s.partition_point(|&t| t < at - window);
+    let to = delivered_s.partition_point(|&t| t <= at + window);
+    delivered_s[from..to].windows(2).map(|pair| pair[1] - pair[0]).fold(0.0, f64::max)
+}
+
+/// Appended and delivered counts at every whole second up to `end_s`, from the acknowledgement
+/// and delivery times (seconds since subscribing, ascending) of the events
+fn timeline(backlog: u64, ack_s: &[f64], delivered_s: &[f64], end_s: f64) -> Vec<BackfillSample> {
+    (1..=end_s.ceil() as u64)
+        .map(|t_s| {
+            let appended = backlog + ack_s.partition_point(|&a| a <= t_s as f64) as u64;
+            let delivered = delivered_s.partition_point(|&d| d <= t_s as f64) as u64;
+            BackfillSample { t_s, appended, delivered, behind: appended.saturating_sub(delivered) }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timeline_and_switchover_gap_follow_the_deliveries() {
+        let acks = [0.5, 1.5, 2.5];
+        let delivered = [0.2, 0.4, 0.6, 1.2, 1.6, 2.6];
+        let samples = timeline(3, &acks, &delivered, 2.6);
+        assert_eq!(samples.len(), 3);
+        assert_eq!((samples[0].appended, samples[0].delivered, samples[0].behind), (4, 3, 1));
+        assert_eq!((samples[2].appended, samples[2].delivered, samples[2].behind), (6, 6, 0));
+        assert!((max_gap_s(&delivered, 1.2, 0.7) - 0.6).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,143 @@
+use crate::host_stats::HostStatsSample;
+use serde::{Deserialize, Serialize};
+
+/// Host CPU above this share (of all cores) counts as saturated
+const HOST_CPU_SATURATED: f64 = 90.0;
+/// Store container CPU above this share of the cores it can use counts as saturated
+const SERVER_CPU_SATURATED: f64 = 90.0;
+const DISK_UTIL_SATURATED: f64 = 80.0;
+const IOWAIT_HIGH: f64 = 20.0;
+/// A throttled run reaching this fraction of its target rate is not bottlenecked
+const TARGET_REACHED: f64 = 0.95;
+
+/// Heuristic classification of what limited a run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Bottleneck {
+    /// The benchmark process (or other host load) used up the host CPUs
+    Client,
+    ServerCpu,
+    ServerDisk,
+    /// No local resource was saturated, so the run was likely bound by round trips
+    Network,
+    None,
+}
+
+/// Likely bottleneck with the numbers it was derived from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BottleneckHint {
+    pub likely: Bottleneck,
+    pub reason: String,
+    /// Average busy share of all host CPUs
+    pub host_cpu_percent: Option<f64>,
+    /// Benchmark process CPU as a share of all host CPUs
+    pub client_cpu_percent: Option<f64>,
+    /// Store container CPU as a share of all host CPUs
+    pub server_cpu_percent: Option<f64>,
+    /// Average utilization of the busiest disk
+    pub disk_util_percent: Option<f64>,
+    pub iowait_percent: Option<f64>,
+    /// Configured operation rate (throttled runs only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_ops_per_second: Option<f64>,
+    /// Achieved / target operation rate (throttled runs only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub achieved_ratio: Option<f64>,
+}
+
+/// Measurements a [`BottleneckHint`] is derived from
+pub struct BottleneckInputs<'a> {
+    pub host_stats: &'a [HostStatsSample],
+    /// Benchmark process CPU in percent of one core (100 = one core busy)
+    pub client_cpu_percent: Option<f64>,
+    /// Store container CPU in percent of one core, summed over its containers
+    pub server_cpu_percent: Option<f64>,
+    pub host_cores: usize,
+    pub target_ops_per_second: Option<f64>,
+    pub achieved_ops_per_second: f64,
+}
+
+/// Apply simple threshold rules: a met target rate means no bottleneck; otherwise the first
+/// saturated resource (host CPU, store CPU, disk) wins, and with nothing saturated the run is
+/// assumed to be latency/network bound.
+pub fn diagnose(inputs: &BottleneckInputs) -> BottleneckHint {
+    let cores = inputs.host_cores.max(1) as f64;
+    let host_cpu = average(inputs.host_stats.iter().map(|s| s.cpu_percent));
+    let disk_util = average(inputs.host_stats.iter().map(|s| s.disk_util_percent));
+    let iowait = average(inputs.host_stats.iter().map(|s| s.iowait_percent));
+    let client_cpu = inputs.client_cpu_percent.map(|cpu| cpu / cores);
+    let server_cpu = inputs.server_cpu_percent.map(|cpu| cpu / cores);
+    let achieved_ratio = inputs
+        .target_ops_per_second
+        .filter(|target| *target > 0.0)
+        .map(|target| inputs.achieved_ops_per_second / target);
+
+    let (likely, reason) = if achieved_ratio.is_some_and(|ratio| ratio >= TARGET_REACHED) {
+        (Bottleneck::None, "target rate achieved".to_string())
+    } else if host_cpu.is_some_and(|cpu| cpu >= HOST_CPU_SATURATED) {
+        // Whoever uses more of the saturated host is the more likely limit
+        if client_cpu.unwrap_or(0.0) >= server_cpu.unwrap_or(0.0) {
+            (
+                Bottleneck::Client,
+                format!(
+                    "host CPU {:.0}% busy, benchmark client using {:.0}%",
+                    host_cpu.unwrap_or(0.0),
+                    client_cpu.unwrap_or(0.0)
+                ),
+            )
+        } else {
+            (
+                Bottleneck::ServerCpu,
+                format!(
+                    "host CPU {:.0}% busy, store using {:.0}%",
+                    host_cpu.unwrap_or(0.0),
+                    server_cpu.unwrap_or(0.0)
+                ),
+            )
+        }
+    } else if server_cpu.is_some_and(|cpu| cpu >= SERVER_CPU_SATURATED) {
+        (
+            Bottleneck::ServerCpu,
+            format!("store using {:.0}% of host CPU", server_cpu.unwrap_or(0.0)),
+        )
+    } else if disk_util.is_some_and(|util| util >= DISK_UTIL_SATURATED)
+        || iowait.is_some_and(|wait| wait >= IOWAIT_HIGH)
+    {
+        (
+            Bottleneck::ServerDisk,
+            format!(
+                "disk {:.0}% utilized, {:.0}% I/O wait",
+                disk_util.unwrap_or(0.0),
+                iowait.unwrap_or(0.0)
+            ),
+        )
+    } else if host_cpu.is_none() && server_cpu.is_none() {
+        (Bottleneck::None, "no resource measurements available".to_string())
+    } else {
+        (
+            Bottleneck::Network,
+            "no CPU or disk saturated; throughput likely bound by round-trip latency".to_string(),
+        )
+    };
+
+    BottleneckHint {
+        likely,
+        reason,
+        host_cpu_percent: host_cpu,
+        client_cpu_percent: client_cpu,
+        server_cpu_percent: server_cpu,
+        disk_util_percent: disk_util,
+        iowait_percent: iowait,
+        target_ops_per_second: inputs.target_ops_per_second,
+        achieved_ratio,
+    }
+}
+
+fn average(values: impl Iterator<Item = Option<f64>>) -> Option<f64> {
+    let values: Vec<f64> = values.flatten().collect();
+    if values.is_empty() {
+        None
+    } else {
+        Some(values.iter().sum::<f64>() / values.len() as f64)
+    }
+}
@@ -0,0 +1,237 @@
+use bollard::container::InspectContainerOptions;
+use bollard::Docker;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::Instant;
+use tokio::task::JoinHandle;
+
+/// Interval between measurements of the store's disk use (inspecting sizes is slow)
+const DISK_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+const GB: f64 = 1e9;
+
+/// Safety limits that end a run early; its results are written as if it had run its course
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BudgetConfig {
+    /// Stop once the run has counted this many events
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_events: Option<u64>,
+    /// Stop once the store's containers use this much disk (writable layers and mounts)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_disk_gb: Option<f64>,
+    /// Stop once this much time has passed since the run started; the store's start and setup
+    /// count towards it (e.g. `90m`, `2h`)
+    #[serde(default, with = "humantime_serde", skip_serializing_if = "Option::is_none")]
+    pub max_wall_clock: Option<Duration>,
+}
+
+impl BudgetConfig {
+    pub fn is_empty(&self) -> bool {
+        self.max_events.is_none() && self.max_disk_gb.is_none() && self.max_wall_clock.is_none()
+    }
+
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if self.max_events == Some(0) {
+            anyhow::bail!("max_events must be > 0");
+        }
+        if self.max_disk_gb.is_some_and(|gb| gb.is_nan() || gb <= 0.0) {
+            anyhow::bail!("max_disk_gb must be > 0");
+        }
+        if self.max_wall_clock == Some(Duration::ZERO) {
+            anyhow::bail!("max_wall_clock must be > 0");
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BudgetLimit {
+    MaxEvents,
+    MaxDiskGb,
+    MaxWallClock,
+}
+
+impl BudgetLimit {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BudgetLimit::MaxEvents => "max_events",
+            BudgetLimit::MaxDiskGb => "max_disk_gb",
+            BudgetLimit::MaxWallClock => "max_wall_clock",
+        }
+    }
+}
+
+/// The limit that ended a run early
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BudgetStop {
+    pub limit: BudgetLimit,
+    /// Time since the run started
+    pub elapsed_s: f64,
+    /// Events counted when the run was stopped
+    pub events: u64,
+    /// Last measured disk use of the store, if measured
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub disk_bytes: Option<u64>,
+}
+
+/// Budget of a single run: checked by the workload's throughput sampler, which stops the
+/// workers like the end of the run's duration does
+pub struct RunBudget {
+    config: BudgetConfig,
+    started: Instant,
+    /// Last measured disk use; `u64::MAX` until measured
+    disk_bytes: Arc<AtomicU64>,
+    disk_task: Option<JoinHandle<()>>,
+    stopped: Mutex<Option<BudgetStop>>,
+}
+
+impl RunBudget {
+    /// Start tracking a run that started at `started`; the disk use of `containers` is measured
+    /// in the background if `max_disk_gb` is set
+    pub fn start(config: BudgetConfig, started: Instant, containers: Vec<String>) -> Self {
+        let disk_bytes = Arc::new(AtomicU64::new(u64::MAX));
+        let disk_task = config.max_disk_gb.map(|_| {
+            let disk_bytes = disk_bytes.clone();
+            tokio::spawn(async move {
+                let Ok(docker) = Docker::connect_with_local_defaults() else {
+                    eprintln!("Failed to connect to Docker; max_disk_gb is not enforced");
+                    return;
+                };
+                let mut ticker = tokio::time::interval(DISK_CHECK_INTERVAL);
+                loop {
+                    ticker.tick().await;
+                    if let Some(bytes) = disk_usage(&docker, &containers).await {
+                        disk_bytes.store(bytes, Ordering::Relaxed);
+                    }
+                }
+            })
+        });
+        Self {
+            config,
+            started,
+            disk_bytes,
+            disk_task,
+            stopped: Mutex::new(None),
+        }
+    }
+
+    fn disk_bytes(&self) -> Option<u64> {
+        let bytes = self.disk_bytes.load(Ordering::Relaxed);
+        (bytes != u64::MAX).then_some(bytes)
+    }
+
+    /// Whether the run is over budget with `events` counted so far; the first exceeded limit is
+    /// kept for the summary
+    pub fn exceeded(&self, events: u64) -> bool {
+        let disk_bytes = self.disk_bytes();
+        let limit = if self.config.max_events.is_some_and(|max| events >= max) {
+            BudgetLimit::MaxEvents
+        } else if self
+            .config
+            .max_disk_gb
+            .zip(disk_bytes)
+            .is_some_and(|(max, bytes)| bytes as f64 >= max * GB)
+        {
+            BudgetLimit::MaxDiskGb
+        } else if self.config.max_wall_clock.is_some_and(|max| self.started.elapsed() >= max) {
+            BudgetLimit::MaxWallClock
+        } else {
+            return false;
+        };
+        let Ok(mut stopped) = self.stopped.lock() else {
+            return true;
+        };
+        if stopped.is_none() {
+            let stop = BudgetStop {
+                limit,
+                elapsed_s: self.started.elapsed().as_secs_f64(),
+                events,
+                disk_bytes,
+            };
+            println!(
+                "Budget limit {} reached after {:.1}s ({} events{}), stopping the run",
+                limit.as_str(),
+                stop.elapsed_s,
+                events,
+                disk_bytes
+                    .map(|bytes| format!(", {:.2} GB on disk", bytes as f64 / GB))
+                    .unwrap_or_default()
+            );
+            *stopped = Some(stop);
+        }
+        true
+    }
+
+    /// Stop measuring; the limit that stopped the run, if any
+    pub fn finish(&self) -> Option<BudgetStop> {
+        if let Some(task) = &self.disk_task {
+            task.abort();
+        }
+        self.stopped.lock().ok()?.take()
+    }
+}
+
+impl Drop for RunBudget {
+    fn drop(&mut self) {
+        if let Some(task) = &self.disk_task {
+            task.abort();
+        }
+    }
+}
+
+/// Disk use of the given containers (see [`disk_usage`]), `None` if Docker is unreachable
+pub(crate) async fn container_disk_usage(containers: &[String]) -> Option<u64> {
+    let docker = Docker::connect_with_local_defaults().ok()?;
+    disk_usage(&docker, containers).await
+}
+
+/// Size of the containers' writable layers plus their mounted directories, as far as they are
+/// readable from this host
+async fn disk_usage(docker: &Docker, containers: &[String]) -> Option<u64> {
+    let mut bytes = 0;
+    let mut sources = Vec::new();
+    for id in containers {
+        let options = InspectContainerOptions { size: true };
+        let inspect = docker.inspect_container(id, Some(options)).await.ok()?;
+        bytes += inspect.size_rw.unwrap_or(0).max(0) as u64;
+        sources.extend(inspect.mounts.unwrap_or_default().into_iter().filter_map(|m| m.source));
+    }
+    let mounted = tokio::task::spawn_blocking(move || {
+        sources.iter().map(|source| dir_size(Path::new(source))).sum::<u64>()
+    })
+    .await
+    .ok()?;
+    Some(bytes + mounted)
+}
+
+fn dir_size(path: &Path) -> u64 {
+    let Ok(metadata) = std::fs::symlink_metadata(path) else {
+        return 0;
+    };
+    if !metadata.is_dir() {
+        return metadata.len();
+    }
+    std::fs::read_dir(path)
+        .map(|entries| entries.filter_map(|e| e.ok()).map(|e| dir_size(&e.path())).sum())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn stops_at_the_first_exceeded_limit() {
+        let config: BudgetConfig = serde_yaml::from_str("max_events: 100\nmax_wall_clock: 1h").unwrap();
+        assert_eq!(config.max_wall_clock, Some(Duration::from_secs(3600)));
+        let budget = RunBudget::start(config, Instant::now(), Vec::new());
+        assert!(!budget.exceeded(99));
+        assert!(budget.exceeded(100));
+        assert!(budget.exceeded(150));
+        let stop = budget.finish().unwrap();
+        assert_eq!((stop.limit, stop.events), (BudgetLimit::MaxEvents, 100));
+    }
+}
@@ -0,0 +1,241 @@
+use crate::adapter::{AdminOp, EventData, EventStoreAdapter, ReadRequest};
+use crate::common::SetupConfig;
+use crate::metrics::{LatencyRecorder, LatencyStats, RawSample};
+use anyhow::Result;
+use bytes::Bytes;
+use futures::TryStreamExt;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::Instant;
+use tokio_util::sync::CancellationToken;
+
+fn default_bulk_event_size() -> usize {
+    256
+}
+
+/// Heavy operation injected into a steady workload
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum BulkOperation {
+    /// Read each of the workload's first `streams` streams from the start (the prepopulated
+    /// streams unless set), like a projection rebuild
+    CatchUpRead {
+        #[serde(default)]
+        streams: Option<u64>,
+    },
+    /// Append `events` events to a new stream in one call, like a bulk import
+    BatchAppend {
+        events: usize,
+        #[serde(default = "default_bulk_event_size")]
+        event_size_bytes: usize,
+    },
+    /// Run a store maintenance command (`scavenge`, `merge_indexes`, `rebuild_indexes`,
+    /// `checkpoint`), for stores whose adapter supports it
+    Admin { op: AdminOp },
+}
+
+/// Periodic heavy operations during a performance workload (`bulk` in the workload config)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BulkConfig {
+    /// Seconds between the starts of consecutive bulk operations, the first one this long
+    /// after the measurement start
+    pub every_seconds: f64,
+    #[serde(flatten)]
+    pub operation: BulkOperation,
+}
+
+impl BulkConfig {
+    pub fn validate(&self, setup: Option<&SetupConfig>) -> Result<()> {
+        if !(self.every_seconds.is_finite() && self.every_seconds > 0.0) {
+            anyhow::bail!("bulk every_seconds must be > 0, got {}", self.every_seconds);
+        }
+        match &self.operation {
+            BulkOperation::CatchUpRead { .. } => {
+                if self.catch_up_streams(setup).unwrap_or(0) == 0 {
+                    anyhow::bail!("bulk catch_up_read needs streams > 0 or a 'setup' config to read");
+                }
+            }
+            BulkOperation::BatchAppend { events, .. } => {
+                if *events == 0 {
+                    anyhow::bail!("bulk batch_append needs events > 0");
+                }
+            }
+            BulkOperation::Admin { .. } => {}
+        }
+        Ok(())
+    }
+
+    /// Streams a catch-up read covers
+    pub fn catch_up_streams(&self, setup: Option<&SetupConfig>) -> Option<u64> {
+        match &self.operation {
+            BulkOperation::CatchUpRead { streams: Some(streams) } => Some(*streams),
+            BulkOperation::CatchUpRead { streams: None } => {
+                setup.map(|setup| setup.prepopulate_streams.unwrap_or(setup.prepopulate_events))
+            }
+            BulkOperation::BatchAppend { .. } | BulkOperation::Admin { .. } => None,
+        }
+    }
+
+    fn kind(&self) -> &'static str {
+        match self.operation {
+            BulkOperation::CatchUpRead { .. } => "catch_up_read",
+            BulkOperation::BatchAppend { .. } => "batch_append",
+            BulkOperation::Admin { op } => op.name(),
+        }
+    }
+}
+
+/// One bulk operation, in seconds from the measurement start
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BulkWindow {
+    pub start_s: f64,
+    pub end_s: f64,
+    /// Events read or appended
+    pub events: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// The bulk operations of a run and the latency of the workload's own operations during and
+/// between them
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BulkStats {
+    pub kind: String,
+    pub every_seconds: f64,
+    pub windows: Vec<BulkWindow>,
+    /// Sampled workload operations started during a bulk operation, and their latency
+    pub ops_in_window: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub in_window: Option<LatencyStats>,
+    pub ops_outside: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub outside: Option<LatencyStats>,
+    /// p99 during bulk operations divided by p99 between them
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub p99_ratio: Option<f64>,
+}
+
+/// Split the successful sampled operations by whether they started during a bulk operation
+pub fn analyze_bulk(config: &BulkConfig, windows: Vec<BulkWindow>, samples: &[RawSample]) -> BulkStats {
+    let mut in_window = LatencyRecorder::new();
+    let mut outside = LatencyRecorder::new();
+    for sample in samples.iter().filter(|s| s.ok) {
+        let t_s = sample.t_rel_us as f64 / 1e6;
+        let recorder = if windows.iter().any(|w| (w.start_s..=w.end_s).contains(&t_s)) {
+            &mut in_window
+        } else {
+            &mut outside
+        };
+        recorder.record(Duration::from_micros(sample.latency_us));
+    }
+    let stats = |rec: &LatencyRecorder| (!rec.hist.is_empty()).then(|| rec.to_stats());
+    let (in_window_stats, outside_stats) = (stats(&in_window), stats(&outside));
+    BulkStats {
+        kind: config.kind().to_string(),
+        every_seconds: config.every_seconds,
+        windows,
+        ops_in_window: in_window.hist.len() as usize,
+        p99_ratio: match (&in_window_stats, &outside_stats) {
+            (Some(during), Some(between)) if between.p99_ms > 0.0 => Some(during.p99_ms / between.p99_ms),
+            _ => None,
+        },
+        in_window: in_window_stats,
+        ops_outside: outside.hist.len() as usize,
+        outside: outside_stats,
+    }
+}
+
+/// Run the bulk operation every `every_seconds` from `start` until `duration` has passed or
+/// `stop` is cancelled; an operation still going when stopped is dropped
+pub(crate) async fn inject_bulk(
+    config: BulkConfig,
+    adapter: Arc<dyn EventStoreAdapter>,
+    streams: Vec<String>,
+    bulk_stream_prefix: String,
+    start: Instant,
+    duration: Duration,
+    stop: CancellationToken,
+) -> Vec<BulkWindow> {
+    let every = Duration::from_secs_f64(config.every_seconds);
+    let mut windows = Vec::new();
+    for n in (1u32..).take_while(|n| every * *n < duration) {
+        tokio::select! {
+            _ = tokio::time::sleep_until(start + every * n) => {}
+            _ = stop.cancelled() => break,
+        }
+        let started = Instant::now();
+        let operation = async {
+            match &config.operation {
+                BulkOperation::CatchUpRead { .. } => {
+                    // Streamed, so a rebuild over long streams holds only the events in flight
+                    let mut events = 0;
+                    for stream in &streams {
+                        let req = ReadRequest {
+                            stream: stream.clone(),
+                            from_offset: None,
+                            limit: None,
+                            consistency: Default::default(),
+                        };
+                        events += adapter.read_stream(req).try_fold(0, |n, _| async move { Ok(n + 1) }).await?;
+                    }
+                    Ok::<_, anyhow::Error>(events)
+                }
+                BulkOperation::BatchAppend { events, event_size_bytes } => {
+                    let stream = format!("{}bulk-{}", bulk_stream_prefix, n);
+                    let payload = Bytes::from(vec![0u8; *event_size_bytes]);
+                    let batch = (0..*events)
+                        .map(|_| EventData {
+                            payload: payload.clone(),
+                            event_type: "bulk".to_string(),
+                            tags: vec![stream.clone()],
+                        })
+                        .collect();
+                    adapter.append(batch).await?;
+                    Ok(*events as u64)
+                }
+                BulkOperation::Admin { op } => {
+                    adapter.admin(*op).await?;
+                    Ok(0)
+                }
+            }
+        };
+        let result = tokio::select! {
+            result = operation => result,
+            _ = stop.cancelled() => break,
+        };
+        windows.push(BulkWindow {
+            start_s: started.duration_since(start).as_secs_f64(),
+            end_s: start.elapsed().as_secs_f64(),
+            events: *result.as_ref().unwrap_or(&0),
+            error: result.err().map(|e| format!("{:#}", e)),
+        });
+    }
+    windows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_samples_by_bulk_window() {
+        let config: BulkConfig = serde_yaml::from_str("every_seconds: 5\nkind: batch_append\nevents: 1000").unwrap();
+        let sample = |t_s: f64, latency_ms: u64| RawSample {
+            t_rel_us: (t_s * 1e6) as u64,
+            t_ms: None,
+            worker: 0,
+            seq: 0,
+            op: "append",
+            latency_us: latency_ms * 1000,
+            ok: true,
+            stream: None,
+        };
+        let windows = vec![BulkWindow { start_s: 5.0, end_s: 6.0, events: 1000, error: None }];
+        let samples = [sample(1.0, 2), sample(5.5, 8), sample(5.9, 8), sample(7.0, 2)];
+        let stats = analyze_bulk(&config, windows, &samples);
+        assert_eq!((stats.kind.as_str(), stats.ops_in_window, stats.ops_outside), ("batch_append", 2, 2));
+        let ratio = stats.p99_ratio.unwrap();
+        assert!((ratio - 4.0).abs() < 0.05, "p99 ratio {}", ratio);
+    }
+}
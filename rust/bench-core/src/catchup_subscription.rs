@@ -0,0 +1,133 @@
+use crate::adapter::{EventData, StoreManager};
+use crate::metrics::{LatencyRecorder, LatencyStats};
+use crate::rate_limit::OpenLoopSchedule;
+use crate::workflow_support::{next_event, prepopulate_stream, run_on_started_store};
+use anyhow::Result;
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::Instant;
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+
+/// Settings for replaying a stream through a subscription and then following it live
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CatchupSubscriptionConfig {
+    /// Events stored before subscribing, replayed in the catch-up phase
+    pub events: u64,
+    /// Events appended once caught up, one at a time, to measure consumption lag
+    pub live_events: u64,
+    /// Appends per second in the live phase
+    pub live_rate_eps: f64,
+    pub event_size_bytes: usize,
+}
+
+/// Outcome of a catch-up subscription study
+#[derive(Debug, Clone, Serialize)]
+pub struct CatchupSubscriptionResult {
+    pub store: String,
+    pub config: CatchupSubscriptionConfig,
+    /// From subscribing until the last prepopulated event arrived
+    pub replay_s: f64,
+    pub replay_eps: f64,
+    /// From subscribing until the first event arrived
+    pub first_event_ms: f64,
+    /// From when each live append was due until the subscription delivered the event
+    pub lag: LatencyStats,
+    pub lag_max_ms: f64,
+}
+
+/// Start the store, prepopulate a stream, time a subscription replaying it from the start, then
+/// append at a steady rate and record how long each event takes to reach the subscription
+pub async fn execute_catchup_subscription(
+    store: Box<dyn StoreManager>,
+    config: &CatchupSubscriptionConfig,
+    cancel_token: CancellationToken,
+) -> Result<CatchupSubscriptionResult> {
+    if config.events == 0 || config.live_events == 0 {
+        anyhow::bail!("Catch-up subscription requires events and live events > 0");
+    }
+    if !(config.live_rate_eps.is_finite() && config.live_rate_eps > 0.0) {
+        anyhow::bail!("Catch-up subscription requires a live rate > 0");
+    }
+
+    run_on_started_store(store, &cancel_token, "catch-up subscription run", async |store| {
+        follow_stream(store, config).await
+    })
+    .await
+}
+
+async fn follow_stream(store: &dyn StoreManager, config: &CatchupSubscriptionConfig) -> Result<CatchupSubscriptionResult> {
+    let writer = store.create_adapter()?;
+    let subscriber = store.create_adapter()?;
+    let stream = format!("catchup-{}", Uuid::new_v4());
+    let payload = Bytes::from(vec![0u8; config.event_size_bytes]);
+    let event = |_: u64| EventData {
+        payload: payload.clone(),
+        event_type: "catchup".to_string(),
+        tags: vec![stream.clone()],
+    };
+
+    // Fail fast for stores without subscriptions instead of after prepopulating
+    drop(subscriber.subscribe(&stream, None).await?);
+
+    println!("Prepopulating a stream of {} events...", config.events);
+    prepopulate_stream(writer.as_ref(), config.events, event).await?;
+
+    println!("Replaying through a subscription...");
+    let subscribed = Instant::now();
+    let mut subscription = subscriber.subscribe(&stream, None).await?;
+    let mut first_event_ms = 0.0;
+    for received in 0..config.events {
+        next_event(&mut subscription).await?;
+        if received == 0 {
+            first_event_ms = subscribed.elapsed().as_secs_f64() * 1000.0;
+        }
+    }
+    let replay_s = subscribed.elapsed().as_secs_f64();
+    println!("  caught up after {:.2} s ({:.0} events/sec)", replay_s, config.events as f64 / replay_s);
+
+    // Append one event at a time on a fixed schedule; the subscription matches the k-th live
+    // event it receives with the k-th append, as a single writer keeps them in order
+    println!("Following {} live appends at {} events/sec...", config.live_events, config.live_rate_eps);
+    let append_started: Arc<Mutex<Vec<Instant>>> = Arc::new(Mutex::new(Vec::new()));
+    let live_writer = {
+        let append_started = append_started.clone();
+        let mut schedule = OpenLoopSchedule::new(config.live_rate_eps)?;
+        let live_events = config.live_events;
+        let batch = vec![event(0)];
+        async move {
+            for _ in 0..live_events {
+                let due = schedule.next_due().await;
+                append_started.lock().unwrap().push(due);
+                writer.append(batch.clone()).await?;
+            }
+            Ok::<(), anyhow::Error>(())
+        }
+    };
+    let consumer = async {
+        let mut lag = LatencyRecorder::new();
+        let mut lag_max = Duration::ZERO;
+        for k in 0..config.live_events as usize {
+            next_event(&mut subscription).await?;
+            let started = append_started.lock().unwrap().get(k).copied();
+            let started = started.ok_or_else(|| anyhow::anyhow!("Subscription delivered an event nobody appended"))?;
+            let elapsed = started.elapsed();
+            lag.record(elapsed);
+            lag_max = lag_max.max(elapsed);
+        }
+        Ok::<_, anyhow::Error>((lag, lag_max))
+    };
+    let ((), (lag, lag_max)) = tokio::try_join!(live_writer, consumer)?;
+
+    Ok(CatchupSubscriptionResult {
+        store: store.name().to_string(),
+        config: config.clone(),
+        replay_s,
+        replay_eps: config.events as f64 / replay_s,
+        first_event_ms,
+        lag: lag.to_stats(),
+        lag_max_ms: lag_max.as_secs_f64() * 1000.0,
+    })
+}
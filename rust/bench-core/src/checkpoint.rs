@@ -0,0 +1,142 @@
+use crate::metrics::{LatencyRecorder, LatencyStats};
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::task::JoinHandle;
+
+/// How long workers get to hand in their latency after a checkpoint is requested. Workers
+/// that are busy longer (or idle) are included with the latency they handed in last.
+const HANDOFF_GRACE: Duration = Duration::from_secs(1);
+
+/// Where and how often a running workload writes intermediate snapshots
+#[derive(Debug, Clone)]
+pub struct CheckpointOptions {
+    pub dir: PathBuf,
+    pub interval: Duration,
+}
+
+/// Snapshot of a run in progress, written as `checkpoint-NNNN.json`
+#[derive(Debug, Clone, Serialize)]
+pub struct Checkpoint {
+    pub sequence: u64,
+    /// Time since the workload started, including client setup and warmup
+    pub elapsed_s: f64,
+    /// Successful operations so far
+    pub operations: u64,
+    pub ops_per_second: f64,
+    /// Workers that have handed in their latency at least once
+    pub workers_reporting: usize,
+    pub latency: LatencyStats,
+    /// HdrHistogram V2 serialization (base64), mergeable like latency.hdr
+    pub latency_hdr: String,
+}
+
+/// Collects each worker's latency histogram when a checkpoint is due
+///
+/// Workers keep recording into their own histogram and only call
+/// [`CheckpointHandle::offer`] after each operation; that is one atomic load unless a
+/// checkpoint was requested since their last hand-in.
+pub struct Checkpoints {
+    epoch: AtomicU64,
+    slots: Vec<Mutex<Option<LatencyRecorder>>>,
+}
+
+impl Checkpoints {
+    pub fn new(workers: usize) -> Arc<Self> {
+        Arc::new(Self {
+            epoch: AtomicU64::new(0),
+            slots: (0..workers).map(|_| Mutex::new(None)).collect(),
+        })
+    }
+
+    pub fn handle(self: &Arc<Self>, worker: usize) -> CheckpointHandle {
+        CheckpointHandle {
+            checkpoints: self.clone(),
+            worker,
+            seen: 0,
+        }
+    }
+
+    /// Write a checkpoint every `options.interval` until the returned task is aborted
+    pub fn spawn_writer(self: &Arc<Self>, options: CheckpointOptions) -> JoinHandle<()> {
+        let checkpoints = self.clone();
+        tokio::spawn(async move {
+            let started = Instant::now();
+            let mut ticks = tokio::time::interval_at(
+                tokio::time::Instant::now() + options.interval,
+                options.interval,
+            );
+            for sequence in 1.. {
+                ticks.tick().await;
+                checkpoints.epoch.fetch_add(1, Ordering::Relaxed);
+                tokio::time::sleep(HANDOFF_GRACE).await;
+                let checkpoint = checkpoints.snapshot(sequence, started.elapsed());
+                if let Err(e) = write_checkpoint(&options.dir, &checkpoint) {
+                    eprintln!("Failed to write checkpoint {}: {:#}", sequence, e);
+                }
+            }
+        })
+    }
+
+    fn snapshot(&self, sequence: u64, elapsed: Duration) -> Checkpoint {
+        let mut merged = LatencyRecorder::new();
+        let mut workers_reporting = 0;
+        for slot in &self.slots {
+            if let Some(rec) = slot.lock().ok().and_then(|s| s.clone()) {
+                let _ = merged.hist.add(&rec.hist);
+                workers_reporting += 1;
+            }
+        }
+        let operations = merged.hist.len();
+        Checkpoint {
+            sequence,
+            elapsed_s: elapsed.as_secs_f64(),
+            operations,
+            ops_per_second: operations as f64 / elapsed.as_secs_f64().max(0.001),
+            workers_reporting,
+            latency: merged.to_stats(),
+            latency_hdr: merged.serialize_to_base64().unwrap_or_default(),
+        }
+    }
+}
+
+/// A worker's side of [`Checkpoints`]
+pub struct CheckpointHandle {
+    checkpoints: Arc<Checkpoints>,
+    worker: usize,
+    seen: u64,
+}
+
+impl CheckpointHandle {
+    /// Hand in a copy of the worker's latency so far if a checkpoint is pending
+    pub fn offer(&mut self, rec: &LatencyRecorder) {
+        let epoch = self.checkpoints.epoch.load(Ordering::Relaxed);
+        if epoch == self.seen {
+            return;
+        }
+        self.seen = epoch;
+        if let Some(Ok(mut slot)) = self.checkpoints.slots.get(self.worker).map(|s| s.lock()) {
+            *slot = Some(rec.clone());
+        }
+    }
+}
+
+/// Offer a worker's latency to its checkpoint handle, if the run takes checkpoints
+pub fn offer_checkpoint(handle: &mut Option<CheckpointHandle>, rec: &LatencyRecorder) {
+    if let Some(handle) = handle {
+        handle.offer(rec);
+    }
+}
+
+/// Write via a temporary file and rename, so a crash never leaves a truncated checkpoint
+fn write_checkpoint(dir: &Path, checkpoint: &Checkpoint) -> Result<()> {
+    std::fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    let path = dir.join(format!("checkpoint-{:04}.json", checkpoint.sequence));
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, serde_json::to_string_pretty(checkpoint)?)?;
+    std::fs::rename(&tmp, &path)?;
+    Ok(())
+}
@@ -0,0 +1,101 @@
+use crate::adapter::StoreManager;
+use crate::metrics::LatencyStats;
+use crate::runner::measured_throughput;
+use crate::workflow_support::run_on_started_store;
+use crate::workloads::{PerformanceOutput, PerformanceWorkload};
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use tokio_util::sync::CancellationToken;
+
+/// Metrics of one of the workloads of a composed run
+#[derive(Debug, Clone, Serialize)]
+pub struct ComposedWorkload {
+    pub workload: String,
+    pub writers: usize,
+    pub readers: usize,
+    pub events_written: u64,
+    pub events_read: u64,
+    pub duration_s: f64,
+    pub throughput_eps: f64,
+    pub latency: LatencyStats,
+    /// Latency by operation type
+    pub op_latency: BTreeMap<String, LatencyStats>,
+}
+
+impl ComposedWorkload {
+    fn new(workload: &PerformanceWorkload, output: &PerformanceOutput) -> Self {
+        let (duration_s, throughput_eps) = measured_throughput(
+            &output.throughput_samples,
+            output.events_written + output.events_read,
+            workload.duration_seconds(),
+        );
+        Self {
+            workload: workload.name().to_string(),
+            writers: workload.writers(),
+            readers: workload.readers(),
+            events_written: output.events_written,
+            events_read: output.events_read,
+            duration_s,
+            throughput_eps,
+            latency: output.latency.to_stats(),
+            op_latency: output
+                .samples
+                .op_latency
+                .iter()
+                .map(|(op, rec)| (op.to_string(), rec.to_stats()))
+                .collect(),
+        }
+    }
+}
+
+/// Outcome of a foreground and a background workload run at the same time on one store
+#[derive(Debug, Clone, Serialize)]
+pub struct CompositionResult {
+    pub store: String,
+    pub foreground: ComposedWorkload,
+    pub background: ComposedWorkload,
+}
+
+/// Start the store, prepare both workloads, then execute them concurrently against the same
+/// store, each with its own workers and metrics (e.g. production traffic in the foreground
+/// and a maintenance job in the background)
+pub async fn execute_composition(
+    store: Box<dyn StoreManager>,
+    foreground: &PerformanceWorkload,
+    background: &PerformanceWorkload,
+    cancel_token: CancellationToken,
+) -> Result<CompositionResult> {
+    run_on_started_store(store, &cancel_token, "composed run", async |store| {
+        run_composed(store, foreground, background, cancel_token.clone()).await
+    })
+    .await
+}
+
+async fn run_composed(
+    store: &dyn StoreManager,
+    foreground: &PerformanceWorkload,
+    background: &PerformanceWorkload,
+    cancel_token: CancellationToken,
+) -> Result<CompositionResult> {
+    foreground.prepare(store).await?;
+    background.prepare(store).await?;
+
+    println!(
+        "Running {} ({}s) with {} ({}s) in the background...",
+        foreground.name(),
+        foreground.duration_seconds(),
+        background.name(),
+        background.duration_seconds()
+    );
+    let (foreground_output, background_output) = tokio::try_join!(
+        foreground.execute(store, cancel_token.clone()),
+        background.execute(store, cancel_token),
+    )?;
+
+    Ok(CompositionResult {
+        store: store.name().to_string(),
+        foreground: ComposedWorkload::new(foreground, &foreground_output),
+        background: ComposedWorkload::new(background, &background_output),
+    })
+}
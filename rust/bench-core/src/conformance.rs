@@ -0,0 +1,40 @@
+use crate::adapter::{EventStoreAdapter, ReadRequest};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// How an adapter answered a read of a stream that was never written
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "result")]
+pub enum MissingStreamRead {
+    /// An empty vec, as the adapter contract requires
+    Empty,
+    /// Events, which means the read is not scoped to the stream
+    Events { count: usize },
+    /// An error, which would count every read of an unwritten stream as a failed read
+    Error { message: String },
+}
+
+impl MissingStreamRead {
+    pub fn conforms(&self) -> bool {
+        *self == MissingStreamRead::Empty
+    }
+}
+
+/// Read a freshly named stream through `adapter` and report what came back
+pub async fn check_missing_stream_read(adapter: &dyn EventStoreAdapter) -> MissingStreamRead {
+    let req = ReadRequest {
+        stream: format!("conformance-missing-{}", Uuid::new_v4()),
+        from_offset: None,
+        limit: Some(1),
+        consistency: Default::default(),
+    };
+    match adapter.read(req).await {
+        Ok(events) if events.is_empty() => MissingStreamRead::Empty,
+        Ok(events) => MissingStreamRead::Events {
+            count: events.len(),
+        },
+        Err(e) => MissingStreamRead::Error {
+            message: e.to_string(),
+        },
+    }
+}
@@ -0,0 +1,171 @@
+use crate::adapter::{EventStoreAdapter, StoreManager};
+use crate::metrics::{LatencyRecorder, LatencyStats};
+use anyhow::Result;
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Connections established at the same time, so thousands of workers don't flood the store
+const ESTABLISH_CONCURRENCY: usize = 64;
+
+/// How long setting up one adapter's connection took, by phase
+#[derive(Debug, Clone)]
+pub struct ConnectTiming {
+    /// Resolving the store's host name
+    pub dns: Option<Duration>,
+    /// Opening a TCP connection to the store (on a probe socket of our own)
+    pub tcp: Option<Duration>,
+    /// Creating the adapter plus its first ping, less the round trip of a ping on the
+    /// established connection, i.e. everything the client does to connect
+    pub connect: Duration,
+    /// Round trip of a ping on the established connection
+    pub ping: Duration,
+    /// Whether both pings succeeded
+    pub ok: bool,
+}
+
+/// Connection setup of a run's adapters, measured before the measurement starts
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConnectionStats {
+    /// Address the adapters connect to, if the store has one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub endpoint: Option<String>,
+    /// Whether the endpoint uses TLS, whose handshake is then part of `handshake`
+    #[serde(default)]
+    pub tls: bool,
+    pub adapters: usize,
+    /// Adapters whose pings failed (their timings are left out)
+    pub failed: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dns: Option<LatencyStats>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tcp: Option<LatencyStats>,
+    /// Client connection time beyond DNS and TCP: TLS and the protocol handshake
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub handshake: Option<LatencyStats>,
+    /// Total client connection time
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub connect: Option<LatencyStats>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ping: Option<LatencyStats>,
+}
+
+/// Host, port and whether TLS is used, from a store address such as `http://localhost:8024`,
+/// `esdb://localhost:2113?tls=false` or `localhost:9092`
+fn parse_endpoint(endpoint: &str) -> Option<(String, u16, bool)> {
+    let (scheme, rest) = endpoint.split_once("://").unwrap_or(("", endpoint));
+    let authority = rest.split(['/', '?']).next()?;
+    let authority = authority.rsplit_once('@').map_or(authority, |(_, host)| host);
+    let (host, port) = authority.rsplit_once(':')?;
+    let tls = matches!(scheme, "https" | "grpcs") || endpoint.contains("tls=true");
+    Some((host.to_string(), port.parse().ok()?, tls))
+}
+
+/// Create `count` adapters and establish each one's connection with two pings, timing the
+/// setup by phase, so no worker pays for its handshake during the measurement
+pub async fn create_connected_adapters(
+    store: &dyn StoreManager,
+    count: usize,
+    role: &str,
+) -> Result<(Vec<Arc<dyn EventStoreAdapter>>, Vec<ConnectTiming>)> {
+    let mut created = Vec::with_capacity(count);
+    for i in 0..count {
+        let started = Instant::now();
+        match store.create_adapter() {
+            Ok(adapter) => created.push((adapter, started.elapsed())),
+            Err(e) => {
+                eprintln!("Failed to create {} {}: {}", role, i, e);
+                anyhow::bail!("Failed to create {} {}: {}", role, i, e);
+            }
+        }
+    }
+    let endpoint = store.endpoint().as_deref().and_then(parse_endpoint);
+    let timings = futures::stream::iter(created.iter())
+        .map(|(adapter, creation)| establish(adapter.as_ref(), *creation, endpoint.as_ref()))
+        .buffered(ESTABLISH_CONCURRENCY)
+        .collect()
+        .await;
+    Ok((created.into_iter().map(|(adapter, _)| adapter).collect(), timings))
+}
+
+async fn establish(
+    adapter: &dyn EventStoreAdapter,
+    creation: Duration,
+    endpoint: Option<&(String, u16, bool)>,
+) -> ConnectTiming {
+    let (dns, tcp) = match endpoint {
+        Some((host, port, _)) => probe(host, *port).await,
+        None => (None, None),
+    };
+    let started = Instant::now();
+    let first = adapter.ping().await;
+    let first_ping = started.elapsed();
+    let started = Instant::now();
+    let second = adapter.ping().await;
+    let ping = started.elapsed();
+    ConnectTiming {
+        dns,
+        tcp,
+        connect: (creation + first_ping).saturating_sub(ping),
+        ping,
+        ok: first.is_ok() && second.is_ok(),
+    }
+}
+
+/// Time resolving the host and opening a TCP connection to it
+async fn probe(host: &str, port: u16) -> (Option<Duration>, Option<Duration>) {
+    let started = Instant::now();
+    let Some(addr) = tokio::net::lookup_host((host, port)).await.ok().and_then(|mut addrs| addrs.next()) else {
+        return (None, None);
+    };
+    let dns = started.elapsed();
+    let started = Instant::now();
+    let tcp = tokio::net::TcpStream::connect(addr).await.ok().map(|_| started.elapsed());
+    (Some(dns), tcp)
+}
+
+/// Summarize the connection setup of a run's adapters
+pub fn analyze_connections(endpoint: Option<String>, timings: &[ConnectTiming]) -> ConnectionStats {
+    let mut dns = LatencyRecorder::new();
+    let mut tcp = LatencyRecorder::new();
+    let mut handshake = LatencyRecorder::new();
+    let mut connect = LatencyRecorder::new();
+    let mut ping = LatencyRecorder::new();
+    for timing in timings.iter().filter(|t| t.ok) {
+        timing.dns.inspect(|d| dns.record(*d));
+        timing.tcp.inspect(|d| tcp.record(*d));
+        if let (Some(d), Some(t)) = (timing.dns, timing.tcp) {
+            handshake.record(timing.connect.saturating_sub(d + t));
+        }
+        connect.record(timing.connect);
+        ping.record(timing.ping);
+    }
+    let stats = |rec: &LatencyRecorder| (!rec.hist.is_empty()).then(|| rec.to_stats());
+    ConnectionStats {
+        tls: endpoint.as_deref().and_then(parse_endpoint).is_some_and(|(_, _, tls)| tls),
+        endpoint,
+        adapters: timings.len(),
+        failed: timings.iter().filter(|t| !t.ok).count(),
+        dns: stats(&dns),
+        tcp: stats(&tcp),
+        handshake: stats(&handshake),
+        connect: stats(&connect),
+        ping: stats(&ping),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_store_endpoints() {
+        assert_eq!(parse_endpoint("http://localhost:8024"), Some(("localhost".to_string(), 8024, false)));
+        assert_eq!(parse_endpoint("https://localhost:3000/"), Some(("localhost".to_string(), 3000, true)));
+        assert_eq!(parse_endpoint("esdb://localhost:2113?tls=false"), Some(("localhost".to_string(), 2113, false)));
+        assert_eq!(parse_endpoint("localhost:9092"), Some(("localhost".to_string(), 9092, false)));
+        assert_eq!(parse_endpoint("postgres://user:pw@db:5432/events"), Some(("db".to_string(), 5432, false)));
+        assert_eq!(parse_endpoint("in-process"), None);
+    }
+}
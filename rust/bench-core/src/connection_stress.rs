@@ -0,0 +1,281 @@
+use crate::adapter::{EventData, EventStoreAdapter, ReadRequest, StoreManager};
+use crate::container_stats::{ContainerGroupMonitor, DEFAULT_STATS_INTERVAL_MS};
+use crate::metrics::{LatencyRecorder, LatencyStats};
+use crate::rate_limit::RateLimiter;
+use crate::stream_growth::LinearFit;
+use crate::workflow_support::run_on_started_store;
+use anyhow::Result;
+use bytes::Bytes;
+use futures::stream::{self, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::task::JoinSet;
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+
+/// Connections opened at the same time while ramping up
+const OPEN_CONCURRENCY: usize = 64;
+
+/// Settings for measuring a write load while more and more client connections are open
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConnectionStressConfig {
+    /// Extra connections open at the last level
+    pub connections: usize,
+    /// Levels after the baseline (no extra connections); level `k` has `connections * k / steps`
+    pub steps: usize,
+    /// Measurement time per level
+    pub phase_seconds: u64,
+    pub writers: usize,
+    /// Total appends per second of the writers
+    pub write_rate: f64,
+    pub event_size_bytes: usize,
+    /// Each extra connection reads once per this many seconds; idle if unset
+    pub read_interval_s: Option<f64>,
+}
+
+/// Write latency and store memory with a number of extra connections open
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConnectionLevel {
+    /// Extra connections attempted at this level
+    pub connections: usize,
+    /// Extra connections that failed to open, e.g. because the server refused them
+    pub failed_connections: usize,
+    pub writes: u64,
+    pub write_errors: u64,
+    pub write_latency: LatencyStats,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub avg_memory_bytes: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub peak_memory_bytes: Option<u64>,
+}
+
+/// Outcome of a connection stress test
+#[derive(Debug, Clone, Serialize)]
+pub struct ConnectionStressResult {
+    pub store: String,
+    pub config: ConnectionStressConfig,
+    pub levels: Vec<ConnectionLevel>,
+    /// Least-squares slope of the store's average memory over open connections
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory_per_connection_bytes: Option<f64>,
+    /// Write p99 at the last level relative to the baseline
+    pub p99_ratio: f64,
+}
+
+/// Start the store and run the write load once per level, opening more connections (kept open
+/// until the end) before each level
+pub async fn execute_connection_stress(
+    store: Box<dyn StoreManager>,
+    config: &ConnectionStressConfig,
+    cancel_token: CancellationToken,
+) -> Result<ConnectionStressResult> {
+    if config.steps == 0 || config.writers == 0 || config.write_rate <= 0.0 {
+        anyhow::bail!("Connection stress test requires steps, writers and write rate > 0");
+    }
+    if config.read_interval_s.is_some_and(|s| s <= 0.0) {
+        anyhow::bail!("Connection read interval must be > 0");
+    }
+
+    run_on_started_store(store, &cancel_token, "connection stress test", async |store| {
+        run_levels(store, config).await
+    })
+    .await
+}
+
+async fn run_levels(store: &dyn StoreManager, config: &ConnectionStressConfig) -> Result<ConnectionStressResult> {
+    let writers = (0..config.writers)
+        .map(|_| store.create_adapter())
+        .collect::<Result<Vec<_>>>()?;
+    let stream_prefix = format!("connections-{}-", Uuid::new_v4());
+    let stop_readers = CancellationToken::new();
+    let mut readers = JoinSet::new();
+    let mut open: Vec<Arc<dyn EventStoreAdapter>> = Vec::new();
+    let mut failed_connections = 0;
+    let mut levels = Vec::with_capacity(config.steps + 1);
+
+    for step in 0..=config.steps {
+        let target = config.connections * step / config.steps;
+        let wanted = target.saturating_sub(open.len() + failed_connections);
+        if wanted > 0 {
+            println!("Opening {} more connections to {}...", wanted, store.name());
+            let opened: Vec<Option<Arc<dyn EventStoreAdapter>>> = stream::iter(0..wanted)
+                .map(|_| open_connection(store, &stream_prefix))
+                .buffer_unordered(OPEN_CONCURRENCY)
+                .collect()
+                .await;
+            for adapter in opened {
+                match adapter {
+                    Some(adapter) => {
+                        if let Some(interval_s) = config.read_interval_s {
+                            readers.spawn(read_periodically(
+                                adapter.clone(),
+                                format!("{}idle", stream_prefix),
+                                Duration::from_secs_f64(interval_s),
+                                stop_readers.clone(),
+                            ));
+                        }
+                        open.push(adapter);
+                    }
+                    None => failed_connections += 1,
+                }
+            }
+        }
+
+        println!(
+            "Writing at {:.0} events/sec with {} extra connections open for {}s...",
+            config.write_rate,
+            open.len(),
+            config.phase_seconds
+        );
+        levels.push(measure_level(store, &writers, config, &stream_prefix, target, failed_connections).await?);
+    }
+    stop_readers.cancel();
+    while readers.join_next().await.is_some() {}
+
+    let mut fit = LinearFit::default();
+    for level in &levels {
+        if let Some(memory) = level.avg_memory_bytes {
+            fit.add((level.connections - level.failed_connections) as f64, memory as f64);
+        }
+    }
+    let memory_per_connection_bytes = levels
+        .iter()
+        .all(|level| level.avg_memory_bytes.is_some())
+        .then(|| fit.slope());
+    let baseline_p99 = levels[0].write_latency.p99_ms;
+    let p99_ratio = match levels.last() {
+        Some(last) if baseline_p99 > 0.0 => last.write_latency.p99_ms / baseline_p99,
+        _ => 1.0,
+    };
+
+    Ok(ConnectionStressResult {
+        store: store.name().to_string(),
+        config: config.clone(),
+        levels,
+        memory_per_connection_bytes,
+        p99_ratio,
+    })
+}
+
+/// A new client that has connected by reading once; `None` if it could not
+async fn open_connection(store: &dyn StoreManager, stream_prefix: &str) -> Option<Arc<dyn EventStoreAdapter>> {
+    let adapter = store.create_adapter().ok()?;
+    let req = ReadRequest {
+        stream: format!("{}idle", stream_prefix),
+        from_offset: None,
+        limit: Some(1),
+        consistency: Default::default(),
+    };
+    adapter.read(req).await.ok()?;
+    Some(adapter)
+}
+
+/// Keep a connection busy at a low rate, starting at a random point of the interval so the
+/// connections don't read in lockstep
+async fn read_periodically(
+    adapter: Arc<dyn EventStoreAdapter>,
+    stream: String,
+    interval: Duration,
+    stop: CancellationToken,
+) {
+    let offset = interval.mul_f64(rand::random::<f64>());
+    let mut ticker = tokio::time::interval_at(tokio::time::Instant::now() + offset, interval);
+    loop {
+        tokio::select! {
+            _ = ticker.tick() => {}
+            _ = stop.cancelled() => return,
+        }
+        let req = ReadRequest {
+            stream: stream.clone(),
+            from_offset: None,
+            limit: Some(1),
+            consistency: Default::default(),
+        };
+        let _ = adapter.read(req).await;
+    }
+}
+
+async fn measure_level(
+    store: &dyn StoreManager,
+    writers: &[Arc<dyn EventStoreAdapter>],
+    config: &ConnectionStressConfig,
+    stream_prefix: &str,
+    connections: usize,
+    failed_connections: usize,
+) -> Result<ConnectionLevel> {
+    let containers = store.containers();
+    let monitor = if containers.is_empty() {
+        None
+    } else {
+        match ContainerGroupMonitor::new(containers) {
+            Ok(mut m) => {
+                m.start(Duration::from_millis(DEFAULT_STATS_INTERVAL_MS)).await;
+                Some(m)
+            }
+            Err(e) => {
+                eprintln!("Failed to initialize container monitor: {}", e);
+                None
+            }
+        }
+    };
+
+    let limiter = Arc::new(RateLimiter::new(config.write_rate, 1));
+    let has_stopped = Arc::new(AtomicBool::new(false));
+    let mut set = JoinSet::new();
+    for (i, adapter) in writers.iter().enumerate() {
+        let adapter = adapter.clone();
+        let limiter = limiter.clone();
+        let has_stopped = has_stopped.clone();
+        let stream = format!("{}writer-{}", stream_prefix, i);
+        let payload = Bytes::from(vec![0u8; config.event_size_bytes]);
+        set.spawn(async move {
+            let mut latency = LatencyRecorder::new();
+            let mut errors = 0u64;
+            loop {
+                limiter.acquire().await;
+                if has_stopped.load(Ordering::Relaxed) {
+                    break;
+                }
+                let event = EventData {
+                    payload: payload.clone(),
+                    event_type: "test".to_string(),
+                    tags: vec![stream.clone()],
+                };
+                let t0 = Instant::now();
+                match adapter.append(vec![event]).await {
+                    Ok(()) => latency.record(t0.elapsed()),
+                    Err(_) => errors += 1,
+                }
+            }
+            (latency, errors)
+        });
+    }
+    tokio::time::sleep(Duration::from_secs(config.phase_seconds)).await;
+    has_stopped.store(true, Ordering::Relaxed);
+
+    let mut latency = LatencyRecorder::new();
+    let mut writes = 0u64;
+    let mut write_errors = 0u64;
+    while let Some(res) = set.join_next().await {
+        let (writer_latency, errors) = res?;
+        writes += writer_latency.hist.len();
+        latency.hist.add(&writer_latency.hist)?;
+        write_errors += errors;
+    }
+    let memory = match monitor {
+        Some(monitor) => Some(monitor.stop().await.0),
+        None => None,
+    };
+
+    Ok(ConnectionLevel {
+        connections,
+        failed_connections,
+        writes,
+        write_errors,
+        write_latency: latency.to_stats(),
+        avg_memory_bytes: memory.as_ref().and_then(|m| m.avg_memory_bytes),
+        peak_memory_bytes: memory.as_ref().and_then(|m| m.peak_memory_bytes),
+    })
+}
@@ -2,9 +2,20 @@ use anyhow::Result;
 use bollard::container::StatsOptions;
 use bollard::Docker;
 use futures::StreamExt;
+use crate::live::LiveStats;
+use crate::metrics::{ContainerResourceStats, ImageLayer};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::sync::Mutex;
 use tokio::task::JoinHandle;
+use tokio::time::MissedTickBehavior;
+
+/// Default interval between container stats samples
+pub const DEFAULT_STATS_INTERVAL_MS: u64 = 1000;
+/// Characters of a layer's creating instruction kept
+const LAYER_COMMAND_CHARS: usize = 120;
 
 pub struct ContainerMonitor {
     docker: Docker,
@@ -12,14 +23,160 @@ pub struct ContainerMonitor {
     stats: Arc<Mutex<CollectedStats>>,
     stop_tx: Option<tokio::sync::oneshot::Sender<()>>,
     monitor_task: Option<JoinHandle<()>>,
+    source: StatsSource,
+}
+
+/// How container CPU and memory are measured
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatsSource {
+    /// Read directly from the container's cgroup v2 files
+    Cgroup,
+    /// One-shot snapshots from the Docker stats API
+    DockerApi,
+}
+
+impl StatsSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StatsSource::Cgroup => "cgroup",
+            StatsSource::DockerApi => "docker_api",
+        }
+    }
 }
 
 #[derive(Default, Clone)]
 struct CollectedStats {
     cpu_samples: Vec<f64>,
+    /// When each CPU sample was taken (the end of the interval it covers)
+    cpu_sampled_at: Vec<Instant>,
     memory_samples: Vec<u64>,
 }
 
+impl CollectedStats {
+    fn summarize(&self) -> ContainerResourceStats {
+        let avg_cpu_percent = if !self.cpu_samples.is_empty() {
+            Some(self.cpu_samples.iter().sum::<f64>() / self.cpu_samples.len() as f64)
+        } else {
+            None
+        };
+
+        let peak_cpu_percent = self.cpu_samples.iter().cloned().fold(None, |acc, x| {
+            Some(acc.map_or(x, |curr| if x > curr { x } else { curr }))
+        });
+
+        let avg_memory_bytes = if !self.memory_samples.is_empty() {
+            Some(self.memory_samples.iter().sum::<u64>() / self.memory_samples.len() as u64)
+        } else {
+            None
+        };
+
+        let peak_memory_bytes = self.memory_samples.iter().max().cloned();
+
+        ContainerResourceStats {
+            avg_cpu_percent,
+            peak_cpu_percent,
+            avg_memory_bytes,
+            peak_memory_bytes,
+        }
+    }
+
+    /// Sum samples taken at the same tick across containers (truncated to the shortest series)
+    fn sum(all: &[CollectedStats]) -> CollectedStats {
+        let cpu_len = all.iter().map(|c| c.cpu_samples.len()).min().unwrap_or(0);
+        let mem_len = all.iter().map(|c| c.memory_samples.len()).min().unwrap_or(0);
+        CollectedStats {
+            cpu_samples: (0..cpu_len)
+                .map(|i| all.iter().map(|c| c.cpu_samples[i]).sum())
+                .collect(),
+            cpu_sampled_at: all.first().map(|c| c.cpu_sampled_at[..cpu_len].to_vec()).unwrap_or_default(),
+            memory_samples: (0..mem_len)
+                .map(|i| all.iter().map(|c| c.memory_samples[i]).sum())
+                .collect(),
+        }
+    }
+}
+
+/// Monitors every container of a store (e.g. cluster nodes or a sidecar), keyed by role
+pub struct ContainerGroupMonitor {
+    monitors: Vec<(String, ContainerMonitor)>,
+}
+
+impl ContainerGroupMonitor {
+    pub fn new(containers: Vec<(String, String)>) -> Result<Self> {
+        let monitors = containers
+            .into_iter()
+            .map(|(role, id)| Ok((role, ContainerMonitor::new(id)?)))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self { monitors })
+    }
+
+    pub async fn start(&mut self, interval: Duration) {
+        for (_, monitor) in &mut self.monitors {
+            monitor.start(interval).await;
+        }
+    }
+
+    /// Stats source of the first (primary) container
+    pub fn source(&self) -> Option<StatsSource> {
+        self.monitors.first().map(|(_, monitor)| monitor.source())
+    }
+
+    /// Image size of the first (primary) container
+    pub async fn get_image_size(&self) -> Result<u64> {
+        match self.monitors.first() {
+            Some((_, monitor)) => monitor.get_image_size().await,
+            None => anyhow::bail!("No containers to inspect"),
+        }
+    }
+
+    /// Image layers of the first (primary) container
+    pub async fn get_image_layers(&self) -> Result<Vec<ImageLayer>> {
+        match self.monitors.first() {
+            Some((_, monitor)) => monitor.get_image_layers().await,
+            None => anyhow::bail!("No containers to inspect"),
+        }
+    }
+
+    /// Copy the latest sample of the containers (summed) into `live` every `interval` until the
+    /// returned task is aborted
+    pub fn feed_live(&self, live: Arc<LiveStats>, interval: Duration) -> JoinHandle<()> {
+        let stats: Vec<_> = self.monitors.iter().map(|(_, monitor)| monitor.stats.clone()).collect();
+        tokio::spawn(async move {
+            let mut ticks = tokio::time::interval(interval);
+            loop {
+                ticks.tick().await;
+                let (mut cpu, mut memory, mut sampled) = (0.0, 0, 0);
+                for stats in &stats {
+                    let stats = stats.lock().await;
+                    if let (Some(c), Some(m)) = (stats.cpu_samples.last(), stats.memory_samples.last()) {
+                        cpu += c;
+                        memory += m;
+                        sampled += 1;
+                    }
+                }
+                if sampled == stats.len() {
+                    live.set_container(cpu, memory);
+                }
+            }
+        })
+    }
+
+    /// Stop all monitors and return the aggregate (summed per tick) and per-role statistics, and
+    /// the aggregate CPU usage of each tick with when it was sampled
+    pub async fn stop(self) -> (ContainerResourceStats, BTreeMap<String, ContainerResourceStats>, Vec<(Instant, f64)>) {
+        let mut per_container = BTreeMap::new();
+        let mut collected = Vec::with_capacity(self.monitors.len());
+        for (role, monitor) in self.monitors {
+            let stats = monitor.stop_collecting().await;
+            per_container.insert(role, stats.summarize());
+            collected.push(stats);
+        }
+        let aggregate = CollectedStats::sum(&collected);
+        let cpu_timeline = aggregate.cpu_sampled_at.iter().copied().zip(aggregate.cpu_samples.iter().copied()).collect();
+        (aggregate.summarize(), per_container, cpu_timeline)
+    }
+}
+
 impl ContainerMonitor {
     pub fn new(container_id: String) -> Result<Self> {
         let docker = Docker::connect_with_local_defaults()?;
@@ -29,51 +186,60 @@ impl ContainerMonitor {
             stats: Arc::new(Mutex::new(CollectedStats::default())),
             stop_tx: None,
             monitor_task: None,
+            source: StatsSource::DockerApi,
         })
     }
 
-    pub async fn start(&mut self) {
-        let docker = self.docker.clone();
-        let container_id = self.container_id.clone();
+    /// Start sampling the container every `interval`
+    ///
+    /// When the container's cgroup v2 directory is visible on this host (Docker running locally
+    /// on Linux), CPU and memory are read directly from the cgroup files, which is much cheaper
+    /// and finer-grained than the Docker API. Otherwise each tick takes a one-shot stats snapshot
+    /// through the Docker API and derives CPU usage from the difference to the previous
+    /// snapshot. Either way intervals well below Docker's own 1-second stats stream (e.g.
+    /// 100-250 ms) can be used for short workloads.
+    pub async fn start(&mut self, interval: Duration) {
         let stats_arc = self.stats.clone();
         let (stop_tx, stop_rx) = tokio::sync::oneshot::channel::<()>();
         self.stop_tx = Some(stop_tx);
 
-        let monitor_task = tokio::spawn(async move {
-            let mut stream = docker.stats(&container_id, Some(StatsOptions { stream: true, one_shot: false }));
-            let mut stop_rx = stop_rx;
+        // The cgroup directory is named after the full container ID
+        let full_id = match self.docker.inspect_container(&self.container_id, None).await {
+            Ok(inspect) => inspect.id.unwrap_or_else(|| self.container_id.clone()),
+            Err(_) => self.container_id.clone(),
+        };
 
-            loop {
-                tokio::select! {
-                    _ = &mut stop_rx => break,
-                    Some(Ok(stats)) = stream.next() => {
-                        let mut guard = stats_arc.lock().await;
-
-                        // Calculate CPU percentage
-                        // bollard provides raw stats, we need to calculate %
-                        // Formula: (cpu_delta / system_delta) * online_cpus * 100.0
-                        let cpu_delta = (stats.cpu_stats.cpu_usage.total_usage as f64) - (stats.precpu_stats.cpu_usage.total_usage as f64);
-                        let system_delta = (stats.cpu_stats.system_cpu_usage.unwrap_or(0) as f64) - (stats.precpu_stats.system_cpu_usage.unwrap_or(0) as f64);
-                        let online_cpus = stats.cpu_stats.online_cpus.unwrap_or(1) as f64;
-
-                        if system_delta > 0.0 && cpu_delta > 0.0 {
-                            let cpu_perc = (cpu_delta / system_delta) * online_cpus * 100.0;
-                            guard.cpu_samples.push(cpu_perc);
-                        }
-
-                        // Memory usage
-                        let mem_usage = stats.memory_stats.usage.unwrap_or(0);
-                        guard.memory_samples.push(mem_usage);
-                    }
-                    else => break,
-                }
+        let monitor_task = match cgroup_dir(&full_id) {
+            Some(dir) => {
+                self.source = StatsSource::Cgroup;
+                tokio::spawn(sample_cgroup(dir, interval, stats_arc, stop_rx))
             }
-        });
+            None => {
+                self.source = StatsSource::DockerApi;
+                tokio::spawn(sample_docker_api(
+                    self.docker.clone(),
+                    self.container_id.clone(),
+                    interval,
+                    stats_arc,
+                    stop_rx,
+                ))
+            }
+        };
 
         self.monitor_task = Some(monitor_task);
     }
 
-    pub async fn stop(mut self) -> Result<(Option<f64>, Option<f64>, Option<u64>, Option<u64>)> {
+    /// Where the samples come from (known once started)
+    pub fn source(&self) -> StatsSource {
+        self.source
+    }
+
+    /// Stop sampling and summarize the collected samples
+    pub async fn stop(self) -> Result<ContainerResourceStats> {
+        Ok(self.stop_collecting().await.summarize())
+    }
+
+    async fn stop_collecting(mut self) -> CollectedStats {
         if let Some(stop_tx) = self.stop_tx.take() {
             let _ = stop_tx.send(());
         }
@@ -81,27 +247,7 @@ impl ContainerMonitor {
             let _ = task.await;
         }
 
-        let guard = self.stats.lock().await;
-
-        let avg_cpu = if !guard.cpu_samples.is_empty() {
-            Some(guard.cpu_samples.iter().sum::<f64>() / guard.cpu_samples.len() as f64)
-        } else {
-            None
-        };
-
-        let peak_cpu = guard.cpu_samples.iter().cloned().fold(None, |acc, x| {
-            Some(acc.map_or(x, |curr| if x > curr { x } else { curr }))
-        });
-
-        let avg_mem = if !guard.memory_samples.is_empty() {
-            Some(guard.memory_samples.iter().sum::<u64>() / guard.memory_samples.len() as u64)
-        } else {
-            None
-        };
-
-        let peak_mem = guard.memory_samples.iter().max().cloned();
-
-        Ok((avg_cpu, peak_cpu, avg_mem, peak_mem))
+        self.stats.lock().await.clone()
     }
 
     pub async fn get_image_size(&self) -> Result<u64> {
@@ -110,4 +256,140 @@ impl ContainerMonitor {
         let image_inspect = self.docker.inspect_image(&image_id).await?;
         Ok(image_inspect.size.unwrap_or(0) as u64)
     }
+
+    /// Layers of the container's image that add content, base layer first
+    pub async fn get_image_layers(&self) -> Result<Vec<ImageLayer>> {
+        let inspect = self.docker.inspect_container(&self.container_id, None).await?;
+        let image_id = inspect.image.ok_or_else(|| anyhow::anyhow!("No image ID for container"))?;
+        let history = self.docker.image_history(&image_id).await?;
+        // History lists the newest layer first
+        Ok(history
+            .into_iter()
+            .rev()
+            .filter(|item| item.size > 0)
+            .map(|item| ImageLayer {
+                size_bytes: item.size as u64,
+                created_by: item.created_by.chars().take(LAYER_COMMAND_CHARS).collect(),
+            })
+            .collect())
+    }
+}
+
+/// Locate the cgroup v2 directory of a container, for both the systemd and cgroupfs drivers
+fn cgroup_dir(full_id: &str) -> Option<PathBuf> {
+    let root = Path::new("/sys/fs/cgroup");
+    [
+        root.join("system.slice").join(format!("docker-{}.scope", full_id)),
+        root.join("docker").join(full_id),
+    ]
+    .into_iter()
+    .find(|dir| dir.join("cpu.stat").is_file() && dir.join("memory.current").is_file())
+}
+
+/// Cumulative CPU time of the cgroup in microseconds (`usage_usec` in cpu.stat)
+fn read_cgroup_cpu_usec(dir: &Path) -> Option<u64> {
+    let cpu_stat = std::fs::read_to_string(dir.join("cpu.stat")).ok()?;
+    cpu_stat
+        .lines()
+        .find_map(|line| line.strip_prefix("usage_usec "))
+        .and_then(|value| value.trim().parse().ok())
+}
+
+fn read_cgroup_memory_bytes(dir: &Path) -> Option<u64> {
+    std::fs::read_to_string(dir.join("memory.current"))
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+}
+
+async fn sample_cgroup(
+    dir: PathBuf,
+    interval: Duration,
+    stats_arc: Arc<Mutex<CollectedStats>>,
+    mut stop_rx: tokio::sync::oneshot::Receiver<()>,
+) {
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
+    // (CPU usage in microseconds, when it was read) of the previous sample
+    let mut previous: Option<(u64, Instant)> = None;
+
+    loop {
+        tokio::select! {
+            _ = &mut stop_rx => break,
+            _ = ticker.tick() => {
+                // The directory disappears when the container stops
+                let Some(usage_usec) = read_cgroup_cpu_usec(&dir) else { break };
+                let read_at = Instant::now();
+                let mem_usage = read_cgroup_memory_bytes(&dir);
+
+                let mut guard = stats_arc.lock().await;
+
+                // CPU time over wall time, so 100% is one fully used core (as with docker stats)
+                if let Some((prev_usage, prev_read_at)) = previous {
+                    let wall_us = read_at.duration_since(prev_read_at).as_micros() as f64;
+                    if wall_us > 0.0 {
+                        let cpu_delta = usage_usec.saturating_sub(prev_usage) as f64;
+                        guard.cpu_samples.push(cpu_delta / wall_us * 100.0);
+                        guard.cpu_sampled_at.push(read_at);
+                    }
+                }
+                previous = Some((usage_usec, read_at));
+
+                if let Some(mem_usage) = mem_usage {
+                    guard.memory_samples.push(mem_usage);
+                }
+            }
+        }
+    }
+}
+
+async fn sample_docker_api(
+    docker: Docker,
+    container_id: String,
+    interval: Duration,
+    stats_arc: Arc<Mutex<CollectedStats>>,
+    mut stop_rx: tokio::sync::oneshot::Receiver<()>,
+) {
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
+    // (container CPU usage, system CPU usage) of the previous snapshot
+    let mut previous: Option<(u64, u64)> = None;
+
+    loop {
+        tokio::select! {
+            _ = &mut stop_rx => break,
+            _ = ticker.tick() => {
+                let options = StatsOptions { stream: false, one_shot: true };
+                let mut stream = docker.stats(&container_id, Some(options));
+                let stats = match stream.next().await {
+                    Some(Ok(stats)) => stats,
+                    Some(Err(_)) => continue,
+                    None => break,
+                };
+
+                let total_usage = stats.cpu_stats.cpu_usage.total_usage;
+                let system_usage = stats.cpu_stats.system_cpu_usage.unwrap_or(0);
+                let online_cpus = stats.cpu_stats.online_cpus.unwrap_or(1) as f64;
+
+                let mut guard = stats_arc.lock().await;
+
+                // Formula: (cpu_delta / system_delta) * online_cpus * 100.0
+                if let Some((prev_total, prev_system)) = previous {
+                    let cpu_delta = total_usage.saturating_sub(prev_total) as f64;
+                    let system_delta = system_usage.saturating_sub(prev_system) as f64;
+                    if system_delta > 0.0 {
+                        let cpu_perc = (cpu_delta / system_delta) * online_cpus * 100.0;
+                        guard.cpu_samples.push(cpu_perc);
+                        guard.cpu_sampled_at.push(Instant::now());
+                    }
+                }
+                previous = Some((total_usage, system_usage));
+
+                // Memory usage
+                let mem_usage = stats.memory_stats.usage.unwrap_or(0);
+                guard.memory_samples.push(mem_usage);
+            }
+        }
+    }
 }
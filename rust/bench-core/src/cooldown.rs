@@ -0,0 +1,53 @@
+use anyhow::{bail, ensure, Context, Result};
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+
+/// Host settling between the runs of a session, so earlier runs don't warm caches for later
+/// ones and the order of the run matrix doesn't show up in the results
+#[derive(Debug, Clone, Default)]
+pub struct BetweenRuns {
+    /// Idle time before every run but the first
+    pub cooldown: Duration,
+    /// Drop the host page cache before every run
+    pub drop_caches: bool,
+    /// Why dropping the page cache failed, after which it is no longer attempted
+    pub drop_error: Option<String>,
+}
+
+impl BetweenRuns {
+    /// Idle for the cooldown (unless this is the first run), then drop the page cache if
+    /// requested. A failed drop is reported once and turns further drops off.
+    pub async fn settle(&mut self, first_run: bool, cancel: &CancellationToken) {
+        if !first_run && !self.cooldown.is_zero() {
+            println!("Cooling down for {:.1}s", self.cooldown.as_secs_f64());
+            tokio::select! {
+                _ = tokio::time::sleep(self.cooldown) => {}
+                _ = cancel.cancelled() => return,
+            }
+        }
+        if self.drop_caches {
+            if let Err(e) = drop_page_cache().await {
+                eprintln!("Warning: could not drop the page cache, continuing without: {:#}", e);
+                self.drop_caches = false;
+                self.drop_error = Some(format!("{:#}", e));
+            }
+        }
+    }
+}
+
+/// Flush dirty pages, then drop the host page cache, dentries and inodes. Needs root on
+/// Linux; with Docker Desktop this only reaches the client's host, not the VM running the stores.
+pub async fn drop_page_cache() -> Result<()> {
+    if !cfg!(target_os = "linux") {
+        bail!("dropping the page cache is only supported on Linux");
+    }
+    let status = tokio::process::Command::new("sync")
+        .status()
+        .await
+        .context("Failed to run sync")?;
+    ensure!(status.success(), "sync exited with {}", status);
+    tokio::fs::write("/proc/sys/vm/drop_caches", "3")
+        .await
+        .context("Failed to write /proc/sys/vm/drop_caches")?;
+    Ok(())
+}
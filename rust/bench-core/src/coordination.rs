@@ -0,0 +1,204 @@
+use crate::metrics::now_ms;
+use chrono::{DateTime, DurationRound, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio::time::Instant;
+use tokio_util::sync::CancellationToken;
+
+/// Starting later than scheduled by more than this is reported
+const LATE_START_TOLERANCE: Duration = Duration::from_millis(100);
+
+/// Wall-clock instant at which the measurement window starts, so coordinated runs on
+/// different machines (or paired runs) begin together. Without either setting the
+/// measurement starts as soon as every worker is ready.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StartSchedule {
+    /// Start at this time (RFC 3339, e.g. `2026-01-01T12:00:00Z`)
+    #[serde(default)]
+    pub start_at: Option<DateTime<Utc>>,
+    /// Start at the next full minute once the workers are ready (or after `start_at`)
+    #[serde(default)]
+    pub align_to_minute: bool,
+}
+
+impl StartSchedule {
+    /// Scheduled start for workers that are ready at `now`
+    pub fn start_time(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let earliest = match self.start_at {
+            Some(start_at) => start_at.max(now),
+            None if self.align_to_minute => now,
+            None => return None,
+        };
+        if !self.align_to_minute {
+            return Some(earliest);
+        }
+        let minute = earliest.duration_trunc(TimeDelta::minutes(1)).ok()?;
+        Some(if minute == earliest {
+            minute
+        } else {
+            minute + TimeDelta::minutes(1)
+        })
+    }
+}
+
+/// Start barrier shared by the workers of a run and the coordinating task
+///
+/// Workers call [`StartBarrier::worker_ready`] once their client is connected and warmed up.
+/// The coordinator calls [`StartBarrier::release`], which resolves when every worker is ready
+/// and marks the start of the measurement window. A worker that fails before it is ready calls
+/// [`StartBarrier::abandon`]; that, or the run's cancel token, makes both return an error
+/// instead of waiting for the missing worker.
+pub struct StartBarrier {
+    workers: usize,
+    /// Workers ready so far
+    ready: watch::Sender<usize>,
+    /// Cancelled by the coordinator once the measurement start is set
+    released: CancellationToken,
+    /// Cancelled by the run's cancel token or by a worker abandoning the barrier
+    aborted: CancellationToken,
+    started_at: OnceLock<MeasurementStart>,
+    schedule: StartSchedule,
+}
+
+/// Start of the measurement window on the monotonic clock, with the matching wall-clock time
+#[derive(Debug, Clone, Copy)]
+pub struct MeasurementStart {
+    pub instant: Instant,
+    pub epoch_ms: u64,
+}
+
+impl MeasurementStart {
+    fn now() -> Self {
+        Self {
+            instant: Instant::now(),
+            epoch_ms: now_ms() as u64,
+        }
+    }
+}
+
+impl StartBarrier {
+    pub fn new(workers: usize, cancel_token: &CancellationToken) -> Arc<Self> {
+        Self::scheduled(workers, StartSchedule::default(), cancel_token)
+    }
+
+    /// Barrier whose release waits for the wall-clock start given by `schedule`
+    pub fn scheduled(workers: usize, schedule: StartSchedule, cancel_token: &CancellationToken) -> Arc<Self> {
+        Arc::new(Self {
+            workers,
+            ready: watch::Sender::new(0),
+            released: CancellationToken::new(),
+            aborted: cancel_token.child_token(),
+            started_at: OnceLock::new(),
+            schedule,
+        })
+    }
+
+    /// Wait for all other workers, then return how long after the measurement start this worker
+    /// resumed; an error if the run stopped before the measurement started
+    pub async fn worker_ready(&self) -> anyhow::Result<Duration> {
+        self.ready.send_modify(|ready| *ready += 1);
+        tokio::select! {
+            _ = self.released.cancelled() => {}
+            _ = self.aborted.cancelled() => anyhow::bail!("Run stopped before the measurement started"),
+        }
+        let started_at = self.started_at.get_or_init(MeasurementStart::now).instant;
+        Ok(Instant::now().saturating_duration_since(started_at))
+    }
+
+    /// Drop out of the barrier from a worker that failed before it became ready, so the
+    /// coordinator and the other workers stop waiting for it
+    pub fn abandon(&self) {
+        self.aborted.cancel();
+    }
+
+    /// Measurement start, available to workers once [`StartBarrier::worker_ready`] has returned
+    pub fn measurement_start(&self) -> MeasurementStart {
+        *self.started_at.get_or_init(MeasurementStart::now)
+    }
+
+    /// Wait for all workers to be ready (then for the scheduled start, if any) and return the
+    /// measurement start instant; an error if the run is cancelled or a worker abandons the
+    /// barrier first
+    pub async fn release(&self) -> anyhow::Result<Instant> {
+        let mut ready = self.ready.subscribe();
+        tokio::select! {
+            res = ready.wait_for(|ready| *ready >= self.workers) => { res?; }
+            _ = self.aborted.cancelled() => anyhow::bail!("Run stopped before all workers were ready"),
+        }
+        // Scheduled from when the workers are ready, so slow connecting can't skip past the start
+        if let Some(start_at) = self.schedule.start_at.filter(|at| *at < Utc::now()) {
+            eprintln!("Warning: start_at {} has already passed, starting now", start_at.to_rfc3339());
+        }
+        let scheduled = self.schedule.start_time(Utc::now());
+        if let Some(start) = scheduled {
+            println!("Waiting for scheduled start at {}", start.to_rfc3339());
+            if let Ok(wait) = (start - Utc::now()).to_std() {
+                tokio::select! {
+                    _ = tokio::time::sleep(wait) => {}
+                    _ = self.aborted.cancelled() => anyhow::bail!("Run stopped before the measurement started"),
+                }
+            }
+        }
+        let started_at = *self.started_at.get_or_init(MeasurementStart::now);
+        self.released.cancel();
+        if let Some(start) = scheduled {
+            let late_ms = started_at.epoch_ms.saturating_sub(start.timestamp_millis() as u64);
+            if Duration::from_millis(late_ms) > LATE_START_TOLERANCE {
+                eprintln!("Warning: measurement started {} ms after the scheduled start", late_ms);
+            }
+        }
+        Ok(started_at.instant)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aligns_start_to_next_full_minute() {
+        let at = |s: &str| s.parse::<DateTime<Utc>>().unwrap();
+        let aligned = StartSchedule {
+            start_at: None,
+            align_to_minute: true,
+        };
+        assert_eq!(aligned.start_time(at("2026-01-01T12:00:10Z")), Some(at("2026-01-01T12:01:00Z")));
+        assert_eq!(aligned.start_time(at("2026-01-01T12:00:00Z")), Some(at("2026-01-01T12:00:00Z")));
+
+        let fixed = StartSchedule {
+            start_at: Some(at("2026-01-01T12:05:30Z")),
+            align_to_minute: false,
+        };
+        assert_eq!(fixed.start_time(at("2026-01-01T12:00:10Z")), Some(at("2026-01-01T12:05:30Z")));
+        // A start time already passed starts immediately
+        assert_eq!(fixed.start_time(at("2026-01-01T12:06:00Z")), Some(at("2026-01-01T12:06:00Z")));
+        assert_eq!(StartSchedule::default().start_time(at("2026-01-01T12:00:10Z")), None);
+    }
+
+    #[tokio::test]
+    async fn worker_failing_before_the_barrier_stops_the_start() {
+        let barrier = StartBarrier::new(2, &CancellationToken::new());
+        let ready = tokio::spawn({
+            let barrier = barrier.clone();
+            async move { barrier.worker_ready().await }
+        });
+        let failing = tokio::spawn({
+            let barrier = barrier.clone();
+            async move {
+                // Connecting failed: drop out instead of reaching the barrier
+                barrier.abandon();
+            }
+        });
+        failing.await.unwrap();
+        assert!(barrier.release().await.is_err());
+        assert!(ready.await.unwrap().is_err());
+
+        // Cancelling the run stops a release still waiting for workers
+        let cancel = CancellationToken::new();
+        let barrier = StartBarrier::new(1, &cancel);
+        cancel.cancel();
+        assert!(barrier.release().await.is_err());
+    }
+}
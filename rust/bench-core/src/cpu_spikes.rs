@@ -0,0 +1,233 @@
+use crate::metrics::RawSample;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// Container CPU samples needed before spikes are looked for
+const MIN_CPU_SAMPLES: usize = 5;
+/// A CPU sample is a spike at this multiple of the run's median CPU...
+const SPIKE_FACTOR: f64 = 1.5;
+/// ...and at least this many percentage points above it
+const SPIKE_MIN_POINTS: f64 = 10.0;
+/// Spikes reported, the highest peaks first
+const MAX_SPIKES: usize = 10;
+
+/// One operation type's rate, share and latency during a spike, against the rest of the run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpikeOp {
+    pub op: String,
+    pub ops_per_s: f64,
+    pub baseline_ops_per_s: f64,
+    /// Share of the spike's successful operations
+    pub share: f64,
+    pub baseline_share: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub p99_ms: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub baseline_p99_ms: Option<f64>,
+}
+
+/// Consecutive container CPU samples above the spike threshold, with the operations that ran
+/// during them
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CpuSpike {
+    /// Seconds from the measurement start
+    pub start_s: f64,
+    pub end_s: f64,
+    pub peak_cpu_percent: f64,
+    pub ops_per_s: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub p99_ms: Option<f64>,
+    /// p99 during the spike divided by p99 outside all spikes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub p99_ratio: Option<f64>,
+    /// By share of the spike's operations, largest first
+    pub ops: Vec<SpikeOp>,
+}
+
+/// Store CPU spikes of a run, cross-referenced with the sampled operations
+///
+/// A CPU sample covers the interval since the previous one. Samples well above the run's median
+/// are merged into spikes, and the sampled operations started during each spike are compared
+/// with those started outside all spikes, so a spike can be traced to a change in the
+/// operation mix (or shown to coincide with none, pointing at the store's own background work).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CpuSpikeStats {
+    pub median_cpu_percent: f64,
+    pub threshold_cpu_percent: f64,
+    /// Throughput and p99 outside all spikes
+    pub baseline_ops_per_s: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub baseline_p99_ms: Option<f64>,
+    /// In time order
+    pub spikes: Vec<CpuSpike>,
+}
+
+/// Successful sampled operations of one time span, by operation type
+#[derive(Default)]
+struct OpMix {
+    latencies_us: BTreeMap<&'static str, Vec<u64>>,
+}
+
+impl OpMix {
+    fn add(&mut self, sample: &RawSample) {
+        self.latencies_us.entry(sample.op).or_default().push(sample.latency_us);
+    }
+
+    fn count(&self) -> usize {
+        self.latencies_us.values().map(Vec::len).sum()
+    }
+
+    fn p99_ms(&self) -> Option<f64> {
+        let mut all: Vec<u64> = self.latencies_us.values().flatten().copied().collect();
+        all.sort_unstable();
+        percentile_ms(&all, 0.99)
+    }
+}
+
+/// Find the spikes in the store's CPU samples (`(seconds from the measurement start at which
+/// the sample was taken, CPU percent)`, in time order) and the operations behind them; `None`
+/// without enough CPU or operation samples
+pub fn analyze_cpu_spikes(
+    cpu: &[(f64, f64)],
+    interval: Duration,
+    samples: &[RawSample],
+    sample_rate: u64,
+) -> Option<CpuSpikeStats> {
+    if cpu.len() < MIN_CPU_SAMPLES || samples.is_empty() || sample_rate == 0 {
+        return None;
+    }
+    let mut sorted_cpu: Vec<f64> = cpu.iter().map(|(_, percent)| *percent).collect();
+    sorted_cpu.sort_by(f64::total_cmp);
+    let median = sorted_cpu[sorted_cpu.len() / 2];
+    let threshold = (median * SPIKE_FACTOR).max(median + SPIKE_MIN_POINTS);
+
+    // (start_s, end_s, peak) of runs of samples above the threshold
+    let mut spans: Vec<(f64, f64, f64)> = Vec::new();
+    let mut previous_end = (cpu[0].0 - interval.as_secs_f64()).max(0.0);
+    let mut extends = false;
+    for &(end_s, percent) in cpu {
+        if percent >= threshold {
+            match spans.last_mut() {
+                Some(span) if extends => {
+                    span.1 = end_s;
+                    span.2 = span.2.max(percent);
+                }
+                _ => spans.push((previous_end, end_s, percent)),
+            }
+        }
+        extends = percent >= threshold;
+        previous_end = end_s;
+    }
+    spans.sort_by(|a, b| b.2.total_cmp(&a.2));
+    spans.truncate(MAX_SPIKES);
+    spans.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let mut during: Vec<OpMix> = spans.iter().map(|_| OpMix::default()).collect();
+    let mut outside = OpMix::default();
+    for sample in samples.iter().filter(|s| s.ok) {
+        let t_s = sample.t_rel_us as f64 / 1e6;
+        let idx = spans.partition_point(|span| span.0 <= t_s);
+        match idx.checked_sub(1).filter(|&i| t_s < spans[i].1) {
+            Some(i) => during[i].add(sample),
+            None => outside.add(sample),
+        }
+    }
+
+    let end_s = samples.iter().map(|s| s.t_rel_us).max().unwrap_or(0) as f64 / 1e6;
+    let outside_s = (end_s.max(previous_end) - spans.iter().map(|span| span.1 - span.0).sum::<f64>()).max(f64::EPSILON);
+    let rate = |count: usize, seconds: f64| count as f64 * sample_rate as f64 / seconds.max(f64::EPSILON);
+    let baseline_p99_ms = outside.p99_ms();
+    let spikes = spans
+        .iter()
+        .zip(&during)
+        .map(|(&(start_s, end_s, peak_cpu_percent), mix)| {
+            let seconds = end_s - start_s;
+            let mut ops: Vec<SpikeOp> = mix
+                .latencies_us
+                .iter()
+                .map(|(op, latencies)| {
+                    let baseline = outside.latencies_us.get(op).map_or(&[][..], Vec::as_slice);
+                    let mut sorted = latencies.clone();
+                    sorted.sort_unstable();
+                    let mut baseline_sorted = baseline.to_vec();
+                    baseline_sorted.sort_unstable();
+                    SpikeOp {
+                        op: op.to_string(),
+                        ops_per_s: rate(latencies.len(), seconds),
+                        baseline_ops_per_s: rate(baseline.len(), outside_s),
+                        share: latencies.len() as f64 / mix.count() as f64,
+                        baseline_share: baseline.len() as f64 / outside.count().max(1) as f64,
+                        p99_ms: percentile_ms(&sorted, 0.99),
+                        baseline_p99_ms: percentile_ms(&baseline_sorted, 0.99),
+                    }
+                })
+                .collect();
+            ops.sort_by(|a, b| b.share.total_cmp(&a.share));
+            let p99_ms = mix.p99_ms();
+            CpuSpike {
+                start_s,
+                end_s,
+                peak_cpu_percent,
+                ops_per_s: rate(mix.count(), seconds),
+                p99_ms,
+                p99_ratio: p99_ms.zip(baseline_p99_ms).filter(|(_, base)| *base > 0.0).map(|(p99, base)| p99 / base),
+                ops,
+            }
+        })
+        .collect();
+    Some(CpuSpikeStats {
+        median_cpu_percent: median,
+        threshold_cpu_percent: threshold,
+        baseline_ops_per_s: rate(outside.count(), outside_s),
+        baseline_p99_ms,
+        spikes,
+    })
+}
+
+/// Nearest-rank percentile of sorted microsecond latencies, in milliseconds
+fn percentile_ms(sorted_us: &[u64], quantile: f64) -> Option<f64> {
+    if sorted_us.is_empty() {
+        return None;
+    }
+    Some(sorted_us[((sorted_us.len() - 1) as f64 * quantile).round() as usize] as f64 / 1000.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn attributes_operations_to_the_cpu_spike_they_ran_in() {
+        let sample = |t_rel_us: u64, op: &'static str, latency_us: u64| RawSample {
+            t_rel_us,
+            t_ms: None,
+            worker: 0,
+            seq: 0,
+            op,
+            latency_us,
+            ok: true,
+            stream: None,
+        };
+        // Spike from 2s to 4s (the samples taken at 3s and 4s)
+        let cpu = [(1.0, 50.0), (2.0, 55.0), (3.0, 150.0), (4.0, 140.0), (5.0, 50.0), (6.0, 45.0)];
+        let mut samples = Vec::new();
+        for t in 0..60 {
+            let t_rel_us = t * 100_000;
+            if (20..40).contains(&t) {
+                samples.push(sample(t_rel_us, "read", 8_000));
+            } else {
+                samples.push(sample(t_rel_us, "append", 1_000));
+            }
+        }
+        let stats = analyze_cpu_spikes(&cpu, Duration::from_secs(1), &samples, 100).unwrap();
+        assert_eq!(stats.median_cpu_percent, 55.0);
+        assert_eq!(stats.spikes.len(), 1);
+        let spike = &stats.spikes[0];
+        assert_eq!((spike.start_s, spike.end_s, spike.peak_cpu_percent), (2.0, 4.0, 150.0));
+        assert_eq!(spike.ops.len(), 1);
+        assert_eq!((spike.ops[0].op.as_str(), spike.ops[0].share, spike.ops[0].baseline_share), ("read", 1.0, 0.0));
+        assert_eq!(spike.ops_per_s, 1000.0);
+        assert_eq!(spike.p99_ratio, Some(8.0));
+    }
+}
@@ -0,0 +1,96 @@
+use serde::{Deserialize, Serialize};
+
+/// Number of leading operations per worker covered by the operation digest
+pub const DIGEST_OPS: u64 = 1000;
+
+/// Digest of one worker's leading operations, as recorded in the run metadata
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorkerDigest {
+    /// Operations covered (fewer than [`DIGEST_OPS`] if the worker stopped earlier)
+    pub ops: u64,
+    pub digest: String,
+}
+
+/// Running FNV-1a digest over the randomly chosen parameters of a worker's first
+/// [`DIGEST_OPS`] operations
+///
+/// Two runs with the same config and seed must produce the same digests; a mismatch means the
+/// seed no longer reproduces the same operation sequence.
+#[derive(Debug, Clone)]
+pub struct OpDigest {
+    hash: u64,
+    ops: u64,
+}
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+impl OpDigest {
+    pub fn new() -> Self {
+        Self {
+            hash: FNV_OFFSET_BASIS,
+            ops: 0,
+        }
+    }
+
+    /// Whether further values still count towards the digest
+    pub fn is_open(&self) -> bool {
+        self.ops < DIGEST_OPS
+    }
+
+    /// Mix a value into the digest (ignored once the digest is complete)
+    pub fn feed(&mut self, value: u64) {
+        if !self.is_open() {
+            return;
+        }
+        for byte in value.to_le_bytes() {
+            self.hash ^= byte as u64;
+            self.hash = self.hash.wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    /// Mark the end of one operation
+    pub fn end_op(&mut self) {
+        if self.is_open() {
+            self.ops += 1;
+        }
+    }
+
+    pub fn hex(&self) -> String {
+        format!("{:016x}", self.hash)
+    }
+
+    pub fn finish(&self) -> WorkerDigest {
+        WorkerDigest {
+            ops: self.ops,
+            digest: self.hex(),
+        }
+    }
+}
+
+impl Default for OpDigest {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_digest_stops_after_limit() {
+        let mut a = OpDigest::new();
+        let mut b = OpDigest::new();
+        for i in 0..DIGEST_OPS {
+            a.feed(i);
+            a.end_op();
+            b.feed(i);
+            b.end_op();
+        }
+        assert_eq!(a.hex(), b.hex());
+        a.feed(42);
+        a.end_op();
+        assert_eq!(a.hex(), b.hex());
+    }
+}
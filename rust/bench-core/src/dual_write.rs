@@ -0,0 +1,208 @@
+use crate::adapter::{EventData, StoreManager};
+use crate::coordination::{StartBarrier, StartSchedule};
+use crate::metrics::{LatencyRecorder, LatencyStats};
+use crate::runner::start_store;
+use anyhow::Result;
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::task::JoinSet;
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+
+/// Settings for a dual-write comparison between two stores
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DualWriteConfig {
+    pub duration_seconds: u64,
+    pub writers: usize,
+    pub event_size_bytes: usize,
+    /// Wall-clock start, e.g. to line up with a paired run on another machine
+    #[serde(flatten)]
+    pub schedule: StartSchedule,
+}
+
+/// Latencies of one logical append issued to both stores at the same time
+#[derive(Debug, Clone, Serialize)]
+pub struct PairedSample {
+    pub op_id: u64,
+    pub a_us: u64,
+    pub b_us: u64,
+}
+
+/// Outcome of a dual-write comparison
+///
+/// Because both appends of a pair run concurrently on the same host, host noise affects both
+/// sides alike and largely cancels out in the paired differences.
+#[derive(Debug, Clone, Serialize)]
+pub struct DualWriteResult {
+    pub store_a: String,
+    pub store_b: String,
+    pub config: DualWriteConfig,
+    /// Pairs where both appends succeeded
+    pub pairs: u64,
+    pub errors_a: u64,
+    pub errors_b: u64,
+    pub latency_a: LatencyStats,
+    pub latency_b: LatencyStats,
+    /// Mean of (b - a) over all pairs, in microseconds
+    pub mean_diff_us: f64,
+    /// Median of (b - a) over all pairs, in microseconds
+    pub median_diff_us: f64,
+    /// Fraction of pairs where store A was faster
+    pub a_faster_fraction: f64,
+    #[serde(skip)]
+    pub samples: Vec<PairedSample>,
+}
+
+/// Start both stores and have each writer issue every append to both stores concurrently
+pub async fn execute_dual_write(
+    mut store_a: Box<dyn StoreManager>,
+    mut store_b: Box<dyn StoreManager>,
+    config: &DualWriteConfig,
+    cancel_token: CancellationToken,
+) -> Result<DualWriteResult> {
+    if config.writers == 0 {
+        anyhow::bail!("Dual-write requires writers > 0");
+    }
+
+    start_store(store_a.as_mut(), &cancel_token).await?;
+    if let Err(e) = start_store(store_b.as_mut(), &cancel_token).await {
+        store_a.stop().await.ok();
+        return Err(e);
+    }
+
+    let result = run_pairs(store_a.as_ref(), store_b.as_ref(), config, cancel_token).await;
+
+    store_a.stop().await.ok();
+    store_b.stop().await.ok();
+    result
+}
+
+async fn run_pairs(
+    store_a: &dyn StoreManager,
+    store_b: &dyn StoreManager,
+    config: &DualWriteConfig,
+    cancel_token: CancellationToken,
+) -> Result<DualWriteResult> {
+    println!(
+        "Creating {} writer client pairs ({} / {})...",
+        config.writers,
+        store_a.name(),
+        store_b.name()
+    );
+
+    let next_op_id = Arc::new(AtomicU64::new(0));
+    let has_stopped = Arc::new(AtomicBool::new(false));
+    let barrier = StartBarrier::scheduled(config.writers, config.schedule.clone(), &cancel_token);
+    let mut set = JoinSet::new();
+
+    for _ in 0..config.writers {
+        let adapter_a = store_a.create_adapter()?;
+        let adapter_b = store_b.create_adapter()?;
+        let event_size = config.event_size_bytes;
+        let next_op_id = next_op_id.clone();
+        let has_stopped = has_stopped.clone();
+        let cancel_token = cancel_token.clone();
+        let barrier = barrier.clone();
+
+        set.spawn(async move {
+            let stream_name = format!("dual-{}", Uuid::new_v4());
+            let payload = Bytes::from(vec![0u8; event_size]);
+            let mut samples = Vec::new();
+            let mut errors_a = 0u64;
+            let mut errors_b = 0u64;
+
+            if barrier.worker_ready().await.is_err() {
+                return (samples, errors_a, errors_b);
+            }
+
+            while !has_stopped.load(Ordering::Relaxed) && !cancel_token.is_cancelled() {
+                let op_id = next_op_id.fetch_add(1, Ordering::Relaxed);
+                let event = EventData {
+                    payload: payload.clone(),
+                    event_type: "test".to_string(),
+                    tags: vec![stream_name.clone()],
+                };
+
+                let (res_a, res_b) = tokio::join!(
+                    timed(adapter_a.append(vec![event.clone()])),
+                    timed(adapter_b.append(vec![event])),
+                );
+
+                match (res_a, res_b) {
+                    (Ok(a), Ok(b)) => samples.push(PairedSample {
+                        op_id,
+                        a_us: a.as_micros() as u64,
+                        b_us: b.as_micros() as u64,
+                    }),
+                    (res_a, res_b) => {
+                        errors_a += res_a.is_err() as u64;
+                        errors_b += res_b.is_err() as u64;
+                    }
+                }
+            }
+            (samples, errors_a, errors_b)
+        });
+    }
+
+    barrier.release().await?;
+    println!("All {} writer client pairs ready", config.writers);
+
+    tokio::select! {
+        _ = tokio::time::sleep(Duration::from_secs(config.duration_seconds)) => {}
+        _ = cancel_token.cancelled() => {}
+    }
+    has_stopped.store(true, Ordering::Relaxed);
+
+    let mut samples = Vec::new();
+    let mut errors_a = 0u64;
+    let mut errors_b = 0u64;
+    while let Some(res) = set.join_next().await {
+        let (writer_samples, writer_errors_a, writer_errors_b) = res.expect("join");
+        samples.extend(writer_samples);
+        errors_a += writer_errors_a;
+        errors_b += writer_errors_b;
+    }
+    if cancel_token.is_cancelled() {
+        println!("Interrupted during dual-write run.");
+        anyhow::bail!("Interrupted");
+    }
+    samples.sort_by_key(|s| s.op_id);
+
+    let mut latency_a = LatencyRecorder::new();
+    let mut latency_b = LatencyRecorder::new();
+    let mut diffs: Vec<i64> = Vec::with_capacity(samples.len());
+    for sample in &samples {
+        latency_a.record(Duration::from_micros(sample.a_us));
+        latency_b.record(Duration::from_micros(sample.b_us));
+        diffs.push(sample.b_us as i64 - sample.a_us as i64);
+    }
+    let pairs = samples.len() as u64;
+    let mean_diff_us = diffs.iter().sum::<i64>() as f64 / pairs.max(1) as f64;
+    let a_faster_fraction = diffs.iter().filter(|d| **d > 0).count() as f64 / pairs.max(1) as f64;
+    diffs.sort_unstable();
+    let median_diff_us = diffs.get(diffs.len() / 2).copied().unwrap_or(0) as f64;
+
+    Ok(DualWriteResult {
+        store_a: store_a.name().to_string(),
+        store_b: store_b.name().to_string(),
+        config: config.clone(),
+        pairs,
+        errors_a,
+        errors_b,
+        latency_a: latency_a.to_stats(),
+        latency_b: latency_b.to_stats(),
+        mean_diff_us,
+        median_diff_us,
+        a_faster_fraction,
+        samples,
+    })
+}
+
+async fn timed<F: std::future::Future<Output = Result<()>>>(fut: F) -> Result<Duration> {
+    let started = Instant::now();
+    fut.await?;
+    Ok(started.elapsed())
+}
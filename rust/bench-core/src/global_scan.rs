@@ -0,0 +1,191 @@
+use crate::adapter::{EventData, EventStoreAdapter, ReadEvent, StoreManager};
+use crate::metrics::{LatencyRecorder, LatencyStats};
+use crate::workflow_support::{prepopulate_batches, run_on_started_store};
+use anyhow::Result;
+use bytes::Bytes;
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::Instant;
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+
+/// Adapters appending concurrently while prepopulating
+const PREPOPULATE_WRITERS: usize = 8;
+
+/// Settings for replaying a store's whole global log after prepopulating it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GlobalScanConfig {
+    /// Events stored before the scan
+    pub events: u64,
+    /// Streams the events are spread over
+    pub streams: u64,
+    /// Events per `read_all` call
+    pub page_size: u64,
+    pub event_size_bytes: usize,
+    /// Read the log as one stream (`read_all_stream`) instead of in pages, so the replay runs at
+    /// the store's streaming rate and the client holds only the events in flight
+    #[serde(default)]
+    pub streamed: bool,
+}
+
+/// Outcome of a global log scan
+#[derive(Debug, Clone, Serialize)]
+pub struct GlobalScanResult {
+    pub store: String,
+    pub config: GlobalScanConfig,
+    pub prepopulate_s: f64,
+    /// Events the scan returned, including any the store held before prepopulating
+    pub events_scanned: u64,
+    pub bytes_scanned: u64,
+    /// From the start of the read until the end of the log was reached
+    pub scan_s: f64,
+    pub scan_eps: f64,
+    pub scan_mb_per_s: f64,
+    /// From the start of the read until its first event arrived
+    pub first_event_ms: f64,
+    /// `read_all` calls of a paged scan
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pages: Option<u64>,
+    /// Latency of each `read_all` call of a paged scan
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page_latency: Option<LatencyStats>,
+}
+
+/// What a scan of the global log read
+#[derive(Default)]
+struct Scanned {
+    events: u64,
+    bytes: u64,
+    first_event: Option<Duration>,
+    pages: Option<u64>,
+    page_latency: Option<LatencyStats>,
+}
+
+impl Scanned {
+    fn add(&mut self, event: &ReadEvent, started: Instant) {
+        self.first_event.get_or_insert_with(|| started.elapsed());
+        self.events += 1;
+        self.bytes += event.payload.len() as u64;
+    }
+}
+
+/// Start the store, spread `events` over `streams`, then read the global log from the start in
+/// pages of `page_size` (or as one stream) until the end, timing the replay of the whole log
+pub async fn execute_global_scan(
+    store: Box<dyn StoreManager>,
+    config: &GlobalScanConfig,
+    cancel_token: CancellationToken,
+) -> Result<GlobalScanResult> {
+    if config.events == 0 || config.streams == 0 || config.page_size == 0 {
+        anyhow::bail!("Global scan requires events, streams and a page size > 0");
+    }
+
+    run_on_started_store(store, &cancel_token, "global scan run", async |store| {
+        scan_log(store, config).await
+    })
+    .await
+}
+
+async fn scan_log(store: &dyn StoreManager, config: &GlobalScanConfig) -> Result<GlobalScanResult> {
+    let reader = store.create_adapter()?;
+    // Fail fast for stores without a global log instead of after prepopulating
+    reader.read_all(None, Some(1)).await?;
+
+    println!("Prepopulating {} events over {} streams...", config.events, config.streams);
+    let started = Instant::now();
+    prepopulate(store, config).await?;
+    let prepopulate_s = started.elapsed().as_secs_f64();
+
+    let started = Instant::now();
+    let scanned = if config.streamed {
+        println!("Scanning the global log as one stream...");
+        scan_streamed(reader.as_ref(), started).await?
+    } else {
+        println!("Scanning the global log in pages of {}...", config.page_size);
+        scan_paged(reader.as_ref(), config.page_size, started).await?
+    };
+    let scan_s = started.elapsed().as_secs_f64();
+    if scanned.events < config.events {
+        println!("  ⚠ scanned {} events, {} were appended", scanned.events, config.events);
+    }
+
+    Ok(GlobalScanResult {
+        store: store.name().to_string(),
+        config: config.clone(),
+        prepopulate_s,
+        events_scanned: scanned.events,
+        bytes_scanned: scanned.bytes,
+        scan_s,
+        scan_eps: scanned.events as f64 / scan_s,
+        scan_mb_per_s: scanned.bytes as f64 / 1_000_000.0 / scan_s,
+        first_event_ms: scanned.first_event.unwrap_or_default().as_secs_f64() * 1000.0,
+        pages: scanned.pages,
+        page_latency: scanned.page_latency,
+    })
+}
+
+async fn scan_paged(reader: &dyn EventStoreAdapter, page_size: u64, started: Instant) -> Result<Scanned> {
+    let mut scanned = Scanned::default();
+    let mut page_latency = LatencyRecorder::new();
+    let mut pages = 0;
+    let mut after = None;
+    loop {
+        let page_started = Instant::now();
+        let page = reader.read_all(after, Some(page_size)).await?;
+        page_latency.record(page_started.elapsed());
+        let Some(last) = page.last() else {
+            break;
+        };
+        after = Some(last.offset);
+        pages += 1;
+        for event in &page {
+            scanned.add(event, started);
+        }
+    }
+    scanned.pages = Some(pages);
+    scanned.page_latency = Some(page_latency.to_stats());
+    Ok(scanned)
+}
+
+async fn scan_streamed(reader: &dyn EventStoreAdapter, started: Instant) -> Result<Scanned> {
+    let mut scanned = Scanned::default();
+    let mut events = reader.read_all_stream(None);
+    while let Some(event) = events.next().await {
+        scanned.add(&event?, started);
+    }
+    Ok(scanned)
+}
+
+/// Append `config.events` events, spread evenly over `config.streams` fresh streams, in batches
+/// to one stream each from several adapters at once
+async fn prepopulate(store: &dyn StoreManager, config: &GlobalScanConfig) -> Result<()> {
+    let writers: Vec<Arc<dyn EventStoreAdapter>> =
+        (0..PREPOPULATE_WRITERS).map(|_| store.create_adapter()).collect::<Result<_>>()?;
+    let run = Uuid::new_v4();
+    let payload = Bytes::from(vec![0u8; config.event_size_bytes]);
+    let mut batches = Vec::new();
+    for stream_idx in 0..config.streams {
+        let stream = format!("scan-{}-{}", run, stream_idx);
+        let events = config.events / config.streams + u64::from(stream_idx < config.events % config.streams);
+        batches.extend(prepopulate_batches(events).map(|batch| (stream.clone(), batch.end - batch.start)));
+    }
+    let mut appends = futures::stream::iter(batches.into_iter().enumerate())
+        .map(|(i, (stream, n))| {
+            let writer = writers[i % writers.len()].clone();
+            let events = (0..n)
+                .map(|_| EventData {
+                    payload: payload.clone(),
+                    event_type: "scan".to_string(),
+                    tags: vec![stream.clone()],
+                })
+                .collect();
+            async move { writer.append(events).await }
+        })
+        .buffer_unordered(PREPOPULATE_WRITERS);
+    while let Some(result) = appends.next().await {
+        result?;
+    }
+    Ok(())
+}
@@ -0,0 +1,137 @@
+use crate::adapter::EventStoreAdapter;
+use crate::metrics::{LatencyRecorder, LatencyStats};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::Instant;
+use tokio::task::JoinHandle;
+use tokio::time::MissedTickBehavior;
+
+/// Interval between health pings during a run
+pub const HEALTH_PING_INTERVAL: Duration = Duration::from_secs(1);
+/// A ping not answered within this time counts as failed
+const PING_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Availability of the store during a run, from pings on a dedicated connection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthStats {
+    pub pings: u64,
+    pub failed: u64,
+    /// Share of successful pings, in percent
+    pub availability_percent: f64,
+    /// Latency of the successful pings
+    pub latency: LatencyStats,
+    /// Longest time from a failed ping to the next successful one (or the end of the run)
+    pub longest_outage_s: f64,
+}
+
+/// Collects ping outcomes into [`HealthStats`]
+#[derive(Debug, Default)]
+struct HealthRecorder {
+    pings: u64,
+    failed: u64,
+    latency: LatencyRecorder,
+    outage_since: Option<Duration>,
+    longest_outage: Duration,
+}
+
+impl HealthRecorder {
+    /// Record a ping sent `at` (since the monitor started)
+    fn record(&mut self, at: Duration, result: Option<Duration>) {
+        self.pings += 1;
+        match result {
+            Some(latency) => {
+                self.latency.record(latency);
+                if let Some(since) = self.outage_since.take() {
+                    self.longest_outage = self.longest_outage.max(at - since);
+                }
+            }
+            None => {
+                self.failed += 1;
+                self.outage_since.get_or_insert(at);
+            }
+        }
+    }
+
+    fn finish(mut self, end: Duration) -> Option<HealthStats> {
+        if self.pings == 0 {
+            return None;
+        }
+        if let Some(since) = self.outage_since.take() {
+            self.longest_outage = self.longest_outage.max(end.saturating_sub(since));
+        }
+        Some(HealthStats {
+            pings: self.pings,
+            failed: self.failed,
+            availability_percent: (self.pings - self.failed) as f64 / self.pings as f64 * 100.0,
+            latency: self.latency.to_stats(),
+            longest_outage_s: self.longest_outage.as_secs_f64(),
+        })
+    }
+}
+
+/// Pings the store at a low rate for the duration of a run
+pub struct HealthMonitor {
+    stop_tx: Option<tokio::sync::oneshot::Sender<()>>,
+    task: JoinHandle<Option<HealthStats>>,
+}
+
+impl HealthMonitor {
+    /// Start pinging through `adapter`, which should not be shared with the workload so pings
+    /// don't queue behind its operations
+    pub fn start(adapter: Arc<dyn EventStoreAdapter>, interval: Duration) -> Self {
+        let (stop_tx, mut stop_rx) = tokio::sync::oneshot::channel::<()>();
+        let task = tokio::spawn(async move {
+            let started = Instant::now();
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
+            let mut recorder = HealthRecorder::default();
+            loop {
+                tokio::select! {
+                    _ = ticker.tick() => {}
+                    _ = &mut stop_rx => break,
+                }
+                let at = started.elapsed();
+                let t0 = Instant::now();
+                let result = match tokio::time::timeout(PING_TIMEOUT, adapter.ping()).await {
+                    Ok(Ok(())) => Some(t0.elapsed()),
+                    _ => None,
+                };
+                recorder.record(at, result);
+            }
+            recorder.finish(started.elapsed())
+        });
+        Self {
+            stop_tx: Some(stop_tx),
+            task,
+        }
+    }
+
+    /// Stop pinging; `None` if no ping was sent
+    pub async fn stop(mut self) -> Option<HealthStats> {
+        if let Some(stop_tx) = self.stop_tx.take() {
+            let _ = stop_tx.send(());
+        }
+        self.task.await.ok().flatten()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn measures_availability_and_outages() {
+        let ok = Some(Duration::from_millis(2));
+        let mut recorder = HealthRecorder::default();
+        for (second, result) in [ok, None, None, ok, None].into_iter().enumerate() {
+            recorder.record(Duration::from_secs(second as u64), result);
+        }
+        let stats = recorder.finish(Duration::from_secs(5)).unwrap();
+        assert_eq!((stats.pings, stats.failed), (5, 3));
+        assert_eq!(stats.availability_percent, 40.0);
+        // Failed at 1s, answered again at 3s
+        assert_eq!(stats.longest_outage_s, 2.0);
+        assert!(HealthRecorder::default().finish(Duration::ZERO).is_none());
+    }
+}
@@ -0,0 +1,339 @@
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+use tokio::time::MissedTickBehavior;
+
+/// One host-level sample, written to host_stats.jsonl
+///
+/// Values come from /proc and are `None` where unavailable (e.g. on macOS), so consumers can
+/// tell whether the benchmark host itself was saturated during the run.
+#[derive(Debug, Clone, Serialize)]
+pub struct HostStatsSample {
+    /// Milliseconds since host monitoring started (just before the workload)
+    pub t_rel_ms: u64,
+    /// Busy share of all host CPUs (0-100) since the previous sample
+    pub cpu_percent: Option<f64>,
+    /// Share of CPU time spent waiting for I/O (0-100) since the previous sample
+    pub iowait_percent: Option<f64>,
+    pub load_avg_1m: Option<f64>,
+    pub memory_used_percent: Option<f64>,
+    /// Share of time some tasks were stalled on memory over the last 10s (PSI `some avg10`)
+    pub memory_pressure_avg10: Option<f64>,
+    /// Utilization (0-100) of the busiest block device since the previous sample
+    pub disk_util_percent: Option<f64>,
+    /// Name of the busiest block device
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub disk_device: Option<String>,
+    /// Current CPU frequency as a share of nominal (base frequency where the driver reports
+    /// it, otherwise the maximum), averaged over all CPUs
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cpu_freq_percent: Option<f64>,
+    /// Whether `cpu_freq_percent` is relative to the base frequency rather than the maximum
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cpu_freq_of_base: Option<bool>,
+    /// Temperature of the hottest thermal zone
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cpu_temp_c: Option<f64>,
+    /// Thermal throttle events counted by the kernel (core and package, all CPUs) since the
+    /// previous sample
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub throttle_events: Option<u64>,
+}
+
+/// Cumulative counters needed to turn the next reading into rates
+#[derive(Default)]
+struct Counters {
+    /// (busy jiffies, iowait jiffies, total jiffies)
+    cpu: Option<(u64, u64, u64)>,
+    /// (device, I/O ticks in ms)
+    disks: Vec<(String, u64)>,
+    throttle_count: Option<u64>,
+    read_at: Option<Instant>,
+}
+
+/// Samples host CPU, load, memory and disk utilization in the background
+pub struct HostMonitor {
+    samples: Arc<Mutex<Vec<HostStatsSample>>>,
+    stop_tx: Option<tokio::sync::oneshot::Sender<()>>,
+    monitor_task: Option<JoinHandle<()>>,
+}
+
+impl HostMonitor {
+    pub fn start(interval: Duration) -> Self {
+        let samples = Arc::new(Mutex::new(Vec::new()));
+        let samples_arc = samples.clone();
+        let (stop_tx, mut stop_rx) = tokio::sync::oneshot::channel::<()>();
+
+        let monitor_task = tokio::spawn(async move {
+            let started = Instant::now();
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
+            let mut counters = Counters::default();
+
+            loop {
+                tokio::select! {
+                    _ = &mut stop_rx => break,
+                    _ = ticker.tick() => {
+                        let first = counters.read_at.is_none();
+                        let sample = read_sample(started, &mut counters);
+                        // The first reading only establishes the counter baseline
+                        if !first {
+                            samples_arc.lock().await.push(sample);
+                        }
+                    }
+                }
+            }
+        });
+
+        Self {
+            samples,
+            stop_tx: Some(stop_tx),
+            monitor_task: Some(monitor_task),
+        }
+    }
+
+    pub async fn stop(mut self) -> Vec<HostStatsSample> {
+        if let Some(stop_tx) = self.stop_tx.take() {
+            let _ = stop_tx.send(());
+        }
+        if let Some(task) = self.monitor_task.take() {
+            let _ = task.await;
+        }
+        std::mem::take(&mut *self.samples.lock().await)
+    }
+}
+
+/// CPU time (user + system) used so far by this process across all threads
+pub fn process_cpu_time() -> Option<Duration> {
+    // USER_HZ is 100 on all mainstream Linux platforms
+    const TICKS_PER_SECOND: u64 = 100;
+    let stat = std::fs::read_to_string("/proc/self/stat").ok()?;
+    // Skip past the command name, which may contain spaces; utime and stime are then the
+    // 12th and 13th fields
+    let mut fields = stat.rsplit_once(')')?.1.split_whitespace().skip(11);
+    let utime: u64 = fields.next()?.parse().ok()?;
+    let stime: u64 = fields.next()?.parse().ok()?;
+    Some(Duration::from_millis((utime + stime) * 1000 / TICKS_PER_SECOND))
+}
+
+fn read_sample(started: Instant, counters: &mut Counters) -> HostStatsSample {
+    let read_at = Instant::now();
+
+    let cpu = read_cpu_jiffies();
+    let (cpu_percent, iowait_percent) = match (cpu, counters.cpu) {
+        (Some((busy, iowait, total)), Some((prev_busy, prev_iowait, prev_total)))
+            if total > prev_total =>
+        {
+            let total_delta = (total - prev_total) as f64;
+            (
+                Some(busy.saturating_sub(prev_busy) as f64 / total_delta * 100.0),
+                Some(iowait.saturating_sub(prev_iowait) as f64 / total_delta * 100.0),
+            )
+        }
+        _ => (None, None),
+    };
+    counters.cpu = cpu;
+
+    let disks = read_disk_io_ticks();
+    let mut busiest: Option<(String, f64)> = None;
+    if let Some(prev_read_at) = counters.read_at {
+        let wall_ms = read_at.duration_since(prev_read_at).as_secs_f64() * 1000.0;
+        for (device, ticks) in &disks {
+            let Some((_, prev_ticks)) = counters.disks.iter().find(|(d, _)| d == device) else {
+                continue;
+            };
+            let util = (ticks.saturating_sub(*prev_ticks) as f64 / wall_ms.max(1.0) * 100.0).min(100.0);
+            if busiest.as_ref().is_none_or(|(_, best)| util > *best) {
+                busiest = Some((device.clone(), util));
+            }
+        }
+    }
+    counters.disks = disks;
+    counters.read_at = Some(read_at);
+
+    let throttle_count = read_throttle_count();
+    let throttle_events = match (throttle_count, counters.throttle_count) {
+        (Some(count), Some(prev)) => Some(count.saturating_sub(prev)),
+        _ => None,
+    };
+    counters.throttle_count = throttle_count;
+    let cpu_freq = read_cpu_freq_percent();
+
+    HostStatsSample {
+        t_rel_ms: read_at.duration_since(started).as_millis() as u64,
+        cpu_percent,
+        iowait_percent,
+        load_avg_1m: read_load_avg_1m(),
+        memory_used_percent: read_memory_used_percent(),
+        memory_pressure_avg10: read_memory_pressure_avg10(),
+        disk_util_percent: busiest.as_ref().map(|(_, util)| *util),
+        disk_device: busiest.map(|(device, _)| device),
+        cpu_freq_percent: cpu_freq.map(|(percent, _)| percent),
+        cpu_freq_of_base: cpu_freq.map(|(_, of_base)| of_base),
+        cpu_temp_c: read_max_temp_c(),
+        throttle_events,
+    }
+}
+
+/// (busy, iowait, total) jiffies from the aggregate `cpu` line of /proc/stat
+fn read_cpu_jiffies() -> Option<(u64, u64, u64)> {
+    let stat = std::fs::read_to_string("/proc/stat").ok()?;
+    let line = stat.lines().find(|line| line.starts_with("cpu "))?;
+    // user nice system idle iowait irq softirq steal (guest time is already in user/nice)
+    let values: Vec<u64> = line
+        .split_whitespace()
+        .skip(1)
+        .take(8)
+        .filter_map(|v| v.parse().ok())
+        .collect();
+    if values.len() < 5 {
+        return None;
+    }
+    let total: u64 = values.iter().sum();
+    let idle = values[3];
+    let iowait = values[4];
+    Some((total - idle - iowait, iowait, total))
+}
+
+fn read_load_avg_1m() -> Option<f64> {
+    std::fs::read_to_string("/proc/loadavg")
+        .ok()?
+        .split_whitespace()
+        .next()?
+        .parse()
+        .ok()
+}
+
+fn read_memory_used_percent() -> Option<f64> {
+    let meminfo = std::fs::read_to_string("/proc/meminfo").ok()?;
+    let field = |name: &str| -> Option<u64> {
+        meminfo
+            .lines()
+            .find(|line| line.starts_with(name))?
+            .split_whitespace()
+            .nth(1)?
+            .parse()
+            .ok()
+    };
+    let total = field("MemTotal:")?;
+    let available = field("MemAvailable:")?;
+    if total == 0 {
+        return None;
+    }
+    Some(total.saturating_sub(available) as f64 / total as f64 * 100.0)
+}
+
+fn read_memory_pressure_avg10() -> Option<f64> {
+    let pressure = std::fs::read_to_string("/proc/pressure/memory").ok()?;
+    pressure
+        .lines()
+        .find(|line| line.starts_with("some "))?
+        .split_whitespace()
+        .find_map(|field| field.strip_prefix("avg10="))?
+        .parse()
+        .ok()
+}
+
+/// Milliseconds spent doing I/O per block device (field 13 of /proc/diskstats), skipping
+/// loop and RAM devices
+fn read_disk_io_ticks() -> Vec<(String, u64)> {
+    let Ok(diskstats) = std::fs::read_to_string("/proc/diskstats") else {
+        return Vec::new();
+    };
+    diskstats
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let device = *fields.get(2)?;
+            if device.starts_with("loop") || device.starts_with("ram") {
+                return None;
+            }
+            let io_ticks = fields.get(12)?.parse().ok()?;
+            Some((device.to_string(), io_ticks))
+        })
+        .collect()
+}
+
+/// Directories of the individual CPUs (`cpu0`, `cpu1`, ...) in /sys
+fn cpu_dirs() -> Vec<std::path::PathBuf> {
+    let Ok(entries) = std::fs::read_dir("/sys/devices/system/cpu") else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            name.strip_prefix("cpu").is_some_and(|n| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()))
+        })
+        .map(|entry| entry.path())
+        .collect()
+}
+
+fn read_u64(path: &std::path::Path) -> Option<u64> {
+    std::fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+/// Average current/nominal frequency over all CPUs with cpufreq, and whether nominal is the
+/// base frequency (only some drivers, e.g. intel_pstate, report it)
+fn read_cpu_freq_percent() -> Option<(f64, bool)> {
+    let mut ratios = Vec::new();
+    let mut of_base = true;
+    for dir in cpu_dirs() {
+        let cpufreq = dir.join("cpufreq");
+        let Some(current) = read_u64(&cpufreq.join("scaling_cur_freq")) else {
+            continue;
+        };
+        let nominal = match read_u64(&cpufreq.join("base_frequency")) {
+            Some(base) => base,
+            None => {
+                of_base = false;
+                match read_u64(&cpufreq.join("cpuinfo_max_freq")) {
+                    Some(max) => max,
+                    None => continue,
+                }
+            }
+        };
+        if nominal > 0 {
+            ratios.push(current as f64 / nominal as f64 * 100.0);
+        }
+    }
+    if ratios.is_empty() {
+        return None;
+    }
+    Some((ratios.iter().sum::<f64>() / ratios.len() as f64, of_base))
+}
+
+/// Highest temperature over the thermal zones, in °C
+fn read_max_temp_c() -> Option<f64> {
+    let entries = std::fs::read_dir("/sys/class/thermal").ok()?;
+    entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_name().to_string_lossy().starts_with("thermal_zone"))
+        .filter_map(|entry| read_u64(&entry.path().join("temp")))
+        .map(|millidegrees| millidegrees as f64 / 1000.0)
+        .max_by(f64::total_cmp)
+}
+
+/// Thermal throttle events so far: core events summed over all CPUs plus package events
+/// once per package (every CPU of a package reports the same count; x86 only)
+fn read_throttle_count() -> Option<u64> {
+    let mut cores = None;
+    let mut packages = std::collections::BTreeMap::new();
+    for dir in cpu_dirs() {
+        let throttle = dir.join("thermal_throttle");
+        if let Some(count) = read_u64(&throttle.join("core_throttle_count")) {
+            *cores.get_or_insert(0) += count;
+        }
+        if let Some(count) = read_u64(&throttle.join("package_throttle_count")) {
+            let package = read_u64(&dir.join("topology/physical_package_id")).unwrap_or(0);
+            packages.insert(package, count);
+        }
+    }
+    if cores.is_none() && packages.is_empty() {
+        return None;
+    }
+    Some(cores.unwrap_or(0) + packages.values().sum::<u64>())
+}
@@ -0,0 +1,281 @@
+use crate::adapter::{AdminOp, AppendCondition, AppendOutcome, EventData, EventStoreAdapter, EventStream, ReadEvent, ReadRequest, ReadStream, RetentionPolicy};
+use crate::registry::MetricsRegistry;
+use async_trait::async_trait;
+use std::cell::RefCell;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Sub-phase of an adapter operation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Phase {
+    /// Turning `EventData` into the driver's request types
+    Serialize,
+    /// Awaiting the driver (request, response and streamed pages)
+    Network,
+    /// Turning the driver's responses into `ReadEvent`s
+    Deserialize,
+    /// Processing time the server reported for the operation (e.g. in `server-timing` gRPC
+    /// response metadata), for stores that send one
+    Server,
+    /// The operation's time minus the server-reported time: driver, serialization and
+    /// network overhead (derived by `Instrumented` when the adapter recorded `Server`)
+    Client,
+}
+
+const PHASES: [Phase; 5] = [Phase::Serialize, Phase::Network, Phase::Deserialize, Phase::Server, Phase::Client];
+
+impl Phase {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Phase::Serialize => "serialize",
+            Phase::Network => "network",
+            Phase::Deserialize => "deserialize",
+            Phase::Server => "server",
+            Phase::Client => "client",
+        }
+    }
+}
+
+/// Called once per phase an operation went through, with the operation name (`append`,
+/// `read`, ...) and the total time the operation spent in that phase
+pub type PhaseCallback = Arc<dyn Fn(&'static str, Phase, Duration) + Send + Sync>;
+
+tokio::task_local! {
+    /// Time spent per phase by the operation currently running under `Instrumented`
+    static PHASE_TIMES: RefCell<[Option<Duration>; 5]>;
+}
+
+/// Whether the current operation runs under `Instrumented` (adapters can skip extra timing
+/// work otherwise)
+pub fn is_instrumented() -> bool {
+    PHASE_TIMES.try_with(|_| ()).is_ok()
+}
+
+/// Add `elapsed` to the current operation's time in `phase`; a no-op outside `Instrumented`.
+/// For phases an adapter measures itself, e.g. interleaved with a streamed read.
+pub fn record_phase(phase: Phase, elapsed: Duration) {
+    let _ = PHASE_TIMES.try_with(|times| {
+        let slot = &mut times.borrow_mut()[phase as usize];
+        *slot = Some(slot.unwrap_or_default() + elapsed);
+    });
+}
+
+/// Record the processing time in a `server-timing` value (`total;dur=1.5`, in milliseconds,
+/// as in the HTTP header) as the current operation's `Server` phase; the `total` entry if
+/// there is one, otherwise the first with a duration
+pub fn record_server_timing(value: Option<&str>) {
+    if let Some(elapsed) = value.and_then(parse_server_timing) {
+        record_phase(Phase::Server, elapsed);
+    }
+}
+
+fn parse_server_timing(value: &str) -> Option<Duration> {
+    let entries: Vec<(&str, f64)> = value
+        .split(',')
+        .filter_map(|entry| {
+            let mut params = entry.split(';').map(str::trim);
+            let name = params.next()?;
+            let dur = params.find_map(|p| p.strip_prefix("dur="))?.parse::<f64>().ok()?;
+            (dur.is_finite() && dur >= 0.0).then_some((name, dur))
+        })
+        .collect();
+    let (_, ms) = entries.iter().find(|(name, _)| *name == "total").or(entries.first())?;
+    // A duration too long for a Duration is as useless as a missing one
+    Duration::try_from_secs_f64(ms / 1000.0).ok()
+}
+
+/// Run `f`, counting its time towards `phase`
+pub fn time_phase<T>(phase: Phase, f: impl FnOnce() -> T) -> T {
+    if !is_instrumented() {
+        return f();
+    }
+    let started = Instant::now();
+    let out = f();
+    record_phase(phase, started.elapsed());
+    out
+}
+
+/// Await `fut`, counting its time towards `phase`
+pub async fn time_phase_async<T>(phase: Phase, fut: impl Future<Output = T>) -> T {
+    if !is_instrumented() {
+        return fut.await;
+    }
+    let started = Instant::now();
+    let out = fut.await;
+    record_phase(phase, started.elapsed());
+    out
+}
+
+/// Adapter wrapper reporting the sub-phase timings the wrapped adapter records with
+/// `time_phase`/`time_phase_async`, so a slow store can be attributed to its driver or its
+/// server. Adapters that record no phases report nothing.
+pub struct Instrumented<A: ?Sized> {
+    inner: Arc<A>,
+    callback: PhaseCallback,
+}
+
+impl<A: EventStoreAdapter + ?Sized> Instrumented<A> {
+    pub fn new(inner: Arc<A>, callback: PhaseCallback) -> Self {
+        Self { inner, callback }
+    }
+
+    async fn run<T: Send>(&self, op: &'static str, fut: impl Future<Output = T> + Send) -> T {
+        let started = Instant::now();
+        let (out, mut times) = PHASE_TIMES
+            .scope(RefCell::new([None; 5]), async {
+                let out = fut.await;
+                (out, PHASE_TIMES.with(|times| *times.borrow()))
+            })
+            .await;
+        if let Some(server) = times[Phase::Server as usize] {
+            times[Phase::Client as usize] = Some(started.elapsed().saturating_sub(server));
+        }
+        for (phase, elapsed) in PHASES.into_iter().zip(times) {
+            if let Some(elapsed) = elapsed {
+                (self.callback)(op, phase, elapsed);
+            }
+        }
+        out
+    }
+}
+
+#[async_trait]
+impl<A: EventStoreAdapter + ?Sized> EventStoreAdapter for Instrumented<A> {
+    async fn append(&self, events: Vec<EventData>) -> anyhow::Result<()> {
+        self.run("append", self.inner.append(events)).await
+    }
+
+    async fn read(&self, req: ReadRequest) -> anyhow::Result<Vec<ReadEvent>> {
+        self.run("read", self.inner.read(req)).await
+    }
+
+    async fn append_conditional(
+        &self,
+        events: Vec<EventData>,
+        condition: AppendCondition,
+    ) -> anyhow::Result<AppendOutcome> {
+        self.run("append_conditional", self.inner.append_conditional(events, condition))
+            .await
+    }
+
+    async fn list_streams(&self, prefix: &str, limit: Option<u64>) -> anyhow::Result<Vec<String>> {
+        self.run("list_streams", self.inner.list_streams(prefix, limit)).await
+    }
+
+    async fn read_all(&self, after: Option<u64>, limit: Option<u64>) -> anyhow::Result<Vec<ReadEvent>> {
+        self.run("read_all", self.inner.read_all(after, limit)).await
+    }
+
+    // The phases of a streamed read or subscription don't add up to one operation, so they
+    // are passed through unmeasured
+    fn read_stream(&self, req: ReadRequest) -> ReadStream<'_> {
+        self.inner.read_stream(req)
+    }
+
+    fn read_all_stream(&self, after: Option<u64>) -> ReadStream<'_> {
+        self.inner.read_all_stream(after)
+    }
+
+    async fn subscribe(&self, stream: &str, from_offset: Option<u64>) -> anyhow::Result<EventStream> {
+        self.inner.subscribe(stream, from_offset).await
+    }
+
+    async fn set_retention(&self, stream: &str, policy: &RetentionPolicy) -> anyhow::Result<()> {
+        self.run("set_retention", self.inner.set_retention(stream, policy)).await
+    }
+
+    async fn admin(&self, op: AdminOp) -> anyhow::Result<()> {
+        self.run("admin", self.inner.admin(op)).await
+    }
+
+    async fn ping(&self) -> anyhow::Result<()> {
+        self.run("ping", self.inner.ping()).await
+    }
+}
+
+/// Collects one adapter's phase timings as `<op>_<phase>_latency` histograms. Each adapter
+/// gets its own recorder, so the lock is uncontended.
+#[derive(Debug, Clone, Default)]
+pub struct PhaseRecorder {
+    registry: Arc<Mutex<MetricsRegistry>>,
+}
+
+impl PhaseRecorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn callback(&self) -> PhaseCallback {
+        let registry = self.registry.clone();
+        Arc::new(move |op, phase, elapsed| {
+            if let Ok(mut registry) = registry.lock() {
+                registry.record(&format!("{}_{}_latency", op, phase.as_str()), elapsed);
+            }
+        })
+    }
+
+    pub fn registry(&self) -> MetricsRegistry {
+        self.registry.lock().map(|r| r.clone()).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct SlowAdapter;
+
+    #[async_trait]
+    impl EventStoreAdapter for SlowAdapter {
+        async fn append(&self, _events: Vec<EventData>) -> anyhow::Result<()> {
+            time_phase(Phase::Serialize, || ());
+            time_phase_async(Phase::Network, tokio::time::sleep(Duration::from_millis(2))).await;
+            time_phase_async(Phase::Network, tokio::time::sleep(Duration::from_millis(2))).await;
+            record_server_timing(Some("db;dur=0.5, total;dur=3"));
+            Ok(())
+        }
+
+        async fn read(&self, _req: ReadRequest) -> anyhow::Result<Vec<ReadEvent>> {
+            Ok(Vec::new())
+        }
+    }
+
+    #[tokio::test]
+    async fn reports_summed_time_per_phase_once_per_operation() {
+        let recorder = PhaseRecorder::new();
+        let adapter = Instrumented::new(Arc::new(SlowAdapter), recorder.callback());
+        adapter.append(Vec::new()).await.unwrap();
+        adapter.append(Vec::new()).await.unwrap();
+
+        let registry = recorder.registry();
+        let Some(crate::registry::CustomMetric::Histogram(network)) =
+            registry.get("append_network_latency")
+        else {
+            panic!("no network histogram");
+        };
+        assert_eq!(network.hist.len(), 2);
+        assert!(network.hist.min() >= 4_000);
+        assert!(registry.get("append_serialize_latency").is_some());
+        assert!(registry.get("append_deserialize_latency").is_none());
+        let Some(crate::registry::CustomMetric::Histogram(server)) = registry.get("append_server_latency") else {
+            panic!("no server histogram");
+        };
+        assert_eq!(server.hist.max(), server.hist.highest_equivalent(3_000));
+        let Some(crate::registry::CustomMetric::Histogram(client)) = registry.get("append_client_latency") else {
+            panic!("no client histogram");
+        };
+        assert!(client.hist.min() >= 1_000);
+        assert!(!is_instrumented());
+    }
+
+    #[test]
+    fn ignores_malformed_server_timing_headers() {
+        assert_eq!(parse_server_timing("db;dur=0.5, total;dur=3"), Some(Duration::from_millis(3)));
+        assert_eq!(parse_server_timing("db;dur=2"), Some(Duration::from_millis(2)));
+        for malformed in ["", "total", "total;dur=abc", "total;dur=-1", "total;dur=NaN", "total;dur=1e300"] {
+            assert_eq!(parse_server_timing(malformed), None, "{}", malformed);
+        }
+    }
+}
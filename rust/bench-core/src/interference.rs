@@ -0,0 +1,148 @@
+use crate::metrics::RawSample;
+use serde::{Deserialize, Serialize};
+
+/// Width of the windows write rate and read latency are compared over
+pub const INTERFERENCE_WINDOW_MS: u64 = 1000;
+/// Number of write-rate buckets read latency is reported for
+const BUCKETS: usize = 10;
+
+/// Read latency in the windows of one write-rate decile
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WriteRateBucket {
+    /// Write rate range (appends/s) of the windows in this bucket
+    pub min_write_ops_per_s: f64,
+    pub max_write_ops_per_s: f64,
+    pub windows: usize,
+    /// Sampled reads in these windows
+    pub reads: usize,
+    pub read_p50_ms: f64,
+    pub read_p99_ms: f64,
+}
+
+/// How read latency in a mixed run varied with the concurrent write rate
+///
+/// The run is cut into fixed windows; each window's write rate (estimated from the sampled
+/// appends) is paired with the latency of the reads sampled in it. Windows are then ranked by
+/// write rate and grouped into deciles, so interference shows up as read p99 climbing with the
+/// decile.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InterferenceStats {
+    pub window_ms: u64,
+    /// Windows containing at least one sampled read
+    pub windows: usize,
+    /// Pearson correlation between a window's write rate and its read p99 (None when either
+    /// does not vary)
+    pub write_rate_read_p99_correlation: Option<f64>,
+    /// Read p99 in the busiest write decile divided by read p99 in the quietest
+    pub top_to_bottom_read_p99_ratio: Option<f64>,
+    /// Lowest to highest write rate
+    pub by_write_rate: Vec<WriteRateBucket>,
+}
+
+struct Window {
+    write_ops_per_s: f64,
+    /// Sorted latencies of the successful reads started in the window
+    read_latencies_us: Vec<u64>,
+}
+
+/// Compare read latency to the concurrent write rate; `None` unless the samples contain both
+/// appends and reads over at least two windows
+pub fn analyze_interference(samples: &[RawSample], sample_rate: u64) -> Option<InterferenceStats> {
+    if sample_rate == 0
+        || !samples.iter().any(|s| s.op == "append")
+        || !samples.iter().any(|s| s.op == "read")
+    {
+        return None;
+    }
+    let window_us = INTERFERENCE_WINDOW_MS * 1000;
+    let window_count = (samples.iter().map(|s| s.t_rel_us).max()? / window_us + 1) as usize;
+    let mut writes = vec![0u64; window_count];
+    let mut reads: Vec<Vec<u64>> = vec![Vec::new(); window_count];
+    for sample in samples.iter().filter(|s| s.ok) {
+        let idx = (sample.t_rel_us / window_us) as usize;
+        match sample.op {
+            "append" => writes[idx] += 1,
+            "read" => reads[idx].push(sample.latency_us),
+            _ => {}
+        }
+    }
+
+    // Each sampled append stands for `sample_rate` appends
+    let scale = sample_rate as f64 * 1000.0 / INTERFERENCE_WINDOW_MS as f64;
+    let mut windows: Vec<Window> = writes
+        .into_iter()
+        .zip(reads)
+        .filter(|(_, reads)| !reads.is_empty())
+        .map(|(writes, mut read_latencies_us)| {
+            read_latencies_us.sort_unstable();
+            Window {
+                write_ops_per_s: writes as f64 * scale,
+                read_latencies_us,
+            }
+        })
+        .collect();
+    if windows.len() < 2 {
+        return None;
+    }
+    windows.sort_by(|a, b| a.write_ops_per_s.total_cmp(&b.write_ops_per_s));
+
+    let rates: Vec<f64> = windows.iter().map(|w| w.write_ops_per_s).collect();
+    let p99s: Vec<f64> = windows
+        .iter()
+        .map(|w| percentile_us(&w.read_latencies_us, 0.99) as f64)
+        .collect();
+
+    let bucket_count = BUCKETS.min(windows.len());
+    let by_write_rate: Vec<WriteRateBucket> = (0..bucket_count)
+        .map(|b| {
+            let group = &windows[b * windows.len() / bucket_count..(b + 1) * windows.len() / bucket_count];
+            let mut latencies: Vec<u64> = group
+                .iter()
+                .flat_map(|w| w.read_latencies_us.iter().copied())
+                .collect();
+            latencies.sort_unstable();
+            WriteRateBucket {
+                min_write_ops_per_s: group[0].write_ops_per_s,
+                max_write_ops_per_s: group[group.len() - 1].write_ops_per_s,
+                windows: group.len(),
+                reads: latencies.len(),
+                read_p50_ms: percentile_us(&latencies, 0.50) as f64 / 1000.0,
+                read_p99_ms: percentile_us(&latencies, 0.99) as f64 / 1000.0,
+            }
+        })
+        .collect();
+
+    let bottom = by_write_rate.first().map(|b| b.read_p99_ms).unwrap_or(0.0);
+    let top = by_write_rate.last().map(|b| b.read_p99_ms).unwrap_or(0.0);
+    Some(InterferenceStats {
+        window_ms: INTERFERENCE_WINDOW_MS,
+        windows: windows.len(),
+        write_rate_read_p99_correlation: pearson(&rates, &p99s),
+        top_to_bottom_read_p99_ratio: (bottom > 0.0).then(|| top / bottom),
+        by_write_rate,
+    })
+}
+
+/// Nearest-rank percentile of sorted values
+fn percentile_us(sorted: &[u64], quantile: f64) -> u64 {
+    if sorted.is_empty() {
+        return 0;
+    }
+    sorted[((sorted.len() - 1) as f64 * quantile).round() as usize]
+}
+
+fn pearson(xs: &[f64], ys: &[f64]) -> Option<f64> {
+    let n = xs.len() as f64;
+    let mean_x = xs.iter().sum::<f64>() / n;
+    let mean_y = ys.iter().sum::<f64>() / n;
+    let (mut cov, mut var_x, mut var_y) = (0.0, 0.0, 0.0);
+    for (x, y) in xs.iter().zip(ys) {
+        cov += (x - mean_x) * (y - mean_y);
+        var_x += (x - mean_x).powi(2);
+        var_y += (y - mean_y).powi(2);
+    }
+    if var_x == 0.0 || var_y == 0.0 {
+        return None;
+    }
+    Some(cov / (var_x * var_y).sqrt())
+}
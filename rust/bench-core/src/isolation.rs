@@ -0,0 +1,153 @@
+use anyhow::{bail, Context, Result};
+use bollard::container::UpdateContainerOptions;
+use bollard::Docker;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Contiguous range of host CPUs a run's containers are pinned to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CpuSet {
+    pub first: usize,
+    pub last: usize,
+}
+
+/// Docker `--cpuset-cpus` notation (`0-3`)
+impl fmt::Display for CpuSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.first == self.last {
+            write!(f, "{}", self.first)
+        } else {
+            write!(f, "{}-{}", self.first, self.last)
+        }
+    }
+}
+
+/// Split `total_cpus` into `slots` disjoint, equally sized CPU sets (leftover CPUs stay unused)
+pub fn partition_cpus(total_cpus: usize, slots: usize) -> Result<Vec<CpuSet>> {
+    if slots == 0 {
+        bail!("at least one slot is needed");
+    }
+    if slots > total_cpus {
+        bail!("cannot give {} parallel runs disjoint CPUs on a host with {} CPUs", slots, total_cpus);
+    }
+    let per_slot = total_cpus / slots;
+    Ok((0..slots)
+        .map(|slot| CpuSet {
+            first: slot * per_slot,
+            last: (slot + 1) * per_slot - 1,
+        })
+        .collect())
+}
+
+/// Restrict running containers to `cpu_set`
+pub async fn pin_containers(container_ids: &[String], cpu_set: CpuSet) -> Result<()> {
+    let docker = Docker::connect_with_local_defaults()?;
+    for id in container_ids {
+        let options = UpdateContainerOptions::<String> {
+            cpuset_cpus: Some(cpu_set.to_string()),
+            ..Default::default()
+        };
+        docker
+            .update_container(id, options)
+            .await
+            .with_context(|| format!("Failed to pin container {} to CPUs {}", id, cpu_set))?;
+    }
+    Ok(())
+}
+
+/// CPU and memory limits for each of a store's containers (`resources` in a workload config,
+/// `--cpus` and `--memory` on `run`), so every store gets the same resource envelope
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ResourceLimits {
+    /// CPUs a container may use, fractions allowed (Docker's `--cpus`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpus: Option<f64>,
+    /// Memory a container may use, such as `512m` or `4g` (Docker's `--memory`); swap is not
+    /// allowed on top
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory: Option<String>,
+}
+
+impl ResourceLimits {
+    pub fn is_empty(&self) -> bool {
+        self.cpus.is_none() && self.memory.is_none()
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if self.cpus.is_some_and(|cpus| !(cpus.is_finite() && cpus > 0.0)) {
+            bail!("resources cpus must be > 0");
+        }
+        self.memory_bytes()?;
+        Ok(())
+    }
+
+    /// These limits with any set in `overrides` taking precedence
+    pub fn overridden_by(&self, overrides: &ResourceLimits) -> ResourceLimits {
+        ResourceLimits {
+            cpus: overrides.cpus.or(self.cpus),
+            memory: overrides.memory.clone().or_else(|| self.memory.clone()),
+        }
+    }
+
+    pub fn memory_bytes(&self) -> Result<Option<u64>> {
+        let Some(memory) = &self.memory else {
+            return Ok(None);
+        };
+        let memory = memory.trim().to_ascii_lowercase();
+        let (number, unit) = match memory.strip_suffix('b').unwrap_or(&memory) {
+            m if m.ends_with('k') => (&m[..m.len() - 1], 1u64 << 10),
+            m if m.ends_with('m') => (&m[..m.len() - 1], 1 << 20),
+            m if m.ends_with('g') => (&m[..m.len() - 1], 1 << 30),
+            m => (m, 1),
+        };
+        match number.trim().parse::<f64>() {
+            Ok(value) if value.is_finite() && value > 0.0 => Ok(Some((value * unit as f64) as u64)),
+            _ => bail!("resources memory must be a size such as 512m or 4g, got '{}'", memory),
+        }
+    }
+}
+
+/// Apply `limits` to each of the running containers
+pub async fn limit_containers(container_ids: &[String], limits: &ResourceLimits) -> Result<()> {
+    if container_ids.is_empty() {
+        return Ok(());
+    }
+    let memory = limits.memory_bytes()?.map(|bytes| bytes as i64);
+    let docker = Docker::connect_with_local_defaults()?;
+    for id in container_ids {
+        let options = UpdateContainerOptions::<String> {
+            nano_cpus: limits.cpus.map(|cpus| (cpus * 1e9) as i64),
+            memory,
+            memory_swap: memory,
+            ..Default::default()
+        };
+        docker
+            .update_container(id, options)
+            .await
+            .with_context(|| format!("Failed to limit the resources of container {}", id))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn partitions_cpus_into_disjoint_equal_sets() {
+        let sets = partition_cpus(10, 3).unwrap();
+        let names: Vec<String> = sets.iter().map(|s| s.to_string()).collect();
+        assert_eq!(names, ["0-2", "3-5", "6-8"]);
+        assert!(partition_cpus(2, 3).is_err());
+    }
+
+    #[test]
+    fn parses_memory_limits_and_applies_overrides() {
+        let config = ResourceLimits { cpus: Some(2.0), memory: Some("4g".to_string()) };
+        assert_eq!(config.memory_bytes().unwrap(), Some(4 << 30));
+        let cli = ResourceLimits { cpus: None, memory: Some("512MB".to_string()) };
+        let limits = config.overridden_by(&cli);
+        assert_eq!((limits.cpus, limits.memory_bytes().unwrap()), (Some(2.0), Some(512 << 20)));
+        assert!(ResourceLimits { cpus: None, memory: Some("lots".to_string()) }.validate().is_err());
+    }
+}
@@ -0,0 +1,307 @@
+use crate::adapter::{AdminOp, AppendCondition, AppendOutcome, EventData, EventStoreAdapter, EventStream, ReadEvent, ReadRequest, ReadStream, RetentionPolicy};
+use crate::payload::fnv1a;
+use anyhow::{ensure, Context, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Missing entries listed in a verification result at most
+const MISSING_LISTED: usize = 10;
+
+/// Where a ledger is written and how many acknowledged events it keeps
+#[derive(Debug, Clone)]
+pub struct LedgerOptions {
+    pub path: PathBuf,
+    /// Share of acknowledged events recorded, in (0, 1]; 0.01 records every hundredth
+    pub sample_rate: f64,
+}
+
+/// One acknowledged event, a line of the ledger file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LedgerEntry {
+    pub stream: String,
+    /// FNV-1a of the payload, identifying the event among the stream's events
+    pub payload_hash: u64,
+    /// Position the store acknowledged (single-event conditional appends only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub position: Option<u64>,
+}
+
+/// Append-only file on the client of events the store acknowledged, so a later check can
+/// tell whether the store still returns them. Sampled, so it keeps up with high throughput.
+pub struct Ledger {
+    every: u64,
+    seen: AtomicU64,
+    recorded: AtomicU64,
+    file: Mutex<BufWriter<File>>,
+}
+
+impl Ledger {
+    pub fn create(options: &LedgerOptions) -> Result<Arc<Self>> {
+        ensure!(
+            options.sample_rate > 0.0 && options.sample_rate <= 1.0,
+            "ledger sample rate must be in (0, 1], got {}",
+            options.sample_rate
+        );
+        if let Some(dir) = options.path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let file = File::create(&options.path)
+            .with_context(|| format!("Failed to create {}", options.path.display()))?;
+        Ok(Arc::new(Self {
+            every: (1.0 / options.sample_rate).round().max(1.0) as u64,
+            seen: AtomicU64::new(0),
+            recorded: AtomicU64::new(0),
+            file: Mutex::new(BufWriter::new(file)),
+        }))
+    }
+
+    /// Entries for the sampled share of `events`, to be written once the store acknowledges them
+    fn sample(&self, events: &[EventData]) -> Vec<LedgerEntry> {
+        let first = self.seen.fetch_add(events.len() as u64, Ordering::Relaxed);
+        events
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| (first + *i as u64).is_multiple_of(self.every))
+            .filter_map(|(_, event)| {
+                Some(LedgerEntry {
+                    stream: event.tags.first()?.clone(),
+                    payload_hash: fnv1a(event.payload.iter()),
+                    position: None,
+                })
+            })
+            .collect()
+    }
+
+    fn write(&self, entries: Vec<LedgerEntry>) {
+        if entries.is_empty() {
+            return;
+        }
+        let Ok(mut file) = self.file.lock() else {
+            return;
+        };
+        for entry in entries {
+            if serde_json::to_writer(&mut *file, &entry).is_ok() && file.write_all(b"\n").is_ok() {
+                self.recorded.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// Flush the ledger to disk; returns the number of events recorded
+    pub fn finish(&self) -> Result<u64> {
+        let mut file = self
+            .file
+            .lock()
+            .map_err(|_| anyhow::anyhow!("ledger lock poisoned"))?;
+        file.flush()?;
+        file.get_ref().sync_all()?;
+        Ok(self.recorded.load(Ordering::Relaxed))
+    }
+}
+
+/// Adapter wrapper recording the events of acknowledged appends in a [`Ledger`]
+pub struct Ledgered<A: ?Sized> {
+    inner: Arc<A>,
+    ledger: Arc<Ledger>,
+}
+
+impl<A: EventStoreAdapter + ?Sized> Ledgered<A> {
+    pub fn new(inner: Arc<A>, ledger: Arc<Ledger>) -> Self {
+        Self { inner, ledger }
+    }
+}
+
+#[async_trait]
+impl<A: EventStoreAdapter + ?Sized> EventStoreAdapter for Ledgered<A> {
+    async fn append(&self, events: Vec<EventData>) -> Result<()> {
+        let entries = self.ledger.sample(&events);
+        self.inner.append(events).await?;
+        self.ledger.write(entries);
+        Ok(())
+    }
+
+    async fn read(&self, req: ReadRequest) -> Result<Vec<ReadEvent>> {
+        self.inner.read(req).await
+    }
+
+    async fn append_conditional(
+        &self,
+        events: Vec<EventData>,
+        condition: AppendCondition,
+    ) -> Result<AppendOutcome> {
+        let single = events.len() == 1;
+        let mut entries = self.ledger.sample(&events);
+        let outcome = self.inner.append_conditional(events, condition).await?;
+        if let AppendOutcome::Appended { position } = outcome {
+            if single {
+                entries.iter_mut().for_each(|entry| entry.position = Some(position));
+            }
+            self.ledger.write(entries);
+        }
+        Ok(outcome)
+    }
+
+    async fn list_streams(&self, prefix: &str, limit: Option<u64>) -> Result<Vec<String>> {
+        self.inner.list_streams(prefix, limit).await
+    }
+
+    async fn read_all(&self, after: Option<u64>, limit: Option<u64>) -> Result<Vec<ReadEvent>> {
+        self.inner.read_all(after, limit).await
+    }
+
+    fn read_stream(&self, req: ReadRequest) -> ReadStream<'_> {
+        self.inner.read_stream(req)
+    }
+
+    fn read_all_stream(&self, after: Option<u64>) -> ReadStream<'_> {
+        self.inner.read_all_stream(after)
+    }
+
+    async fn subscribe(&self, stream: &str, from_offset: Option<u64>) -> Result<EventStream> {
+        self.inner.subscribe(stream, from_offset).await
+    }
+
+    async fn set_retention(&self, stream: &str, policy: &RetentionPolicy) -> Result<()> {
+        self.inner.set_retention(stream, policy).await
+    }
+
+    async fn admin(&self, op: AdminOp) -> Result<()> {
+        self.inner.admin(op).await
+    }
+
+    async fn ping(&self) -> Result<()> {
+        self.inner.ping().await
+    }
+}
+
+/// Outcome of reading a ledger's events back from a store
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LedgerVerification {
+    /// Ledger entries checked
+    pub checked: u64,
+    pub streams: u64,
+    /// Entries the store no longer returns
+    pub missing: u64,
+    /// The first missing entries
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub missing_entries: Vec<LedgerEntry>,
+}
+
+/// Check that the store returns every event in the ledger at `path`. Events are matched by
+/// stream and payload hash, counting duplicates, so payloads may repeat within a stream.
+pub async fn verify_ledger(path: &Path, adapter: &dyn EventStoreAdapter) -> Result<LedgerVerification> {
+    let file = File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    let mut by_stream: BTreeMap<String, Vec<LedgerEntry>> = BTreeMap::new();
+    for line in BufReader::new(file).lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let entry: LedgerEntry = serde_json::from_str(&line)
+            .with_context(|| format!("Invalid ledger entry in {}: {}", path.display(), line))?;
+        by_stream.entry(entry.stream.clone()).or_default().push(entry);
+    }
+
+    let mut result = LedgerVerification {
+        streams: by_stream.len() as u64,
+        ..Default::default()
+    };
+    for (stream, entries) in by_stream {
+        let events = adapter
+            .read(ReadRequest {
+                stream: stream.clone(),
+                from_offset: None,
+                limit: None,
+                consistency: Default::default(),
+            })
+            .await
+            .with_context(|| format!("Failed to read {} back", stream))?;
+        let mut available: HashMap<u64, u64> = HashMap::new();
+        for event in &events {
+            *available.entry(fnv1a(event.payload.iter())).or_default() += 1;
+        }
+        for entry in entries {
+            result.checked += 1;
+            match available.get_mut(&entry.payload_hash) {
+                Some(count) if *count > 0 => *count -= 1,
+                _ => {
+                    result.missing += 1;
+                    if result.missing_entries.len() < MISSING_LISTED {
+                        result.missing_entries.push(entry);
+                    }
+                }
+            }
+        }
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::Bytes;
+
+    /// Acknowledges every append but keeps only the first four
+    #[derive(Default)]
+    struct LossyStore {
+        appends: AtomicU64,
+        events: Mutex<Vec<EventData>>,
+    }
+
+    #[async_trait]
+    impl EventStoreAdapter for LossyStore {
+        async fn append(&self, events: Vec<EventData>) -> Result<()> {
+            if self.appends.fetch_add(1, Ordering::Relaxed) < 4 {
+                self.events.lock().unwrap().extend(events);
+            }
+            Ok(())
+        }
+
+        async fn read(&self, req: ReadRequest) -> Result<Vec<ReadEvent>> {
+            let events = self.events.lock().unwrap();
+            Ok(events
+                .iter()
+                .filter(|e| e.tags[0] == req.stream)
+                .enumerate()
+                .map(|(offset, e)| ReadEvent {
+                    offset: offset as u64,
+                    event_type: e.event_type.clone(),
+                    payload: e.payload.clone(),
+                    timestamp_ms: 0,
+                    tags: e.tags.clone(),
+                })
+                .collect())
+        }
+    }
+
+    #[tokio::test]
+    async fn finds_acknowledged_events_the_store_lost() {
+        let path = std::env::temp_dir().join(format!("ledger-{}.jsonl", uuid::Uuid::new_v4()));
+        let ledger = Ledger::create(&LedgerOptions {
+            path: path.clone(),
+            sample_rate: 0.5,
+        })
+        .unwrap();
+        let store = Arc::new(LossyStore::default());
+        let adapter = Ledgered::new(store.clone(), ledger.clone());
+        for i in 0..8u8 {
+            let event = EventData {
+                payload: Bytes::from(vec![i]),
+                event_type: "test".to_string(),
+                tags: vec![format!("stream-{}", i % 2)],
+            };
+            adapter.append(vec![event]).await.unwrap();
+        }
+        assert_eq!(ledger.finish().unwrap(), 4);
+
+        // Events 0, 2, 4 and 6 are sampled, and only 0 and 2 were kept
+        let verification = verify_ledger(&path, store.as_ref()).await.unwrap();
+        std::fs::remove_file(&path).ok();
+        assert_eq!((verification.checked, verification.streams, verification.missing), (4, 1, 2));
+    }
+}
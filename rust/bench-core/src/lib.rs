@@ -1,18 +1,113 @@
 pub mod adapter;
+pub mod assertions;
+pub mod backfill_tail;
+pub mod bottleneck;
+pub mod budget;
+pub mod bulk;
+pub mod catchup_subscription;
+pub mod checkpoint;
 pub mod common;
+pub mod composition;
+pub mod connection_stress;
+pub mod connection;
+pub mod conformance;
 pub mod container_stats;
+pub mod cooldown;
+pub mod cpu_spikes;
+pub mod coordination;
+pub mod determinism;
+pub mod dual_write;
+pub mod global_scan;
+pub mod health;
+pub mod host_stats;
+pub mod instrument;
+pub mod interference;
+pub mod isolation;
+pub mod ledger;
+pub mod live;
+pub mod lint;
+pub mod merge;
 pub mod metrics;
+pub mod micro_bench;
+pub mod migration;
+pub mod option_sweep;
+pub mod payload;
+pub mod propagation;
+pub mod rate_limit;
+pub mod ratio_sweep;
+pub mod registry;
+pub mod result_sink;
+pub mod retention;
 pub mod retry;
 pub mod runner;
+pub mod schema;
+pub mod schema_registry;
+pub mod slow_consumer;
+pub mod startup_bench;
+pub mod stream_growth;
+pub mod stream_position;
 pub mod system_info;
+pub mod testkit;
+pub mod think_time;
+pub mod traced;
+pub mod throttling;
+pub mod upload;
+pub mod version_compare;
+pub mod virtual_time;
+pub mod workflow_support;
 pub mod workloads;
 
-pub use adapter::{EventStoreAdapter, StoreDataDir, StoreManager, StoreManagerFactory};
+pub use assertions::{AssertConfig, AssertionResult};
+pub use adapter::{AdminOp, AppendCondition, AppendOutcome, EventStoreAdapter, EventStream, RetentionPolicy, StoreDataDir, StoreManager, StoreManagerFactory};
+pub use rate_limit::{OpenLoopSchedule, Pacer, RateLimitConfig, RateLimitScope, RateLimiter};
+pub use registry::{CustomMetric, MetricsRegistry};
 pub use retry::wait_for_ready;
+pub use bottleneck::{Bottleneck, BottleneckHint};
+pub use budget::{BudgetConfig, BudgetLimit, BudgetStop, RunBudget};
+pub use bulk::{analyze_bulk, BulkConfig, BulkOperation, BulkStats, BulkWindow};
+pub use backfill_tail::{execute_backfill_tail, BackfillSample, BackfillTailConfig, BackfillTailResult};
+pub use catchup_subscription::{execute_catchup_subscription, CatchupSubscriptionConfig, CatchupSubscriptionResult};
 pub use common::{is_image_pulled, mark_image_pulled, SetupConfig};
-pub use metrics::{LatencyStats, ThroughputSample, RunMetrics, Summary};
-pub use metrics::{SessionMetadata, EnvironmentInfo, RunManifest};
+pub use composition::{execute_composition, ComposedWorkload, CompositionResult};
+pub use connection::{analyze_connections, ConnectTiming, ConnectionStats};
+pub use connection_stress::{execute_connection_stress, ConnectionLevel, ConnectionStressConfig, ConnectionStressResult};
+pub use cooldown::BetweenRuns;
+pub use cpu_spikes::{analyze_cpu_spikes, CpuSpike, CpuSpikeStats, SpikeOp};
+pub use coordination::{MeasurementStart, StartBarrier, StartSchedule};
+pub use determinism::{OpDigest, WorkerDigest, DIGEST_OPS};
+pub use dual_write::{execute_dual_write, DualWriteConfig, DualWriteResult, PairedSample};
+pub use global_scan::{execute_global_scan, GlobalScanConfig, GlobalScanResult};
+pub use health::{HealthMonitor, HealthStats};
+pub use host_stats::{HostMonitor, HostStatsSample};
+pub use interference::{analyze_interference, InterferenceStats, WriteRateBucket};
+pub use checkpoint::{Checkpoint, CheckpointOptions};
+pub use isolation::{partition_cpus, CpuSet, ResourceLimits};
+pub use ledger::{verify_ledger, LedgerOptions, LedgerVerification};
+pub use live::{LiveStats, LiveWindow};
+pub use lint::{LintFinding, LintLevel, RunLimits};
+pub use merge::{merge_runs, MergeMode, MergedRun};
+pub use metrics::{ContentionStats, FanOutStats, LatencyStats, RawSample, ThroughputSample, RunMetrics, Summary};
+pub use metrics::{throughput_series, SeriesBucket};
+pub use metrics::{ContainerResourceStats, ImageLayer, SessionMetadata, EnvironmentInfo, RunManifest};
+pub use metrics::{OpSamples, PercentilePoint, CDF_POINTS};
 pub use metrics::{OsInfo, CpuInfo, MemoryInfo, DiskInfo, ContainerRuntimeInfo};
-pub use runner::execute_run;
+pub use payload::{CloudEvent, PayloadConfig, PayloadCorpusInfo, PayloadKind, SchemaRegistryConfig};
+pub use micro_bench::{execute_micro_bench, MicroBenchConfig, MicroBenchResult, Primitive, PrimitiveStats};
+pub use migration::{execute_migration, MigrationConfig, MigrationResult};
+pub use startup_bench::{execute_startup_bench, DurationStats, StartupBenchConfig, StartupBenchResult, StartupIteration, StartupStats};
+pub use stream_growth::{execute_stream_growth, OffsetBucket, StreamGrowthConfig, StreamGrowthResult};
+pub use propagation::{execute_propagation, PropagationConfig, PropagationResult};
+pub use stream_position::{execute_stream_position, StreamPositionConfig, StreamPositionResult};
+pub use ratio_sweep::{RatioRun, ReadWriteMix};
+pub use result_sink::{parse_sink, FilesystemSink, HttpSink, ResultSink, RunRecord, SqliteSink};
+pub use retention::{execute_retention, RetentionConfig, RetentionPhase, RetentionResult, ScavengeWindow};
+pub use slow_consumer::{execute_slow_consumer, SlowConsumerConfig, SlowConsumerPhase, SlowConsumerResult};
+pub use option_sweep::{best_configurations, run_order, store_runs, BestConfiguration, OptionSweep, OptionSweepResult, StoreRun};
+pub use runner::{execute_run, execute_run_with_options, start_store, RunOptions, StoreStartup};
+pub use schema::{load_summary, parse_summary, SCHEMA_VERSION};
 pub use system_info::{collect_environment_info, get_git_commit_hash};
-pub use workloads::{Workload, WorkloadFactory, WorkloadType, PerformanceWorkload, PerformanceConfig};
+pub use think_time::{ThinkTime, ThinkTimeDistribution};
+pub use throttling::{analyze_throttling, ThrottlingStats};
+pub use upload::{upload_dir, S3Location};
+pub use version_compare::{version_deltas, VersionDelta, VersionRun};
+pub use workloads::{Workload, WorkloadFactory, WorkloadType, PerformanceWorkload, PerformanceConfig, PerformanceOutput};
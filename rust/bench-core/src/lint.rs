@@ -0,0 +1,123 @@
+use std::fmt;
+use std::fs;
+
+/// File descriptors kept free for the process itself (logs, results, Docker, stats files)
+const RESERVED_FDS: u64 = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LintLevel {
+    /// The run can go ahead but its results are likely not what was intended
+    Warning,
+    /// The run cannot do what the config asks; refused unless overridden
+    Error,
+}
+
+/// A problem found in a workload config before running it
+#[derive(Debug, Clone)]
+pub struct LintFinding {
+    pub level: LintLevel,
+    pub message: String,
+}
+
+impl LintFinding {
+    pub fn warning(message: String) -> Self {
+        Self {
+            level: LintLevel::Warning,
+            message,
+        }
+    }
+
+    pub fn error(message: String) -> Self {
+        Self {
+            level: LintLevel::Error,
+            message,
+        }
+    }
+}
+
+impl fmt::Display for LintFinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.level {
+            LintLevel::Warning => write!(f, "⚠ {}", self.message),
+            LintLevel::Error => write!(f, "✗ {}", self.message),
+        }
+    }
+}
+
+/// What the host and store offer a run; `None` where unknown, which skips the related checks
+#[derive(Debug, Clone, Default)]
+pub struct RunLimits {
+    /// Speed of the link carrying the host's default route, in bytes per second
+    pub network_bytes_per_second: Option<f64>,
+    /// Connections this process can open (open-file limit less a reserve)
+    pub client_connections: Option<u64>,
+    /// Connections the store accepts
+    pub store_connections: Option<usize>,
+}
+
+impl RunLimits {
+    /// Read the host's limits (Linux only); `store_connections` is left to the caller
+    pub fn detect() -> Self {
+        Self {
+            network_bytes_per_second: default_route_speed(),
+            client_connections: fs::read_to_string("/proc/self/limits")
+                .ok()
+                .and_then(|limits| max_open_files(&limits))
+                .map(|fds| fds.saturating_sub(RESERVED_FDS)),
+            store_connections: None,
+        }
+    }
+}
+
+/// Link speed of the interface with the default route, from `/proc/net/route` and
+/// `/sys/class/net/<iface>/speed` (Mbit/s; absent or -1 for virtual interfaces)
+fn default_route_speed() -> Option<f64> {
+    let routes = fs::read_to_string("/proc/net/route").ok()?;
+    let iface = routes.lines().skip(1).find_map(|line| {
+        let mut fields = line.split_whitespace();
+        let iface = fields.next()?;
+        (fields.next()? == "00000000").then_some(iface)
+    })?;
+    let mbits: f64 = fs::read_to_string(format!("/sys/class/net/{}/speed", iface))
+        .ok()?
+        .trim()
+        .parse()
+        .ok()?;
+    (mbits > 0.0).then_some(mbits * 1e6 / 8.0)
+}
+
+/// Soft limit of the "Max open files" line of a `/proc/<pid>/limits` file
+fn max_open_files(limits: &str) -> Option<u64> {
+    limits
+        .lines()
+        .find_map(|line| line.strip_prefix("Max open files"))
+        .and_then(|rest| rest.split_whitespace().next())
+        .and_then(|soft| soft.parse().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::workloads::PerformanceWorkload;
+
+    #[test]
+    fn refuses_more_workers_than_connections() {
+        let limits = "Limit                     Soft Limit           Hard Limit           Units\n\
+                      Max open files            1024                 524288               files\n";
+        assert_eq!(max_open_files(limits), Some(1024));
+
+        let yaml = "name: w\nmode: write\nduration_seconds: 10\nconcurrency: {writers: 200}\n\
+                    operations: {write: {event_size_bytes: 1000}}\ntarget_rate_eps: 200000\n";
+        let workload = PerformanceWorkload::from_yaml(yaml, 1).unwrap();
+        let limits = RunLimits {
+            network_bytes_per_second: Some(125e6),
+            client_connections: Some(960),
+            store_connections: Some(100),
+        };
+        let findings = workload.lint("postgres", &limits);
+        let levels: Vec<LintLevel> = findings.iter().map(|f| f.level).collect();
+        // 200 MB/s offered over a 1 Gbit/s link, and 201 connections for a store accepting 100
+        assert_eq!(levels, [LintLevel::Warning, LintLevel::Error]);
+        assert!(workload.lint("postgres", &RunLimits::default()).is_empty());
+    }
+}
@@ -0,0 +1,106 @@
+use crate::metrics::LatencyRecorder;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Every this many operations of a worker has its latency recorded for the live view
+const LIVE_LATENCY_EVERY: u64 = 10;
+
+/// Operations, errors and container usage of a running workload, shared by its workers and
+/// read out once per refresh of a live view
+///
+/// Workers add each operation with one relaxed atomic increment; only every
+/// `LIVE_LATENCY_EVERY`-th one takes the lock of the latency histogram.
+#[derive(Debug)]
+pub struct LiveStats {
+    started: Instant,
+    ok: AtomicU64,
+    errors: AtomicU64,
+    latency: Mutex<LatencyRecorder>,
+    /// CPU percent and memory bytes of the store's containers (summed), latest sample
+    container: Mutex<Option<(f64, u64)>>,
+    window_started: Mutex<Instant>,
+}
+
+/// What a running workload did since the previous [`LiveStats::take_window`]
+#[derive(Debug, Clone)]
+pub struct LiveWindow {
+    /// Time since the workload's clients were created
+    pub elapsed: Duration,
+    pub ops_per_s: f64,
+    pub errors_per_s: f64,
+    /// Share of the window's operations that failed
+    pub error_rate: f64,
+    /// Of the sampled successful operations, `None` without any
+    pub p50_ms: Option<f64>,
+    pub p99_ms: Option<f64>,
+    pub cpu_percent: Option<f64>,
+    pub memory_bytes: Option<u64>,
+}
+
+impl Default for LiveStats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LiveStats {
+    pub fn new() -> Self {
+        let now = Instant::now();
+        Self {
+            started: now,
+            ok: AtomicU64::new(0),
+            errors: AtomicU64::new(0),
+            latency: Mutex::new(LatencyRecorder::new()),
+            container: Mutex::new(None),
+            window_started: Mutex::new(now),
+        }
+    }
+
+    /// Count one operation; `seq` is the worker's operation sequence number
+    pub fn record(&self, seq: u64, latency: Duration, ok: bool) {
+        if !ok {
+            self.errors.fetch_add(1, Ordering::Relaxed);
+            return;
+        }
+        self.ok.fetch_add(1, Ordering::Relaxed);
+        if seq.is_multiple_of(LIVE_LATENCY_EVERY) {
+            if let Ok(mut rec) = self.latency.lock() {
+                rec.record(latency);
+            }
+        }
+    }
+
+    pub fn set_container(&self, cpu_percent: f64, memory_bytes: u64) {
+        if let Ok(mut container) = self.container.lock() {
+            *container = Some((cpu_percent, memory_bytes));
+        }
+    }
+
+    /// Rates and latency since the previous call (or since the start), starting a new window
+    pub fn take_window(&self) -> LiveWindow {
+        let now = Instant::now();
+        let seconds = match self.window_started.lock() {
+            Ok(mut window_started) => std::mem::replace(&mut *window_started, now).elapsed(),
+            Err(_) => Duration::ZERO,
+        }
+        .as_secs_f64()
+        .max(f64::EPSILON);
+        let ok = self.ok.swap(0, Ordering::Relaxed);
+        let errors = self.errors.swap(0, Ordering::Relaxed);
+        let latency = self.latency.lock().map(|mut rec| std::mem::take(&mut *rec)).unwrap_or_default();
+        let percentile =
+            |q| (!latency.hist.is_empty()).then(|| latency.hist.value_at_quantile(q) as f64 / 1000.0);
+        let container = self.container.lock().ok().and_then(|c| *c);
+        LiveWindow {
+            elapsed: now.duration_since(self.started),
+            ops_per_s: ok as f64 / seconds,
+            errors_per_s: errors as f64 / seconds,
+            error_rate: if ok + errors == 0 { 0.0 } else { errors as f64 / (ok + errors) as f64 },
+            p50_ms: percentile(0.5),
+            p99_ms: percentile(0.99),
+            cpu_percent: container.map(|(cpu, _)| cpu),
+            memory_bytes: container.map(|(_, memory)| memory),
+        }
+    }
+}
@@ -0,0 +1,238 @@
+use crate::metrics::{ContainerMetrics, ContentionStats, LatencyRecorder, Summary};
+use crate::schema::{load_summary, SCHEMA_VERSION};
+use anyhow::{bail, Context, Result};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// How the partial runs being merged relate to each other in time
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum MergeMode {
+    /// Runs covered the same measurement window on different agents: clients and
+    /// throughput add up, the duration is the longest run's
+    Parallel,
+    /// Runs are consecutive pieces of one experiment (e.g. resumed after an interruption):
+    /// durations add up and later runs' samples are shifted after the earlier ones
+    Sequential,
+}
+
+/// One logical result combined from several run directories
+pub struct MergedRun {
+    pub summary: Summary,
+    /// Exact merge of every run's HDR histogram
+    pub latency_histogram: LatencyRecorder,
+    /// Exact merge of the runs' per-operation-type histograms (latency_<op>.hdr), for the
+    /// operation types any run has one of
+    pub op_latency: BTreeMap<String, LatencyRecorder>,
+    /// samples.jsonl lines of all runs, worker ids made unique and ordered by `t_rel_us`
+    pub samples: Vec<serde_json::Value>,
+    pub merged_from: Vec<PathBuf>,
+}
+
+/// Merge run directories (each holding summary.json, latency.hdr and optionally
+/// samples.jsonl) written for the same workload and store
+///
+/// Latency percentiles come from the merged histograms, so they are exact rather than
+/// averages of percentiles. Per-run resource figures that cannot be combined meaningfully
+/// (container CPU averages, bottleneck hints, mode-specific extras) are left out.
+pub fn merge_runs(run_dirs: &[PathBuf], mode: MergeMode) -> Result<MergedRun> {
+    if run_dirs.len() < 2 {
+        bail!("merging needs at least two run directories");
+    }
+
+    let mut summaries = Vec::with_capacity(run_dirs.len());
+    for dir in run_dirs {
+        summaries.push(load_summary(&dir.join("summary.json"))?);
+    }
+    let first = &summaries[0];
+    for (dir, summary) in run_dirs.iter().zip(&summaries).skip(1) {
+        if summary.workload != first.workload || summary.adapter != first.adapter {
+            bail!(
+                "{} is {} on {}, expected {} on {}",
+                dir.display(),
+                summary.workload,
+                summary.adapter,
+                first.workload,
+                first.adapter
+            );
+        }
+    }
+
+    let mut latency_histogram = LatencyRecorder::new();
+    for dir in run_dirs {
+        let path = dir.join("latency.hdr");
+        let encoded = std::fs::read_to_string(&path).with_context(|| {
+            format!("Failed to read {} (runs without it cannot be merged)", path.display())
+        })?;
+        let rec = LatencyRecorder::deserialize_from_base64(&encoded)
+            .with_context(|| format!("Failed to parse {}", path.display()))?;
+        latency_histogram.hist.add(&rec.hist)?;
+    }
+    let mut op_latency: BTreeMap<String, LatencyRecorder> = BTreeMap::new();
+    for dir in run_dirs {
+        for (op, rec) in op_histograms(dir)? {
+            op_latency.entry(op).or_default().hist.add(&rec.hist)?;
+        }
+    }
+
+    let events_written = summaries.iter().map(|s| s.events_written).sum();
+    let events_read = summaries.iter().map(|s| s.events_read).sum();
+    let (writers, readers, duration_s, throughput_eps) = match mode {
+        MergeMode::Parallel => (
+            summaries.iter().map(|s| s.writers).sum(),
+            summaries.iter().map(|s| s.readers).sum(),
+            summaries.iter().map(|s| s.duration_s).fold(0.0, f64::max),
+            summaries.iter().map(|s| s.throughput_eps).sum(),
+        ),
+        MergeMode::Sequential => {
+            let duration_s: f64 = summaries.iter().map(|s| s.duration_s).sum();
+            // Weight each run's rate by its duration so the result is total ops / total time
+            let ops: f64 = summaries.iter().map(|s| s.throughput_eps * s.duration_s).sum();
+            (
+                summaries.iter().map(|s| s.writers).max().unwrap_or(0),
+                summaries.iter().map(|s| s.readers).max().unwrap_or(0),
+                duration_s,
+                ops / duration_s.max(0.001),
+            )
+        }
+    };
+
+    let contention = summaries.iter().filter_map(|s| s.contention.as_ref()).fold(
+        None,
+        |acc: Option<ContentionStats>, stats| {
+            let mut merged = acc.unwrap_or_default();
+            merged.merge(stats);
+            Some(merged)
+        },
+    );
+
+    let container = ContainerMetrics {
+        image_size_bytes: first.container.image_size_bytes,
+        startup_time_s: first.container.startup_time_s,
+        resource_limits: first.container.resource_limits.clone(),
+        image_layers: first.container.image_layers.clone(),
+        peak_cpu_percent: summaries
+            .iter()
+            .filter_map(|s| s.container.peak_cpu_percent)
+            .reduce(f64::max),
+        peak_memory_bytes: summaries.iter().filter_map(|s| s.container.peak_memory_bytes).max(),
+        data_bytes_written: summaries.iter().filter_map(|s| s.container.data_bytes_written).max(),
+        ..Default::default()
+    };
+
+    let summary = Summary {
+        schema_version: SCHEMA_VERSION,
+        workload: first.workload.clone(),
+        adapter: first.adapter.clone(),
+        writers,
+        readers,
+        events_written,
+        events_read,
+        duration_s,
+        throughput_eps,
+        latency: latency_histogram.to_stats(),
+        throughput_series: Vec::new(),
+        container,
+        contention,
+        fan_out: None,
+        bottleneck: None,
+        interference: None,
+        cpu_spikes: None,
+        throttling: None,
+        health: None,
+        budget_stop: None,
+        bulk: None,
+        connections: None,
+        read_consistency: summaries
+            .iter()
+            .all(|s| s.read_consistency == first.read_consistency)
+            .then_some(first.read_consistency)
+            .flatten(),
+        // Kept only when every run used the same options
+        store_options: if summaries.iter().all(|s| s.store_options == first.store_options) {
+            first.store_options.clone()
+        } else {
+            BTreeMap::new()
+        },
+        store_rev: summaries
+            .iter()
+            .all(|s| s.store_rev == first.store_rev)
+            .then(|| first.store_rev.clone())
+            .flatten(),
+        // Each part was checked against its own numbers, which the merged result replaces
+        assertions: Vec::new(),
+        extra: BTreeMap::new(),
+    };
+
+    let samples = merge_samples(run_dirs, &summaries, mode)?;
+
+    Ok(MergedRun {
+        summary,
+        latency_histogram,
+        op_latency,
+        samples,
+        merged_from: run_dirs.to_vec(),
+    })
+}
+
+/// The latency_<op>.hdr histograms of a run directory by operation type
+fn op_histograms(dir: &Path) -> Result<Vec<(String, LatencyRecorder)>> {
+    let mut histograms = Vec::new();
+    for entry in std::fs::read_dir(dir).with_context(|| format!("Failed to list {}", dir.display()))? {
+        let path = entry?.path();
+        let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+        let Some(op) = file_name.strip_prefix("latency_").and_then(|n| n.strip_suffix(".hdr")) else {
+            continue;
+        };
+        let encoded = std::fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+        let rec = LatencyRecorder::deserialize_from_base64(&encoded)
+            .with_context(|| format!("Failed to parse {}", path.display()))?;
+        histograms.push((op.to_string(), rec));
+    }
+    Ok(histograms)
+}
+
+/// Concatenate samples.jsonl files, offsetting worker ids by the clients of earlier runs and,
+/// for sequential runs, timestamps by their durations
+fn merge_samples(
+    run_dirs: &[PathBuf],
+    summaries: &[Summary],
+    mode: MergeMode,
+) -> Result<Vec<serde_json::Value>> {
+    let mut merged = Vec::new();
+    let mut worker_offset = 0u64;
+    let mut time_offset_us = 0u64;
+    for (dir, summary) in run_dirs.iter().zip(summaries) {
+        for mut sample in read_samples(&dir.join("samples.jsonl"))? {
+            if let Some(obj) = sample.as_object_mut() {
+                if let Some(worker) = obj.get("worker").and_then(|w| w.as_u64()) {
+                    obj.insert("worker".to_string(), (worker + worker_offset).into());
+                }
+                if let Some(t_rel_us) = obj.get("t_rel_us").and_then(|t| t.as_u64()) {
+                    obj.insert("t_rel_us".to_string(), (t_rel_us + time_offset_us).into());
+                }
+            }
+            merged.push(sample);
+        }
+        worker_offset += (summary.writers + summary.readers) as u64;
+        if mode == MergeMode::Sequential {
+            time_offset_us += (summary.duration_s * 1_000_000.0) as u64;
+        }
+    }
+    merged.sort_by_key(|s| s.get("t_rel_us").and_then(|t| t.as_u64()).unwrap_or(0));
+    Ok(merged)
+}
+
+fn read_samples(path: &Path) -> Result<Vec<serde_json::Value>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            serde_json::from_str(line).with_context(|| format!("Bad sample in {}", path.display()))
+        })
+        .collect()
+}
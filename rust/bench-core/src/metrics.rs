@@ -1,10 +1,29 @@
 use base64::Engine;
 use hdrhistogram::Histogram;
-use hdrhistogram::serialization::{Serializer, V2Serializer};
-use serde::Serialize;
-use std::collections::HashMap;
+use hdrhistogram::serialization::{Deserializer, Serializer, V2Serializer};
+use crate::adapter::ReadConsistency;
+use crate::assertions::AssertionResult;
+use crate::bottleneck::BottleneckHint;
+use crate::conformance::MissingStreamRead;
+use crate::determinism::WorkerDigest;
+use crate::host_stats::HostStatsSample;
+use crate::interference::InterferenceStats;
+use crate::ledger::LedgerVerification;
+use crate::payload::PayloadCorpusInfo;
+use crate::budget::BudgetStop;
+use crate::bulk::BulkStats;
+use crate::connection::ConnectionStats;
+use crate::cpu_spikes::CpuSpikeStats;
+use crate::isolation::ResourceLimits;
+use crate::health::HealthStats;
+use crate::live::LiveStats;
+use crate::throttling::ThrottlingStats;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 use std::io::Write;
+use std::sync::Arc;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::time::Instant;
 
 /// Throughput time-series sample: elapsed time from workload start and cumulative operation count
 #[derive(Debug, Clone, Serialize)]
@@ -13,7 +32,217 @@ pub struct ThroughputSample {
     pub count: u64,
 }
 
+/// One in this many operations per worker is kept as a [`RawSample`]
+pub const SAMPLE_RATE: u64 = 100;
+
+/// One sampled operation, written to samples.jsonl
+///
+/// `t_rel_us` is measured on the monotonic clock from the measurement start (the release of the
+/// start barrier), so time axes of different stores line up exactly regardless of wall-clock
+/// adjustments or when each run happened.
 #[derive(Debug, Clone, Serialize)]
+pub struct RawSample {
+    /// Microseconds from measurement start to the start of the operation
+    pub t_rel_us: u64,
+    /// Wall-clock epoch milliseconds, only recorded when requested in the workload config
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub t_ms: Option<u64>,
+    /// Worker that issued the operation
+    pub worker: usize,
+    /// Per-worker operation sequence number, counting every operation (not just sampled ones)
+    pub seq: u64,
+    pub op: &'static str,
+    pub latency_us: u64,
+    pub ok: bool,
+    /// Stream index (or hashed bucket of it) the operation targeted, when enabled
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stream: Option<u64>,
+}
+
+/// One window of a run's throughput series, from the sampled operations that started in it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SeriesBucket {
+    /// Start of the window, in seconds from the measurement start
+    pub t_s: f64,
+    /// Successful operations per second (sampled operations scaled by the sample rate)
+    pub ops_per_s: f64,
+    /// Latency of the window's successful sampled operations; absent for windows without any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latency_p50_ms: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latency_p99_ms: Option<f64>,
+}
+
+/// Bucket sampled operations (sorted by `t_rel_us`) into windows of `bucket` from the
+/// measurement start, so plots don't have to re-derive throughput over time from samples.jsonl
+pub fn throughput_series(samples: &[RawSample], sample_rate: u64, bucket: Duration) -> Vec<SeriesBucket> {
+    let bucket_us = bucket.as_micros().max(1) as u64;
+    let Some(last) = samples.last() else {
+        return Vec::new();
+    };
+    let mut latencies: Vec<Vec<u64>> = vec![Vec::new(); (last.t_rel_us / bucket_us + 1) as usize];
+    for sample in samples.iter().filter(|s| s.ok) {
+        latencies[(sample.t_rel_us / bucket_us) as usize].push(sample.latency_us);
+    }
+    let percentile_ms = |sorted: &[u64], q: f64| {
+        (!sorted.is_empty()).then(|| sorted[((sorted.len() - 1) as f64 * q).round() as usize] as f64 / 1000.0)
+    };
+    latencies
+        .into_iter()
+        .enumerate()
+        .map(|(i, mut window)| {
+            window.sort_unstable();
+            SeriesBucket {
+                t_s: (i as u64 * bucket_us) as f64 / 1e6,
+                ops_per_s: (window.len() as u64 * sample_rate) as f64 / bucket.as_secs_f64(),
+                latency_p50_ms: percentile_ms(&window, 0.5),
+                latency_p99_ms: percentile_ms(&window, 0.99),
+            }
+        })
+        .collect()
+}
+
+/// Whether and how the target stream of each sampled operation is recorded
+#[derive(Debug, Clone, Copy, Default)]
+pub enum SampleStreams {
+    #[default]
+    Off,
+    /// Record the stream index itself
+    Index,
+    /// Record a hashed bucket of the stream index (0..n)
+    Buckets(u64),
+}
+
+impl SampleStreams {
+    fn key(&self, stream_idx: usize) -> Option<u64> {
+        match *self {
+            SampleStreams::Off => None,
+            SampleStreams::Index => Some(stream_idx as u64),
+            // Fibonacci hashing spreads neighbouring indices over the buckets
+            SampleStreams::Buckets(n) => {
+                Some(((stream_idx as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15) >> 32) % n.max(1))
+            }
+        }
+    }
+}
+
+/// Per-worker collector keeping every `sample_rate`-th operation as a [`RawSample`] (none when
+/// `sample_rate` is 0; sequence numbers are still counted) and the latency of every successful
+/// operation in a histogram per operation type
+pub struct SampleLog {
+    sample_rate: u64,
+    worker: usize,
+    seen: u64,
+    start: Instant,
+    epoch_start_ms: Option<u64>,
+    streams: SampleStreams,
+    samples: Vec<RawSample>,
+    op_latency: BTreeMap<&'static str, LatencyRecorder>,
+    live: Option<Arc<LiveStats>>,
+}
+
+/// What a [`SampleLog`] collected; workers' collections are merged by the coordinator
+#[derive(Debug, Default)]
+pub struct OpSamples {
+    pub samples: Vec<RawSample>,
+    /// Latency of every successful operation, keyed by operation type
+    pub op_latency: BTreeMap<&'static str, LatencyRecorder>,
+}
+
+impl OpSamples {
+    pub fn merge(&mut self, other: OpSamples) {
+        self.samples.extend(other.samples);
+        for (op, rec) in other.op_latency {
+            match self.op_latency.get_mut(op) {
+                Some(existing) => {
+                    let _ = existing.hist.add(&rec.hist);
+                }
+                None => {
+                    self.op_latency.insert(op, rec);
+                }
+            }
+        }
+    }
+}
+
+impl SampleLog {
+    /// `epoch_start_ms` is the wall-clock time of `start`; pass it to also record `t_ms`
+    pub fn new(sample_rate: u64, worker: usize, start: Instant, epoch_start_ms: Option<u64>) -> Self {
+        Self {
+            sample_rate,
+            worker,
+            seen: 0,
+            start,
+            epoch_start_ms,
+            streams: SampleStreams::Off,
+            samples: Vec::new(),
+            op_latency: BTreeMap::new(),
+            live: None,
+        }
+    }
+
+    pub fn with_streams(mut self, streams: SampleStreams) -> Self {
+        self.streams = streams;
+        self
+    }
+
+    /// Also count every operation in a live view of the run
+    pub fn with_live(mut self, live: Option<Arc<LiveStats>>) -> Self {
+        self.live = live;
+        self
+    }
+
+    pub fn worker(&self) -> usize {
+        self.worker
+    }
+
+    /// Sequence number the next recorded operation will get
+    pub fn next_seq(&self) -> u64 {
+        self.seen
+    }
+
+    /// Record an operation; `stream_idx` is the targeted stream, if the mode picks one
+    pub fn record(
+        &mut self,
+        op: &'static str,
+        started: Instant,
+        latency: Duration,
+        ok: bool,
+        stream_idx: Option<usize>,
+    ) {
+        let index = self.seen;
+        self.seen += 1;
+        if let Some(live) = &self.live {
+            live.record(index, latency, ok);
+        }
+        if ok {
+            self.op_latency.entry(op).or_default().record(latency);
+        }
+        if self.sample_rate == 0 || !index.is_multiple_of(self.sample_rate) {
+            return;
+        }
+        let t_rel_us = started.saturating_duration_since(self.start).as_micros() as u64;
+        self.samples.push(RawSample {
+            t_rel_us,
+            t_ms: self.epoch_start_ms.map(|epoch| epoch + t_rel_us / 1000),
+            worker: self.worker,
+            seq: index,
+            op,
+            latency_us: latency.as_micros() as u64,
+            ok,
+            stream: stream_idx.and_then(|idx| self.streams.key(idx)),
+        });
+    }
+
+    pub fn into_samples(self) -> OpSamples {
+        OpSamples {
+            samples: self.samples,
+            op_latency: self.op_latency,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LatencyStats {
     pub p50_ms: f64,
     pub p95_ms: f64,
@@ -21,12 +250,22 @@ pub struct LatencyStats {
     pub p999_ms: f64,
 }
 
-#[derive(Debug, Clone, Serialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct ContainerMetrics {
     /// Container image size in bytes
     pub image_size_bytes: Option<u64>,
     /// Time to start the container in seconds
     pub startup_time_s: f64,
+    /// Whether the image was pulled (or checked for updates) before this run, which happens
+    /// for the first run of each store in a session
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image_pulled: Option<bool>,
+    /// Time spent pulling the image, not part of `startup_time_s`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image_pull_s: Option<f64>,
+    /// Non-empty image layers, base layer first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub image_layers: Vec<ImageLayer>,
     /// Average CPU usage percentage during run
     pub avg_cpu_percent: Option<f64>,
     /// Peak CPU usage percentage during run
@@ -35,10 +274,91 @@ pub struct ContainerMetrics {
     pub avg_memory_bytes: Option<u64>,
     /// Peak memory usage in bytes during run
     pub peak_memory_bytes: Option<u64>,
+    /// Growth of the containers' on-disk data (writable layer and named volumes) from before
+    /// the workload to after it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data_bytes_written: Option<u64>,
+    /// CPU and memory limits the containers ran under, if any were set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resource_limits: Option<ResourceLimits>,
+    /// Interval between CPU/memory samples in milliseconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stats_interval_ms: Option<u64>,
+    /// Where CPU/memory samples were read from ("cgroup" or "docker_api")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stats_source: Option<String>,
+    /// CPU/memory of each container keyed by role, for stores running more than one container
+    /// (the fields above are then the per-tick sums across containers)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub per_container: BTreeMap<String, ContainerResourceStats>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+/// One layer of a container image
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImageLayer {
+    pub size_bytes: u64,
+    /// Instruction that created the layer (shortened)
+    pub created_by: String,
+}
+
+/// CPU and memory usage of one container (or the sum across a store's containers)
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ContainerResourceStats {
+    pub avg_cpu_percent: Option<f64>,
+    pub peak_cpu_percent: Option<f64>,
+    pub avg_memory_bytes: Option<u64>,
+    pub peak_memory_bytes: Option<u64>,
+}
+
+/// Append outcomes for the hot-stream contention mode
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ContentionStats {
+    pub attempts: u64,
+    pub appended: u64,
+    pub conflicts: u64,
+    pub errors: u64,
+    /// Fraction of attempts rejected by the concurrency check
+    pub conflict_rate: f64,
+}
+
+impl ContentionStats {
+    pub fn conflict_rate(&self) -> f64 {
+        if self.attempts == 0 {
+            0.0
+        } else {
+            self.conflicts as f64 / self.attempts as f64
+        }
+    }
+
+    pub fn merge(&mut self, other: &ContentionStats) {
+        self.attempts += other.attempts;
+        self.appended += other.appended;
+        self.conflicts += other.conflicts;
+        self.errors += other.errors;
+        self.conflict_rate = self.conflict_rate();
+    }
+}
+
+/// Writer and reader behaviour for the fan-out mode, reported separately so writer latency
+/// degradation under read load is visible
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FanOutStats {
+    pub readers: usize,
+    pub writer_latency: LatencyStats,
+    pub reader_latency: LatencyStats,
+    /// Read requests issued by all readers
+    pub reads: u64,
+    /// Read requests that returned no new events (reader caught up with the writer)
+    pub empty_reads: u64,
+    /// Events read per second, per reader
+    pub read_eps_per_reader: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Summary {
+    /// Layout version of summary.json (see `schema`); files without it are version 1
+    #[serde(default = "legacy_schema_version")]
+    pub schema_version: u32,
     pub workload: String,
     pub adapter: String,
     pub writers: usize,
@@ -48,16 +368,93 @@ pub struct Summary {
     pub duration_s: f64,
     pub throughput_eps: f64,
     pub latency: LatencyStats,
+    /// Throughput and latency per window of `series_bucket_ms` (performance workloads)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub throughput_series: Vec<SeriesBucket>,
     #[serde(default)]
     pub container: ContainerMetrics,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contention: Option<ContentionStats>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fan_out: Option<FanOutStats>,
+    /// Heuristic hint at what limited the run
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bottleneck: Option<BottleneckHint>,
+    /// Read latency by concurrent write rate (runs sampling both appends and reads)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interference: Option<InterferenceStats>,
+    /// Store CPU spikes and the operations running during them (containerized performance runs)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpu_spikes: Option<CpuSpikeStats>,
+    /// Host CPU frequency and thermal throttling (hosts exposing cpufreq or thermal zones)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub throttling: Option<ThrottlingStats>,
+    /// Store availability from pings on a dedicated connection throughout the run
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub health: Option<HealthStats>,
+    /// The budget limit (`max_events`, `max_disk_gb`, `max_wall_clock`) that ended the run early
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub budget_stop: Option<BudgetStop>,
+    /// Bulk operations run during the workload and the latency around them (`bulk` configs)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bulk: Option<BulkStats>,
+    /// How long the adapters took to connect, by phase, before the measurement
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub connections: Option<ConnectionStats>,
+    /// Read consistency the run's reads asked for, if not the store's default
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub read_consistency: Option<ReadConsistency>,
+    /// `store_options` the store was started with, including swept values
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub store_options: BTreeMap<String, String>,
+    /// Commit of the store under test given with `--store-rev`, for locally built stores
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub store_rev: Option<String>,
+    /// Outcome of each bound of the workload's `assert` section
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub assertions: Vec<AssertionResult>,
+    /// Mode-specific metrics from the workload's `MetricsRegistry`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub extra: BTreeMap<String, serde_json::Value>,
+}
+
+fn legacy_schema_version() -> u32 {
+    1
 }
 
 #[derive(Debug, Clone, Serialize)]
 pub struct RunMetrics {
+    /// Layout version of the per-run result files (same as `Summary::schema_version`)
+    pub schema_version: u32,
     pub summary: Summary,
     pub throughput_samples: Vec<ThroughputSample>,
     #[serde(default = "default_sample_rate")]
     pub sample_rate: u64,
+    /// Per-worker delay between the start barrier releasing and the worker starting its operations
+    pub worker_start_offsets_ms: Vec<f64>,
+    /// Seed of each worker's RNG
+    pub worker_seeds: Vec<u64>,
+    /// Per-worker digest of the first operations' random choices (see `determinism`)
+    pub op_digests: Vec<WorkerDigest>,
+    /// Payloads appended during the run (performance workloads that write)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub payload_corpus: Option<PayloadCorpusInfo>,
+    /// Result of the pre-run check reading a stream that was never written
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub missing_stream_read: Option<MissingStreamRead>,
+    /// Acknowledged appends from the run's ledger read back after the workload
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ledger_verification: Option<LedgerVerification>,
+    /// Sampled operations ordered by `t_rel_us` (written to samples.jsonl, not the summary)
+    #[serde(skip)]
+    pub samples: Vec<RawSample>,
+    /// Latency of every successful operation per operation type (written to latency_cdf.json
+    /// as percentile tables)
+    #[serde(skip)]
+    pub op_latency: BTreeMap<&'static str, LatencyRecorder>,
+    /// Host CPU/load/memory/disk samples (written to host_stats.jsonl)
+    #[serde(skip)]
+    pub host_stats: Vec<HostStatsSample>,
     #[serde(skip)]  // Don't serialize histogram to JSON
     pub latency_histogram: LatencyRecorder,
 }
@@ -67,6 +464,24 @@ pub struct LatencyRecorder {
     pub hist: Histogram<u64>,
 }
 
+/// Number of points in the per-operation percentile tables (latency_cdf.json)
+pub const CDF_POINTS: usize = 200;
+/// Percentile tables reach p99.9999
+const CDF_MAX_NINES: f64 = 6.0;
+
+/// One row of a percentile -> latency table
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PercentilePoint {
+    pub percentile: f64,
+    pub latency_us: u64,
+}
+
+impl Default for LatencyRecorder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl LatencyRecorder {
     pub fn new() -> Self {
         Self {
@@ -99,6 +514,63 @@ impl LatencyRecorder {
         Ok(base64::engine::general_purpose::STANDARD.encode(&vec))
     }
 
+    /// Percentile distribution in HdrHistogram's `.hgrm` text format, values in milliseconds,
+    /// as read by the HdrHistogram plotter and `HistogramLogProcessor` tooling
+    pub fn to_hgrm(&self) -> String {
+        let mut out = format!(
+            "{:>12} {:>14} {:>10} {:>14}\n\n",
+            "Value", "Percentile", "TotalCount", "1/(1-Percentile)"
+        );
+        let mut total = 0;
+        for v in self.hist.iter_quantiles(5) {
+            total += v.count_since_last_iteration();
+            let value_ms = v.value_iterated_to() as f64 / 1000.0;
+            let quantile = v.quantile_iterated_to();
+            if quantile < 1.0 {
+                out += &format!(
+                    "{:12.3} {:2.12} {:10} {:14.2}\n",
+                    value_ms,
+                    quantile,
+                    total,
+                    1.0 / (1.0 - quantile)
+                );
+            } else {
+                out += &format!("{:12.3} {:2.12} {:10}\n", value_ms, quantile, total);
+            }
+        }
+        out += &format!(
+            "#[Mean    = {:12.3}, StdDeviation   = {:12.3}]\n#[Max     = {:12.3}, Total count    = {:12}]\n",
+            self.hist.mean() / 1000.0,
+            self.hist.stdev() / 1000.0,
+            self.hist.max() as f64 / 1000.0,
+            self.hist.len()
+        );
+        out
+    }
+
+    /// `points` percentiles spaced evenly in "nines" (log of 1 - p), from p0 up to p99.9999,
+    /// so the tail is resolved as finely as the body
+    pub fn percentile_table(&self, points: usize) -> Vec<PercentilePoint> {
+        let points = points.max(2);
+        (0..points)
+            .map(|i| {
+                let nines = CDF_MAX_NINES * i as f64 / (points - 1) as f64;
+                let percentile = 100.0 * (1.0 - 10f64.powf(-nines));
+                PercentilePoint {
+                    percentile,
+                    latency_us: self.hist.value_at_quantile(percentile / 100.0),
+                }
+            })
+            .collect()
+    }
+
+    /// Inverse of `serialize_to_base64`
+    pub fn deserialize_from_base64(encoded: &str) -> anyhow::Result<Self> {
+        let bytes = base64::engine::general_purpose::STANDARD.decode(encoded.trim())?;
+        let hist = Deserializer::new().deserialize(&mut bytes.as_slice())?;
+        Ok(Self { hist })
+    }
+
     /// Export histogram percentile data as JSON for analysis
     pub fn to_percentile_json(&self) -> serde_json::Value {
         let mut percentiles = Vec::new();
@@ -211,3 +683,52 @@ pub struct RunManifest {
     pub store: String,
     pub parameters: HashMap<String, serde_json::Value>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn buckets_sampled_operations_by_start_time() {
+        let sample = |t_rel_us: u64, latency_us: u64, ok: bool| RawSample {
+            t_rel_us,
+            t_ms: None,
+            worker: 0,
+            seq: 0,
+            op: "append",
+            latency_us,
+            ok,
+            stream: None,
+        };
+        let samples = [
+            sample(100_000, 1_000, true),
+            sample(400_000, 3_000, true),
+            sample(900_000, 2_000, true),
+            sample(1_600_000, 9_000, false),
+            sample(2_100_000, 5_000, true),
+        ];
+        let series = throughput_series(&samples, 100, Duration::from_millis(1000));
+        assert_eq!(series.len(), 3);
+        assert_eq!((series[0].ops_per_s, series[0].latency_p50_ms, series[0].latency_p99_ms), (300.0, Some(2.0), Some(3.0)));
+        // Failed operations count towards neither throughput nor latency
+        assert_eq!((series[1].ops_per_s, series[1].latency_p50_ms), (0.0, None));
+        assert_eq!((series[2].t_s, series[2].ops_per_s), (2.0, 100.0));
+    }
+
+    #[test]
+    fn writes_hgrm_percentile_distribution_in_milliseconds() {
+        let mut rec = LatencyRecorder::new();
+        for ms in 1..=100 {
+            rec.record(Duration::from_millis(ms));
+        }
+        let hgrm = rec.to_hgrm();
+        let rows: Vec<Vec<&str>> = hgrm
+            .lines()
+            .skip(2)
+            .take_while(|line| !line.starts_with('#'))
+            .map(|line| line.split_whitespace().collect())
+            .collect();
+        assert_eq!(rows.last().unwrap(), &["100.031", "1.000000000000", "100"]);
+        assert!(hgrm.contains("#[Max     =      100.031, Total count    =          100]"), "{}", hgrm);
+    }
+}
@@ -0,0 +1,246 @@
+use crate::adapter::{EventData, EventStoreAdapter, ReadRequest, StoreManager};
+use crate::workflow_support::{prepopulate_stream, run_on_started_store, stream_event};
+use anyhow::Result;
+use bytes::Bytes;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+
+/// Length of the stream the read primitives read from
+const READ_STREAM_EVENTS: u64 = 10_000;
+/// Resamples for the bootstrapped confidence interval of the mean
+const BOOTSTRAP_RESAMPLES: usize = 10_000;
+
+/// A single adapter call measured in isolation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Primitive {
+    Ping,
+    /// Append of a single event
+    Append1,
+    /// Append of a batch of 100 events to one stream
+    Append100,
+    /// Read of 100 events from the start of a stream
+    Read100,
+    /// Read of 10,000 events from the start of a stream
+    Read10k,
+}
+
+impl Primitive {
+    pub const ALL: [Primitive; 5] = [
+        Primitive::Ping,
+        Primitive::Append1,
+        Primitive::Append100,
+        Primitive::Read100,
+        Primitive::Read10k,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Primitive::Ping => "ping",
+            Primitive::Append1 => "append_1",
+            Primitive::Append100 => "append_100",
+            Primitive::Read100 => "read_100",
+            Primitive::Read10k => "read_10k",
+        }
+    }
+}
+
+/// Settings for benchmarking the adapter primitives one at a time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MicroBenchConfig {
+    /// Measured calls per primitive
+    pub iterations: usize,
+    /// Calls per primitive before measuring, to warm up connections and caches
+    pub warmup: usize,
+    pub event_size_bytes: usize,
+    /// Seed for the bootstrap resampling
+    pub seed: u64,
+}
+
+/// Criterion-style statistics of one primitive's call times
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrimitiveStats {
+    pub primitive: Primitive,
+    pub iterations: usize,
+    pub mean_ms: f64,
+    /// 95% confidence interval of the mean, bootstrapped from the samples
+    pub mean_ci_low_ms: f64,
+    pub mean_ci_high_ms: f64,
+    pub std_dev_ms: f64,
+    pub median_ms: f64,
+    /// Median absolute deviation from the median
+    pub mad_ms: f64,
+    pub min_ms: f64,
+    pub max_ms: f64,
+    /// Samples beyond 1.5 (mild) and 3 (severe) interquartile ranges from the quartiles
+    pub outliers_mild: usize,
+    pub outliers_severe: usize,
+    /// Events appended or returned per call, on average
+    pub events_per_call: f64,
+    /// Events per second of call time (appends and reads only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub throughput_eps: Option<f64>,
+}
+
+impl PrimitiveStats {
+    /// Summarize the call times of `primitive`, which moved `events` events in total
+    fn from_samples(primitive: Primitive, samples_ms: &[f64], events: u64, rng: &mut StdRng) -> Self {
+        let n = samples_ms.len();
+        let mut sorted = samples_ms.to_vec();
+        sorted.sort_by(f64::total_cmp);
+        let total: f64 = sorted.iter().sum();
+        let mean = total / n as f64;
+        let variance = sorted.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / (n.max(2) - 1) as f64;
+        let median = quantile(&sorted, 0.5);
+        let mut deviations: Vec<f64> = sorted.iter().map(|s| (s - median).abs()).collect();
+        deviations.sort_by(f64::total_cmp);
+
+        let mut means: Vec<f64> = (0..BOOTSTRAP_RESAMPLES)
+            .map(|_| (0..n).map(|_| sorted[rng.gen_range(0..n)]).sum::<f64>() / n as f64)
+            .collect();
+        means.sort_by(f64::total_cmp);
+
+        let (q1, q3) = (quantile(&sorted, 0.25), quantile(&sorted, 0.75));
+        let iqr = q3 - q1;
+        let beyond = |k: f64| sorted.iter().filter(|&&s| s < q1 - k * iqr || s > q3 + k * iqr).count();
+        let severe = beyond(3.0);
+
+        Self {
+            primitive,
+            iterations: n,
+            mean_ms: mean,
+            mean_ci_low_ms: quantile(&means, 0.025),
+            mean_ci_high_ms: quantile(&means, 0.975),
+            std_dev_ms: variance.sqrt(),
+            median_ms: median,
+            mad_ms: quantile(&deviations, 0.5),
+            min_ms: sorted[0],
+            max_ms: sorted[n - 1],
+            outliers_mild: beyond(1.5) - severe,
+            outliers_severe: severe,
+            events_per_call: events as f64 / n as f64,
+            throughput_eps: (primitive != Primitive::Ping && total > 0.0).then(|| events as f64 / (total / 1000.0)),
+        }
+    }
+}
+
+/// Quantile of sorted samples, interpolating between the closest two
+fn quantile(sorted: &[f64], q: f64) -> f64 {
+    let pos = q * (sorted.len() - 1) as f64;
+    let (lower, upper) = (pos.floor() as usize, pos.ceil() as usize);
+    sorted[lower] + (sorted[upper] - sorted[lower]) * (pos - lower as f64)
+}
+
+/// Outcome of a micro-benchmark
+#[derive(Debug, Clone, Serialize)]
+pub struct MicroBenchResult {
+    pub store: String,
+    pub config: MicroBenchConfig,
+    pub primitives: Vec<PrimitiveStats>,
+}
+
+/// Start the store and time each primitive on a single client, one call at a time
+pub async fn execute_micro_bench(
+    store: Box<dyn StoreManager>,
+    config: &MicroBenchConfig,
+    cancel_token: CancellationToken,
+) -> Result<MicroBenchResult> {
+    if config.iterations == 0 {
+        anyhow::bail!("Micro-benchmark requires at least one iteration");
+    }
+
+    run_on_started_store(store, &cancel_token, "micro-benchmark", async |store| {
+        measure_primitives(store, config).await
+    })
+    .await
+}
+
+async fn measure_primitives(store: &dyn StoreManager, config: &MicroBenchConfig) -> Result<MicroBenchResult> {
+    let adapter = store.create_adapter()?;
+    let payload = Bytes::from(vec![0u8; config.event_size_bytes]);
+    let run_id = Uuid::new_v4();
+
+    let read_stream = format!("micro-{}-read", run_id);
+    println!("Prepopulating a stream of {} events...", READ_STREAM_EVENTS);
+    prepopulate_stream(adapter.as_ref(), READ_STREAM_EVENTS, |_| stream_event(&read_stream, "micro", &payload)).await?;
+
+    let mut rng = StdRng::seed_from_u64(config.seed);
+    let mut primitives = Vec::new();
+    for primitive in Primitive::ALL {
+        let stream = format!("micro-{}-{}", run_id, primitive.as_str());
+        let mut samples_ms = Vec::with_capacity(config.iterations);
+        let mut moved = 0;
+        for i in 0..config.warmup + config.iterations {
+            let t0 = Instant::now();
+            let n = call(adapter.as_ref(), primitive, &stream, &read_stream, &payload).await?;
+            let elapsed_ms = t0.elapsed().as_secs_f64() * 1000.0;
+            if i >= config.warmup {
+                samples_ms.push(elapsed_ms);
+                moved += n;
+            }
+        }
+        let stats = PrimitiveStats::from_samples(primitive, &samples_ms, moved, &mut rng);
+        println!(
+            "  {:<12} time: [{:.3} ms {:.3} ms {:.3} ms]",
+            primitive.as_str(),
+            stats.mean_ci_low_ms,
+            stats.mean_ms,
+            stats.mean_ci_high_ms
+        );
+        primitives.push(stats);
+    }
+
+    Ok(MicroBenchResult {
+        store: store.name().to_string(),
+        config: config.clone(),
+        primitives,
+    })
+}
+
+fn events(stream: &str, payload: &Bytes, n: u64) -> Vec<EventData> {
+    (0..n).map(|_| stream_event(stream, "micro", payload)).collect()
+}
+
+/// Make one call of `primitive`; the number of events it appended or returned
+async fn call(
+    adapter: &dyn EventStoreAdapter,
+    primitive: Primitive,
+    stream: &str,
+    read_stream: &str,
+    payload: &Bytes,
+) -> Result<u64> {
+    let read = |limit: u64| ReadRequest {
+        stream: read_stream.to_string(),
+        from_offset: None,
+        limit: Some(limit),
+        consistency: Default::default(),
+    };
+    Ok(match primitive {
+        Primitive::Ping => adapter.ping().await.map(|_| 0)?,
+        Primitive::Append1 => adapter.append(events(stream, payload, 1)).await.map(|_| 1)?,
+        Primitive::Append100 => adapter.append(events(stream, payload, 100)).await.map(|_| 100)?,
+        Primitive::Read100 => adapter.read(read(100)).await?.len() as u64,
+        Primitive::Read10k => adapter.read(read(READ_STREAM_EVENTS)).await?.len() as u64,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summarizes_call_times() {
+        let mut samples: Vec<f64> = (1..=20).map(|i| i as f64).collect();
+        samples.push(100.0);
+        let stats = PrimitiveStats::from_samples(Primitive::Append1, &samples, 21, &mut StdRng::seed_from_u64(1));
+        assert_eq!((stats.median_ms, stats.min_ms, stats.max_ms), (11.0, 1.0, 100.0));
+        assert_eq!(stats.mad_ms, 5.0);
+        assert!(stats.mean_ci_low_ms < stats.mean_ms && stats.mean_ms < stats.mean_ci_high_ms);
+        // Quartiles 6 and 16: 100 is more than 3 IQRs beyond the upper one
+        assert_eq!((stats.outliers_mild, stats.outliers_severe), (0, 1));
+        assert_eq!(stats.events_per_call, 1.0);
+    }
+}
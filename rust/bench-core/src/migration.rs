@@ -0,0 +1,305 @@
+use crate::adapter::{EventData, EventStoreAdapter, ReadRequest, StoreManager};
+use crate::ledger::{verify_ledger, Ledger, LedgerOptions, LedgerVerification, Ledgered};
+use crate::metrics::{LatencyRecorder, LatencyStats};
+use crate::runner::start_store;
+use anyhow::Result;
+use bytes::{Bytes, BytesMut};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::task::JoinSet;
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+
+/// Settings for a store-to-store copy benchmark
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MigrationConfig {
+    /// Events written to the source store before copying
+    pub events: u64,
+    /// Number of streams the events are spread across
+    pub streams: u64,
+    pub event_size_bytes: usize,
+    /// Events per read page and per append
+    pub batch_size: u64,
+    /// Number of concurrent copy tasks (each owns a share of the streams)
+    pub concurrency: usize,
+}
+
+/// Outcome of a store-to-store copy
+#[derive(Debug, Clone, Serialize)]
+pub struct MigrationResult {
+    pub source: String,
+    pub target: String,
+    pub config: MigrationConfig,
+    pub events_copied: u64,
+    pub streams_copied: u64,
+    pub bytes_copied: u64,
+    /// Time spent seeding the source store (not part of the copy measurement)
+    pub seed_duration_s: f64,
+    pub duration_s: f64,
+    pub throughput_eps: f64,
+    pub throughput_bytes_per_s: f64,
+    /// Latency of each page read from the source
+    pub read_latency: LatencyStats,
+    /// Latency of each batch appended to the target
+    pub append_latency: LatencyStats,
+    /// Sampled seeded events read back from the target after the copy
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verification: Option<LedgerVerification>,
+}
+
+/// Start both stores, seed the source and copy every seeded stream into the target. With a
+/// `ledger`, a sample of the seeded events is recorded and read back from the target afterwards.
+pub async fn execute_migration(
+    mut source: Box<dyn StoreManager>,
+    mut target: Box<dyn StoreManager>,
+    config: &MigrationConfig,
+    ledger: Option<&LedgerOptions>,
+    cancel_token: CancellationToken,
+) -> Result<MigrationResult> {
+    if config.streams == 0 || config.batch_size == 0 || config.concurrency == 0 {
+        anyhow::bail!("Migration requires streams, batch size and concurrency > 0");
+    }
+
+    start_store(source.as_mut(), &cancel_token).await?;
+    if let Err(e) = start_store(target.as_mut(), &cancel_token).await {
+        source.stop().await.ok();
+        return Err(e);
+    }
+
+    let result = tokio::select! {
+        res = copy_between(source.as_ref(), target.as_ref(), config, ledger) => res,
+        _ = cancel_token.cancelled() => {
+            println!("Interrupted during migration.");
+            Err(anyhow::anyhow!("Interrupted"))
+        }
+    };
+
+    source.stop().await.ok();
+    target.stop().await.ok();
+    result
+}
+
+async fn copy_between(
+    source: &dyn StoreManager,
+    target: &dyn StoreManager,
+    config: &MigrationConfig,
+    ledger_options: Option<&LedgerOptions>,
+) -> Result<MigrationResult> {
+    let ledger = ledger_options.map(Ledger::create).transpose()?;
+    let stream_prefix = format!("migrate-{}-", Uuid::new_v4());
+    let streams: Vec<String> = (0..config.streams)
+        .map(|idx| format!("{}{}", stream_prefix, idx))
+        .collect();
+
+    println!(
+        "Seeding {} with {} events in {} streams...",
+        source.name(),
+        config.events,
+        config.streams
+    );
+    let seed_started = Instant::now();
+    let seeded: Vec<(String, u64)> = streams
+        .iter()
+        .enumerate()
+        .map(|(idx, stream)| (stream.clone(), stream_events(config.events, config.streams, idx as u64)))
+        .collect();
+    let mut seed_set = JoinSet::new();
+    for (task, chunk) in partition(&seeded, config.concurrency).into_iter().enumerate() {
+        let mut adapter = source.create_adapter()?;
+        if let Some(ledger) = &ledger {
+            adapter = Arc::new(Ledgered::new(adapter, ledger.clone()));
+        }
+        let event_size = config.event_size_bytes;
+        let batch_size = config.batch_size;
+        let payload = Bytes::from(vec![0u8; event_size]);
+        // The ledger tells events apart by payload, so they are numbered when one is kept
+        let stamp = ledger.is_some() && event_size >= 16;
+        seed_set.spawn(async move {
+            let mut seq = 0u64;
+            for (stream, events) in chunk {
+                let mut remaining = events;
+                while remaining > 0 {
+                    let n = remaining.min(batch_size);
+                    let events = (0..n)
+                        .map(|_| EventData {
+                            payload: if stamp {
+                                seq += 1;
+                                let mut stamped = BytesMut::from(&payload[..]);
+                                stamped[..8].copy_from_slice(&(task as u64).to_le_bytes());
+                                stamped[8..16].copy_from_slice(&seq.to_le_bytes());
+                                stamped.freeze()
+                            } else {
+                                payload.clone()
+                            },
+                            event_type: "migrate".to_string(),
+                            tags: vec![stream.clone()],
+                        })
+                        .collect();
+                    adapter.append(events).await?;
+                    remaining -= n;
+                }
+            }
+            Ok::<(), anyhow::Error>(())
+        });
+    }
+    while let Some(res) = seed_set.join_next().await {
+        res??;
+    }
+    let seed_duration_s = seed_started.elapsed().as_secs_f64();
+    println!("Seeding completed in {:.2} seconds", seed_duration_s);
+
+    println!(
+        "Copying from {} to {} with {} tasks...",
+        source.name(),
+        target.name(),
+        config.concurrency
+    );
+    let copy_started = Instant::now();
+    let mut copy_set = JoinSet::new();
+    for chunk in partition(&streams, config.concurrency) {
+        let reader = source.create_adapter()?;
+        let writer = target.create_adapter()?;
+        let batch_size = config.batch_size;
+        copy_set.spawn(async move { copy_streams(reader, writer, chunk, batch_size).await });
+    }
+
+    let mut read_latency = LatencyRecorder::new();
+    let mut append_latency = LatencyRecorder::new();
+    let mut events_copied = 0u64;
+    let mut bytes_copied = 0u64;
+    while let Some(res) = copy_set.join_next().await {
+        let copied = res??;
+        read_latency.hist.add(&copied.read_latency.hist)?;
+        append_latency.hist.add(&copied.append_latency.hist)?;
+        events_copied += copied.events;
+        bytes_copied += copied.bytes;
+    }
+    let duration_s = copy_started.elapsed().as_secs_f64();
+
+    let verification = match (&ledger, ledger_options) {
+        (Some(ledger), Some(options)) => {
+            let recorded = ledger.finish()?;
+            println!("Reading {} sampled events back from {}...", recorded, target.name());
+            let verification = verify_ledger(&options.path, target.create_adapter()?.as_ref()).await?;
+            if verification.missing > 0 {
+                eprintln!(
+                    "Warning: {} of {} sampled events were not found in {}",
+                    verification.missing,
+                    verification.checked,
+                    target.name()
+                );
+            }
+            Some(verification)
+        }
+        _ => None,
+    };
+
+    Ok(MigrationResult {
+        source: source.name().to_string(),
+        target: target.name().to_string(),
+        config: config.clone(),
+        events_copied,
+        streams_copied: config.streams,
+        bytes_copied,
+        seed_duration_s,
+        duration_s,
+        throughput_eps: events_copied as f64 / duration_s.max(0.001),
+        throughput_bytes_per_s: bytes_copied as f64 / duration_s.max(0.001),
+        read_latency: read_latency.to_stats(),
+        append_latency: append_latency.to_stats(),
+        verification,
+    })
+}
+
+struct CopiedStreams {
+    events: u64,
+    bytes: u64,
+    read_latency: LatencyRecorder,
+    append_latency: LatencyRecorder,
+}
+
+/// Page through each stream in the source and append every page to the same stream in the target
+async fn copy_streams(
+    reader: Arc<dyn EventStoreAdapter>,
+    writer: Arc<dyn EventStoreAdapter>,
+    streams: Vec<String>,
+    batch_size: u64,
+) -> Result<CopiedStreams> {
+    let mut copied = CopiedStreams {
+        events: 0,
+        bytes: 0,
+        read_latency: LatencyRecorder::new(),
+        append_latency: LatencyRecorder::new(),
+    };
+
+    for stream in streams {
+        let mut from_offset = None;
+        loop {
+            let req = ReadRequest {
+                stream: stream.clone(),
+                from_offset,
+                limit: Some(batch_size),
+                consistency: Default::default(),
+            };
+            let read_started = Instant::now();
+            let page = reader.read(req).await?;
+            copied.read_latency.record(read_started.elapsed());
+
+            let Some(last) = page.last() else {
+                break;
+            };
+            from_offset = Some(last.offset + 1);
+            let page_len = page.len() as u64;
+
+            let events: Vec<EventData> = page
+                .into_iter()
+                .map(|evt| EventData {
+                    payload: evt.payload,
+                    event_type: evt.event_type,
+                    tags: vec![stream.clone()],
+                })
+                .collect();
+            copied.bytes += events.iter().map(|e| e.payload.len() as u64).sum::<u64>();
+
+            let append_started = Instant::now();
+            writer.append(events).await?;
+            copied.append_latency.record(append_started.elapsed());
+            copied.events += page_len;
+
+            if page_len < batch_size {
+                break;
+            }
+        }
+    }
+    Ok(copied)
+}
+
+/// Split streams round-robin into at most `parts` non-empty chunks
+fn partition<T: Clone>(streams: &[T], parts: usize) -> Vec<Vec<T>> {
+    let parts = parts.min(streams.len());
+    let mut chunks = vec![Vec::new(); parts];
+    for (i, stream) in streams.iter().enumerate() {
+        chunks[i % parts].push(stream.clone());
+    }
+    chunks
+}
+
+/// Events seeded into stream `idx`: an even share of `events`, the first streams taking one
+/// more each until the remainder is used up
+fn stream_events(events: u64, streams: u64, idx: u64) -> u64 {
+    events / streams + u64::from(idx < events % streams)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spreads_exactly_the_configured_events_over_the_streams() {
+        let per_stream: Vec<u64> = (0..4).map(|idx| stream_events(10, 4, idx)).collect();
+        assert_eq!(per_stream, [3, 3, 2, 2]);
+        assert_eq!((0..7).map(|idx| stream_events(100, 7, idx)).sum::<u64>(), 100);
+        assert_eq!(stream_events(2, 5, 4), 0);
+    }
+}
@@ -0,0 +1,170 @@
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+/// Option values to try per store, e.g. `umadb: {batch_size: [10, 100, 1000]}`
+pub type OptionSweep = HashMap<String, BTreeMap<String, Vec<String>>>;
+
+/// One store configuration to run: a store started with a particular set of options
+#[derive(Debug, Clone)]
+pub struct StoreRun {
+    pub store: String,
+    /// Name the run's results are stored under: the store name, followed by the swept option
+    /// values when the store is swept (`umadb-batch_size-100`)
+    pub label: String,
+    /// `store_options` of the store overridden by the swept values
+    pub options: HashMap<String, String>,
+    /// Only the swept options
+    pub swept: BTreeMap<String, String>,
+}
+
+/// Expand stores into the runs of every combination of their swept option values
+pub fn store_runs(
+    stores: &[String],
+    store_options: &HashMap<String, HashMap<String, String>>,
+    option_sweep: &OptionSweep,
+) -> Vec<StoreRun> {
+    let mut runs = Vec::new();
+    for store in stores {
+        let base = store_options.get(store).cloned().unwrap_or_default();
+        let mut combinations = vec![BTreeMap::new()];
+        for (key, values) in option_sweep.get(store).into_iter().flatten() {
+            combinations = combinations
+                .into_iter()
+                .flat_map(|combination| {
+                    values.iter().map(move |value| {
+                        let mut combination = combination.clone();
+                        combination.insert(key.clone(), value.clone());
+                        combination
+                    })
+                })
+                .collect();
+        }
+        for swept in combinations {
+            let mut label = store.clone();
+            for (key, value) in &swept {
+                label.push_str(&format!("-{}-{}", key, value));
+            }
+            let mut options = base.clone();
+            options.extend(swept.iter().map(|(k, v)| (k.clone(), v.clone())));
+            runs.push(StoreRun {
+                store: store.clone(),
+                label,
+                options,
+                swept,
+            });
+        }
+    }
+    runs
+}
+
+/// Outcome of one swept configuration
+#[derive(Debug, Clone, Serialize)]
+pub struct OptionSweepResult {
+    pub workload: String,
+    pub store: String,
+    pub options: BTreeMap<String, String>,
+    pub throughput_eps: f64,
+    pub latency_p99_ms: f64,
+}
+
+/// Highest-throughput configuration of a store for one workload variant
+#[derive(Debug, Clone, Serialize)]
+pub struct BestConfiguration {
+    pub workload: String,
+    pub store: String,
+    pub options: BTreeMap<String, String>,
+    pub throughput_eps: f64,
+    pub latency_p99_ms: f64,
+    /// Every configuration tried, in run order
+    pub results: Vec<OptionSweepResult>,
+}
+
+/// Pick the configuration with the highest throughput per workload and store; ties go to
+/// the lower p99
+pub fn best_configurations(results: &[OptionSweepResult]) -> Vec<BestConfiguration> {
+    let mut groups: BTreeMap<(&str, &str), Vec<&OptionSweepResult>> = BTreeMap::new();
+    for result in results {
+        groups
+            .entry((result.workload.as_str(), result.store.as_str()))
+            .or_default()
+            .push(result);
+    }
+    groups
+        .into_values()
+        .filter_map(|group| {
+            let best = group.iter().max_by(|a, b| {
+                a.throughput_eps
+                    .total_cmp(&b.throughput_eps)
+                    .then(b.latency_p99_ms.total_cmp(&a.latency_p99_ms))
+            })?;
+            Some(BestConfiguration {
+                workload: best.workload.clone(),
+                store: best.store.clone(),
+                options: best.options.clone(),
+                throughput_eps: best.throughput_eps,
+                latency_p99_ms: best.latency_p99_ms,
+                results: group.into_iter().cloned().collect(),
+            })
+        })
+        .collect()
+}
+
+/// Order to run every (workload, store run) pair of a session in, as indices: config order
+/// (workloads outer, store runs inner), or shuffled by `shuffle_seed` so drifting host
+/// conditions (thermal throttling, a filling disk) don't always penalize the same store
+pub fn run_order(workloads: usize, store_runs: usize, shuffle_seed: Option<u64>) -> Vec<(usize, usize)> {
+    let mut order: Vec<(usize, usize)> = (0..workloads)
+        .flat_map(|w| (0..store_runs).map(move |s| (w, s)))
+        .collect();
+    if let Some(seed) = shuffle_seed {
+        order.shuffle(&mut StdRng::seed_from_u64(seed));
+    }
+    order
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expands_every_combination_of_swept_values() {
+        let stores = vec!["umadb".to_string(), "dummy".to_string()];
+        let store_options = HashMap::from([(
+            "umadb".to_string(),
+            HashMap::from([("batch_size".to_string(), "1".to_string())]),
+        )]);
+        let sweep = OptionSweep::from([(
+            "umadb".to_string(),
+            BTreeMap::from([
+                ("batch_size".to_string(), vec!["10".to_string(), "100".to_string()]),
+                ("mode".to_string(), vec!["a".to_string(), "b".to_string()]),
+            ]),
+        )]);
+
+        let runs = store_runs(&stores, &store_options, &sweep);
+        let labels: Vec<&str> = runs.iter().map(|r| r.label.as_str()).collect();
+        assert_eq!(
+            labels,
+            [
+                "umadb-batch_size-10-mode-a",
+                "umadb-batch_size-10-mode-b",
+                "umadb-batch_size-100-mode-a",
+                "umadb-batch_size-100-mode-b",
+                "dummy",
+            ]
+        );
+        assert_eq!(runs[2].options["batch_size"], "100");
+        assert!(runs[4].options.is_empty());
+    }
+
+    #[test]
+    fn shuffled_run_order_is_a_seeded_permutation() {
+        assert_eq!(run_order(2, 2, None), [(0, 0), (0, 1), (1, 0), (1, 1)]);
+        let shuffled = run_order(3, 4, Some(7));
+        assert_eq!(shuffled, run_order(3, 4, Some(7)));
+        let mut sorted = shuffled.clone();
+        sorted.sort();
+        assert_eq!(sorted, run_order(3, 4, None));
+    }
+}
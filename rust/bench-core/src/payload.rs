@@ -0,0 +1,401 @@
+use bytes::{Bytes, BytesMut};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Default number of distinct payloads generated for random and JSON content
+pub const DEFAULT_CORPUS_SIZE: usize = 256;
+
+/// Content of appended event payloads (`operations.write.payload`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PayloadConfig {
+    #[serde(default)]
+    pub kind: PayloadKind,
+    /// Distinct payloads pre-generated at startup and cycled through during the run
+    /// (default [`DEFAULT_CORPUS_SIZE`]; zero payloads are all the same)
+    #[serde(default)]
+    pub corpus_size: Option<usize>,
+    /// Register the avro or protobuf schema with a schema registry and prefix payloads with
+    /// the id it assigns, as Kafka producers do
+    #[serde(default)]
+    pub schema_registry: Option<SchemaRegistryConfig>,
+}
+
+/// Confluent-compatible schema registry the payload schema is registered with
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SchemaRegistryConfig {
+    pub url: String,
+    #[serde(default = "default_subject")]
+    pub subject: String,
+}
+
+fn default_subject() -> String {
+    "eventstore-benchmarks-value".to_string()
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PayloadKind {
+    /// All-zero bytes (compresses perfectly)
+    #[default]
+    Zeros,
+    /// Uniformly random bytes (incompressible)
+    Random,
+    /// JSON objects with random field values, padded to the event size
+    Json,
+    /// Structured-mode CloudEvents 1.0 envelopes with a JSON object as `data`. EventsourcingDB
+    /// takes `source`, `type` and `data` natively; other stores store the envelope as is.
+    #[serde(rename = "cloudevents")]
+    CloudEvents,
+    /// The JSON fields as a protobuf message ([`PROTOBUF_SCHEMA`])
+    Protobuf,
+    /// The JSON fields as an Avro binary datum ([`AVRO_SCHEMA`])
+    Avro,
+}
+
+/// Identifies the payloads of a run, recorded in run.meta.json
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PayloadCorpusInfo {
+    pub kind: PayloadKind,
+    pub payloads: usize,
+    pub event_size_bytes: usize,
+    /// FNV-1a over every payload in order; equal hashes mean identical payload content
+    pub hash: String,
+    /// Schema registry id the payloads are framed with
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schema_id: Option<u32>,
+}
+
+/// Payloads generated once from the workload seed, so producing them costs nothing during the
+/// run and the same seed yields byte-identical payloads
+#[derive(Debug)]
+pub struct PayloadCorpus {
+    payloads: Vec<Bytes>,
+    info: PayloadCorpusInfo,
+}
+
+impl PayloadCorpus {
+    pub fn generate(config: &PayloadConfig, event_size: usize, seed: u64) -> Self {
+        let count = match config.kind {
+            PayloadKind::Zeros => 1,
+            _ => config.corpus_size.unwrap_or(DEFAULT_CORPUS_SIZE).max(1),
+        };
+        let mut rng = StdRng::seed_from_u64(seed);
+        // Leave room for the registry framing, added once the schema is registered
+        let framing = match config.schema_registry {
+            Some(_) => framing_len(config.kind),
+            None => 0,
+        };
+        let body_size = event_size.saturating_sub(framing);
+        let payloads: Vec<Bytes> = (0..count)
+            .map(|_| match config.kind {
+                PayloadKind::Zeros => Bytes::from(vec![0u8; body_size]),
+                PayloadKind::Random => {
+                    let mut payload = BytesMut::zeroed(body_size);
+                    rng.fill(&mut payload[..]);
+                    payload.freeze()
+                }
+                PayloadKind::Json => Bytes::from(json_payload(&mut rng, body_size)),
+                PayloadKind::CloudEvents => Bytes::from(cloud_event_payload(&mut rng, body_size)),
+                PayloadKind::Protobuf => Bytes::from(protobuf_payload(&mut rng, body_size)),
+                PayloadKind::Avro => Bytes::from(avro_payload(&mut rng, body_size)),
+            })
+            .collect();
+
+        let hash = fnv1a(payloads.iter().flatten());
+        let info = PayloadCorpusInfo {
+            kind: config.kind,
+            payloads: payloads.len(),
+            event_size_bytes: event_size,
+            hash: format!("{:016x}", hash),
+            schema_id: None,
+        };
+        Self { payloads, info }
+    }
+
+    /// The payloads in the Confluent wire format: a zero magic byte and the big-endian schema
+    /// id, then for protobuf the index of the message in the schema (0, the first)
+    pub fn framed(&self, schema_id: u32) -> Self {
+        let payloads: Vec<Bytes> = self
+            .payloads
+            .iter()
+            .map(|payload| {
+                let mut framed = BytesMut::with_capacity(framing_len(self.info.kind) + payload.len());
+                framed.extend_from_slice(&[0]);
+                framed.extend_from_slice(&schema_id.to_be_bytes());
+                if self.info.kind == PayloadKind::Protobuf {
+                    framed.extend_from_slice(&[0]);
+                }
+                framed.extend_from_slice(payload);
+                framed.freeze()
+            })
+            .collect();
+        let info = PayloadCorpusInfo {
+            hash: format!("{:016x}", fnv1a(payloads.iter().flatten())),
+            schema_id: Some(schema_id),
+            ..self.info.clone()
+        };
+        Self { payloads, info }
+    }
+
+    pub fn info(&self) -> &PayloadCorpusInfo {
+        &self.info
+    }
+
+    /// Cycle through the corpus for one worker, starting at a worker-specific offset so
+    /// concurrent workers do not append the same payload at the same time
+    pub fn cursor(self: &Arc<Self>, worker: usize) -> PayloadCursor {
+        PayloadCursor {
+            corpus: self.clone(),
+            next: worker.wrapping_mul(7919) % self.payloads.len(),
+        }
+    }
+}
+
+pub struct PayloadCursor {
+    corpus: Arc<PayloadCorpus>,
+    next: usize,
+}
+
+impl PayloadCursor {
+    /// The next payload, sharing the corpus buffer
+    pub fn next_payload(&mut self) -> Bytes {
+        let idx = self.next;
+        self.next = (self.next + 1) % self.corpus.payloads.len();
+        self.corpus.payloads[idx].clone()
+    }
+}
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+/// FNV-1a hash of a byte sequence, stable across platforms and releases
+pub(crate) fn fnv1a<'a>(bytes: impl IntoIterator<Item = &'a u8>) -> u64 {
+    bytes.into_iter().fold(FNV_OFFSET_BASIS, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(FNV_PRIME)
+    })
+}
+const ALPHANUMERIC: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+
+/// `{"id":..,"amount":..,"active":..,"note":"<random text>"}`, with the note sized so the
+/// document is `size` bytes (or minimal, if `size` is too small for the fixed fields)
+fn json_payload(rng: &mut StdRng, size: usize) -> Vec<u8> {
+    let head = format!(
+        r#"{{"id":"{:016x}","amount":{},"active":{},"note":""#,
+        rng.gen::<u64>(),
+        rng.gen_range(0..1_000_000),
+        rng.gen_bool(0.5)
+    );
+    let note_len = size.saturating_sub(head.len() + 2);
+    let mut payload = head.into_bytes();
+    push_text(&mut payload, rng, note_len);
+    payload.extend_from_slice(b"\"}");
+    payload
+}
+
+fn push_text(out: &mut Vec<u8>, rng: &mut StdRng, len: usize) {
+    out.extend((0..len).map(|_| ALPHANUMERIC[rng.gen_range(0..ALPHANUMERIC.len())]));
+}
+
+/// Schema of protobuf payloads
+pub const PROTOBUF_SCHEMA: &str = r#"syntax = "proto3";
+package eventstorebenchmarks;
+
+message BenchEvent {
+  string id = 1;
+  int64 amount = 2;
+  bool active = 3;
+  string note = 4;
+}
+"#;
+
+/// Schema of Avro payloads
+pub const AVRO_SCHEMA: &str = r#"{"type":"record","name":"BenchEvent","namespace":"eventstorebenchmarks","fields":[{"name":"id","type":"string"},{"name":"amount","type":"long"},{"name":"active","type":"boolean"},{"name":"note","type":"string"}]}"#;
+
+/// Bytes the Confluent wire format adds in front of a payload
+fn framing_len(kind: PayloadKind) -> usize {
+    match kind {
+        PayloadKind::Protobuf => 6,
+        _ => 5,
+    }
+}
+
+fn put_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+fn varint_len(value: u64) -> usize {
+    (64 - value.leading_zeros() as usize).max(1).div_ceil(7)
+}
+
+/// Avro encodes lengths and longs as zigzag varints
+fn zigzag(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+/// Length of a text whose encoded length prefix (`encode`d) and content fill `room` bytes; one
+/// byte short where no length fits exactly
+fn fill_len(room: usize, encode: impl Fn(usize) -> u64) -> usize {
+    (1..=10)
+        .find_map(|prefix| room.checked_sub(prefix).filter(|len| varint_len(encode(*len)) == prefix))
+        .unwrap_or_else(|| room.saturating_sub(varint_len(encode(room))))
+}
+
+/// The [`json_payload`] fields as a `BenchEvent` protobuf message of `size` bytes (or minimal).
+/// Like protobuf encoders, fields with default values are left out.
+fn protobuf_payload(rng: &mut StdRng, size: usize) -> Vec<u8> {
+    let id = format!("{:016x}", rng.gen::<u64>());
+    let amount: u64 = rng.gen_range(0..1_000_000);
+    let active = rng.gen_bool(0.5);
+    let mut payload = Vec::with_capacity(size);
+    payload.push(0x0a);
+    put_varint(&mut payload, id.len() as u64);
+    payload.extend_from_slice(id.as_bytes());
+    if amount != 0 {
+        payload.push(0x10);
+        put_varint(&mut payload, amount);
+    }
+    if active {
+        payload.extend_from_slice(&[0x18, 1]);
+    }
+    let note_len = fill_len(size.saturating_sub(payload.len() + 1), |len| len as u64);
+    if note_len > 0 {
+        payload.push(0x22);
+        put_varint(&mut payload, note_len as u64);
+        push_text(&mut payload, rng, note_len);
+    }
+    payload
+}
+
+/// The [`json_payload`] fields as a `BenchEvent` Avro datum of `size` bytes (or minimal)
+fn avro_payload(rng: &mut StdRng, size: usize) -> Vec<u8> {
+    let id = format!("{:016x}", rng.gen::<u64>());
+    let amount: i64 = rng.gen_range(0..1_000_000);
+    let active = rng.gen_bool(0.5);
+    let mut payload = Vec::with_capacity(size);
+    put_varint(&mut payload, zigzag(id.len() as i64));
+    payload.extend_from_slice(id.as_bytes());
+    put_varint(&mut payload, zigzag(amount));
+    payload.push(active as u8);
+    let note_len = fill_len(size.saturating_sub(payload.len()), |len| zigzag(len as i64));
+    put_varint(&mut payload, zigzag(note_len as i64));
+    push_text(&mut payload, rng, note_len);
+    payload
+}
+
+/// `source` of generated CloudEvents
+pub const CLOUD_EVENT_SOURCE: &str = "https://github.com/pyeventsourcing/eventstore-benchmarks";
+/// `type` of generated CloudEvents
+pub const CLOUD_EVENT_TYPE: &str = "io.eventstorebenchmarks.event";
+/// Generated event times fall in the year from this Unix time (2024-01-01)
+const CLOUD_EVENT_EPOCH: i64 = 1_704_067_200;
+
+/// CloudEvents 1.0 envelope in structured JSON mode
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CloudEvent {
+    pub specversion: String,
+    pub id: String,
+    pub source: String,
+    #[serde(rename = "type")]
+    pub ty: String,
+    pub time: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub datacontenttype: Option<String>,
+    pub data: serde_json::Value,
+}
+
+impl CloudEvent {
+    /// Parse a payload generated as a CloudEvent; `None` for other payloads, which are told
+    /// apart by the leading `specversion` without parsing them
+    pub fn parse(payload: &[u8]) -> Option<Self> {
+        if !payload.starts_with(br#"{"specversion":"#) {
+            return None;
+        }
+        serde_json::from_slice(payload).ok()
+    }
+}
+
+/// A CloudEvent with a random v4 `id` and `time`, and a [`json_payload`] as `data` sized so the
+/// envelope is `size` bytes (or minimal)
+fn cloud_event_payload(rng: &mut StdRng, size: usize) -> Vec<u8> {
+    let id = uuid::Builder::from_random_bytes(rng.gen()).into_uuid();
+    let time = chrono::DateTime::from_timestamp(
+        CLOUD_EVENT_EPOCH + rng.gen_range(0..365 * 86_400),
+        rng.gen_range(0..1_000_000) * 1_000,
+    )
+    .unwrap_or_default();
+    let head = format!(
+        r#"{{"specversion":"1.0","id":"{}","source":"{}","type":"{}","time":"{}","datacontenttype":"application/json","data":"#,
+        id,
+        CLOUD_EVENT_SOURCE,
+        CLOUD_EVENT_TYPE,
+        time.to_rfc3339_opts(chrono::SecondsFormat::Micros, true)
+    );
+    let mut payload = head.into_bytes();
+    payload.extend(json_payload(rng, size.saturating_sub(payload.len() + 1)));
+    payload.push(b'}');
+    payload
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_gives_same_corpus() {
+        let config = PayloadConfig {
+            kind: PayloadKind::Json,
+            corpus_size: Some(8),
+            schema_registry: None,
+        };
+        let a = PayloadCorpus::generate(&config, 200, 42);
+        let b = PayloadCorpus::generate(&config, 200, 42);
+        let c = PayloadCorpus::generate(&config, 200, 43);
+        assert_eq!(a.info(), b.info());
+        assert_ne!(a.info().hash, c.info().hash);
+        for payload in &a.payloads {
+            assert_eq!(payload.len(), 200);
+            serde_json::from_slice::<serde_json::Value>(payload).unwrap();
+        }
+    }
+
+    #[test]
+    fn cloud_events_fill_the_event_size() {
+        let config = PayloadConfig {
+            kind: PayloadKind::CloudEvents,
+            corpus_size: Some(8),
+            schema_registry: None,
+        };
+        for payload in &PayloadCorpus::generate(&config, 400, 42).payloads {
+            assert_eq!(payload.len(), 400);
+            let event = CloudEvent::parse(payload).unwrap();
+            assert_eq!((event.specversion.as_str(), event.ty.as_str()), ("1.0", CLOUD_EVENT_TYPE));
+            assert!(event.data["note"].is_string());
+        }
+    }
+
+    #[test]
+    fn binary_payloads_fill_the_event_size_with_framing() {
+        for kind in [PayloadKind::Protobuf, PayloadKind::Avro] {
+            let config = PayloadConfig {
+                kind,
+                corpus_size: Some(8),
+                schema_registry: Some(SchemaRegistryConfig {
+                    url: "http://localhost:8081".to_string(),
+                    subject: default_subject(),
+                }),
+            };
+            for size in [64, 129, 1000] {
+                let corpus = PayloadCorpus::generate(&config, size, 42).framed(7);
+                for payload in &corpus.payloads {
+                    assert!((size - 1..=size).contains(&payload.len()), "{:?} {}", kind, payload.len());
+                    assert_eq!(payload[..5], [0, 0, 0, 0, 7]);
+                }
+            }
+        }
+    }
+}
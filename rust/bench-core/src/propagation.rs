@@ -0,0 +1,235 @@
+use crate::adapter::{EventData, EventStoreAdapter, ReadRequest, StoreManager};
+use crate::metrics::{LatencyRecorder, LatencyStats, PercentilePoint, CDF_POINTS};
+use crate::rate_limit::OpenLoopSchedule;
+use crate::workflow_support::{run_on_started_store, STALL_TIMEOUT};
+use anyhow::Result;
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tokio::time::Instant;
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+
+/// Settings for measuring how long appended events take to become readable
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PropagationConfig {
+    /// Streams written and tailed concurrently, each by its own writer and reader
+    pub streams: usize,
+    pub events_per_stream: u64,
+    /// Appends per second over all writers, one event per append
+    pub write_rate_eps: f64,
+    /// Pause of a reader after a read that returned nothing new
+    pub poll_interval_ms: u64,
+    pub event_size_bytes: usize,
+}
+
+/// Outcome of a write-to-read propagation study
+#[derive(Debug, Clone, Serialize)]
+pub struct PropagationResult {
+    pub store: String,
+    pub config: PropagationConfig,
+    pub events: u64,
+    /// From when each append was due until the store acknowledged it
+    pub append: LatencyStats,
+    /// From the acknowledgement of each append until a read returned the event
+    pub end_to_end: LatencyStats,
+    pub end_to_end_max_ms: f64,
+    /// Events a read returned before their append was acknowledged (counted as 0 ms)
+    pub visible_before_ack: u64,
+    /// Events no read returned before the readers gave up
+    pub unseen: u64,
+    pub append_cdf: Vec<PercentilePoint>,
+    pub end_to_end_cdf: Vec<PercentilePoint>,
+}
+
+/// Start the store, then append sequence-numbered events to every stream at a steady rate
+/// while a reader tails each stream, and record when each event first comes back from a read
+pub async fn execute_propagation(
+    store: Box<dyn StoreManager>,
+    config: &PropagationConfig,
+    cancel_token: CancellationToken,
+) -> Result<PropagationResult> {
+    if config.streams == 0 || config.events_per_stream == 0 {
+        anyhow::bail!("Propagation requires streams and events per stream > 0");
+    }
+    if !(config.write_rate_eps.is_finite() && config.write_rate_eps > 0.0) {
+        anyhow::bail!("Propagation requires a write rate > 0");
+    }
+
+    run_on_started_store(store, &cancel_token, "propagation run", async |store| {
+        measure_propagation(store, config).await
+    })
+    .await
+}
+
+async fn measure_propagation(store: &dyn StoreManager, config: &PropagationConfig) -> Result<PropagationResult> {
+    let run_id = Uuid::new_v4();
+    println!(
+        "Appending {} events to each of {} streams at {} events/sec while tailing them...",
+        config.events_per_stream, config.streams, config.write_rate_eps
+    );
+    let mut tails = Vec::with_capacity(config.streams);
+    for i in 0..config.streams {
+        let writer = store.create_adapter()?;
+        let reader = store.create_adapter()?;
+        let stream = format!("propagation-{}-{}", run_id, i);
+        tails.push(async move { tail_stream(writer.as_ref(), reader.as_ref(), &stream, config).await });
+    }
+    let outcomes = futures::future::try_join_all(tails).await?;
+
+    let mut append = LatencyRecorder::new();
+    let mut end_to_end = LatencyRecorder::new();
+    let (mut end_to_end_max, mut visible_before_ack, mut unseen) = (Duration::ZERO, 0, 0);
+    for outcome in outcomes {
+        append.hist.add(&outcome.append.hist)?;
+        let visibility = Visibility::of(&outcome.acked, &outcome.seen);
+        end_to_end.hist.add(&visibility.end_to_end.hist)?;
+        end_to_end_max = end_to_end_max.max(visibility.max);
+        visible_before_ack += visibility.before_ack;
+        unseen += visibility.unseen;
+    }
+
+    Ok(PropagationResult {
+        store: store.name().to_string(),
+        config: config.clone(),
+        events: config.streams as u64 * config.events_per_stream,
+        append: append.to_stats(),
+        end_to_end: end_to_end.to_stats(),
+        end_to_end_max_ms: end_to_end_max.as_secs_f64() * 1000.0,
+        visible_before_ack,
+        unseen,
+        append_cdf: append.percentile_table(CDF_POINTS),
+        end_to_end_cdf: end_to_end.percentile_table(CDF_POINTS),
+    })
+}
+
+/// Acknowledgement and first-read times of one stream's events, by sequence number
+struct StreamOutcome {
+    append: LatencyRecorder,
+    acked: Vec<Instant>,
+    seen: Vec<Option<Instant>>,
+}
+
+/// Write one stream at its share of the rate and tail it until every event was read or the
+/// reads stall
+async fn tail_stream(
+    writer: &dyn EventStoreAdapter,
+    reader: &dyn EventStoreAdapter,
+    stream: &str,
+    config: &PropagationConfig,
+) -> Result<StreamOutcome> {
+    let n = config.events_per_stream as usize;
+    let write = async {
+        let mut schedule = OpenLoopSchedule::new(config.write_rate_eps / config.streams as f64)?;
+        let mut append = LatencyRecorder::new();
+        let mut acked = Vec::with_capacity(n);
+        for seq in 0..n as u64 {
+            let due = schedule.next_due().await;
+            writer.append(vec![sequenced_event(stream, "propagation", seq, config.event_size_bytes)]).await?;
+            let now = Instant::now();
+            append.record(now.duration_since(due));
+            acked.push(now);
+        }
+        Ok::<_, anyhow::Error>((append, acked))
+    };
+    let read = async {
+        let poll_interval = Duration::from_millis(config.poll_interval_ms);
+        let mut seen = vec![None; n];
+        let mut remaining = n;
+        let mut from_offset = None;
+        let mut last_progress = Instant::now();
+        while remaining > 0 && last_progress.elapsed() < STALL_TIMEOUT {
+            let req = ReadRequest {
+                stream: stream.to_string(),
+                from_offset,
+                limit: None,
+                consistency: Default::default(),
+            };
+            let events = reader.read(req).await?;
+            let now = Instant::now();
+            if events.is_empty() {
+                tokio::time::sleep(poll_interval).await;
+                continue;
+            }
+            last_progress = now;
+            for event in events {
+                from_offset = Some(event.offset + 1);
+                let Some(slot) = sequence_of(&event.payload).and_then(|seq| seen.get_mut(seq as usize)) else {
+                    continue;
+                };
+                if slot.is_none() {
+                    *slot = Some(now);
+                    remaining -= 1;
+                }
+            }
+        }
+        Ok::<_, anyhow::Error>(seen)
+    };
+    let ((append, acked), seen) = tokio::try_join!(write, read)?;
+    Ok(StreamOutcome { append, acked, seen })
+}
+
+/// An event whose payload starts with its sequence number in the stream
+pub(crate) fn sequenced_event(stream: &str, event_type: &str, seq: u64, size: usize) -> EventData {
+    let mut payload = vec![0u8; size.max(8)];
+    payload[..8].copy_from_slice(&seq.to_le_bytes());
+    EventData {
+        payload: Bytes::from(payload),
+        event_type: event_type.to_string(),
+        tags: vec![stream.to_string()],
+    }
+}
+
+pub(crate) fn sequence_of(payload: &[u8]) -> Option<u64> {
+    Some(u64::from_le_bytes(payload.get(..8)?.try_into().ok()?))
+}
+
+/// End-to-end latencies of a stream's events from their acknowledgement and first-read times
+struct Visibility {
+    end_to_end: LatencyRecorder,
+    max: Duration,
+    before_ack: u64,
+    unseen: u64,
+}
+
+impl Visibility {
+    fn of(acked: &[Instant], seen: &[Option<Instant>]) -> Self {
+        let mut visibility = Self {
+            end_to_end: LatencyRecorder::new(),
+            max: Duration::ZERO,
+            before_ack: 0,
+            unseen: 0,
+        };
+        for (ack, seen) in acked.iter().zip(seen) {
+            let Some(seen) = seen else {
+                visibility.unseen += 1;
+                continue;
+            };
+            if seen < ack {
+                visibility.before_ack += 1;
+            }
+            let delay = seen.saturating_duration_since(*ack);
+            visibility.end_to_end.record(delay);
+            visibility.max = visibility.max.max(delay);
+        }
+        visibility
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn measures_visibility_from_the_acknowledgement() {
+        let t0 = Instant::now();
+        let ms = |n: u64| t0 + Duration::from_millis(n);
+        let acked = [ms(10), ms(20), ms(30), ms(40)];
+        let seen = [Some(ms(15)), Some(ms(18)), None, Some(ms(70))];
+        let visibility = Visibility::of(&acked, &seen);
+        assert_eq!((visibility.before_ack, visibility.unseen), (1, 1));
+        assert_eq!(visibility.max, Duration::from_millis(30));
+        assert_eq!(visibility.end_to_end.hist.len(), 3);
+        assert_eq!(sequence_of(&sequenced_event("s", "propagation", 42, 4).payload), Some(42));
+    }
+}
@@ -0,0 +1,229 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Whether a rate limit is applied to each worker separately or shared by all workers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RateLimitScope {
+    /// Every worker gets its own bucket with the full rate
+    #[default]
+    PerWorker,
+    /// All workers draw from one bucket, so the rate is the total across workers
+    Global,
+}
+
+/// Token-bucket rate limit configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RateLimitConfig {
+    /// Sustained operations per second
+    pub ops_per_second: f64,
+    /// Number of operations that may be issued back-to-back after an idle period
+    #[serde(default = "default_burst")]
+    pub burst: u32,
+    #[serde(default)]
+    pub scope: RateLimitScope,
+}
+
+fn default_burst() -> u32 {
+    1
+}
+
+impl RateLimitConfig {
+    /// Total operation rate across `workers` workers
+    pub fn total_ops_per_second(&self, workers: usize) -> f64 {
+        match self.scope {
+            RateLimitScope::PerWorker => self.ops_per_second * workers as f64,
+            RateLimitScope::Global => self.ops_per_second,
+        }
+    }
+
+    /// Build one limiter handle per worker. In global scope all handles share a single bucket.
+    pub fn limiters(&self, workers: usize) -> Result<Vec<Arc<RateLimiter>>> {
+        if !self.ops_per_second.is_finite() || self.ops_per_second <= 0.0 {
+            anyhow::bail!("rate limit ops_per_second must be > 0, got {}", self.ops_per_second);
+        }
+        if self.burst == 0 {
+            anyhow::bail!("rate limit burst must be > 0");
+        }
+
+        let limiters = match self.scope {
+            RateLimitScope::PerWorker => (0..workers)
+                .map(|_| Arc::new(RateLimiter::new(self.ops_per_second, self.burst)))
+                .collect(),
+            RateLimitScope::Global => {
+                let shared = Arc::new(RateLimiter::new(self.ops_per_second, self.burst));
+                vec![shared; workers]
+            }
+        };
+        Ok(limiters)
+    }
+}
+
+/// Async token-bucket rate limiter
+///
+/// Callers reserve a token up front and sleep off any deficit, so concurrent callers
+/// sharing one limiter are served in the order they arrived without busy polling.
+pub struct RateLimiter {
+    ops_per_second: f64,
+    burst: f64,
+    state: Mutex<BucketState>,
+}
+
+struct BucketState {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl RateLimiter {
+    /// Create a limiter that starts with a full bucket
+    pub fn new(ops_per_second: f64, burst: u32) -> Self {
+        Self {
+            ops_per_second,
+            burst: burst as f64,
+            state: Mutex::new(BucketState {
+                tokens: burst as f64,
+                last_refill: Instant::now(),
+            }),
+        }
+    }
+
+    pub fn ops_per_second(&self) -> f64 {
+        self.ops_per_second
+    }
+
+    /// Reserve one token and return how long the caller must wait before using it
+    pub fn reserve(&self) -> Duration {
+        let mut state = self.state.lock().unwrap();
+        let now = Instant::now();
+        let refill = now.duration_since(state.last_refill).as_secs_f64() * self.ops_per_second;
+        state.tokens = (state.tokens + refill).min(self.burst);
+        state.last_refill = now;
+
+        state.tokens -= 1.0;
+        if state.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-state.tokens / self.ops_per_second)
+        }
+    }
+
+    /// Wait until the next operation is allowed to start
+    pub async fn acquire(&self) {
+        let wait = self.reserve();
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+/// Fixed-interval schedule of one worker's operations for open-loop load. Operation `n` is due
+/// `n` intervals after the first, whether or not the earlier ones have completed, so latency
+/// measured from the due time includes the time an operation spent waiting behind slow ones
+/// (avoiding coordinated omission).
+#[derive(Clone)]
+pub struct OpenLoopSchedule {
+    interval: Duration,
+    next_due: Option<Instant>,
+}
+
+impl OpenLoopSchedule {
+    pub fn new(ops_per_second: f64) -> Result<Self> {
+        anyhow::ensure!(
+            ops_per_second.is_finite() && ops_per_second > 0.0,
+            "open-loop rate must be > 0 events/sec, got {}",
+            ops_per_second
+        );
+        let interval = Duration::try_from_secs_f64(1.0 / ops_per_second)
+            .map_err(|_| anyhow::anyhow!("open-loop rate of {} events/sec is too low", ops_per_second))?;
+        Ok(Self { interval, next_due: None })
+    }
+
+    /// Wait until the next operation is due and return its due time; the first is due at once
+    pub async fn next_due(&mut self) -> Instant {
+        let due = self.next_due.unwrap_or_else(Instant::now);
+        self.next_due = Some(due + self.interval);
+        if due > Instant::now() {
+            tokio::time::sleep_until(due).await;
+        }
+        due
+    }
+}
+
+/// How a worker paces its operations
+#[derive(Clone)]
+pub enum Pacer {
+    /// Issue the next operation once the previous one completed (optionally throttled);
+    /// latency is measured from when it is issued
+    Closed(Option<Arc<RateLimiter>>),
+    /// Issue operations at fixed intervals; latency is measured from when they were due
+    Open(OpenLoopSchedule),
+}
+
+impl Pacer {
+    /// Wait until the next operation may start. Returns its due time in open-loop mode, which
+    /// its latency is measured from.
+    pub async fn wait(&mut self) -> Option<Instant> {
+        match self {
+            Pacer::Closed(limiter) => {
+                if let Some(limiter) = limiter {
+                    limiter.acquire().await;
+                }
+                None
+            }
+            Pacer::Open(schedule) => Some(schedule.next_due().await),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_open_loop_schedule_keeps_intervals_when_behind() {
+        let mut schedule = OpenLoopSchedule::new(1000.0).unwrap();
+        let first = schedule.next_due().await;
+        let second = schedule.next_due().await;
+        assert_eq!(second - first, Duration::from_millis(1));
+        // A slow operation doesn't push back the operations due meanwhile
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        let third = schedule.next_due().await;
+        assert_eq!(third - first, Duration::from_millis(2));
+        assert!(third.elapsed() >= Duration::from_millis(15));
+    }
+
+    #[test]
+    fn test_open_loop_schedule_rejects_rates_without_an_interval() {
+        for rate in [0.0, -1.0, f64::NAN, f64::INFINITY, 1e-300] {
+            assert!(OpenLoopSchedule::new(rate).is_err(), "{}", rate);
+        }
+        assert!(OpenLoopSchedule::new(0.001).is_ok());
+    }
+
+    #[test]
+    fn test_burst_is_immediate_then_paced() {
+        let limiter = RateLimiter::new(10.0, 3);
+        for _ in 0..3 {
+            assert_eq!(limiter.reserve(), Duration::ZERO);
+        }
+        let wait = limiter.reserve();
+        assert!(wait > Duration::from_millis(50) && wait <= Duration::from_millis(100));
+        // Reservations queue up behind each other
+        assert!(limiter.reserve() > wait);
+    }
+
+    #[test]
+    fn test_global_scope_shares_bucket() {
+        let config = RateLimitConfig {
+            ops_per_second: 10.0,
+            burst: 1,
+            scope: RateLimitScope::Global,
+        };
+        let limiters = config.limiters(2).unwrap();
+        assert_eq!(limiters[0].reserve(), Duration::ZERO);
+        assert!(limiters[1].reserve() > Duration::ZERO);
+    }
+}
@@ -0,0 +1,103 @@
+use crate::metrics::RunMetrics;
+use anyhow::Result;
+use serde::Serialize;
+
+/// Shares of read and write operations of a mixed workload, in percent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct ReadWriteMix {
+    pub read_percent: u32,
+    pub write_percent: u32,
+}
+
+impl ReadWriteMix {
+    /// Parse comma-separated `read:write` percentages adding up to 100, e.g. `90:10,50:50`
+    pub fn parse_list(list: &str) -> Result<Vec<Self>> {
+        let mut mixes = Vec::new();
+        for pair in list.split(',').map(str::trim).filter(|pair| !pair.is_empty()) {
+            let (read, write) = pair
+                .split_once(':')
+                .ok_or_else(|| anyhow::anyhow!("ratio '{}' must be read:write, e.g. 90:10", pair))?;
+            let mix = Self {
+                read_percent: read.trim().parse()?,
+                write_percent: write.trim().parse()?,
+            };
+            if mix.read_percent + mix.write_percent != 100 {
+                anyhow::bail!("ratio '{}' must add up to 100", pair);
+            }
+            if mixes.contains(&mix) {
+                anyhow::bail!("ratio '{}' is listed twice", pair);
+            }
+            mixes.push(mix);
+        }
+        if mixes.is_empty() {
+            anyhow::bail!("ratio sweep lists no ratios");
+        }
+        Ok(mixes)
+    }
+
+    /// Chance of each operation being an append
+    pub fn write_probability(&self) -> f64 {
+        self.write_percent as f64 / 100.0
+    }
+
+    /// Suffix of the workload variant running this mix, e.g. `r90w10`
+    pub fn label(&self) -> String {
+        format!("r{}w{}", self.read_percent, self.write_percent)
+    }
+}
+
+/// Results of one mix on one store
+#[derive(Debug, Clone, Serialize)]
+pub struct RatioRun {
+    pub store: String,
+    pub workload: String,
+    pub read_percent: u32,
+    pub write_percent: u32,
+    pub throughput_eps: f64,
+    pub latency_p50_ms: f64,
+    pub latency_p99_ms: f64,
+    /// Latency of reads and appends alone (absent when the mix has none)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub read_p50_ms: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub read_p99_ms: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub append_p50_ms: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub append_p99_ms: Option<f64>,
+}
+
+impl RatioRun {
+    pub fn new(store: &str, mix: ReadWriteMix, metrics: &RunMetrics) -> Self {
+        let op = |name: &str| metrics.op_latency.get(name).map(|rec| rec.to_stats());
+        let (read, append) = (op("read"), op("append"));
+        Self {
+            store: store.to_string(),
+            workload: metrics.summary.workload.clone(),
+            read_percent: mix.read_percent,
+            write_percent: mix.write_percent,
+            throughput_eps: metrics.summary.throughput_eps,
+            latency_p50_ms: metrics.summary.latency.p50_ms,
+            latency_p99_ms: metrics.summary.latency.p99_ms,
+            read_p50_ms: read.as_ref().map(|s| s.p50_ms),
+            read_p99_ms: read.as_ref().map(|s| s.p99_ms),
+            append_p50_ms: append.as_ref().map(|s| s.p50_ms),
+            append_p99_ms: append.as_ref().map(|s| s.p99_ms),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_read_write_ratios() {
+        let mixes = ReadWriteMix::parse_list("100:0, 90:10,10:90").unwrap();
+        assert_eq!(mixes.iter().map(ReadWriteMix::label).collect::<Vec<_>>(), ["r100w0", "r90w10", "r10w90"]);
+        assert_eq!(mixes[1].write_probability(), 0.1);
+        assert!(ReadWriteMix::parse_list("90:20").is_err());
+        assert!(ReadWriteMix::parse_list("50:50,50:50").is_err());
+        assert!(ReadWriteMix::parse_list("90").is_err());
+    }
+}
@@ -0,0 +1,103 @@
+use crate::metrics::LatencyRecorder;
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// One named metric attached by a workload
+#[derive(Debug, Clone)]
+pub enum CustomMetric {
+    /// Monotonic count; merging adds
+    Counter(u64),
+    /// Point-in-time value; merging keeps the maximum
+    Gauge(f64),
+    /// Latency distribution; merging combines the histograms
+    Histogram(LatencyRecorder),
+}
+
+/// Named counters, gauges and histograms that a workload mode attaches to its result
+///
+/// Workers keep their own registry and the coordinator merges them, so nothing is shared
+/// between tasks. The merged registry ends up in `Summary::extra`, which keeps mode-specific
+/// metrics (errors, retries, lag, ...) out of the fixed summary struct.
+#[derive(Debug, Clone, Default)]
+pub struct MetricsRegistry {
+    metrics: BTreeMap<String, CustomMetric>,
+}
+
+impl MetricsRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.metrics.is_empty()
+    }
+
+    /// Add `delta` to a counter, creating it at zero first
+    pub fn increment(&mut self, name: &str, delta: u64) {
+        match self.metrics.get_mut(name) {
+            Some(CustomMetric::Counter(count)) => *count += delta,
+            _ => {
+                self.metrics.insert(name.to_string(), CustomMetric::Counter(delta));
+            }
+        }
+    }
+
+    pub fn set_gauge(&mut self, name: &str, value: f64) {
+        self.metrics.insert(name.to_string(), CustomMetric::Gauge(value));
+    }
+
+    /// Record a duration into a named histogram
+    pub fn record(&mut self, name: &str, value: Duration) {
+        match self.metrics.get_mut(name) {
+            Some(CustomMetric::Histogram(rec)) => rec.record(value),
+            _ => {
+                let mut rec = LatencyRecorder::new();
+                rec.record(value);
+                self.metrics.insert(name.to_string(), CustomMetric::Histogram(rec));
+            }
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&CustomMetric> {
+        self.metrics.get(name)
+    }
+
+    /// Fold another registry (typically a worker's) into this one
+    pub fn merge(&mut self, other: &MetricsRegistry) {
+        for (name, metric) in &other.metrics {
+            match (self.metrics.get_mut(name), metric) {
+                (Some(CustomMetric::Counter(a)), CustomMetric::Counter(b)) => *a += b,
+                (Some(CustomMetric::Gauge(a)), CustomMetric::Gauge(b)) => *a = a.max(*b),
+                (Some(CustomMetric::Histogram(a)), CustomMetric::Histogram(b)) => {
+                    let _ = a.hist.add(&b.hist);
+                }
+                _ => {
+                    self.metrics.insert(name.clone(), metric.clone());
+                }
+            }
+        }
+    }
+
+    /// JSON values keyed by metric name: counters and gauges as numbers, histograms as
+    /// percentiles plus a sample count
+    pub fn to_json(&self) -> BTreeMap<String, serde_json::Value> {
+        self.metrics
+            .iter()
+            .map(|(name, metric)| {
+                let value = match metric {
+                    CustomMetric::Counter(count) => serde_json::json!(count),
+                    CustomMetric::Gauge(value) => serde_json::json!(value),
+                    CustomMetric::Histogram(rec) => {
+                        let mut stats = serde_json::to_value(rec.to_stats())
+                            .unwrap_or(serde_json::Value::Null);
+                        if let Some(obj) = stats.as_object_mut() {
+                            obj.insert("count".to_string(), serde_json::json!(rec.hist.len()));
+                        }
+                        stats
+                    }
+                };
+                (name.clone(), value)
+            })
+            .collect()
+    }
+}
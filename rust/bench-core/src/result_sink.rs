@@ -0,0 +1,281 @@
+use crate::determinism::DIGEST_OPS;
+use crate::isolation::CpuSet;
+use crate::metrics::{PercentilePoint, RunMetrics, CDF_POINTS};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Timeout of a POST to a results collector
+const HTTP_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The results of one workload variant on one store, as handed to every sink
+pub struct RunRecord<'a> {
+    pub session_id: &'a str,
+    pub workload: &'a str,
+    /// Store label, including the option set for option sweeps
+    pub store: &'a str,
+    pub cpu_set: Option<CpuSet>,
+    pub metrics: &'a RunMetrics,
+}
+
+impl RunRecord<'_> {
+    /// Contents of run.meta.json
+    pub fn metadata(&self) -> serde_json::Value {
+        let result = self.metrics;
+        serde_json::json!({
+            "schema_version": result.schema_version,
+            "sample_rate": result.sample_rate,
+            "cpu_set": self.cpu_set.map(|s| s.to_string()),
+            "worker_start_offsets_ms": result.worker_start_offsets_ms,
+            "worker_seeds": result.worker_seeds,
+            "op_digests": result.op_digests,
+            "op_digest_ops": DIGEST_OPS,
+            "payload_corpus": result.payload_corpus,
+            "missing_stream_read": result.missing_stream_read,
+            "ledger_verification": result.ledger_verification,
+        })
+    }
+}
+
+/// A destination for run results; a session writes every run to each of its sinks
+#[async_trait]
+pub trait ResultSink: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    async fn write_run(&self, run: &RunRecord<'_>) -> Result<()>;
+}
+
+/// Create a sink from its command-line form: `sqlite:<path>` for a catalog database or an
+/// `http://` or `https://` URL of a results collector
+pub fn parse_sink(spec: &str) -> Result<Box<dyn ResultSink>> {
+    if let Some(path) = spec.strip_prefix("sqlite:") {
+        anyhow::ensure!(!path.is_empty(), "sqlite sink requires a path (sqlite:<path>)");
+        return Ok(Box::new(SqliteSink::new(path)));
+    }
+    if spec.starts_with("http://") || spec.starts_with("https://") {
+        return Ok(Box::new(HttpSink::new(spec)?));
+    }
+    anyhow::bail!("unknown result sink '{}' (expected sqlite:<path> or an http(s) URL)", spec)
+}
+
+/// The results directory layout: `<session>/<workload>/<store>/` with summary.json, the
+/// sample files, run.meta.json and the latency tables and histogram
+pub struct FilesystemSink {
+    session_dir: PathBuf,
+}
+
+impl FilesystemSink {
+    pub fn new(session_dir: &Path) -> Self {
+        Self {
+            session_dir: session_dir.to_path_buf(),
+        }
+    }
+
+    pub fn run_dir(&self, workload: &str, store: &str) -> PathBuf {
+        self.session_dir.join(workload).join(store)
+    }
+}
+
+/// One JSON document per line
+fn json_lines<T: Serialize>(items: &[T]) -> Result<String> {
+    let mut lines = String::new();
+    for item in items {
+        lines.push_str(&serde_json::to_string(item)?);
+        lines.push('\n');
+    }
+    Ok(lines)
+}
+
+#[async_trait]
+impl ResultSink for FilesystemSink {
+    fn name(&self) -> &'static str {
+        "filesystem"
+    }
+
+    async fn write_run(&self, run: &RunRecord<'_>) -> Result<()> {
+        let result = run.metrics;
+        let store_dir = self.run_dir(run.workload, run.store);
+        fs::create_dir_all(&store_dir)?;
+
+        fs::write(store_dir.join("summary.json"), serde_json::to_string_pretty(&result.summary)?)?;
+        fs::write(store_dir.join("throughput.jsonl"), json_lines(&result.throughput_samples)?)?;
+        // t_rel_us of the sampled operations is relative to the measurement start
+        fs::write(store_dir.join("samples.jsonl"), json_lines(&result.samples)?)?;
+        fs::write(store_dir.join("host_stats.jsonl"), json_lines(&result.host_stats)?)?;
+        fs::write(store_dir.join("run.meta.json"), serde_json::to_string_pretty(&run.metadata())?)?;
+
+        let percentile_json = result.latency_histogram.to_percentile_json();
+        fs::write(store_dir.join("latency.json"), serde_json::to_string_pretty(&percentile_json)?)?;
+
+        // A log-spaced percentile table per operation type
+        let op_cdf: BTreeMap<&str, Vec<PercentilePoint>> = result
+            .op_latency
+            .iter()
+            .map(|(op, rec)| (*op, rec.percentile_table(CDF_POINTS)))
+            .collect();
+        fs::write(store_dir.join("latency_cdf.json"), serde_json::to_string_pretty(&op_cdf)?)?;
+
+        // The full histograms, so partial runs can be merged exactly and any percentile computed
+        // later: overall and per operation type, base64 V2 encoded and as `.hgrm` tables
+        fs::write(store_dir.join("latency.hdr"), result.latency_histogram.serialize_to_base64()?)?;
+        fs::write(store_dir.join("latency.hgrm"), result.latency_histogram.to_hgrm())?;
+        for (op, rec) in &result.op_latency {
+            fs::write(store_dir.join(format!("latency_{}.hdr", op)), rec.serialize_to_base64()?)?;
+            fs::write(store_dir.join(format!("latency_{}.hgrm", op)), rec.to_hgrm())?;
+        }
+        Ok(())
+    }
+}
+
+/// A catalog of runs in a SQLite database, one row per run with the headline numbers and the
+/// full summary, so results of many sessions can be queried in one place
+pub struct SqliteSink {
+    path: PathBuf,
+}
+
+impl SqliteSink {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    fn insert(path: &Path, row: CatalogRow) -> Result<()> {
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)?;
+        }
+        let conn = rusqlite::Connection::open(path)
+            .with_context(|| format!("Failed to open result catalog {}", path.display()))?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS runs (
+                session_id TEXT NOT NULL,
+                workload TEXT NOT NULL,
+                store TEXT NOT NULL,
+                recorded_at TEXT NOT NULL,
+                events_written INTEGER NOT NULL,
+                events_read INTEGER NOT NULL,
+                duration_s REAL NOT NULL,
+                throughput_eps REAL NOT NULL,
+                latency_p50_ms REAL NOT NULL,
+                latency_p99_ms REAL NOT NULL,
+                summary_json TEXT NOT NULL,
+                metadata_json TEXT NOT NULL,
+                PRIMARY KEY (session_id, workload, store)
+            )",
+        )?;
+        conn.execute(
+            "INSERT OR REPLACE INTO runs VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+            rusqlite::params![
+                row.session_id,
+                row.workload,
+                row.store,
+                chrono::Utc::now().to_rfc3339(),
+                row.events_written as i64,
+                row.events_read as i64,
+                row.duration_s,
+                row.throughput_eps,
+                row.latency_p50_ms,
+                row.latency_p99_ms,
+                row.summary_json,
+                row.metadata_json,
+            ],
+        )?;
+        Ok(())
+    }
+}
+
+/// Values of a catalog row, owned so the insert can run on a blocking thread
+struct CatalogRow {
+    session_id: String,
+    workload: String,
+    store: String,
+    events_written: u64,
+    events_read: u64,
+    duration_s: f64,
+    throughput_eps: f64,
+    latency_p50_ms: f64,
+    latency_p99_ms: f64,
+    summary_json: String,
+    metadata_json: String,
+}
+
+#[async_trait]
+impl ResultSink for SqliteSink {
+    fn name(&self) -> &'static str {
+        "sqlite"
+    }
+
+    async fn write_run(&self, run: &RunRecord<'_>) -> Result<()> {
+        let summary = &run.metrics.summary;
+        let row = CatalogRow {
+            session_id: run.session_id.to_string(),
+            workload: run.workload.to_string(),
+            store: run.store.to_string(),
+            events_written: summary.events_written,
+            events_read: summary.events_read,
+            duration_s: summary.duration_s,
+            throughput_eps: summary.throughput_eps,
+            latency_p50_ms: summary.latency.p50_ms,
+            latency_p99_ms: summary.latency.p99_ms,
+            summary_json: serde_json::to_string(summary)?,
+            metadata_json: run.metadata().to_string(),
+        };
+        let path = self.path.clone();
+        tokio::task::spawn_blocking(move || Self::insert(&path, row)).await?
+    }
+}
+
+/// A results collector receiving each run as a JSON POST of its identity, summary and
+/// metadata
+pub struct HttpSink {
+    url: String,
+    client: reqwest::Client,
+}
+
+impl HttpSink {
+    pub fn new(url: &str) -> Result<Self> {
+        Ok(Self {
+            url: url.to_string(),
+            client: reqwest::Client::builder().timeout(HTTP_TIMEOUT).build()?,
+        })
+    }
+}
+
+#[async_trait]
+impl ResultSink for HttpSink {
+    fn name(&self) -> &'static str {
+        "http"
+    }
+
+    async fn write_run(&self, run: &RunRecord<'_>) -> Result<()> {
+        let body = serde_json::json!({
+            "session_id": run.session_id,
+            "workload": run.workload,
+            "store": run.store,
+            "summary": run.metrics.summary,
+            "metadata": run.metadata(),
+        });
+        let response = self.client.post(&self.url).json(&body).send().await?;
+        let status = response.status();
+        if !status.is_success() {
+            let text = response.text().await.unwrap_or_default();
+            anyhow::bail!("Results collector {} returned {}: {}", self.url, status, text);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_sink_specs() {
+        assert_eq!(parse_sink("sqlite:results/catalog.db").unwrap().name(), "sqlite");
+        assert_eq!(parse_sink("https://collector.example/runs").unwrap().name(), "http");
+        assert!(parse_sink("sqlite:").is_err());
+        assert!(parse_sink("s3://bucket/prefix").is_err());
+    }
+}
@@ -0,0 +1,305 @@
+use crate::adapter::{AdminOp, EventStoreAdapter, ReadRequest, RetentionPolicy, StoreManager};
+use crate::metrics::{LatencyRecorder, LatencyStats};
+use crate::rate_limit::OpenLoopSchedule;
+use crate::workflow_support::{prepopulate_stream, run_on_started_store, stream_event};
+use anyhow::Result;
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tokio::task::JoinSet;
+use tokio::time::Instant;
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+
+/// Settings for measuring writes and reads on streams with a retention policy, before, while
+/// and after a scavenge reclaims the events the policy excludes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetentionConfig {
+    pub streams: u64,
+    /// Events written to each stream before the measurement
+    pub events_per_stream: u64,
+    pub event_size_bytes: usize,
+    /// Policy set on every stream (`max_count`, `max_age_s`, `truncate_before`)
+    #[serde(flatten)]
+    pub policy: RetentionPolicy,
+    pub duration_seconds: u64,
+    /// Appends per second during the measurement, one event per append, round-robin over
+    /// the streams
+    pub write_rate_eps: f64,
+    /// Events per read; a reader reads each stream from its start in turn
+    pub page_size: u64,
+    /// Seconds into the measurement a scavenge is started (none if unset)
+    #[serde(default)]
+    pub scavenge_at_s: Option<f64>,
+}
+
+/// When the scavenge ran, in seconds from the measurement start
+#[derive(Debug, Clone, Serialize)]
+pub struct ScavengeWindow {
+    pub start_s: f64,
+    /// `None` if the scavenge was still running when the measurement ended
+    pub end_s: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Operations started in one phase of the run relative to the scavenge
+#[derive(Debug, Clone, Serialize)]
+pub struct RetentionPhase {
+    /// `before`, `during` or `after` the scavenge
+    pub phase: &'static str,
+    pub appends: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub append_latency: Option<LatencyStats>,
+    pub reads: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub read_latency: Option<LatencyStats>,
+    /// Mean offset of the first event returned by the reads from a stream's start, i.e. how
+    /// far the policy has cut the streams as seen by readers
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub first_offset_mean: Option<f64>,
+    /// Failed appends and reads
+    pub errors: u64,
+}
+
+/// Outcome of a retention study
+#[derive(Debug, Clone, Serialize)]
+pub struct RetentionResult {
+    pub store: String,
+    pub config: RetentionConfig,
+    /// Time spent prepopulating the streams (not part of the measurement)
+    pub prepopulate_duration_s: f64,
+    /// Time spent setting the policy on every stream
+    pub set_policy_duration_s: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scavenge: Option<ScavengeWindow>,
+    pub phases: Vec<RetentionPhase>,
+}
+
+/// Start the store, set the retention policy on the streams and prepopulate them, then append
+/// at a steady rate while reading the streams from their start, starting a scavenge partway
+/// through if configured, and report both by phase relative to the scavenge
+pub async fn execute_retention(
+    store: Box<dyn StoreManager>,
+    config: &RetentionConfig,
+    cancel_token: CancellationToken,
+) -> Result<RetentionResult> {
+    if config.streams == 0 || config.page_size == 0 || config.duration_seconds == 0 {
+        anyhow::bail!("Retention study requires streams, page size and duration > 0");
+    }
+    if !(config.write_rate_eps.is_finite() && config.write_rate_eps > 0.0) {
+        anyhow::bail!("Retention study requires a write rate > 0");
+    }
+    if config.policy == RetentionPolicy::default() {
+        anyhow::bail!("Retention study requires max_count, max_age_s or truncate_before");
+    }
+
+    run_on_started_store(store, &cancel_token, "retention study", async |store| {
+        measure_retention(store, config).await
+    })
+    .await
+}
+
+/// One measured append or read
+struct Op {
+    t_s: f64,
+    append: bool,
+    latency: Duration,
+    ok: bool,
+    first_offset: Option<u64>,
+}
+
+async fn measure_retention(store: &dyn StoreManager, config: &RetentionConfig) -> Result<RetentionResult> {
+    let stream_prefix = format!("retention-{}-", Uuid::new_v4());
+    let streams: Vec<String> = (0..config.streams)
+        .map(|idx| format!("{}{}", stream_prefix, idx))
+        .collect();
+    let payload = Bytes::from(vec![0u8; config.event_size_bytes]);
+
+    // Set first, so a store without retention policies fails before the prepopulation
+    println!("Setting the retention policy on {} streams...", config.streams);
+    let admin = store.create_adapter()?;
+    let policy_started = Instant::now();
+    for stream in &streams {
+        admin.set_retention(stream, &config.policy).await?;
+    }
+    let set_policy_duration_s = policy_started.elapsed().as_secs_f64();
+
+    println!(
+        "Prepopulating {} with {} streams of {} events...",
+        store.name(),
+        config.streams,
+        config.events_per_stream
+    );
+    let prepopulate_started = Instant::now();
+    let mut set = JoinSet::new();
+    for stream in &streams {
+        let adapter = store.create_adapter()?;
+        let stream = stream.clone();
+        let payload = payload.clone();
+        let total = config.events_per_stream;
+        set.spawn(async move { prepopulate_stream(adapter.as_ref(), total, |_| stream_event(&stream, "retention", &payload)).await });
+    }
+    while let Some(res) = set.join_next().await {
+        res??;
+    }
+    let prepopulate_duration_s = prepopulate_started.elapsed().as_secs_f64();
+
+    println!(
+        "Appending {} events/sec and reading for {}s{}...",
+        config.write_rate_eps,
+        config.duration_seconds,
+        config
+            .scavenge_at_s
+            .map(|at| format!(", scavenging after {}s", at))
+            .unwrap_or_default()
+    );
+    let writer = store.create_adapter()?;
+    let reader = store.create_adapter()?;
+    let start = Instant::now();
+    let end = start + Duration::from_secs(config.duration_seconds);
+
+    let mut schedule = OpenLoopSchedule::new(config.write_rate_eps)?;
+    let write = async {
+        let mut ops = Vec::new();
+        for i in 0.. {
+            let due = schedule.next_due().await;
+            if due >= end {
+                break;
+            }
+            let stream = &streams[i % streams.len()];
+            let ok = writer.append(vec![stream_event(stream, "retention", &payload)]).await.is_ok();
+            ops.push(Op {
+                t_s: due.duration_since(start).as_secs_f64(),
+                append: true,
+                latency: due.elapsed(),
+                ok,
+                first_offset: None,
+            });
+        }
+        ops
+    };
+    let read = async {
+        let mut ops = Vec::new();
+        for stream in streams.iter().cycle() {
+            let issued = Instant::now();
+            if issued >= end {
+                break;
+            }
+            let req = ReadRequest {
+                stream: stream.clone(),
+                from_offset: None,
+                limit: Some(config.page_size),
+                consistency: Default::default(),
+            };
+            let result = reader.read(req).await;
+            ops.push(Op {
+                t_s: issued.duration_since(start).as_secs_f64(),
+                append: false,
+                latency: issued.elapsed(),
+                ok: result.is_ok(),
+                first_offset: result.ok().and_then(|events| events.first().map(|e| e.offset)),
+            });
+        }
+        ops
+    };
+    let scavenge = async {
+        let at = Duration::from_secs_f64(config.scavenge_at_s?.max(0.0));
+        tokio::time::sleep_until(start + at).await;
+        if Instant::now() >= end {
+            return None;
+        }
+        Some(run_scavenge(admin.as_ref(), start, end).await)
+    };
+    let (writes, reads, scavenge) = tokio::join!(write, read, scavenge);
+
+    let phases = ["before", "during", "after"]
+        .into_iter()
+        .filter_map(|phase| {
+            let ops: Vec<&Op> = writes
+                .iter()
+                .chain(&reads)
+                .filter(|op| phase_of(op.t_s, scavenge.as_ref()) == phase)
+                .collect();
+            (!ops.is_empty()).then(|| summarize_phase(phase, &ops))
+        })
+        .collect();
+
+    Ok(RetentionResult {
+        store: store.name().to_string(),
+        config: config.clone(),
+        prepopulate_duration_s,
+        set_policy_duration_s,
+        scavenge,
+        phases,
+    })
+}
+
+async fn run_scavenge(adapter: &dyn EventStoreAdapter, start: Instant, end: Instant) -> ScavengeWindow {
+    let start_s = start.elapsed().as_secs_f64();
+    println!("Starting a scavenge at {:.1}s", start_s);
+    match tokio::time::timeout_at(end, adapter.admin(AdminOp::Scavenge)).await {
+        Ok(result) => ScavengeWindow {
+            start_s,
+            end_s: Some(start.elapsed().as_secs_f64()),
+            error: result.err().map(|e| format!("{:#}", e)),
+        },
+        Err(_) => ScavengeWindow {
+            start_s,
+            end_s: None,
+            error: None,
+        },
+    }
+}
+
+/// Phase of an operation started `t_s` seconds into the measurement
+fn phase_of(t_s: f64, scavenge: Option<&ScavengeWindow>) -> &'static str {
+    match scavenge {
+        Some(window) if t_s >= window.start_s => match window.end_s {
+            Some(end_s) if t_s > end_s => "after",
+            _ => "during",
+        },
+        _ => "before",
+    }
+}
+
+fn summarize_phase(phase: &'static str, ops: &[&Op]) -> RetentionPhase {
+    let mut appends = LatencyRecorder::new();
+    let mut reads = LatencyRecorder::new();
+    let mut first_offsets = Vec::new();
+    for op in ops.iter().filter(|op| op.ok) {
+        if op.append {
+            appends.record(op.latency);
+        } else {
+            reads.record(op.latency);
+            first_offsets.extend(op.first_offset);
+        }
+    }
+    let stats = |rec: &LatencyRecorder| (!rec.hist.is_empty()).then(|| rec.to_stats());
+    RetentionPhase {
+        phase,
+        appends: appends.hist.len(),
+        append_latency: stats(&appends),
+        reads: reads.hist.len(),
+        read_latency: stats(&reads),
+        first_offset_mean: (!first_offsets.is_empty())
+            .then(|| first_offsets.iter().sum::<u64>() as f64 / first_offsets.len() as f64),
+        errors: ops.iter().filter(|op| !op.ok).count() as u64,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn phases_follow_the_scavenge_window() {
+        let finished = ScavengeWindow { start_s: 10.0, end_s: Some(20.0), error: None };
+        let phases: Vec<_> = [5.0, 10.0, 20.0, 25.0].iter().map(|t| phase_of(*t, Some(&finished))).collect();
+        assert_eq!(phases, ["before", "during", "during", "after"]);
+
+        let unfinished = ScavengeWindow { start_s: 10.0, end_s: None, error: None };
+        assert_eq!(phase_of(50.0, Some(&unfinished)), "during");
+        assert_eq!(phase_of(50.0, None), "before");
+    }
+}
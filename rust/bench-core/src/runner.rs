@@ -1,76 +1,108 @@
 use crate::adapter::StoreManager;
-use crate::metrics::{RunMetrics, Summary};
-use crate::workloads::{Workload, PerformanceWorkload};
+use crate::checkpoint::CheckpointOptions;
+use crate::ledger::{verify_ledger, Ledger, LedgerOptions, LedgerVerification};
+use crate::live::LiveStats;
+use crate::conformance::check_missing_stream_read;
+use crate::metrics::{throughput_series, OpSamples, RunMetrics, Summary, ThroughputSample, SAMPLE_RATE};
+use crate::schema::SCHEMA_VERSION;
+use crate::workloads::{Workload, PerformanceWorkload, PerformanceOutput};
 use crate::metrics::ContainerMetrics;
-use crate::container_stats::ContainerMonitor;
+use crate::container_stats::{ContainerGroupMonitor, DEFAULT_STATS_INTERVAL_MS};
+use crate::bottleneck::{diagnose, BottleneckInputs};
+use crate::budget::{container_disk_usage, RunBudget};
+use crate::bulk::analyze_bulk;
+use crate::connection::analyze_connections;
+use crate::host_stats::{process_cpu_time, HostMonitor};
+use crate::cpu_spikes::analyze_cpu_spikes;
+use crate::interference::analyze_interference;
+use crate::health::{HealthMonitor, HEALTH_PING_INTERVAL};
+use crate::throttling::analyze_throttling;
+use crate::isolation::{limit_containers, pin_containers, CpuSet, ResourceLimits};
 use anyhow::Result;
-use std::time::{Instant};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::Instant;
 use tokio_util::sync::CancellationToken;
 
+/// Options for embedding the runner in other programs
+#[derive(Debug, Clone, Default)]
+pub struct RunOptions {
+    /// Keep only aggregates: no per-operation samples are collected and no host stats series is
+    /// returned, so `RunMetrics::samples` and `RunMetrics::host_stats` stay empty
+    pub summary_only: bool,
+    /// Pin the store's containers to these host CPUs once started (parallel runs)
+    pub cpu_set: Option<CpuSet>,
+    /// Limit the CPU and memory of each of the store's containers once started
+    pub limits: Option<ResourceLimits>,
+    /// Write intermediate snapshots while a performance workload runs (soak runs)
+    pub checkpoint: Option<CheckpointOptions>,
+    /// Record a sample of acknowledged appends and read them back once the workload is done
+    pub ledger: Option<LedgerOptions>,
+    /// Count the operations and container usage of a performance workload in a live view
+    pub live: Option<Arc<LiveStats>>,
+}
+
 pub async fn execute_run(
-    mut store: Box<dyn StoreManager>,
+    store: Box<dyn StoreManager>,
     workload: &Workload,
     cancel_token: CancellationToken,
 ) -> Result<RunMetrics> {
-    // Start store container
-    let store_name = store.name();
-    if !crate::is_image_pulled(store_name) {
-        println!("Pulling {} image...", store_name);
-        let mut last_err = None;
-        let max_retries = 3;
-        for attempt in 1..=(max_retries + 1) {
-            let res = tokio::select! {
-                res = store.pull() => res,
-                _ = cancel_token.cancelled() => {
-                    println!("Interrupted while pulling image.");
-                    anyhow::bail!("Interrupted");
-                }
-            };
+    execute_run_with_options(store, workload, RunOptions::default(), cancel_token).await
+}
 
-            match res {
-                Ok(_) => {
-                    crate::mark_image_pulled(store_name);
-                    last_err = None;
-                    break;
-                }
-                Err(e) => {
-                    if attempt <= max_retries {
-                        println!("Failed to pull {} image (attempt {}/{}): {}. Retrying in 5s...", store_name, attempt, max_retries + 1, e);
-                        tokio::time::sleep(std::time::Duration::from_secs(5)).await;
-                    }
-                    last_err = Some(e);
-                }
-            }
+pub async fn execute_run_with_options(
+    mut store: Box<dyn StoreManager>,
+    workload: &Workload,
+    options: RunOptions,
+    cancel_token: CancellationToken,
+) -> Result<RunMetrics> {
+    let run_started = Instant::now();
+    let startup = start_store(store.as_mut(), &cancel_token).await?;
+    if let Some(cpu_set) = options.cpu_set {
+        let ids: Vec<String> = store.containers().into_iter().map(|(_, id)| id).collect();
+        if let Err(e) = pin_containers(&ids, cpu_set).await {
+            store.stop().await.ok();
+            return Err(e);
         }
-        if let Some(e) = last_err {
+    }
+    if let Some(limits) = &options.limits {
+        let ids: Vec<String> = store.containers().into_iter().map(|(_, id)| id).collect();
+        if let Err(e) = limit_containers(&ids, limits).await {
+            store.stop().await.ok();
             return Err(e);
         }
     }
 
-    println!("Starting {} container...", store.name());
-    let setup_start = Instant::now();
-
-    tokio::select! {
-        res = store.start() => res?,
-        _ = cancel_token.cancelled() => {
-            println!("Interrupted while starting container.");
+    // Reads of unwritten streams must come back empty, or reader error rates are skewed
+    let missing_stream_read = match store.create_adapter() {
+        Ok(adapter) => check_missing_stream_read(adapter.as_ref()).await,
+        Err(e) => {
             store.stop().await.ok();
-            anyhow::bail!("Interrupted");
+            return Err(e);
         }
+    };
+    if !missing_stream_read.conforms() {
+        eprintln!(
+            "Warning: {} does not read unwritten streams as empty: {:?}",
+            store.name(),
+            missing_stream_read
+        );
     }
 
-    let startup_time_s = setup_start.elapsed().as_secs_f64();
-    println!(
-        "{} container is ready after {:.2} seconds",
-        store.name(),
-        startup_time_s
-    );
-
     // Initialize container monitoring if possible
-    let monitor = if let Some(id) = store.container_id() {
-        match ContainerMonitor::new(id) {
+    let stats_interval = match workload {
+        Workload::Performance(perf_workload) => perf_workload.stats_interval(),
+        _ => Duration::from_millis(DEFAULT_STATS_INTERVAL_MS),
+    };
+    let containers = store.containers();
+    let monitor = if containers.is_empty() {
+        None
+    } else {
+        match ContainerGroupMonitor::new(containers) {
             Ok(mut m) => {
-                m.start().await;
+                m.start(stats_interval).await;
                 Some(m)
             }
             Err(e) => {
@@ -78,16 +110,63 @@ pub async fn execute_run(
                 None
             }
         }
-    } else {
+    };
+    // Baseline for the store's data growth over the workload, prepopulation included
+    let container_ids: Vec<String> = store.containers().into_iter().map(|(_, id)| id).collect();
+    let disk_before = if container_ids.is_empty() {
         None
+    } else {
+        container_disk_usage(&container_ids).await
+    };
+
+    let ledger = match options.ledger.as_ref().map(Ledger::create).transpose() {
+        Ok(ledger) => ledger,
+        Err(e) => {
+            store.stop().await.ok();
+            return Err(e);
+        }
+    };
+
+    let budget = match workload {
+        Workload::Performance(perf_workload) if !perf_workload.budget().is_empty() => {
+            Some(Arc::new(RunBudget::start(perf_workload.budget().clone(), run_started, container_ids.clone())))
+        }
+        _ => None,
+    };
+
+    let host_monitor = HostMonitor::start(stats_interval);
+    let health_monitor = match store.create_adapter() {
+        Ok(adapter) => Some(HealthMonitor::start(adapter, HEALTH_PING_INTERVAL)),
+        Err(e) => {
+            eprintln!("Failed to create the health check client: {:#}", e);
+            None
+        }
     };
+    let live_feed = monitor
+        .as_ref()
+        .zip(options.live.clone())
+        .map(|(monitor, live)| monitor.feed_live(live, stats_interval));
+    if let Workload::Performance(perf_workload) = workload {
+        perf_workload.watch(options.live.clone());
+    }
+    let client_cpu_start = process_cpu_time();
+    let client_wall_start = Instant::now();
 
     // Extract workload details and execute based on type
     let workload_res = tokio::select! {
         res = async {
             match workload {
                 Workload::Performance(perf_workload) => {
-                    execute_performance_workload(store.as_ref(), perf_workload, cancel_token.clone()).await
+                    execute_performance_workload(
+                        store.as_ref(),
+                        perf_workload,
+                        !options.summary_only,
+                        options.checkpoint.as_ref(),
+                        ledger.clone(),
+                        budget.clone(),
+                        cancel_token.clone(),
+                    )
+                    .await
                 }
                 Workload::Durability(dur_workload) => {
                     anyhow::bail!("Durability workloads not yet implemented: {}", dur_workload.name());
@@ -102,12 +181,37 @@ pub async fn execute_run(
         } => res,
         _ = cancel_token.cancelled() => {
             println!("Interrupted during workload execution.");
+            if let Some(feed) = &live_feed {
+                feed.abort();
+            }
             store.stop().await.ok();
             anyhow::bail!("Interrupted");
         }
     };
 
-    let (workload_name, duration_seconds, writers, readers, overall, events_written, events_read, throughput_samples) = match workload_res {
+    if let Workload::Performance(perf_workload) = workload {
+        perf_workload.watch(None);
+    }
+    if let Some(feed) = live_feed {
+        feed.abort();
+    }
+    let host_stats = host_monitor.stop().await;
+    let budget_stop = budget.and_then(|budget| budget.finish());
+    let health = match health_monitor {
+        Some(monitor) => monitor.stop().await,
+        None => None,
+    };
+    // Benchmark process CPU in percent of one core
+    let client_cpu_percent = match (client_cpu_start, process_cpu_time()) {
+        (Some(start), Some(end)) => Some(
+            end.saturating_sub(start).as_secs_f64()
+                / client_wall_start.elapsed().as_secs_f64().max(0.001)
+                * 100.0,
+        ),
+        _ => None,
+    };
+
+    let (workload_name, duration_seconds, writers, readers, output) = match workload_res {
         Ok(vals) => vals,
         Err(e) => {
             // Ensure container is stopped on error/interruption
@@ -116,42 +220,111 @@ pub async fn execute_run(
         }
     };
 
-    let (dur_s, throughput_eps) = if throughput_samples.len() >= 2 {
-        let first_sample = throughput_samples.first().unwrap();
-        let last_sample = throughput_samples.last().unwrap();
-        let duration = last_sample.elapsed_s - first_sample.elapsed_s;
-        let count_delta = last_sample.count - first_sample.count;
-        let throughput = (count_delta as f64) / duration.max(0.001);
-        (duration, throughput)
-    } else {
-        let total_ops = events_written + events_read;
-        (duration_seconds as f64, (total_ops as f64) / (duration_seconds as f64).max(0.001))
+    // Read the acknowledged events back while the store still runs
+    let ledger_verification = match (&ledger, &options.ledger) {
+        (Some(ledger), Some(ledger_options)) => {
+            match check_ledger(ledger, &ledger_options.path, store.as_ref()).await {
+                Ok(verification) => {
+                    if verification.missing > 0 {
+                        eprintln!(
+                            "Warning: {} of {} acknowledged events in the ledger were not read back from {}",
+                            verification.missing,
+                            verification.checked,
+                            store.name()
+                        );
+                    }
+                    Some(verification)
+                }
+                Err(e) => {
+                    eprintln!("Failed to verify the ledger: {:#}", e);
+                    None
+                }
+            }
+        }
+        _ => None,
+    };
+    let PerformanceOutput {
+        latency: overall,
+        events_written,
+        events_read,
+        throughput_samples,
+        worker_start_offsets,
+        contention,
+        fan_out,
+        op_digests,
+        samples: OpSamples {
+            mut samples,
+            op_latency,
+        },
+        extra,
+        bulk_windows,
+        connections,
+    } = output;
+    samples.sort_by_key(|s| s.t_rel_us);
+    let (worker_seeds, payload_corpus) = match workload {
+        Workload::Performance(perf_workload) => (
+            perf_workload.worker_seeds(),
+            perf_workload.payload_corpus(),
+        ),
+        _ => (Vec::new(), None),
     };
 
+    let (dur_s, throughput_eps) =
+        measured_throughput(&throughput_samples, events_written + events_read, duration_seconds);
+
     // Collect container metrics
     let mut container_metrics = ContainerMetrics {
-        startup_time_s,
+        startup_time_s: startup.startup_time_s,
+        image_pulled: Some(startup.image_pull_s.is_some()),
+        image_pull_s: startup.image_pull_s,
+        resource_limits: options.limits.clone().filter(|_| !store.containers().is_empty()),
         ..Default::default()
     };
 
+    let mut cpu_timeline = Vec::new();
     if let Some(m) = monitor {
         match m.get_image_size().await {
             Ok(size) => container_metrics.image_size_bytes = Some(size),
             Err(e) => eprintln!("Failed to get image size: {}", e),
         }
-
-        match m.stop().await {
-            Ok((avg_cpu, peak_cpu, avg_mem, peak_mem)) => {
-                container_metrics.avg_cpu_percent = avg_cpu;
-                container_metrics.peak_cpu_percent = peak_cpu;
-                container_metrics.avg_memory_bytes = avg_mem;
-                container_metrics.peak_memory_bytes = peak_mem;
+        match m.get_image_layers().await {
+            Ok(layers) => container_metrics.image_layers = layers,
+            Err(e) => eprintln!("Failed to get image layers: {}", e),
+        }
+        if let Some(before) = disk_before {
+            match container_disk_usage(&container_ids).await {
+                Some(after) => container_metrics.data_bytes_written = Some(after.saturating_sub(before)),
+                None => eprintln!("Failed to measure the containers' disk usage"),
             }
-            Err(e) => eprintln!("Failed to stop container monitor: {}", e),
+        }
+
+        container_metrics.stats_source = m.source().map(|source| source.as_str().to_string());
+        let (aggregate, per_container, timeline) = m.stop().await;
+        cpu_timeline = timeline;
+        container_metrics.stats_interval_ms = Some(stats_interval.as_millis() as u64);
+        container_metrics.avg_cpu_percent = aggregate.avg_cpu_percent;
+        container_metrics.peak_cpu_percent = aggregate.peak_cpu_percent;
+        container_metrics.avg_memory_bytes = aggregate.avg_memory_bytes;
+        container_metrics.peak_memory_bytes = aggregate.peak_memory_bytes;
+        if per_container.len() > 1 {
+            container_metrics.per_container = per_container;
         }
     }
 
+    let bottleneck = match workload {
+        Workload::Performance(perf_workload) => Some(diagnose(&BottleneckInputs {
+            host_stats: &host_stats,
+            client_cpu_percent,
+            server_cpu_percent: container_metrics.avg_cpu_percent,
+            host_cores: num_cpus::get(),
+            target_ops_per_second: perf_workload.target_ops_per_second(),
+            achieved_ops_per_second: throughput_eps / perf_workload.events_per_operation(),
+        })),
+        _ => None,
+    };
+
     let summary = Summary {
+        schema_version: SCHEMA_VERSION,
         workload: workload_name,
         adapter: store.name().to_string(),
         writers,
@@ -161,15 +334,73 @@ pub async fn execute_run(
         duration_s: dur_s,
         throughput_eps,
         latency: overall.to_stats(),
+        throughput_series: match workload {
+            Workload::Performance(perf_workload) => {
+                throughput_series(&samples, SAMPLE_RATE, perf_workload.series_bucket())
+            }
+            _ => Vec::new(),
+        },
         container: container_metrics,
+        contention,
+        fan_out,
+        bottleneck,
+        interference: analyze_interference(&samples, SAMPLE_RATE),
+        cpu_spikes: match workload {
+            Workload::Performance(perf_workload) => perf_workload.measurement_start().and_then(|start| {
+                // Seconds from the measurement start, leaving out samples taken before it
+                let cpu: Vec<(f64, f64)> = cpu_timeline
+                    .iter()
+                    .filter_map(|(at, percent)| Some((at.checked_duration_since(start.into_std())?.as_secs_f64(), *percent)))
+                    .collect();
+                analyze_cpu_spikes(&cpu, stats_interval, &samples, SAMPLE_RATE)
+            }),
+            _ => None,
+        },
+        throttling: analyze_throttling(&host_stats),
+        health,
+        budget_stop,
+        bulk: match workload {
+            Workload::Performance(perf_workload) => perf_workload
+                .bulk()
+                .map(|config| analyze_bulk(config, bulk_windows, &samples)),
+            _ => None,
+        },
+        connections: (!connections.is_empty()).then(|| analyze_connections(store.endpoint(), &connections)),
+        read_consistency: match workload {
+            Workload::Performance(perf_workload) => perf_workload.read_consistency(),
+            _ => None,
+        },
+        store_options: BTreeMap::new(),
+        store_rev: None,
+        assertions: Vec::new(),
+        extra: extra.to_json(),
     };
 
-    let metrics = RunMetrics {
+    let mut metrics = RunMetrics {
+        schema_version: SCHEMA_VERSION,
         summary,
         throughput_samples,
-        sample_rate: 100, // 1-in-100 sampling
+        sample_rate: SAMPLE_RATE,
+        worker_start_offsets_ms: worker_start_offsets
+            .iter()
+            .map(|offset| offset.as_secs_f64() * 1000.0)
+            .collect(),
+        worker_seeds,
+        op_digests,
+        payload_corpus,
+        missing_stream_read: Some(missing_stream_read),
+        ledger_verification,
+        samples,
+        op_latency,
+        host_stats: if options.summary_only { Vec::new() } else { host_stats },
         latency_histogram: overall,
     };
+    if let Some(assertions) = match workload {
+        Workload::Performance(perf_workload) => perf_workload.assertions(),
+        _ => None,
+    } {
+        metrics.summary.assertions = assertions.check(&metrics);
+    }
 
     // Stop container
     store.stop().await?;
@@ -180,8 +411,12 @@ pub async fn execute_run(
 async fn execute_performance_workload(
     store: &dyn StoreManager,
     workload: &PerformanceWorkload,
+    collect_samples: bool,
+    checkpoint: Option<&CheckpointOptions>,
+    ledger: Option<Arc<Ledger>>,
+    budget: Option<Arc<RunBudget>>,
     cancel_token: CancellationToken,
-) -> Result<(String, u64, usize, usize, crate::metrics::LatencyRecorder, u64, u64, Vec<crate::metrics::ThroughputSample>)> {
+) -> Result<(String, u64, usize, usize, PerformanceOutput)> {
     // Prepare the workload
     workload.prepare(store).await?;
 
@@ -189,8 +424,8 @@ async fn execute_performance_workload(
     let duration_seconds = workload.duration_seconds();
 
     // Execute the workload
-    let (overall, events_written, events_read, throughput_samples) = workload
-        .execute(store, cancel_token)
+    let output = workload
+        .execute_with_options(store, cancel_token, collect_samples, checkpoint, ledger, budget)
         .await?;
 
     Ok((
@@ -198,9 +433,102 @@ async fn execute_performance_workload(
         duration_seconds,
         workload.writers(),
         workload.readers(),
-        overall,
-        events_written,
-        events_read,
-        throughput_samples,
+        output,
     ))
 }
+
+/// Measured duration and events per second: between the first and last throughput sample, or
+/// all events over the configured duration if there are fewer than two samples
+pub(crate) fn measured_throughput(throughput_samples: &[ThroughputSample], total_events: u64, duration_seconds: u64) -> (f64, f64) {
+    if let [first_sample, .., last_sample] = throughput_samples {
+        let duration = last_sample.elapsed_s - first_sample.elapsed_s;
+        let count_delta = last_sample.count - first_sample.count;
+        (duration, (count_delta as f64) / duration.max(0.001))
+    } else {
+        (duration_seconds as f64, (total_events as f64) / (duration_seconds as f64).max(0.001))
+    }
+}
+
+/// Flush the ledger and check the store returns every event in it
+async fn check_ledger(ledger: &Ledger, path: &Path, store: &dyn StoreManager) -> Result<LedgerVerification> {
+    let recorded = ledger.finish()?;
+    println!("Reading {} acknowledged events from the ledger back...", recorded);
+    let adapter = store.create_adapter()?;
+    verify_ledger(path, adapter.as_ref()).await
+}
+
+/// How long starting a store took
+#[derive(Debug, Clone, Copy)]
+pub struct StoreStartup {
+    /// Time from starting the container until the store was ready, in seconds
+    pub startup_time_s: f64,
+    /// Time spent pulling the image, if it was pulled (the first start of the session)
+    pub image_pull_s: Option<f64>,
+}
+
+/// Pull the store image (once per session, with retries) and start its container.
+pub async fn start_store(
+    store: &mut dyn StoreManager,
+    cancel_token: &CancellationToken,
+) -> Result<StoreStartup> {
+    let store_name = store.name();
+    let mut image_pull_s = None;
+    if !crate::is_image_pulled(store_name) {
+        println!("Pulling {} image...", store_name);
+        let pull_start = Instant::now();
+        let mut last_err = None;
+        let max_retries = 3;
+        for attempt in 1..=(max_retries + 1) {
+            let res = tokio::select! {
+                res = store.pull() => res,
+                _ = cancel_token.cancelled() => {
+                    println!("Interrupted while pulling image.");
+                    anyhow::bail!("Interrupted");
+                }
+            };
+
+            match res {
+                Ok(_) => {
+                    crate::mark_image_pulled(store_name);
+                    image_pull_s = Some(pull_start.elapsed().as_secs_f64());
+                    last_err = None;
+                    break;
+                }
+                Err(e) => {
+                    if attempt <= max_retries {
+                        println!("Failed to pull {} image (attempt {}/{}): {}. Retrying in 5s...", store_name, attempt, max_retries + 1, e);
+                        tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+                    }
+                    last_err = Some(e);
+                }
+            }
+        }
+        if let Some(e) = last_err {
+            return Err(e);
+        }
+    }
+
+    println!("Starting {} container...", store.name());
+    let setup_start = Instant::now();
+
+    tokio::select! {
+        res = store.start() => res?,
+        _ = cancel_token.cancelled() => {
+            println!("Interrupted while starting container.");
+            store.stop().await.ok();
+            anyhow::bail!("Interrupted");
+        }
+    }
+
+    let startup_time_s = setup_start.elapsed().as_secs_f64();
+    println!(
+        "{} container is ready after {:.2} seconds",
+        store.name(),
+        startup_time_s
+    );
+
+    Ok(StoreStartup {
+        startup_time_s,
+        image_pull_s,
+    })
+}
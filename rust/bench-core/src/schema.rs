@@ -0,0 +1,86 @@
+use crate::metrics::Summary;
+use anyhow::{bail, Context, Result};
+use std::path::Path;
+
+/// Current layout version of summary.json and run.meta.json
+///
+/// Bump this when a field is renamed, removed or changes meaning, and add an upgrade step to
+/// `upgrade` so that archived results keep loading. Purely additive optional fields don't need
+/// a bump.
+///
+/// - 1: everything written before the version was recorded
+/// - 2: adds `schema_version` itself
+pub const SCHEMA_VERSION: u32 = 2;
+
+/// Parse a summary.json written by this or any earlier version of the benchmark
+pub fn parse_summary(json: &str) -> Result<Summary> {
+    let mut value: serde_json::Value = serde_json::from_str(json)?;
+    let version = schema_version(&value);
+    if version > SCHEMA_VERSION {
+        bail!(
+            "summary has schema version {}, newer than the supported {}",
+            version,
+            SCHEMA_VERSION
+        );
+    }
+    upgrade(&mut value, version);
+    Ok(serde_json::from_value(value)?)
+}
+
+/// Read and parse a summary.json file (see `parse_summary`)
+pub fn load_summary(path: &Path) -> Result<Summary> {
+    let json = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    parse_summary(&json).with_context(|| format!("Failed to parse {}", path.display()))
+}
+
+/// Version recorded in a result file; files without one predate versioning
+pub fn schema_version(value: &serde_json::Value) -> u32 {
+    value
+        .get("schema_version")
+        .and_then(|v| v.as_u64())
+        .map_or(1, |v| v as u32)
+}
+
+/// Rewrite an older summary in place, one version step at a time, into the current layout
+fn upgrade(value: &mut serde_json::Value, mut version: u32) {
+    while version < SCHEMA_VERSION {
+        // 1 -> 2: the fields added since the first release all have serde defaults, so only
+        // the version marker itself is missing
+        version += 1;
+    }
+    if let Some(obj) = value.as_object_mut() {
+        obj.insert("schema_version".to_string(), serde_json::json!(SCHEMA_VERSION));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_unversioned_summary() {
+        let json = r#"{
+            "workload": "append",
+            "adapter": "dummy",
+            "writers": 4,
+            "readers": 0,
+            "events_written": 1000,
+            "events_read": 0,
+            "duration_s": 10.0,
+            "throughput_eps": 100.0,
+            "latency": {"p50_ms": 1.0, "p95_ms": 2.0, "p99_ms": 3.0, "p999_ms": 4.0}
+        }"#;
+        let summary = parse_summary(json).unwrap();
+        assert_eq!(summary.schema_version, SCHEMA_VERSION);
+        assert_eq!(summary.events_written, 1000);
+        assert!(summary.bottleneck.is_none());
+        assert!(summary.extra.is_empty());
+    }
+
+    #[test]
+    fn rejects_newer_summary() {
+        let json = format!(r#"{{"schema_version": {}}}"#, SCHEMA_VERSION + 1);
+        assert!(parse_summary(&json).is_err());
+    }
+}
@@ -0,0 +1,41 @@
+use crate::payload::{PayloadKind, SchemaRegistryConfig, AVRO_SCHEMA, PROTOBUF_SCHEMA};
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use serde_json::json;
+
+#[derive(Deserialize)]
+struct Registered {
+    id: u32,
+}
+
+/// Register the schema of `kind` payloads under the configured subject and return its id.
+/// Registering a schema the subject already has returns the existing id.
+pub async fn register_schema(config: &SchemaRegistryConfig, kind: PayloadKind) -> Result<u32> {
+    let body = match kind {
+        PayloadKind::Avro => json!({ "schema": AVRO_SCHEMA }),
+        PayloadKind::Protobuf => json!({ "schema": PROTOBUF_SCHEMA, "schemaType": "PROTOBUF" }),
+        _ => bail!("schema_registry needs payload kind avro or protobuf"),
+    };
+    let url = format!(
+        "{}/subjects/{}/versions",
+        config.url.trim_end_matches('/'),
+        config.subject
+    );
+    let response = reqwest::Client::new()
+        .post(&url)
+        .header(reqwest::header::CONTENT_TYPE, "application/vnd.schemaregistry.v1+json")
+        .json(&body)
+        .send()
+        .await
+        .with_context(|| format!("Failed to reach the schema registry at {}", url))?;
+    let status = response.status();
+    if !status.is_success() {
+        let text = response.text().await.unwrap_or_default();
+        bail!("Schema registry rejected the schema ({}): {}", status, text);
+    }
+    let registered: Registered = response
+        .json()
+        .await
+        .context("Invalid schema registry response")?;
+    Ok(registered.id)
+}
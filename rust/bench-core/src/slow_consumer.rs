@@ -0,0 +1,200 @@
+use crate::adapter::{EventStream, StoreManager};
+use crate::metrics::{LatencyRecorder, LatencyStats};
+use crate::propagation::sequenced_event;
+use crate::rate_limit::OpenLoopSchedule;
+use crate::workflow_support::{next_sequence, run_on_started_store};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::Instant;
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+
+/// Settings for following a stream with several subscribers, one of which consumes slowly
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlowConsumerConfig {
+    /// Subscribers following the stream, including the slow one
+    pub subscribers: usize,
+    /// Pause of the slow subscriber after each event it receives
+    pub slow_delay_ms: u64,
+    /// Appends per second (one event each)
+    pub write_rate_eps: f64,
+    /// Seconds of appends in each phase
+    pub duration_s: u64,
+    pub event_size_bytes: usize,
+}
+
+/// Writer and subscriber metrics of one phase of a slow-consumer study
+#[derive(Debug, Clone, Serialize)]
+pub struct SlowConsumerPhase {
+    /// Whether one of the subscribers consumed slowly
+    pub slow: bool,
+    pub appended: u64,
+    /// From when each append was due until the store acknowledged it
+    pub append_latency: LatencyStats,
+    /// From when each append was due until a subscriber that keeps up received it
+    pub delivery_lag: LatencyStats,
+    pub delivery_lag_max_ms: f64,
+    /// Events the slow subscriber had received when the other subscribers were done
+    pub slow_consumed: Option<u64>,
+}
+
+/// Outcome of a slow-consumer study
+#[derive(Debug, Clone, Serialize)]
+pub struct SlowConsumerResult {
+    pub store: String,
+    pub config: SlowConsumerConfig,
+    /// Every subscriber keeping up
+    pub baseline: SlowConsumerPhase,
+    /// The same load with one subscriber pausing after each event
+    pub with_slow: SlowConsumerPhase,
+    /// Relative change of the p99 append latency from the baseline (0.1 = 10% slower)
+    pub append_p99_change: f64,
+    /// Relative change of the other subscribers' p99 delivery lag from the baseline
+    pub delivery_p99_change: f64,
+}
+
+/// Start the store and append at a steady rate while the subscribers follow the stream, first
+/// with every subscriber keeping up, then on a new stream with one of them pausing after each
+/// event, and report whether the slow one degrades the writer or the other subscribers
+pub async fn execute_slow_consumer(
+    store: Box<dyn StoreManager>,
+    config: &SlowConsumerConfig,
+    cancel_token: CancellationToken,
+) -> Result<SlowConsumerResult> {
+    if config.subscribers < 2 {
+        anyhow::bail!("Slow-consumer study requires at least 2 subscribers");
+    }
+    if config.slow_delay_ms == 0 || config.duration_s == 0 {
+        anyhow::bail!("Slow-consumer study requires a slow delay and a duration > 0");
+    }
+    if !(config.write_rate_eps.is_finite() && config.write_rate_eps > 0.0) {
+        anyhow::bail!("Slow-consumer study requires a write rate > 0");
+    }
+
+    run_on_started_store(store, &cancel_token, "slow-consumer study", async |store| {
+        println!("Baseline: {} subscribers keeping up...", config.subscribers);
+        let baseline = follow_phase(store, config, false).await?;
+        println!("With one subscriber pausing {} ms after each event...", config.slow_delay_ms);
+        let with_slow = follow_phase(store, config, true).await?;
+        Ok(SlowConsumerResult {
+            store: store.name().to_string(),
+            config: config.clone(),
+            append_p99_change: change(baseline.append_latency.p99_ms, with_slow.append_latency.p99_ms),
+            delivery_p99_change: change(baseline.delivery_lag.p99_ms, with_slow.delivery_lag.p99_ms),
+            baseline,
+            with_slow,
+        })
+    })
+    .await
+}
+
+/// Append to a new stream for the configured duration while every subscriber follows it, the
+/// first one pausing after each event if `slow`
+async fn follow_phase(store: &dyn StoreManager, config: &SlowConsumerConfig, slow: bool) -> Result<SlowConsumerPhase> {
+    let writer = store.create_adapter()?;
+    let stream = format!("slow-consumer-{}", Uuid::new_v4());
+    let total = ((config.write_rate_eps * config.duration_s as f64).round() as u64).max(1);
+
+    // Everyone subscribes before the first append, so all of them see every event
+    let mut subscriptions = Vec::with_capacity(config.subscribers);
+    for _ in 0..config.subscribers {
+        subscriptions.push(store.create_adapter()?.subscribe(&stream, None).await?);
+    }
+    let slow_subscription = slow.then(|| subscriptions.remove(0));
+
+    // Due times of the appends by sequence number
+    let due: Arc<Mutex<Vec<Instant>>> = Arc::new(Mutex::new(Vec::with_capacity(total as usize)));
+    let mut schedule = OpenLoopSchedule::new(config.write_rate_eps)?;
+    let write = async {
+        let mut latency = LatencyRecorder::new();
+        for seq in 0..total {
+            let next = schedule.next_due().await;
+            due.lock().unwrap().push(next);
+            writer.append(vec![sequenced_event(&stream, "slow-consumer", seq, config.event_size_bytes)]).await?;
+            latency.record(next.elapsed());
+        }
+        Ok::<_, anyhow::Error>(latency)
+    };
+    let keep_up = futures::future::try_join_all(subscriptions.into_iter().map(|subscription| {
+        let due = due.clone();
+        async move { follow(subscription, total, &due).await }
+    }));
+
+    // The slow subscriber is still behind when the others are done, so it stops with them
+    let others_done = CancellationToken::new();
+    let followed = async {
+        let result = tokio::try_join!(write, keep_up);
+        others_done.cancel();
+        result
+    };
+    let slow_follow = async {
+        let Some(mut subscription) = slow_subscription else {
+            return Ok(None);
+        };
+        let delay = Duration::from_millis(config.slow_delay_ms);
+        let mut consumed = 0;
+        loop {
+            tokio::select! {
+                _ = others_done.cancelled() => break,
+                seq = next_sequence(&mut subscription) => {
+                    seq?;
+                    consumed += 1;
+                }
+            }
+            tokio::select! {
+                _ = others_done.cancelled() => break,
+                _ = tokio::time::sleep(delay) => {}
+            }
+        }
+        Ok::<_, anyhow::Error>(Some(consumed))
+    };
+    let ((append_latency, lags), slow_consumed) = tokio::try_join!(followed, slow_follow)?;
+
+    let mut delivery_lag = LatencyRecorder::new();
+    let mut delivery_lag_max = Duration::ZERO;
+    for (lag, lag_max) in lags {
+        let _ = delivery_lag.hist.add(&lag.hist);
+        delivery_lag_max = delivery_lag_max.max(lag_max);
+    }
+    let phase = SlowConsumerPhase {
+        slow,
+        appended: total,
+        append_latency: append_latency.to_stats(),
+        delivery_lag: delivery_lag.to_stats(),
+        delivery_lag_max_ms: delivery_lag_max.as_secs_f64() * 1000.0,
+        slow_consumed,
+    };
+    println!(
+        "  append p99 {:.2} ms, delivery lag p99 {:.2} ms{}",
+        phase.append_latency.p99_ms,
+        phase.delivery_lag.p99_ms,
+        slow_consumed.map_or(String::new(), |consumed| format!(", slow subscriber at {} of {} events", consumed, total))
+    );
+    Ok(phase)
+}
+
+/// Receive `total` events, recording each one's lag from when its append was due
+async fn follow(mut subscription: EventStream, total: u64, due: &Mutex<Vec<Instant>>) -> Result<(LatencyRecorder, Duration)> {
+    let mut lag = LatencyRecorder::new();
+    let mut lag_max = Duration::ZERO;
+    for _ in 0..total {
+        let seq = next_sequence(&mut subscription).await?;
+        let appended = due.lock().unwrap().get(seq as usize).copied();
+        let appended = appended.ok_or_else(|| anyhow::anyhow!("Subscription delivered an event nobody appended"))?;
+        let elapsed = appended.elapsed();
+        lag.record(elapsed);
+        lag_max = lag_max.max(elapsed);
+    }
+    Ok((lag, lag_max))
+}
+
+/// Relative change from `baseline` to `value` (0 when the baseline is 0)
+fn change(baseline: f64, value: f64) -> f64 {
+    if baseline > 0.0 {
+        value / baseline - 1.0
+    } else {
+        0.0
+    }
+}
@@ -0,0 +1,251 @@
+use crate::adapter::{EventData, StoreManager};
+use anyhow::{Context, Result};
+use bollard::image::RemoveImageOptions;
+use bollard::Docker;
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+
+/// Give up on the first append this long after the container was started
+const FIRST_APPEND_TIMEOUT: Duration = Duration::from_secs(60);
+const FIRST_APPEND_RETRY: Duration = Duration::from_millis(50);
+
+/// Settings for repeatedly starting and stopping a store
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StartupBenchConfig {
+    /// Starts with the image already present
+    pub iterations: usize,
+    /// Starts after removing the image, so it is pulled again first (run after the warm ones)
+    pub cold_iterations: usize,
+}
+
+/// Timings of one start of the store
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StartupIteration {
+    /// The image was removed before this start
+    pub cold: bool,
+    /// Time to pull the image (cold starts only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pull_s: Option<f64>,
+    /// Time until the store reported ready
+    pub start_s: f64,
+    /// Time from starting the container until the first append succeeded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub first_append_s: Option<f64>,
+    pub stop_s: f64,
+}
+
+/// Distribution of a duration over the iterations
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DurationStats {
+    pub runs: usize,
+    pub min_s: f64,
+    pub p50_s: f64,
+    pub mean_s: f64,
+    pub max_s: f64,
+}
+
+impl DurationStats {
+    /// `None` without samples
+    pub fn from_samples(samples: impl IntoIterator<Item = f64>) -> Option<Self> {
+        let mut sorted: Vec<f64> = samples.into_iter().collect();
+        if sorted.is_empty() {
+            return None;
+        }
+        sorted.sort_by(f64::total_cmp);
+        Some(Self {
+            runs: sorted.len(),
+            min_s: sorted[0],
+            p50_s: sorted[(sorted.len() - 1) / 2],
+            mean_s: sorted.iter().sum::<f64>() / sorted.len() as f64,
+            max_s: sorted[sorted.len() - 1],
+        })
+    }
+}
+
+/// Startup distributions of warm or cold starts
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StartupStats {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pull: Option<DurationStats>,
+    pub start: DurationStats,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub first_append: Option<DurationStats>,
+}
+
+impl StartupStats {
+    fn summarize(iterations: &[StartupIteration], cold: bool) -> Option<Self> {
+        let runs: Vec<&StartupIteration> = iterations.iter().filter(|i| i.cold == cold).collect();
+        Some(Self {
+            pull: DurationStats::from_samples(runs.iter().filter_map(|i| i.pull_s)),
+            start: DurationStats::from_samples(runs.iter().map(|i| i.start_s))?,
+            first_append: DurationStats::from_samples(runs.iter().filter_map(|i| i.first_append_s)),
+        })
+    }
+}
+
+/// Outcome of a startup benchmark
+#[derive(Debug, Clone, Serialize)]
+pub struct StartupBenchResult {
+    pub store: String,
+    pub config: StartupBenchConfig,
+    pub iterations: Vec<StartupIteration>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub warm: Option<StartupStats>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cold: Option<StartupStats>,
+}
+
+/// Start and stop the store repeatedly: first `iterations` warm starts, then `cold_iterations`
+/// starts that each remove the store's image and pull it again
+pub async fn execute_startup_bench(
+    mut store: Box<dyn StoreManager>,
+    config: &StartupBenchConfig,
+    cancel_token: CancellationToken,
+) -> Result<StartupBenchResult> {
+    if config.iterations + config.cold_iterations == 0 {
+        anyhow::bail!("Startup benchmark requires at least one iteration");
+    }
+
+    // Warm starts need the image; it is pulled outside the measurement
+    println!("Pulling {} image...", store.name());
+    store.pull().await?;
+    crate::mark_image_pulled(store.name());
+
+    let mut iterations = Vec::with_capacity(config.iterations + config.cold_iterations);
+    let mut image = None;
+    for n in 0..config.iterations + config.cold_iterations {
+        if cancel_token.is_cancelled() {
+            println!("Interrupted during startup benchmark.");
+            anyhow::bail!("Interrupted");
+        }
+        let cold = n >= config.iterations;
+        let pull_s = if cold {
+            // Without an earlier start the image is unknown, so the first cold start finds it
+            let image_id = match image.clone() {
+                Some(id) => id,
+                None => {
+                    let warmup = start_once(store.as_mut(), false).await?;
+                    image = warmup.1;
+                    image.clone().context("Could not determine the store's image")?
+                }
+            };
+            remove_image(&image_id).await?;
+            let pull_started = Instant::now();
+            store.pull().await?;
+            Some(pull_started.elapsed().as_secs_f64())
+        } else {
+            None
+        };
+        let (mut iteration, image_id) = start_once(store.as_mut(), true).await?;
+        iteration.cold = cold;
+        iteration.pull_s = pull_s;
+        image = image.or(image_id);
+        println!(
+            "  {} start {}: ready after {:.2}s, first append after {}",
+            if cold { "cold" } else { "warm" },
+            n + 1,
+            iteration.start_s,
+            iteration
+                .first_append_s
+                .map(|s| format!("{:.2}s", s))
+                .unwrap_or_else(|| "timeout".to_string())
+        );
+        iterations.push(iteration);
+    }
+
+    Ok(StartupBenchResult {
+        store: store.name().to_string(),
+        config: config.clone(),
+        warm: StartupStats::summarize(&iterations, false),
+        cold: StartupStats::summarize(&iterations, true),
+        iterations,
+    })
+}
+
+/// Start the store, append until it succeeds (if `append`), and stop it again. Returns the
+/// timings and the container's image id.
+async fn start_once(store: &mut dyn StoreManager, append: bool) -> Result<(StartupIteration, Option<String>)> {
+    let started = Instant::now();
+    if let Err(e) = store.start().await {
+        store.stop().await.ok();
+        return Err(e);
+    }
+    let start_s = started.elapsed().as_secs_f64();
+    let image = match store.container_id() {
+        Some(id) => container_image(&id).await,
+        None => None,
+    };
+
+    let first_append_s = if append {
+        first_append(store)
+            .await
+            .then(|| started.elapsed().as_secs_f64())
+    } else {
+        None
+    };
+
+    let stop_started = Instant::now();
+    store.stop().await?;
+    let iteration = StartupIteration {
+        cold: false,
+        pull_s: None,
+        start_s,
+        first_append_s,
+        stop_s: stop_started.elapsed().as_secs_f64(),
+    };
+    Ok((iteration, image))
+}
+
+/// Retry a single append until it succeeds; false on timeout
+async fn first_append(store: &dyn StoreManager) -> bool {
+    let started = Instant::now();
+    let stream = format!("startup-{}", Uuid::new_v4());
+    while started.elapsed() < FIRST_APPEND_TIMEOUT {
+        if let Ok(adapter) = store.create_adapter() {
+            let event = EventData {
+                payload: Bytes::from_static(b"{}"),
+                event_type: "startup".to_string(),
+                tags: vec![stream.clone()],
+            };
+            if adapter.append(vec![event]).await.is_ok() {
+                return true;
+            }
+        }
+        tokio::time::sleep(FIRST_APPEND_RETRY).await;
+    }
+    false
+}
+
+async fn container_image(container_id: &str) -> Option<String> {
+    let docker = Docker::connect_with_local_defaults().ok()?;
+    docker.inspect_container(container_id, None).await.ok()?.image
+}
+
+async fn remove_image(image_id: &str) -> Result<()> {
+    let docker = Docker::connect_with_local_defaults()?;
+    let options = RemoveImageOptions {
+        force: true,
+        ..Default::default()
+    };
+    docker
+        .remove_image(image_id, Some(options), None)
+        .await
+        .with_context(|| format!("Failed to remove image {}", image_id))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summarizes_durations() {
+        let stats = DurationStats::from_samples([3.0, 1.0, 2.0, 10.0]).unwrap();
+        assert_eq!((stats.runs, stats.min_s, stats.p50_s, stats.max_s), (4, 1.0, 2.0, 10.0));
+        assert_eq!(stats.mean_s, 4.0);
+        assert!(DurationStats::from_samples([]).is_none());
+    }
+}
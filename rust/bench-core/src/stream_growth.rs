@@ -0,0 +1,182 @@
+use crate::adapter::{EventData, StoreManager};
+use crate::metrics::{LatencyRecorder, LatencyStats};
+use crate::runner::start_store;
+use anyhow::Result;
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+
+/// Settings for appending to a single ever-growing stream
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StreamGrowthConfig {
+    /// Events the stream grows to
+    pub events: u64,
+    /// Events per append
+    pub batch_size: u64,
+    pub event_size_bytes: usize,
+    /// Number of equally wide stream length ranges latencies are reported for
+    pub buckets: u64,
+}
+
+/// Latency of the operations issued while the stream length (or read offset) was in
+/// `from..to`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OffsetBucket {
+    pub from: u64,
+    pub to: u64,
+    pub ops: u64,
+    pub latency: LatencyStats,
+}
+
+/// Outcome of a stream growth study
+#[derive(Debug, Clone, Serialize)]
+pub struct StreamGrowthResult {
+    pub store: String,
+    pub config: StreamGrowthConfig,
+    pub events_appended: u64,
+    pub duration_s: f64,
+    /// Append latency by the stream length before the append
+    pub buckets: Vec<OffsetBucket>,
+    /// Least-squares slope of append latency over stream length, in microseconds per 10,000
+    /// events; near zero when appends cost the same however long the stream is
+    pub slope_us_per_10k_events: f64,
+    /// p50 of the last bucket relative to the first
+    pub p50_growth_ratio: f64,
+}
+
+/// Start the store and append to one stream until it holds `events` events, recording each
+/// append's latency against the stream length it was appended at
+pub async fn execute_stream_growth(
+    mut store: Box<dyn StoreManager>,
+    config: &StreamGrowthConfig,
+    cancel_token: CancellationToken,
+) -> Result<StreamGrowthResult> {
+    if config.events == 0 || config.batch_size == 0 || config.buckets == 0 {
+        anyhow::bail!("Stream growth requires events, batch size and buckets > 0");
+    }
+
+    start_store(store.as_mut(), &cancel_token).await?;
+    let result = grow_stream(store.as_ref(), config, &cancel_token).await;
+    store.stop().await.ok();
+    result
+}
+
+async fn grow_stream(
+    store: &dyn StoreManager,
+    config: &StreamGrowthConfig,
+    cancel_token: &CancellationToken,
+) -> Result<StreamGrowthResult> {
+    let adapter = store.create_adapter()?;
+    let stream = format!("growth-{}", Uuid::new_v4());
+    let payload = Bytes::from(vec![0u8; config.event_size_bytes]);
+    let width = config.events.div_ceil(config.buckets);
+    let mut recorders: Vec<(u64, LatencyRecorder)> = (0..config.buckets)
+        .map(|_| (0, LatencyRecorder::new()))
+        .collect();
+    let mut fit = LinearFit::default();
+
+    println!("Appending {} events to a single {} stream...", config.events, store.name());
+    let started = Instant::now();
+    let mut length = 0u64;
+    let mut next_report = width;
+    while length < config.events {
+        if cancel_token.is_cancelled() {
+            println!("Interrupted during stream growth run.");
+            anyhow::bail!("Interrupted");
+        }
+        let n = config.batch_size.min(config.events - length);
+        let events = (0..n)
+            .map(|_| EventData {
+                payload: payload.clone(),
+                event_type: "growth".to_string(),
+                tags: vec![stream.clone()],
+            })
+            .collect();
+        let t0 = Instant::now();
+        adapter.append(events).await?;
+        let latency = t0.elapsed();
+
+        let (ops, recorder) = &mut recorders[(length / width) as usize];
+        *ops += 1;
+        recorder.record(latency);
+        fit.add(length as f64, latency.as_secs_f64() * 1e6);
+        length += n;
+        if length >= next_report {
+            println!("  {} events, {:.0} events/sec", length, length as f64 / started.elapsed().as_secs_f64());
+            next_report += width;
+        }
+    }
+    let duration_s = started.elapsed().as_secs_f64();
+
+    let buckets: Vec<OffsetBucket> = recorders
+        .into_iter()
+        .enumerate()
+        .filter(|(_, (ops, _))| *ops > 0)
+        .map(|(idx, (ops, recorder))| OffsetBucket {
+            from: idx as u64 * width,
+            to: ((idx as u64 + 1) * width).min(config.events),
+            ops,
+            latency: recorder.to_stats(),
+        })
+        .collect();
+    let p50_growth_ratio = match (buckets.first(), buckets.last()) {
+        (Some(first), Some(last)) if first.latency.p50_ms > 0.0 => last.latency.p50_ms / first.latency.p50_ms,
+        _ => 1.0,
+    };
+
+    Ok(StreamGrowthResult {
+        store: store.name().to_string(),
+        config: config.clone(),
+        events_appended: length,
+        duration_s,
+        buckets,
+        slope_us_per_10k_events: fit.slope() * 10_000.0,
+        p50_growth_ratio,
+    })
+}
+
+/// Running least-squares fit of y over x
+#[derive(Debug, Default)]
+pub(crate) struct LinearFit {
+    n: f64,
+    sum_x: f64,
+    sum_y: f64,
+    sum_xx: f64,
+    sum_xy: f64,
+}
+
+impl LinearFit {
+    pub(crate) fn add(&mut self, x: f64, y: f64) {
+        self.n += 1.0;
+        self.sum_x += x;
+        self.sum_y += y;
+        self.sum_xx += x * x;
+        self.sum_xy += x * y;
+    }
+
+    /// Slope of the fitted line; 0 with fewer than two distinct x
+    pub(crate) fn slope(&self) -> f64 {
+        let denominator = self.n * self.sum_xx - self.sum_x * self.sum_x;
+        if denominator.abs() < f64::EPSILON {
+            return 0.0;
+        }
+        (self.n * self.sum_xy - self.sum_x * self.sum_y) / denominator
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fits_the_slope_of_growing_latencies() {
+        let mut fit = LinearFit::default();
+        assert_eq!(fit.slope(), 0.0);
+        for length in 0..100 {
+            fit.add(length as f64, 50.0 + 0.5 * length as f64 + if length % 2 == 0 { 1.0 } else { -1.0 });
+        }
+        assert!((fit.slope() - 0.5).abs() < 0.01, "{}", fit.slope());
+    }
+}
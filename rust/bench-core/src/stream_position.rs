@@ -0,0 +1,164 @@
+use crate::adapter::{EventData, ReadRequest, StoreManager};
+use crate::metrics::LatencyRecorder;
+use crate::stream_growth::OffsetBucket;
+use crate::workflow_support::{prepopulate_stream, run_on_started_store};
+use anyhow::Result;
+use bytes::Bytes;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+use tokio::task::JoinSet;
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+
+/// Settings for reading pages at different positions of long streams
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StreamPositionConfig {
+    /// Length of each stream
+    pub events: u64,
+    pub streams: u64,
+    pub event_size_bytes: usize,
+    /// Events per read
+    pub page_size: u64,
+    /// Number of reads measured, spread evenly over the buckets
+    pub reads: u64,
+    /// Number of equally wide offset ranges latencies are reported for
+    pub buckets: u64,
+    /// Seed for the read offsets
+    pub seed: u64,
+}
+
+/// Outcome of a read position study
+#[derive(Debug, Clone, Serialize)]
+pub struct StreamPositionResult {
+    pub store: String,
+    pub config: StreamPositionConfig,
+    /// Time spent prepopulating the streams (not part of the measurement)
+    pub prepopulate_duration_s: f64,
+    pub reads: u64,
+    /// Reads that returned no events, e.g. because the store lost them or doesn't keep events
+    pub empty_reads: u64,
+    /// Read latency by the offset the page starts at
+    pub buckets: Vec<OffsetBucket>,
+    /// p50 of the first, middle and last bucket
+    pub start_p50_ms: f64,
+    pub middle_p50_ms: f64,
+    pub tail_p50_ms: f64,
+}
+
+/// Start the store, prepopulate the streams, then read pages starting at random offsets
+/// (bucket by bucket in turn), recording each read's latency against its offset
+pub async fn execute_stream_position(
+    store: Box<dyn StoreManager>,
+    config: &StreamPositionConfig,
+    cancel_token: CancellationToken,
+) -> Result<StreamPositionResult> {
+    if config.streams == 0 || config.page_size == 0 || config.reads == 0 || config.buckets == 0 {
+        anyhow::bail!("Stream position study requires streams, page size, reads and buckets > 0");
+    }
+    if config.events < config.buckets * config.page_size {
+        anyhow::bail!(
+            "Streams of {} events are too short for {} buckets of {}-event pages",
+            config.events,
+            config.buckets,
+            config.page_size
+        );
+    }
+
+    run_on_started_store(store, &cancel_token, "stream position study", async |store| {
+        read_positions(store, config).await
+    })
+    .await
+}
+
+async fn read_positions(store: &dyn StoreManager, config: &StreamPositionConfig) -> Result<StreamPositionResult> {
+    let stream_prefix = format!("position-{}-", Uuid::new_v4());
+    let streams: Vec<String> = (0..config.streams)
+        .map(|idx| format!("{}{}", stream_prefix, idx))
+        .collect();
+
+    println!(
+        "Prepopulating {} with {} streams of {} events...",
+        store.name(),
+        config.streams,
+        config.events
+    );
+    let prepopulate_started = Instant::now();
+    let mut set = JoinSet::new();
+    for stream in &streams {
+        let adapter = store.create_adapter()?;
+        let stream = stream.clone();
+        let payload = Bytes::from(vec![0u8; config.event_size_bytes]);
+        let total = config.events;
+        set.spawn(async move {
+            let event = |_| EventData {
+                payload: payload.clone(),
+                event_type: "setup".to_string(),
+                tags: vec![stream.clone()],
+            };
+            prepopulate_stream(adapter.as_ref(), total, event).await
+        });
+    }
+    while let Some(res) = set.join_next().await {
+        res??;
+    }
+    let prepopulate_duration_s = prepopulate_started.elapsed().as_secs_f64();
+    println!("Prepopulation completed in {:.2} seconds", prepopulate_duration_s);
+
+    // Pages must fit in the stream, so the last bucket ends a page before its end
+    let readable = config.events - config.page_size + 1;
+    let width = readable.div_ceil(config.buckets);
+    let mut recorders: Vec<(u64, LatencyRecorder)> = (0..config.buckets)
+        .map(|_| (0, LatencyRecorder::new()))
+        .collect();
+    let mut rng = StdRng::seed_from_u64(config.seed);
+    let mut empty_reads = 0u64;
+
+    println!("Reading {} pages of {} events...", config.reads, config.page_size);
+    let adapter = store.create_adapter()?;
+    for i in 0..config.reads {
+        let bucket = i % config.buckets;
+        let from = bucket * width;
+        let to = ((bucket + 1) * width).min(readable);
+        let offset = rng.gen_range(from..to);
+        let stream = streams[rng.gen_range(0..streams.len())].clone();
+        let t0 = Instant::now();
+        let events = adapter
+            .read(ReadRequest {
+                stream,
+                from_offset: Some(offset),
+                limit: Some(config.page_size),
+                consistency: Default::default(),
+            })
+            .await?;
+        let latency = t0.elapsed();
+        empty_reads += events.is_empty() as u64;
+        let (ops, recorder) = &mut recorders[bucket as usize];
+        *ops += 1;
+        recorder.record(latency);
+    }
+
+    let buckets: Vec<OffsetBucket> = recorders
+        .into_iter()
+        .enumerate()
+        .map(|(idx, (ops, recorder))| OffsetBucket {
+            from: idx as u64 * width,
+            to: ((idx as u64 + 1) * width).min(readable),
+            ops,
+            latency: recorder.to_stats(),
+        })
+        .collect();
+    let p50 = |idx: usize| buckets[idx].latency.p50_ms;
+
+    Ok(StreamPositionResult {
+        store: store.name().to_string(),
+        config: config.clone(),
+        prepopulate_duration_s,
+        reads: config.reads,
+        empty_reads,
+        start_p50_ms: p50(0),
+        middle_p50_ms: p50(buckets.len() / 2),
+        tail_p50_ms: p50(buckets.len() - 1),
+        buckets,
+    })
+}
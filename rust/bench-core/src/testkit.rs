@@ -0,0 +1,482 @@
+use crate::adapter::{AppendCondition, AppendOutcome, EventData, EventStoreAdapter, ReadEvent, ReadRequest, StoreManager};
+use crate::conformance::check_missing_stream_read;
+use anyhow::{bail, ensure, Result};
+use bytes::Bytes;
+use futures::{StreamExt, TryStreamExt};
+use std::time::Duration;
+use uuid::Uuid;
+
+/// Optional parts of the adapter contract the store implements
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Capabilities {
+    /// `append_conditional` is supported
+    pub conditional_append: bool,
+    /// `subscribe` is supported
+    pub subscribe: bool,
+    /// `read_all` is supported
+    pub read_all: bool,
+}
+
+/// Events appended per checked stream
+const EVENTS: usize = 3;
+/// Events per `read_all` call when paging through the global log
+const GLOBAL_PAGE: u64 = 2;
+/// Longest wait for a subscription to deliver an event
+const SUBSCRIPTION_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Run the adapter conformance suite against `store` (already started), reporting all failed
+/// checks at once. Adapter crates call this from their integration tests, so a new adapter
+/// meets the `EventStoreAdapter` contract before its numbers are trusted.
+pub async fn run_conformance(store: &dyn StoreManager, capabilities: Capabilities) -> Result<()> {
+    let adapter = store.create_adapter()?;
+    let adapter = adapter.as_ref();
+    let mut failures = Vec::new();
+    let mut check = |name: &str, result: Result<()>| {
+        if let Err(e) = result {
+            failures.push(format!("{}: {:#}", name, e));
+        }
+    };
+
+    check("append/read roundtrip", roundtrip(adapter).await);
+    check("tags roundtrip", tags_roundtrip(adapter).await);
+    check("offsets monotonic", offsets_monotonic(adapter).await);
+    check("limit honored", limit_honored(adapter).await);
+    check("from_offset", from_offset(adapter).await);
+    check("missing stream reads empty", missing_stream(adapter).await);
+    check("streamed read", streamed_read(adapter).await);
+    if capabilities.conditional_append {
+        check("conditional append", conditional_append(adapter).await);
+    }
+    if capabilities.subscribe {
+        check("subscription", subscription(adapter).await);
+    }
+    if capabilities.read_all {
+        check("global log", global_log(adapter).await);
+    }
+
+    if !failures.is_empty() {
+        bail!("{} failed conformance:\n  {}", store.name(), failures.join("\n  "));
+    }
+    Ok(())
+}
+
+/// Event `i` of a checked stream. Payloads are compact JSON, so stores that keep JSON
+/// documents hand them back byte for byte.
+fn event(stream: &str, i: usize) -> EventData {
+    EventData {
+        payload: Bytes::from(format!(r#"{{"n":{}}}"#, i)),
+        event_type: format!("conformance-{}", i),
+        tags: vec![stream.to_string()],
+    }
+}
+
+/// A fresh stream holding `EVENTS` events, appended one at a time
+async fn seeded_stream(adapter: &dyn EventStoreAdapter) -> Result<String> {
+    let stream = format!("conformance-{}", Uuid::new_v4());
+    for i in 0..EVENTS {
+        adapter.append(vec![event(&stream, i)]).await?;
+    }
+    Ok(stream)
+}
+
+async fn read_whole_stream(adapter: &dyn EventStoreAdapter, stream: &str) -> Result<Vec<ReadEvent>> {
+    adapter
+        .read(ReadRequest {
+            stream: stream.to_string(),
+            from_offset: None,
+            limit: None,
+            consistency: Default::default(),
+        })
+        .await
+}
+
+async fn roundtrip(adapter: &dyn EventStoreAdapter) -> Result<()> {
+    let stream = seeded_stream(adapter).await?;
+    let events = read_whole_stream(adapter, &stream).await?;
+    ensure!(events.len() == EVENTS, "read {} events, appended {}", events.len(), EVENTS);
+    for (i, read) in events.iter().enumerate() {
+        let written = event(&stream, i);
+        ensure!(
+            read.payload == written.payload,
+            "event {} payload {:?}, appended {:?}",
+            i,
+            read.payload,
+            written.payload
+        );
+        // Stores may namespace event types
+        ensure!(
+            read.event_type.ends_with(&written.event_type),
+            "event {} type {:?}, appended {:?}",
+            i,
+            read.event_type,
+            written.event_type
+        );
+    }
+    Ok(())
+}
+
+/// Events read back carry the tags they were appended with, in order, the stream tag first
+async fn tags_roundtrip(adapter: &dyn EventStoreAdapter) -> Result<()> {
+    let stream = format!("conformance-{}", Uuid::new_v4());
+    let tagged = |i: usize, extra: &[&str]| EventData {
+        tags: std::iter::once(stream.clone()).chain(extra.iter().map(|t| t.to_string())).collect(),
+        ..event(&stream, i)
+    };
+    let written = [
+        tagged(0, &[]),
+        tagged(1, &["tenant:acme", "order-42"]),
+        tagged(2, &["with space", "a,b \"quoted\" ünïcode"]),
+    ];
+    for evt in &written {
+        adapter.append(vec![evt.clone()]).await?;
+    }
+    let events = read_whole_stream(adapter, &stream).await?;
+    ensure!(events.len() == written.len(), "read {} events, appended {}", events.len(), written.len());
+    for (i, (read, written)) in events.iter().zip(&written).enumerate() {
+        ensure!(read.tags == written.tags, "event {} tags {:?}, appended {:?}", i, read.tags, written.tags);
+    }
+    Ok(())
+}
+
+async fn offsets_monotonic(adapter: &dyn EventStoreAdapter) -> Result<()> {
+    let stream = seeded_stream(adapter).await?;
+    let events = read_whole_stream(adapter, &stream).await?;
+    for pair in events.windows(2) {
+        ensure!(
+            pair[1].offset > pair[0].offset,
+            "offset {} follows offset {}",
+            pair[1].offset,
+            pair[0].offset
+        );
+    }
+    Ok(())
+}
+
+async fn limit_honored(adapter: &dyn EventStoreAdapter) -> Result<()> {
+    let stream = seeded_stream(adapter).await?;
+    let events = adapter
+        .read(ReadRequest {
+            stream,
+            from_offset: None,
+            limit: Some(2),
+            consistency: Default::default(),
+        })
+        .await?;
+    ensure!(events.len() == 2, "read {} events with limit 2", events.len());
+    Ok(())
+}
+
+async fn from_offset(adapter: &dyn EventStoreAdapter) -> Result<()> {
+    let stream = seeded_stream(adapter).await?;
+    let all = read_whole_stream(adapter, &stream).await?;
+    ensure!(all.len() == EVENTS, "read {} events, appended {}", all.len(), EVENTS);
+    let tail = adapter
+        .read(ReadRequest {
+            stream,
+            from_offset: Some(all[1].offset),
+            limit: None,
+            consistency: Default::default(),
+        })
+        .await?;
+    let offsets: Vec<u64> = tail.iter().map(|e| e.offset).collect();
+    let expected: Vec<u64> = all[1..].iter().map(|e| e.offset).collect();
+    ensure!(
+        offsets == expected,
+        "reading from offset {} returned offsets {:?}, expected {:?}",
+        all[1].offset,
+        offsets,
+        expected
+    );
+    Ok(())
+}
+
+/// `read_stream` yields the events `read` returns, honoring `from_offset` and `limit`
+async fn streamed_read(adapter: &dyn EventStoreAdapter) -> Result<()> {
+    let stream = seeded_stream(adapter).await?;
+    let all = read_whole_stream(adapter, &stream).await?;
+    ensure!(all.len() == EVENTS, "read {} events, appended {}", all.len(), EVENTS);
+    for (from_offset, limit) in [(None, None), (Some(all[1].offset), Some(1))] {
+        let req = ReadRequest {
+            stream: stream.clone(),
+            from_offset,
+            limit,
+            consistency: Default::default(),
+        };
+        let expected: Vec<u64> = adapter.read(req.clone()).await?.iter().map(|e| e.offset).collect();
+        let streamed: Vec<u64> = adapter.read_stream(req).map_ok(|e| e.offset).try_collect().await?;
+        ensure!(
+            streamed == expected,
+            "streaming from {:?} with limit {:?} yielded offsets {:?}, read returned {:?}",
+            from_offset,
+            limit,
+            streamed,
+            expected
+        );
+    }
+    Ok(())
+}
+
+async fn missing_stream(adapter: &dyn EventStoreAdapter) -> Result<()> {
+    let result = check_missing_stream_read(adapter).await;
+    ensure!(result.conforms(), "{:?}", result);
+    Ok(())
+}
+
+async fn conditional_append(adapter: &dyn EventStoreAdapter) -> Result<()> {
+    let stream = format!("conformance-{}", Uuid::new_v4());
+    let append = |i, condition| adapter.append_conditional(vec![event(&stream, i)], condition);
+
+    let AppendOutcome::Appended { position: first } = append(0, AppendCondition::NoStream).await?
+    else {
+        bail!("NoStream append to a new stream conflicted");
+    };
+    if let AppendOutcome::Appended { .. } = append(1, AppendCondition::NoStream).await? {
+        bail!("NoStream append to an existing stream succeeded");
+    }
+    let AppendOutcome::Appended { position: second } =
+        append(1, AppendCondition::After(first)).await?
+    else {
+        bail!("append after the current position {} conflicted", first);
+    };
+    ensure!(second > first, "position {} after position {}", second, first);
+    if let AppendOutcome::Appended { .. } = append(2, AppendCondition::After(first)).await? {
+        bail!("append after the stale position {} succeeded", first);
+    }
+    Ok(())
+}
+
+/// A subscription from the second stored event delivers the rest, then an event appended later
+async fn subscription(adapter: &dyn EventStoreAdapter) -> Result<()> {
+    let stream = seeded_stream(adapter).await?;
+    let stored = read_whole_stream(adapter, &stream).await?;
+    ensure!(stored.len() == EVENTS, "read {} events, appended {}", stored.len(), EVENTS);
+    let mut subscription = adapter.subscribe(&stream, Some(stored[1].offset)).await?;
+    let mut next = async || match tokio::time::timeout(SUBSCRIPTION_TIMEOUT, subscription.next()).await {
+        Ok(Some(event)) => event,
+        Ok(None) => bail!("subscription ended"),
+        Err(_) => bail!("no event within {:?}", SUBSCRIPTION_TIMEOUT),
+    };
+    for expected in &stored[1..] {
+        let delivered = next().await?;
+        ensure!(
+            delivered.offset == expected.offset,
+            "delivered offset {}, expected {}",
+            delivered.offset,
+            expected.offset
+        );
+    }
+    let live = event(&stream, EVENTS);
+    adapter.append(vec![live.clone()]).await?;
+    let delivered = next().await?;
+    ensure!(
+        delivered.payload == live.payload,
+        "delivered {:?} after appending {:?}",
+        delivered.payload,
+        live.payload
+    );
+    Ok(())
+}
+
+/// Paging through the global log by position returns the events of different streams in
+/// append order, each once
+async fn global_log(adapter: &dyn EventStoreAdapter) -> Result<()> {
+    let streams = [format!("conformance-{}", Uuid::new_v4()), format!("conformance-{}", Uuid::new_v4())];
+    let mut written = Vec::new();
+    for i in 0..EVENTS {
+        for stream in &streams {
+            let evt = event(stream, i);
+            adapter.append(vec![evt.clone()]).await?;
+            written.push(evt);
+        }
+    }
+    let mut read = Vec::new();
+    let mut after = None;
+    loop {
+        let page = adapter.read_all(after, Some(GLOBAL_PAGE)).await?;
+        ensure!(page.len() as u64 <= GLOBAL_PAGE, "read {} events with limit {}", page.len(), GLOBAL_PAGE);
+        if page.is_empty() {
+            break;
+        }
+        for evt in page {
+            ensure!(
+                after.is_none_or(|after| evt.offset > after),
+                "position {} after position {:?}",
+                evt.offset,
+                after
+            );
+            after = Some(evt.offset);
+            if evt.tags.first().is_some_and(|tag| streams.contains(tag)) {
+                read.push(evt);
+            }
+        }
+    }
+    ensure!(read.len() == written.len(), "read {} events, appended {}", read.len(), written.len());
+    let streamed: Vec<ReadEvent> = adapter
+        .read_all_stream(None)
+        .try_filter(|evt| futures::future::ready(evt.tags.first().is_some_and(|tag| streams.contains(tag))))
+        .try_collect()
+        .await?;
+    let offsets = |events: &[ReadEvent]| events.iter().map(|e| e.offset).collect::<Vec<_>>();
+    ensure!(
+        offsets(&streamed) == offsets(&read),
+        "streaming the global log yielded positions {:?}, paging read {:?}",
+        offsets(&streamed),
+        offsets(&read)
+    );
+    for (i, (read, written)) in read.iter().zip(&written).enumerate() {
+        ensure!(
+            read.tags[0] == written.tags[0] && read.payload == written.payload,
+            "event {} of stream {} with payload {:?}, appended to {} with {:?}",
+            i,
+            read.tags[0],
+            read.payload,
+            written.tags[0],
+            written.payload
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapter::EventStream;
+    use async_trait::async_trait;
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+
+    /// Minimal store following the contract, to check the suite itself
+    #[derive(Default)]
+    struct MemoryStore {
+        streams: Arc<Mutex<HashMap<String, Vec<EventData>>>>,
+        log: Arc<Mutex<Vec<EventData>>>,
+    }
+
+    #[async_trait]
+    impl StoreManager for MemoryStore {
+        async fn start(&mut self) -> Result<()> {
+            Ok(())
+        }
+        async fn pull(&mut self) -> Result<()> {
+            Ok(())
+        }
+        async fn stop(&mut self) -> Result<()> {
+            Ok(())
+        }
+        fn container_id(&self) -> Option<String> {
+            None
+        }
+        fn name(&self) -> &'static str {
+            "memory"
+        }
+        fn create_adapter(&self) -> Result<Arc<dyn EventStoreAdapter>> {
+            Ok(Arc::new(MemoryStore {
+                streams: self.streams.clone(),
+                log: self.log.clone(),
+            }))
+        }
+    }
+
+    #[async_trait]
+    impl EventStoreAdapter for MemoryStore {
+        async fn append(&self, events: Vec<EventData>) -> Result<()> {
+            let mut streams = self.streams.lock().unwrap();
+            self.log.lock().unwrap().extend(events.iter().cloned());
+            streams.entry(events[0].tags[0].clone()).or_default().extend(events);
+            Ok(())
+        }
+
+        async fn read(&self, req: ReadRequest) -> Result<Vec<ReadEvent>> {
+            let streams = self.streams.lock().unwrap();
+            Ok(streams
+                .get(&req.stream)
+                .into_iter()
+                .flatten()
+                .enumerate()
+                .skip(req.from_offset.unwrap_or(0) as usize)
+                .take(req.limit.unwrap_or(u64::MAX) as usize)
+                .map(|(offset, evt)| ReadEvent {
+                    offset: offset as u64,
+                    event_type: evt.event_type.clone(),
+                    payload: evt.payload.clone(),
+                    timestamp_ms: 0,
+                    tags: evt.tags.clone(),
+                })
+                .collect())
+        }
+
+        async fn append_conditional(
+            &self,
+            events: Vec<EventData>,
+            condition: AppendCondition,
+        ) -> Result<AppendOutcome> {
+            let mut streams = self.streams.lock().unwrap();
+            let stream = streams.entry(events[0].tags[0].clone()).or_default();
+            let current = stream.len().checked_sub(1).map(|p| p as u64);
+            let matches = match condition {
+                AppendCondition::NoStream => current.is_none(),
+                AppendCondition::After(position) => current == Some(position),
+            };
+            if !matches {
+                return Ok(AppendOutcome::Conflict { current });
+            }
+            self.log.lock().unwrap().extend(events.iter().cloned());
+            stream.extend(events);
+            Ok(AppendOutcome::Appended {
+                position: stream.len() as u64 - 1,
+            })
+        }
+
+        async fn read_all(&self, after: Option<u64>, limit: Option<u64>) -> Result<Vec<ReadEvent>> {
+            let log = self.log.lock().unwrap();
+            Ok(log
+                .iter()
+                .enumerate()
+                .skip(after.map_or(0, |after| after as usize + 1))
+                .take(limit.unwrap_or(u64::MAX) as usize)
+                .map(|(position, evt)| ReadEvent {
+                    offset: position as u64,
+                    event_type: evt.event_type.clone(),
+                    payload: evt.payload.clone(),
+                    timestamp_ms: 0,
+                    tags: evt.tags.clone(),
+                })
+                .collect())
+        }
+
+        async fn subscribe(&self, stream: &str, from_offset: Option<u64>) -> Result<EventStream> {
+            let adapter = MemoryStore {
+                streams: self.streams.clone(),
+                log: self.log.clone(),
+            };
+            let state = (adapter, stream.to_string(), from_offset.unwrap_or(0));
+            Ok(Box::pin(futures::stream::unfold(state, |(adapter, stream, next)| async move {
+                loop {
+                    let req = ReadRequest {
+                        stream: stream.clone(),
+                        from_offset: Some(next),
+                        limit: Some(1),
+                        consistency: Default::default(),
+                    };
+                    match adapter.read(req).await {
+                        Ok(mut events) if !events.is_empty() => {
+                            return Some((Ok(events.remove(0)), (adapter, stream, next + 1)))
+                        }
+                        Ok(_) => tokio::time::sleep(Duration::from_millis(1)).await,
+                        Err(e) => return Some((Err(e), (adapter, stream, next))),
+                    }
+                }
+            })))
+        }
+    }
+
+    #[tokio::test]
+    async fn conforming_store_passes() {
+        let capabilities = Capabilities {
+            conditional_append: true,
+            subscribe: true,
+            read_all: true,
+        };
+        run_conformance(&MemoryStore::default(), capabilities).await.unwrap();
+    }
+}
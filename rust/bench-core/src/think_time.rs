@@ -0,0 +1,98 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Longest single pause; an exponential draw far in its tail is cut off here
+pub const MAX_THINK_TIME: Duration = Duration::from_secs(3600);
+
+/// Pause between a worker's operations, modelling interactive clients instead of saturation load
+///
+/// Configured either as a fixed number of milliseconds (`think_time_ms: 50`) or as a distribution
+/// (`think_time_ms: { distribution: exponential, mean_ms: 50 }`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ThinkTime {
+    Fixed(u64),
+    Distribution(ThinkTimeDistribution),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "distribution", rename_all = "snake_case")]
+pub enum ThinkTimeDistribution {
+    /// Uniformly distributed between `min_ms` and `max_ms` (inclusive)
+    Uniform { min_ms: u64, max_ms: u64 },
+    /// Exponentially distributed with the given mean (memoryless user arrivals)
+    Exponential { mean_ms: f64 },
+}
+
+impl ThinkTime {
+    pub fn validate(&self) -> anyhow::Result<()> {
+        match self {
+            ThinkTime::Fixed(_) => Ok(()),
+            ThinkTime::Distribution(ThinkTimeDistribution::Uniform { min_ms, max_ms }) => {
+                if min_ms > max_ms {
+                    anyhow::bail!("think_time_ms min_ms ({}) must not exceed max_ms ({})", min_ms, max_ms);
+                }
+                Ok(())
+            }
+            ThinkTime::Distribution(ThinkTimeDistribution::Exponential { mean_ms }) => {
+                if !mean_ms.is_finite() || *mean_ms < 0.0 || *mean_ms > MAX_THINK_TIME.as_millis() as f64 {
+                    anyhow::bail!(
+                        "think_time_ms mean_ms must be between 0 and {}, got {}",
+                        MAX_THINK_TIME.as_millis(),
+                        mean_ms
+                    );
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Draw the next pause
+    pub fn sample<R: Rng>(&self, rng: &mut R) -> Duration {
+        match self {
+            ThinkTime::Fixed(ms) => Duration::from_millis(*ms),
+            ThinkTime::Distribution(ThinkTimeDistribution::Uniform { min_ms, max_ms }) => {
+                Duration::from_millis(rng.gen_range(*min_ms..=*max_ms))
+            }
+            ThinkTime::Distribution(ThinkTimeDistribution::Exponential { mean_ms }) => {
+                // Inverse transform sampling; gen::<f64>() is in [0, 1) so 1 - u is never zero
+                let u: f64 = rng.gen();
+                Duration::try_from_secs_f64(-mean_ms * (1.0 - u).ln() / 1000.0)
+                    .map_or(MAX_THINK_TIME, |pause| pause.min(MAX_THINK_TIME))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn samples_constant_and_exponential_think_times() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let fixed: ThinkTime = serde_yaml::from_str("50").unwrap();
+        assert!((0..100).all(|_| fixed.sample(&mut rng) == Duration::from_millis(50)));
+
+        let exponential: ThinkTime = serde_yaml::from_str("{distribution: exponential, mean_ms: 20}").unwrap();
+        exponential.validate().unwrap();
+        let pauses: Vec<Duration> = (0..10_000).map(|_| exponential.sample(&mut rng)).collect();
+        let mean_ms = pauses.iter().map(|p| p.as_secs_f64() * 1000.0).sum::<f64>() / pauses.len() as f64;
+        assert!((18.0..22.0).contains(&mean_ms), "mean {} ms", mean_ms);
+        // The same seed draws the same pauses
+        let mut again = StdRng::seed_from_u64(42);
+        (0..100).for_each(|_| {
+            fixed.sample(&mut again);
+        });
+        assert_eq!(exponential.sample(&mut again), pauses[0]);
+
+        let huge = ThinkTime::Distribution(ThinkTimeDistribution::Exponential { mean_ms: f64::MAX });
+        assert!(huge.validate().is_err());
+        assert!(huge.sample(&mut rng) <= MAX_THINK_TIME);
+        let nan = ThinkTime::Distribution(ThinkTimeDistribution::Exponential { mean_ms: f64::NAN });
+        assert!(nan.validate().is_err());
+    }
+}
@@ -0,0 +1,116 @@
+use crate::host_stats::HostStatsSample;
+use serde::{Deserialize, Serialize};
+
+/// Only samples with the host at least this busy count towards frequency drops; idle CPUs
+/// clock down without being throttled
+const BUSY_CPU_PERCENT: f64 = 50.0;
+/// Below this share of the base frequency a busy CPU is throttled
+const LOW_FREQ_OF_BASE: f64 = 90.0;
+/// Below this share of the maximum (turbo) frequency a busy CPU is throttled; all-core load
+/// alone keeps most CPUs well below their single-core maximum
+const LOW_FREQ_OF_MAX: f64 = 60.0;
+/// Share of busy samples that must be low for the run to count as throttled
+const LOW_FREQ_SHARE: f64 = 0.25;
+
+/// CPU frequency and thermal behaviour of the host during a run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThrottlingStats {
+    /// Significant throttling happened, so the run's throughput and tail latencies are suspect
+    /// (and likely worse than for runs earlier in the session)
+    pub throttled: bool,
+    pub reason: String,
+    /// Average frequency as a share of nominal (see `freq_of_base`)
+    pub avg_freq_percent: Option<f64>,
+    pub min_freq_percent: Option<f64>,
+    /// Whether frequencies are relative to the base frequency rather than the maximum
+    pub freq_of_base: Option<bool>,
+    /// Share of busy samples below the low-frequency threshold
+    pub low_freq_share: Option<f64>,
+    pub max_temp_c: Option<f64>,
+    /// Thermal throttle events the kernel counted during the run
+    pub throttle_events: Option<u64>,
+}
+
+/// Flag throttling from the frequency and thermal fields of the host samples; `None` where the
+/// host exposes none of them (containers, macOS, most VMs)
+pub fn analyze_throttling(host_stats: &[HostStatsSample]) -> Option<ThrottlingStats> {
+    let freqs: Vec<f64> = host_stats.iter().filter_map(|s| s.cpu_freq_percent).collect();
+    let max_temp_c = host_stats.iter().filter_map(|s| s.cpu_temp_c).max_by(f64::total_cmp);
+    let throttle_events = host_stats
+        .iter()
+        .filter_map(|s| s.throttle_events)
+        .reduce(|a, b| a + b);
+    if freqs.is_empty() && max_temp_c.is_none() && throttle_events.is_none() {
+        return None;
+    }
+
+    let freq_of_base = host_stats.iter().find_map(|s| s.cpu_freq_of_base);
+    let threshold = if freq_of_base == Some(true) { LOW_FREQ_OF_BASE } else { LOW_FREQ_OF_MAX };
+    let busy: Vec<f64> = host_stats
+        .iter()
+        .filter(|s| s.cpu_percent.is_none_or(|cpu| cpu >= BUSY_CPU_PERCENT))
+        .filter_map(|s| s.cpu_freq_percent)
+        .collect();
+    let low_freq_share = (!busy.is_empty())
+        .then(|| busy.iter().filter(|f| **f < threshold).count() as f64 / busy.len() as f64);
+
+    let (throttled, reason) = if let Some(events @ 1..) = throttle_events {
+        (true, format!("{} thermal throttle events", events))
+    } else if let Some(share) = low_freq_share.filter(|share| *share >= LOW_FREQ_SHARE) {
+        (
+            true,
+            format!(
+                "CPU below {:.0}% of its {} frequency in {:.0}% of busy samples",
+                threshold,
+                if freq_of_base == Some(true) { "base" } else { "maximum" },
+                share * 100.0
+            ),
+        )
+    } else {
+        (false, "no significant throttling".to_string())
+    };
+
+    Some(ThrottlingStats {
+        throttled,
+        reason,
+        avg_freq_percent: (!freqs.is_empty()).then(|| freqs.iter().sum::<f64>() / freqs.len() as f64),
+        min_freq_percent: freqs.iter().copied().min_by(f64::total_cmp),
+        freq_of_base,
+        low_freq_share,
+        max_temp_c,
+        throttle_events,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(cpu_percent: f64, cpu_freq_percent: f64) -> HostStatsSample {
+        HostStatsSample {
+            t_rel_ms: 0,
+            cpu_percent: Some(cpu_percent),
+            iowait_percent: None,
+            load_avg_1m: None,
+            memory_used_percent: None,
+            memory_pressure_avg10: None,
+            disk_util_percent: None,
+            disk_device: None,
+            cpu_freq_percent: Some(cpu_freq_percent),
+            cpu_freq_of_base: Some(true),
+            cpu_temp_c: None,
+            throttle_events: None,
+        }
+    }
+
+    #[test]
+    fn flags_low_frequency_only_while_busy() {
+        // An idle dip doesn't count, a busy one in a quarter of the samples does
+        let samples = [sample(10.0, 30.0), sample(95.0, 100.0), sample(95.0, 100.0), sample(95.0, 100.0)];
+        assert!(!analyze_throttling(&samples).unwrap().throttled);
+        let samples = [sample(95.0, 70.0), sample(95.0, 100.0), sample(95.0, 100.0), sample(95.0, 100.0)];
+        let stats = analyze_throttling(&samples).unwrap();
+        assert!(stats.throttled, "{}", stats.reason);
+        assert!(analyze_throttling(&[]).is_none());
+    }
+}
@@ -0,0 +1,232 @@
+use crate::adapter::{AdminOp, AppendCondition, AppendOutcome, EventData, EventStoreAdapter, EventStream, ReadEvent, ReadRequest, ReadStream, RetentionPolicy};
+use async_trait::async_trait;
+use std::future::Future;
+use std::sync::Arc;
+use tracing::{Instrument, Level, Span};
+
+/// Target of the run and operation spans, which the CLI exports over OTLP when built with the
+/// `otel` feature and given `--otlp-endpoint`
+pub const SPAN_TARGET: &str = "es_bench::op";
+
+/// Whether a subscriber collects the run and operation spans; without one, performance runs
+/// don't wrap their adapters in `Traced`
+pub fn spans_enabled() -> bool {
+    tracing::enabled!(target: SPAN_TARGET, Level::INFO)
+}
+
+/// Adapter wrapper running every operation in a span carrying the store name, the stream and
+/// the payload size, as a child of the run's span, so client-observed latency can be lined up
+/// with the store's own traces
+pub struct Traced<A: ?Sized> {
+    inner: Arc<A>,
+    store: &'static str,
+    run: Span,
+}
+
+impl<A: EventStoreAdapter + ?Sized> Traced<A> {
+    pub fn new(inner: Arc<A>, store: &'static str, run: Span) -> Self {
+        Self { inner, store, run }
+    }
+
+    async fn run<T: Send>(&self, span: Span, fut: impl Future<Output = anyhow::Result<T>> + Send) -> anyhow::Result<T> {
+        let out = fut.instrument(span.clone()).await;
+        if let Err(e) = &out {
+            span.record("otel.status_code", "ERROR");
+            span.record("error", tracing::field::display(e));
+        }
+        out
+    }
+
+    fn span(&self, op: &'static str, stream: &str, events: &[EventData]) -> Span {
+        tracing::info_span!(
+            target: SPAN_TARGET,
+            parent: &self.run,
+            "op",
+            otel.name = op,
+            otel.kind = "client",
+            otel.status_code = tracing::field::Empty,
+            error = tracing::field::Empty,
+            store = self.store,
+            stream = stream,
+            events = events.len(),
+            payload_bytes = events.iter().map(|e| e.payload.len()).sum::<usize>(),
+        )
+    }
+}
+
+/// Record the events a read returned on its span
+fn record_read(span: &Span, events: &[ReadEvent]) {
+    span.record("events", events.len());
+    span.record("payload_bytes", events.iter().map(|e| e.payload.len()).sum::<usize>());
+}
+
+/// The stream an append goes to (its events' first tag)
+fn stream_of(events: &[EventData]) -> &str {
+    events.first().and_then(|e| e.tags.first()).map_or("", String::as_str)
+}
+
+#[async_trait]
+impl<A: EventStoreAdapter + ?Sized> EventStoreAdapter for Traced<A> {
+    async fn append(&self, events: Vec<EventData>) -> anyhow::Result<()> {
+        let span = self.span("append", stream_of(&events), &events);
+        self.run(span, self.inner.append(events)).await
+    }
+
+    async fn read(&self, req: ReadRequest) -> anyhow::Result<Vec<ReadEvent>> {
+        let span = self.span("read", &req.stream, &[]);
+        let events = self.run(span.clone(), self.inner.read(req)).await?;
+        record_read(&span, &events);
+        Ok(events)
+    }
+
+    async fn append_conditional(
+        &self,
+        events: Vec<EventData>,
+        condition: AppendCondition,
+    ) -> anyhow::Result<AppendOutcome> {
+        let span = self.span("append_conditional", stream_of(&events), &events);
+        self.run(span, self.inner.append_conditional(events, condition)).await
+    }
+
+    async fn list_streams(&self, prefix: &str, limit: Option<u64>) -> anyhow::Result<Vec<String>> {
+        let span = self.span("list_streams", prefix, &[]);
+        self.run(span, self.inner.list_streams(prefix, limit)).await
+    }
+
+    async fn read_all(&self, after: Option<u64>, limit: Option<u64>) -> anyhow::Result<Vec<ReadEvent>> {
+        let span = self.span("read_all", "", &[]);
+        let events = self.run(span.clone(), self.inner.read_all(after, limit)).await?;
+        record_read(&span, &events);
+        Ok(events)
+    }
+
+    // Streamed reads and subscriptions outlive any one operation, so they get no span
+    fn read_stream(&self, req: ReadRequest) -> ReadStream<'_> {
+        self.inner.read_stream(req)
+    }
+
+    fn read_all_stream(&self, after: Option<u64>) -> ReadStream<'_> {
+        self.inner.read_all_stream(after)
+    }
+
+    async fn subscribe(&self, stream: &str, from_offset: Option<u64>) -> anyhow::Result<EventStream> {
+        self.inner.subscribe(stream, from_offset).await
+    }
+
+    async fn set_retention(&self, stream: &str, policy: &RetentionPolicy) -> anyhow::Result<()> {
+        let span = self.span("set_retention", stream, &[]);
+        self.run(span, self.inner.set_retention(stream, policy)).await
+    }
+
+    async fn admin(&self, op: AdminOp) -> anyhow::Result<()> {
+        let span = self.span("admin", "", &[]);
+        self.run(span, self.inner.admin(op)).await
+    }
+
+    async fn ping(&self) -> anyhow::Result<()> {
+        self.inner.ping().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instrument::{Instrumented, PhaseRecorder};
+    use crate::ledger::{Ledger, LedgerOptions, Ledgered};
+    use futures::StreamExt;
+    use std::sync::Mutex;
+
+    /// Adapter implementing every operation, logging the ones called
+    #[derive(Default)]
+    struct Complete {
+        calls: Mutex<Vec<&'static str>>,
+    }
+
+    impl Complete {
+        fn event(&self, op: &'static str) -> ReadEvent {
+            self.calls.lock().unwrap().push(op);
+            ReadEvent {
+                offset: 0,
+                event_type: op.to_string(),
+                payload: Default::default(),
+                timestamp_ms: 0,
+                tags: Vec::new(),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl EventStoreAdapter for Complete {
+        async fn append(&self, _events: Vec<EventData>) -> anyhow::Result<()> {
+            self.event("append");
+            Ok(())
+        }
+
+        async fn read(&self, _req: ReadRequest) -> anyhow::Result<Vec<ReadEvent>> {
+            Ok(vec![self.event("read")])
+        }
+
+        async fn read_all(&self, _after: Option<u64>, _limit: Option<u64>) -> anyhow::Result<Vec<ReadEvent>> {
+            Ok(vec![self.event("read_all")])
+        }
+
+        fn read_stream(&self, _req: ReadRequest) -> ReadStream<'_> {
+            Box::pin(futures::stream::iter([Ok(self.event("read_stream"))]))
+        }
+
+        fn read_all_stream(&self, _after: Option<u64>) -> ReadStream<'_> {
+            Box::pin(futures::stream::iter([Ok(self.event("read_all_stream"))]))
+        }
+
+        async fn subscribe(&self, _stream: &str, _from_offset: Option<u64>) -> anyhow::Result<EventStream> {
+            Ok(Box::pin(futures::stream::iter([Ok(self.event("subscribe"))])))
+        }
+
+        async fn set_retention(&self, _stream: &str, _policy: &RetentionPolicy) -> anyhow::Result<()> {
+            self.event("set_retention");
+            Ok(())
+        }
+
+        async fn admin(&self, _op: AdminOp) -> anyhow::Result<()> {
+            self.event("admin");
+            Ok(())
+        }
+    }
+
+    async fn call_optional_operations(adapter: &dyn EventStoreAdapter) -> anyhow::Result<()> {
+        let req = ReadRequest {
+            stream: "s".to_string(),
+            from_offset: None,
+            limit: None,
+            consistency: Default::default(),
+        };
+        adapter.read_all(None, None).await?;
+        adapter.read_stream(req).next().await.unwrap()?;
+        adapter.read_all_stream(None).next().await.unwrap()?;
+        adapter.subscribe("s", None).await?.next().await.unwrap()?;
+        adapter.set_retention("s", &RetentionPolicy::default()).await?;
+        adapter.admin(AdminOp::Scavenge).await
+    }
+
+    #[tokio::test]
+    async fn wrappers_forward_every_operation() {
+        let path = std::env::temp_dir().join(format!("ledger-{}.jsonl", uuid::Uuid::new_v4()));
+        let ledger = Ledger::create(&LedgerOptions {
+            path: path.clone(),
+            sample_rate: 1.0,
+        })
+        .unwrap();
+        let inner = Arc::new(Complete::default());
+        let wrapped: [Box<dyn EventStoreAdapter>; 3] = [
+            Box::new(Traced::new(inner.clone(), "complete", Span::none())),
+            Box::new(Ledgered::new(inner.clone(), ledger)),
+            Box::new(Instrumented::new(inner.clone(), PhaseRecorder::new().callback())),
+        ];
+        for adapter in &wrapped {
+            call_optional_operations(adapter.as_ref()).await.unwrap();
+            let calls = std::mem::take(&mut *inner.calls.lock().unwrap());
+            assert_eq!(calls, ["read_all", "read_stream", "read_all_stream", "subscribe", "set_retention", "admin"]);
+        }
+        std::fs::remove_file(&path).ok();
+    }
+}
@@ -0,0 +1,81 @@
+use serde::Serialize;
+
+/// Results of one image tag of a store for one workload variant
+#[derive(Debug, Clone, Serialize)]
+pub struct VersionRun {
+    pub workload: String,
+    pub tag: String,
+    pub throughput_eps: f64,
+    pub latency_p50_ms: f64,
+    pub latency_p99_ms: f64,
+}
+
+/// A tag's results relative to the baseline tag of the same workload, in percent (positive
+/// means higher: better for throughput, worse for latency)
+#[derive(Debug, Clone, Serialize)]
+pub struct VersionDelta {
+    pub workload: String,
+    pub baseline_tag: String,
+    pub tag: String,
+    pub throughput_change_percent: f64,
+    pub latency_p50_change_percent: f64,
+    pub latency_p99_change_percent: f64,
+}
+
+fn change_percent(baseline: f64, value: f64) -> f64 {
+    if baseline == 0.0 {
+        return 0.0;
+    }
+    (value - baseline) / baseline * 100.0
+}
+
+/// Compare every tag with the first tag run for the same workload, in run order
+pub fn version_deltas(runs: &[VersionRun]) -> Vec<VersionDelta> {
+    let mut deltas = Vec::new();
+    for run in runs {
+        let Some(baseline) = runs.iter().find(|r| r.workload == run.workload) else {
+            continue;
+        };
+        if std::ptr::eq(baseline, run) {
+            continue;
+        }
+        deltas.push(VersionDelta {
+            workload: run.workload.clone(),
+            baseline_tag: baseline.tag.clone(),
+            tag: run.tag.clone(),
+            throughput_change_percent: change_percent(baseline.throughput_eps, run.throughput_eps),
+            latency_p50_change_percent: change_percent(baseline.latency_p50_ms, run.latency_p50_ms),
+            latency_p99_change_percent: change_percent(baseline.latency_p99_ms, run.latency_p99_ms),
+        });
+    }
+    deltas
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compares_each_tag_with_the_first_of_its_workload() {
+        let run = |workload: &str, tag: &str, eps: f64, p99: f64| VersionRun {
+            workload: workload.to_string(),
+            tag: tag.to_string(),
+            throughput_eps: eps,
+            latency_p50_ms: 1.0,
+            latency_p99_ms: p99,
+        };
+        let runs = [
+            run("w4", "24.10", 1000.0, 10.0),
+            run("w8", "24.10", 2000.0, 20.0),
+            run("w4", "25.1", 1100.0, 8.0),
+            run("w8", "25.1", 1500.0, 30.0),
+        ];
+        let deltas = version_deltas(&runs);
+        assert_eq!(deltas.len(), 2);
+        assert_eq!((deltas[0].workload.as_str(), deltas[0].baseline_tag.as_str()), ("w4", "24.10"));
+        assert!((deltas[0].throughput_change_percent - 10.0).abs() < 1e-9);
+        assert!((deltas[0].latency_p99_change_percent + 20.0).abs() < 1e-9);
+        assert!((deltas[1].throughput_change_percent + 25.0).abs() < 1e-9);
+        assert_eq!(deltas[1].latency_p50_change_percent, 0.0);
+    }
+}
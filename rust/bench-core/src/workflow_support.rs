@@ -0,0 +1,88 @@
+use crate::adapter::{EventData, EventStoreAdapter, EventStream, ReadEvent, StoreManager};
+use crate::propagation::sequence_of;
+use crate::runner::start_store;
+use anyhow::Result;
+use bytes::Bytes;
+use futures::StreamExt;
+use std::ops::Range;
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+
+/// Events per append while prepopulating a workflow's streams
+pub(crate) const PREPOPULATE_BATCH: u64 = 500;
+/// Longest wait for the next event of a stream being followed before it counts as stalled
+pub(crate) const STALL_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Start `store`, run `workflow` on it until it finishes or the run is cancelled, then stop
+/// the store; `what` names the workflow in the interruption message
+pub(crate) async fn run_on_started_store<T>(
+    mut store: Box<dyn StoreManager>,
+    cancel_token: &CancellationToken,
+    what: &str,
+    workflow: impl AsyncFnOnce(&dyn StoreManager) -> Result<T>,
+) -> Result<T> {
+    start_store(store.as_mut(), cancel_token).await?;
+    let result = tokio::select! {
+        res = workflow(store.as_ref()) => res,
+        _ = cancel_token.cancelled() => {
+            println!("Interrupted during {}.", what);
+            Err(anyhow::anyhow!("Interrupted"))
+        }
+    };
+    store.stop().await.ok();
+    result
+}
+
+/// Sequence numbers of the appends that write `total` events in batches of `PREPOPULATE_BATCH`
+pub(crate) fn prepopulate_batches(total: u64) -> impl Iterator<Item = Range<u64>> {
+    (0..total).step_by(PREPOPULATE_BATCH as usize).map(move |start| start..total.min(start + PREPOPULATE_BATCH))
+}
+
+/// Append `total` events to a stream in batches of `PREPOPULATE_BATCH`, `event(seq)` being the
+/// event with sequence number `seq`
+pub(crate) async fn prepopulate_stream(
+    adapter: &dyn EventStoreAdapter,
+    total: u64,
+    mut event: impl FnMut(u64) -> EventData,
+) -> Result<()> {
+    for batch in prepopulate_batches(total) {
+        adapter.append(batch.map(&mut event).collect()).await?;
+    }
+    Ok(())
+}
+
+/// Event of type `event_type` on `stream` carrying `payload`
+pub(crate) fn stream_event(stream: &str, event_type: &str, payload: &Bytes) -> EventData {
+    EventData {
+        payload: payload.clone(),
+        event_type: event_type.to_string(),
+        tags: vec![stream.to_string()],
+    }
+}
+
+/// Next event of a subscription, failing if it ends or stalls for `STALL_TIMEOUT`
+pub(crate) async fn next_event(subscription: &mut EventStream) -> Result<ReadEvent> {
+    match tokio::time::timeout(STALL_TIMEOUT, subscription.next()).await {
+        Ok(Some(event)) => event,
+        Ok(None) => anyhow::bail!("Subscription ended early"),
+        Err(_) => anyhow::bail!("Subscription stalled: no event for {} s", STALL_TIMEOUT.as_secs()),
+    }
+}
+
+/// Sequence number of a subscription's next event (see `sequenced_event`)
+pub(crate) async fn next_sequence(subscription: &mut EventStream) -> Result<u64> {
+    let event = next_event(subscription).await?;
+    sequence_of(&event.payload).ok_or_else(|| anyhow::anyhow!("Consumer delivered an event without a sequence number"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_prepopulation_into_batches() {
+        let batches: Vec<_> = prepopulate_batches(1200).collect();
+        assert_eq!(batches, [0..500, 500..1000, 1000..1200]);
+        assert_eq!(prepopulate_batches(0).count(), 0);
+    }
+}
@@ -1,7 +1,11 @@
 use anyhow::Result;
 use serde_yaml::Value;
+use std::collections::{BTreeMap, HashMap};
 
-use super::performance::{PerformanceWorkload, PerformanceConfig};
+use super::performance::{ConcurrencyValue, PerformanceConfig, PerformanceMode, PerformanceWorkload};
+use crate::option_sweep::OptionSweep;
+use crate::rate_limit::{RateLimitConfig, RateLimitScope};
+use crate::ratio_sweep::ReadWriteMix;
 use super::durability::DurabilityWorkload;
 use super::consistency::ConsistencyWorkload;
 use super::operational::OperationalWorkload;
@@ -17,7 +21,7 @@ pub enum WorkloadType {
 
 /// Represents a workload that can be executed
 pub enum Workload {
-    Performance(PerformanceWorkload),
+    Performance(Box<PerformanceWorkload>),
     Durability(DurabilityWorkload),
     Consistency(ConsistencyWorkload),
     Operational(OperationalWorkload),
@@ -40,7 +44,7 @@ impl WorkloadFactory {
         match workload_type {
             "performance" => {
                 let workload = PerformanceWorkload::from_yaml(yaml_config, seed)?;
-                Ok(Workload::Performance(workload))
+                Ok(Workload::Performance(Box::new(workload)))
             }
             "durability" => {
                 let workload = DurabilityWorkload::from_yaml(yaml_config)?;
@@ -90,6 +94,57 @@ impl WorkloadFactory {
         }
     }
 
+    /// Extract per-store options from YAML config, keyed by store name
+    ///
+    /// ```yaml
+    /// store_options:
+    ///   eventsourcingdb:
+    ///     api_tokens: token-a,token-b
+    /// ```
+    pub fn extract_store_options(yaml_config: &str) -> Result<HashMap<String, HashMap<String, String>>> {
+        let value: Value = serde_yaml::from_str(yaml_config)?;
+        let mut store_options = HashMap::new();
+        for (store, options) in store_maps(&value, "store_options")? {
+            let mut parsed = HashMap::new();
+            for (key, value) in options {
+                parsed.insert(key, option_value(&value, "store_options")?);
+            }
+            store_options.insert(store, parsed);
+        }
+        Ok(store_options)
+    }
+
+    /// Extract per-store option values to sweep from YAML config; every combination is run
+    /// and the best one reported per store
+    ///
+    /// ```yaml
+    /// option_sweep:
+    ///   umadb:
+    ///     batch_size: [10, 100, 1000]
+    /// ```
+    pub fn extract_option_sweep(yaml_config: &str) -> Result<OptionSweep> {
+        let value: Value = serde_yaml::from_str(yaml_config)?;
+        let mut sweep = OptionSweep::new();
+        for (store, options) in store_maps(&value, "option_sweep")? {
+            let mut parsed = BTreeMap::new();
+            for (key, values) in options {
+                let values = match &values {
+                    Value::Sequence(seq) => seq
+                        .iter()
+                        .map(|v| option_value(v, "option_sweep"))
+                        .collect::<Result<Vec<_>>>()?,
+                    single => vec![option_value(single, "option_sweep")?],
+                };
+                if values.is_empty() {
+                    anyhow::bail!("option_sweep.{}.{} lists no values", store, key);
+                }
+                parsed.insert(key, values);
+            }
+            sweep.insert(store, parsed);
+        }
+        Ok(sweep)
+    }
+
     /// Detect if config represents a sweep (only supports performance workloads)
     pub fn is_sweep(yaml_config: &str) -> Result<bool> {
         let value: Value = serde_yaml::from_str(yaml_config)?;
@@ -127,9 +182,99 @@ impl WorkloadFactory {
         for expanded_config in expanded_configs {
             let yaml = serde_yaml::to_string(&expanded_config)?;
             let workload = PerformanceWorkload::from_yaml(&yaml, seed)?;
-            workloads.push(Workload::Performance(workload));
+            workloads.push(Workload::Performance(Box::new(workload)));
         }
 
         Ok(workloads)
     }
+
+    /// Create a variant of a (non-sweep) mixed workload in which every worker reads or appends
+    /// in the proportions of `mix`, keeping the total worker count, with the mix appended to its
+    /// name
+    pub fn create_mix_variant(yaml_config: &str, seed: u64, mix: ReadWriteMix) -> Result<Workload> {
+        let mut config: PerformanceConfig = serde_yaml::from_str(yaml_config)?;
+        if config.is_sweep() {
+            return Err(anyhow::anyhow!("Ratio sweeps require a non-sweep config"));
+        }
+        if !matches!(config.mode, PerformanceMode::Mixed) {
+            return Err(anyhow::anyhow!("Ratio sweeps require a mixed mode config"));
+        }
+        let (Some(write), Some(_)) = (config.operations.write.as_mut(), &config.operations.read) else {
+            return Err(anyhow::anyhow!("Ratio sweeps require both 'write' and 'read' operation config"));
+        };
+        write.probability = Some(mix.write_probability());
+        let workers = config.concurrency.writers.first() + config.concurrency.readers.first();
+        config.concurrency.writers = ConcurrencyValue::Single(workers);
+        config.concurrency.readers = ConcurrencyValue::Single(0);
+        config.name = format!("{}-{}", config.name, mix.label());
+
+        let yaml = serde_yaml::to_string(&config)?;
+        let workload = PerformanceWorkload::from_yaml(&yaml, seed)?;
+        Ok(Workload::Performance(Box::new(workload)))
+    }
+
+    /// Create a variant of a (non-sweep) performance workload paced at a fixed total operation
+    /// rate, with `name_suffix` appended to its name
+    pub fn create_rate_limited(
+        yaml_config: &str,
+        seed: u64,
+        ops_per_second: f64,
+        name_suffix: &str,
+    ) -> Result<Workload> {
+        let mut config: PerformanceConfig = serde_yaml::from_str(yaml_config)?;
+        if config.is_sweep() {
+            return Err(anyhow::anyhow!("Rate-limited variants require a non-sweep config"));
+        }
+        config.name = format!("{}-{}", config.name, name_suffix);
+        config.rate_limit = Some(RateLimitConfig {
+            ops_per_second,
+            burst: 1,
+            scope: RateLimitScope::Global,
+        });
+
+        let yaml = serde_yaml::to_string(&config)?;
+        let workload = PerformanceWorkload::from_yaml(&yaml, seed)?;
+        Ok(Workload::Performance(Box::new(workload)))
+    }
+}
+
+/// Option names and their YAML values for one store
+type RawStoreOptions = Vec<(String, Value)>;
+
+/// `section` as store name -> (option name -> YAML value)
+fn store_maps(config: &Value, section: &str) -> Result<Vec<(String, RawStoreOptions)>> {
+    let Some(stores) = config.get(section) else {
+        return Ok(Vec::new());
+    };
+    let stores = stores
+        .as_mapping()
+        .ok_or_else(|| anyhow::anyhow!("'{}' must map store names to options", section))?;
+    let mut out = Vec::new();
+    for (store, options) in stores {
+        let store = store
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("'{}' keys must be store names", section))?;
+        let options = options
+            .as_mapping()
+            .ok_or_else(|| anyhow::anyhow!("{}.{} must be a map of options", section, store))?;
+        let mut entries = Vec::new();
+        for (key, value) in options {
+            let key = key
+                .as_str()
+                .ok_or_else(|| anyhow::anyhow!("{}.{} option names must be strings", section, store))?;
+            entries.push((key.to_string(), value.clone()));
+        }
+        out.push((store.to_string(), entries));
+    }
+    Ok(out)
+}
+
+/// Store options are passed as strings; scalars are accepted as written
+fn option_value(value: &Value, section: &str) -> Result<String> {
+    match value {
+        Value::String(s) => Ok(s.clone()),
+        Value::Number(n) => Ok(n.to_string()),
+        Value::Bool(b) => Ok(b.to_string()),
+        other => anyhow::bail!("{} values must be scalars, got {:?}", section, other),
+    }
 }
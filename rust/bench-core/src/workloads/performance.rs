@@ -1,16 +1,60 @@
-use crate::adapter::{EventData, ReadRequest, StoreManager};
+use crate::adapter::{EventData, EventStoreAdapter, ReadConsistency, ReadRequest, StoreManager};
+use crate::assertions::AssertConfig;
+use crate::checkpoint::{offer_checkpoint, CheckpointHandle, CheckpointOptions, Checkpoints};
+use crate::budget::{BudgetConfig, RunBudget};
+use crate::bulk::{inject_bulk, BulkConfig, BulkWindow};
 use crate::common::{SetupConfig};
-use crate::metrics::{LatencyRecorder, ThroughputSample};
+use crate::connection::{create_connected_adapters, ConnectTiming};
+use crate::container_stats::DEFAULT_STATS_INTERVAL_MS;
+use crate::coordination::{StartBarrier, StartSchedule};
+use crate::determinism::{WorkerDigest, DIGEST_OPS};
+use crate::instrument::{Instrumented, PhaseRecorder};
+use crate::traced::{spans_enabled, Traced, SPAN_TARGET};
+use crate::isolation::ResourceLimits;
+use crate::ledger::{Ledger, Ledgered};
+use crate::live::LiveStats;
+use crate::metrics::{
+    ContentionStats, FanOutStats, LatencyRecorder, OpSamples, SampleLog, SampleStreams,
+    ThroughputSample, SAMPLE_RATE,
+};
+use crate::payload::{PayloadConfig, PayloadCorpus, PayloadCorpusInfo, PayloadKind};
+use crate::schema_registry::register_schema;
+use crate::rate_limit::{OpenLoopSchedule, Pacer, RateLimitConfig, RateLimiter};
+use crate::registry::MetricsRegistry;
+use crate::think_time::ThinkTime;
 use anyhow::Result;
+use bytes::{Bytes, BytesMut};
 use rand::{rngs::StdRng, Rng, SeedableRng};
 use serde::{Deserialize, Serialize};
-use std::sync::atomic::{AtomicU64, Ordering};
-use std::sync::Arc;
-use std::time::{Duration, Instant};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::Span;
+use tokio::time::Instant;
 use uuid::Uuid;
-use tokio::task::JoinSet;
+use tokio::task::{JoinHandle, JoinSet};
 use tokio_util::sync::CancellationToken;
 
+/// Shortest accepted container stats interval (each sample is a Docker API round trip)
+const MIN_STATS_INTERVAL_MS: u64 = 50;
+/// Most events per append while prepopulating a stream
+const SETUP_BATCH: usize = 500;
+/// Keeps the payload RNG stream independent of the worker RNGs derived from the same seed
+const PAYLOAD_SEED_SALT: u64 = 0x7061_796c_6f61_6473;
+
+mod contention;
+mod event_types;
+mod fan_out;
+mod lint;
+mod list_streams;
+mod plan;
+mod stream_names;
+
+use event_types::EventTypes;
+use plan::WrittenStreams;
+pub use event_types::{EventTypeDistribution, EventTypesConfig};
+pub use stream_names::{StreamNameScheme, StreamNames, StreamNamingConfig};
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PerformanceConfig {
     pub name: String,
@@ -20,6 +64,67 @@ pub struct PerformanceConfig {
     pub operations: OperationConfig,
     #[serde(default)]
     pub setup: Option<SetupConfig>,
+    /// Optional token-bucket pacing applied to worker operations
+    #[serde(default)]
+    pub rate_limit: Option<RateLimitConfig>,
+    /// Open-loop load: total events per second across the writers (operations across the
+    /// workers in mixed mode), issued as appends of `write.batch_size` events at fixed
+    /// intervals per writer with latency measured from when each was due rather than when the
+    /// previous one completed
+    #[serde(default)]
+    pub target_rate_eps: Option<f64>,
+    /// Optional pause between each worker's operations (fixed milliseconds or a distribution)
+    #[serde(default)]
+    pub think_time_ms: Option<ThinkTime>,
+    /// Hot-stream settings for contention mode
+    #[serde(default)]
+    pub contention: Option<ContentionConfig>,
+    /// Reader behaviour for fan-out mode
+    #[serde(default)]
+    pub fan_out: Option<FanOutConfig>,
+    /// Listing settings for list_streams mode
+    #[serde(default)]
+    pub list_streams: Option<ListStreamsConfig>,
+    /// Also record wall-clock epoch milliseconds (`t_ms`) in samples.jsonl
+    #[serde(default)]
+    pub sample_epoch_ms: bool,
+    /// Record the target stream of each sample in samples.jsonl
+    #[serde(default)]
+    pub sample_stream: Option<SampleStreamConfig>,
+    /// Stamp the worker index and op sequence number into each appended payload (first
+    /// 12 bytes: u32 worker, u64 seq, little-endian), matching `worker`/`seq` in samples.jsonl
+    #[serde(default)]
+    pub payload_op_seq: bool,
+    /// Interval between container CPU/memory samples (defaults to 1000 ms)
+    #[serde(default)]
+    pub stats_interval_ms: Option<u64>,
+    /// Window of the summary's throughput series (defaults to 1000 ms)
+    #[serde(default)]
+    pub series_bucket_ms: Option<u64>,
+    /// Time adapter sub-phases (serialization, network, deserialization) and report them as
+    /// `<op>_<phase>_latency` histograms in the summary's extra metrics
+    #[serde(default)]
+    pub instrument_phases: bool,
+    /// Wall-clock start of the measurement window (`start_at`, `align_to_minute`)
+    #[serde(flatten)]
+    pub schedule: StartSchedule,
+    /// Shape of stream names (`scheme`, `prefix`, `template`, `tenants`, `length`)
+    #[serde(default)]
+    pub stream_naming: StreamNamingConfig,
+    /// Safety limits ending the run early (`max_events`, `max_disk_gb`, `max_wall_clock`)
+    #[serde(flatten)]
+    pub budget: BudgetConfig,
+    /// Heavy operation (`catch_up_read` or `batch_append`) run every `every_seconds` during
+    /// the measurement window
+    #[serde(default)]
+    pub bulk: Option<BulkConfig>,
+    /// CPU and memory limits for the store's containers (`cpus`, `memory`)
+    #[serde(default)]
+    pub resources: ResourceLimits,
+    /// Bounds each run must stay within (`min_throughput_eps`, `max_p99_ms`, `max_error_rate`,
+    /// ...); violations fail `run` after the session
+    #[serde(default, rename = "assert")]
+    pub assertions: Option<AssertConfig>,
 }
 
 impl PerformanceConfig {
@@ -27,6 +132,12 @@ impl PerformanceConfig {
     pub fn is_sweep(&self) -> bool {
         matches!(self.concurrency.writers, ConcurrencyValue::Multiple(_))
             || matches!(self.concurrency.readers, ConcurrencyValue::Multiple(_))
+            || self.write_batch_sizes().len() > 1
+    }
+
+    /// Events per append, several when `write.batch_size` lists a sweep
+    fn write_batch_sizes(&self) -> Vec<usize> {
+        self.operations.write.as_ref().map_or(vec![1], |write| write.batch_size.as_vec())
     }
 
     /// Expand a sweep config into multiple single-value configs
@@ -34,15 +145,25 @@ impl PerformanceConfig {
         let writers_vec = self.concurrency.writers.as_vec();
         let readers_vec = self.concurrency.readers.as_vec();
 
+        let batch_sizes = self.write_batch_sizes();
+
         let mut configs = Vec::new();
         for &writers in &writers_vec {
             for &readers in &readers_vec {
-                let mut new_config = self.clone();
-                new_config.concurrency.writers = ConcurrencyValue::Single(writers);
-                new_config.concurrency.readers = ConcurrencyValue::Single(readers);
-                // Add sweep suffix to name
-                new_config.name = format!("{}-w{}-r{}", self.name, writers, readers);
-                configs.push(new_config);
+                for &batch_size in &batch_sizes {
+                    let mut new_config = self.clone();
+                    new_config.concurrency.writers = ConcurrencyValue::Single(writers);
+                    new_config.concurrency.readers = ConcurrencyValue::Single(readers);
+                    // Add sweep suffix to name
+                    new_config.name = format!("{}-w{}-r{}", self.name, writers, readers);
+                    if let Some(write) = &mut new_config.operations.write {
+                        write.batch_size = ConcurrencyValue::Single(batch_size);
+                    }
+                    if batch_sizes.len() > 1 {
+                        new_config.name = format!("{}-b{}", new_config.name, batch_size);
+                    }
+                    configs.push(new_config);
+                }
             }
         }
         configs
@@ -55,6 +176,14 @@ pub enum PerformanceMode {
     Write,
     Read,
     Mixed,
+    /// All writers append to a small set of hot streams
+    Contention,
+    /// One writer appends to a single stream while many readers read it
+    #[serde(rename = "fan_out")]
+    FanOut,
+    /// Readers repeatedly enumerate the prepopulated streams
+    #[serde(rename = "list_streams")]
+    ListStreams,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -105,8 +234,15 @@ pub struct OperationConfig {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WriteOpConfig {
     pub event_size_bytes: usize,
+    /// Payload content (defaults to zeros)
+    #[serde(default)]
+    pub payload: PayloadConfig,
+    /// Distinct event types and how often each is appended (defaults to a single type)
+    #[serde(default)]
+    pub event_types: Option<EventTypesConfig>,
+    /// Events per append, all to the same stream (a list sweeps batch sizes)
     #[serde(default = "default_batch_size")]
-    pub batch_size: usize,
+    pub batch_size: ConcurrencyValue,
     #[serde(default)]
     pub probability: Option<f64>, // For mixed mode
 }
@@ -117,21 +253,135 @@ pub struct ReadOpConfig {
     pub batch_size: usize,
     #[serde(default)]
     pub probability: Option<f64>, // For mixed mode
+    /// Mixed mode: fraction of reads (0 to 1) aimed at a stream already appended to during
+    /// the run, so they return events; the other reads pick any stream
+    #[serde(default)]
+    pub non_empty_fraction: Option<f64>,
+    /// How up to date reads must be, mapped onto each store's read options
+    #[serde(default)]
+    pub consistency: ReadConsistency,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContentionConfig {
+    /// Number of hot streams shared by all writers
+    #[serde(default = "default_hot_streams")]
+    pub streams: usize,
+    /// Use optimistic concurrency (expected position) on every append
+    #[serde(default = "default_conditional")]
+    pub conditional: bool,
+}
+
+impl Default for ContentionConfig {
+    fn default() -> Self {
+        Self {
+            streams: default_hot_streams(),
+            conditional: default_conditional(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FanOutConfig {
+    /// Readers follow the stream from their last read position; when false every read starts
+    /// from the beginning of the stream
+    #[serde(default = "default_tail")]
+    pub tail: bool,
+}
+
+impl Default for FanOutConfig {
+    fn default() -> Self {
+        Self { tail: default_tail() }
+    }
 }
 
-fn default_batch_size() -> usize {
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SampleStreamConfig {
+    /// Record a hashed bucket (0..buckets) instead of the raw stream index
+    #[serde(default)]
+    pub buckets: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ListStreamsConfig {
+    /// Maximum number of stream names per listing call (unbounded when not set)
+    #[serde(default)]
+    pub limit: Option<u64>,
+}
+
+fn default_tail() -> bool {
+    true
+}
+
+fn default_hot_streams() -> usize {
     1
 }
 
+fn default_conditional() -> bool {
+    true
+}
+
+fn default_batch_size() -> ConcurrencyValue {
+    ConcurrencyValue::Single(1)
+}
+
 fn default_read_batch() -> usize {
     100
 }
 
+/// Measurements collected while executing a performance workload
+pub struct PerformanceOutput {
+    pub latency: LatencyRecorder,
+    pub events_written: u64,
+    pub events_read: u64,
+    pub throughput_samples: Vec<ThroughputSample>,
+    /// How long after the start barrier released each worker began issuing operations
+    pub worker_start_offsets: Vec<Duration>,
+    /// Conflict statistics (contention mode only)
+    pub contention: Option<ContentionStats>,
+    /// Separate writer/reader statistics (fan-out mode only)
+    pub fan_out: Option<FanOutStats>,
+    /// Per-worker digest of the first operations' random choices
+    pub op_digests: Vec<WorkerDigest>,
+    /// Sampled operations from all workers, timed relative to the measurement start, and
+    /// per-operation-type latency histograms
+    pub samples: OpSamples,
+    /// Mode-specific named metrics (reported as `Summary::extra`)
+    pub extra: MetricsRegistry,
+    /// Bulk operations run during the measurement window, if `bulk` is configured
+    pub bulk_windows: Vec<BulkWindow>,
+    /// Connection setup of each adapter, before the measurement
+    pub connections: Vec<ConnectTiming>,
+}
+
 /// Performance workload - generic event store read/write patterns
 pub struct PerformanceWorkload {
     config: PerformanceConfig,
     seed: u64,
-    stream_prefix: String,
+    stream_names: StreamNames,
+    /// Append payloads, generated up front from the seed (and framed once the schema is
+    /// registered, if a schema registry is configured)
+    payloads: Mutex<Arc<PayloadCorpus>>,
+    /// Event types appended, if `event_types` is configured
+    event_types: Option<Arc<EventTypes>>,
+    /// Phase recorders of the adapters created for the current run (`instrument_phases`)
+    phase_recorders: Mutex<Vec<PhaseRecorder>>,
+    /// Checkpoint collection of the current run, if checkpoints are written
+    checkpoints: Mutex<Option<Arc<Checkpoints>>>,
+    /// Ledger of acknowledged appends of the current run, if one is kept
+    ledger: Mutex<Option<Arc<Ledger>>>,
+    /// Budget of the current run, if limits are configured
+    budget: Mutex<Option<Arc<RunBudget>>>,
+    /// Span of the current run, parent of its operations' spans, if a subscriber collects them
+    run_span: Mutex<Option<Span>>,
+    /// Live view the workers of the next runs count their operations in, if one is shown
+    live: Mutex<Option<Arc<LiveStats>>>,
+    /// Stop token and task of the current run's bulk operations, if `bulk` is configured
+    bulk: Mutex<Option<(CancellationToken, JoinHandle<Vec<BulkWindow>>)>>,
+    /// Connection setup of the adapters created for the current run
+    connections: Mutex<Vec<ConnectTiming>>,
+    /// Start of the current run's measurement window
+    measurement_started: Mutex<Option<Instant>>,
 }
 
 impl PerformanceWorkload {
@@ -173,11 +423,180 @@ impl PerformanceWorkload {
                         "Mixed mode requires at least one of 'write' or 'read' operation config"
                     ));
                 }
+                if let Some(fraction) = config.operations.read.as_ref().and_then(|r| r.non_empty_fraction) {
+                    if !(0.0..=1.0).contains(&fraction) {
+                        return Err(anyhow::anyhow!(
+                            "read.non_empty_fraction must be between 0 and 1, got {}",
+                            fraction
+                        ));
+                    }
+                }
+            }
+            PerformanceMode::Contention => {
+                if config.concurrency.writers.first() == 0 {
+                    return Err(anyhow::anyhow!(
+                        "Contention mode requires writers > 0 in concurrency config"
+                    ));
+                }
+                if config.operations.write.is_none() {
+                    return Err(anyhow::anyhow!(
+                        "Contention mode requires 'write' operation config"
+                    ));
+                }
+                if config.contention.as_ref().is_some_and(|c| c.streams == 0) {
+                    return Err(anyhow::anyhow!("Contention mode requires streams > 0"));
+                }
+            }
+            PerformanceMode::FanOut => {
+                if config.concurrency.writers.first() == 0 || config.concurrency.readers.first() == 0 {
+                    return Err(anyhow::anyhow!(
+                        "Fan-out mode requires writers > 0 and readers > 0"
+                    ));
+                }
+                if config.operations.write.is_none() || config.operations.read.is_none() {
+                    return Err(anyhow::anyhow!(
+                        "Fan-out mode requires both 'write' and 'read' operation config"
+                    ));
+                }
+            }
+            PerformanceMode::ListStreams => {
+                if config.concurrency.readers.first() == 0 {
+                    return Err(anyhow::anyhow!(
+                        "List streams mode requires readers > 0 in concurrency config"
+                    ));
+                }
+                if config.setup.is_none() {
+                    return Err(anyhow::anyhow!(
+                        "List streams mode requires a 'setup' config to create the streams"
+                    ));
+                }
             }
         }
 
-        let stream_prefix = format!("stream-{}-", Uuid::new_v4());
-        Ok(Self { config, seed, stream_prefix })
+        if let Some(setup) = &config.setup {
+            if setup.prepopulate_events == 0 || setup.prepopulate_streams == Some(0) {
+                return Err(anyhow::anyhow!("setup prepopulate_events and prepopulate_streams must be > 0"));
+            }
+            if setup.prepopulate_streams.is_some_and(|streams| streams > setup.prepopulate_events) {
+                return Err(anyhow::anyhow!("setup prepopulate_streams must not exceed prepopulate_events"));
+            }
+            // The prepopulated events take their size and payloads from the write config
+            if config.operations.write.is_none() {
+                return Err(anyhow::anyhow!("Setup requires write operation config for prepopulation"));
+            }
+        }
+        if let Some(think_time) = &config.think_time_ms {
+            think_time.validate()?;
+        }
+        if let Some(rate) = config.target_rate_eps {
+            if !(rate.is_finite() && rate > 0.0) {
+                return Err(anyhow::anyhow!("target_rate_eps must be > 0, got {}", rate));
+            }
+            if matches!(config.mode, PerformanceMode::Read | PerformanceMode::ListStreams) {
+                return Err(anyhow::anyhow!("target_rate_eps needs a mode with writers"));
+            }
+            if config.rate_limit.is_some() || config.think_time_ms.is_some() {
+                return Err(anyhow::anyhow!(
+                    "target_rate_eps cannot be combined with rate_limit or think_time_ms"
+                ));
+            }
+        }
+        if config
+            .sample_stream
+            .as_ref()
+            .is_some_and(|s| s.buckets == Some(0))
+        {
+            return Err(anyhow::anyhow!("sample_stream buckets must be > 0"));
+        }
+        if config.series_bucket_ms == Some(0) {
+            return Err(anyhow::anyhow!("series_bucket_ms must be > 0"));
+        }
+        if config.stats_interval_ms.is_some_and(|ms| ms < MIN_STATS_INTERVAL_MS) {
+            return Err(anyhow::anyhow!(
+                "stats_interval_ms must be at least {} ms",
+                MIN_STATS_INTERVAL_MS
+            ));
+        }
+
+        if config.write_batch_sizes().contains(&0) {
+            return Err(anyhow::anyhow!("write batch_size must be > 0"));
+        }
+        config.budget.validate()?;
+        config.resources.validate()?;
+        if let Some(assertions) = &config.assertions {
+            assertions.validate()?;
+        }
+        if let Some(bulk) = &config.bulk {
+            bulk.validate(config.setup.as_ref())?;
+        }
+        config.stream_naming.validate()?;
+        let stream_names = StreamNames::new(config.stream_naming.clone(), Uuid::new_v4());
+        if let Some(write) = &config.operations.write {
+            if write.payload.schema_registry.is_some()
+                && !matches!(write.payload.kind, PayloadKind::Avro | PayloadKind::Protobuf)
+            {
+                return Err(anyhow::anyhow!("schema_registry needs payload kind avro or protobuf"));
+            }
+        }
+        let payloads = Mutex::new(Arc::new(match &config.operations.write {
+            Some(write) => PayloadCorpus::generate(
+                &write.payload,
+                write.event_size_bytes,
+                seed ^ PAYLOAD_SEED_SALT,
+            ),
+            None => PayloadCorpus::generate(&PayloadConfig::default(), 0, seed),
+        }));
+        let event_types = match config.operations.write.as_ref().and_then(|w| w.event_types.as_ref()) {
+            Some(types) => Some(Arc::new(EventTypes::new(types)?)),
+            None => None,
+        };
+        Ok(Self {
+            config,
+            seed,
+            stream_names,
+            payloads,
+            event_types,
+            phase_recorders: Mutex::new(Vec::new()),
+            connections: Mutex::new(Vec::new()),
+            measurement_started: Mutex::new(None),
+            checkpoints: Mutex::new(None),
+            live: Mutex::new(None),
+            ledger: Mutex::new(None),
+            budget: Mutex::new(None),
+            run_span: Mutex::new(None),
+            bulk: Mutex::new(None),
+        })
+    }
+
+    /// Payloads appended by this workload, for the run metadata (`None` without writes)
+    pub fn payload_corpus(&self) -> Option<PayloadCorpusInfo> {
+        self.config.operations.write.as_ref().map(|_| self.payloads().info().clone())
+    }
+
+    fn payloads(&self) -> Arc<PayloadCorpus> {
+        self.payloads.lock().map(|p| p.clone()).unwrap_or_else(|e| e.into_inner().clone())
+    }
+
+    /// Register the payload schema if a schema registry is configured and frame the payloads
+    /// with its id. Done once, before the first append.
+    async fn register_schema(&self) -> Result<()> {
+        let Some(write) = &self.config.operations.write else {
+            return Ok(());
+        };
+        let Some(registry) = &write.payload.schema_registry else {
+            return Ok(());
+        };
+        let payloads = self.payloads();
+        if payloads.info().schema_id.is_some() {
+            return Ok(());
+        }
+        let schema_id = register_schema(registry, write.payload.kind).await?;
+        println!("Registered the {:?} payload schema as id {}", write.payload.kind, schema_id);
+        let framed = Arc::new(payloads.framed(schema_id));
+        if let Ok(mut current) = self.payloads.lock() {
+            *current = framed;
+        }
+        Ok(())
     }
 
     pub fn name(&self) -> &str {
@@ -196,8 +615,146 @@ impl PerformanceWorkload {
         self.config.duration_seconds
     }
 
+    pub fn budget(&self) -> &BudgetConfig {
+        &self.config.budget
+    }
+
+    pub fn assertions(&self) -> Option<&AssertConfig> {
+        self.config.assertions.as_ref()
+    }
+
+    /// Events per append
+    pub fn write_batch_size(&self) -> usize {
+        self.config.operations.write.as_ref().map_or(1, |write| write.batch_size.first())
+    }
+
+    pub fn resources(&self) -> &ResourceLimits {
+        &self.config.resources
+    }
+
+    pub fn bulk(&self) -> Option<&BulkConfig> {
+        self.config.bulk.as_ref()
+    }
+
+    /// Start of the last run's measurement window, which sampled operations are timed from
+    pub fn measurement_start(&self) -> Option<Instant> {
+        *self.measurement_started.lock().ok()?
+    }
+
+    /// Interval between container stats samples
+    pub fn stats_interval(&self) -> Duration {
+        Duration::from_millis(
+            self.config
+                .stats_interval_ms
+                .unwrap_or(DEFAULT_STATS_INTERVAL_MS),
+        )
+    }
+
+    /// Window of the summary's throughput series
+    pub fn series_bucket(&self) -> Duration {
+        Duration::from_millis(self.config.series_bucket_ms.unwrap_or(1000))
+    }
+
+    /// Read consistency asked for, unless the reads use the store's default
+    pub fn read_consistency(&self) -> Option<ReadConsistency> {
+        let consistency = self.config.operations.read.as_ref()?.consistency;
+        (consistency != ReadConsistency::Default).then_some(consistency)
+    }
+
+    /// Approximate events per operation, for converting measured events/sec into an operation
+    /// rate for the rate limiter (reads return up to a batch of events, appends write a batch)
+    pub fn events_per_operation(&self) -> f64 {
+        let read_batch = self
+            .config
+            .operations
+            .read
+            .as_ref()
+            .map_or(1.0, |r| r.batch_size as f64);
+        let write_batch = self.write_batch_size() as f64;
+        match self.config.mode {
+            PerformanceMode::Write => write_batch,
+            PerformanceMode::Read | PerformanceMode::FanOut => read_batch,
+            PerformanceMode::Mixed => {
+                let write_prob = self
+                    .config
+                    .operations
+                    .write
+                    .as_ref()
+                    .and_then(|w| w.probability)
+                    .unwrap_or(0.5);
+                write_prob * write_batch + (1.0 - write_prob) * read_batch
+            }
+            _ => 1.0,
+        }
+    }
+
+    fn sample_settings(&self, collect: bool) -> SampleSettings {
+        let streams = match &self.config.sample_stream {
+            None => SampleStreams::Off,
+            Some(SampleStreamConfig { buckets: None }) => SampleStreams::Index,
+            Some(SampleStreamConfig { buckets: Some(n) }) => SampleStreams::Buckets(*n),
+        };
+        SampleSettings {
+            collect,
+            epoch_ms: self.config.sample_epoch_ms,
+            streams,
+            payload_op_seq: self.config.payload_op_seq,
+            live: self.live.lock().ok().and_then(|live| live.clone()),
+        }
+    }
+
+    /// Count the operations of the following runs in `live` (or stop counting with `None`)
+    pub fn watch(&self, live: Option<Arc<LiveStats>>) {
+        if let Ok(mut current) = self.live.lock() {
+            *current = live;
+        }
+    }
+
+    /// Total configured operation rate, if the workload is throttled or open-loop
+    pub fn target_ops_per_second(&self) -> Option<f64> {
+        self.open_loop_ops_per_second().or_else(|| {
+            self.config
+                .rate_limit
+                .as_ref()
+                .map(|rate_limit| rate_limit.total_ops_per_second(self.worker_count()))
+        })
+    }
+
+    /// Total operation rate of `target_rate_eps`: in write mode each append carries a batch of
+    /// the target events, in the other modes the target counts operations
+    fn open_loop_ops_per_second(&self) -> Option<f64> {
+        let rate = self.config.target_rate_eps?;
+        match self.config.mode {
+            PerformanceMode::Write => Some(rate / self.write_batch_size() as f64),
+            _ => Some(rate),
+        }
+    }
+
+    /// Build one rate limiter per worker (all `None` when the workload is unthrottled)
+    fn rate_limiters(&self, workers: usize) -> Result<Vec<Option<Arc<RateLimiter>>>> {
+        match &self.config.rate_limit {
+            Some(rate_limit) => Ok(rate_limit.limiters(workers)?.into_iter().map(Some).collect()),
+            None => Ok(vec![None; workers]),
+        }
+    }
+
+    /// Build one pacer per worker: with `target_rate_eps` the first `writers` workers share it
+    /// in open-loop schedules, otherwise every worker uses its rate limiter
+    fn pacers(&self, workers: usize, writers: usize) -> Result<Vec<Pacer>> {
+        let limiters = self.rate_limiters(workers)?;
+        limiters
+            .into_iter()
+            .enumerate()
+            .map(|(i, limiter)| match self.open_loop_ops_per_second() {
+                Some(rate) if i < writers => Ok(Pacer::Open(OpenLoopSchedule::new(rate / writers as f64)?)),
+                _ => Ok(Pacer::Closed(limiter)),
+            })
+            .collect()
+    }
+
     /// Prepare the workload (e.g., prepopulate data for read workloads)
     pub async fn prepare(&self, store: &dyn StoreManager) -> Result<()> {
+        self.register_schema().await?;
         if let Some(setup_config) = &self.config.setup {
             let setup_start = Instant::now();
 
@@ -209,18 +766,12 @@ impl PerformanceWorkload {
                 "Running setup phase: prepopulating {} events in {} streams...",
                 total_events, num_streams
             );
-            let events_per_stream = (total_events as f64 / num_streams as f64).ceil() as u64;
 
             // Prepopulate events across streams concurrently
             let mut setup_set = JoinSet::new();
             let concurrency = 10;
             let streams_per_task = (num_streams as f64 / concurrency as f64).ceil() as usize;
 
-            let write_config = self.config.operations.write.as_ref().ok_or_else(|| {
-                anyhow::anyhow!("Setup requires write operation config for prepopulation")
-            })?;
-            let event_size = write_config.event_size_bytes;
-
             for task_idx in 0..concurrency {
                 let start_stream = task_idx * streams_per_task;
                 let end_stream = (start_stream + streams_per_task).min(num_streams as usize);
@@ -230,19 +781,26 @@ impl PerformanceWorkload {
 
                 let adapter = store.create_adapter()?;
 
-                let stream_prefix = self.stream_prefix.clone();
+                let stream_names = self.stream_names.clone();
+                let mut payloads = self.payloads().cursor(task_idx);
                 setup_set.spawn(async move {
                     for stream_idx in start_stream..end_stream {
-                        let stream_name = format!("{}{}", stream_prefix, stream_idx);
-                        let mut events = Vec::with_capacity(events_per_stream as usize);
-                        for _ in 0..events_per_stream {
-                            events.push(EventData {
-                                payload: vec![0u8; event_size],
-                                event_type: "setup".to_string(),
-                                tags: vec![stream_name.clone()],
-                            });
+                        let stream_name = stream_names.name(stream_idx as u64);
+                        // Exactly `total_events` in all: the first streams take the remainder
+                        let mut remaining = total_events / num_streams
+                            + u64::from((stream_idx as u64) < total_events % num_streams);
+                        while remaining > 0 {
+                            let batch = remaining.min(SETUP_BATCH as u64);
+                            let events = (0..batch)
+                                .map(|_| EventData {
+                                    payload: payloads.next_payload(),
+                                    event_type: "setup".to_string(),
+                                    tags: vec![stream_name.clone()],
+                                })
+                                .collect();
+                            adapter.append(events).await?;
+                            remaining -= batch;
                         }
-                        adapter.append(events).await?;
                     }
                     Ok::<(), anyhow::Error>(())
                 });
@@ -267,161 +825,244 @@ impl PerformanceWorkload {
         &self,
         store: &dyn StoreManager,
         cancel_token: CancellationToken,
-    ) -> Result<(LatencyRecorder, u64, u64, Vec<ThroughputSample>)> {
-        match self.config.mode {
+    ) -> Result<PerformanceOutput> {
+        self.execute_with_options(store, cancel_token, true, None, None, None).await
+    }
+
+    /// Execute the workload; with `collect_samples` off no per-operation samples are kept and
+    /// `PerformanceOutput::samples` is empty. With `checkpoint` set, intermediate snapshots
+    /// are written while the workload runs; with `ledger` set, acknowledged appends are
+    /// recorded in it. With `budget` set, the workers stop early once it is exceeded.
+    pub async fn execute_with_options(
+        &self,
+        store: &dyn StoreManager,
+        cancel_token: CancellationToken,
+        collect_samples: bool,
+        checkpoint: Option<&CheckpointOptions>,
+        ledger: Option<Arc<Ledger>>,
+        budget: Option<Arc<RunBudget>>,
+    ) -> Result<PerformanceOutput> {
+        self.register_schema().await?;
+        let sample_settings = self.sample_settings(collect_samples);
+        if let Ok(mut current) = self.ledger.lock() {
+            *current = ledger;
+        }
+        if let Ok(mut current) = self.budget.lock() {
+            *current = budget;
+        }
+        if let Ok(mut current) = self.run_span.lock() {
+            *current = spans_enabled().then(|| {
+                tracing::info_span!(
+                    target: SPAN_TARGET,
+                    "run",
+                    otel.name = %self.config.name,
+                    store = store.name(),
+                    mode = ?self.config.mode,
+                )
+            });
+        }
+        let checkpoint_writer = checkpoint.map(|options| {
+            let checkpoints = Checkpoints::new(self.worker_count());
+            if let Ok(mut current) = self.checkpoints.lock() {
+                *current = Some(checkpoints.clone());
+            }
+            checkpoints.spawn_writer(options.clone())
+        });
+        let result = match self.config.mode {
             PerformanceMode::Write => {
-                self.execute_write_workload(store, cancel_token)
+                self.execute_write_workload(store, cancel_token, sample_settings)
                     .await
             }
             PerformanceMode::Read => {
-                self.execute_read_workload(store, cancel_token)
+                self.execute_read_workload(store, cancel_token, sample_settings)
                     .await
             }
             PerformanceMode::Mixed => {
-                self.execute_mixed_workload(store, cancel_token)
+                self.execute_mixed_workload(store, cancel_token, sample_settings)
+                    .await
+            }
+            PerformanceMode::Contention => {
+                self.execute_contention_workload(store, cancel_token, sample_settings)
+                    .await
+            }
+            PerformanceMode::FanOut => {
+                self.execute_fan_out_workload(store, cancel_token, sample_settings)
                     .await
             }
+            PerformanceMode::ListStreams => {
+                self.execute_list_streams_workload(store, cancel_token, sample_settings)
+                    .await
+            }
+        };
+        if let Some(writer) = checkpoint_writer {
+            writer.abort();
+        }
+        if let Ok(mut current) = self.checkpoints.lock() {
+            *current = None;
+        }
+        if let Ok(mut current) = self.ledger.lock() {
+            *current = None;
+        }
+        if let Ok(mut current) = self.budget.lock() {
+            *current = None;
+        }
+        if let Ok(mut current) = self.run_span.lock() {
+            *current = None;
+        }
+        let bulk = self.bulk.lock().ok().and_then(|mut current| current.take());
+        let bulk_windows = match bulk {
+            Some((stop, task)) => {
+                stop.cancel();
+                task.await.unwrap_or_default()
+            }
+            None => Vec::new(),
+        };
+        let mut output = result?;
+        output.bulk_windows = bulk_windows;
+        if let Ok(mut connections) = self.connections.lock() {
+            output.connections = std::mem::take(&mut *connections);
         }
+        if let Ok(mut recorders) = self.phase_recorders.lock() {
+            for recorder in recorders.drain(..) {
+                output.extra.merge(&recorder.registry());
+            }
+        }
+        Ok(output)
     }
 
     async fn execute_write_workload(
         &self,
         store: &dyn StoreManager,
         cancel_token: CancellationToken,
-    ) -> Result<(LatencyRecorder, u64, u64, Vec<ThroughputSample>)> {
+        sample_settings: SampleSettings,
+    ) -> Result<PerformanceOutput> {
         let writers = self.config.concurrency.writers.first();
         println!("Creating {} writer clients...", writers);
 
-        let mut writer_adapters = Vec::new();
-        for i in 0..writers {
-            match store.create_adapter() {
-                Ok(adapter) => writer_adapters.push(adapter),
-                Err(e) => {
-                    eprintln!("Failed to create writer {}: {}", i, e);
-                    anyhow::bail!("Failed to create writer {}: {}", i, e);
-                }
-            }
-        }
-        println!("All {} writer clients ready", writers);
+        let writer_adapters = self.create_adapters(store, writers, "writer").await?;
 
         let mut set = JoinSet::new();
 
-        let write_config = self.config.operations.write.as_ref().unwrap();
-
         // Per-worker atomic counters to avoid contention
         let worker_counters: Vec<Arc<AtomicU64>> = (0..writers)
             .map(|_| Arc::new(AtomicU64::new(0)))
             .collect();
 
-        let has_stopped = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let has_stopped = Arc::new(AtomicBool::new(false));
+        let pacers = self.pacers(writers, writers)?;
+        let barrier = StartBarrier::scheduled(writers, self.config.schedule.clone(), &cancel_token);
+        let batch_size = self.write_batch_size();
         
         // Spawn writer tasks first
         for (i, adapter) in writer_adapters.into_iter().enumerate() {
-            let write_cfg = write_config.clone();
+            let mut payloads = self.payloads().cursor(i);
+            let mut pacer = pacers[i].clone();
+            let mut planner = self.op_planner(i);
+            let mut checkpoint = self.checkpoint_handle(i);
             let worker_counter = worker_counters[i].clone();
             let has_stopped = has_stopped.clone();
             let cancel_token = cancel_token.clone();
+            let barrier = barrier.clone();
+            let stream_names = self.stream_names.clone();
 
+            let sample_settings = sample_settings.clone();
             set.spawn(async move {
                 let mut local_count = 0u64;
-                let size = write_cfg.event_size_bytes;
 
                 // Pre-allocate strings outside loop
                 let event_type = "test".to_string();
-                let payload = vec![0u8; size];
 
                 // Sampling for latency measurement (1 in every N operations)
                 let mut rec = LatencyRecorder::new();
 
-                // Tight loop with minimal overhead
-                let mut stream_name = format!("stream-{}-", Uuid::new_v4());
+                let Ok(start_offset) = barrier.worker_ready().await else {
+                    // The run stopped before the measurement started
+                    return (i, rec, Duration::ZERO, planner.digest(), OpSamples::default(), MetricsRegistry::new());
+                };
+                let mut samples = sample_log(&barrier, &sample_settings, i);
+                let mut metrics = MetricsRegistry::new();
+
+                // Tight loop with minimal overhead. Writers take turns in the stream indexes,
+                // so every stream they start is new.
+                let mut next_stream = i as u64;
+                let mut stream_name = stream_names.name(next_stream);
                 let stream_len = 10;
                 let mut stream_position = 0;
                 while !has_stopped.load(Ordering::Relaxed) && !cancel_token.is_cancelled() {
-                    let evt = EventData {
-                        payload: payload.clone(),
-                        event_type: format!("{}-{}", event_type.clone(), stream_position),
-                        tags: vec![stream_name.clone()],
-                    };
-
-                    let operation_started = Instant::now();
-                    if adapter.append(vec![evt]).await.is_ok() {
-                        local_count += 1;
+                    let due = pacer.wait().await;
+                    let op = planner.next_op();
+                    let batch: Vec<EventData> = (stream_position..stream_position + batch_size)
+                        .map(|position| EventData {
+                            payload: op_payload(payloads.next_payload(), &sample_settings, &samples),
+                            event_type: planner
+                                .event_type(&op)
+                                .unwrap_or_else(|| format!("{}-{}", event_type, position)),
+                            tags: vec![stream_name.clone()],
+                        })
+                        .collect();
+
+                    let operation_started = due.unwrap_or_else(Instant::now);
+                    let ok = adapter.append(batch).await.is_ok();
+                    let latency = operation_started.elapsed();
+                    samples.record("append", operation_started, latency, ok, None);
+                    if ok {
+                        local_count += batch_size as u64;
 
                         // Update shared counter on every operation for maximum throughput accuracy
                         // (atomic store is ~0.5ns, negligible compared to append latency)
                         worker_counter.store(local_count, Ordering::Relaxed);
 
                         // Record latency sample
-                        rec.record(operation_started.elapsed());
+                        rec.record(latency);
 
                         // Increment stream position, maybe reset and change name.
-                        stream_position += 1;
-                        if stream_position == stream_len {
-                            stream_name = format!("stream-{}-", Uuid::new_v4());
+                        stream_position += batch_size;
+                        if stream_position >= stream_len {
+                            next_stream += writers as u64;
+                            stream_name = stream_names.name(next_stream);
                             stream_position = 0;
                         }
-
+                    } else {
+                        metrics.increment("append_errors", 1);
                     }
+
+                    offer_checkpoint(&mut checkpoint, &rec);
+
+                    planner.think(&barrier, &cancel_token).await;
                 }
 
                 // Store final count for this worker
                 worker_counter.store(local_count, Ordering::Relaxed);
-                rec
+                (i, rec, start_offset, planner.digest(), samples.into_samples(), metrics)
             });
         }
 
-        // Spawn throughput sampling task that waits for warmup, then samples
-        tokio::time::sleep(Duration::from_secs(1)).await;
-        let sample_counters = worker_counters.clone();
-        let duration_seconds = self.config.duration_seconds;
-        let samples_per_second = 2;
-        let num_intervals = duration_seconds * samples_per_second; 
-        let has_stopped_throughput = has_stopped.clone();
-        let cancel_token_throughput = cancel_token.clone();
-        let throughput_handle = tokio::spawn(async move {
-            // Pre-allocate vector for N+1 samples
-            let mut samples = Vec::with_capacity((num_intervals + 1) as usize);
-            let sampling_started = Instant::now();
-
-            // Take samples at fixed intervals (N+1 total for N seconds)
-            for i in 0..=num_intervals {
-                if cancel_token_throughput.is_cancelled() {
-                    break;
-                }
-                let total_count: u64 = sample_counters.iter()
-                    .map(|c| c.load(Ordering::Relaxed))
-                    .sum();
-
-                samples.push(ThroughputSample {
-                    elapsed_s: sampling_started.elapsed().as_secs_f64(),
-                    count: total_count,
-                });
-
-                // Sleep until next second (except after last sample)
-                if i < num_intervals {
-                    let sleep_duration = {
-                        let target_time = Duration::from_secs_f64((i + 1) as f64 / samples_per_second as f64);
-                        let elapsed = sampling_started.elapsed();
-                        target_time.saturating_sub(elapsed)
-                    };
-                    tokio::select! {
-                        _ = tokio::time::sleep(sleep_duration) => {}
-                        _ = cancel_token_throughput.cancelled() => { break; }
-                    }
-                } else {
-                    has_stopped_throughput.store(true, Ordering::Relaxed);
-                }
-            }
+        let measurement_start = barrier.release().await?;
+        self.on_measurement_start(store, measurement_start)?;
+        println!("All {} writer clients ready", writers);
 
-            samples
-        });
+        let throughput_handle = start_throughput_sampler(
+            worker_counters.clone(),
+            self.config.duration_seconds,
+            has_stopped.clone(),
+            cancel_token.clone(),
+            self.run_budget(),
+        )
+        .await;
 
         // Collect results from writer tasks
         let mut overall = LatencyRecorder::new();
+        let mut worker_start_offsets = vec![Duration::ZERO; writers];
+        let mut op_digests = vec![WorkerDigest::default(); writers];
+        let mut samples = OpSamples::default();
+        let mut extra = MetricsRegistry::new();
         while let Some(res) = set.join_next().await {
-            let rec = res.expect("join");
+            let (i, rec, start_offset, digest, worker_samples, worker_metrics) = res.expect("join");
             overall.hist.add(&rec.hist).unwrap();
+            worker_start_offsets[i] = start_offset;
+            op_digests[i] = digest;
+            samples.merge(worker_samples);
+            extra.merge(&worker_metrics);
         }
 
         // Get final count from all workers
@@ -430,28 +1071,32 @@ impl PerformanceWorkload {
             .sum();
         let throughput_samples = throughput_handle.await.expect("throughput task");
 
-        Ok((overall, events_written, 0, throughput_samples))
+        Ok(PerformanceOutput {
+            latency: overall,
+            events_written,
+            events_read: 0,
+            throughput_samples,
+            worker_start_offsets,
+            contention: None,
+            fan_out: None,
+            op_digests,
+            samples,
+            extra,
+            bulk_windows: Vec::new(),
+            connections: Vec::new(),
+        })
     }
 
     async fn execute_read_workload(
         &self,
         store: &dyn StoreManager,
         cancel_token: CancellationToken,
-    ) -> Result<(LatencyRecorder, u64, u64, Vec<ThroughputSample>)> {
+        sample_settings: SampleSettings,
+    ) -> Result<PerformanceOutput> {
         let readers = self.config.concurrency.readers.first();
         println!("Creating {} reader clients...", readers);
 
-        let mut reader_adapters = Vec::new();
-        for i in 0..readers {
-            match store.create_adapter() {
-                Ok(adapter) => reader_adapters.push(adapter),
-                Err(e) => {
-                    eprintln!("Failed to create reader {}: {}", i, e);
-                    anyhow::bail!("Failed to create reader {}: {}", i, e);
-                }
-            }
-        }
-        println!("All {} reader clients ready", readers);
+        let reader_adapters = self.create_adapters(store, readers, "reader").await?;
 
         let mut set = JoinSet::new();
 
@@ -462,133 +1107,140 @@ impl PerformanceWorkload {
             .map(|_| Arc::new(AtomicU64::new(0)))
             .collect();
 
-        let has_stopped = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let has_stopped = Arc::new(AtomicBool::new(false));
+        let limiters = self.rate_limiters(readers)?;
+        let barrier = StartBarrier::scheduled(readers, self.config.schedule.clone(), &cancel_token);
 
         // Spawn reader tasks
         for (i, adapter) in reader_adapters.into_iter().enumerate() {
-            let config = self.config.clone();
+            let limiter = limiters[i].clone();
             let read_cfg = read_config.clone();
-            let seed = self.seed + (i as u64);
+            let mut planner = self.op_planner(i);
+            let mut checkpoint = self.checkpoint_handle(i);
             let worker_counter = worker_counters[i].clone();
             let has_stopped = has_stopped.clone();
             let cancel_token = cancel_token.clone();
-            let stream_prefix = self.stream_prefix.clone();
-            let prepopulated_streams = if let Some(setup) = config.setup {
-                setup.prepopulate_streams.unwrap_or(setup.prepopulate_events)
-            } else {
-                1
-            };
+            let barrier = barrier.clone();
+            let stream_names = self.stream_names.clone();
+            let sample_settings = sample_settings.clone();
             set.spawn(async move {
-                let mut rng = StdRng::seed_from_u64(seed);
                 let mut rec = LatencyRecorder::new();
                 let mut total_events_read = 0u64;
 
+                let Ok(start_offset) = barrier.worker_ready().await else {
+                    // The run stopped before the measurement started
+                    return (i, rec, 0, Duration::ZERO, planner.digest(), OpSamples::default(), MetricsRegistry::new());
+                };
+                let mut samples = sample_log(&barrier, &sample_settings, i);
+                let mut metrics = MetricsRegistry::new();
+
                 while !has_stopped.load(Ordering::Relaxed) && !cancel_token.is_cancelled() {
-                    let stream_idx = rng.gen_range(0..prepopulated_streams);
+                    if let Some(limiter) = &limiter {
+                        limiter.acquire().await;
+                    }
+                    let stream_idx = planner.next_op().stream_idx;
 
                     let req = ReadRequest {
-                        stream: format!("{}{}", stream_prefix, stream_idx),
+                        stream: stream_names.name(stream_idx as u64),
                         from_offset: None,
                         limit: Some(read_cfg.batch_size as u64),
+                        consistency: read_cfg.consistency,
                     };
 
                     let operation_started = Instant::now();
                     let result = adapter.read(req).await;
+                    let latency = operation_started.elapsed();
+                    samples.record("read", operation_started, latency, result.is_ok(), Some(stream_idx));
 
-                    if let Ok(events) = result {
-                        total_events_read += events.len() as u64;
-                        worker_counter.store(total_events_read, Ordering::Relaxed);
+                    match result {
+                        Ok(events) => {
+                            total_events_read += events.len() as u64;
+                            worker_counter.store(total_events_read, Ordering::Relaxed);
+                        }
+                        Err(_) => metrics.increment("read_errors", 1),
                     }
 
                     // Record latency for all operations
-                    rec.record(operation_started.elapsed());
+                    rec.record(latency);
+
+                    offer_checkpoint(&mut checkpoint, &rec);
+
+                    planner.think(&barrier, &cancel_token).await;
                 }
-                (rec, total_events_read)
+                (
+                    i,
+                    rec,
+                    total_events_read,
+                    start_offset,
+                    planner.digest(),
+                    samples.into_samples(),
+                    metrics,
+                )
             });
         }
 
-        // Spawn throughput sampling task that waits for warmup, then samples
-        tokio::time::sleep(Duration::from_secs(1)).await;
-        let sample_counters = worker_counters.clone();
-        let duration_seconds = self.config.duration_seconds;
-        let samples_per_second = 2;
-        let num_intervals = duration_seconds * samples_per_second;
-        let has_stopped_throughput = has_stopped.clone();
-        let cancel_token_throughput = cancel_token.clone();
-        let throughput_handle = tokio::spawn(async move {
-            // Pre-allocate vector for N+1 samples
-            let mut samples = Vec::with_capacity((num_intervals + 1) as usize);
-            let sampling_started = Instant::now();
-
-            // Take samples at fixed intervals (N+1 total for N seconds)
-            for i in 0..=num_intervals {
-                if cancel_token_throughput.is_cancelled() {
-                    break;
-                }
-                let total_count: u64 = sample_counters.iter()
-                    .map(|c| c.load(Ordering::Relaxed))
-                    .sum();
-
-                samples.push(ThroughputSample {
-                    elapsed_s: sampling_started.elapsed().as_secs_f64(),
-                    count: total_count,
-                });
-
-                // Sleep until next interval (except after last sample)
-                if i < num_intervals {
-                    let sleep_duration = {
-                        let target_time = Duration::from_secs_f64((i + 1) as f64 / samples_per_second as f64);
-                        let elapsed = sampling_started.elapsed();
-                        target_time.saturating_sub(elapsed)
-                    };
-                    tokio::select! {
-                        _ = tokio::time::sleep(sleep_duration) => {}
-                        _ = cancel_token_throughput.cancelled() => { break; }
-                    }
-                } else {
-                    has_stopped_throughput.store(true, Ordering::Relaxed);
-                }
-            }
+        let measurement_start = barrier.release().await?;
+        self.on_measurement_start(store, measurement_start)?;
+        println!("All {} reader clients ready", readers);
 
-            samples
-        });
+        let throughput_handle = start_throughput_sampler(
+            worker_counters.clone(),
+            self.config.duration_seconds,
+            has_stopped.clone(),
+            cancel_token.clone(),
+            self.run_budget(),
+        )
+        .await;
 
         // Collect results from reader tasks
         let mut overall = LatencyRecorder::new();
         let mut events_read: u64 = 0;
+        let mut worker_start_offsets = vec![Duration::ZERO; readers];
+        let mut op_digests = vec![WorkerDigest::default(); readers];
+        let mut samples = OpSamples::default();
+        let mut extra = MetricsRegistry::new();
         while let Some(res) = set.join_next().await {
-            let (rec, reader_events_read) = res.expect("join");
+            let (i, rec, reader_events_read, start_offset, digest, worker_samples, worker_metrics) =
+                res.expect("join");
             overall.hist.add(&rec.hist)?;
             events_read += reader_events_read;
+            worker_start_offsets[i] = start_offset;
+            op_digests[i] = digest;
+            samples.merge(worker_samples);
+            extra.merge(&worker_metrics);
         }
 
         let throughput_samples = throughput_handle.await.expect("throughput task");
 
-        Ok((overall, 0, events_read, throughput_samples))
+        Ok(PerformanceOutput {
+            latency: overall,
+            events_written: 0,
+            events_read,
+            throughput_samples,
+            worker_start_offsets,
+            contention: None,
+            fan_out: None,
+            op_digests,
+            samples,
+            extra,
+            bulk_windows: Vec::new(),
+            connections: Vec::new(),
+        })
     }
 
     async fn execute_mixed_workload(
         &self,
         store: &dyn StoreManager,
         cancel_token: CancellationToken,
-    ) -> Result<(LatencyRecorder, u64, u64, Vec<ThroughputSample>)> {
+        sample_settings: SampleSettings,
+    ) -> Result<PerformanceOutput> {
         let writers = self.config.concurrency.writers.first();
         let readers = self.config.concurrency.readers.first();
         let total_workers = writers + readers;
 
         println!("Creating {} worker clients ({} writers, {} readers)...", total_workers, writers, readers);
 
-        let mut worker_adapters = Vec::new();
-        for i in 0..total_workers {
-            match store.create_adapter() {
-                Ok(adapter) => worker_adapters.push(adapter),
-                Err(e) => {
-                    eprintln!("Failed to create worker {}: {}", i, e);
-                    anyhow::bail!("Failed to create worker {}: {}", i, e);
-                }
-            }
-        }
-        println!("All {} worker clients ready", total_workers);
+        let worker_adapters = self.create_adapters(store, total_workers, "worker").await?;
 
         let mut set = JoinSet::new();
 
@@ -597,148 +1249,352 @@ impl PerformanceWorkload {
             .map(|_| Arc::new(AtomicU64::new(0)))
             .collect();
 
-        let has_stopped = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let has_stopped = Arc::new(AtomicBool::new(false));
 
-        let write_prob = self
-            .config
-            .operations
-            .write
-            .as_ref()
-            .and_then(|w| w.probability)
-            .unwrap_or(0.5);
+        let pacers = self.pacers(total_workers, total_workers)?;
+        let barrier = StartBarrier::scheduled(total_workers, self.config.schedule.clone(), &cancel_token);
+        let batch_size = self.write_batch_size();
+        let written_streams = Arc::new(WrittenStreams::new(self.prepopulated_streams()));
 
         // Spawn worker tasks
         for (i, adapter) in worker_adapters.into_iter().enumerate() {
             let config = self.config.clone();
-            let seed = self.seed + (i as u64);
-            let is_writer = i < writers;
+            let written_streams = written_streams.clone();
+            let mut payloads = self.payloads().cursor(i);
+            let mut pacer = pacers[i].clone();
+            let mut planner = self.op_planner(i);
+            let mut checkpoint = self.checkpoint_handle(i);
             let worker_counter = worker_counters[i].clone();
             let has_stopped = has_stopped.clone();
             let cancel_token = cancel_token.clone();
+            let barrier = barrier.clone();
+            let stream_names = self.stream_names.clone();
 
+            let sample_settings = sample_settings.clone();
             set.spawn(async move {
-                let mut rng = StdRng::seed_from_u64(seed);
                 let mut rec = LatencyRecorder::new();
                 let mut events_written = 0u64;
                 let mut events_read = 0u64;
-                let prepopulated_streams = if let Some(setup) = config.setup {
-                    setup.prepopulate_streams.unwrap_or(setup.prepopulate_events)
-                } else {
-                    1
-                };
 
-                let write_cfg = config.operations.write.as_ref();
+                let can_write = config.operations.write.is_some();
                 let read_cfg = config.operations.read.as_ref();
 
-                while !has_stopped.load(Ordering::Relaxed) && !cancel_token.is_cancelled() {
-                    let stream_idx = rng.gen_range(0..prepopulated_streams);
+                let Ok(start_offset) = barrier.worker_ready().await else {
+                    // The run stopped before the measurement started
+                    return (i, rec, 0, 0, Duration::ZERO, planner.digest(), OpSamples::default(), MetricsRegistry::new());
+                };
+                let mut samples = sample_log(&barrier, &sample_settings, i);
+                let mut metrics = MetricsRegistry::new();
 
+                while !has_stopped.load(Ordering::Relaxed) && !cancel_token.is_cancelled() {
+                    let due = pacer.wait().await;
                     // Decide operation based on worker type and probability
-                    let should_write = if is_writer {
-                        write_cfg.is_some() && (read_cfg.is_none() || rng.gen_bool(write_prob))
-                    } else {
-                        false
+                    let op = planner.next_op();
+                    let should_write = op.write;
+                    let stream_idx = match op.non_empty_pick {
+                        Some(pick) => written_streams.pick(pick).unwrap_or_else(|| {
+                            metrics.increment("non_empty_read_fallbacks", 1);
+                            op.stream_idx
+                        }),
+                        None => op.stream_idx,
                     };
 
-                    let operation_started = Instant::now();
-
-                    if should_write {
-                        if let Some(write_cfg) = write_cfg {
-                            let evt = EventData {
-                                payload: vec![0u8; write_cfg.event_size_bytes],
-                                event_type: "test".to_string(),
-                                tags: vec![format!("stream-{}", stream_idx)],
-                            };
-                            if adapter.append(vec![evt]).await.is_ok() {
-                                events_written += 1;
-                                worker_counter.store(events_written, Ordering::Relaxed);
+                    let operation_started = due.unwrap_or_else(Instant::now);
+
+                    let ok = if should_write {
+                        if can_write {
+                            let stream = stream_names.name(stream_idx as u64);
+                            let batch = (0..batch_size)
+                                .map(|_| EventData {
+                                    payload: op_payload(payloads.next_payload(), &sample_settings, &samples),
+                                    event_type: planner.event_type(&op).unwrap_or_else(|| "test".to_string()),
+                                    tags: vec![stream.clone()],
+                                })
+                                .collect();
+                            let ok = adapter.append(batch).await.is_ok();
+                            if ok {
+                                events_written += batch_size as u64;
+                                worker_counter.store(events_written + events_read, Ordering::Relaxed);
+                                written_streams.insert(stream_idx);
                             }
+                            ok
                         } else {
                             continue;
                         }
                     } else {
                         if let Some(read_cfg) = read_cfg {
                             let req = ReadRequest {
-                                stream: format!("stream-{}", stream_idx),
+                                stream: stream_names.name(stream_idx as u64),
                                 from_offset: None,
                                 limit: Some(read_cfg.batch_size as u64),
+                                consistency: read_cfg.consistency,
                             };
                             let result = adapter.read(req).await;
+                            let ok = result.is_ok();
                             if let Ok(events) = result {
                                 events_read += events.len() as u64;
-                                worker_counter.store(events_read, Ordering::Relaxed);
+                                worker_counter.store(events_written + events_read, Ordering::Relaxed);
                             }
+                            ok
                         } else {
                             continue;
                         }
                     };
 
                     // Record latency for all operations
-                    rec.record(operation_started.elapsed());
+                    let latency = operation_started.elapsed();
+                    rec.record(latency);
+                    let op = if should_write { "append" } else { "read" };
+                    samples.record(op, operation_started, latency, ok, Some(stream_idx));
+                    if !ok {
+                        metrics.increment(if should_write { "append_errors" } else { "read_errors" }, 1);
+                    }
+
+                    offer_checkpoint(&mut checkpoint, &rec);
+
+                    planner.think(&barrier, &cancel_token).await;
                 }
-                (rec, events_written, events_read)
+                (
+                    i,
+                    rec,
+                    events_written,
+                    events_read,
+                    start_offset,
+                    planner.digest(),
+                    samples.into_samples(),
+                    metrics,
+                )
             });
         }
 
-        // Spawn throughput sampling task that waits for warmup, then samples
-        tokio::time::sleep(Duration::from_secs(1)).await;
-        let sample_counters = worker_counters.clone();
-        let duration_seconds = self.config.duration_seconds;
-        let samples_per_second = 2;
-        let num_intervals = duration_seconds * samples_per_second;
-        let has_stopped_throughput = has_stopped.clone();
-        let cancel_token_throughput = cancel_token.clone();
-        let throughput_handle = tokio::spawn(async move {
-            // Pre-allocate vector for N+1 samples
-            let mut samples = Vec::with_capacity((num_intervals + 1) as usize);
-            let sampling_started = Instant::now();
-
-            // Take samples at fixed intervals (N+1 total for N seconds)
-            for i in 0..=num_intervals {
-                if cancel_token_throughput.is_cancelled() {
-                    break;
-                }
-                let total_count: u64 = sample_counters.iter()
-                    .map(|c| c.load(Ordering::Relaxed))
-                    .sum();
-
-                samples.push(ThroughputSample {
-                    elapsed_s: sampling_started.elapsed().as_secs_f64(),
-                    count: total_count,
-                });
-
-                // Sleep until next interval (except after last sample)
-                if i < num_intervals {
-                    let sleep_duration = {
-                        let target_time = Duration::from_secs_f64((i + 1) as f64 / samples_per_second as f64);
-                        let elapsed = sampling_started.elapsed();
-                        target_time.saturating_sub(elapsed)
-                    };
-                    tokio::select! {
-                        _ = tokio::time::sleep(sleep_duration) => {}
-                        _ = cancel_token_throughput.cancelled() => { break; }
-                    }
-                } else {
-                    has_stopped_throughput.store(true, Ordering::Relaxed);
-                }
-            }
+        let measurement_start = barrier.release().await?;
+        self.on_measurement_start(store, measurement_start)?;
+        println!("All {} worker clients ready", total_workers);
 
-            samples
-        });
+        let throughput_handle = start_throughput_sampler(
+            worker_counters.clone(),
+            self.config.duration_seconds,
+            has_stopped.clone(),
+            cancel_token.clone(),
+            self.run_budget(),
+        )
+        .await;
 
         // Collect results from worker tasks
         let mut overall = LatencyRecorder::new();
         let mut total_events_written: u64 = 0;
         let mut total_events_read: u64 = 0;
+        let mut worker_start_offsets = vec![Duration::ZERO; total_workers];
+        let mut op_digests = vec![WorkerDigest::default(); total_workers];
+        let mut samples = OpSamples::default();
+        let mut extra = MetricsRegistry::new();
         while let Some(res) = set.join_next().await {
-            let (rec, written, read) = res.expect("join");
+            let (i, rec, written, read, start_offset, digest, worker_samples, worker_metrics) =
+                res.expect("join");
             overall.hist.add(&rec.hist)?;
             total_events_written += written;
             total_events_read += read;
+            worker_start_offsets[i] = start_offset;
+            op_digests[i] = digest;
+            samples.merge(worker_samples);
+            extra.merge(&worker_metrics);
         }
 
         let throughput_samples = throughput_handle.await.expect("throughput task");
 
-        Ok((overall, total_events_written, total_events_read, throughput_samples))
+        Ok(PerformanceOutput {
+            latency: overall,
+            events_written: total_events_written,
+            events_read: total_events_read,
+            throughput_samples,
+            worker_start_offsets,
+            contention: None,
+            fan_out: None,
+            op_digests,
+            samples,
+            extra,
+            bulk_windows: Vec::new(),
+            connections: Vec::new(),
+        })
+    }
+}
+
+impl PerformanceWorkload {
+    /// Checkpoint hand-in for worker `i`, if the current run writes checkpoints
+    fn checkpoint_handle(&self, i: usize) -> Option<CheckpointHandle> {
+        let checkpoints = self.checkpoints.lock().ok()?;
+        checkpoints.as_ref().map(|c| c.handle(i))
+    }
+
+    /// Note the measurement start and start the configured bulk operations, timed from it
+    fn on_measurement_start(&self, store: &dyn StoreManager, started: Instant) -> Result<()> {
+        if let Ok(mut measurement_started) = self.measurement_started.lock() {
+            *measurement_started = Some(started);
+        }
+        let Some(config) = &self.config.bulk else {
+            return Ok(());
+        };
+        let adapter = store.create_adapter()?;
+        let streams = (0..config.catch_up_streams(self.config.setup.as_ref()).unwrap_or(0))
+            .map(|idx| self.stream_names.name(idx))
+            .collect();
+        let stop = CancellationToken::new();
+        let task = tokio::spawn(inject_bulk(
+            config.clone(),
+            adapter,
+            streams,
+            self.stream_names.prefix().to_string(),
+            started,
+            Duration::from_secs(self.config.duration_seconds),
+            stop.clone(),
+        ));
+        if let Ok(mut current) = self.bulk.lock() {
+            *current = Some((stop, task));
+        }
+        Ok(())
     }
+
+    /// Budget of the current run, if limits are configured
+    fn run_budget(&self) -> Option<Arc<RunBudget>> {
+        self.budget.lock().ok()?.clone()
+    }
+
+    /// Create `count` adapters with their connections established (and the setup timed),
+    /// wrapped in `Traced` when a subscriber collects the run's spans, in `Ledgered` when the
+    /// run keeps a ledger and in `Instrumented` when `instrument_phases` is set
+    async fn create_adapters(
+        &self,
+        store: &dyn StoreManager,
+        count: usize,
+        role: &str,
+    ) -> Result<Vec<Arc<dyn EventStoreAdapter>>> {
+        let (mut adapters, timings) = create_connected_adapters(store, count, role).await?;
+        if let Ok(mut connections) = self.connections.lock() {
+            connections.extend(timings);
+        }
+        if let Some(run_span) = self.run_span.lock().ok().and_then(|s| s.clone()) {
+            adapters = adapters
+                .into_iter()
+                .map(|adapter| Arc::new(Traced::new(adapter, store.name(), run_span.clone())) as Arc<dyn EventStoreAdapter>)
+                .collect();
+        }
+        if let Some(ledger) = self.ledger.lock().ok().and_then(|l| l.clone()) {
+            adapters = adapters
+                .into_iter()
+                .map(|adapter| Arc::new(Ledgered::new(adapter, ledger.clone())) as Arc<dyn EventStoreAdapter>)
+                .collect();
+        }
+        if !self.config.instrument_phases {
+            return Ok(adapters);
+        }
+        let mut recorders = self
+            .phase_recorders
+            .lock()
+            .map_err(|_| anyhow::anyhow!("phase recorders lock poisoned"))?;
+        Ok(adapters
+            .into_iter()
+            .map(|adapter| {
+                let recorder = PhaseRecorder::new();
+                let callback = recorder.callback();
+                recorders.push(recorder);
+                Arc::new(Instrumented::new(adapter, callback)) as Arc<dyn EventStoreAdapter>
+            })
+            .collect())
+    }
+}
+
+/// What each worker records in its samples besides the timing
+#[derive(Clone)]
+struct SampleSettings {
+    collect: bool,
+    epoch_ms: bool,
+    streams: SampleStreams,
+    payload_op_seq: bool,
+    live: Option<Arc<LiveStats>>,
+}
+
+/// Bytes at the start of a payload taken by the worker index and op sequence number
+const OP_SEQ_STAMP_BYTES: usize = 12;
+
+/// Sample collector for one worker, timed from the measurement start. Call after
+/// [`StartBarrier::worker_ready`] has returned.
+fn sample_log(barrier: &StartBarrier, settings: &SampleSettings, worker: usize) -> SampleLog {
+    let start = barrier.measurement_start();
+    SampleLog::new(
+        if settings.collect { SAMPLE_RATE } else { 0 },
+        worker,
+        start.instant,
+        settings.epoch_ms.then_some(start.epoch_ms),
+    )
+    .with_streams(settings.streams)
+    .with_live(settings.live.clone())
+}
+
+/// Payload for a worker's next append, stamped with the worker index and the sequence number
+/// of the operation when `payload_op_seq` is enabled and the payload has room for it
+fn op_payload(template: Bytes, settings: &SampleSettings, samples: &SampleLog) -> Bytes {
+    if !settings.payload_op_seq || template.len() < OP_SEQ_STAMP_BYTES {
+        return template;
+    }
+    // Stamped payloads differ per operation, so only they are copied
+    let mut payload = BytesMut::from(&template[..]);
+    payload[..4].copy_from_slice(&(samples.worker() as u32).to_le_bytes());
+    payload[4..OP_SEQ_STAMP_BYTES].copy_from_slice(&samples.next_seq().to_le_bytes());
+    payload.freeze()
+}
+
+/// Wait for the warmup period, then spawn the throughput sampling task. The task samples the
+/// summed worker counters at fixed intervals for the workload duration (or until the budget is
+/// exceeded) and then tells the workers to stop.
+async fn start_throughput_sampler(
+    worker_counters: Vec<Arc<AtomicU64>>,
+    duration_seconds: u64,
+    has_stopped: Arc<AtomicBool>,
+    cancel_token: CancellationToken,
+    budget: Option<Arc<RunBudget>>,
+) -> JoinHandle<Vec<ThroughputSample>> {
+    tokio::time::sleep(Duration::from_secs(1)).await;
+    let samples_per_second = 2;
+    let num_intervals = duration_seconds * samples_per_second;
+    tokio::spawn(async move {
+        // Pre-allocate vector for N+1 samples
+        let mut samples = Vec::with_capacity((num_intervals + 1) as usize);
+        let sampling_started = Instant::now();
+
+        // Take samples at fixed intervals (N+1 total for N seconds)
+        for i in 0..=num_intervals {
+            if cancel_token.is_cancelled() {
+                break;
+            }
+            let total_count: u64 = worker_counters.iter()
+                .map(|c| c.load(Ordering::Relaxed))
+                .sum();
+
+            samples.push(ThroughputSample {
+                elapsed_s: sampling_started.elapsed().as_secs_f64(),
+                count: total_count,
+            });
+
+            if budget.as_ref().is_some_and(|budget| budget.exceeded(total_count)) {
+                has_stopped.store(true, Ordering::Relaxed);
+                break;
+            }
+            // Sleep until next interval (except after last sample)
+            if i < num_intervals {
+                let sleep_duration = {
+                    let target_time = Duration::from_secs_f64((i + 1) as f64 / samples_per_second as f64);
+                    let elapsed = sampling_started.elapsed();
+                    target_time.saturating_sub(elapsed)
+                };
+                tokio::select! {
+                    _ = tokio::time::sleep(sleep_duration) => {}
+                    _ = cancel_token.cancelled() => { break; }
+                }
+            } else {
+                has_stopped.store(true, Ordering::Relaxed);
+            }
+        }
+
+        samples
+    })
 }
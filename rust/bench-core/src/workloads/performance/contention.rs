@@ -0,0 +1,185 @@
+use super::*;
+use crate::adapter::{AppendCondition, AppendOutcome};
+
+impl PerformanceWorkload {
+    /// All writers append to the same K hot streams. With conditional appends enabled each writer
+    /// tracks the last position it saw per stream and retries with the position reported on conflict,
+    /// so throughput and conflict rate show how the store copes with a single-aggregate bottleneck.
+    pub(super) async fn execute_contention_workload(
+        &self,
+        store: &dyn StoreManager,
+        cancel_token: CancellationToken,
+        sample_settings: SampleSettings,
+    ) -> Result<PerformanceOutput> {
+        let writers = self.config.concurrency.writers.first();
+        let contention = self.config.contention.clone().unwrap_or_default();
+        println!(
+            "Creating {} writer clients contending on {} stream(s) ({} appends)...",
+            writers,
+            contention.streams,
+            if contention.conditional { "conditional" } else { "unconditional" }
+        );
+
+        let writer_adapters = self.create_adapters(store, writers, "writer").await?;
+
+        let mut set = JoinSet::new();
+
+        // Per-worker counters of successful appends
+        let worker_counters: Vec<Arc<AtomicU64>> = (0..writers)
+            .map(|_| Arc::new(AtomicU64::new(0)))
+            .collect();
+
+        let has_stopped = Arc::new(AtomicBool::new(false));
+        let pacers = self.pacers(writers, writers)?;
+        let barrier = StartBarrier::scheduled(writers, self.config.schedule.clone(), &cancel_token);
+
+        let hot_streams: Arc<Vec<String>> = Arc::new(
+            (0..contention.streams)
+                .map(|k| format!("{}hot-{}", self.stream_names.prefix(), k))
+                .collect(),
+        );
+
+        for (i, adapter) in writer_adapters.into_iter().enumerate() {
+            let mut payloads = self.payloads().cursor(i);
+            let mut pacer = pacers[i].clone();
+            let mut planner = self.op_planner(i);
+            let mut checkpoint = self.checkpoint_handle(i);
+            let worker_counter = worker_counters[i].clone();
+            let has_stopped = has_stopped.clone();
+            let cancel_token = cancel_token.clone();
+            let barrier = barrier.clone();
+            let hot_streams = hot_streams.clone();
+            let conditional = contention.conditional;
+
+            let sample_settings = sample_settings.clone();
+            set.spawn(async move {
+                let mut rec = LatencyRecorder::new();
+                let mut stats = ContentionStats::default();
+
+                // Last position this writer knows for each hot stream (None = assume empty)
+                let mut known_positions: Vec<Option<u64>> = vec![None; hot_streams.len()];
+
+                let Ok(start_offset) = barrier.worker_ready().await else {
+                    // The run stopped before the measurement started
+                    return (i, rec, stats, Duration::ZERO, planner.digest(), OpSamples::default(), MetricsRegistry::new());
+                };
+                let mut samples = sample_log(&barrier, &sample_settings, i);
+                let mut metrics = MetricsRegistry::new();
+
+                while !has_stopped.load(Ordering::Relaxed) && !cancel_token.is_cancelled() {
+                    let due = pacer.wait().await;
+                    let op = planner.next_op();
+                    let stream_idx = op.stream_idx;
+                    let evt = EventData {
+                        payload: op_payload(payloads.next_payload(), &sample_settings, &samples),
+                        event_type: planner.event_type(&op).unwrap_or_else(|| "test".to_string()),
+                        tags: vec![hot_streams[stream_idx].clone()],
+                    };
+
+                    stats.attempts += 1;
+                    let operation_started = due.unwrap_or_else(Instant::now);
+                    let ok = if conditional {
+                        let condition = match known_positions[stream_idx] {
+                            Some(position) => AppendCondition::After(position),
+                            None => AppendCondition::NoStream,
+                        };
+                        match adapter.append_conditional(vec![evt], condition).await {
+                            Ok(AppendOutcome::Appended { position }) => {
+                                rec.record(operation_started.elapsed());
+                                known_positions[stream_idx] = Some(position);
+                                stats.appended += 1;
+                                true
+                            }
+                            Ok(AppendOutcome::Conflict { current }) => {
+                                metrics.record("conflict_latency", operation_started.elapsed());
+                                known_positions[stream_idx] = current;
+                                stats.conflicts += 1;
+                                false
+                            }
+                            Err(_) => {
+                                stats.errors += 1;
+                                metrics.increment("contention_errors", 1);
+                                false
+                            }
+                        }
+                    } else if adapter.append(vec![evt]).await.is_ok() {
+                        rec.record(operation_started.elapsed());
+                        stats.appended += 1;
+                        true
+                    } else {
+                        stats.errors += 1;
+                        metrics.increment("contention_errors", 1);
+                        false
+                    };
+                    samples.record(
+                        "append",
+                        operation_started,
+                        operation_started.elapsed(),
+                        ok,
+                        Some(stream_idx),
+                    );
+                    worker_counter.store(stats.appended, Ordering::Relaxed);
+
+                    offer_checkpoint(&mut checkpoint, &rec);
+
+                    planner.think(&barrier, &cancel_token).await;
+                }
+                (i, rec, stats, start_offset, planner.digest(), samples.into_samples(), metrics)
+            });
+        }
+
+        let measurement_start = barrier.release().await?;
+        self.on_measurement_start(store, measurement_start)?;
+        println!("All {} writer clients ready", writers);
+
+        let throughput_handle = start_throughput_sampler(
+            worker_counters.clone(),
+            self.config.duration_seconds,
+            has_stopped.clone(),
+            cancel_token.clone(),
+            self.run_budget(),
+        )
+        .await;
+
+        let mut overall = LatencyRecorder::new();
+        let mut totals = ContentionStats::default();
+        let mut worker_start_offsets = vec![Duration::ZERO; writers];
+        let mut op_digests = vec![WorkerDigest::default(); writers];
+        let mut samples = OpSamples::default();
+        let mut extra = MetricsRegistry::new();
+        while let Some(res) = set.join_next().await {
+            let (i, rec, stats, start_offset, digest, worker_samples, worker_metrics) =
+                res.expect("join");
+            overall.hist.add(&rec.hist)?;
+            totals.merge(&stats);
+            worker_start_offsets[i] = start_offset;
+            op_digests[i] = digest;
+            samples.merge(worker_samples);
+            extra.merge(&worker_metrics);
+        }
+
+        let throughput_samples = throughput_handle.await.expect("throughput task");
+        println!(
+            "Contention: {} attempts, {} conflicts ({:.1}%), {} errors",
+            totals.attempts,
+            totals.conflicts,
+            totals.conflict_rate() * 100.0,
+            totals.errors
+        );
+
+        Ok(PerformanceOutput {
+            latency: overall,
+            events_written: totals.appended,
+            events_read: 0,
+            throughput_samples,
+            worker_start_offsets,
+            contention: Some(totals),
+            fan_out: None,
+            op_digests,
+            samples,
+            extra,
+            bulk_windows: Vec::new(),
+            connections: Vec::new(),
+        })
+    }
+}
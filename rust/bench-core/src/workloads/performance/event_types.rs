@@ -0,0 +1,106 @@
+use anyhow::Result;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// How often each event type is appended
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventTypeDistribution {
+    #[default]
+    Uniform,
+    /// Type `k` is appended in proportion to `1 / (k + 1)^exponent`, so a few types dominate
+    Zipf,
+}
+
+/// Event types appended by the workers (`operations.write.event_types`), so stores indexing by
+/// type see realistic cardinality and type queries have meaningful selectivity
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventTypesConfig {
+    /// Number of distinct types, named `type-0`, `type-1`, ...
+    pub count: usize,
+    #[serde(default)]
+    pub distribution: EventTypeDistribution,
+    /// Skew of the zipf distribution
+    #[serde(default = "default_exponent")]
+    pub exponent: f64,
+}
+
+fn default_exponent() -> f64 {
+    1.0
+}
+
+/// Type names with the cumulative weights to draw them by
+#[derive(Debug)]
+pub(super) struct EventTypes {
+    names: Vec<String>,
+    /// Cumulative probability per type; `None` for uniform
+    cdf: Option<Vec<f64>>,
+}
+
+impl EventTypes {
+    pub(super) fn new(config: &EventTypesConfig) -> Result<Self> {
+        if config.count == 0 {
+            anyhow::bail!("event_types count must be > 0");
+        }
+        if !(config.exponent.is_finite() && config.exponent > 0.0) {
+            anyhow::bail!("event_types exponent must be > 0, got {}", config.exponent);
+        }
+        let cdf = (config.distribution == EventTypeDistribution::Zipf).then(|| {
+            let weights: Vec<f64> = (0..config.count)
+                .map(|k| 1.0 / ((k + 1) as f64).powf(config.exponent))
+                .collect();
+            let total: f64 = weights.iter().sum();
+            weights
+                .iter()
+                .scan(0.0, |sum, w| {
+                    *sum += w / total;
+                    Some(*sum)
+                })
+                .collect()
+        });
+        Ok(Self {
+            names: (0..config.count).map(|k| format!("type-{}", k)).collect(),
+            cdf,
+        })
+    }
+
+    /// Draw the index of the next event's type
+    pub(super) fn pick<R: Rng>(&self, rng: &mut R) -> usize {
+        match &self.cdf {
+            None => rng.gen_range(0..self.names.len()),
+            Some(cdf) => {
+                let u: f64 = rng.gen();
+                cdf.partition_point(|p| *p <= u).min(self.names.len() - 1)
+            }
+        }
+    }
+
+    pub(super) fn name(&self, idx: usize) -> &str {
+        &self.names[idx]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn zipf_favours_the_first_types() {
+        let types = EventTypes::new(&EventTypesConfig {
+            count: 10,
+            distribution: EventTypeDistribution::Zipf,
+            exponent: 1.0,
+        })
+        .unwrap();
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut counts = [0usize; 10];
+        for _ in 0..10_000 {
+            counts[types.pick(&mut rng)] += 1;
+        }
+        // Type 0 has weight 1/H(10) ≈ 34%, type 9 one tenth of that
+        assert!((3_000..3_800).contains(&counts[0]), "{:?}", counts);
+        assert!(counts[0] > counts[4] && counts[4] > counts[9]);
+        assert_eq!(types.name(9), "type-9");
+    }
+}
@@ -0,0 +1,296 @@
+use super::*;
+
+impl PerformanceWorkload {
+    /// Writers append to one shared stream while many readers repeatedly read it, either tailing
+    /// from their last position or re-reading from the start. Throughput samples count events
+    /// read, and writer latency is reported separately so its degradation under read load shows.
+    pub(super) async fn execute_fan_out_workload(
+        &self,
+        store: &dyn StoreManager,
+        cancel_token: CancellationToken,
+        sample_settings: SampleSettings,
+    ) -> Result<PerformanceOutput> {
+        let writers = self.config.concurrency.writers.first();
+        let readers = self.config.concurrency.readers.first();
+        let fan_out = self.config.fan_out.clone().unwrap_or_default();
+        println!(
+            "Creating {} writer and {} reader clients on one stream ({} reads)...",
+            writers,
+            readers,
+            if fan_out.tail { "tailing" } else { "full" }
+        );
+
+        let writer_adapters = self.create_adapters(store, writers, "writer").await?;
+        let reader_adapters = self.create_adapters(store, readers, "reader").await?;
+
+        let read_config = self.config.operations.read.as_ref().unwrap();
+        let stream_name = format!("{}fan-out", self.stream_names.prefix());
+
+        let writer_counters: Vec<Arc<AtomicU64>> = (0..writers)
+            .map(|_| Arc::new(AtomicU64::new(0)))
+            .collect();
+        // Throughput is sampled from the readers only
+        let reader_counters: Vec<Arc<AtomicU64>> = (0..readers)
+            .map(|_| Arc::new(AtomicU64::new(0)))
+            .collect();
+
+        let has_stopped = Arc::new(AtomicBool::new(false));
+        let pacers = self.pacers(writers + readers, writers)?;
+        let barrier = StartBarrier::scheduled(writers + readers, self.config.schedule.clone(), &cancel_token);
+
+        let mut writer_set = JoinSet::new();
+        for (i, adapter) in writer_adapters.into_iter().enumerate() {
+            let mut payloads = self.payloads().cursor(i);
+            let mut pacer = pacers[i].clone();
+            let mut planner = self.op_planner(i);
+            let mut checkpoint = self.checkpoint_handle(i);
+            let worker_counter = writer_counters[i].clone();
+            let has_stopped = has_stopped.clone();
+            let cancel_token = cancel_token.clone();
+            let barrier = barrier.clone();
+            let stream_name = stream_name.clone();
+
+            let sample_settings = sample_settings.clone();
+            writer_set.spawn(async move {
+                let mut rec = LatencyRecorder::new();
+                let mut events_written = 0u64;
+
+                let Ok(start_offset) = barrier.worker_ready().await else {
+                    // The run stopped before the measurement started
+                    return (i, rec, 0, Duration::ZERO, planner.digest(), OpSamples::default(), MetricsRegistry::new());
+                };
+                let mut samples = sample_log(&barrier, &sample_settings, i);
+                let mut metrics = MetricsRegistry::new();
+
+                while !has_stopped.load(Ordering::Relaxed) && !cancel_token.is_cancelled() {
+                    let due = pacer.wait().await;
+                    let op = planner.next_op();
+                    let evt = EventData {
+                        payload: op_payload(payloads.next_payload(), &sample_settings, &samples),
+                        event_type: planner.event_type(&op).unwrap_or_else(|| "test".to_string()),
+                        tags: vec![stream_name.clone()],
+                    };
+
+                    let operation_started = due.unwrap_or_else(Instant::now);
+                    let ok = adapter.append(vec![evt]).await.is_ok();
+                    let latency = operation_started.elapsed();
+                    samples.record("append", operation_started, latency, ok, Some(0));
+                    if ok {
+                        rec.record(latency);
+                        events_written += 1;
+                        worker_counter.store(events_written, Ordering::Relaxed);
+                    } else {
+                        metrics.increment("append_errors", 1);
+                    }
+
+                    offer_checkpoint(&mut checkpoint, &rec);
+
+                    planner.think(&barrier, &cancel_token).await;
+                }
+                (
+                    i,
+                    rec,
+                    events_written,
+                    start_offset,
+                    planner.digest(),
+                    samples.into_samples(),
+                    metrics,
+                )
+            });
+        }
+
+        let mut reader_set = JoinSet::new();
+        for (r, adapter) in reader_adapters.into_iter().enumerate() {
+            let i = writers + r;
+            let batch_size = read_config.batch_size as u64;
+            let consistency = read_config.consistency;
+            let tail = fan_out.tail;
+            let mut pacer = pacers[i].clone();
+            let mut planner = self.op_planner(i);
+            let mut checkpoint = self.checkpoint_handle(i);
+            let worker_counter = reader_counters[r].clone();
+            let has_stopped = has_stopped.clone();
+            let cancel_token = cancel_token.clone();
+            let barrier = barrier.clone();
+            let stream_name = stream_name.clone();
+            let writer_counters = writer_counters.clone();
+
+            let sample_settings = sample_settings.clone();
+            reader_set.spawn(async move {
+                let mut rec = LatencyRecorder::new();
+                let mut events_read = 0u64;
+                let mut reads = 0u64;
+                let mut empty_reads = 0u64;
+                let mut next_offset: Option<u64> = None;
+                let mut max_lag = 0u64;
+
+                let Ok(start_offset) = barrier.worker_ready().await else {
+                    // The run stopped before the measurement started
+                    return (i, rec, 0, 0, 0, Duration::ZERO, planner.digest(), OpSamples::default(), MetricsRegistry::new());
+                };
+                let mut samples = sample_log(&barrier, &sample_settings, i);
+                let mut metrics = MetricsRegistry::new();
+
+                while !has_stopped.load(Ordering::Relaxed) && !cancel_token.is_cancelled() {
+                    let due = pacer.wait().await;
+                    planner.next_op();
+                    let req = ReadRequest {
+                        stream: stream_name.clone(),
+                        from_offset: if tail { next_offset } else { None },
+                        limit: Some(batch_size),
+                        consistency,
+                    };
+
+                    let operation_started = due.unwrap_or_else(Instant::now);
+                    let result = adapter.read(req).await;
+                    let latency = operation_started.elapsed();
+                    rec.record(latency);
+                    samples.record("read", operation_started, latency, result.is_ok(), Some(0));
+                    reads += 1;
+
+                    match result {
+                        Ok(events) => {
+                            if events.is_empty() {
+                                empty_reads += 1;
+                            }
+                            if let Some(last) = events.last() {
+                                next_offset = Some(last.offset + 1);
+                            }
+                            events_read += events.len() as u64;
+                            worker_counter.store(events_read, Ordering::Relaxed);
+                        }
+                        Err(_) => metrics.increment("read_errors", 1),
+                    }
+
+                    // A tailing reader has consumed exactly `events_read` events of the stream,
+                    // so the rest of what the writers appended is its lag
+                    if tail {
+                        let written: u64 = writer_counters
+                            .iter()
+                            .map(|c| c.load(Ordering::Relaxed))
+                            .sum();
+                        max_lag = max_lag.max(written.saturating_sub(events_read));
+                    }
+
+                    offer_checkpoint(&mut checkpoint, &rec);
+
+                    planner.think(&barrier, &cancel_token).await;
+                }
+                if tail {
+                    metrics.set_gauge("max_tail_lag_events", max_lag as f64);
+                }
+                (
+                    i,
+                    rec,
+                    events_read,
+                    reads,
+                    empty_reads,
+                    start_offset,
+                    planner.digest(),
+                    samples.into_samples(),
+                    metrics,
+                )
+            });
+        }
+
+        let measurement_start = barrier.release().await?;
+        self.on_measurement_start(store, measurement_start)?;
+        println!("All {} writer and {} reader clients ready", writers, readers);
+
+        let throughput_handle = start_throughput_sampler(
+            reader_counters.clone(),
+            self.config.duration_seconds,
+            has_stopped.clone(),
+            cancel_token.clone(),
+            self.run_budget(),
+        )
+        .await;
+
+        let mut worker_start_offsets = vec![Duration::ZERO; writers + readers];
+        let mut op_digests = vec![WorkerDigest::default(); writers + readers];
+        let mut samples = OpSamples::default();
+        let mut extra = MetricsRegistry::new();
+
+        let mut writer_latency = LatencyRecorder::new();
+        let mut events_written = 0u64;
+        while let Some(res) = writer_set.join_next().await {
+            let (i, rec, written, start_offset, digest, worker_samples, worker_metrics) =
+                res.expect("join");
+            writer_latency.hist.add(&rec.hist)?;
+            events_written += written;
+            worker_start_offsets[i] = start_offset;
+            op_digests[i] = digest;
+            samples.merge(worker_samples);
+            extra.merge(&worker_metrics);
+        }
+
+        let mut reader_latency = LatencyRecorder::new();
+        let mut events_read = 0u64;
+        let mut reads = 0u64;
+        let mut empty_reads = 0u64;
+        while let Some(res) = reader_set.join_next().await {
+            let (
+                i,
+                rec,
+                read,
+                reader_reads,
+                reader_empty_reads,
+                start_offset,
+                digest,
+                worker_samples,
+                worker_metrics,
+            ) = res.expect("join");
+            reader_latency.hist.add(&rec.hist)?;
+            events_read += read;
+            reads += reader_reads;
+            empty_reads += reader_empty_reads;
+            worker_start_offsets[i] = start_offset;
+            op_digests[i] = digest;
+            samples.merge(worker_samples);
+            extra.merge(&worker_metrics);
+        }
+
+        let throughput_samples = throughput_handle.await.expect("throughput task");
+
+        let elapsed_s = match (throughput_samples.first(), throughput_samples.last()) {
+            (Some(first), Some(last)) if last.elapsed_s > first.elapsed_s => {
+                last.elapsed_s - first.elapsed_s
+            }
+            _ => self.config.duration_seconds as f64,
+        };
+        let read_eps_per_reader = events_read as f64 / elapsed_s.max(0.001) / readers as f64;
+        println!(
+            "Fan-out: writer p99 {:.2} ms, reader p99 {:.2} ms, {:.0} events/s per reader",
+            writer_latency.to_stats().p99_ms,
+            reader_latency.to_stats().p99_ms,
+            read_eps_per_reader
+        );
+
+        let stats = FanOutStats {
+            readers,
+            writer_latency: writer_latency.to_stats(),
+            reader_latency: reader_latency.to_stats(),
+            reads,
+            empty_reads,
+            read_eps_per_reader,
+        };
+
+        let mut overall = reader_latency;
+        overall.hist.add(&writer_latency.hist)?;
+
+        Ok(PerformanceOutput {
+            latency: overall,
+            events_written,
+            events_read,
+            throughput_samples,
+            worker_start_offsets,
+            contention: None,
+            fan_out: Some(stats),
+            op_digests,
+            samples,
+            extra,
+            bulk_windows: Vec::new(),
+            connections: Vec::new(),
+        })
+    }
+}
@@ -0,0 +1,71 @@
+use super::*;
+use crate::lint::{LintFinding, RunLimits};
+
+const MB: f64 = 1e6;
+
+impl PerformanceWorkload {
+    /// Check the config against what the host and `store` offer, for configurations that cannot
+    /// run as intended
+    pub fn lint(&self, store: &str, limits: &RunLimits) -> Vec<LintFinding> {
+        let name = &self.config.name;
+        let mut findings = Vec::new();
+
+        // Open-loop load is offered whatever the store manages, so its payload bytes alone are a
+        // lower bound on the traffic; local containers are reached over a bridge instead
+        let write_share = match self.config.mode {
+            PerformanceMode::Mixed => self
+                .config
+                .operations
+                .write
+                .as_ref()
+                .and_then(|w| w.probability)
+                .unwrap_or(0.5),
+            _ => 1.0,
+        };
+        let event_size = self.config.operations.write.as_ref().map_or(0, |w| w.event_size_bytes)
+            * self.write_batch_size();
+        let offered = self.config.target_rate_eps.map(|rate| rate * write_share * event_size as f64);
+        if let Some((offered, link)) = offered.zip(limits.network_bytes_per_second) {
+            if offered > link {
+                findings.push(LintFinding::warning(format!(
+                    "{}: target_rate_eps offers {:.1} MB/s of payload, more than the host's {:.1} MB/s \
+                     network link carries, so a store reached over it cannot keep up",
+                    name,
+                    offered / MB,
+                    link / MB
+                )));
+            }
+        }
+
+        // Every worker opens its own client, and the health monitor one more
+        let connections = self.worker_count() + 1;
+        if let Some(max) = limits.store_connections.filter(|max| connections > *max) {
+            findings.push(LintFinding::error(format!(
+                "{}: needs {} connections ({} workers and a health check) but {} accepts {}",
+                name,
+                connections,
+                connections - 1,
+                store,
+                max
+            )));
+        }
+        if let Some(max) = limits.client_connections.filter(|max| connections as u64 > *max) {
+            findings.push(LintFinding::error(format!(
+                "{}: needs {} connections but the open-file limit leaves room for {} (raise `ulimit -n`)",
+                name, connections, max
+            )));
+        }
+
+        if let Some(max) = self.config.budget.max_wall_clock {
+            if max.as_secs() < self.config.duration_seconds {
+                findings.push(LintFinding::warning(format!(
+                    "{}: max_wall_clock ({}s) ends the run before its {}s duration",
+                    name,
+                    max.as_secs(),
+                    self.config.duration_seconds
+                )));
+            }
+        }
+        findings
+    }
+}
@@ -0,0 +1,154 @@
+use super::*;
+
+impl PerformanceWorkload {
+    /// Readers repeatedly list the streams created in the setup phase. Latency is recorded per
+    /// listing call and throughput counts stream names returned (reported as events read).
+    pub(super) async fn execute_list_streams_workload(
+        &self,
+        store: &dyn StoreManager,
+        cancel_token: CancellationToken,
+        sample_settings: SampleSettings,
+    ) -> Result<PerformanceOutput> {
+        let readers = self.config.concurrency.readers.first();
+        let limit = self.config.list_streams.clone().unwrap_or_default().limit;
+        println!("Creating {} reader clients listing streams...", readers);
+
+        let reader_adapters = self.create_adapters(store, readers, "reader").await?;
+
+        // Fail fast for stores without a listing capability instead of recording errors
+        if let Some(adapter) = reader_adapters.first() {
+            adapter.list_streams(self.stream_names.prefix(), Some(1)).await?;
+        }
+
+        let mut set = JoinSet::new();
+
+        let worker_counters: Vec<Arc<AtomicU64>> = (0..readers)
+            .map(|_| Arc::new(AtomicU64::new(0)))
+            .collect();
+
+        let has_stopped = Arc::new(AtomicBool::new(false));
+        let limiters = self.rate_limiters(readers)?;
+        let barrier = StartBarrier::scheduled(readers, self.config.schedule.clone(), &cancel_token);
+
+        for (i, adapter) in reader_adapters.into_iter().enumerate() {
+            let limiter = limiters[i].clone();
+            let mut planner = self.op_planner(i);
+            let mut checkpoint = self.checkpoint_handle(i);
+            let worker_counter = worker_counters[i].clone();
+            let has_stopped = has_stopped.clone();
+            let cancel_token = cancel_token.clone();
+            let barrier = barrier.clone();
+            let stream_prefix = self.stream_names.prefix().to_string();
+
+            let sample_settings = sample_settings.clone();
+            set.spawn(async move {
+                let mut rec = LatencyRecorder::new();
+                let mut streams_listed = 0u64;
+                let mut calls = 0u64;
+
+                let Ok(start_offset) = barrier.worker_ready().await else {
+                    // The run stopped before the measurement started
+                    return (i, rec, 0, 0, Duration::ZERO, planner.digest(), OpSamples::default(), MetricsRegistry::new());
+                };
+                let mut samples = sample_log(&barrier, &sample_settings, i);
+                let mut metrics = MetricsRegistry::new();
+
+                while !has_stopped.load(Ordering::Relaxed) && !cancel_token.is_cancelled() {
+                    if let Some(limiter) = &limiter {
+                        limiter.acquire().await;
+                    }
+                    planner.next_op();
+
+                    let operation_started = Instant::now();
+                    let result = adapter.list_streams(&stream_prefix, limit).await;
+                    let latency = operation_started.elapsed();
+                    samples.record(
+                        "list_streams",
+                        operation_started,
+                        latency,
+                        result.is_ok(),
+                        None,
+                    );
+                    match result {
+                        Ok(streams) => {
+                            rec.record(latency);
+                            calls += 1;
+                            streams_listed += streams.len() as u64;
+                            worker_counter.store(streams_listed, Ordering::Relaxed);
+                        }
+                        Err(_) => metrics.increment("list_errors", 1),
+                    }
+
+                    offer_checkpoint(&mut checkpoint, &rec);
+
+                    planner.think(&barrier, &cancel_token).await;
+                }
+                (
+                    i,
+                    rec,
+                    streams_listed,
+                    calls,
+                    start_offset,
+                    planner.digest(),
+                    samples.into_samples(),
+                    metrics,
+                )
+            });
+        }
+
+        let measurement_start = barrier.release().await?;
+        self.on_measurement_start(store, measurement_start)?;
+        println!("All {} reader clients ready", readers);
+
+        let throughput_handle = start_throughput_sampler(
+            worker_counters.clone(),
+            self.config.duration_seconds,
+            has_stopped.clone(),
+            cancel_token.clone(),
+            self.run_budget(),
+        )
+        .await;
+
+        let mut overall = LatencyRecorder::new();
+        let mut streams_listed = 0u64;
+        let mut calls = 0u64;
+        let mut worker_start_offsets = vec![Duration::ZERO; readers];
+        let mut op_digests = vec![WorkerDigest::default(); readers];
+        let mut samples = OpSamples::default();
+        let mut extra = MetricsRegistry::new();
+        while let Some(res) = set.join_next().await {
+            let (i, rec, listed, reader_calls, start_offset, digest, worker_samples, worker_metrics) =
+                res.expect("join");
+            overall.hist.add(&rec.hist)?;
+            streams_listed += listed;
+            calls += reader_calls;
+            worker_start_offsets[i] = start_offset;
+            op_digests[i] = digest;
+            samples.merge(worker_samples);
+            extra.merge(&worker_metrics);
+        }
+
+        let throughput_samples = throughput_handle.await.expect("throughput task");
+        println!(
+            "Listed {} stream names in {} calls ({:.0} per call)",
+            streams_listed,
+            calls,
+            streams_listed as f64 / calls.max(1) as f64
+        );
+
+        Ok(PerformanceOutput {
+            latency: overall,
+            events_written: 0,
+            events_read: streams_listed,
+            throughput_samples,
+            worker_start_offsets,
+            contention: None,
+            fan_out: None,
+            op_digests,
+            samples,
+            extra,
+            bulk_windows: Vec::new(),
+            connections: Vec::new(),
+        })
+    }
+}
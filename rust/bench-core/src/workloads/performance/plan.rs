@@ -0,0 +1,275 @@
+use super::*;
+use super::event_types::EventTypes;
+use crate::determinism::OpDigest;
+use std::sync::atomic::AtomicUsize;
+
+/// Which random choices a worker makes per operation
+pub(super) enum OpMix {
+    /// No per-operation choice (only think time is random)
+    Fixed,
+    /// Pick one of `n` streams uniformly
+    Streams(usize),
+    /// Pick a stream, then read or write according to the configured probability
+    Mixed {
+        streams: usize,
+        write_prob: f64,
+        can_write: bool,
+        can_read: bool,
+        /// Fraction of reads redirected to a stream written during the run
+        non_empty_fraction: f64,
+    },
+}
+
+/// Next operation chosen by an [`OpPlanner`]
+pub(super) struct PlannedOp {
+    pub write: bool,
+    pub stream_idx: usize,
+    /// Set for reads that should go to an already written stream: the random draw to pick it
+    /// with from [`WrittenStreams`] (which streams exist depends on timing, so only the draw is
+    /// part of the digest)
+    pub non_empty_pick: Option<u64>,
+    /// Index of the event type to append with, when `event_types` is configured
+    pub event_type: Option<usize>,
+}
+
+/// Streams appended to during a run, shared by the workers of a mixed workload
+///
+/// Lock-free: a bitmap deduplicates the streams and each newly written stream takes the next
+/// slot of a fixed-size list, so readers can pick one uniformly.
+pub(super) struct WrittenStreams {
+    seen: Vec<AtomicU64>,
+    /// Stream index + 1 per slot (0 while the slot is being filled)
+    slots: Vec<AtomicUsize>,
+    len: AtomicUsize,
+}
+
+impl WrittenStreams {
+    pub(super) fn new(streams: usize) -> Self {
+        Self {
+            seen: (0..streams.div_ceil(64)).map(|_| AtomicU64::new(0)).collect(),
+            slots: (0..streams).map(|_| AtomicUsize::new(0)).collect(),
+            len: AtomicUsize::new(0),
+        }
+    }
+
+    pub(super) fn insert(&self, stream_idx: usize) {
+        let Some(word) = self.seen.get(stream_idx / 64) else {
+            return;
+        };
+        let bit = 1u64 << (stream_idx % 64);
+        if word.fetch_or(bit, Ordering::Relaxed) & bit == 0 {
+            let slot = self.len.fetch_add(1, Ordering::Relaxed);
+            self.slots[slot].store(stream_idx + 1, Ordering::Release);
+        }
+    }
+
+    /// A written stream chosen by `draw`, or `None` if nothing has been written yet
+    pub(super) fn pick(&self, draw: u64) -> Option<usize> {
+        let len = self.len.load(Ordering::Relaxed);
+        if len == 0 {
+            return None;
+        }
+        match self.slots[(draw % len as u64) as usize].load(Ordering::Acquire) {
+            0 => None,
+            stream => Some(stream - 1),
+        }
+    }
+}
+
+/// Per-worker source of all randomness in the worker loops
+///
+/// Worker loops and [`PerformanceWorkload::op_digests`] draw from the same planner, so the
+/// digests recorded during a run can be regenerated offline from the config and seed.
+pub(super) struct OpPlanner {
+    rng: StdRng,
+    mix: OpMix,
+    think_time: Option<ThinkTime>,
+    event_types: Option<Arc<EventTypes>>,
+    digest: OpDigest,
+    /// Length of the measurement window, which no think time extends
+    run_length: Duration,
+}
+
+impl OpPlanner {
+    pub(super) fn new(
+        seed: u64,
+        mix: OpMix,
+        think_time: Option<ThinkTime>,
+        event_types: Option<Arc<EventTypes>>,
+        run_length: Duration,
+    ) -> Self {
+        Self {
+            rng: StdRng::seed_from_u64(seed),
+            mix,
+            think_time,
+            event_types,
+            digest: OpDigest::new(),
+            run_length,
+        }
+    }
+
+    pub(super) fn next_op(&mut self) -> PlannedOp {
+        let op = match self.mix {
+            OpMix::Fixed => PlannedOp {
+                write: false,
+                stream_idx: 0,
+                non_empty_pick: None,
+                event_type: None,
+            },
+            OpMix::Streams(streams) => PlannedOp {
+                write: false,
+                stream_idx: self.rng.gen_range(0..streams),
+                non_empty_pick: None,
+                event_type: None,
+            },
+            OpMix::Mixed {
+                streams,
+                write_prob,
+                can_write,
+                can_read,
+                non_empty_fraction,
+            } => {
+                let stream_idx = self.rng.gen_range(0..streams);
+                let write = can_write && (!can_read || self.rng.gen_bool(write_prob));
+                let non_empty_pick = (!write
+                    && non_empty_fraction > 0.0
+                    && self.rng.gen_bool(non_empty_fraction))
+                .then(|| self.rng.gen());
+                PlannedOp {
+                    write,
+                    stream_idx,
+                    non_empty_pick,
+                    event_type: None,
+                }
+            }
+        };
+        let op = PlannedOp {
+            event_type: self.event_types.as_ref().map(|types| types.pick(&mut self.rng)),
+            ..op
+        };
+        self.digest.feed(op.write as u64);
+        self.digest.feed(op.stream_idx as u64);
+        if let Some(pick) = op.non_empty_pick {
+            self.digest.feed(pick);
+        }
+        if let Some(event_type) = op.event_type {
+            self.digest.feed(event_type as u64);
+        }
+        self.digest.end_op();
+        op
+    }
+
+    /// Name of the event type planned for `op`, if `event_types` is configured
+    pub(super) fn event_type(&self, op: &PlannedOp) -> Option<String> {
+        Some(self.event_types.as_ref()?.name(op.event_type?).to_string())
+    }
+
+    /// Draw the pause to take after the current operation, if think time is configured
+    pub(super) fn think_time(&mut self) -> Option<Duration> {
+        let pause = self.think_time.as_ref()?.sample(&mut self.rng);
+        self.digest.feed(pause.as_nanos() as u64);
+        Some(pause)
+    }
+
+    /// Take the think time after the current operation, if configured, waking early when the
+    /// run is cancelled or its measurement window ends
+    pub(super) async fn think(&mut self, barrier: &StartBarrier, cancel_token: &CancellationToken) {
+        let Some(pause) = self.think_time() else {
+            return;
+        };
+        let window_end = barrier.measurement_start().instant + self.run_length;
+        tokio::select! {
+            _ = tokio::time::sleep(pause.min(window_end.saturating_duration_since(Instant::now()))) => {}
+            _ = cancel_token.cancelled() => {}
+        }
+    }
+
+    pub(super) fn digest(&self) -> WorkerDigest {
+        self.digest.finish()
+    }
+}
+
+impl PerformanceWorkload {
+    /// Number of worker tasks the configured mode spawns
+    pub(super) fn worker_count(&self) -> usize {
+        let writers = self.config.concurrency.writers.first();
+        let readers = self.config.concurrency.readers.first();
+        match self.config.mode {
+            PerformanceMode::Write | PerformanceMode::Contention => writers,
+            PerformanceMode::Read | PerformanceMode::ListStreams => readers,
+            PerformanceMode::Mixed | PerformanceMode::FanOut => writers + readers,
+        }
+    }
+
+    /// Seed of each worker's RNG
+    pub fn worker_seeds(&self) -> Vec<u64> {
+        (0..self.worker_count())
+            .map(|i| self.seed + (i as u64))
+            .collect()
+    }
+
+    /// Number of streams read and mixed workers choose from
+    pub(super) fn prepopulated_streams(&self) -> usize {
+        match &self.config.setup {
+            Some(setup) => setup.prepopulate_streams.unwrap_or(setup.prepopulate_events) as usize,
+            None => 1,
+        }
+    }
+
+    /// Planner for worker `i` (workers are numbered writers first, then readers)
+    pub(super) fn op_planner(&self, i: usize) -> OpPlanner {
+        let prepopulated_streams = self.prepopulated_streams();
+        let mix = match self.config.mode {
+            PerformanceMode::Write | PerformanceMode::FanOut | PerformanceMode::ListStreams => {
+                OpMix::Fixed
+            }
+            PerformanceMode::Read => OpMix::Streams(prepopulated_streams),
+            PerformanceMode::Contention => OpMix::Streams(
+                self.config.contention.clone().unwrap_or_default().streams,
+            ),
+            PerformanceMode::Mixed => OpMix::Mixed {
+                streams: prepopulated_streams,
+                write_prob: self
+                    .config
+                    .operations
+                    .write
+                    .as_ref()
+                    .and_then(|w| w.probability)
+                    .unwrap_or(0.5),
+                can_write: i < self.config.concurrency.writers.first()
+                    && self.config.operations.write.is_some(),
+                can_read: self.config.operations.read.is_some(),
+                non_empty_fraction: self
+                    .config
+                    .operations
+                    .read
+                    .as_ref()
+                    .and_then(|r| r.non_empty_fraction)
+                    .unwrap_or(0.0),
+            },
+        };
+        OpPlanner::new(
+            self.seed + (i as u64),
+            mix,
+            self.config.think_time_ms.clone(),
+            self.event_types.clone(),
+            Duration::from_secs(self.config.duration_seconds),
+        )
+    }
+
+    /// Regenerate the per-worker digests without running anything. `ops_per_worker` gives the
+    /// number of operations each worker covered in the recorded run (at most [`DIGEST_OPS`]).
+    pub fn op_digests(&self, ops_per_worker: &[u64]) -> Vec<WorkerDigest> {
+        (0..self.worker_count())
+            .map(|i| {
+                let mut planner = self.op_planner(i);
+                let ops = ops_per_worker.get(i).copied().unwrap_or(DIGEST_OPS).min(DIGEST_OPS);
+                for _ in 0..ops {
+                    planner.next_op();
+                    planner.think_time();
+                }
+                planner.digest()
+            })
+            .collect()
+    }
+}
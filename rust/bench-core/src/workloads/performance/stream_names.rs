@@ -0,0 +1,149 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Odd multiplier spreading stream indexes over the UUID space (any odd number keeps the
+/// mapping one-to-one)
+const UUID_MIX: u128 = 0x9e37_79b9_7f4a_7c15_f39c_c060_5ced_c835;
+
+/// Shape of the part of a stream name that follows the run prefix
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StreamNameScheme {
+    /// The stream index: `stream-<run>-17`
+    #[default]
+    Indexed,
+    /// A UUID derived from the stream index: `stream-<run>-6f1c...`
+    Uuid,
+    /// `template` with `{t}` (tenant) and `{i}` (stream index) filled in:
+    /// `stream-<run>-tenant/3/order-17`
+    Hierarchical,
+}
+
+/// How stream names are built. Stores index and partition by stream name, so its shape can
+/// affect performance. Every name starts with `prefix` and an id unique to the run, so runs
+/// never share streams.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StreamNamingConfig {
+    #[serde(default)]
+    pub scheme: StreamNameScheme,
+    #[serde(default = "default_prefix")]
+    pub prefix: String,
+    /// Name template for the hierarchical scheme; must contain `{i}`
+    #[serde(default = "default_template")]
+    pub template: String,
+    /// Tenants the stream indexes are spread over (`{t}` is the index modulo this)
+    #[serde(default = "default_tenants")]
+    pub tenants: u64,
+    /// Pad names on the right with `_` to at least this many characters
+    #[serde(default)]
+    pub length: Option<usize>,
+}
+
+impl Default for StreamNamingConfig {
+    fn default() -> Self {
+        Self {
+            scheme: StreamNameScheme::default(),
+            prefix: default_prefix(),
+            template: default_template(),
+            tenants: default_tenants(),
+            length: None,
+        }
+    }
+}
+
+fn default_prefix() -> String {
+    "stream-".to_string()
+}
+
+fn default_template() -> String {
+    "tenant/{t}/order-{i}".to_string()
+}
+
+fn default_tenants() -> u64 {
+    10
+}
+
+impl StreamNamingConfig {
+    pub fn validate(&self) -> Result<()> {
+        if self.tenants == 0 {
+            anyhow::bail!("stream_naming tenants must be > 0");
+        }
+        if self.scheme == StreamNameScheme::Hierarchical && !self.template.contains("{i}") {
+            anyhow::bail!("stream_naming template must contain {{i}} so stream names are unique");
+        }
+        Ok(())
+    }
+}
+
+/// Stream names of one workload; cheap to clone into workers
+#[derive(Debug, Clone)]
+pub struct StreamNames {
+    config: Arc<StreamNamingConfig>,
+    run: Uuid,
+    prefix: Arc<str>,
+}
+
+impl StreamNames {
+    pub fn new(config: StreamNamingConfig, run: Uuid) -> Self {
+        let prefix = format!("{}{}-", config.prefix, run).into();
+        Self {
+            config: Arc::new(config),
+            run,
+            prefix,
+        }
+    }
+
+    /// Prefix shared by every stream of the run
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    /// Name of the stream with index `idx`
+    pub fn name(&self, idx: u64) -> String {
+        let mut name = match self.config.scheme {
+            StreamNameScheme::Indexed => format!("{}{}", self.prefix, idx),
+            StreamNameScheme::Uuid => {
+                let id = Uuid::from_u128(self.run.as_u128() ^ (idx as u128).wrapping_mul(UUID_MIX));
+                format!("{}{}", self.prefix, id)
+            }
+            StreamNameScheme::Hierarchical => format!(
+                "{}{}",
+                self.prefix,
+                self.config
+                    .template
+                    .replace("{t}", &(idx % self.config.tenants).to_string())
+                    .replace("{i}", &idx.to_string())
+            ),
+        };
+        if let Some(length) = self.config.length {
+            let padding = length.saturating_sub(name.len());
+            name.extend(std::iter::repeat_n('_', padding));
+        }
+        name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_names_per_scheme() {
+        let run = Uuid::nil();
+        let config = |scheme| StreamNamingConfig {
+            scheme,
+            tenants: 4,
+            length: Some(64),
+            ..Default::default()
+        };
+        let names = StreamNames::new(config(StreamNameScheme::Hierarchical), run);
+        assert!(names.name(17).starts_with(&format!("stream-{}-tenant/1/order-17_", run)));
+        assert_eq!(names.name(17).len(), 64);
+
+        let names = StreamNames::new(config(StreamNameScheme::Uuid), run);
+        assert_ne!(names.name(1), names.name(2));
+        assert_eq!(names.name(1), StreamNames::new(config(StreamNameScheme::Uuid), run).name(1));
+    }
+}
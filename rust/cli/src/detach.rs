@@ -0,0 +1,222 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::ErrorKind;
+use std::os::unix::process::CommandExt;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// Directory holding one `<run-id>/` directory (state file and log) per detached run
+const DETACHED_DIR: &str = "results/detached";
+/// Set in the detached process to the run id, so it can record how it ended
+const DETACHED_RUN_ENV: &str = "ES_BENCH_DETACHED_RUN";
+
+/// State file of a detached run
+#[derive(Debug, Serialize, Deserialize)]
+struct DetachedRun {
+    run_id: String,
+    /// Recorded by the run itself once it has started
+    #[serde(default)]
+    pid: Option<u32>,
+    args: Vec<String>,
+    started_at: DateTime<Utc>,
+    log: PathBuf,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    finished_at: Option<DateTime<Utc>>,
+    /// `None` after a successful run, otherwise the error it ended with
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+fn state_path(run_id: &str) -> PathBuf {
+    Path::new(DETACHED_DIR).join(run_id).join("state.json")
+}
+
+fn read_state(run_id: &str) -> Result<DetachedRun> {
+    let path = state_path(run_id);
+    let content = fs::read_to_string(&path).with_context(|| format!("No detached run {}", run_id))?;
+    serde_json::from_str(&content).with_context(|| format!("Failed to parse {}", path.display()))
+}
+
+fn write_state(run: &DetachedRun) -> Result<()> {
+    fs::write(state_path(&run.run_id), serde_json::to_string_pretty(run)?)?;
+    Ok(())
+}
+
+/// Whether a process with this pid exists
+fn is_alive(pid: u32) -> bool {
+    Command::new("kill")
+        .args(["-0", &pid.to_string()])
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+/// Create the directory of a new detached run, named after the current time with a suffix if
+/// another run started in the same second
+fn create_run_dir() -> Result<(String, PathBuf)> {
+    fs::create_dir_all(DETACHED_DIR)?;
+    let started = Utc::now().format("%Y-%m-%dT%H-%M-%S").to_string();
+    let mut attempt = 1;
+    loop {
+        let run_id = if attempt == 1 { started.clone() } else { format!("{}-{}", started, attempt) };
+        let run_dir = Path::new(DETACHED_DIR).join(&run_id);
+        match fs::create_dir(&run_dir) {
+            Ok(()) => return Ok((run_id, run_dir)),
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => attempt += 1,
+            Err(e) => return Err(e).with_context(|| format!("Failed to create {}", run_dir.display())),
+        }
+    }
+}
+
+/// Run this command again in the background without `--detach`, with its output going to a log
+/// file and in its own process group so it survives the terminal closing
+pub fn spawn_detached() -> Result<()> {
+    let (run_id, run_dir) = create_run_dir()?;
+    let log = run_dir.join("run.log");
+    let log_file = fs::File::create(&log)?;
+
+    // The state exists before the run does, so the run can always record its start and end
+    let args: Vec<String> = std::env::args().skip(1).filter(|arg| arg != "--detach").collect();
+    let mut run = DetachedRun {
+        run_id: run_id.clone(),
+        pid: None,
+        args,
+        started_at: Utc::now(),
+        log: log.clone(),
+        finished_at: None,
+        error: None,
+    };
+    write_state(&run)?;
+
+    let spawned = Command::new(std::env::current_exe()?)
+        .args(&run.args)
+        .env(DETACHED_RUN_ENV, &run_id)
+        .stdin(Stdio::null())
+        .stdout(log_file.try_clone()?)
+        .stderr(log_file)
+        .process_group(0)
+        .spawn();
+    let child = match spawned {
+        Ok(child) => child,
+        Err(e) => {
+            run.finished_at = Some(Utc::now());
+            run.error = Some(format!("Failed to start: {}", e));
+            write_state(&run)?;
+            return Err(e).context("Failed to start the detached run");
+        }
+    };
+    println!("Started detached run {} (pid {})", run_id, child.id());
+    println!("  Log: {}", log.display());
+    println!("  Check on it with `es-bench status`, stop it with `es-bench stop {}`", run_id);
+    Ok(())
+}
+
+/// In a detached run, update its state file with `update`; `what` names the update in the
+/// warning if it fails
+fn update_own_state(what: &str, update: impl FnOnce(&mut DetachedRun)) {
+    let Ok(run_id) = std::env::var(DETACHED_RUN_ENV) else {
+        return;
+    };
+    let result = read_state(&run_id).and_then(|mut run| {
+        update(&mut run);
+        write_state(&run)
+    });
+    if let Err(e) = result {
+        eprintln!("Failed to record the {} of detached run {}: {:#}", what, run_id, e);
+    }
+}
+
+/// In a detached run, record in its state file that it has started
+pub fn record_start() {
+    update_own_state("start", |run| run.pid = Some(std::process::id()));
+}
+
+/// In a detached run, record in its state file that it has ended
+pub fn record_exit(result: &Result<()>) {
+    update_own_state("end", |run| {
+        run.finished_at = Some(Utc::now());
+        run.error = result.as_ref().err().map(|e| format!("{:#}", e));
+    });
+}
+
+/// The session a run's log says it created
+fn session_of(log: &Path) -> Option<String> {
+    let content = fs::read_to_string(log).ok()?;
+    content
+        .lines()
+        .rev()
+        .find_map(|line| line.strip_prefix("Session ID: "))
+        .map(|id| id.trim().to_string())
+}
+
+/// List the detached runs and whether they are still going
+pub fn print_status() -> Result<()> {
+    let mut run_ids: Vec<String> = match fs::read_dir(DETACHED_DIR) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().join("state.json").is_file())
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+    if run_ids.is_empty() {
+        println!("No detached runs.");
+        return Ok(());
+    }
+    run_ids.sort();
+
+    println!("{:<22} {:>8} {:<10} {:>10}  {:<22} Args", "Run", "PID", "Status", "Elapsed", "Session");
+    for run_id in run_ids {
+        let run = read_state(&run_id)?;
+        let (status, ended) = match (&run.finished_at, &run.error) {
+            (Some(finished), None) => ("finished", Some(*finished)),
+            (Some(finished), Some(_)) => ("failed", Some(*finished)),
+            (None, _) if run.pid.is_none() => ("starting", Some(Utc::now())),
+            (None, _) if run.pid.is_some_and(is_alive) => ("running", Some(Utc::now())),
+            // Killed without a chance to record its end
+            (None, _) => ("died", None),
+        };
+        let elapsed = match ended {
+            Some(ended) => {
+                let s = (ended - run.started_at).num_seconds().max(0);
+                format!("{}h{:02}m{:02}s", s / 3600, s / 60 % 60, s % 60)
+            }
+            None => "-".to_string(),
+        };
+        println!(
+            "{:<22} {:>8} {:<10} {:>10}  {:<22} {}",
+            run.run_id,
+            run.pid.map_or_else(|| "-".to_string(), |pid| pid.to_string()),
+            status,
+            elapsed,
+            session_of(&run.log).unwrap_or_else(|| "-".to_string()),
+            run.args.join(" ")
+        );
+        if let Some(error) = &run.error {
+            println!("    {}", error);
+        }
+    }
+    Ok(())
+}
+
+/// Interrupt a detached run as Ctrl+C would, so it finalizes its results
+pub fn stop(run_id: &str) -> Result<()> {
+    let run = read_state(run_id)?;
+    let pid = match run.pid {
+        None if run.finished_at.is_none() => anyhow::bail!("Detached run {} is still starting", run_id),
+        Some(pid) if run.finished_at.is_none() && is_alive(pid) => pid,
+        _ => anyhow::bail!("Detached run {} is not running", run_id),
+    };
+    let status = Command::new("kill")
+        .args(["-INT", &pid.to_string()])
+        .status()
+        .context("Failed to run kill")?;
+    if !status.success() {
+        anyhow::bail!("Failed to signal detached run {} (pid {})", run_id, pid);
+    }
+    println!("Sent interrupt to detached run {} (pid {}); it stops after finalizing its results", run_id, pid);
+    println!("  Log: {}", run.log.display());
+    Ok(())
+}
@@ -0,0 +1,144 @@
+use anyhow::Result;
+use bench_core::{LiveStats, LiveWindow};
+use ratatui::backend::CrosstermBackend;
+use ratatui::crossterm::cursor::{Hide, Show};
+use ratatui::crossterm::execute;
+use ratatui::crossterm::terminal::{EnterAlternateScreen, LeaveAlternateScreen};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Borders, Paragraph, Sparkline};
+use ratatui::{Frame, Terminal};
+use std::collections::VecDeque;
+use std::io::Stdout;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+
+/// Time between refreshes of the dashboard
+const REFRESH: Duration = Duration::from_secs(1);
+/// Refreshes kept in the throughput and p99 history
+const HISTORY: usize = 300;
+
+/// Dashboard of a single run, drawn on the terminal's alternate screen until it is finished
+pub struct Dashboard {
+    stop: CancellationToken,
+    task: JoinHandle<()>,
+}
+
+impl Dashboard {
+    /// Take over the terminal and redraw the run's rolling throughput, latency, errors and
+    /// container usage every second. Raw mode stays off, so Ctrl+C still interrupts the run.
+    pub fn start(title: String, live: Arc<LiveStats>) -> Result<Self> {
+        let mut stdout = std::io::stdout();
+        execute!(stdout, EnterAlternateScreen, Hide)?;
+        let mut terminal = Terminal::new(CrosstermBackend::new(stdout))?;
+        let stop = CancellationToken::new();
+        let stopped = stop.clone();
+        let task = tokio::spawn(async move {
+            let mut history = History::default();
+            let mut ticks = tokio::time::interval(REFRESH);
+            ticks.tick().await;
+            loop {
+                tokio::select! {
+                    _ = ticks.tick() => {}
+                    _ = stopped.cancelled() => break,
+                }
+                history.push(live.take_window());
+                // Redraw from scratch, so anything the run printed meanwhile is wiped
+                if terminal.clear().is_err() || terminal.draw(|frame| draw(frame, &title, &history)).is_err() {
+                    break;
+                }
+            }
+            restore(&mut terminal);
+        });
+        Ok(Self { stop, task })
+    }
+
+    /// Stop redrawing and give the terminal back
+    pub async fn finish(self) {
+        self.stop.cancel();
+        let _ = self.task.await;
+    }
+}
+
+fn restore(terminal: &mut Terminal<CrosstermBackend<Stdout>>) {
+    let _ = execute!(terminal.backend_mut(), LeaveAlternateScreen, Show);
+}
+
+/// The latest windows of a run, oldest first
+#[derive(Default)]
+struct History {
+    windows: VecDeque<LiveWindow>,
+}
+
+impl History {
+    fn push(&mut self, window: LiveWindow) {
+        if self.windows.len() == HISTORY {
+            self.windows.pop_front();
+        }
+        self.windows.push_back(window);
+    }
+
+    /// One value per window, scaled to integers for a sparkline
+    fn series(&self, value: impl Fn(&LiveWindow) -> f64) -> Vec<u64> {
+        self.windows.iter().map(|w| value(w).round() as u64).collect()
+    }
+}
+
+fn draw(frame: &mut Frame, title: &str, history: &History) {
+    let [header, throughput, latency] =
+        Layout::vertical([Constraint::Length(4), Constraint::Fill(1), Constraint::Fill(1)]).areas(frame.area());
+    let Some(latest) = history.windows.back() else {
+        return;
+    };
+    let ms = |value: Option<f64>| value.map_or("-".to_string(), |v| format!("{:.2} ms", v));
+    let lines = vec![
+        Line::from(format!(
+            "{:>10.0} ops/s   p50 {:>10}   p99 {:>10}   errors {:.0}/s ({:.2}%)",
+            latest.ops_per_s,
+            ms(latest.p50_ms),
+            ms(latest.p99_ms),
+            latest.errors_per_s,
+            latest.error_rate * 100.0
+        )),
+        Line::from(format!(
+            "store CPU {}   memory {}",
+            latest.cpu_percent.map_or("-".to_string(), |cpu| format!("{:.0}%", cpu)),
+            latest.memory_bytes.map_or("-".to_string(), |bytes| format!("{:.0} MB", bytes as f64 / 1_000_000.0))
+        )),
+    ];
+    frame.render_widget(
+        Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(format!(
+            " {} · {}s · Ctrl+C to stop ",
+            title,
+            latest.elapsed.as_secs()
+        ))),
+        header,
+    );
+
+    // Newest on the right, as many windows as fit
+    let fit = |data: Vec<u64>, width: u16| {
+        let skip = data.len().saturating_sub(width.saturating_sub(2) as usize);
+        data[skip..].to_vec()
+    };
+    let ops = fit(history.series(|w| w.ops_per_s), throughput.width);
+    let peak_ops = ops.iter().max().copied().unwrap_or(0);
+    frame.render_widget(
+        Sparkline::default()
+            .block(Block::default().borders(Borders::ALL).title(format!(" ops/s (peak {}) ", peak_ops)))
+            .style(Style::default().fg(Color::Green))
+            .data(&ops),
+        throughput,
+    );
+    let p99 = fit(history.series(|w| w.p99_ms.unwrap_or(0.0) * 1000.0), latency.width);
+    let peak_p99 = p99.iter().max().copied().unwrap_or(0) as f64 / 1000.0;
+    frame.render_widget(
+        Sparkline::default()
+            .block(Block::default().borders(Borders::ALL).title(format!(" p99 (peak {:.2} ms) ", peak_p99)))
+            .style(Style::default().fg(Color::Yellow))
+            .data(&p99),
+        latency,
+    );
+}
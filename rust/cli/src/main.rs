@@ -1,22 +1,46 @@
-use anyhow::Result;
+use analytics::{compare_results, compare_timelines, compute_trends, plot_run, write_ci_artifacts, PlotFormat};
+use anyhow::{Context, Result};
 use bench_core::{
-    collect_environment_info, execute_run, get_git_commit_hash, SessionMetadata,
-    StoreManagerFactory, WorkloadFactory,
+    best_configurations, collect_environment_info, execute_connection_stress, execute_dual_write, execute_slow_consumer, execute_migration, execute_startup_bench, execute_stream_growth, execute_stream_position, execute_run_with_options,
+    get_git_commit_hash, merge_runs, partition_cpus, run_order, store_runs, BetweenRuns, CheckpointOptions, CpuSet, LedgerOptions, ResourceLimits, OptionSweepResult, RunOptions, StoreRun, DualWriteConfig, MergeMode, WorkerDigest,
+    MigrationConfig, RunMetrics, SessionMetadata, StartSchedule, StoreManagerFactory, StartupBenchConfig, ConnectionStressConfig, SlowConsumerConfig, StreamGrowthConfig, StreamPositionConfig, Workload, WorkloadFactory,
+    BackfillTailConfig, CatchupSubscriptionConfig, GlobalScanConfig, LintLevel, MicroBenchConfig, OptionSweep, RatioRun, ReadWriteMix, RunLimits, VersionRun, execute_backfill_tail, execute_catchup_subscription, execute_global_scan, execute_micro_bench, version_deltas,
+    parse_sink, FilesystemSink, ResultSink, RunRecord, PropagationConfig, execute_propagation, upload_dir, S3Location,
+    execute_composition, PerformanceWorkload, RetentionConfig, RetentionPolicy, execute_retention, LiveStats,
 };
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use clap::{Parser, Subcommand};
+use futures::future::join_all;
 use rand::Rng;
+use std::collections::{BTreeMap, HashSet};
 use std::fs;
+use std::io::IsTerminal;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use suite::{with_stores, unique_name, EntryStatus, Suite, SuiteEntry, SuiteIndex, SuiteWorkflow, SUITES_DIR};
 use tokio::runtime::Runtime;
 use tokio_util::sync::CancellationToken;
-use tracing_subscriber::EnvFilter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Layer};
+
+mod detach;
+mod live;
+#[cfg(feature = "otel")]
+mod otel;
+mod suite;
 
 #[derive(Parser, Debug)]
 #[command(name = "es-bench", version, about = "Event Store Benchmark Suite CLI")]
 struct Cli {
     #[arg(long, default_value = "info")]
     log: String,
+    /// Export a span per run and per operation to this OTLP/HTTP collector, e.g.
+    /// `http://localhost:4318`
+    #[cfg(feature = "otel")]
+    #[arg(long, global = true)]
+    otlp_endpoint: Option<String>,
     #[command(subcommand)]
     command: Commands,
 }
@@ -34,6 +58,524 @@ enum Commands {
         /// Optional directory to store benchmark data (enables bind mounts)
         #[arg(long)]
         data_dir: Option<String>,
+        /// Instead of running, regenerate the operation sequences for this config and check them
+        /// against the digests recorded in the given session directory (uses the session's seed
+        /// unless --seed is given)
+        #[arg(long, value_name = "SESSION_DIR")]
+        verify_determinism: Option<PathBuf>,
+        /// Run up to this many stores at once, each with its containers pinned to a disjoint
+        /// set of host CPUs
+        #[arg(long, default_value_t = 1)]
+        parallel: usize,
+        /// Also write benchmark-summary.md and github-action-benchmark JSON files for the
+        /// session into this directory
+        #[arg(long, value_name = "DIR")]
+        ci_output: Option<PathBuf>,
+        /// Write an intermediate summary snapshot of each run every this many minutes
+        /// (`checkpoints/` in the run's results directory), for long soak runs
+        #[arg(long, value_name = "MINUTES")]
+        checkpoint_minutes: Option<f64>,
+        /// Idle this many seconds between consecutive runs, so the host settles before the next
+        #[arg(long, value_name = "SECONDS", default_value_t = 0.0)]
+        cooldown_seconds: f64,
+        /// Drop the host page cache before every run (Linux, needs root), so earlier runs
+        /// don't warm caches for later ones
+        #[arg(long)]
+        drop_caches: bool,
+        /// Run the (workload, store) combinations in an order shuffled by the seed, recorded in
+        /// the session's `matrix.json`, so host drift doesn't always penalize the same store
+        #[arg(long)]
+        shuffle: bool,
+        /// Record this share of acknowledged appends in each run's `ledger.jsonl` and read them
+        /// back from the store once the workload is done (e.g. 0.01)
+        #[arg(long, value_name = "RATE")]
+        ledger_sample_rate: Option<f64>,
+        /// Run in the background with output going to `results/detached/<run-id>/run.log`, for
+        /// long soak runs; manage it with `status` and `stop`
+        #[arg(long)]
+        detach: bool,
+        /// Run even if the pre-run checks find the config impossible to satisfy on this host or
+        /// store (e.g. more workers than the store accepts connections)
+        #[arg(long)]
+        force: bool,
+        /// Run on a paused tokio clock that jumps ahead whenever all tasks wait on timers, so
+        /// a 60-second workload finishes in moments (dummy store only; latencies are the
+        /// simulated ones), for fast deterministic checks of phases, pacing and sampling
+        #[arg(long)]
+        virtual_time: bool,
+        /// Run the mixed workload once per `read:write` operation ratio (e.g.
+        /// `100:0,90:10,70:30,50:50,10:90`), keeping the total worker count, and write how
+        /// each store's throughput and latency shift with the mix to `ratio_sweep.json`
+        #[arg(long)]
+        ratio_sweep: Option<String>,
+        /// Also send each run's results to this sink: `sqlite:<path>` adds a row to a catalog
+        /// database, an `http://` or `https://` URL receives the summary as a JSON POST
+        /// (repeatable; the results directory is always written)
+        #[arg(long = "sink", value_name = "SINK")]
+        sinks: Vec<String>,
+        /// Once the session is complete, upload its directory as a `.tar.gz` to
+        /// `s3://bucket/prefix` (credentials, region and any `AWS_ENDPOINT_URL` from the
+        /// standard AWS environment variables), retrying failed attempts
+        #[arg(long, value_name = "S3_URL")]
+        upload: Option<String>,
+        /// Commit of the store under test (e.g. a locally built image), recorded in every
+        /// run's summary.json so `trend` can map results back to store commits
+        #[arg(long, value_name = "SHA")]
+        store_rev: Option<String>,
+        /// Limit each store container to this many CPUs (e.g. 2 or 1.5), overriding the
+        /// workload config's `resources.cpus`
+        #[arg(long)]
+        cpus: Option<f64>,
+        /// Limit each store container's memory (e.g. 4g or 512m), overriding the workload
+        /// config's `resources.memory`
+        #[arg(long)]
+        memory: Option<String>,
+        /// Show a dashboard of each run's rolling throughput, p50/p99, error rate and store
+        /// container CPU/memory, refreshed every second, instead of the run's progress output
+        #[arg(long, conflicts_with = "detach")]
+        live: bool,
+    },
+    /// List the runs started with `run --detach` and whether they are still going
+    Status,
+    /// Stop a run started with `run --detach`, finalizing its results as Ctrl+C would
+    Stop {
+        /// Run id printed by `run --detach`
+        run_id: String,
+    },
+    /// Compare stores at equalized load: find each store's max rate, then re-run every store at a
+    /// percentage of its own max and at a common absolute rate
+    Equalized {
+        /// Path to workload YAML config file (must not be a sweep)
+        #[arg(long)]
+        config: PathBuf,
+        /// Percentage of each store's own max rate for the "at its best" runs
+        #[arg(long, default_value_t = 60.0)]
+        percent: f64,
+        /// Common operation rate for the "at equal load" runs (default: `percent` of the slowest store's max)
+        #[arg(long)]
+        absolute_rate: Option<f64>,
+        /// Random seed (defaults to random value)
+        #[arg(long)]
+        seed: Option<u64>,
+        /// Optional directory to store benchmark data (enables bind mounts)
+        #[arg(long)]
+        data_dir: Option<String>,
+    },
+    /// Run a workload against several image tags of one store and report each tag's throughput
+    /// and latency change relative to the first tag
+    CompareVersions {
+        /// Path to workload YAML config file (its `stores` are ignored)
+        #[arg(long)]
+        config: PathBuf,
+        /// Store name (must accept the `image_tag` store option)
+        #[arg(long)]
+        store: String,
+        /// Comma-separated image tags; the first is the baseline
+        #[arg(long, value_delimiter = ',', required = true)]
+        tags: Vec<String>,
+        /// Random seed (defaults to random value)
+        #[arg(long)]
+        seed: Option<u64>,
+        /// Optional directory to store benchmark data (enables bind mounts)
+        #[arg(long)]
+        data_dir: Option<String>,
+    },
+    /// Measure store-to-store copy throughput: seed one store, then copy all events into another
+    MigrateBench {
+        /// Source store name
+        #[arg(long)]
+        from: String,
+        /// Target store name
+        #[arg(long)]
+        to: String,
+        /// Number of events seeded into the source store
+        #[arg(long, default_value_t = 100_000)]
+        events: u64,
+        /// Number of streams the seeded events are spread across
+        #[arg(long, default_value_t = 1_000)]
+        streams: u64,
+        /// Payload size of each seeded event in bytes
+        #[arg(long, default_value_t = 256)]
+        event_size: usize,
+        /// Events per read page and per append
+        #[arg(long, default_value_t = 500)]
+        batch_size: u64,
+        /// Number of concurrent copy tasks
+        #[arg(long, default_value_t = 4)]
+        concurrency: usize,
+        /// Optional directory to store benchmark data (enables bind mounts)
+        #[arg(long)]
+        data_dir: Option<String>,
+        /// Record this share of the seeded events in `ledger.jsonl` and check the target holds
+        /// them after the copy (e.g. 0.01)
+        #[arg(long, value_name = "RATE")]
+        ledger_sample_rate: Option<f64>,
+    },
+    /// Append to a single stream until it is long and report append latency by stream length,
+    /// showing whether a store's append cost grows with the stream
+    StreamGrowth {
+        /// Store name
+        #[arg(long)]
+        store: String,
+        /// Number of events the stream grows to
+        #[arg(long, default_value_t = 100_000)]
+        events: u64,
+        /// Events per append
+        #[arg(long, default_value_t = 1)]
+        batch_size: u64,
+        /// Payload size of each event in bytes
+        #[arg(long, default_value_t = 256)]
+        event_size: usize,
+        /// Number of stream length ranges latencies are reported for
+        #[arg(long, default_value_t = 20)]
+        buckets: u64,
+        /// Optional directory to store benchmark data (enables bind mounts)
+        #[arg(long)]
+        data_dir: Option<String>,
+    },
+    /// Prepopulate long streams and report read latency by the offset pages start at, showing
+    /// how a store's reads depend on the position in the stream
+    StreamPosition {
+        /// Store name
+        #[arg(long)]
+        store: String,
+        /// Length of each stream
+        #[arg(long, default_value_t = 1_000_000)]
+        events: u64,
+        /// Number of streams prepopulated (and read from at random)
+        #[arg(long, default_value_t = 1)]
+        streams: u64,
+        /// Payload size of each event in bytes
+        #[arg(long, default_value_t = 256)]
+        event_size: usize,
+        /// Events per read
+        #[arg(long, default_value_t = 100)]
+        page_size: u64,
+        /// Number of reads measured
+        #[arg(long, default_value_t = 10_000)]
+        reads: u64,
+        /// Number of offset ranges latencies are reported for
+        #[arg(long, default_value_t = 10)]
+        buckets: u64,
+        /// Random seed for the read offsets (defaults to random value)
+        #[arg(long)]
+        seed: Option<u64>,
+        /// Optional directory to store benchmark data (enables bind mounts)
+        #[arg(long)]
+        data_dir: Option<String>,
+    },
+    /// Set a retention policy (max count, max age or truncate-before) on prepopulated streams,
+    /// then append and read them at a steady pace, optionally starting a scavenge partway
+    /// through, and report latency before, during and after the scavenge
+    Retention {
+        /// Store name (one supporting retention policies, e.g. kurrentdb)
+        #[arg(long)]
+        store: String,
+        /// Number of streams
+        #[arg(long, default_value_t = 10)]
+        streams: u64,
+        /// Events written to each stream before the measurement
+        #[arg(long, default_value_t = 10_000)]
+        events_per_stream: u64,
+        /// Payload size of each event in bytes
+        #[arg(long, default_value_t = 256)]
+        event_size: usize,
+        /// Keep only each stream's last N events
+        #[arg(long)]
+        max_count: Option<u64>,
+        /// Drop events older than this many seconds
+        #[arg(long)]
+        max_age_s: Option<u64>,
+        /// Drop the events before this stream offset
+        #[arg(long)]
+        truncate_before: Option<u64>,
+        /// Measurement duration in seconds
+        #[arg(long, default_value_t = 60)]
+        duration: u64,
+        /// Appends per second during the measurement
+        #[arg(long, default_value_t = 200.0)]
+        write_rate: f64,
+        /// Events per read
+        #[arg(long, default_value_t = 100)]
+        page_size: u64,
+        /// Seconds into the measurement a scavenge is started (no scavenge if unset)
+        #[arg(long)]
+        scavenge_at: Option<f64>,
+        /// Optional directory to store benchmark data (enables bind mounts)
+        #[arg(long)]
+        data_dir: Option<String>,
+    },
+    /// Run a modest write load while opening more and more idle or low-rate client connections,
+    /// reporting write latency and store memory per number of open connections
+    ConnectionStress {
+        /// Store name
+        #[arg(long)]
+        store: String,
+        /// Extra connections open at the last level
+        #[arg(long, default_value_t = 2000)]
+        connections: usize,
+        /// Levels after the baseline without extra connections
+        #[arg(long, default_value_t = 4)]
+        steps: usize,
+        /// Measurement time per level in seconds
+        #[arg(long, default_value_t = 30)]
+        phase_seconds: u64,
+        /// Number of writers
+        #[arg(long, default_value_t = 4)]
+        writers: usize,
+        /// Total appends per second of the writers
+        #[arg(long, default_value_t = 200.0)]
+        write_rate: f64,
+        /// Payload size of each event in bytes
+        #[arg(long, default_value_t = 256)]
+        event_size: usize,
+        /// Have each extra connection read once per this many seconds (idle if not given)
+        #[arg(long, value_name = "SECONDS")]
+        read_interval: Option<f64>,
+        /// Optional directory to store benchmark data (enables bind mounts)
+        #[arg(long)]
+        data_dir: Option<String>,
+    },
+    /// Start and stop a store's container repeatedly and report the distributions of startup
+    /// time and time to the first successful append, for warm and (optionally) cold starts
+    StartupBench {
+        /// Store name
+        #[arg(long)]
+        store: String,
+        /// Number of warm starts (image already present)
+        #[arg(long, default_value_t = 5)]
+        iterations: usize,
+        /// Number of cold starts after the warm ones; each REMOVES the store's image from the
+        /// local Docker first, so it is pulled again
+        #[arg(long, default_value_t = 0)]
+        cold_iterations: usize,
+        /// Optional directory to store benchmark data (enables bind mounts)
+        #[arg(long)]
+        data_dir: Option<String>,
+    },
+    /// Time each adapter primitive in isolation (ping, single append, batch append of 100,
+    /// read of 100 and of 10,000 events) with criterion-style statistics
+    Micro {
+        /// Store name
+        #[arg(long)]
+        store: String,
+        /// Measured calls per primitive
+        #[arg(long, default_value_t = 100)]
+        iterations: usize,
+        /// Unmeasured calls per primitive before measuring
+        #[arg(long, default_value_t = 10)]
+        warmup: usize,
+        #[arg(long, default_value_t = 256)]
+        event_size: usize,
+        /// Random seed for the bootstrap (defaults to random value)
+        #[arg(long)]
+        seed: Option<u64>,
+        /// Optional directory to store benchmark data (enables bind mounts)
+        #[arg(long)]
+        data_dir: Option<String>,
+    },
+    /// Append sequence-numbered events at a steady rate while readers tail the same streams,
+    /// and report how long each event takes from its acknowledgement to showing up in a read
+    Propagation {
+        /// Store name
+        #[arg(long)]
+        store: String,
+        /// Streams written and tailed concurrently
+        #[arg(long, default_value_t = 4)]
+        streams: usize,
+        #[arg(long, default_value_t = 1000)]
+        events_per_stream: u64,
+        /// Appends per second over all streams
+        #[arg(long, default_value_t = 200.0)]
+        write_rate: f64,
+        /// Milliseconds a reader waits after a read returning nothing new
+        #[arg(long, default_value_t = 10)]
+        poll_interval_ms: u64,
+        #[arg(long, default_value_t = 256)]
+        event_size: usize,
+        /// Optional directory to store benchmark data (enables bind mounts)
+        #[arg(long)]
+        data_dir: Option<String>,
+    },
+    /// Time a subscription replaying a prepopulated stream from the start, then the lag with
+    /// which it delivers events appended at a steady rate (stores with `subscribe`)
+    CatchupSubscription {
+        /// Store name
+        #[arg(long)]
+        store: String,
+        /// Events stored before subscribing
+        #[arg(long, default_value_t = 100_000)]
+        events: u64,
+        /// Events appended once the subscription has caught up
+        #[arg(long, default_value_t = 1000)]
+        live_events: u64,
+        /// Appends per second once caught up
+        #[arg(long, default_value_t = 100.0)]
+        live_rate: f64,
+        #[arg(long, default_value_t = 256)]
+        event_size: usize,
+        /// Optional directory to store benchmark data (enables bind mounts)
+        #[arg(long)]
+        data_dir: Option<String>,
+    },
+    /// Subscribe from the start of a prepopulated backlog while appending to it at a steady
+    /// rate, and report the catch-up time, the switchover to the live appends and the lag once
+    /// caught up (stores with `subscribe`)
+    BackfillTail {
+        /// Store name
+        #[arg(long)]
+        store: String,
+        /// Events stored before the consumer starts
+        #[arg(long, default_value_t = 100_000)]
+        backlog_events: u64,
+        /// Appends per second from when the consumer starts
+        #[arg(long, default_value_t = 500.0)]
+        live_rate: f64,
+        /// Seconds of live appends
+        #[arg(long, default_value_t = 30)]
+        live_duration: u64,
+        #[arg(long, default_value_t = 256)]
+        event_size: usize,
+        /// Optional directory to store benchmark data (enables bind mounts)
+        #[arg(long)]
+        data_dir: Option<String>,
+    },
+    /// Append at a steady rate while several subscribers follow the stream, first all keeping
+    /// up and then with one pausing after each event, and report whether the slow subscriber
+    /// degrades the writer or the other subscribers (stores with `subscribe`)
+    SlowConsumer {
+        /// Store name
+        #[arg(long)]
+        store: String,
+        /// Subscribers following the stream, including the slow one
+        #[arg(long, default_value_t = 3)]
+        subscribers: usize,
+        /// Pause of the slow subscriber after each event, in milliseconds
+        #[arg(long, default_value_t = 100)]
+        slow_delay_ms: u64,
+        /// Appends per second
+        #[arg(long, default_value_t = 200.0)]
+        write_rate: f64,
+        /// Seconds of appends in each phase
+        #[arg(long, default_value_t = 30)]
+        duration: u64,
+        #[arg(long, default_value_t = 256)]
+        event_size: usize,
+        /// Optional directory to store benchmark data (enables bind mounts)
+        #[arg(long)]
+        data_dir: Option<String>,
+    },
+    /// Time a replay of the store's whole global log, read in pages from the start after
+    /// prepopulating it (stores with `read_all`)
+    GlobalScan {
+        /// Store name
+        #[arg(long)]
+        store: String,
+        /// Events stored before the scan
+        #[arg(long, default_value_t = 1_000_000)]
+        events: u64,
+        /// Streams the events are spread over
+        #[arg(long, default_value_t = 1000)]
+        streams: u64,
+        /// Events per read of the global log
+        #[arg(long, default_value_t = 1000)]
+        page_size: u64,
+        /// Read the log as one stream instead of in pages
+        #[arg(long, conflicts_with = "page_size")]
+        streamed: bool,
+        #[arg(long, default_value_t = 256)]
+        event_size: usize,
+        /// Optional directory to store benchmark data (enables bind mounts)
+        #[arg(long)]
+        data_dir: Option<String>,
+    },
+    /// Compare two stores by issuing every append to both concurrently (paired latency samples)
+    DualWrite {
+        /// First store name
+        #[arg(long)]
+        a: String,
+        /// Second store name
+        #[arg(long)]
+        b: String,
+        /// Measurement duration in seconds
+        #[arg(long, default_value_t = 60)]
+        duration: u64,
+        /// Number of concurrent writers (each with one client per store)
+        #[arg(long, default_value_t = 4)]
+        writers: usize,
+        /// Payload size of each event in bytes
+        #[arg(long, default_value_t = 256)]
+        event_size: usize,
+        /// Optional directory to store benchmark data (enables bind mounts)
+        #[arg(long)]
+        data_dir: Option<String>,
+        /// Start measuring at this wall-clock time (RFC 3339), e.g. to line up with a paired run
+        #[arg(long)]
+        start_at: Option<DateTime<Utc>>,
+        /// Start measuring at the next full minute (after --start-at, if given)
+        #[arg(long)]
+        align_to_minute: bool,
+    },
+    /// Run a foreground and a background workload at the same time against one store (e.g.
+    /// steady writes alongside a periodic bulk read) and report each workload's metrics
+    Compose {
+        /// Store name
+        #[arg(long)]
+        store: String,
+        /// Workload YAML config of the foreground traffic
+        #[arg(long)]
+        config: PathBuf,
+        /// Workload YAML config of the concurrent background job
+        #[arg(long)]
+        background: PathBuf,
+        /// Random seed of both workloads (defaults to random value)
+        #[arg(long)]
+        seed: Option<u64>,
+        /// Optional directory to store benchmark data (enables bind mounts)
+        #[arg(long)]
+        data_dir: Option<String>,
+    },
+    /// Run a suite YAML's workload configs and workflows on each of its stores one after
+    /// another, writing all results to one `results/raw/suites/<suite-id>/` directory with an
+    /// `index.json` of every run
+    Suite {
+        /// Built-in suite to run instead of a suite file: `official-v1` is the sequence behind
+        /// the published comparisons
+        #[arg(required_unless_present = "config", conflicts_with = "config")]
+        name: Option<String>,
+        /// Path to suite YAML file
+        #[arg(long)]
+        config: Option<PathBuf>,
+        /// Random seed of every workload session (defaults to random value)
+        #[arg(long)]
+        seed: Option<u64>,
+        /// Optional directory to store benchmark data (enables bind mounts)
+        #[arg(long)]
+        data_dir: Option<String>,
+    },
+    /// Merge partial results of one experiment (e.g. split across agents or resumed after an
+    /// interruption) into a single result; latency histograms are merged exactly
+    Merge {
+        /// Run directories to merge (each `<session>/<workload>/<store>/`)
+        #[arg(required = true, num_args = 2..)]
+        runs: Vec<PathBuf>,
+        /// Directory to write the merged result to
+        #[arg(long)]
+        output: PathBuf,
+        /// Whether the runs measured the same window in parallel or followed each other
+        #[arg(long, value_enum, default_value_t = MergeMode::Parallel)]
+        mode: MergeMode,
+    },
+    /// Render throughput, latency-over-time and latency CDF charts for one run directory
+    Plot {
+        /// Run directory (`<session>/<workload>/<store>/`)
+        run: PathBuf,
+        /// Directory for the charts (default: `<run>/plots`)
+        #[arg(long)]
+        output: Option<PathBuf>,
+        /// Image format: png or svg
+        #[arg(long, default_value = "png")]
+        format: PlotFormat,
     },
     /// List available store adapters
     ListStores,
@@ -46,6 +588,51 @@ enum Commands {
         #[arg(long, default_value = "results/published")]
         output: PathBuf,
     },
+    /// Write benchmark-summary.md and github-action-benchmark JSON files for a session
+    CiSummary {
+        /// Session directory (`results/raw/sessions/<id>`)
+        #[arg(long)]
+        session: PathBuf,
+        /// Directory to write the files to
+        #[arg(long, default_value = ".")]
+        output: PathBuf,
+    },
+    /// Rank the stores of each workload found in the given run, session or results directories
+    /// by throughput, p99, startup time and container memory
+    Compare {
+        /// Directories to search for `summary.json` files
+        #[arg(required = true)]
+        dirs: Vec<PathBuf>,
+        /// Also write the comparison as JSON to this file
+        #[arg(long)]
+        json: Option<PathBuf>,
+        /// Also write the comparison as markdown tables to this file
+        #[arg(long)]
+        markdown: Option<PathBuf>,
+        /// Align the per-window throughput and p99 of two run directories instead, marking the
+        /// windows where they diverge
+        #[arg(long, conflicts_with = "markdown")]
+        timeline: bool,
+        /// Relative difference in throughput or p99 from which a window is divergent
+        #[arg(long, default_value_t = 0.2, requires = "timeline")]
+        threshold: f64,
+        /// Also write the timeline overlay as an HTML page to this file
+        #[arg(long, requires = "timeline")]
+        html: Option<PathBuf>,
+    },
+    /// Track throughput and p99 of every (store, workload) across sessions and flag the latest
+    /// run when it deviates from the earlier ones
+    Trend {
+        /// Results directory holding raw/sessions
+        #[arg(long, default_value = "results")]
+        results_dir: PathBuf,
+        /// Alert when the latest run is more than this many standard deviations from history
+        #[arg(long, default_value_t = 3.0)]
+        sigma: f64,
+        /// Exit with an error when there are alerts (for nightly jobs)
+        #[arg(long)]
+        fail_on_alert: bool,
+    },
 }
 
 fn store_manager_factories() -> Vec<Box<dyn StoreManagerFactory>> {
@@ -53,20 +640,36 @@ fn store_manager_factories() -> Vec<Box<dyn StoreManagerFactory>> {
         Box::new(dummy_adapter::DummyFactory),
         Box::new(umadb_adapter::UmaDbFactory),
         Box::new(kurrentdb_adapter::KurrentDbFactory),
+        Box::new(kurrentdb_adapter::KurrentDbProjectionsFactory),
         Box::new(axonserver_adapter::AxonServerFactory),
         Box::new(eventsourcingdb_adapter::EventsourcingDbFactory),
+        Box::new(postgres_adapter::PostgresFactory),
+        Box::new(kafka_adapter::KafkaFactory),
+        Box::new(sqlite_adapter::SqliteFactory),
     ]
 }
 
 fn main() -> Result<()> {
     let cli = Cli::parse();
 
-    // Suppress the noise from the KurrentDB Rust client
-    tracing_subscriber::fmt()
-        .with_env_filter(
-            EnvFilter::new(&cli.log).add_directive("kurrentdb::grpc=off".parse()?),
-        )
-        .init();
+    // Suppress the noise from the KurrentDB Rust client, and leave the operation spans to the
+    // OTLP exporter
+    let fmt = tracing_subscriber::fmt::layer().with_filter(
+        EnvFilter::new(&cli.log)
+            .add_directive("kurrentdb::grpc=off".parse()?)
+            .add_directive(format!("{}=off", bench_core::traced::SPAN_TARGET).parse()?),
+    );
+    #[cfg(feature = "otel")]
+    let (otel_layer, tracer_provider) = match &cli.otlp_endpoint {
+        Some(endpoint) => {
+            let (layer, provider) = otel::layer(endpoint)?;
+            (Some(layer), Some(provider))
+        }
+        None => (None, None),
+    };
+    #[cfg(not(feature = "otel"))]
+    let otel_layer: Option<tracing_subscriber::layer::Identity> = None;
+    tracing_subscriber::registry().with(fmt).with(otel_layer).init();
 
     let rt = Runtime::new()?;
     let cancel_token = CancellationToken::new();
@@ -79,36 +682,524 @@ fn main() -> Result<()> {
         ct.cancel();
     });
 
-    match cli.command {
+    detach::record_start();
+    let result = run_command(cli.command, &rt, cancel_token);
+    #[cfg(feature = "otel")]
+    if let Some(provider) = tracer_provider {
+        if let Err(e) = provider.shutdown() {
+            eprintln!("⚠ Failed to export the last spans: {}", e);
+        }
+    }
+    detach::record_exit(&result);
+    result
+}
+
+fn run_command(command: Commands, rt: &Runtime, cancel_token: CancellationToken) -> Result<()> {
+    match command {
         Commands::ListStores => {
             for f in store_manager_factories() {
                 println!("{}", f.name());
             }
             Ok(())
         }
-        Commands::Run { config, seed, data_dir } => {
-            rt.block_on(async { run_benchmark(&config, seed, data_dir, cancel_token).await })?;
+        Commands::Run {
+            config,
+            seed,
+            verify_determinism: Some(session_dir),
+            ..
+        } => verify_determinism(&config, seed, &session_dir),
+        Commands::Run {
+            config,
+            seed,
+            data_dir,
+            verify_determinism: None,
+            parallel,
+            ci_output,
+            checkpoint_minutes,
+            cooldown_seconds,
+            drop_caches,
+            shuffle,
+            ledger_sample_rate,
+            detach,
+            force,
+            virtual_time,
+            ratio_sweep,
+            sinks,
+            upload,
+            store_rev,
+            cpus,
+            memory,
+            live,
+        } => {
+            let limits = ResourceLimits { cpus, memory };
+            limits.validate()?;
+            let checkpoint_interval = match checkpoint_minutes {
+                Some(minutes) if minutes > 0.0 => Some(Duration::from_secs_f64(minutes * 60.0)),
+                Some(minutes) => anyhow::bail!("--checkpoint-minutes must be positive, got {}", minutes),
+                None => None,
+            };
+            if !(cooldown_seconds >= 0.0 && cooldown_seconds.is_finite()) {
+                anyhow::bail!("--cooldown-seconds must be non-negative, got {}", cooldown_seconds);
+            }
+            if let Some(rate) = ledger_sample_rate.filter(|rate| !(*rate > 0.0 && *rate <= 1.0)) {
+                anyhow::bail!("--ledger-sample-rate must be in (0, 1], got {}", rate);
+            }
+            let ratio_sweep = ratio_sweep.as_deref().map(ReadWriteMix::parse_list).transpose()?;
+            let sinks = sinks.iter().map(|spec| parse_sink(spec)).collect::<Result<Vec<_>>>()?;
+            let upload = upload.as_deref().map(S3Location::parse).transpose()?;
+            if store_rev.as_deref().is_some_and(|rev| rev.trim().is_empty()) {
+                anyhow::bail!("--store-rev must not be empty");
+            }
+            if virtual_time {
+                let stores = stores_for_config(&fs::read_to_string(&config)?)?;
+                if let Some(store) = stores.iter().find(|store| store.as_str() != "dummy") {
+                    anyhow::bail!("--virtual-time only works with the in-memory dummy store, not {}", store);
+                }
+            }
+            if live && parallel > 1 {
+                anyhow::bail!("--live shows one run at a time and cannot be combined with --parallel");
+            }
+            if live && !std::io::stdout().is_terminal() {
+                anyhow::bail!("--live needs a terminal");
+            }
+            if detach {
+                return detach::spawn_detached();
+            }
+            let options = SessionOptions {
+                parallel,
+                run: RunSettings {
+                    cpu_set: None,
+                    limits,
+                    checkpoint_interval,
+                    ledger_sample_rate,
+                    store_rev,
+                    live,
+                },
+                between_runs: BetweenRuns {
+                    cooldown: Duration::from_secs_f64(cooldown_seconds),
+                    drop_caches,
+                    drop_error: None,
+                },
+                shuffle,
+                force,
+                ratio_sweep,
+                sinks,
+                session_dir: None,
+            };
+            let benchmark = run_benchmark(&config, seed, data_dir, options, cancel_token);
+            let session_dir = if virtual_time {
+                bench_core::virtual_time::runtime()?.block_on(benchmark)?
+            } else {
+                rt.block_on(benchmark)?
+            };
+            if let Some(ci_output) = ci_output {
+                write_ci_summary(&session_dir, &ci_output)?;
+            }
+            if let Some(location) = upload {
+                let url = rt.block_on(upload_dir(&session_dir, &location))?;
+                println!("✓ Uploaded session to {}", url);
+            }
+            check_assertions(&session_dir)
+        }
+        Commands::Equalized {
+            config,
+            percent,
+            absolute_rate,
+            seed,
+            data_dir,
+        } => {
+            rt.block_on(async {
+                run_equalized(&config, percent, absolute_rate, seed, data_dir, cancel_token).await
+            })?;
+            Ok(())
+        }
+        Commands::CompareVersions {
+            config,
+            store,
+            tags,
+            seed,
+            data_dir,
+        } => {
+            rt.block_on(async {
+                run_compare_versions(&config, &store, &tags, seed, data_dir, cancel_token).await
+            })?;
+            Ok(())
+        }
+        Commands::MigrateBench {
+            from,
+            to,
+            events,
+            streams,
+            event_size,
+            batch_size,
+            concurrency,
+            data_dir,
+            ledger_sample_rate,
+        } => {
+            let config = MigrationConfig {
+                events,
+                streams,
+                event_size_bytes: event_size,
+                batch_size,
+                concurrency,
+            };
+            rt.block_on(async {
+                run_migration(&from, &to, config, data_dir, ledger_sample_rate, cancel_token).await
+            })?;
+            Ok(())
+        }
+        Commands::StreamGrowth {
+            store,
+            events,
+            batch_size,
+            event_size,
+            buckets,
+            data_dir,
+        } => {
+            let config = StreamGrowthConfig {
+                events,
+                batch_size,
+                event_size_bytes: event_size,
+                buckets,
+            };
+            let run_dir = study_run_dir("stream-growth", &store);
+            rt.block_on(async { run_stream_growth(&store, config, data_dir, run_dir, cancel_token).await })?;
+            Ok(())
+        }
+        Commands::StreamPosition {
+            store,
+            events,
+            streams,
+            event_size,
+            page_size,
+            reads,
+            buckets,
+            seed,
+            data_dir,
+        } => {
+            let config = StreamPositionConfig {
+                events,
+                streams,
+                event_size_bytes: event_size,
+                page_size,
+                reads,
+                buckets,
+                seed: seed.unwrap_or_else(|| rand::thread_rng().gen()),
+            };
+            let run_dir = study_run_dir("stream-position", &store);
+            rt.block_on(async { run_stream_position(&store, config, data_dir, run_dir, cancel_token).await })?;
+            Ok(())
+        }
+        Commands::Retention {
+            store,
+            streams,
+            events_per_stream,
+            event_size,
+            max_count,
+            max_age_s,
+            truncate_before,
+            duration,
+            write_rate,
+            page_size,
+            scavenge_at,
+            data_dir,
+        } => {
+            let config = RetentionConfig {
+                streams,
+                events_per_stream,
+                event_size_bytes: event_size,
+                policy: RetentionPolicy {
+                    max_count,
+                    max_age_s,
+                    truncate_before,
+                },
+                duration_seconds: duration,
+                write_rate_eps: write_rate,
+                page_size,
+                scavenge_at_s: scavenge_at,
+            };
+            let run_dir = study_run_dir("retention", &store);
+            rt.block_on(async { run_retention(&store, config, data_dir, run_dir, cancel_token).await })?;
+            Ok(())
+        }
+        Commands::ConnectionStress {
+            store,
+            connections,
+            steps,
+            phase_seconds,
+            writers,
+            write_rate,
+            event_size,
+            read_interval,
+            data_dir,
+        } => {
+            let config = ConnectionStressConfig {
+                connections,
+                steps,
+                phase_seconds,
+                writers,
+                write_rate,
+                event_size_bytes: event_size,
+                read_interval_s: read_interval,
+            };
+            let run_dir = study_run_dir("connection-stress", &store);
+            rt.block_on(async { run_connection_stress(&store, config, data_dir, run_dir, cancel_token).await })?;
+            Ok(())
+        }
+        Commands::StartupBench {
+            store,
+            iterations,
+            cold_iterations,
+            data_dir,
+        } => {
+            let config = StartupBenchConfig {
+                iterations,
+                cold_iterations,
+            };
+            let run_dir = study_run_dir("startup", &store);
+            rt.block_on(async { run_startup_bench(&store, config, data_dir, run_dir, cancel_token).await })?;
+            Ok(())
+        }
+        Commands::Micro {
+            store,
+            iterations,
+            warmup,
+            event_size,
+            seed,
+            data_dir,
+        } => {
+            let config = MicroBenchConfig {
+                iterations,
+                warmup,
+                event_size_bytes: event_size,
+                seed: seed.unwrap_or_else(|| rand::thread_rng().gen()),
+            };
+            let run_dir = study_run_dir("micro", &store);
+            rt.block_on(async { run_micro_bench(&store, config, data_dir, run_dir, cancel_token).await })?;
+            Ok(())
+        }
+        Commands::Propagation {
+            store,
+            streams,
+            events_per_stream,
+            write_rate,
+            poll_interval_ms,
+            event_size,
+            data_dir,
+        } => {
+            let config = PropagationConfig {
+                streams,
+                events_per_stream,
+                write_rate_eps: write_rate,
+                poll_interval_ms,
+                event_size_bytes: event_size,
+            };
+            let run_dir = study_run_dir("propagation", &store);
+            rt.block_on(async { run_propagation(&store, config, data_dir, run_dir, cancel_token).await })?;
+            Ok(())
+        }
+        Commands::CatchupSubscription {
+            store,
+            events,
+            live_events,
+            live_rate,
+            event_size,
+            data_dir,
+        } => {
+            let config = CatchupSubscriptionConfig {
+                events,
+                live_events,
+                live_rate_eps: live_rate,
+                event_size_bytes: event_size,
+            };
+            let run_dir = study_run_dir("catchup-subscription", &store);
+            rt.block_on(async { run_catchup_subscription(&store, config, data_dir, run_dir, cancel_token).await })?;
+            Ok(())
+        }
+        Commands::BackfillTail {
+            store,
+            backlog_events,
+            live_rate,
+            live_duration,
+            event_size,
+            data_dir,
+        } => {
+            let config = BackfillTailConfig {
+                backlog_events,
+                live_rate_eps: live_rate,
+                live_duration_s: live_duration,
+                event_size_bytes: event_size,
+            };
+            let run_dir = study_run_dir("backfill-tail", &store);
+            rt.block_on(async { run_backfill_tail(&store, config, data_dir, run_dir, cancel_token).await })?;
+            Ok(())
+        }
+        Commands::SlowConsumer {
+            store,
+            subscribers,
+            slow_delay_ms,
+            write_rate,
+            duration,
+            event_size,
+            data_dir,
+        } => {
+            let config = SlowConsumerConfig {
+                subscribers,
+                slow_delay_ms,
+                write_rate_eps: write_rate,
+                duration_s: duration,
+                event_size_bytes: event_size,
+            };
+            let run_dir = study_run_dir("slow-consumer", &store);
+            rt.block_on(async { run_slow_consumer(&store, config, data_dir, run_dir, cancel_token).await })?;
+            Ok(())
+        }
+        Commands::GlobalScan {
+            store,
+            events,
+            streams,
+            page_size,
+            streamed,
+            event_size,
+            data_dir,
+        } => {
+            let config = GlobalScanConfig {
+                events,
+                streams,
+                page_size,
+                event_size_bytes: event_size,
+                streamed,
+            };
+            let run_dir = study_run_dir("global-scan", &store);
+            rt.block_on(async { run_global_scan(&store, config, data_dir, run_dir, cancel_token).await })?;
+            Ok(())
+        }
+        Commands::DualWrite {
+            a,
+            b,
+            duration,
+            writers,
+            event_size,
+            data_dir,
+            start_at,
+            align_to_minute,
+        } => {
+            let config = DualWriteConfig {
+                duration_seconds: duration,
+                writers,
+                event_size_bytes: event_size,
+                schedule: StartSchedule {
+                    start_at,
+                    align_to_minute,
+                },
+            };
+            rt.block_on(async { run_dual_write(&a, &b, config, data_dir, cancel_token).await })?;
+            Ok(())
+        }
+        Commands::Compose {
+            store,
+            config,
+            background,
+            seed,
+            data_dir,
+        } => {
+            let run_dir = study_run_dir("composition", &store);
+            rt.block_on(async { run_composition(&store, &config, &background, seed, data_dir, run_dir, cancel_token).await })?;
+            Ok(())
+        }
+        Commands::Suite { name, config, seed, data_dir } => {
+            let suite = match (name, config) {
+                (Some(name), _) => Suite::builtin(&name)?,
+                (None, Some(config)) => Suite::load(&config)?,
+                (None, None) => unreachable!("clap requires a suite name or --config"),
+            };
+            rt.block_on(async { run_suite(suite, seed, data_dir, cancel_token).await })?;
+            Ok(())
+        }
+        Commands::Merge { runs, output, mode } => merge_results(&runs, &output, mode),
+        Commands::Plot { run, output, format } => {
+            let output = output.unwrap_or_else(|| run.join("plots"));
+            for path in plot_run(&run, &output, format)? {
+                println!("✓ Wrote {}", path.display());
+            }
             Ok(())
         }
         Commands::Report { sessions, output } => {
             generate_report(&sessions, &output)?;
             Ok(())
         }
+        Commands::CiSummary { session, output } => write_ci_summary(&session, &output),
+        Commands::Compare { dirs, json, markdown, timeline, threshold, html } => {
+            if timeline {
+                compare_run_timelines(&dirs, threshold, json.as_deref(), html.as_deref())
+            } else {
+                compare_stores(&dirs, json.as_deref(), markdown.as_deref())
+            }
+        }
+        Commands::Trend { results_dir, sigma, fail_on_alert } => {
+            report_trends(&results_dir, sigma, fail_on_alert)
+        }
+        Commands::Status => detach::print_status(),
+        Commands::Stop { run_id } => detach::stop(&run_id),
     }
 }
 
-async fn run_benchmark(config_path: &PathBuf, seed: Option<u64>, data_dir: Option<String>, cancel_token: CancellationToken) -> Result<()> {
-    let actual_seed = seed.unwrap_or_else(|| rand::thread_rng().gen());
+/// Mixed into the session seed for the shuffled run order, so it doesn't correlate with the
+/// workloads' operation sequences
+const ORDER_SEED_SALT: u64 = 0x6f72_6465_7273_6565;
 
-    // Resolve data_dir to an absolute path if provided
-    let data_dir = if let Some(path) = data_dir {
-        let abs_path = fs::canonicalize(&path)
-            .or_else(|_| {
-                // If it doesn't exist yet, create it and then canonicalize
-                fs::create_dir_all(&path)?;
-                fs::canonicalize(&path)
-            })?;
-        Some(abs_path.to_string_lossy().to_string())
+/// How `run` schedules the runs of a session
+struct SessionOptions {
+    parallel: usize,
+    run: RunSettings,
+    between_runs: BetweenRuns,
+    shuffle: bool,
+    /// Run despite lint errors
+    force: bool,
+    /// Read/write mixes to run the (mixed) workload at instead of its configured one
+    ratio_sweep: Option<Vec<ReadWriteMix>>,
+    /// Where run results go besides the results directory
+    sinks: Vec<Box<dyn ResultSink>>,
+    /// Directory of the session instead of a new one under `results/raw/sessions/`
+    session_dir: Option<PathBuf>,
+}
+
+/// Settings of a single run, turned into `RunOptions` once the run's directory is known
+#[derive(Debug, Clone, Default)]
+struct RunSettings {
+    cpu_set: Option<CpuSet>,
+    /// Container limits from the command line, overriding the workload's `resources`
+    limits: ResourceLimits,
+    checkpoint_interval: Option<Duration>,
+    ledger_sample_rate: Option<f64>,
+    /// Store commit recorded in the summary
+    store_rev: Option<String>,
+    /// Show the live dashboard while each run executes
+    live: bool,
+}
+
+async fn run_benchmark(
+    config_path: &PathBuf,
+    seed: Option<u64>,
+    data_dir: Option<String>,
+    options: SessionOptions,
+    cancel_token: CancellationToken,
+) -> Result<PathBuf> {
+    let SessionOptions {
+        parallel,
+        run: run_settings,
+        mut between_runs,
+        shuffle,
+        force,
+        ratio_sweep,
+        sinks,
+        session_dir,
+    } = options;
+    let actual_seed = seed.unwrap_or_else(|| rand::thread_rng().gen());
+    let data_dir = resolve_data_dir(data_dir)?;
+    let cpu_sets = if parallel > 1 {
+        let total_cpus = std::thread::available_parallelism()?.get();
+        Some(partition_cpus(total_cpus, parallel)?)
     } else {
         None
     };
@@ -118,37 +1209,25 @@ async fn run_benchmark(config_path: &PathBuf, seed: Option<u64>, data_dir: Optio
 
     // Extract workload name and stores from config
     let workload_name = WorkloadFactory::extract_workload_name(&config_yaml)?;
-    let stores_from_config = WorkloadFactory::extract_stores(&config_yaml)?;
-
-    // Determine which stores to run
-    let stores_to_run = if let Some(stores) = stores_from_config {
-        stores
-    } else {
-        // Default to all stores
-        store_manager_factories()
-            .into_iter()
-            .map(|f| f.name().to_string())
-            .collect()
-    };
+    let stores_to_run = stores_for_config(&config_yaml)?;
+    let store_runs = store_runs(
+        &stores_to_run,
+        &WorkloadFactory::extract_store_options(&config_yaml)?,
+        &WorkloadFactory::extract_option_sweep(&config_yaml)?,
+    );
 
     println!("Running workload: {}", workload_name);
     println!("Stores: {}", stores_to_run.join(", "));
     println!("Seed: {}", actual_seed);
 
-    // Generate session ID (ISO timestamp)
-    let session_id = Utc::now().format("%Y-%m-%dT%H-%M-%S").to_string();
-    println!("Session ID: {}", session_id);
-
-    // Collect environment info
-    let data_dir_path = data_dir.as_ref().map(Path::new);
-    let environment_info = collect_environment_info(data_dir_path).await?;
-
-    // Get benchmark version (git commit)
-    let benchmark_version = get_git_commit_hash().unwrap_or_else(|_| "unknown".to_string());
-
     // Detect if this is a sweep and expand if needed
-    let is_sweep = WorkloadFactory::is_sweep(&config_yaml)?;
-    let workloads = if is_sweep {
+    let is_sweep = WorkloadFactory::is_sweep(&config_yaml)? || ratio_sweep.is_some();
+    let workloads = if let Some(mixes) = &ratio_sweep {
+        mixes
+            .iter()
+            .map(|&mix| WorkloadFactory::create_mix_variant(&config_yaml, actual_seed, mix))
+            .collect::<Result<Vec<_>>>()?
+    } else if is_sweep {
         WorkloadFactory::expand_sweep(&config_yaml, actual_seed)?
     } else {
         vec![WorkloadFactory::create_from_yaml(&config_yaml, actual_seed)?]
@@ -158,112 +1237,1637 @@ async fn run_benchmark(config_path: &PathBuf, seed: Option<u64>, data_dir: Optio
     if is_sweep {
         println!("Running {} workload variants", workloads.len());
     }
+    lint_workloads(&workloads, &stores_to_run, force)?;
 
-    // Create session directory
-    let session_dir = PathBuf::from("results/raw/sessions").join(&session_id);
-    fs::create_dir_all(&session_dir)?;
-
-    // Write session metadata
-    let session_metadata = SessionMetadata {
-        session_id: session_id.clone(),
-        benchmark_version,
-        workload_name: workload_name.clone(),
-        workload_type: "performance".to_string(), // TODO: Extract from workload
-        config_file: config_path.to_string_lossy().to_string(),
-        seed: actual_seed,
-        stores_run: stores_to_run.clone(),
+    let session_dir = create_session(
+        config_path,
+        &workload_name,
+        &stores_to_run,
+        actual_seed,
         is_sweep,
-    };
+        data_dir.as_deref(),
+        session_dir,
+    )
+    .await?;
 
-    let session_json = serde_json::to_string_pretty(&session_metadata)?;
-    fs::write(session_dir.join("session.json"), session_json)?;
+    if let Some(cpu_sets) = &cpu_sets {
+        let isolation = serde_json::json!({
+            "parallel_runs": parallel,
+            "cpu_sets": cpu_sets.iter().map(|s| s.to_string()).collect::<Vec<_>>(),
+            "pinned": "store containers",
+            "shared": ["benchmark client process", "host stats", "disk", "network"],
+        });
+        fs::write(session_dir.join("isolation.json"), serde_json::to_string_pretty(&isolation)?)?;
+        println!(
+            "Running up to {} stores in parallel, containers pinned to CPUs {} (clients and disk are shared)",
+            parallel,
+            cpu_sets.iter().map(|s| s.to_string()).collect::<Vec<_>>().join(" / ")
+        );
+    }
 
-    // Write environment info
-    let environment_json = serde_json::to_string_pretty(&environment_info)?;
+    let order_seed = shuffle.then_some(actual_seed ^ ORDER_SEED_SALT);
+    let order = run_order(workloads.len(), store_runs.len(), order_seed);
+    let matrix = serde_json::json!({
+        "order": if shuffle { "shuffled" } else { "config" },
+        "order_seed": order_seed,
+        "runs": order
+            .iter()
+            .map(|&(w, s)| serde_json::json!({
+                "workload": variant_name(&workloads[w]),
+                "store": store_runs[s].label,
+            }))
+            .collect::<Vec<_>>(),
+    });
+    fs::write(session_dir.join("matrix.json"), serde_json::to_string_pretty(&matrix)?)?;
+
+    // Run every (workload variant, store configuration) pair, `parallel` at a time
+    let mut sweep_results = Vec::new();
+    let mut ratio_runs = Vec::new();
+    let mut assertion_runs = Vec::new();
+    for (position, group) in order.chunks(parallel.max(1)).enumerate() {
+        if cancel_token.is_cancelled() {
+            break;
+        }
+        between_runs.settle(position == 0, &cancel_token).await;
+        if cancel_token.is_cancelled() {
+            break;
+        }
+        let runs = group.iter().enumerate().map(|(slot, &(w, s))| {
+            let cpu_set = cpu_sets.as_ref().map(|sets| sets[slot]);
+            // Concurrent runs of the same store must not share a data directory
+            let slot_data_dir = match &cpu_sets {
+                Some(_) => data_dir.as_ref().map(|dir| format!("{}/slot-{}", dir, slot)),
+                None => data_dir.clone(),
+            };
+            run_on_store(
+                &store_runs[s],
+                &workloads[w],
+                &session_dir,
+                slot_data_dir,
+                RunSettings { cpu_set, ..run_settings.clone() },
+                &sinks,
+                cancel_token.clone(),
+            )
+        });
+        for (&(w, s), result) in group.iter().zip(join_all(runs).await) {
+            let store_run = &store_runs[s];
+            let Some(result) = result? else {
+                continue;
+            };
+            if let Some(mixes) = &ratio_sweep {
+                ratio_runs.push(RatioRun::new(&store_run.label, mixes[w], &result));
+            }
+            if !result.summary.assertions.is_empty() {
+                assertion_runs.push(serde_json::json!({
+                    "workload": result.summary.workload,
+                    "store": store_run.label,
+                    "passed": result.summary.assertions.iter().all(|a| a.passed),
+                    "assertions": result.summary.assertions,
+                }));
+            }
+            if !store_run.swept.is_empty() {
+                sweep_results.push(OptionSweepResult {
+                    workload: result.summary.workload.clone(),
+                    store: store_run.store.clone(),
+                    options: store_run.swept.clone(),
+                    throughput_eps: result.summary.throughput_eps,
+                    latency_p99_ms: result.summary.latency.p99_ms,
+                });
+            }
+        }
+    }
+
+    if !between_runs.cooldown.is_zero() || between_runs.drop_caches || between_runs.drop_error.is_some() {
+        let settling = serde_json::json!({
+            "cooldown_s": between_runs.cooldown.as_secs_f64(),
+            "page_cache_dropped": between_runs.drop_caches,
+            "drop_caches_error": between_runs.drop_error,
+        });
+        fs::write(session_dir.join("between_runs.json"), serde_json::to_string_pretty(&settling)?)?;
+    }
+
+    if !assertion_runs.is_empty() {
+        fs::write(session_dir.join(ASSERTIONS_FILE), serde_json::to_string_pretty(&assertion_runs)?)?;
+    }
+
+    if !ratio_runs.is_empty() {
+        ratio_runs.sort_by(|a, b| a.store.cmp(&b.store).then(b.read_percent.cmp(&a.read_percent)));
+        fs::write(session_dir.join("ratio_sweep.json"), serde_json::to_string_pretty(&ratio_runs)?)?;
+        println!("\nLatency by read:write mix:");
+        println!("{:<24} {:>9} {:>14} {:>10} {:>10}", "Store", "mix", "events/sec", "p50 (ms)", "p99 (ms)");
+        for run in &ratio_runs {
+            println!(
+                "{:<24} {:>9} {:>14.2} {:>10.2} {:>10.2}",
+                run.store,
+                format!("{}:{}", run.read_percent, run.write_percent),
+                run.throughput_eps,
+                run.latency_p50_ms,
+                run.latency_p99_ms
+            );
+        }
+    }
+
+    if !sweep_results.is_empty() {
+        let best = best_configurations(&sweep_results);
+        fs::write(session_dir.join("option_sweep.json"), serde_json::to_string_pretty(&best)?)?;
+        println!("\nBest store options:");
+        for config in &best {
+            let options: Vec<String> = config.options.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+            println!(
+                "  {} on {}: {} ({:.2} events/sec, p99 {:.2}ms)",
+                config.workload,
+                config.store,
+                options.join(", "),
+                config.throughput_eps,
+                config.latency_p99_ms
+            );
+        }
+    }
+
+    println!("\n✓ Session complete: {}", session_dir.display());
+    Ok(session_dir)
+}
+
+/// Runs of a session whose workload has an `assert` section, with each bound's outcome
+const ASSERTIONS_FILE: &str = "assertions.json";
+
+/// Fail if a run of the session broke a bound of its workload's `assert` section, once all
+/// results are written, so `run` exits nonzero
+fn check_assertions(session_dir: &Path) -> Result<()> {
+    let path = session_dir.join(ASSERTIONS_FILE);
+    if !path.exists() {
+        return Ok(());
+    }
+    let runs: Vec<serde_json::Value> = serde_json::from_str(&fs::read_to_string(&path)?)?;
+    let failed: Vec<String> = runs
+        .iter()
+        .filter(|run| run["passed"] == false)
+        .map(|run| format!("{} on {}", run["workload"].as_str().unwrap_or("?"), run["store"].as_str().unwrap_or("?")))
+        .collect();
+    if !failed.is_empty() {
+        anyhow::bail!("Assertions failed for {} of {} runs: {}", failed.len(), runs.len(), failed.join(", "));
+    }
+    println!("✓ All assertions passed ({} runs)", runs.len());
+    Ok(())
+}
+
+/// Max-rate runs followed by relative- and absolute-rate runs for every store
+async fn run_equalized(
+    config_path: &PathBuf,
+    percent: f64,
+    absolute_rate: Option<f64>,
+    seed: Option<u64>,
+    data_dir: Option<String>,
+    cancel_token: CancellationToken,
+) -> Result<()> {
+    if !(percent > 0.0 && percent <= 100.0) {
+        anyhow::bail!("--percent must be in (0, 100], got {}", percent);
+    }
+    let actual_seed = seed.unwrap_or_else(|| rand::thread_rng().gen());
+    let data_dir = resolve_data_dir(data_dir)?;
+
+    let config_yaml = fs::read_to_string(config_path)?;
+    if WorkloadFactory::is_sweep(&config_yaml)? {
+        anyhow::bail!("Equalized comparison requires a non-sweep config");
+    }
+    let workload_name = WorkloadFactory::extract_workload_name(&config_yaml)?;
+    let stores_to_run = stores_for_config(&config_yaml)?;
+    let store_runs = store_runs(
+        &stores_to_run,
+        &WorkloadFactory::extract_store_options(&config_yaml)?,
+        &WorkloadFactory::extract_option_sweep(&config_yaml)?,
+    );
+
+    println!("Equalized load comparison: {}", workload_name);
+    println!("Stores: {}", stores_to_run.join(", "));
+    println!("Seed: {}", actual_seed);
+
+    let session_dir = create_session(
+        config_path,
+        &workload_name,
+        &stores_to_run,
+        actual_seed,
+        false,
+        data_dir.as_deref(),
+        None,
+    )
+    .await?;
+
+    // Phase 1: unthrottled runs establish each store's max operation rate
+    let max_workload = WorkloadFactory::create_from_yaml(&config_yaml, actual_seed)?;
+    let events_per_op = match &max_workload {
+        Workload::Performance(w) => w.events_per_operation(),
+        _ => anyhow::bail!("Equalized comparison only supports performance workloads"),
+    };
+    let mut max_rates: Vec<(&StoreRun, f64)> = Vec::new();
+    for store_run in &store_runs {
+        if cancel_token.is_cancelled() {
+            return Ok(());
+        }
+        if let Some(result) = run_on_store(store_run, &max_workload, &session_dir, data_dir.clone(), RunSettings::default(), &[], cancel_token.clone()).await? {
+            let max_ops = result.summary.throughput_eps / events_per_op;
+            println!("{} max rate: {:.2} ops/sec", store_run.label, max_ops);
+            max_rates.push((store_run, max_ops));
+        }
+    }
+
+    // Phase 2: each store at a percentage of its own max
+    let relative_suffix = format!("p{}", percent);
+    for (store_run, max_ops) in &max_rates {
+        if cancel_token.is_cancelled() {
+            return Ok(());
+        }
+        let rate = max_ops * percent / 100.0;
+        let workload = WorkloadFactory::create_rate_limited(&config_yaml, actual_seed, rate, &relative_suffix)?;
+        run_on_store(store_run, &workload, &session_dir, data_dir.clone(), RunSettings::default(), &[], cancel_token.clone()).await?;
+    }
+
+    // Phase 3: every store at the same absolute rate
+    let common_rate = absolute_rate.unwrap_or_else(|| {
+        max_rates
+            .iter()
+            .map(|(_, max_ops)| *max_ops)
+            .fold(f64::INFINITY, f64::min)
+            * percent
+            / 100.0
+    });
+    if common_rate.is_finite() && common_rate > 0.0 {
+        let absolute_suffix = format!("at-{:.0}ops", common_rate);
+        let workload = WorkloadFactory::create_rate_limited(&config_yaml, actual_seed, common_rate, &absolute_suffix)?;
+        for (store_run, _) in &max_rates {
+            if cancel_token.is_cancelled() {
+                return Ok(());
+            }
+            run_on_store(store_run, &workload, &session_dir, data_dir.clone(), RunSettings::default(), &[], cancel_token.clone()).await?;
+        }
+    }
+
+    let equalized = serde_json::json!({
+        "percent": percent,
+        "events_per_operation": events_per_op,
+        "max_ops_per_second": max_rates.iter().map(|(run, max_ops)| (run.label.as_str(), *max_ops)).collect::<BTreeMap<_, _>>(),
+        "absolute_ops_per_second": common_rate,
+    });
+    fs::write(session_dir.join("equalized.json"), serde_json::to_string_pretty(&equalized)?)?;
+
+    println!("\n✓ Session complete: {}", session_dir.display());
+    Ok(())
+}
+
+/// Runs of every workload variant against each image tag of one store, followed by a report of
+/// each tag's change relative to the first
+async fn run_compare_versions(
+    config_path: &PathBuf,
+    store: &str,
+    tags: &[String],
+    seed: Option<u64>,
+    data_dir: Option<String>,
+    cancel_token: CancellationToken,
+) -> Result<()> {
+    if tags.len() < 2 {
+        anyhow::bail!("--tags must list at least two image tags");
+    }
+    let actual_seed = seed.unwrap_or_else(|| rand::thread_rng().gen());
+    let data_dir = resolve_data_dir(data_dir)?;
+
+    let config_yaml = fs::read_to_string(config_path)?;
+    let workload_name = WorkloadFactory::extract_workload_name(&config_yaml)?;
+    let stores_to_run = vec![store.to_string()];
+    let sweep = OptionSweep::from([(
+        store.to_string(),
+        BTreeMap::from([("image_tag".to_string(), tags.to_vec())]),
+    )]);
+    let store_runs = store_runs(&stores_to_run, &WorkloadFactory::extract_store_options(&config_yaml)?, &sweep);
+    // Fail before starting anything if the store can't run other tags
+    let factory = find_store_factory(store)?;
+    for store_run in &store_runs {
+        factory.create_store_manager_with_options(None, &store_run.options)?;
+    }
+
+    let is_sweep = WorkloadFactory::is_sweep(&config_yaml)?;
+    let workloads = if is_sweep {
+        WorkloadFactory::expand_sweep(&config_yaml, actual_seed)?
+    } else {
+        vec![WorkloadFactory::create_from_yaml(&config_yaml, actual_seed)?]
+    };
+
+    println!("Version comparison: {} on {}", workload_name, store);
+    println!("Tags: {} (baseline {})", tags.join(", "), tags[0]);
+    println!("Seed: {}", actual_seed);
+
+    let session_dir = create_session(
+        config_path,
+        &workload_name,
+        &stores_to_run,
+        actual_seed,
+        is_sweep,
+        data_dir.as_deref(),
+        None,
+    )
+    .await?;
+
+    let mut runs = Vec::new();
+    for workload in &workloads {
+        for store_run in &store_runs {
+            if cancel_token.is_cancelled() {
+                return Ok(());
+            }
+            if let Some(result) = run_on_store(store_run, workload, &session_dir, data_dir.clone(), RunSettings::default(), &[], cancel_token.clone()).await? {
+                runs.push(VersionRun {
+                    workload: result.summary.workload.clone(),
+                    tag: store_run.swept["image_tag"].clone(),
+                    throughput_eps: result.summary.throughput_eps,
+                    latency_p50_ms: result.summary.latency.p50_ms,
+                    latency_p99_ms: result.summary.latency.p99_ms,
+                });
+            }
+        }
+    }
+
+    let deltas = version_deltas(&runs);
+    let versions = serde_json::json!({
+        "store": store,
+        "tags": tags,
+        "runs": runs,
+        "deltas": deltas,
+    });
+    fs::write(session_dir.join("versions.json"), serde_json::to_string_pretty(&versions)?)?;
+
+    println!("\nChange relative to {}:", tags[0]);
+    println!("{:<30} {:<20} {:>12} {:>12} {:>12}", "Workload", "Tag", "throughput", "p50", "p99");
+    for delta in &deltas {
+        println!(
+            "{:<30} {:<20} {:>+11.1}% {:>+11.1}% {:>+11.1}%",
+            delta.workload,
+            delta.tag,
+            delta.throughput_change_percent,
+            delta.latency_p50_change_percent,
+            delta.latency_p99_change_percent
+        );
+    }
+
+    println!("\n✓ Session complete: {}", session_dir.display());
+    Ok(())
+}
+
+/// Regenerate the per-worker operation digests for a config and compare them with those recorded
+/// in each run of a session
+fn verify_determinism(config_path: &Path, seed: Option<u64>, session_dir: &Path) -> Result<()> {
+    let session: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(session_dir.join("session.json"))?)?;
+    let seed = match seed {
+        Some(seed) => seed,
+        None => session
+            .get("seed")
+            .and_then(|v| v.as_u64())
+            .ok_or_else(|| anyhow::anyhow!("No seed recorded in {}", session_dir.display()))?,
+    };
+
+    let config_yaml = fs::read_to_string(config_path)?;
+    let workloads = if WorkloadFactory::is_sweep(&config_yaml)? {
+        WorkloadFactory::expand_sweep(&config_yaml, seed)?
+    } else {
+        vec![WorkloadFactory::create_from_yaml(&config_yaml, seed)?]
+    };
+
+    let mut checked = 0;
+    let mut mismatches = 0;
+    for workload in &workloads {
+        let Workload::Performance(perf_workload) = workload else {
+            continue;
+        };
+        let workload_dir = session_dir.join(perf_workload.name());
+        let Ok(entries) = fs::read_dir(&workload_dir) else {
+            println!("- {}: no runs recorded", perf_workload.name());
+            continue;
+        };
+        for entry in entries {
+            let meta_path = entry?.path().join("run.meta.json");
+            if !meta_path.exists() {
+                continue;
+            }
+            let meta: serde_json::Value = serde_json::from_str(&fs::read_to_string(&meta_path)?)?;
+            let recorded: Option<Vec<WorkerDigest>> = meta
+                .get("op_digests")
+                .and_then(|v| serde_json::from_value(v.clone()).ok());
+            let Some(recorded) = recorded else {
+                println!("- {}: no digests recorded", meta_path.display());
+                continue;
+            };
+            // Regenerate exactly as many operations as each worker covered in the recorded run
+            let ops_per_worker: Vec<u64> = recorded.iter().map(|d| d.ops).collect();
+            if perf_workload.op_digests(&ops_per_worker) == recorded {
+                println!("✓ {}", meta_path.display());
+            } else {
+                println!("✗ {}: operation digests differ", meta_path.display());
+                mismatches += 1;
+            }
+            checked += 1;
+        }
+    }
+
+    if mismatches > 0 {
+        anyhow::bail!("{} of {} runs are not reproducible with seed {}", mismatches, checked, seed);
+    }
+    if checked == 0 {
+        anyhow::bail!("No recorded digests found in {}", session_dir.display());
+    }
+    println!("All {} runs reproduce with seed {}", checked, seed);
+    Ok(())
+}
+
+/// Resolve data_dir to an absolute path if provided, creating it if needed
+fn resolve_data_dir(data_dir: Option<String>) -> Result<Option<String>> {
+    if let Some(path) = data_dir {
+        let abs_path = fs::canonicalize(&path)
+            .or_else(|_| {
+                // If it doesn't exist yet, create it and then canonicalize
+                fs::create_dir_all(&path)?;
+                fs::canonicalize(&path)
+            })?;
+        Ok(Some(abs_path.to_string_lossy().to_string()))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Stores listed in the config, or all known stores
+fn stores_for_config(config_yaml: &str) -> Result<Vec<String>> {
+    match WorkloadFactory::extract_stores(config_yaml)? {
+        Some(stores) => Ok(stores),
+        None => Ok(store_manager_factories()
+            .into_iter()
+            .map(|f| f.name().to_string())
+            .collect()),
+    }
+}
+
+/// Create a session directory (`session_dir`, or one named after the session id) with session
+/// metadata, environment info and a copy of the config
+async fn create_session(
+    config_path: &Path,
+    workload_name: &str,
+    stores_to_run: &[String],
+    seed: u64,
+    is_sweep: bool,
+    data_dir: Option<&str>,
+    session_dir: Option<PathBuf>,
+) -> Result<PathBuf> {
+    // Generate session ID (ISO timestamp)
+    let session_id = Utc::now().format("%Y-%m-%dT%H-%M-%S").to_string();
+    println!("Session ID: {}", session_id);
+
+    // Collect environment info
+    let environment_info = collect_environment_info(data_dir.map(Path::new)).await?;
+
+    // Get benchmark version (git commit)
+    let benchmark_version = get_git_commit_hash().unwrap_or_else(|_| "unknown".to_string());
+
+    // Create session directory
+    let session_dir = session_dir.unwrap_or_else(|| PathBuf::from("results/raw/sessions").join(&session_id));
+    fs::create_dir_all(&session_dir)?;
+
+    // Write session metadata
+    let session_metadata = SessionMetadata {
+        session_id,
+        benchmark_version,
+        workload_name: workload_name.to_string(),
+        workload_type: "performance".to_string(), // TODO: Extract from workload
+        config_file: config_path.to_string_lossy().to_string(),
+        seed,
+        stores_run: stores_to_run.to_vec(),
+        is_sweep,
+    };
+
+    let session_json = serde_json::to_string_pretty(&session_metadata)?;
+    fs::write(session_dir.join("session.json"), session_json)?;
+
+    // Write environment info
+    let environment_json = serde_json::to_string_pretty(&environment_info)?;
     fs::write(session_dir.join("environment.json"), environment_json)?;
 
     // Copy config file to session directory
     fs::copy(config_path, session_dir.join("config.yaml"))?;
 
-    // Run each workload variant
-    for workload in workloads {
-        let workload_name = match &workload {
-            bench_core::Workload::Performance(w) => w.name(),
-            _ => "unknown",
+    Ok(session_dir)
+}
+
+/// Check every workload variant against the host and each store before starting the session;
+/// errors refuse the session unless `force` is set
+fn lint_workloads(workloads: &[Workload], stores: &[String], force: bool) -> Result<()> {
+    let host = RunLimits::detect();
+    let mut findings = Vec::new();
+    for store in stores {
+        let limits = RunLimits {
+            store_connections: find_store_factory(store)?.max_connections(),
+            ..host.clone()
         };
+        for workload in workloads {
+            if let Workload::Performance(w) = workload {
+                findings.extend(w.lint(store, &limits).into_iter().map(|f| (store, f)));
+            }
+        }
+    }
+    if findings.is_empty() {
+        return Ok(());
+    }
+    println!("Config checks:");
+    for (store, finding) in &findings {
+        println!("  [{}] {}", store, finding);
+    }
+    let errors = findings.iter().filter(|(_, f)| f.level == LintLevel::Error).count();
+    if errors > 0 {
+        if !force {
+            anyhow::bail!("{} config check(s) failed; pass --force to run anyway", errors);
+        }
+        println!("Running anyway (--force)");
+    }
+    Ok(())
+}
+
+fn variant_name(workload: &Workload) -> &str {
+    match workload {
+        Workload::Performance(w) => w.name(),
+        _ => "unknown",
+    }
+}
+
+/// Run one workload on one store and write its results under `<session>/<workload>/<store>/`.
+/// Returns `None` if the run was interrupted.
+async fn run_on_store(
+    store_run: &StoreRun,
+    workload: &Workload,
+    session_dir: &Path,
+    data_dir: Option<String>,
+    settings: RunSettings,
+    sinks: &[Box<dyn ResultSink>],
+    cancel_token: CancellationToken,
+) -> Result<Option<RunMetrics>> {
+    let workload_name = variant_name(workload);
+    let store_name = store_run.label.as_str();
+    println!("\n=== Running {} on {} ===", workload_name, store_name);
+
+    // Find store factory
+    let store_factory = find_store_factory(&store_run.store)?;
+
+    // Create store manager
+    let store_manager = store_factory.create_store_manager_with_options(data_dir, &store_run.options)?;
 
-        // Create workload directory
-        let workload_dir = session_dir.join(workload_name);
-        fs::create_dir_all(&workload_dir)?;
+    // Create store directory
+    let results_dir = FilesystemSink::new(session_dir);
+    let store_dir = results_dir.run_dir(workload_name, store_name);
+    fs::create_dir_all(&store_dir)?;
 
-        // Run workload for each store
-        for store_name in &stores_to_run {
+    // Execute the run
+    let limits = match workload {
+        Workload::Performance(perf_workload) => perf_workload.resources().overridden_by(&settings.limits),
+        _ => settings.limits.clone(),
+    };
+    let options = RunOptions {
+        cpu_set: settings.cpu_set,
+        limits: (!limits.is_empty()).then_some(limits),
+        checkpoint: settings.checkpoint_interval.map(|interval| CheckpointOptions {
+            dir: store_dir.join("checkpoints"),
+            interval,
+        }),
+        ledger: settings.ledger_sample_rate.map(|sample_rate| LedgerOptions {
+            path: store_dir.join("ledger.jsonl"),
+            sample_rate,
+        }),
+        live: settings.live.then(|| Arc::new(LiveStats::new())),
+        ..Default::default()
+    };
+    let dashboard = match &options.live {
+        Some(live) => Some(live::Dashboard::start(format!("{} on {}", workload_name, store_name), live.clone())?),
+        None => None,
+    };
+    let result = execute_run_with_options(store_manager, workload, options, cancel_token.clone()).await;
+    if let Some(dashboard) = dashboard {
+        dashboard.finish().await;
+    }
+
+    let mut result = match result {
+        Ok(res) => res,
+        Err(e) => {
+            if cancel_token.is_cancelled() {
+                println!("Run interrupted, skipping results for {}", store_name);
+                return Ok(None);
+            }
+            return Err(e);
+        }
+    };
+
+    // Write the results directory, then any other sinks; results already on disk aren't
+    // worth failing the session over when a catalog or collector is unavailable
+    result.summary.store_options = store_run.options.clone().into_iter().collect();
+    result.summary.store_rev = settings.store_rev;
+    let session_id = session_dir.file_name().map(|name| name.to_string_lossy()).unwrap_or_default();
+    let record = RunRecord {
+        session_id: &session_id,
+        workload: workload_name,
+        store: store_name,
+        cpu_set: settings.cpu_set,
+        metrics: &result,
+    };
+    results_dir.write_run(&record).await?;
+    for sink in sinks {
+        if let Err(e) = sink.write_run(&record).await {
+            println!("  ⚠ Failed to write results to the {} sink: {:#}", sink.name(), e);
+        }
+    }
+
+    println!(
+        "✓ {} completed: {:.2} events/sec",
+        store_name, result.summary.throughput_eps
+    );
+    for assertion in &result.summary.assertions {
+        println!(
+            "  {} assert {} {}: {}",
+            if assertion.passed { "✓" } else { "✗" },
+            assertion.assertion,
+            assertion.limit,
+            assertion.actual.map_or("not measured".to_string(), |actual| format!("{:.4}", actual))
+        );
+    }
+    if let Some(interference) = &result.summary.interference {
+        let fmt = |value: Option<f64>| value.map_or("n/a".to_string(), |v| format!("{:.2}", v));
+        println!(
+            "  Read/write interference: p99 correlation with write rate {}, busiest/quietest write decile read p99 ratio {}",
+            fmt(interference.write_rate_read_p99_correlation),
+            fmt(interference.top_to_bottom_read_p99_ratio)
+        );
+    }
+    if let Some(cpu_spikes) = result.summary.cpu_spikes.as_ref().filter(|c| !c.spikes.is_empty()) {
+        println!(
+            "  {} store CPU spikes above {:.0}% (median {:.0}%):",
+            cpu_spikes.spikes.len(),
+            cpu_spikes.threshold_cpu_percent,
+            cpu_spikes.median_cpu_percent
+        );
+        for spike in cpu_spikes.spikes.iter().take(3) {
+            let mix: Vec<String> = spike
+                .ops
+                .iter()
+                .map(|op| format!("{} {:.0}% (otherwise {:.0}%)", op.op, op.share * 100.0, op.baseline_share * 100.0))
+                .collect();
+            println!(
+                "    {:.1}-{:.1}s at {:.0}%: p99 {} ({}x), {}",
+                spike.start_s,
+                spike.end_s,
+                spike.peak_cpu_percent,
+                spike.p99_ms.map_or("n/a".to_string(), |p99| format!("{:.2} ms", p99)),
+                spike.p99_ratio.map_or("n/a".to_string(), |ratio| format!("{:.1}", ratio)),
+                if mix.is_empty() { "no operations".to_string() } else { mix.join(", ") }
+            );
+        }
+    }
+    if let Some(connections) = &result.summary.connections {
+        if let Some(connect) = &connections.connect {
+            println!(
+                "  Connection setup of {} clients: p50 {:.2} ms, p99 {:.2} ms",
+                connections.adapters - connections.failed,
+                connect.p50_ms,
+                connect.p99_ms
+            );
+        }
+        if connections.failed > 0 {
+            println!("  ⚠ {} of {} clients failed to connect before the measurement", connections.failed, connections.adapters);
+        }
+    }
+    if let Some(throttling) = result.summary.throttling.as_ref().filter(|t| t.throttled) {
+        println!("  ⚠ CPU throttled during the run: {}", throttling.reason);
+    }
+    if let Some(health) = result.summary.health.as_ref().filter(|h| h.failed > 0) {
+        println!(
+            "  ⚠ {} of {} health pings failed ({:.1}% available, longest outage {:.1}s)",
+            health.failed, health.pings, health.availability_percent, health.longest_outage_s
+        );
+    }
+    if let Some(stop) = &result.summary.budget_stop {
+        println!(
+            "  ⚠ Stopped early by {} after {:.1}s ({} events)",
+            stop.limit.as_str(),
+            stop.elapsed_s,
+            stop.events
+        );
+    }
+    Ok(Some(result))
+}
+
+fn merge_results(runs: &[PathBuf], output: &Path, mode: MergeMode) -> Result<()> {
+    let merged = merge_runs(runs, mode)?;
+    fs::create_dir_all(output)?;
+
+    fs::write(output.join("summary.json"), serde_json::to_string_pretty(&merged.summary)?)?;
+
+    let mut sample_lines = String::new();
+    for sample in &merged.samples {
+        sample_lines.push_str(&serde_json::to_string(sample)?);
+        sample_lines.push('\n');
+    }
+    fs::write(output.join("samples.jsonl"), sample_lines)?;
+
+    fs::write(
+        output.join("latency.json"),
+        serde_json::to_string_pretty(&merged.latency_histogram.to_percentile_json())?,
+    )?;
+    fs::write(output.join("latency.hdr"), merged.latency_histogram.serialize_to_base64()?)?;
+    fs::write(output.join("latency.hgrm"), merged.latency_histogram.to_hgrm())?;
+    for (op, rec) in &merged.op_latency {
+        fs::write(output.join(format!("latency_{}.hdr", op)), rec.serialize_to_base64()?)?;
+        fs::write(output.join(format!("latency_{}.hgrm", op)), rec.to_hgrm())?;
+    }
+
+    let metadata = serde_json::json!({
+        "schema_version": merged.summary.schema_version,
+        "merged_from": merged.merged_from,
+        "merge_mode": format!("{:?}", mode).to_lowercase(),
+    });
+    fs::write(output.join("run.meta.json"), serde_json::to_string_pretty(&metadata)?)?;
+
+    println!(
+        "✓ Merged {} runs into {}: {} events written, {:.2} events/sec, p99 {:.2}ms",
+        merged.merged_from.len(),
+        output.display(),
+        merged.summary.events_written,
+        merged.summary.throughput_eps,
+        merged.summary.latency.p99_ms
+    );
+    Ok(())
+}
+
+fn find_store_factory(store_name: &str) -> Result<Box<dyn StoreManagerFactory>> {
+    store_manager_factories()
+        .into_iter()
+        .find(|f| f.name() == store_name)
+        .ok_or_else(|| anyhow::anyhow!("Unknown store: {}", store_name))
+}
+
+async fn run_migration(
+    from: &str,
+    to: &str,
+    config: MigrationConfig,
+    data_dir: Option<String>,
+    ledger_sample_rate: Option<f64>,
+    cancel_token: CancellationToken,
+) -> Result<()> {
+    // Each store gets its own directory, so two file-backed stores don't share one
+    let store_dir = |role: &str| data_dir.as_ref().map(|dir| Path::new(dir).join(role).to_string_lossy().to_string());
+    let source = find_store_factory(from)?.create_store_manager(store_dir("source"))?;
+    let target = find_store_factory(to)?.create_store_manager(store_dir("target"))?;
+
+    println!("Migration benchmark: {} -> {}", from, to);
+    let data_dir_path = data_dir.as_ref().map(Path::new);
+    let environment_info = collect_environment_info(data_dir_path).await?;
+
+    let run_id = Utc::now().format("%Y-%m-%dT%H-%M-%S").to_string();
+    let run_dir = PathBuf::from("results/raw/migrations").join(format!("{}-{}-to-{}", run_id, from, to));
+    let ledger = ledger_sample_rate.map(|sample_rate| LedgerOptions {
+        path: run_dir.join("ledger.jsonl"),
+        sample_rate,
+    });
+
+    let result = execute_migration(source, target, &config, ledger.as_ref(), cancel_token).await?;
+    fs::create_dir_all(&run_dir)?;
+    fs::write(run_dir.join("migration.json"), serde_json::to_string_pretty(&result)?)?;
+    fs::write(run_dir.join("environment.json"), serde_json::to_string_pretty(&environment_info)?)?;
+
+    println!(
+        "✓ Copied {} events in {:.2}s: {:.2} events/sec, {:.2} MB/s",
+        result.events_copied,
+        result.duration_s,
+        result.throughput_eps,
+        result.throughput_bytes_per_s / 1_000_000.0
+    );
+    if result.throughput_eps > 0.0 {
+        println!("  Estimated time per million events: {:.0}s", 1_000_000.0 / result.throughput_eps);
+    }
+    println!("Results: {}", run_dir.display());
+    Ok(())
+}
+
+/// Default results directory of a single-store study: `results/raw/<kind>/<timestamp>-<store>/`
+fn study_run_dir(kind: &str, store: &str) -> PathBuf {
+    let run_id = Utc::now().format("%Y-%m-%dT%H-%M-%S").to_string();
+    PathBuf::from("results/raw").join(kind).join(format!("{}-{}", run_id, store))
+}
+
+async fn run_stream_growth(
+    store: &str,
+    config: StreamGrowthConfig,
+    data_dir: Option<String>,
+    run_dir: PathBuf,
+    cancel_token: CancellationToken,
+) -> Result<()> {
+    let store_manager = find_store_factory(store)?.create_store_manager(data_dir.clone())?;
+
+    println!("Stream growth study: {}", store);
+    let data_dir_path = data_dir.as_ref().map(Path::new);
+    let environment_info = collect_environment_info(data_dir_path).await?;
+
+    let result = execute_stream_growth(store_manager, &config, cancel_token).await?;
+
+    fs::create_dir_all(&run_dir)?;
+    fs::write(run_dir.join("stream_growth.json"), serde_json::to_string_pretty(&result)?)?;
+    fs::write(run_dir.join("environment.json"), serde_json::to_string_pretty(&environment_info)?)?;
+
+    println!("{:>21}  {:>8}  {:>9}  {:>9}", "stream length", "appends", "p50 ms", "p99 ms");
+    for bucket in &result.buckets {
+        println!(
+            "{:>10}..{:<9}  {:>8}  {:>9.3}  {:>9.3}",
+            bucket.from, bucket.to, bucket.ops, bucket.latency.p50_ms, bucket.latency.p99_ms
+        );
+    }
+    println!(
+        "✓ Appended {} events in {:.2}s; latency grows {:.2} µs per 10k events, last/first p50 {:.2}x",
+        result.events_appended, result.duration_s, result.slope_us_per_10k_events, result.p50_growth_ratio
+    );
+    println!("Results: {}", run_dir.display());
+    Ok(())
+}
+
+async fn run_stream_position(
+    store: &str,
+    config: StreamPositionConfig,
+    data_dir: Option<String>,
+    run_dir: PathBuf,
+    cancel_token: CancellationToken,
+) -> Result<()> {
+    let store_manager = find_store_factory(store)?.create_store_manager(data_dir.clone())?;
+
+    println!("Read position study: {}", store);
+    let data_dir_path = data_dir.as_ref().map(Path::new);
+    let environment_info = collect_environment_info(data_dir_path).await?;
+
+    let result = execute_stream_position(store_manager, &config, cancel_token).await?;
+
+    fs::create_dir_all(&run_dir)?;
+    fs::write(run_dir.join("stream_position.json"), serde_json::to_string_pretty(&result)?)?;
+    fs::write(run_dir.join("environment.json"), serde_json::to_string_pretty(&environment_info)?)?;
+
+    println!("{:>21}  {:>8}  {:>9}  {:>9}", "page offset", "reads", "p50 ms", "p99 ms");
+    for bucket in &result.buckets {
+        println!(
+            "{:>10}..{:<9}  {:>8}  {:>9.3}  {:>9.3}",
+            bucket.from, bucket.to, bucket.ops, bucket.latency.p50_ms, bucket.latency.p99_ms
+        );
+    }
+    println!(
+        "✓ {} reads: p50 {:.3} ms at the start, {:.3} ms in the middle, {:.3} ms at the tail",
+        result.reads, result.start_p50_ms, result.middle_p50_ms, result.tail_p50_ms
+    );
+    if result.empty_reads > 0 {
+        eprintln!("Warning: {} of {} reads returned no events", result.empty_reads, result.reads);
+    }
+    println!("Results: {}", run_dir.display());
+    Ok(())
+}
+
+async fn run_retention(
+    store: &str,
+    config: RetentionConfig,
+    data_dir: Option<String>,
+    run_dir: PathBuf,
+    cancel_token: CancellationToken,
+) -> Result<()> {
+    let store_manager = find_store_factory(store)?.create_store_manager(data_dir.clone())?;
+
+    println!("Retention study: {}", store);
+    let data_dir_path = data_dir.as_ref().map(Path::new);
+    let environment_info = collect_environment_info(data_dir_path).await?;
+
+    let result = execute_retention(store_manager, &config, cancel_token).await?;
+
+    fs::create_dir_all(&run_dir)?;
+    fs::write(run_dir.join("retention.json"), serde_json::to_string_pretty(&result)?)?;
+    fs::write(run_dir.join("environment.json"), serde_json::to_string_pretty(&environment_info)?)?;
+
+    let p99 = |stats: &Option<bench_core::LatencyStats>| {
+        stats.as_ref().map(|s| format!("{:.3}", s.p99_ms)).unwrap_or_else(|| "-".to_string())
+    };
+    println!(
+        "{:>8}  {:>8}  {:>13}  {:>8}  {:>11}  {:>12}  {:>6}",
+        "phase", "appends", "append p99 ms", "reads", "read p99 ms", "first offset", "errors"
+    );
+    for phase in &result.phases {
+        println!(
+            "{:>8}  {:>8}  {:>13}  {:>8}  {:>11}  {:>12}  {:>6}",
+            phase.phase,
+            phase.appends,
+            p99(&phase.append_latency),
+            phase.reads,
+            p99(&phase.read_latency),
+            phase.first_offset_mean.map(|o| format!("{:.0}", o)).unwrap_or_else(|| "-".to_string()),
+            phase.errors
+        );
+    }
+    match &result.scavenge {
+        Some(window) => match (&window.error, window.end_s) {
+            (Some(error), _) => eprintln!("Warning: scavenge at {:.1}s failed: {}", window.start_s, error),
+            (None, Some(end_s)) => println!("✓ Scavenge ran from {:.1}s to {:.1}s", window.start_s, end_s),
+            (None, None) => println!("✓ Scavenge started at {:.1}s, still running at the end", window.start_s),
+        },
+        None => println!("✓ No scavenge ran"),
+    }
+    println!("Results: {}", run_dir.display());
+    Ok(())
+}
+
+async fn run_connection_stress(
+    store: &str,
+    config: ConnectionStressConfig,
+    data_dir: Option<String>,
+    run_dir: PathBuf,
+    cancel_token: CancellationToken,
+) -> Result<()> {
+    let store_manager = find_store_factory(store)?.create_store_manager(data_dir.clone())?;
+
+    println!("Connection stress test: {}", store);
+    let data_dir_path = data_dir.as_ref().map(Path::new);
+    let environment_info = collect_environment_info(data_dir_path).await?;
+
+    let result = execute_connection_stress(store_manager, &config, cancel_token).await?;
+
+    fs::create_dir_all(&run_dir)?;
+    fs::write(run_dir.join("connection_stress.json"), serde_json::to_string_pretty(&result)?)?;
+    fs::write(run_dir.join("environment.json"), serde_json::to_string_pretty(&environment_info)?)?;
+
+    println!("{:>11}  {:>6}  {:>8}  {:>9}  {:>9}  {:>10}", "connections", "failed", "writes", "p50 ms", "p99 ms", "memory MB");
+    for level in &result.levels {
+        println!(
+            "{:>11}  {:>6}  {:>8}  {:>9.3}  {:>9.3}  {:>10}",
+            level.connections,
+            level.failed_connections,
+            level.writes,
+            level.write_latency.p50_ms,
+            level.write_latency.p99_ms,
+            level
+                .avg_memory_bytes
+                .map(|bytes| format!("{:.1}", bytes as f64 / 1_000_000.0))
+                .unwrap_or_else(|| "-".to_string())
+        );
+    }
+    match result.memory_per_connection_bytes {
+        Some(bytes) => println!(
+            "✓ Write p99 {:.2}x the baseline, {:.1} KB of store memory per connection",
+            result.p99_ratio,
+            bytes / 1000.0
+        ),
+        None => println!("✓ Write p99 {:.2}x the baseline", result.p99_ratio),
+    }
+    println!("Results: {}", run_dir.display());
+    Ok(())
+}
+
+async fn run_startup_bench(
+    store: &str,
+    config: StartupBenchConfig,
+    data_dir: Option<String>,
+    run_dir: PathBuf,
+    cancel_token: CancellationToken,
+) -> Result<()> {
+    let store_manager = find_store_factory(store)?.create_store_manager(data_dir.clone())?;
+
+    println!("Startup benchmark: {}", store);
+    let data_dir_path = data_dir.as_ref().map(Path::new);
+    let environment_info = collect_environment_info(data_dir_path).await?;
+
+    let result = execute_startup_bench(store_manager, &config, cancel_token).await?;
+
+    fs::create_dir_all(&run_dir)?;
+    fs::write(run_dir.join("startup.json"), serde_json::to_string_pretty(&result)?)?;
+    fs::write(run_dir.join("environment.json"), serde_json::to_string_pretty(&environment_info)?)?;
+
+    for (label, stats) in [("warm", &result.warm), ("cold", &result.cold)] {
+        let Some(stats) = stats else { continue };
+        let mut line = format!(
+            "✓ {} starts ({}): ready p50 {:.2}s (min {:.2}s, max {:.2}s)",
+            label, stats.start.runs, stats.start.p50_s, stats.start.min_s, stats.start.max_s
+        );
+        if let Some(first_append) = &stats.first_append {
+            line.push_str(&format!(", first append p50 {:.2}s", first_append.p50_s));
+        }
+        if let Some(pull) = &stats.pull {
+            line.push_str(&format!(", image pull p50 {:.2}s", pull.p50_s));
+        }
+        println!("{}", line);
+    }
+    println!("Results: {}", run_dir.display());
+    Ok(())
+}
+
+async fn run_micro_bench(
+    store: &str,
+    config: MicroBenchConfig,
+    data_dir: Option<String>,
+    run_dir: PathBuf,
+    cancel_token: CancellationToken,
+) -> Result<()> {
+    let store_manager = find_store_factory(store)?.create_store_manager(data_dir.clone())?;
+
+    println!("Micro-benchmark: {} ({} iterations per primitive)", store, config.iterations);
+    let data_dir_path = data_dir.as_ref().map(Path::new);
+    let environment_info = collect_environment_info(data_dir_path).await?;
+
+    let result = execute_micro_bench(store_manager, &config, cancel_token).await?;
+
+    fs::create_dir_all(&run_dir)?;
+    fs::write(run_dir.join("micro.json"), serde_json::to_string_pretty(&result)?)?;
+    fs::write(run_dir.join("environment.json"), serde_json::to_string_pretty(&environment_info)?)?;
+
+    println!(
+        "{:<12} {:>10} {:>10} {:>10} {:>10} {:>10} {:>9} {:>12}",
+        "Primitive", "mean ms", "95% CI ±", "median ms", "std dev", "MAD", "outliers", "events/s"
+    );
+    for stats in &result.primitives {
+        println!(
+            "{:<12} {:>10.3} {:>10.3} {:>10.3} {:>10.3} {:>10.3} {:>9} {:>12}",
+            stats.primitive.as_str(),
+            stats.mean_ms,
+            (stats.mean_ci_high_ms - stats.mean_ci_low_ms) / 2.0,
+            stats.median_ms,
+            stats.std_dev_ms,
+            stats.mad_ms,
+            format!("{}/{}", stats.outliers_mild, stats.outliers_severe),
+            stats.throughput_eps.map(|eps| format!("{:.0}", eps)).unwrap_or_else(|| "-".to_string())
+        );
+    }
+    println!("Results: {}", run_dir.display());
+    Ok(())
+}
+
+async fn run_propagation(
+    store: &str,
+    config: PropagationConfig,
+    data_dir: Option<String>,
+    run_dir: PathBuf,
+    cancel_token: CancellationToken,
+) -> Result<()> {
+    let store_manager = find_store_factory(store)?.create_store_manager(data_dir.clone())?;
+
+    println!("Propagation: {} ({} streams of {} events)", store, config.streams, config.events_per_stream);
+    let data_dir_path = data_dir.as_ref().map(Path::new);
+    let environment_info = collect_environment_info(data_dir_path).await?;
+
+    let result = execute_propagation(store_manager, &config, cancel_token).await?;
+
+    fs::create_dir_all(&run_dir)?;
+    fs::write(run_dir.join("propagation.json"), serde_json::to_string_pretty(&result)?)?;
+    fs::write(run_dir.join("environment.json"), serde_json::to_string_pretty(&environment_info)?)?;
+
+    println!(
+        "Append: p50 {:.2} ms, p99 {:.2} ms",
+        result.append.p50_ms, result.append.p99_ms
+    );
+    println!(
+        "Write to read: p50 {:.2} ms, p99 {:.2} ms, max {:.2} ms",
+        result.end_to_end.p50_ms, result.end_to_end.p99_ms, result.end_to_end_max_ms
+    );
+    if result.visible_before_ack > 0 {
+        println!("  {} events were readable before their append was acknowledged", result.visible_before_ack);
+    }
+    if result.unseen > 0 {
+        println!("  ⚠ {} of {} events never showed up in a read", result.unseen, result.events);
+    }
+    println!("Results: {}", run_dir.display());
+    Ok(())
+}
+
+async fn run_catchup_subscription(
+    store: &str,
+    config: CatchupSubscriptionConfig,
+    data_dir: Option<String>,
+    run_dir: PathBuf,
+    cancel_token: CancellationToken,
+) -> Result<()> {
+    let store_manager = find_store_factory(store)?.create_store_manager(data_dir.clone())?;
+
+    println!("Catch-up subscription: {} ({} events, then {} live)", store, config.events, config.live_events);
+    let data_dir_path = data_dir.as_ref().map(Path::new);
+    let environment_info = collect_environment_info(data_dir_path).await?;
+
+    let result = execute_catchup_subscription(store_manager, &config, cancel_token).await?;
+
+    fs::create_dir_all(&run_dir)?;
+    fs::write(run_dir.join("catchup_subscription.json"), serde_json::to_string_pretty(&result)?)?;
+    fs::write(run_dir.join("environment.json"), serde_json::to_string_pretty(&environment_info)?)?;
+
+    println!(
+        "Replay: {:.2} s ({:.0} events/sec, first event after {:.2} ms)",
+        result.replay_s, result.replay_eps, result.first_event_ms
+    );
+    println!(
+        "Live lag: p50 {:.2} ms, p99 {:.2} ms, max {:.2} ms",
+        result.lag.p50_ms, result.lag.p99_ms, result.lag_max_ms
+    );
+    println!("Results: {}", run_dir.display());
+    Ok(())
+}
+
+async fn run_backfill_tail(
+    store: &str,
+    config: BackfillTailConfig,
+    data_dir: Option<String>,
+    run_dir: PathBuf,
+    cancel_token: CancellationToken,
+) -> Result<()> {
+    let store_manager = find_store_factory(store)?.create_store_manager(data_dir.clone())?;
+
+    println!(
+        "Backfill then tail: {} ({} backlog events, then {} events/sec for {} s)",
+        store, config.backlog_events, config.live_rate_eps, config.live_duration_s
+    );
+    let data_dir_path = data_dir.as_ref().map(Path::new);
+    let environment_info = collect_environment_info(data_dir_path).await?;
+
+    let result = execute_backfill_tail(store_manager, &config, cancel_token).await?;
+
+    fs::create_dir_all(&run_dir)?;
+    fs::write(run_dir.join("backfill_tail.json"), serde_json::to_string_pretty(&result)?)?;
+    fs::write(run_dir.join("environment.json"), serde_json::to_string_pretty(&environment_info)?)?;
+
+    println!(
+        "Backlog: {:.2} s ({:.0} events/sec)",
+        result.backlog_replay_s, result.backlog_replay_eps
+    );
+    println!(
+        "Caught up after {:.2} s, {} live events in ({})",
+        result.catch_up_s,
+        result.live_events_during_catch_up,
+        if result.kept_up { "kept up" } else { "only once the live appends ended" }
+    );
+    println!(
+        "Switchover: longest pause {:.2} ms, lag up to {:.2} ms",
+        result.switchover_max_gap_ms, result.switchover_lag_max_ms
+    );
+    if let Some(lag) = &result.steady_lag {
+        println!(
+            "Steady lag: p50 {:.2} ms, p99 {:.2} ms, max {:.2} ms",
+            lag.p50_ms, lag.p99_ms, result.steady_lag_max_ms
+        );
+    }
+    if result.duplicates > 0 || result.out_of_order > 0 {
+        println!("  ⚠ {} duplicate and {} out-of-order deliveries", result.duplicates, result.out_of_order);
+    }
+    println!("Results: {}", run_dir.display());
+    Ok(())
+}
+
+async fn run_slow_consumer(
+    store: &str,
+    config: SlowConsumerConfig,
+    data_dir: Option<String>,
+    run_dir: PathBuf,
+    cancel_token: CancellationToken,
+) -> Result<()> {
+    let store_manager = find_store_factory(store)?.create_store_manager(data_dir.clone())?;
+
+    println!(
+        "Slow consumer: {} ({} subscribers, one pausing {} ms per event, {} events/sec for {} s)",
+        store, config.subscribers, config.slow_delay_ms, config.write_rate_eps, config.duration_s
+    );
+    let data_dir_path = data_dir.as_ref().map(Path::new);
+    let environment_info = collect_environment_info(data_dir_path).await?;
+
+    let result = execute_slow_consumer(store_manager, &config, cancel_token).await?;
+
+    fs::create_dir_all(&run_dir)?;
+    fs::write(run_dir.join("slow_consumer.json"), serde_json::to_string_pretty(&result)?)?;
+    fs::write(run_dir.join("environment.json"), serde_json::to_string_pretty(&environment_info)?)?;
+
+    println!(
+        "Append p99: {:.2} ms -> {:.2} ms ({:+.0}%)",
+        result.baseline.append_latency.p99_ms,
+        result.with_slow.append_latency.p99_ms,
+        result.append_p99_change * 100.0
+    );
+    println!(
+        "Delivery lag p99 of the other subscribers: {:.2} ms -> {:.2} ms ({:+.0}%)",
+        result.baseline.delivery_lag.p99_ms,
+        result.with_slow.delivery_lag.p99_ms,
+        result.delivery_p99_change * 100.0
+    );
+    println!("Results: {}", run_dir.display());
+    Ok(())
+}
+
+async fn run_global_scan(
+    store: &str,
+    config: GlobalScanConfig,
+    data_dir: Option<String>,
+    run_dir: PathBuf,
+    cancel_token: CancellationToken,
+) -> Result<()> {
+    let store_manager = find_store_factory(store)?.create_store_manager(data_dir.clone())?;
+
+    println!("Global scan: {} ({} events over {} streams)", store, config.events, config.streams);
+    let data_dir_path = data_dir.as_ref().map(Path::new);
+    let environment_info = collect_environment_info(data_dir_path).await?;
+
+    let result = execute_global_scan(store_manager, &config, cancel_token).await?;
+
+    fs::create_dir_all(&run_dir)?;
+    fs::write(run_dir.join("global_scan.json"), serde_json::to_string_pretty(&result)?)?;
+    fs::write(run_dir.join("environment.json"), serde_json::to_string_pretty(&environment_info)?)?;
+
+    println!(
+        "Scan: {} events in {:.2} s ({:.0} events/sec, {:.1} MB/s)",
+        result.events_scanned, result.scan_s, result.scan_eps, result.scan_mb_per_s
+    );
+    println!("First event after {:.2} ms", result.first_event_ms);
+    if let (Some(pages), Some(latency)) = (result.pages, &result.page_latency) {
+        println!(
+            "Page of {}: p50 {:.2} ms, p99 {:.2} ms over {} pages",
+            config.page_size, latency.p50_ms, latency.p99_ms, pages
+        );
+    }
+    println!("Results: {}", run_dir.display());
+    Ok(())
+}
+
+async fn run_dual_write(
+    a: &str,
+    b: &str,
+    config: DualWriteConfig,
+    data_dir: Option<String>,
+    cancel_token: CancellationToken,
+) -> Result<()> {
+    let store_a = find_store_factory(a)?.create_store_manager(data_dir.clone())?;
+    let store_b = find_store_factory(b)?.create_store_manager(data_dir.clone())?;
+
+    println!("Dual-write comparison: {} vs {}", a, b);
+    let data_dir_path = data_dir.as_ref().map(Path::new);
+    let environment_info = collect_environment_info(data_dir_path).await?;
+
+    let result = execute_dual_write(store_a, store_b, &config, cancel_token).await?;
+
+    let run_id = Utc::now().format("%Y-%m-%dT%H-%M-%S").to_string();
+    let run_dir = PathBuf::from("results/raw/dual-write").join(format!("{}-{}-vs-{}", run_id, a, b));
+    fs::create_dir_all(&run_dir)?;
+    fs::write(run_dir.join("dual_write.json"), serde_json::to_string_pretty(&result)?)?;
+    fs::write(run_dir.join("environment.json"), serde_json::to_string_pretty(&environment_info)?)?;
+
+    // Paired per-operation samples
+    let mut pair_lines = String::new();
+    for sample in &result.samples {
+        pair_lines.push_str(&serde_json::to_string(sample)?);
+        pair_lines.push('\n');
+    }
+    fs::write(run_dir.join("pairs.jsonl"), pair_lines)?;
+
+    println!(
+        "✓ {} pairs: {} p50 {:.2} ms, {} p50 {:.2} ms, median difference {:.0} µs ({} faster in {:.1}% of pairs)",
+        result.pairs,
+        a,
+        result.latency_a.p50_ms,
+        b,
+        result.latency_b.p50_ms,
+        result.median_diff_us,
+        a,
+        result.a_faster_fraction * 100.0
+    );
+    println!("Results: {}", run_dir.display());
+    Ok(())
+}
+
+/// A single (non-sweep) performance workload from a config file
+fn performance_workload(config_path: &Path, seed: u64) -> Result<Box<PerformanceWorkload>> {
+    let config_yaml = fs::read_to_string(config_path).with_context(|| format!("Failed to read {}", config_path.display()))?;
+    if WorkloadFactory::is_sweep(&config_yaml)? {
+        anyhow::bail!("{} is a sweep; composed runs take single workloads", config_path.display());
+    }
+    match WorkloadFactory::create_from_yaml(&config_yaml, seed)? {
+        Workload::Performance(workload) => Ok(workload),
+        _ => anyhow::bail!("{} is not a performance workload", config_path.display()),
+    }
+}
+
+async fn run_composition(
+    store: &str,
+    config: &Path,
+    background: &Path,
+    seed: Option<u64>,
+    data_dir: Option<String>,
+    run_dir: PathBuf,
+    cancel_token: CancellationToken,
+) -> Result<()> {
+    let seed = seed.unwrap_or_else(|| rand::thread_rng().gen());
+    let foreground_workload = performance_workload(config, seed)?;
+    let background_workload = performance_workload(background, seed)?;
+    if background_workload.duration_seconds() < foreground_workload.duration_seconds() {
+        eprintln!(
+            "Warning: the background workload ({}s) ends before the foreground one ({}s)",
+            background_workload.duration_seconds(),
+            foreground_workload.duration_seconds()
+        );
+    }
+    let store_manager = find_store_factory(store)?.create_store_manager(data_dir.clone())?;
+
+    println!("Composed run: {} (seed {})", store, seed);
+    let data_dir_path = data_dir.as_ref().map(Path::new);
+    let environment_info = collect_environment_info(data_dir_path).await?;
+
+    let result = execute_composition(store_manager, &foreground_workload, &background_workload, cancel_token).await?;
+
+    fs::create_dir_all(&run_dir)?;
+    fs::write(run_dir.join("composition.json"), serde_json::to_string_pretty(&result)?)?;
+    fs::write(run_dir.join("environment.json"), serde_json::to_string_pretty(&environment_info)?)?;
+    fs::copy(config, run_dir.join("foreground.yaml"))?;
+    fs::copy(background, run_dir.join("background.yaml"))?;
+
+    println!("{:<11} {:<28} {:>14} {:>10} {:>10}", "Role", "Workload", "events/sec", "p50 (ms)", "p99 (ms)");
+    for (role, workload) in [("foreground", &result.foreground), ("background", &result.background)] {
+        println!(
+            "{:<11} {:<28} {:>14.2} {:>10.2} {:>10.2}",
+            role, workload.workload, workload.throughput_eps, workload.latency.p50_ms, workload.latency.p99_ms
+        );
+    }
+    println!("Results: {}", run_dir.display());
+    Ok(())
+}
+
+/// Run every workload config of a suite as one session (or one per repetition) on all of its
+/// stores, then every workflow on each store, on this runtime and one at a time; a failed entry
+/// is recorded in index.json and the suite moves on
+async fn run_suite(
+    suite: Suite,
+    seed: Option<u64>,
+    data_dir: Option<String>,
+    cancel_token: CancellationToken,
+) -> Result<PathBuf> {
+    let Suite { config: suite, yaml, workloads } = suite;
+    for store in &suite.stores {
+        find_store_factory(store)?;
+    }
+    let workloads = workloads
+        .into_iter()
+        .map(|(name, config_yaml)| Ok((name, with_stores(&config_yaml, &suite.stores)?)))
+        .collect::<Result<Vec<_>>>()?;
+    let seed = seed.unwrap_or_else(|| rand::thread_rng().gen());
+
+    let suite_id = Utc::now().format("%Y-%m-%dT%H-%M-%S").to_string();
+    let suite_dir = PathBuf::from(SUITES_DIR).join(&suite_id);
+    fs::create_dir_all(suite_dir.join("configs"))?;
+    fs::write(suite_dir.join("suite.yaml"), &yaml)?;
+    let mut index = SuiteIndex {
+        suite: suite.name.clone(),
+        suite_id,
+        started_at: Utc::now(),
+        finished_at: None,
+        seed,
+        repetitions: suite.repetitions,
+        entries: Vec::new(),
+    };
+    index.write(&suite_dir)?;
+    println!(
+        "Suite {}: {} workloads ×{} and {} workflows on {} (seed {})",
+        suite.name,
+        workloads.len(),
+        suite.repetitions,
+        suite.workflows.len(),
+        suite.stores.join(", "),
+        seed
+    );
+
+    let mut taken = HashSet::new();
+    for (name, config_yaml) in &workloads {
+        if cancel_token.is_cancelled() {
+            break;
+        }
+        let name = unique_name(name, &mut taken);
+        let config_path = suite_dir.join("configs").join(format!("{}.yaml", name));
+        fs::write(&config_path, config_yaml)?;
+        for repetition in 1..=suite.repetitions {
             if cancel_token.is_cancelled() {
                 break;
             }
-            println!("\n=== Running {} on {} ===", workload_name, store_name);
-
-            // Find store factory
-            let store_factory = store_manager_factories()
-                .into_iter()
-                .find(|f| f.name() == store_name)
-                .ok_or_else(|| anyhow::anyhow!("Unknown store: {}", store_name))?;
-
-            // Create store manager
-            let store_manager = store_factory.create_store_manager(data_dir.clone())?;
-
-            // Create store directory
-            let store_dir = workload_dir.join(store_name);
-            fs::create_dir_all(&store_dir)?;
-
-            // Execute the run
-            let result = execute_run(store_manager, &workload, cancel_token.clone()).await;
-            
-            let result = match result {
-                Ok(res) => res,
-                Err(e) => {
-                    if cancel_token.is_cancelled() {
-                        println!("Run interrupted, skipping results for {}", store_name);
-                        continue;
-                    }
-                    return Err(e);
-                }
+            // Repetitions are sibling sessions, `workloads/<name>/rep-<n>/`
+            let path = match suite.repetitions {
+                1 => Path::new("workloads").join(&name),
+                _ => Path::new("workloads").join(&name).join(format!("rep-{}", repetition)),
             };
+            println!("\n##### Suite workload {} ({}) #####", name, path.display());
+            let options = SessionOptions {
+                parallel: 1,
+                run: RunSettings {
+                    limits: suite.resources.clone(),
+                    ..RunSettings::default()
+                },
+                between_runs: BetweenRuns {
+                    cooldown: Duration::from_secs_f64(suite.cooldown_seconds),
+                    ..BetweenRuns::default()
+                },
+                shuffle: false,
+                force: false,
+                ratio_sweep: None,
+                sinks: Vec::new(),
+                session_dir: Some(suite_dir.join(&path)),
+            };
+            let started = Instant::now();
+            let result = run_benchmark(&config_path, Some(seed), data_dir.clone(), options, cancel_token.clone()).await;
+            index.entries.push(suite_entry(
+                "workload",
+                name.clone(),
+                suite.stores.clone(),
+                path,
+                result.map(|_| ()),
+                started,
+                &cancel_token,
+            ));
+            index.write(&suite_dir)?;
+        }
+    }
+    for workflow in &suite.workflows {
+        let name = unique_name(workflow.name(), &mut taken);
+        for store in &suite.stores {
+            if cancel_token.is_cancelled() {
+                break;
+            }
+            println!("\n##### Suite workflow {} on {} #####", name, store);
+            let path = Path::new("workflows").join(&name).join(store);
+            let started = Instant::now();
+            let result =
+                run_workflow(workflow.clone(), store, data_dir.clone(), suite_dir.join(&path), cancel_token.clone()).await;
+            index.entries.push(suite_entry("workflow", name.clone(), vec![store.clone()], path, result, started, &cancel_token));
+            index.write(&suite_dir)?;
+        }
+    }
+    index.finished_at = Some(Utc::now());
+    index.write(&suite_dir)?;
 
-            // Write summary
-            let summary_json = serde_json::to_string_pretty(&result.summary)?;
-            fs::write(store_dir.join("summary.json"), summary_json)?;
-
-            // Write throughput time-series samples
-            let mut throughput_lines = String::new();
-            for sample in result.throughput_samples {
-                throughput_lines.push_str(&serde_json::to_string(&sample)?);
-                throughput_lines.push('\n');
+    println!("\n{:<9} {:<32} {:<24} {:>10}  Status", "Kind", "Name", "Stores", "Time (s)");
+    for entry in &index.entries {
+        println!(
+            "{:<9} {:<32} {:<24} {:>10.1}  {}",
+            entry.kind,
+            entry.name,
+            entry.stores.join(","),
+            entry.duration_s,
+            match (entry.status, &entry.error) {
+                (EntryStatus::Failed, Some(error)) => format!("failed: {}", error),
+                (status, _) => format!("{:?}", status).to_lowercase(),
             }
-            fs::write(store_dir.join("throughput.jsonl"), throughput_lines)?;
-
-            // Write metadata with sample rate
-            let metadata = serde_json::json!({
-                "sample_rate": result.sample_rate,
-            });
-            let metadata_json = serde_json::to_string_pretty(&metadata)?;
-            fs::write(store_dir.join("run.meta.json"), metadata_json)?;
-
-            // Write histogram as JSON percentile data
-            let percentile_json = result.latency_histogram.to_percentile_json();
-            fs::write(
-                store_dir.join("latency.json"),
-                serde_json::to_string_pretty(&percentile_json)?
-            )?;
+        );
+    }
+    if suite.report && !cancel_token.is_cancelled() {
+        if let Err(e) = write_suite_report(&suite_dir) {
+            eprintln!("✗ Suite report failed: {:#}", e);
+        }
+    }
+    let failed = index.entries.iter().filter(|e| e.status == EntryStatus::Failed).count();
+    println!("\n✓ Suite complete: {} ({} of {} entries failed)", suite_dir.display(), failed, index.entries.len());
+    Ok(suite_dir)
+}
 
-            println!(
-                "✓ {} completed: {:.2} events/sec",
-                store_name, result.summary.throughput_eps
-            );
+/// Write the HTML report of a suite's workload sessions and the store comparison of their runs
+/// (`comparison.json`, `comparison.md`) to the suite's `report/`
+fn write_suite_report(suite_dir: &Path) -> Result<()> {
+    let workloads_dir = suite_dir.join("workloads");
+    let report_dir = suite_dir.join("report");
+    println!("\n##### Suite report #####");
+    generate_report(&workloads_dir, &report_dir)?;
+    compare_stores(
+        &[workloads_dir],
+        Some(&report_dir.join("comparison.json")),
+        Some(&report_dir.join("comparison.md")),
+    )
+}
+
+/// The index entry of a finished suite run; runs cut short by Ctrl+C count as interrupted
+fn suite_entry(
+    kind: &'static str,
+    name: String,
+    stores: Vec<String>,
+    path: PathBuf,
+    result: Result<()>,
+    started: Instant,
+    cancel_token: &CancellationToken,
+) -> SuiteEntry {
+    let (status, error) = match result {
+        _ if cancel_token.is_cancelled() => (EntryStatus::Interrupted, None),
+        Ok(()) => (EntryStatus::Ok, None),
+        Err(e) => {
+            eprintln!("✗ {} {} failed: {:#}", kind, name, e);
+            (EntryStatus::Failed, Some(format!("{:#}", e)))
         }
+    };
+    SuiteEntry {
+        kind,
+        name,
+        stores,
+        path,
+        status,
+        error,
+        duration_s: started.elapsed().as_secs_f64(),
     }
+}
 
-    println!("\n✓ Session complete: {}", session_dir.display());
+async fn run_workflow(
+    workflow: SuiteWorkflow,
+    store: &str,
+    data_dir: Option<String>,
+    run_dir: PathBuf,
+    cancel_token: CancellationToken,
+) -> Result<()> {
+    match workflow {
+        SuiteWorkflow::Propagation(config) => run_propagation(store, config, data_dir, run_dir, cancel_token).await,
+        SuiteWorkflow::CatchupSubscription(config) => {
+            run_catchup_subscription(store, config, data_dir, run_dir, cancel_token).await
+        }
+        SuiteWorkflow::BackfillTail(config) => run_backfill_tail(store, config, data_dir, run_dir, cancel_token).await,
+        SuiteWorkflow::SlowConsumer(config) => run_slow_consumer(store, config, data_dir, run_dir, cancel_token).await,
+        SuiteWorkflow::GlobalScan(config) => run_global_scan(store, config, data_dir, run_dir, cancel_token).await,
+        SuiteWorkflow::StreamGrowth(config) => run_stream_growth(store, config, data_dir, run_dir, cancel_token).await,
+        SuiteWorkflow::StreamPosition(config) => run_stream_position(store, config, data_dir, run_dir, cancel_token).await,
+        SuiteWorkflow::ConnectionStress(config) => {
+            run_connection_stress(store, config, data_dir, run_dir, cancel_token).await
+        }
+        SuiteWorkflow::Retention(config) => run_retention(store, config, data_dir, run_dir, cancel_token).await,
+        SuiteWorkflow::StartupBench(config) => run_startup_bench(store, config, data_dir, run_dir, cancel_token).await,
+        SuiteWorkflow::Micro(config) => run_micro_bench(store, config, data_dir, run_dir, cancel_token).await,
+    }
+}
+
+fn write_ci_summary(session_dir: &Path, output: &Path) -> Result<()> {
+    for path in write_ci_artifacts(session_dir, output)? {
+        println!("✓ Wrote {}", path.display());
+    }
+    Ok(())
+}
+
+fn compare_stores(dirs: &[PathBuf], json: Option<&Path>, markdown: Option<&Path>) -> Result<()> {
+    let comparison = compare_results(dirs)?;
+    print!("{}", comparison.to_text());
+    if let Some(path) = json {
+        fs::write(path, serde_json::to_string_pretty(&comparison)?)?;
+        println!("✓ Wrote {}", path.display());
+    }
+    if let Some(path) = markdown {
+        fs::write(path, comparison.to_markdown())?;
+        println!("✓ Wrote {}", path.display());
+    }
+    Ok(())
+}
+
+fn compare_run_timelines(dirs: &[PathBuf], threshold: f64, json: Option<&Path>, html: Option<&Path>) -> Result<()> {
+    let [a, b] = dirs else {
+        anyhow::bail!("--timeline compares exactly two run directories, got {}", dirs.len());
+    };
+    let timeline = compare_timelines(a, b, threshold)?;
+    print!("{}", timeline.to_text());
+    if let Some(path) = json {
+        fs::write(path, serde_json::to_string_pretty(&timeline)?)?;
+        println!("✓ Wrote {}", path.display());
+    }
+    if let Some(path) = html {
+        fs::write(path, timeline.to_html()?)?;
+        println!("✓ Wrote {}", path.display());
+    }
+    Ok(())
+}
+
+fn report_trends(results_dir: &Path, sigma: f64, fail_on_alert: bool) -> Result<()> {
+    let report = compute_trends(&results_dir.join("raw/sessions"), sigma)?;
+    let trend_dir = results_dir.join("trend");
+    fs::create_dir_all(&trend_dir)?;
+    let trend_path = trend_dir.join("trend.json");
+    fs::write(&trend_path, serde_json::to_string_pretty(&report)?)?;
+
+    for series in &report.series {
+        println!(
+            "{} / {}: {} runs, throughput {:.2} events/sec ({:+.2}/day), p99 {:.2}ms ({:+.3}/day)",
+            series.store,
+            series.workload,
+            series.points.len(),
+            series.throughput_eps.latest,
+            series.throughput_eps.slope_per_day,
+            series.p99_ms.latest,
+            series.p99_ms.slope_per_day
+        );
+    }
+    for alert in &report.alerts {
+        println!(
+            "⚠ {} / {}: {} {:.2} in {} is {:+.1}σ from the history mean {:.2}",
+            alert.store,
+            alert.workload,
+            alert.metric,
+            alert.latest,
+            alert.session_id,
+            alert.z_score,
+            alert.history_mean
+        );
+        if let Some(rev) = &alert.store_rev {
+            match &alert.previous_store_rev {
+                Some(previous) if previous != rev => println!("  store commits {}..{}", previous, rev),
+                _ => println!("  store commit {}", rev),
+            }
+        }
+    }
+    println!("✓ Trend report written to {}", trend_path.display());
+
+    if fail_on_alert && !report.alerts.is_empty() {
+        anyhow::bail!("{} trend alerts beyond {}σ", report.alerts.len(), sigma);
+    }
     Ok(())
 }
 
@@ -0,0 +1,37 @@
+use anyhow::Result;
+use bench_core::traced::SPAN_TARGET;
+use opentelemetry::trace::TracerProvider;
+use opentelemetry_otlp::{SpanExporter, WithExportConfig};
+use opentelemetry_sdk::trace::SdkTracerProvider;
+use opentelemetry_sdk::Resource;
+use tracing::{Level, Subscriber};
+use tracing_subscriber::filter::Targets;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+/// Path the OTLP/HTTP collector receives traces on
+const TRACES_PATH: &str = "/v1/traces";
+
+/// Layer exporting the run and operation spans to the OTLP/HTTP collector at `endpoint` (e.g.
+/// `http://localhost:4318`), with the provider to shut down before exiting so the last batch
+/// is sent
+pub fn layer<S>(endpoint: &str) -> Result<(impl Layer<S>, SdkTracerProvider)>
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+{
+    let endpoint = endpoint.trim_end_matches('/');
+    let endpoint = if endpoint.ends_with(TRACES_PATH) {
+        endpoint.to_string()
+    } else {
+        format!("{}{}", endpoint, TRACES_PATH)
+    };
+    let exporter = SpanExporter::builder().with_http().with_endpoint(endpoint).build()?;
+    let provider = SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .with_resource(Resource::builder().with_service_name("es-bench").build())
+        .build();
+    let layer = tracing_opentelemetry::layer()
+        .with_tracer(provider.tracer("es-bench"))
+        .with_filter(Targets::new().with_target(SPAN_TARGET, Level::INFO));
+    Ok((layer, provider))
+}
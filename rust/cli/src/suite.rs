@@ -0,0 +1,307 @@
+use anyhow::{Context, Result};
+use bench_core::{
+    BackfillTailConfig, CatchupSubscriptionConfig, ConnectionStressConfig, GlobalScanConfig, MicroBenchConfig, PropagationConfig, ResourceLimits, RetentionConfig,
+    SlowConsumerConfig, StartupBenchConfig, StreamGrowthConfig, StreamPositionConfig,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_yaml::Value;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Directory holding one `<suite-id>/` directory per suite run
+pub const SUITES_DIR: &str = "results/raw/suites";
+
+/// A suite YAML: every workload config and every workflow is run on every store
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SuiteConfig {
+    pub name: String,
+    /// Replaces the `stores` of each workload config
+    pub stores: Vec<String>,
+    /// Workload config files, relative to the suite file
+    #[serde(default)]
+    pub workloads: Vec<PathBuf>,
+    /// Each one a map from the workflow name to its settings
+    #[serde(default, with = "serde_yaml::with::singleton_map_recursive")]
+    pub workflows: Vec<SuiteWorkflow>,
+    /// Sessions run of each workload config
+    #[serde(default = "one")]
+    pub repetitions: u32,
+    /// Container limits of every workload run, overriding the workload configs' `resources`
+    #[serde(default)]
+    pub resources: ResourceLimits,
+    /// Idle time between the runs of a workload session
+    #[serde(default)]
+    pub cooldown_seconds: f64,
+    /// Write the HTML report and the store comparison of the workload sessions to `report/`
+    #[serde(default)]
+    pub report: bool,
+}
+
+fn one() -> u32 {
+    1
+}
+
+/// Name, YAML and the workload configs (by the path the suite lists them at) of a built-in suite
+type BuiltinSuite = (&'static str, &'static str, &'static [(&'static str, &'static str)]);
+
+/// Suites built into the binary with the workload configs they list, so published comparisons
+/// can be reproduced by name from any checkout
+const BUILTIN_SUITES: &[BuiltinSuite] = &[(
+    "official-v1",
+    include_str!("../../../configs/suites/official-v1.yaml"),
+    &[
+        ("official-v1/baseline-writes-w1.yaml", include_str!("../../../configs/suites/official-v1/baseline-writes-w1.yaml")),
+        ("official-v1/baseline-writes-w16.yaml", include_str!("../../../configs/suites/official-v1/baseline-writes-w16.yaml")),
+        ("official-v1/baseline-reads-r16.yaml", include_str!("../../../configs/suites/official-v1/baseline-reads-r16.yaml")),
+        ("official-v1/heavy-writes-zipf-w4.yaml", include_str!("../../../configs/suites/official-v1/heavy-writes-zipf-w4.yaml")),
+        ("official-v1/heavy-reads-zipf-r16.yaml", include_str!("../../../configs/suites/official-v1/heavy-reads-zipf-r16.yaml")),
+        ("official-v1/mixed-balanced-50-50.yaml", include_str!("../../../configs/suites/official-v1/mixed-balanced-50-50.yaml")),
+        ("official-v1/mixed-read-heavy-70-30.yaml", include_str!("../../../configs/suites/official-v1/mixed-read-heavy-70-30.yaml")),
+        ("official-v1/mixed-write-heavy-30-70.yaml", include_str!("../../../configs/suites/official-v1/mixed-write-heavy-30-70.yaml")),
+    ],
+)];
+
+/// Names of the built-in suites
+pub fn builtin_suites() -> impl Iterator<Item = &'static str> {
+    BUILTIN_SUITES.iter().map(|(name, ..)| *name)
+}
+
+/// A suite ready to run
+pub struct Suite {
+    pub config: SuiteConfig,
+    /// The suite YAML, copied into the suite directory
+    pub yaml: String,
+    /// Name (the file stem) and YAML of each workload config, in order
+    pub workloads: Vec<(String, String)>,
+}
+
+impl Suite {
+    /// Read a suite file and its workload configs, resolving their paths against the file's
+    /// directory
+    pub fn load(path: &Path) -> Result<Self> {
+        let yaml = fs::read_to_string(path).with_context(|| format!("Failed to read suite {}", path.display()))?;
+        let config = SuiteConfig::parse(&yaml, &path.display().to_string())?;
+        let base = path.parent().unwrap_or(Path::new(""));
+        // Read every workload config up front, so a typo doesn't surface hours into the suite
+        let workloads = config
+            .workloads
+            .iter()
+            .map(|workload| {
+                let path = base.join(workload);
+                let workload_yaml =
+                    fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+                Ok((file_stem(workload), workload_yaml))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { config, yaml, workloads })
+    }
+
+    /// A suite built into the binary, with the workload configs it was built with
+    pub fn builtin(name: &str) -> Result<Self> {
+        let Some((_, yaml, embedded)) = BUILTIN_SUITES.iter().find(|(builtin, ..)| *builtin == name) else {
+            anyhow::bail!(
+                "Unknown suite '{}' (built-in suites: {})",
+                name,
+                builtin_suites().collect::<Vec<_>>().join(", ")
+            );
+        };
+        let config = SuiteConfig::parse(yaml, name)?;
+        let workloads = config
+            .workloads
+            .iter()
+            .map(|workload| {
+                let (_, workload_yaml) = embedded
+                    .iter()
+                    .find(|(path, _)| Path::new(path) == workload)
+                    .ok_or_else(|| anyhow::anyhow!("Suite {} lists {}, which is not built in", name, workload.display()))?;
+                Ok((file_stem(workload), workload_yaml.to_string()))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { config, yaml: yaml.to_string(), workloads })
+    }
+}
+
+impl SuiteConfig {
+    /// Parse and check a suite YAML; `origin` names it in errors
+    fn parse(yaml: &str, origin: &str) -> Result<Self> {
+        let suite: Self = serde_yaml::from_str(yaml).with_context(|| format!("Failed to parse suite {}", origin))?;
+        anyhow::ensure!(!suite.stores.is_empty(), "Suite {} lists no stores", origin);
+        anyhow::ensure!(
+            !suite.workloads.is_empty() || !suite.workflows.is_empty(),
+            "Suite {} lists no workloads or workflows",
+            origin
+        );
+        anyhow::ensure!(suite.repetitions > 0, "Suite {} needs at least one repetition", origin);
+        anyhow::ensure!(
+            suite.cooldown_seconds.is_finite() && suite.cooldown_seconds >= 0.0,
+            "Suite {} needs a finite cooldown_seconds >= 0",
+            origin
+        );
+        suite.resources.validate().with_context(|| format!("Suite {} has invalid resources", origin))?;
+        Ok(suite)
+    }
+}
+
+fn file_stem(path: &Path) -> String {
+    path.file_stem().unwrap_or_default().to_string_lossy().to_string()
+}
+
+/// A single-store study run by the suite, with the settings of its subcommand
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SuiteWorkflow {
+    Propagation(PropagationConfig),
+    CatchupSubscription(CatchupSubscriptionConfig),
+    BackfillTail(BackfillTailConfig),
+    SlowConsumer(SlowConsumerConfig),
+    GlobalScan(GlobalScanConfig),
+    StreamGrowth(StreamGrowthConfig),
+    StreamPosition(StreamPositionConfig),
+    ConnectionStress(ConnectionStressConfig),
+    Retention(RetentionConfig),
+    StartupBench(StartupBenchConfig),
+    Micro(MicroBenchConfig),
+}
+
+impl SuiteWorkflow {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Propagation(_) => "propagation",
+            Self::CatchupSubscription(_) => "catchup-subscription",
+            Self::BackfillTail(_) => "backfill-tail",
+            Self::SlowConsumer(_) => "slow-consumer",
+            Self::GlobalScan(_) => "global-scan",
+            Self::StreamGrowth(_) => "stream-growth",
+            Self::StreamPosition(_) => "stream-position",
+            Self::ConnectionStress(_) => "connection-stress",
+            Self::Retention(_) => "retention",
+            Self::StartupBench(_) => "startup-bench",
+            Self::Micro(_) => "micro",
+        }
+    }
+}
+
+/// A workload config with its `stores` replaced by the suite's
+pub fn with_stores(config_yaml: &str, stores: &[String]) -> Result<String> {
+    let mut config: Value = serde_yaml::from_str(config_yaml)?;
+    let mapping = config
+        .as_mapping_mut()
+        .ok_or_else(|| anyhow::anyhow!("Workload config is not a mapping"))?;
+    mapping.insert(
+        Value::from("stores"),
+        Value::Sequence(stores.iter().map(|s| Value::from(s.as_str())).collect()),
+    );
+    Ok(serde_yaml::to_string(&config)?)
+}
+
+/// `name`, or `name-2`, `name-3`, ... if it was taken before
+pub fn unique_name(name: &str, taken: &mut HashSet<String>) -> String {
+    let mut candidate = name.to_string();
+    let mut n = 1;
+    while !taken.insert(candidate.clone()) {
+        n += 1;
+        candidate = format!("{}-{}", name, n);
+    }
+    candidate
+}
+
+/// How an entry of a suite ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EntryStatus {
+    Ok,
+    Failed,
+    Interrupted,
+}
+
+/// One workload session or workflow run of a suite, as listed in index.json
+#[derive(Debug, Serialize)]
+pub struct SuiteEntry {
+    /// `workload` or `workflow`
+    pub kind: &'static str,
+    pub name: String,
+    pub stores: Vec<String>,
+    /// Results directory, relative to the suite directory
+    pub path: PathBuf,
+    pub status: EntryStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub duration_s: f64,
+}
+
+/// Contents of a suite directory's index.json, rewritten after every entry
+#[derive(Debug, Serialize)]
+pub struct SuiteIndex {
+    pub suite: String,
+    pub suite_id: String,
+    pub started_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub finished_at: Option<DateTime<Utc>>,
+    /// Seed of every workload session
+    pub seed: u64,
+    /// Sessions run of each workload config
+    pub repetitions: u32,
+    pub entries: Vec<SuiteEntry>,
+}
+
+impl SuiteIndex {
+    pub fn write(&self, suite_dir: &Path) -> Result<()> {
+        fs::write(suite_dir.join("index.json"), serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_workflows_and_overrides_stores() {
+        let suite: SuiteConfig = serde_yaml::from_str(
+            "name: nightly
+stores: [dummy]
+workflows:
+  - propagation: {streams: 2, events_per_stream: 10, write_rate_eps: 100, poll_interval_ms: 5, event_size_bytes: 64}
+  - startup_bench: {iterations: 3, cold_iterations: 0}
+  - slow_consumer: {subscribers: 3, slow_delay_ms: 50, write_rate_eps: 100, duration_s: 5, event_size_bytes: 64}",
+        )
+        .unwrap();
+        let names: Vec<_> = suite.workflows.iter().map(SuiteWorkflow::name).collect();
+        assert_eq!(names, ["propagation", "startup-bench", "slow-consumer"]);
+
+        let config = with_stores("name: w\nstores: [umadb, kurrentdb]\n", &suite.stores).unwrap();
+        assert_eq!(
+            bench_core::WorkloadFactory::extract_stores(&config).unwrap(),
+            Some(vec!["dummy".to_string()])
+        );
+
+        let mut taken = HashSet::new();
+        let labels: Vec<_> = ["micro", "micro", "micro"].iter().map(|n| unique_name(n, &mut taken)).collect();
+        assert_eq!(labels, ["micro", "micro-2", "micro-3"]);
+    }
+
+    #[test]
+    fn builtin_suite_embeds_its_frozen_workload_configs() {
+        let official = Suite::builtin("official-v1").unwrap();
+        assert_eq!(official.workloads.len(), official.config.workloads.len());
+        assert!(official.config.repetitions > 1 && official.config.report);
+        for (name, yaml) in &official.workloads {
+            bench_core::WorkloadFactory::create_from_yaml(yaml, 1).unwrap_or_else(|e| panic!("{}: {:#}", name, e));
+        }
+        assert!(official.config.workloads.iter().all(|path| path.starts_with("official-v1")));
+        assert!(Suite::builtin("official-v0").is_err());
+    }
+
+    #[test]
+    fn rejects_invalid_cooldown_and_resources() {
+        let suite = |extra: &str| SuiteConfig::parse(&format!("name: s\nstores: [dummy]\nworkloads: [w.yaml]\n{}", extra), "s");
+        assert!(suite("cooldown_seconds: 5").is_ok());
+        assert!(suite("cooldown_seconds: .inf").is_err());
+        assert!(suite("cooldown_seconds: -1").is_err());
+        assert!(suite("resources: {cpus: 0}").is_err());
+        assert!(suite("resources: {memory: lots}").is_err());
+    }
+}
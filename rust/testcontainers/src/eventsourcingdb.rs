@@ -4,15 +4,21 @@ use testcontainers::Image;
 const NAME: &str = "thenativeweb/eventsourcingdb";
 const TAG: &str = "1.2.0";
 
-/// Container port exposed by EventsourcingDB (HTTP).
+/// Container port exposed by EventsourcingDB (HTTP or HTTPS).
 pub const EVENTSOURCINGDB_PORT: ContainerPort = ContainerPort::Tcp(3000);
 
 /// Default API token used for the benchmarking container.
 pub const EVENTSOURCINGDB_API_TOKEN: &str = "secret";
 
+const TLS_CERTIFICATE_PATH: &str = "/etc/esdb/tls/server.crt";
+const TLS_PRIVATE_KEY_PATH: &str = "/etc/esdb/tls/server.key";
+
 #[derive(Debug, Clone)]
 pub struct EventsourcingDb {
+    tag: String,
     mounts: Vec<Mount>,
+    api_tokens: Vec<String>,
+    tls: bool,
 }
 
 impl EventsourcingDb {
@@ -22,9 +28,32 @@ impl EventsourcingDb {
             None => Mount::volume_mount("", "/var/lib/esdb"),
         };
         Self {
+            tag: TAG.to_string(),
             mounts: vec![mount],
+            api_tokens: vec![EVENTSOURCINGDB_API_TOKEN.to_string()],
+            tls: false,
         }
     }
+
+    /// Accept each of the given API tokens instead of the default one
+    pub fn with_api_tokens(mut self, tokens: Vec<String>) -> Self {
+        self.api_tokens = tokens;
+        self
+    }
+
+    /// Serve HTTPS only, using the certificate and private key files on the host
+    pub fn with_tls(mut self, certificate_file: String, private_key_file: String) -> Self {
+        self.mounts.push(Mount::bind_mount(certificate_file, TLS_CERTIFICATE_PATH));
+        self.mounts.push(Mount::bind_mount(private_key_file, TLS_PRIVATE_KEY_PATH));
+        self.tls = true;
+        self
+    }
+
+    /// Run this tag of the image instead of the default one
+    pub fn with_image_tag(mut self, tag: &str) -> Self {
+        self.tag = tag.to_string();
+        self
+    }
 }
 
 impl Default for EventsourcingDb {
@@ -38,20 +67,29 @@ impl Image for EventsourcingDb {
         NAME
     }
     fn tag(&self) -> &str {
-        TAG
+        &self.tag
     }
     fn ready_conditions(&self) -> Vec<WaitFor> {
         vec![]
     }
     fn cmd(&self) -> impl IntoIterator<Item = impl Into<std::borrow::Cow<'_, str>>> {
-        vec![
-            "run",
-            "--data-directory-temporary",
-            "--https-enabled=false",
-            "--http-enabled",
-            "--api-token",
-            EVENTSOURCINGDB_API_TOKEN,
-        ]
+        let mut cmd = vec!["run", "--data-directory-temporary"];
+        if self.tls {
+            cmd.extend([
+                "--https-enabled",
+                "--http-enabled=false",
+                "--https-certificate-file",
+                TLS_CERTIFICATE_PATH,
+                "--https-private-key-file",
+                TLS_PRIVATE_KEY_PATH,
+            ]);
+        } else {
+            cmd.extend(["--https-enabled=false", "--http-enabled"]);
+        }
+        for token in &self.api_tokens {
+            cmd.extend(["--api-token", token.as_str()]);
+        }
+        cmd
     }
     fn mounts(&self) -> impl IntoIterator<Item = &Mount> {
         self.mounts.iter()
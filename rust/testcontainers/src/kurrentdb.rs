@@ -9,6 +9,7 @@ pub const KURRENTDB_PORT: ContainerPort = ContainerPort::Tcp(2113);
 
 #[derive(Debug, Clone)]
 pub struct KurrentDb {
+    tag: String,
     env_vars: Vec<(&'static str, &'static str)>,
     mounts: Vec<Mount>,
 }
@@ -20,6 +21,7 @@ impl KurrentDb {
             None => Mount::volume_mount("", "/var/lib/kurrentdb"),
         };
         Self {
+            tag: TAG.to_string(),
             env_vars: vec![
                 ("KURRENTDB_INSECURE", "true"),
                 ("KURRENTDB_RUN_PROJECTIONS", "All"),
@@ -33,6 +35,20 @@ impl KurrentDb {
     }
 }
 
+impl KurrentDb {
+    /// Run this tag of the image instead of the default one
+    pub fn with_image_tag(mut self, tag: &str) -> Self {
+        self.tag = tag.to_string();
+        self
+    }
+
+    /// Also start the standard system projections ($by_category, $by_event_type, ...)
+    pub fn with_standard_projections(mut self) -> Self {
+        self.env_vars.push(("KURRENTDB_START_STANDARD_PROJECTIONS", "true"));
+        self
+    }
+}
+
 impl Default for KurrentDb {
     fn default() -> Self {
         Self::new(None)
@@ -45,7 +61,7 @@ impl Image for KurrentDb {
     }
 
     fn tag(&self) -> &str {
-        TAG
+        &self.tag
     }
 
     fn ready_conditions(&self) -> Vec<WaitFor> {
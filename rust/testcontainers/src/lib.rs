@@ -1,4 +1,6 @@
 pub mod axonserver;
 pub mod eventsourcingdb;
 pub mod kurrentdb;
+pub mod postgres;
+pub mod redpanda;
 pub mod umadb;
@@ -0,0 +1,85 @@
+use testcontainers::core::{ContainerPort, Mount, WaitFor};
+use testcontainers::Image;
+
+const NAME: &str = "postgres";
+const TAG: &str = "17.2-alpine";
+
+/// Container port exposed by PostgreSQL.
+pub const POSTGRES_PORT: ContainerPort = ContainerPort::Tcp(5432);
+
+/// User, password and database of the benchmarking container.
+pub const POSTGRES_USER: &str = "postgres";
+pub const POSTGRES_PASSWORD: &str = "postgres";
+pub const POSTGRES_DB: &str = "postgres";
+
+#[derive(Debug, Clone)]
+pub struct Postgres {
+    tag: String,
+    env_vars: Vec<(&'static str, &'static str)>,
+    mounts: Vec<Mount>,
+}
+
+impl Postgres {
+    pub fn new(data_dir: Option<String>) -> Self {
+        let mount = match data_dir {
+            Some(path) => Mount::bind_mount(path, "/var/lib/postgresql/data"),
+            None => Mount::volume_mount("", "/var/lib/postgresql/data"),
+        };
+        Self {
+            tag: TAG.to_string(),
+            env_vars: vec![
+                ("POSTGRES_USER", POSTGRES_USER),
+                ("POSTGRES_PASSWORD", POSTGRES_PASSWORD),
+                ("POSTGRES_DB", POSTGRES_DB),
+            ],
+            mounts: vec![mount],
+        }
+    }
+
+    /// Run this tag of the image instead of the default one
+    pub fn with_image_tag(mut self, tag: &str) -> Self {
+        self.tag = tag.to_string();
+        self
+    }
+}
+
+impl Default for Postgres {
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
+
+impl Image for Postgres {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn tag(&self) -> &str {
+        &self.tag
+    }
+
+    fn ready_conditions(&self) -> Vec<WaitFor> {
+        // Also logged by the temporary server that initializes the database, so clients still
+        // retry until the final server accepts connections
+        vec![WaitFor::message_on_stderr("database system is ready to accept connections")]
+    }
+
+    fn env_vars(
+        &self,
+    ) -> impl IntoIterator<
+        Item = (
+            impl Into<std::borrow::Cow<'_, str>>,
+            impl Into<std::borrow::Cow<'_, str>>,
+        ),
+    > {
+        self.env_vars.iter().map(|(k, v)| (*k, *v))
+    }
+
+    fn mounts(&self) -> impl IntoIterator<Item = &Mount> {
+        self.mounts.iter()
+    }
+
+    fn expose_ports(&self) -> &[ContainerPort] {
+        &[POSTGRES_PORT]
+    }
+}
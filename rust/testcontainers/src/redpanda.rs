@@ -0,0 +1,81 @@
+use testcontainers::core::{ContainerPort, Mount, WaitFor};
+use testcontainers::Image;
+
+const NAME: &str = "docker.redpanda.com/redpandadata/redpanda";
+const TAG: &str = "v24.2.7";
+
+/// Container port exposed by Redpanda (Kafka API).
+pub const REDPANDA_PORT: ContainerPort = ContainerPort::Tcp(9092);
+
+/// Redpanda, a single-binary Kafka API implementation. Clients connect to the address the
+/// broker advertises, so the container must be started with its port mapped to the same
+/// `host_port` it was created with.
+#[derive(Debug, Clone)]
+pub struct Redpanda {
+    tag: String,
+    mounts: Vec<Mount>,
+    advertised_address: String,
+}
+
+impl Redpanda {
+    pub fn new(data_dir: Option<String>, host_port: u16) -> Self {
+        let mount = match data_dir {
+            Some(path) => Mount::bind_mount(path, "/var/lib/redpanda/data"),
+            None => Mount::volume_mount("", "/var/lib/redpanda/data"),
+        };
+        Self {
+            tag: TAG.to_string(),
+            mounts: vec![mount],
+            advertised_address: format!("localhost:{}", host_port),
+        }
+    }
+
+    /// Run this tag of the image instead of the default one
+    pub fn with_image_tag(mut self, tag: &str) -> Self {
+        self.tag = tag.to_string();
+        self
+    }
+}
+
+impl Default for Redpanda {
+    fn default() -> Self {
+        Self::new(None, 9092)
+    }
+}
+
+impl Image for Redpanda {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn tag(&self) -> &str {
+        &self.tag
+    }
+
+    fn ready_conditions(&self) -> Vec<WaitFor> {
+        vec![WaitFor::message_on_stderr("Successfully started Redpanda!")]
+    }
+
+    fn cmd(&self) -> impl IntoIterator<Item = impl Into<std::borrow::Cow<'_, str>>> {
+        [
+            "redpanda",
+            "start",
+            "--mode",
+            "dev-container",
+            "--smp",
+            "1",
+            "--kafka-addr",
+            "0.0.0.0:9092",
+            "--advertise-kafka-addr",
+            self.advertised_address.as_str(),
+        ]
+    }
+
+    fn mounts(&self) -> impl IntoIterator<Item = &Mount> {
+        self.mounts.iter()
+    }
+
+    fn expose_ports(&self) -> &[ContainerPort] {
+        &[REDPANDA_PORT]
+    }
+}
@@ -9,6 +9,7 @@ pub const UMADB_PORT: ContainerPort = ContainerPort::Tcp(50051);
 
 #[derive(Debug, Clone)]
 pub struct UmaDb {
+    tag: String,
     mounts: Vec<Mount>,
 }
 
@@ -19,9 +20,16 @@ impl UmaDb {
             None => Mount::volume_mount("", "/data"),
         };
         Self {
+            tag: TAG.to_string(),
             mounts: vec![mount],
         }
     }
+
+    /// Run this tag of the image instead of the default one
+    pub fn with_image_tag(mut self, tag: &str) -> Self {
+        self.tag = tag.to_string();
+        self
+    }
 }
 
 impl Default for UmaDb {
@@ -36,7 +44,7 @@ impl Image for UmaDb {
     }
 
     fn tag(&self) -> &str {
-        TAG
+        &self.tag
     }
 
     fn ready_conditions(&self) -> Vec<WaitFor> {